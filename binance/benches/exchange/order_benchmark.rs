@@ -1,11 +1,14 @@
 use std::hint::black_box;
 
 use binance::services::{
-    exchange::order::{OrderBuilder, OrderSymbol, SymbolFilter},
+    exchange::order::{LimitPricing, OrderBuilder, OrderSymbol, SymbolFilter},
     storage::BookTickerEvent,
 };
 use criterion::{Criterion, criterion_group};
-use engine::enums::SymbolOrder;
+use engine::{
+    FeeSchedule, MinNotional,
+    enums::{OrderType, SymbolOrder},
+};
 use rust_decimal::{Decimal, prelude::FromPrimitive};
 
 pub fn calculate_chain_profit_benchmark(c: &mut Criterion) {
@@ -16,6 +19,7 @@ pub fn calculate_chain_profit_benchmark(c: &mut Criterion) {
         bid_qty: Decimal::from_f64(7.27795000).unwrap(),
         ask_price: Decimal::from_f64(109615.47000000).unwrap(),
         ask_qty: Decimal::from_f64(2.22969000).unwrap(),
+        ..Default::default()
     };
 
     let order_book_2 = BookTickerEvent {
@@ -25,6 +29,7 @@ pub fn calculate_chain_profit_benchmark(c: &mut Criterion) {
         bid_qty: Decimal::from_f64(14.64600000).unwrap(),
         ask_price: Decimal::from_f64(2585.71000000).unwrap(),
         ask_qty: Decimal::from_f64(19.28810000).unwrap(),
+        ..Default::default()
     };
 
     let order_book_3 = BookTickerEvent {
@@ -34,6 +39,7 @@ pub fn calculate_chain_profit_benchmark(c: &mut Criterion) {
         bid_qty: Decimal::from_f64(105.74550000).unwrap(),
         ask_price: Decimal::from_f64(0.02359000).unwrap(),
         ask_qty: Decimal::from_f64(25.63400000).unwrap(),
+        ..Default::default()
     };
 
     let order_symbols = vec![
@@ -42,13 +48,16 @@ pub fn calculate_chain_profit_benchmark(c: &mut Criterion) {
             base_asset_precision: 8,
             quote_precision: 8,
             symbol_order: SymbolOrder::Asc,
+            order_type: OrderType::Market,
             min_profit_qty: Decimal::from_f64(0.000030),
+            min_profit_percent: None,
             max_order_qty: Decimal::from_f64(0.00030),
             order_book: &order_book_1,
             symbol_filter: SymbolFilter {
                 lot_size_step: 5,
                 tick_size: 2,
                 lot_size_min_qty: Decimal::from_f64(0.00001000).unwrap(),
+                ..Default::default()
             },
         },
         OrderSymbol {
@@ -56,13 +65,16 @@ pub fn calculate_chain_profit_benchmark(c: &mut Criterion) {
             base_asset_precision: 8,
             quote_precision: 8,
             symbol_order: SymbolOrder::Desc,
+            order_type: OrderType::Market,
             min_profit_qty: None,
+            min_profit_percent: None,
             max_order_qty: None,
             order_book: &order_book_2,
             symbol_filter: SymbolFilter {
                 lot_size_step: 4,
                 tick_size: 2,
                 lot_size_min_qty: Decimal::from_f64(0.00010000).unwrap(),
+                ..Default::default()
             },
         },
         OrderSymbol {
@@ -70,26 +82,33 @@ pub fn calculate_chain_profit_benchmark(c: &mut Criterion) {
             base_asset_precision: 8,
             quote_precision: 8,
             symbol_order: SymbolOrder::Asc,
+            order_type: OrderType::Market,
             min_profit_qty: None,
+            min_profit_percent: None,
             max_order_qty: None,
             order_book: &order_book_3,
             symbol_filter: SymbolFilter {
                 lot_size_step: 4,
                 tick_size: 5,
                 lot_size_min_qty: Decimal::from_f64(0.00010000).unwrap(),
+                ..Default::default()
             },
         },
     ];
 
     let market_depth_limit: usize = 1;
-    let fee_percent: Decimal = Decimal::from_f64(0.075).unwrap();
+    let fees = FeeSchedule::flat(Decimal::from_f64(0.075).unwrap());
 
     c.bench_function("binance::calculate_chain_profit", |b| {
         b.iter(|| {
             OrderBuilder::calculate_chain_profit(
                 black_box(&order_symbols),
                 black_box(market_depth_limit),
-                black_box(fee_percent),
+                black_box(OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS),
+                black_box(&fees),
+                black_box(&MinNotional::default()),
+                LimitPricing::default(),
+                false,
             )
         })
     });
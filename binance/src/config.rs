@@ -1,8 +1,28 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
+
 use anyhow::bail;
-use engine::Validatable;
+use engine::{
+    FeeRates, FeeSchedule, MIN_CHAIN_LEGS, ProfitWindow, SizeRamp, Validatable,
+    enums::{LiquidityRole, OrderType, ReconnectStrategy},
+};
 use rust_decimal::Decimal;
 use serde::Deserialize;
 
+use crate::{
+    libs::binance_client::{ClientConfig, HttpConfig, SelfTradePreventionMode},
+    services::{
+        exchange::{
+            chain::UnsupportedOrderType,
+            order::{OrderBuilder, PriceRounding},
+            ticker::PriceSource,
+        },
+        sender::service::DescLegQuantity,
+    },
+};
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct Config {
     pub api_url: String,
@@ -10,12 +30,185 @@ pub struct Config {
     pub api_secret_key: String,
     pub ws_url: String,
     pub ws_streams_url: String,
+    /// Connect to the spot testnet instead of the URLs above.
+    #[serde(default)]
+    pub testnet: bool,
     pub ws_max_connections: usize,
     #[serde(with = "rust_decimal::serde::float")]
     pub fee_percent: Decimal,
+    /// Fee of maker legs in percent, negative for a rebate (unset = `fee_percent`).
+    #[serde(default, with = "rust_decimal::serde::float_option")]
+    pub maker_fee_percent: Option<Decimal>,
+    /// Liquidity role of the chain legs by position: "maker" or "taker" (unset legs = taker).
+    #[serde(default)]
+    pub leg_roles: Vec<LiquidityRole>,
+    /// Fees of the buying legs (unset = `fee_percent` and `maker_fee_percent`).
+    #[serde(default)]
+    pub buy_fees: Option<FeeRates>,
+    /// Fees of the selling legs (unset = `fee_percent` and `maker_fee_percent`).
+    #[serde(default)]
+    pub sell_fees: Option<FeeRates>,
+    /// Fees of the legs on a symbol whatever their side, over the side fees.
+    #[serde(default)]
+    pub symbol_fees: Vec<SymbolFees>,
+    /// Minimum leg value per symbol in its quote asset, raising the `NOTIONAL` filter one.
+    #[serde(default)]
+    pub min_notional: HashMap<String, Decimal>,
     pub api_weight_limit: usize,
+    /// Percentage of `api_weight_limit` from which the weight Binance reports as used pauses
+    /// requests until the next minute (unset = off).
+    #[serde(default)]
+    pub weight_backoff_percent: Option<u8>,
+    /// Seconds requests and trading pause for after a 429 or 418 response without a longer
+    /// `Retry-After`, doubled by every further one until a request succeeds (unset = only
+    /// `Retry-After` pauses requests).
+    #[serde(default)]
+    pub rate_limit_cooldown_secs: Option<u64>,
     pub error_timeout: u64,
     pub send_orders: bool,
+    /// Log the signed order requests instead of sending them.
+    #[serde(default)]
+    pub shadow_orders: bool,
+    /// Endpoint receiving detected chains as JSON instead of trading them (unset = trade).
+    #[serde(default)]
+    pub forward_orders_url: Option<String>,
+    /// Minimum time a chain must stay profitable before orders are sent (0 = act at once).
+    #[serde(default)]
+    pub min_profit_persist_ms: u64,
+    /// Minimum price move (bps) on any leg before an already executed chain is sent again.
+    #[serde(default)]
+    pub min_price_change_bps: Option<u32>,
+    /// Maximum executions of a single chain per window (unset = no cap).
+    #[serde(default)]
+    pub max_chain_executions: Option<u32>,
+    /// Window of `max_chain_executions`, started by the first execution of a chain.
+    #[serde(default = "default_chain_executions_window_secs")]
+    pub chain_executions_window_secs: u64,
+    /// Maximum drift from the exchange clock before order placement is refused (unset = off).
+    #[serde(default)]
+    pub max_clock_drift_ms: Option<u64>,
+    /// Refuse to start if a chain symbol has no stream subscription (otherwise only warn).
+    #[serde(default)]
+    pub fail_on_missing_subscriptions: bool,
+    /// Recovery from a dropped stream connection: "resubscribe" or "rebuild" (restart service).
+    #[serde(default)]
+    pub reconnect_strategy: ReconnectStrategy,
+    /// Maximum stream connections being (re)established at once, the rest queue (unset = no
+    /// limit).
+    #[serde(default)]
+    pub max_concurrent_reconnects: Option<usize>,
+    /// Symbol streams allowed to fail their first connection at startup: their chains are
+    /// dropped and the rest run, more failures abort startup (unset = failed connections are
+    /// retried).
+    #[serde(default)]
+    pub max_failed_streams: Option<usize>,
+    /// Book ticker source: "ws" streams or "rest" polling where websockets are blocked.
+    #[serde(default)]
+    pub price_source: PriceSource,
+    /// Interval between book ticker polls with `price_source = "rest"`.
+    #[serde(default = "default_rest_poll_interval_ms")]
+    pub rest_poll_interval_ms: u64,
+    /// Shut down instead of idling once the session profit target of a base asset is reached.
+    #[serde(default)]
+    pub exit_on_session_profit_target: bool,
+    /// Chains failing in a row after which trading halts until restart (unset = the first
+    /// failed chain stops sending).
+    #[serde(default)]
+    pub max_consecutive_failed_chains: Option<usize>,
+    /// Start chains at a fraction of their size and ramp up over chains executed without a loss.
+    #[serde(default)]
+    pub size_ramp: Option<SizeRamp>,
+    /// Cap `max_order_qty` at the free balance of the base asset, fetched at startup and
+    /// refreshed every this many seconds (unset = off).
+    #[serde(default)]
+    pub balance_refresh_interval_secs: Option<u64>,
+    /// Alert when no chain passes the profit gate for this long (unset = off).
+    #[serde(default)]
+    pub no_opportunity_alert_after_secs: Option<u64>,
+    /// Maximum orders left open (unfilled) on the account before new chains are deferred.
+    #[serde(default)]
+    pub max_open_orders: Option<usize>,
+    /// Maximum age of the exchange info (filters, statuses) before it is re-fetched (unset =
+    /// never).
+    #[serde(default)]
+    pub exchange_info_max_age_secs: Option<u64>,
+    /// Log the parsed filters of every traded symbol at startup.
+    #[serde(default)]
+    pub log_symbol_filters: bool,
+    /// Symbols per chain, at least [`MIN_CHAIN_LEGS`].
+    #[serde(default = "default_chain_legs")]
+    pub chain_legs: usize,
+    /// Chains, as their symbols in order, whose every price update, profit calculation and
+    /// gate decision is logged.
+    #[serde(default)]
+    pub debug_chains: Vec<Vec<String>>,
+    /// Convert balances worth less than `dust_threshold_btc` to BNB on shutdown.
+    #[serde(default)]
+    pub convert_dust: bool,
+    /// Also convert dust periodically while running (unset = only on shutdown).
+    #[serde(default)]
+    pub dust_conversion_interval_secs: Option<u64>,
+    /// Value in BTC below which a balance counts as dust.
+    #[serde(
+        default = "default_dust_threshold_btc",
+        with = "rust_decimal::serde::float"
+    )]
+    pub dust_threshold_btc: Decimal,
+    /// Symbols whose latest book is periodically appended to `book_snapshot_path` (empty =
+    /// off).
+    #[serde(default)]
+    pub book_snapshot_symbols: Vec<String>,
+    /// Interval between book snapshots.
+    #[serde(default = "default_book_snapshot_interval_secs")]
+    pub book_snapshot_interval_secs: u64,
+    /// JSON lines file book snapshots are appended to.
+    #[serde(default = "default_book_snapshot_path")]
+    pub book_snapshot_path: PathBuf,
+    /// JSON lines file every received book ticker is appended to, for replay with the
+    /// backtest (unset = off).
+    #[serde(default)]
+    pub record_path: Option<PathBuf>,
+    /// Size in MiB from which the recording is moved aside to `<record_path>.<n>`.
+    #[serde(default = "default_record_max_file_mb")]
+    pub record_max_file_mb: u64,
+    /// Self-trade prevention mode sent with every order (account default if unset).
+    #[serde(default)]
+    pub self_trade_prevention_mode: Option<SelfTradePreventionMode>,
+    /// Quantity field for Desc (buy) legs: "quote" (quoteOrderQty) or "base" (quantity).
+    #[serde(default)]
+    pub desc_leg_quantity: DescLegQuantity,
+    /// Unwind the filled legs of a chain with market orders when a later leg fails.
+    #[serde(default)]
+    pub unwind_on_failure: bool,
+    /// Time a chain may take before its next leg is placed, the chain being aborted (and
+    /// unwound if enabled) past it.
+    #[serde(default)]
+    pub max_chain_execution_ms: Option<u64>,
+    /// Check that a leg spends no more than the previous leg returned before placing it,
+    /// aborting the chain (and unwinding it if enabled) otherwise.
+    #[serde(default)]
+    pub verify_leg_input: bool,
+    /// Order type of the chain legs: "market" (fills at any price) or "limit" (IOC at the
+    /// chain price, may leave the chain unfilled).
+    #[serde(default)]
+    pub order_type: OrderType,
+    /// Rounding of limit leg prices to the tick size: "aggressive" (up for buy legs, down
+    /// for sell legs, so the leg stays marketable) or "truncate".
+    #[serde(default)]
+    pub limit_price_rounding: PriceRounding,
+    /// Ticks limit leg prices are moved toward the aggressive side, past the touch.
+    #[serde(default)]
+    pub limit_price_offset_ticks: u32,
+    /// Connections to the trade API opened at startup and kept alive.
+    #[serde(default)]
+    pub warm_order_connections: usize,
+    /// Dedicated credentials and endpoints for order placement, keeping order requests off
+    /// the market data client (unset = the shared ones).
+    #[serde(default)]
+    pub trade_api: Option<TradeApiConfig>,
+    /// Chains with a symbol lacking the leg order type: "skip" them or only "warn".
+    #[serde(default)]
+    pub unsupported_order_type: UnsupportedOrderType,
     #[serde(with = "rust_decimal::serde::float")]
     pub min_profit_qty: Decimal,
     #[serde(with = "rust_decimal::serde::float")]
@@ -23,11 +216,242 @@ pub struct Config {
     #[serde(with = "rust_decimal::serde::float")]
     pub min_ticker_qty_24h: Decimal,
     pub skip_assets: Vec<String>,
+    /// Levels of the partial depth streams walked by the profit calculation: 5, 10 or 20
+    /// (unset = top-of-book from book ticker streams).
+    #[serde(default)]
+    pub market_depth_levels: Option<u16>,
+    /// Threads of a dedicated pool calculating chain profits (unset = on the async runtime).
+    #[serde(default)]
+    pub compute_threads: Option<usize>,
+    /// Times the processing of one book ticker update out of every this many into the
+    /// `ticker_update_processing_seconds` histogram (unset = not timed).
+    #[serde(default)]
+    pub update_latency_sample_every: Option<u64>,
+    /// Upper bound on order book depth passes per chain profit calculation.
+    #[serde(default = "default_max_depth_iterations")]
+    pub max_depth_iterations: usize,
+    /// Refuse chains that do not return to their starting asset.
+    #[serde(default = "default_verify_chain_cycle")]
+    pub verify_chain_cycle: bool,
+    /// Evaluate chains on crossed books (bid at or above ask) instead of skipping them until
+    /// they uncross.
+    #[serde(default)]
+    pub allow_crossed_books: bool,
+    /// UTC time windows that scale `min_profit_qty` (e.g. lower thresholds in quiet hours).
+    #[serde(default)]
+    pub min_profit_schedule: Vec<ProfitWindow>,
     pub assets: Vec<Asset>,
 }
 
+fn default_max_depth_iterations() -> usize {
+    OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS
+}
+
+fn default_verify_chain_cycle() -> bool {
+    true
+}
+
+fn default_rest_poll_interval_ms() -> u64 {
+    1000
+}
+
+fn default_dust_threshold_btc() -> Decimal {
+    Decimal::new(1, 3)
+}
+
+fn default_book_snapshot_interval_secs() -> u64 {
+    60
+}
+
+fn default_book_snapshot_path() -> PathBuf {
+    PathBuf::from("book-snapshots.jsonl")
+}
+
+fn default_record_max_file_mb() -> u64 {
+    256
+}
+
+fn default_chain_legs() -> usize {
+    MIN_CHAIN_LEGS
+}
+
+fn default_chain_executions_window_secs() -> u64 {
+    3600
+}
+
+impl Config {
+    /// Spot testnet REST API, in place of `api.binance.com`. The `/sapi` endpoints (dust
+    /// conversion) are not served there.
+    pub const TESTNET_API_URL: &str = "https://testnet.binance.vision";
+    /// Spot testnet websocket API, in place of `ws-api.binance.com`.
+    pub const TESTNET_WS_URL: &str = "wss://ws-api.testnet.binance.vision:443/ws-api/v3";
+    /// Spot testnet market streams, in place of `stream.binance.com`.
+    pub const TESTNET_WS_STREAMS_URL: &str = "wss://stream.testnet.binance.vision:443";
+
+    /// Points every REST and websocket URL, the trade API ones included, at the testnet
+    /// when `testnet` is set. Paths and payloads are the same as on mainnet.
+    fn apply_testnet(&mut self) {
+        if !self.testnet {
+            return;
+        }
+        Self::TESTNET_API_URL.clone_into(&mut self.api_url);
+        Self::TESTNET_WS_URL.clone_into(&mut self.ws_url);
+        Self::TESTNET_WS_STREAMS_URL.clone_into(&mut self.ws_streams_url);
+        if let Some(trade_api) = &mut self.trade_api {
+            trade_api.api_url = None;
+            trade_api.ws_url = None;
+        }
+    }
+
+    /// Client config of the order requests: the dedicated trade API if set, otherwise the
+    /// shared credentials.
+    #[must_use]
+    pub fn trade_client_config(&self) -> ClientConfig {
+        let (api_url, api_token, api_secret_key) = match &self.trade_api {
+            Some(trade_api) => (
+                trade_api.api_url.as_ref().unwrap_or(&self.api_url),
+                &trade_api.api_token,
+                &trade_api.api_secret_key,
+            ),
+            None => (&self.api_url, &self.api_token, &self.api_secret_key),
+        };
+        ClientConfig {
+            api_url: api_url.clone(),
+            api_token: api_token.clone(),
+            api_secret_key: api_secret_key.clone(),
+            http_config: HttpConfig {
+                warm_connections: self.warm_order_connections,
+                ..HttpConfig::default()
+            },
+        }
+    }
+
+    /// Websocket API url orders are placed on.
+    #[must_use]
+    pub fn trade_ws_url(&self) -> &str {
+        self.trade_api
+            .as_ref()
+            .and_then(|trade_api| trade_api.ws_url.as_deref())
+            .unwrap_or(&self.ws_url)
+    }
+
+    /// Builds the fee schedule of the chain legs from the configured fees and overrides.
+    #[must_use]
+    pub fn fee_schedule(&self) -> FeeSchedule {
+        let mut schedule = FeeSchedule::new(FeeRates {
+            maker_fee_percent: self.maker_fee_percent.unwrap_or(self.fee_percent),
+            taker_fee_percent: self.fee_percent,
+        })
+        .with_leg_roles(self.leg_roles.clone());
+        if let Some(rates) = self.buy_fees {
+            schedule = schedule.with_buy_fees(rates);
+        }
+        if let Some(rates) = self.sell_fees {
+            schedule = schedule.with_sell_fees(rates);
+        }
+        for symbol_fees in &self.symbol_fees {
+            schedule = schedule.with_symbol_fees(symbol_fees.symbol.clone(), symbol_fees.rates);
+        }
+        schedule
+    }
+}
+
 impl Validatable for Config {
     fn validate(&mut self) -> anyhow::Result<()> {
+        self.apply_testnet();
+        if self.chain_legs < MIN_CHAIN_LEGS {
+            bail!("chain_legs must be at least {MIN_CHAIN_LEGS}");
+        }
+        if self.max_depth_iterations == 0 {
+            bail!("max_depth_iterations must be greater than 0");
+        }
+        if self.compute_threads == Some(0) {
+            bail!("compute_threads must be greater than 0");
+        }
+        if self.update_latency_sample_every == Some(0) {
+            bail!("update_latency_sample_every must be greater than 0");
+        }
+        if self
+            .weight_backoff_percent
+            .is_some_and(|percent| !(1..=100).contains(&percent))
+        {
+            bail!("weight_backoff_percent must be in [1, 100]");
+        }
+        if self.rate_limit_cooldown_secs == Some(0) {
+            bail!("rate_limit_cooldown_secs must be greater than 0");
+        }
+        if self
+            .market_depth_levels
+            .is_some_and(|levels| !matches!(levels, 5 | 10 | 20))
+        {
+            bail!("market_depth_levels must be 5, 10 or 20");
+        }
+        if self.leg_roles.contains(&LiquidityRole::Maker) && self.maker_fee_percent.is_none() {
+            bail!("maker_fee_percent must be set when a leg in leg_roles is a maker");
+        }
+        if self.max_concurrent_reconnects == Some(0) {
+            bail!("max_concurrent_reconnects must be greater than 0");
+        }
+        if self.rest_poll_interval_ms == 0 {
+            bail!("rest_poll_interval_ms must be greater than 0");
+        }
+        if self.exchange_info_max_age_secs == Some(0) {
+            bail!("exchange_info_max_age_secs must be greater than 0");
+        }
+        if self.max_consecutive_failed_chains == Some(0) {
+            bail!("max_consecutive_failed_chains must be greater than 0");
+        }
+        if let Some(ramp) = &self.size_ramp {
+            ramp.validate()?;
+        }
+        if let Some(trade_api) = &self.trade_api
+            && (trade_api.api_token.is_empty() || trade_api.api_secret_key.is_empty())
+        {
+            bail!("trade_api requires api_token and api_secret_key");
+        }
+        if self.balance_refresh_interval_secs == Some(0) {
+            bail!("balance_refresh_interval_secs must be greater than 0");
+        }
+        if self.no_opportunity_alert_after_secs == Some(0) {
+            bail!("no_opportunity_alert_after_secs must be greater than 0");
+        }
+        if self.max_chain_execution_ms == Some(0) {
+            bail!("max_chain_execution_ms must be greater than 0");
+        }
+        if self.max_chain_executions == Some(0) {
+            bail!("max_chain_executions must be greater than 0");
+        }
+        if self.chain_executions_window_secs == 0 {
+            bail!("chain_executions_window_secs must be greater than 0");
+        }
+        if self.dust_conversion_interval_secs == Some(0) {
+            bail!("dust_conversion_interval_secs must be greater than 0");
+        }
+        if self.testnet && self.convert_dust {
+            bail!("convert_dust is not available on the testnet");
+        }
+        if self.dust_threshold_btc <= Decimal::ZERO {
+            bail!("dust_threshold_btc must be greater than 0");
+        }
+        if self.book_snapshot_interval_secs == 0 {
+            bail!("book_snapshot_interval_secs must be greater than 0");
+        }
+        if self.record_max_file_mb == 0 {
+            bail!("record_max_file_mb must be greater than 0");
+        }
+        if self
+            .min_notional
+            .values()
+            .any(|min_notional| *min_notional < Decimal::ZERO)
+        {
+            bail!("min_notional must not be negative");
+        }
+        for window in &self.min_profit_schedule {
+            window.validate()?;
+        }
+        if let Some(asset) = Asset::find_duplicate(&self.assets) {
+            bail!("Base asset {asset} is listed more than once in assets");
+        }
         for asset in self.assets.iter_mut() {
             asset.validate(
                 self.min_profit_qty,
@@ -39,25 +463,94 @@ impl Validatable for Config {
     }
 }
 
+/// Credentials and endpoints of the dedicated trade API.
+#[derive(Deserialize, Clone, Debug)]
+pub struct TradeApiConfig {
+    /// REST API url of the order requests (unset = `api_url`).
+    #[serde(default)]
+    pub api_url: Option<String>,
+    /// Websocket API url orders are placed on (unset = `ws_url`).
+    #[serde(default)]
+    pub ws_url: Option<String>,
+    pub api_token: String,
+    pub api_secret_key: String,
+}
+
+/// Fees overridden for a single symbol.
+#[derive(Deserialize, Clone, Debug)]
+pub struct SymbolFees {
+    pub symbol: String,
+    #[serde(flatten)]
+    pub rates: FeeRates,
+}
+
 #[derive(Deserialize, Clone, Debug)]
 pub struct Asset {
     pub asset: String,
     pub symbol: Option<String>,
     #[serde(with = "rust_decimal::serde::float")]
     pub min_profit_qty: Decimal,
+    /// Minimum chain profit after fees as a percentage of the quantity spent by the first
+    /// leg, required on top of `min_profit_qty` (unset = off). With `min_profit_qty = 0` it
+    /// is the only threshold.
+    #[serde(default, with = "rust_decimal::serde::float_option")]
+    pub min_profit_percent: Option<Decimal>,
     #[serde(with = "rust_decimal::serde::float")]
     pub max_order_qty: Decimal,
     #[serde(with = "rust_decimal::serde::float")]
     pub min_ticker_qty_24h: Decimal,
+    /// Realized session profit of the chains starting in this asset, in units of it, at which
+    /// trading stops (unset = no target).
+    #[serde(default, with = "rust_decimal::serde::float_option")]
+    pub session_profit_target: Option<Decimal>,
+    /// Realized loss of the chains starting in this asset within a UTC day, in units of it,
+    /// at which trading pauses until the next day (unset = no limit).
+    #[serde(default, with = "rust_decimal::serde::float_option")]
+    pub max_daily_loss: Option<Decimal>,
 }
 
 impl Asset {
+    /// Returns the first asset listed more than once, only its first entry would ever be
+    /// looked up.
+    #[must_use]
+    pub fn find_duplicate(assets: &[Self]) -> Option<&str> {
+        let mut seen = HashSet::new();
+        assets
+            .iter()
+            .map(|asset| asset.asset.as_str())
+            .find(|asset| !seen.insert(*asset))
+    }
+
     pub fn validate(
         &mut self,
         min_profit_qty: Decimal,
         max_order_qty: Decimal,
         min_ticker_qty_24h: Decimal,
     ) -> anyhow::Result<()> {
+        if self
+            .min_profit_percent
+            .is_some_and(|percent| percent <= Decimal::ZERO)
+        {
+            bail!(
+                "min_profit_percent of {} must be greater than 0",
+                self.asset
+            );
+        }
+        if self
+            .session_profit_target
+            .is_some_and(|target| target <= Decimal::ZERO)
+        {
+            bail!(
+                "session_profit_target of {} must be greater than 0",
+                self.asset
+            );
+        }
+        if self
+            .max_daily_loss
+            .is_some_and(|loss| loss <= Decimal::ZERO)
+        {
+            bail!("max_daily_loss of {} must be greater than 0", self.asset);
+        }
         match &self.symbol {
             Some(symbol) => {
                 if !symbol.contains("USDT") {
@@ -81,3 +574,77 @@ impl Asset {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(asset: &str, symbol: Option<&str>) -> Asset {
+        Asset {
+            asset: asset.to_owned(),
+            symbol: symbol.map(ToOwned::to_owned),
+            min_profit_qty: Decimal::ZERO,
+            min_profit_percent: None,
+            max_order_qty: Decimal::ZERO,
+            min_ticker_qty_24h: Decimal::ZERO,
+            session_profit_target: None,
+            max_daily_loss: None,
+        }
+    }
+
+    #[test]
+    fn test_duplicate_base_assets_are_found() {
+        let assets = [
+            asset("USDT", None),
+            asset("BTC", Some("BTCUSDT")),
+            asset("ETH", Some("ETHUSDT")),
+        ];
+        assert_eq!(Asset::find_duplicate(&assets), None);
+
+        let assets = [
+            asset("BTC", Some("BTCUSDT")),
+            asset("USDT", None),
+            asset("BTC", None),
+        ];
+        assert_eq!(Asset::find_duplicate(&assets), Some("BTC"));
+    }
+
+    #[test]
+    fn test_testnet_switches_every_url() -> anyhow::Result<()> {
+        let example: toml::Table = toml::from_str(include_str!("../../config.example.toml"))?;
+        let mut config: Config = example["binance"].clone().try_into()?;
+        config.trade_api = Some(TradeApiConfig {
+            api_url: Some("https://api.binance.com".to_owned()),
+            ws_url: Some("wss://ws-api.binance.com:443/ws-api/v3".to_owned()),
+            api_token: "token".to_owned(),
+            api_secret_key: "secret".to_owned(),
+        });
+
+        config.apply_testnet();
+        assert_eq!(config.api_url, "https://api.binance.com");
+        assert_eq!(config.ws_url, "wss://ws-api.binance.com:443/ws-api/v3");
+        assert_eq!(config.ws_streams_url, "wss://stream.binance.com:443");
+        assert_eq!(
+            config.trade_ws_url(),
+            "wss://ws-api.binance.com:443/ws-api/v3"
+        );
+
+        config.testnet = true;
+        config.apply_testnet();
+        assert_eq!(config.api_url, "https://testnet.binance.vision");
+        assert_eq!(
+            config.ws_url,
+            "wss://ws-api.testnet.binance.vision:443/ws-api/v3"
+        );
+        assert_eq!(
+            config.ws_streams_url,
+            "wss://stream.testnet.binance.vision:443"
+        );
+        assert_eq!(
+            config.trade_client_config().api_url,
+            "https://testnet.binance.vision"
+        );
+        assert_eq!(config.trade_ws_url(), config.ws_url);
+        Ok(())
+    }
+}
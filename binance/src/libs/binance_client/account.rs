@@ -1,8 +1,8 @@
 use std::time::SystemTime;
 
 use crate::libs::binance_client::{
-    AccountInformation,
-    api::{Api, Spot},
+    AccountInformation, DustAssets, DustTransfer,
+    api::{Api, Sapi, Spot},
     client::Client,
     utils,
 };
@@ -40,4 +40,28 @@ impl Account {
             .get(Api::Spot(Spot::Account), Some(&params), true)
             .await
     }
+
+    /// Lists the assets that can be converted to BNB as dust.
+    pub async fn dust_assets(&self) -> anyhow::Result<DustAssets> {
+        let ts = utils::get_timestamp(SystemTime::now())?;
+        let params: Vec<(String, String)> = vec![("timestamp".to_owned(), ts.to_string())];
+
+        self.client
+            .post(Api::Savings(Sapi::DustAssets), Some(&params), true)
+            .await
+    }
+
+    /// Converts the given dust assets to BNB.
+    pub async fn convert_dust(&self, assets: &[String]) -> anyhow::Result<DustTransfer> {
+        let ts = utils::get_timestamp(SystemTime::now())?;
+        let mut params: Vec<(String, String)> = assets
+            .iter()
+            .map(|asset| ("asset".to_owned(), asset.clone()))
+            .collect();
+        params.push(("timestamp".to_owned(), ts.to_string()));
+
+        self.client
+            .post(Api::Savings(Sapi::Dust), Some(&params), true)
+            .await
+    }
 }
@@ -3,6 +3,7 @@ use crate::libs::binance_client::{
     client::{Client, ClientConfig},
 };
 
+#[derive(Clone, Copy)]
 pub enum Api {
     Spot(Spot),
     Savings(Sapi),
@@ -12,6 +13,7 @@ pub enum Api {
 /// Endpoint for production and test orders.
 ///
 /// Orders issued to test are validated, but not sent into the matching engine.
+#[derive(Clone, Copy)]
 pub enum Spot {
     Ping,
     Time,
@@ -38,13 +40,17 @@ pub enum Spot {
     UserDataStream,
 }
 
+#[derive(Clone, Copy)]
 pub enum Sapi {
     AllCoins,
     AssetDetail,
     DepositAddress,
     SpotFuturesTransfer,
+    DustAssets,
+    Dust,
 }
 
+#[derive(Clone, Copy)]
 pub enum Futures {
     Ping,
     Time,
@@ -120,6 +126,8 @@ impl From<Api> for String {
                 Sapi::AssetDetail => "/sapi/v1/asset/assetDetail",
                 Sapi::DepositAddress => "/sapi/v1/capital/deposit/address",
                 Sapi::SpotFuturesTransfer => "/sapi/v1/futures/transfer",
+                Sapi::DustAssets => "/sapi/v1/asset/dust-btc",
+                Sapi::Dust => "/sapi/v1/asset/dust",
             },
             Api::Futures(route) => match route {
                 Futures::Ping => "/fapi/v1/ping",
@@ -166,6 +174,36 @@ impl From<Api> for String {
     }
 }
 
+impl Api {
+    /// Request weight of the endpoint with default parameters, so multi-symbol endpoints are
+    /// counted for all symbols. Savings and futures endpoints are limited separately and
+    /// count as 1.
+    #[must_use]
+    pub fn weight(self) -> u16 {
+        match self {
+            Self::Spot(route) => match route {
+                Spot::Ping | Spot::Time | Spot::OrderTest | Spot::Oco => 1,
+                Spot::Klines | Spot::AvgPrice | Spot::UserDataStream => 2,
+                Spot::AggTrades
+                | Spot::Price
+                | Spot::BookTicker
+                | Spot::Order
+                | Spot::OrderList => 4,
+                Spot::Depth => 5,
+                Spot::OpenOrderList => 6,
+                Spot::ExchangeInfo
+                | Spot::AllOrders
+                | Spot::AllOrderList
+                | Spot::Account
+                | Spot::MyTrades => 20,
+                Spot::Trades | Spot::HistoricalTrades => 25,
+                Spot::Ticker24hr | Spot::OpenOrders => 80,
+            },
+            Self::Savings(_) | Self::Futures(_) => 1,
+        }
+    }
+}
+
 pub trait Binance {
     fn new(cfg: ClientConfig) -> anyhow::Result<Self>
     where
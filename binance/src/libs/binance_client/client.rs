@@ -1,11 +1,20 @@
-use std::time::Duration;
+use std::{
+    hash::{BuildHasher, RandomState},
+    time::{Duration, SystemTime},
+};
 
 use anyhow::{anyhow, bail};
 use axum::http::{HeaderMap, HeaderName, HeaderValue};
-use reqwest::{Response, StatusCode};
+use engine::{METRICS, REQUEST_WEIGHT, RequestWeight};
+use futures_util::future;
+use reqwest::{Method, Response, StatusCode};
 use serde::de::DeserializeOwned;
+use tracing::warn;
 
-use crate::libs::binance_client::{api::Api, utils::generate_signature};
+use crate::libs::binance_client::{
+    api::{Api, Spot},
+    utils::{self, generate_signature},
+};
 
 /// Primary client for interacting with the Binance API.
 ///
@@ -22,6 +31,16 @@ pub struct Client {
     secret_key: String,
     /// Inner HTTP client with configured timeouts and connection pooling.
     inner_client: reqwest::Client,
+    /// Retries of a GET request failed with a timeout, a connection error, 5xx or 429.
+    max_retries: u32,
+    /// Delay before the first retry, doubled for every next one.
+    retry_base_delay: Duration,
+    /// Upper bound of the random delay added to every retry.
+    retry_jitter: Duration,
+    /// Connections opened ahead of requests by [`Client::warm_up`].
+    warm_connections: usize,
+    /// Idle time after which pooled connections are closed.
+    pool_idle_timeout: Duration,
 }
 
 impl Client {
@@ -42,12 +61,21 @@ impl Client {
             inner_client: reqwest::Client::builder()
                 .connect_timeout(cfg.http_config.connect_timeout)
                 .pool_idle_timeout(cfg.http_config.pool_idle_timeout)
-                .pool_max_idle_per_host(cfg.http_config.pool_max_idle_per_host)
+                .pool_max_idle_per_host(
+                    cfg.http_config
+                        .pool_max_idle_per_host
+                        .max(cfg.http_config.warm_connections),
+                )
                 .tcp_keepalive(cfg.http_config.tcp_keepalive)
                 .tcp_keepalive_interval(cfg.http_config.tcp_keepalive_interval)
                 .tcp_keepalive_retries(cfg.http_config.tcp_keepalive_retries)
                 .timeout(cfg.http_config.timeout)
                 .build()?,
+            max_retries: cfg.http_config.max_retries,
+            retry_base_delay: cfg.http_config.retry_base_delay,
+            retry_jitter: cfg.http_config.retry_jitter,
+            warm_connections: cfg.http_config.warm_connections,
+            pool_idle_timeout: cfg.http_config.pool_idle_timeout,
         };
 
         Ok(client)
@@ -74,18 +102,7 @@ impl Client {
         query: Option<&Vec<(String, String)>>,
         with_signature: bool,
     ) -> anyhow::Result<T> {
-        let url = self.build_url(path, query, with_signature)?;
-        let request = if with_signature {
-            self.inner_client
-                .get(url)
-                .headers(self.build_headers()?)
-                .build()?
-        } else {
-            self.inner_client.get(url).build()?
-        };
-
-        let response = self.inner_client.execute(request).await?;
-        response_handler(response).await
+        self.execute(Method::GET, path, query, with_signature).await
     }
 
     /// Performs a POST request to the Binance API.
@@ -109,18 +126,135 @@ impl Client {
         query: Option<&Vec<(String, String)>>,
         with_signature: bool,
     ) -> anyhow::Result<T> {
-        let url = self.build_url(path, query, with_signature)?;
-        let request = if with_signature {
-            self.inner_client
-                .post(url)
-                .headers(self.build_headers()?)
-                .build()?
+        self.execute(Method::POST, path, query, with_signature)
+            .await
+    }
+
+    /// Number of connections kept warm, zero if warming is disabled.
+    #[must_use]
+    pub fn warm_connections(&self) -> usize {
+        self.warm_connections
+    }
+
+    /// How often [`Client::warm_up`] must run to keep the connections from idling out.
+    #[must_use]
+    pub fn warm_interval(&self) -> Duration {
+        self.pool_idle_timeout / 2
+    }
+
+    /// Opens the configured number of pooled connections, or refreshes the idle ones, by
+    /// sending concurrent pings, so later requests to the host skip the TCP and TLS
+    /// handshakes. Returns the number of connections that answered.
+    pub async fn warm_up(&self) -> usize {
+        let path = Api::Spot(Spot::Ping);
+        for _ in 0..self.warm_connections {
+            while !REQUEST_WEIGHT.lock().await.add(path.weight() as usize) {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+
+        let pings = (0..self.warm_connections).map(|_| async {
+            let url = self.build_url(Api::Spot(Spot::Ping), None, false)?;
+            let response = self.inner_client.get(url).send().await?;
+            track_rate_limits(&response).await;
+            response_handler::<serde_json::Value>(response).await
+        });
+
+        let mut warmed = 0;
+        for result in future::join_all(pings).await {
+            match result {
+                Ok(_) => warmed += 1,
+                Err(e) => warn!(error = ?e, "Failed to warm Binance connection"),
+            }
+        }
+        warmed
+    }
+
+    /// Sends the request. GET requests failed with a timeout, a connection error, 5xx or 429
+    /// are retried with exponential backoff and jitter, every retry re-signed with a fresh
+    /// timestamp and waiting for and charged the endpoint weight, while the first attempt is
+    /// charged by the caller. A 429 pauses requests for `Retry-After` or the rate limit
+    /// cooldown, so its retry is only sent once the pause is over. Other requests are never
+    /// retried: a POST failed that way may still have placed an order. Other 4xx responses,
+    /// 418 included, are returned as is.
+    async fn execute<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: Api,
+        query: Option<&Vec<(String, String)>>,
+        with_signature: bool,
+    ) -> anyhow::Result<T> {
+        let weight = path.weight();
+        let retryable = method == Method::GET;
+
+        let mut attempt = 0;
+        loop {
+            // A retry keeps the signed timestamp within recvWindow.
+            let refreshed = match query {
+                Some(query) if attempt > 0 && with_signature => {
+                    Some(with_current_timestamp(query)?)
+                }
+                _ => None,
+            };
+            let url = self.build_url(path, refreshed.as_ref().or(query), with_signature)?;
+
+            let mut request = self.inner_client.request(method.clone(), &url);
+            if with_signature {
+                request = request.headers(self.build_headers()?);
+            }
+
+            let response = self.inner_client.execute(request.build()?).await;
+            if let Ok(response) = &response {
+                track_rate_limits(response).await;
+            }
+
+            let error = match response {
+                Ok(response)
+                    if !retryable
+                        || !(response.status().is_server_error()
+                            || response.status() == StatusCode::TOO_MANY_REQUESTS) =>
+                {
+                    return response_handler(response).await;
+                }
+                Ok(response) => response_handler::<T>(response)
+                    .await
+                    .err()
+                    .unwrap_or_else(|| anyhow!("Unexpected successful response")),
+                Err(e) if retryable && (e.is_timeout() || e.is_connect() || e.is_request()) => {
+                    anyhow!(e)
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            if attempt >= self.max_retries {
+                return Err(error);
+            }
+            attempt += 1;
+
+            let delay = self.retry_delay(attempt);
+            warn!(error = ?error, attempt, ?delay, "Retrying Binance request");
+            tokio::time::sleep(delay).await;
+
+            // Held back while requests are paused by a rate limit response.
+            while !REQUEST_WEIGHT.lock().await.add(weight as usize) {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+    }
+
+    /// Delay before the given retry: the base delay doubled for every earlier retry, plus
+    /// a random jitter.
+    fn retry_delay(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .retry_base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)));
+        let jitter_ms = u64::try_from(self.retry_jitter.as_millis()).unwrap_or(u64::MAX);
+        let jitter = if jitter_ms == 0 {
+            Duration::ZERO
         } else {
-            self.inner_client.post(url).build()?
+            Duration::from_millis(RandomState::new().hash_one(attempt) % (jitter_ms + 1))
         };
-
-        let response = self.inner_client.execute(request).await?;
-        response_handler(response).await
+        backoff.saturating_add(jitter)
     }
 
     /// Builds the full API URL with query params and optional signature.
@@ -206,6 +340,89 @@ async fn response_handler<T: DeserializeOwned>(resp: Response) -> anyhow::Result
     }
 }
 
+/// Feeds the rate limit state reported by Binance into [`REQUEST_WEIGHT`].
+async fn track_rate_limits(response: &Response) {
+    let (status, headers) = (response.status(), response.headers());
+    if !is_rate_limited(status) && used_weight(headers).is_none() {
+        return;
+    }
+
+    apply_rate_limits(&mut *REQUEST_WEIGHT.lock().await, status, headers);
+}
+
+/// Reconciles `request_weight` with the weight used in the current minute and, for 429 and
+/// 418 responses, pauses requests and trading for the cooldown derived from `Retry-After`.
+fn apply_rate_limits(request_weight: &mut RequestWeight, status: StatusCode, headers: &HeaderMap) {
+    if let Some(used_weight) = used_weight(headers)
+        && request_weight.sync_used(used_weight)
+    {
+        warn!(
+            used_weight,
+            "Binance request weight near the limit, backing off"
+        );
+    }
+
+    if is_rate_limited(status) {
+        METRICS.record_rate_limit_hit(status.as_u16());
+        let retry_after = retry_after(headers);
+        if let Some(pause) = request_weight.rate_limited(retry_after) {
+            METRICS.record_rate_limit_pause(pause);
+            warn!(
+                %status,
+                ?retry_after,
+                ?pause,
+                "⏸️ [Engine] Binance rate limit hit, requests and trading paused"
+            );
+        } else {
+            warn!(%status, "Binance rate limit hit");
+        }
+    } else if status.is_success() {
+        request_weight.clear_rate_limited();
+    }
+}
+
+/// Request weight used in the current minute, from `X-MBX-USED-WEIGHT-1M`.
+fn used_weight(headers: &HeaderMap) -> Option<usize> {
+    headers
+        .get("x-mbx-used-weight-1m")?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()
+}
+
+/// Back-off requested by a rate limited response, from `Retry-After` in seconds.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// Returns true for the 429 and 418 responses of a client over the rate limit.
+fn is_rate_limited(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::IM_A_TEAPOT
+}
+
+/// Returns `query` with its `timestamp` parameter set to the current time.
+fn with_current_timestamp(query: &[(String, String)]) -> anyhow::Result<Vec<(String, String)>> {
+    let now = utils::get_timestamp(SystemTime::now())?.to_string();
+    Ok(query
+        .iter()
+        .map(|(key, value)| {
+            let value = if key == "timestamp" {
+                now.clone()
+            } else {
+                value.clone()
+            };
+            (key.clone(), value)
+        })
+        .collect())
+}
+
 /// Builds a query string from key-value pairs.
 fn build_query(params: &Vec<(String, String)>) -> String {
     let mut query = String::new();
@@ -233,7 +450,7 @@ pub struct ClientConfig {
 
 /// HTTP configuration for the inner `reqwest::Client`.
 ///
-/// Controls timeouts, pooling, and TCP keepalive.
+/// Controls timeouts, pooling, TCP keepalive and request retries.
 #[derive(Clone)]
 pub struct HttpConfig {
     pub connect_timeout: Duration,
@@ -243,6 +460,15 @@ pub struct HttpConfig {
     pub tcp_keepalive_interval: Duration,
     pub tcp_keepalive_retries: u32,
     pub timeout: Duration,
+    /// Retries of a GET request failed with a timeout, a connection error, 5xx or 429 (once
+    /// the pause of the 429 is over).
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for every next one.
+    pub retry_base_delay: Duration,
+    /// Upper bound of the random delay added to every retry.
+    pub retry_jitter: Duration,
+    /// Connections opened at startup and kept alive, zero to open them on demand.
+    pub warm_connections: usize,
 }
 
 impl Default for HttpConfig {
@@ -255,19 +481,29 @@ impl Default for HttpConfig {
             tcp_keepalive_interval: Duration::from_secs(30),
             tcp_keepalive_retries: 5,
             timeout: Duration::from_secs(10),
+            max_retries: 3,
+            retry_base_delay: Duration::from_millis(200),
+            retry_jitter: Duration::from_millis(100),
+            warm_connections: 0,
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::time::Duration;
+    use std::{
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+        time::Duration,
+    };
 
     use mockito::Server;
     use serde::{Deserialize, Serialize};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
     use super::*;
-    use crate::libs::binance_client::api::Spot;
 
     #[derive(Debug, Deserialize, Serialize, PartialEq)]
     struct TestResponse {
@@ -286,6 +522,22 @@ mod tests {
         Client::from_config(&config).unwrap()
     }
 
+    fn create_retrying_test_client(server_url: &str) -> Client {
+        let config = ClientConfig {
+            api_url: server_url.to_owned(),
+            api_token: "test_api_key".to_owned(),
+            api_secret_key: "test_secret_key".to_owned(),
+            http_config: HttpConfig {
+                max_retries: 2,
+                retry_base_delay: Duration::from_millis(10),
+                retry_jitter: Duration::from_millis(5),
+                ..HttpConfig::default()
+            },
+        };
+
+        Client::from_config(&config).unwrap()
+    }
+
     #[tokio::test]
     async fn test_client_creation() {
         let config = ClientConfig {
@@ -431,6 +683,239 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_server_errors_are_retried() {
+        REQUEST_WEIGHT.lock().await.set_weight_limit(6000);
+
+        let mut server = Server::new_async().await;
+        let failing = server
+            .mock("GET", "/api/v3/ticker/price")
+            .match_query(mockito::Matcher::Any)
+            .with_status(503)
+            .with_body("Service Unavailable")
+            .expect(2)
+            .create_async()
+            .await;
+
+        // Mocks still missing their expected hits are matched first, so the success mock
+        // only answers once the failing one has been hit twice.
+        let succeeding = server
+            .mock("GET", "/api/v3/ticker/price")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"symbol": "BTCUSDT", "price": "50000.0"}"#)
+            .create_async()
+            .await;
+
+        let client = create_retrying_test_client(&server.url());
+        let response: TestResponse = client
+            .get(Api::Spot(Spot::Price), None, false)
+            .await
+            .unwrap();
+
+        failing.assert_async().await;
+        succeeding.assert_async().await;
+        assert_eq!(response.symbol, "BTCUSDT");
+    }
+
+    #[tokio::test]
+    async fn test_orders_are_not_retried() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/v3/order")
+            .match_query(mockito::Matcher::Any)
+            .with_status(503)
+            .with_body("Service Unavailable")
+            .expect(1)
+            .create_async()
+            .await;
+
+        // The order may have been placed: a retry could place it twice.
+        let client = create_retrying_test_client(&server.url());
+        let result: anyhow::Result<TestResponse> =
+            client.post(Api::Spot(Spot::Order), None, true).await;
+
+        mock.assert_async().await;
+        assert_eq!(result.unwrap_err().to_string(), "Service Unavailable");
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_requests_are_retried() {
+        REQUEST_WEIGHT.lock().await.set_weight_limit(6000);
+
+        // Without Retry-After nor a cooldown the 429 sets no pause, the retry only backs off.
+        let mut server = Server::new_async().await;
+        let limited = server
+            .mock("GET", "/api/v3/ticker/price")
+            .match_query(mockito::Matcher::Any)
+            .with_status(429)
+            .with_body(r#"{"code": -1003, "msg": "Too many requests."}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let succeeding = server
+            .mock("GET", "/api/v3/ticker/price")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"symbol": "BTCUSDT", "price": "50000.0"}"#)
+            .create_async()
+            .await;
+
+        let client = create_retrying_test_client(&server.url());
+        let response: TestResponse = client
+            .get(Api::Spot(Spot::Price), None, false)
+            .await
+            .unwrap();
+
+        limited.assert_async().await;
+        succeeding.assert_async().await;
+        assert_eq!(response.symbol, "BTCUSDT");
+    }
+
+    #[tokio::test]
+    async fn test_banned_requests_are_not_retried() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v3/ticker/price")
+            .match_query(mockito::Matcher::Any)
+            .with_status(418)
+            .with_body(r#"{"code": -1003, "msg": "Way too many requests; IP banned."}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = create_retrying_test_client(&server.url());
+        let result: anyhow::Result<TestResponse> =
+            client.get(Api::Spot(Spot::Price), None, false).await;
+
+        mock.assert_async().await;
+        assert!(result.unwrap_err().to_string().contains("code=418"));
+    }
+
+    #[tokio::test]
+    async fn test_retries_are_signed_with_a_fresh_timestamp() {
+        REQUEST_WEIGHT.lock().await.set_weight_limit(6000);
+
+        let mut server = Server::new_async().await;
+        let failing = server
+            .mock("GET", "/api/v3/order")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "timestamp".to_owned(),
+                "1".to_owned(),
+            ))
+            .with_status(503)
+            .with_body("Service Unavailable")
+            .expect(1)
+            .create_async()
+            .await;
+        let succeeding = server
+            .mock("GET", "/api/v3/order")
+            .match_query(mockito::Matcher::Regex(
+                "timestamp=[0-9]{13}&signature=".to_owned(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"symbol": "BTCUSDT", "price": "50000.0"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = create_retrying_test_client(&server.url());
+        let query = vec![
+            ("symbol".to_owned(), "BTCUSDT".to_owned()),
+            ("timestamp".to_owned(), "1".to_owned()),
+        ];
+        let response: TestResponse = client
+            .get(Api::Spot(Spot::Order), Some(&query), true)
+            .await
+            .unwrap();
+
+        failing.assert_async().await;
+        succeeding.assert_async().await;
+        assert_eq!(response.symbol, "BTCUSDT");
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_are_not_retried() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v3/ticker/price")
+            .match_query(mockito::Matcher::Any)
+            .with_status(400)
+            .with_body(r#"{"code": -1121, "msg": "Invalid symbol."}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = create_retrying_test_client(&server.url());
+        let result: anyhow::Result<TestResponse> =
+            client.get(Api::Spot(Spot::Price), None, false).await;
+
+        mock.assert_async().await;
+        assert!(result.unwrap_err().to_string().contains("code=400"));
+    }
+
+    /// Serves `{}` to every request over keep-alive connections, counting the accepted ones.
+    async fn spawn_counting_server() -> (String, Arc<AtomicUsize>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let accepted = Arc::new(AtomicUsize::new(0));
+
+        tokio::spawn({
+            let accepted = accepted.clone();
+            async move {
+                while let Ok((mut stream, _)) = listener.accept().await {
+                    accepted.fetch_add(1, Ordering::SeqCst);
+                    tokio::spawn(async move {
+                        let mut buf = [0u8; 4096];
+                        while let Ok(n) = stream.read(&mut buf).await
+                            && n > 0
+                        {
+                            let response = "HTTP/1.1 200 OK\r\ncontent-type: \
+                                            application/json\r\ncontent-length: 2\r\n\r\n{}";
+                            if stream.write_all(response.as_bytes()).await.is_err() {
+                                break;
+                            }
+                        }
+                    });
+                }
+            }
+        });
+
+        (url, accepted)
+    }
+
+    #[tokio::test]
+    async fn test_connections_warmed_before_first_order() {
+        REQUEST_WEIGHT.lock().await.set_weight_limit(6000);
+
+        let (url, accepted) = spawn_counting_server().await;
+        let client = Client::from_config(&ClientConfig {
+            api_url: url,
+            api_token: "test_api_key".to_owned(),
+            api_secret_key: "test_secret_key".to_owned(),
+            http_config: HttpConfig {
+                warm_connections: 3,
+                ..HttpConfig::default()
+            },
+        })
+        .unwrap();
+
+        assert_eq!(client.warm_up().await, 3);
+        assert_eq!(accepted.load(Ordering::SeqCst), 3);
+
+        // Orders reuse the warm connections instead of opening new ones.
+        for _ in 0..3 {
+            let _: serde_json::Value = client
+                .post(Api::Spot(Spot::Order), None, true)
+                .await
+                .unwrap();
+        }
+        assert_eq!(accepted.load(Ordering::SeqCst), 3);
+    }
+
     #[tokio::test]
     async fn test_response_handler_success() {
         let expected_response = TestResponse {
@@ -525,6 +1010,112 @@ mod tests {
         assert!(error_msg.contains("Bad Request: Invalid symbol"));
     }
 
+    #[test]
+    fn test_rate_limit_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(used_weight(&headers), None);
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert("x-mbx-used-weight-1m", HeaderValue::from_static("1195"));
+        headers.insert("retry-after", HeaderValue::from_static("42"));
+        assert_eq!(used_weight(&headers), Some(1195));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(42)));
+
+        headers.insert("retry-after", HeaderValue::from_static("soon"));
+        assert_eq!(retry_after(&headers), None);
+    }
+
+    #[test]
+    fn test_weight_headers_sync_the_request_weight() {
+        let response = |status: u16, used_weight: &str| {
+            reqwest::Response::from(
+                http::Response::builder()
+                    .status(status)
+                    .header("x-mbx-used-weight-1m", used_weight)
+                    .header("retry-after", "30")
+                    .body("")
+                    .unwrap(),
+            )
+        };
+        let mut request_weight = RequestWeight::new();
+        request_weight.set_weight_limit(100);
+        request_weight.set_backoff_percent(Some(95));
+
+        // Requests of other clients on the same IP count against the limit.
+        let ok = response(200, "40");
+        apply_rate_limits(&mut request_weight, ok.status(), ok.headers());
+        assert_eq!(request_weight.used(), 40);
+        assert!(request_weight.add(50));
+        assert!(!request_weight.add(20));
+        assert_eq!(request_weight.paused_for(), None);
+
+        // Near the real limit new requests wait for the next minute.
+        let ok = response(200, "96");
+        apply_rate_limits(&mut request_weight, ok.status(), ok.headers());
+        assert_eq!(request_weight.used(), 96);
+        assert!(request_weight.paused_for().is_some());
+        assert!(!request_weight.add(1));
+
+        let limited = response(429, "100");
+        apply_rate_limits(&mut request_weight, limited.status(), limited.headers());
+        assert_eq!(request_weight.used(), 100);
+        assert!(
+            request_weight
+                .paused_for()
+                .is_some_and(|d| d > Duration::from_secs(28))
+        );
+    }
+
+    #[test]
+    fn test_rate_limited_responses_pause_for_the_cooldown() {
+        let response = |status: u16, retry_after: Option<&str>| {
+            let mut builder = http::Response::builder()
+                .status(status)
+                .header("x-mbx-used-weight-1m", "10");
+            if let Some(retry_after) = retry_after {
+                builder = builder.header("retry-after", retry_after);
+            }
+            reqwest::Response::from(builder.body("").unwrap())
+        };
+        let mut request_weight = RequestWeight::new();
+        request_weight.set_weight_limit(100);
+        request_weight.set_rate_limit_cooldown(Some(Duration::from_secs(60)));
+
+        // Without `Retry-After` the configured cooldown applies.
+        let limited = response(429, None);
+        apply_rate_limits(&mut request_weight, limited.status(), limited.headers());
+        assert!(
+            request_weight
+                .paused_for()
+                .is_some_and(|d| d > Duration::from_secs(58) && d <= Duration::from_secs(60))
+        );
+        assert!(!request_weight.add(1));
+
+        // A repeated one doubles it, a ban warning longer than that is honoured.
+        let limited = response(429, Some("5"));
+        apply_rate_limits(&mut request_weight, limited.status(), limited.headers());
+        assert!(
+            request_weight
+                .paused_for()
+                .is_some_and(|d| d > Duration::from_secs(118))
+        );
+        let banned = response(418, Some("600"));
+        apply_rate_limits(&mut request_weight, banned.status(), banned.headers());
+        assert!(
+            request_weight
+                .paused_for()
+                .is_some_and(|d| d > Duration::from_secs(598))
+        );
+
+        // A successful response resets the escalation.
+        let ok = response(200, None);
+        apply_rate_limits(&mut request_weight, ok.status(), ok.headers());
+        assert_eq!(
+            request_weight.rate_limited(None),
+            Some(Duration::from_secs(60))
+        );
+    }
+
     #[test]
     fn test_build_query() {
         let params = vec![
@@ -626,6 +1217,10 @@ mod tests {
         assert_eq!(config.tcp_keepalive_interval, Duration::from_secs(30));
         assert_eq!(config.tcp_keepalive_retries, 5);
         assert_eq!(config.timeout, Duration::from_secs(10));
+        assert_eq!(config.max_retries, 3);
+        assert_eq!(config.retry_base_delay, Duration::from_millis(200));
+        assert_eq!(config.retry_jitter, Duration::from_millis(100));
+        assert_eq!(config.warm_connections, 0);
     }
 
     #[test]
@@ -645,6 +1240,11 @@ mod tests {
             api_key: "invalid\nkey".to_owned(),
             secret_key: "test_secret".to_owned(),
             inner_client: reqwest::Client::new(),
+            max_retries: 0,
+            retry_base_delay: Duration::ZERO,
+            retry_jitter: Duration::ZERO,
+            warm_connections: 0,
+            pool_idle_timeout: Duration::ZERO,
         };
 
         let result = client.build_headers();
@@ -1,5 +1,6 @@
 use std::fmt::Display;
 
+use engine::enums;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
@@ -66,6 +67,15 @@ impl Display for OrderType {
     }
 }
 
+impl From<enums::OrderType> for OrderType {
+    fn from(order_type: enums::OrderType) -> Self {
+        match order_type {
+            enums::OrderType::Market => Self::Market,
+            enums::OrderType::Limit => Self::Limit,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TimeInForce {
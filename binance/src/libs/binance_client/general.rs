@@ -1,7 +1,7 @@
 use crate::libs::binance_client::{
     api::{Api, Spot},
     client::Client,
-    models::ExchangeInformation,
+    models::{ExchangeInformation, ServerTime},
 };
 
 #[derive(Clone)]
@@ -10,6 +10,11 @@ pub struct General {
 }
 
 impl General {
+    /// Current server time.
+    pub async fn server_time(&self) -> anyhow::Result<ServerTime> {
+        self.client.get(Api::Spot(Spot::Time), None, false).await
+    }
+
     /// Exchange information.
     pub async fn exchange_info(&self) -> anyhow::Result<ExchangeInformation> {
         let params: Vec<(String, String)> = vec![
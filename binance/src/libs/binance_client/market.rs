@@ -1,5 +1,5 @@
 use crate::libs::binance_client::{
-    OrderBook, TickerPriceResponseType, TickerPriceStats,
+    BookTicker, OrderBook, TickerPriceResponseType, TickerPriceStats,
     api::{Api, Spot},
     client::Client,
 };
@@ -26,6 +26,13 @@ impl Market {
             .await
     }
 
+    // Best price/qty on the order book for all symbols.
+    pub async fn get_book_tickers(&self) -> anyhow::Result<Vec<BookTicker>> {
+        self.client
+            .get(Api::Spot(Spot::BookTicker), None, false)
+            .await
+    }
+
     // 24hr ticker price change statistics.
     pub async fn get_ticker_price_24h<S>(
         &self,
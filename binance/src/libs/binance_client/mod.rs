@@ -7,11 +7,11 @@ pub mod market;
 pub mod models;
 pub mod stream;
 pub mod trade;
-mod utils;
+pub(crate) mod utils;
 pub mod ws;
 
 pub use account::Account;
-pub use api::Binance;
+pub use api::{Api, Binance, Spot};
 pub use client::{ClientConfig, HttpConfig};
 pub use enums::*;
 pub use general::General;
@@ -9,6 +9,12 @@ use crate::libs::binance_client::{
     },
 };
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerTime {
+    pub server_time: u64,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ExchangeInformation {
@@ -66,6 +72,35 @@ pub struct Balance {
     pub locked: String,
 }
 
+/// Assets that can be converted to BNB as dust.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DustAssets {
+    pub details: Vec<DustAsset>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DustAsset {
+    pub asset: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub amount_free: Decimal,
+    #[serde(rename = "toBTC", with = "rust_decimal::serde::float")]
+    pub to_btc: Decimal,
+    #[serde(rename = "toBNB", with = "rust_decimal::serde::float")]
+    pub to_bnb: Decimal,
+}
+
+/// Result of a dust to BNB conversion.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DustTransfer {
+    #[serde(with = "rust_decimal::serde::float")]
+    pub total_service_charge: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub total_transfered: Decimal,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SendOrderRequest {
     pub symbol: String,
@@ -153,6 +188,21 @@ pub struct OrderBookUnit {
     pub qty: Decimal,
 }
 
+/// Best price and quantity on the order book of a symbol.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BookTicker {
+    pub symbol: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub bid_price: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub bid_qty: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub ask_price: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub ask_qty: Decimal,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 #[serde(default)]
@@ -233,7 +233,7 @@ pub fn partial_book_depth_stream(symbol: &str, levels: u16, update_speed: u16) -
 /// Wrapper for stream events containing metadata and payload.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamEvent<T> {
-    stream: String,
+    pub stream: String,
     pub data: T,
 }
 
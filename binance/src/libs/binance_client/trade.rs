@@ -5,6 +5,7 @@ use crate::libs::binance_client::{
     api::{Api, Spot},
     client::Client,
     utils,
+    ws::QueryOrderResponse,
 };
 
 #[derive(Clone)]
@@ -86,4 +87,26 @@ impl Trade {
             .post(Api::Spot(Spot::Order), Some(&params), true)
             .await
     }
+
+    /// Check an order's status by its client order id.
+    pub async fn query_order(
+        &self,
+        symbol: &str,
+        orig_client_order_id: &str,
+    ) -> anyhow::Result<QueryOrderResponse> {
+        let ts = utils::get_timestamp(SystemTime::now())?;
+
+        let params = vec![
+            ("symbol".to_owned(), symbol.to_owned()),
+            (
+                "origClientOrderId".to_owned(),
+                orig_client_order_id.to_owned(),
+            ),
+            ("timestamp".to_owned(), ts.to_string()),
+        ];
+
+        self.client
+            .get(Api::Spot(Spot::Order), Some(&params), true)
+            .await
+    }
 }
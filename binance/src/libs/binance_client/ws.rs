@@ -142,66 +142,10 @@ impl WebsocketWriter {
     /// Send in a new order.
     pub async fn place_order(
         &mut self,
-        mut request: PlaceOrderRequest,
+        request: PlaceOrderRequest,
     ) -> anyhow::Result<PlaceOrderResponse> {
-        let mut params: Vec<(String, String)> = Vec::new();
-
         let timestamp = utils::get_timestamp(SystemTime::now())?;
-
-        params.push(("apiKey".to_owned(), self.api_key.clone()));
-        params.push(("side".to_owned(), request.order_side.to_string()));
-        params.push(("symbol".to_owned(), request.symbol.clone()));
-        params.push(("timestamp".to_owned(), timestamp.to_string()));
-        params.push(("type".to_owned(), request.order_type.to_string()));
-
-        if let Some(ref v) = request.iceberg_qty {
-            params.push(("icebergQty".to_owned(), v.clone()));
-        }
-        if let Some(ref v) = request.new_client_order_id {
-            params.push(("newClientOrderId".to_owned(), v.clone()));
-        }
-        if let Some(ref v) = request.new_order_resp_type {
-            params.push(("newOrderRespType".to_owned(), v.to_string()));
-        }
-        if let Some(ref v) = request.price {
-            params.push(("price".to_owned(), v.clone()));
-        }
-        if let Some(ref v) = request.quantity {
-            params.push(("quantity".to_owned(), v.clone()));
-        }
-        if let Some(ref v) = request.quote_order_qty {
-            params.push(("quoteOrderQty".to_owned(), v.clone()));
-        }
-        if let Some(v) = request.recv_window {
-            params.push(("recvWindow".to_owned(), v.to_string()));
-        }
-        if let Some(ref v) = request.self_trade_prevention_mode {
-            params.push(("selfTradePreventionMode".to_owned(), v.to_string()));
-        }
-        if let Some(ref v) = request.stop_price {
-            params.push(("stopPrice".to_owned(), v.clone()));
-        }
-        if let Some(v) = request.strategy_id {
-            params.push(("strategyId".to_owned(), v.to_string()));
-        }
-        if let Some(v) = request.strategy_type {
-            params.push(("strategyType".to_owned(), v.to_string()));
-        }
-        if let Some(ref v) = request.time_in_force {
-            params.push(("timeInForce".to_owned(), v.to_string()));
-        }
-        if let Some(ref v) = request.trailing_delta {
-            params.push(("trailingDelta".to_owned(), v.clone()));
-        }
-
-        params.sort_by(|a, b| a.0.cmp(&b.0));
-
-        let query = build_query_string(&params);
-        let signature = generate_signature(&self.secret_key, Some(&query));
-
-        request.timestamp = Some(timestamp);
-        request.api_key = Some(self.api_key.clone());
-        request.signature = Some(signature);
+        let request = sign_place_order(request, &self.api_key, &self.secret_key, timestamp);
 
         self.send_request::<PlaceOrderRequest, PlaceOrderResponse>(
             WebsocketApi::PlaceOrder,
@@ -362,6 +306,75 @@ impl WebsocketReader {
 }
 
 /// Builds a query string from a vector of sorted key-value pairs.
+/// Signs an order request the way [`WebsocketWriter::place_order`] sends it: the parameters
+/// sorted by name, signed with the secret key, plus the API key and timestamp.
+#[must_use]
+pub fn sign_place_order(
+    mut request: PlaceOrderRequest,
+    api_key: &str,
+    secret_key: &str,
+    timestamp: u64,
+) -> PlaceOrderRequest {
+    let mut params: Vec<(String, String)> = vec![
+        ("apiKey".to_owned(), api_key.to_owned()),
+        ("side".to_owned(), request.order_side.to_string()),
+        ("symbol".to_owned(), request.symbol.clone()),
+        ("timestamp".to_owned(), timestamp.to_string()),
+        ("type".to_owned(), request.order_type.to_string()),
+    ];
+
+    if let Some(ref v) = request.iceberg_qty {
+        params.push(("icebergQty".to_owned(), v.clone()));
+    }
+    if let Some(ref v) = request.new_client_order_id {
+        params.push(("newClientOrderId".to_owned(), v.clone()));
+    }
+    if let Some(ref v) = request.new_order_resp_type {
+        params.push(("newOrderRespType".to_owned(), v.to_string()));
+    }
+    if let Some(ref v) = request.price {
+        params.push(("price".to_owned(), v.clone()));
+    }
+    if let Some(ref v) = request.quantity {
+        params.push(("quantity".to_owned(), v.clone()));
+    }
+    if let Some(ref v) = request.quote_order_qty {
+        params.push(("quoteOrderQty".to_owned(), v.clone()));
+    }
+    if let Some(v) = request.recv_window {
+        params.push(("recvWindow".to_owned(), v.to_string()));
+    }
+    if let Some(ref v) = request.self_trade_prevention_mode {
+        params.push(("selfTradePreventionMode".to_owned(), v.to_string()));
+    }
+    if let Some(ref v) = request.stop_price {
+        params.push(("stopPrice".to_owned(), v.clone()));
+    }
+    if let Some(v) = request.strategy_id {
+        params.push(("strategyId".to_owned(), v.to_string()));
+    }
+    if let Some(v) = request.strategy_type {
+        params.push(("strategyType".to_owned(), v.to_string()));
+    }
+    if let Some(ref v) = request.time_in_force {
+        params.push(("timeInForce".to_owned(), v.to_string()));
+    }
+    if let Some(ref v) = request.trailing_delta {
+        params.push(("trailingDelta".to_owned(), v.clone()));
+    }
+
+    params.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let query = build_query_string(&params);
+    let signature = generate_signature(secret_key, Some(&query));
+
+    request.timestamp = Some(timestamp);
+    request.api_key = Some(api_key.to_owned());
+    request.signature = Some(signature);
+
+    request
+}
+
 fn build_query_string(params: &[(String, String)]) -> String {
     params
         .iter()
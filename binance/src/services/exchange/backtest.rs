@@ -0,0 +1,285 @@
+//! Replay of recorded book ticker events through the chain evaluation, without sending orders.
+
+use std::{collections::HashMap, io::BufRead, sync::Arc};
+
+use anyhow::Context;
+use engine::BacktestReport;
+use rust_decimal::Decimal;
+use tracing::error;
+
+use crate::{
+    config::{Asset, Config},
+    libs::{
+        binance_client,
+        binance_client::{Binance, General, Market},
+    },
+    services::{
+        exchange::{
+            asset::AssetBuilder,
+            chain::{ChainBuilder, ChainSymbol},
+            order::OrderBuilder,
+        },
+        storage::{BookTickerEvent, BookTickerStore},
+    },
+};
+
+/// Evaluates the chains on recorded ticker events and aggregates the chains they would have
+/// produced.
+pub struct Backtest {
+    order_builder: OrderBuilder,
+    chains: Vec<Vec<ChainSymbol>>,
+    base_assets: Vec<Asset>,
+}
+
+impl Backtest {
+    #[must_use]
+    pub fn new(
+        order_builder: OrderBuilder,
+        chains: Vec<Vec<ChainSymbol>>,
+        base_assets: Vec<Asset>,
+    ) -> Self {
+        Self {
+            order_builder,
+            chains,
+            base_assets,
+        }
+    }
+
+    /// Builds the base assets and chains of `config` from the public market data, the same
+    /// way the exchange service does on start.
+    pub async fn from_config(config: &Config) -> anyhow::Result<Self> {
+        let api_config = binance_client::ClientConfig {
+            api_url: config.api_url.clone(),
+            api_token: config.api_token.clone(),
+            api_secret_key: config.api_secret_key.clone(),
+            http_config: binance_client::HttpConfig::default(),
+        };
+        let general_api: General =
+            Binance::new(api_config.clone()).context("Failed to init general binance client")?;
+        let market_api: Market =
+            Binance::new(api_config).context("Failed to init market binance client")?;
+
+        let base_assets = AssetBuilder::new(
+            market_api.clone(),
+            config.assets.clone(),
+            config.min_profit_qty,
+            config.max_order_qty,
+            config.min_ticker_qty_24h,
+        )
+        .update_base_assets_info()
+        .await
+        .context("Failed to update base assets info")?;
+
+        let chains = Arc::new(
+            ChainBuilder::new(
+                general_api,
+                market_api,
+                config.skip_assets.clone(),
+                config.order_type.into(),
+                config.unsupported_order_type,
+            )
+            .with_chain_legs(config.chain_legs),
+        )
+        .build_symbols_chains(base_assets.clone())
+        .await
+        .context("Failed to build symbols chains")?;
+
+        Ok(Self::new(
+            OrderBuilder::from_config(config),
+            chains,
+            base_assets,
+        ))
+    }
+
+    /// Replays the newline-delimited JSON ticker events of `input`.
+    ///
+    /// The events are fed to the chains of their symbol in the recorded order instead of
+    /// going through the ticker broadcast, every chain keeping its own books as it does live.
+    /// Blank lines are skipped.
+    ///
+    /// # Errors
+    /// Returns an error if `input` cannot be read or a line is not a ticker event.
+    pub fn replay(&self, input: impl BufRead) -> anyhow::Result<BacktestReport> {
+        let mut symbol_chains: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (idx, chain) in self.chains.iter().enumerate() {
+            for chain_symbol in chain {
+                symbol_chains
+                    .entry(chain_symbol.symbol.symbol.as_str())
+                    .or_default()
+                    .push(idx);
+            }
+        }
+
+        let mut books: Vec<(BookTickerStore, Vec<Decimal>)> =
+            vec![(self.order_builder.book_store(), vec![]); self.chains.len()];
+        let mut report = BacktestReport::default();
+
+        for (line_idx, line) in input.lines().enumerate() {
+            let line = line.context("Failed to read ticker events")?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let event: BookTickerEvent = serde_json::from_str(&line)
+                .with_context(|| format!("Invalid ticker event on line {}", line_idx + 1))?;
+            report.record_event();
+
+            let Some(chain_indexes) = symbol_chains.get(event.symbol.as_str()) else {
+                continue;
+            };
+            for &idx in chain_indexes {
+                let chain = &self.chains[idx];
+                let (storage, last_prices) = &mut books[idx];
+                let Some((messages, received_at)) = OrderBuilder::chain_books(
+                    storage,
+                    chain,
+                    event.clone(),
+                    last_prices,
+                    self.order_builder.disabled_symbols(),
+                    self.order_builder.is_traced(chain),
+                ) else {
+                    continue;
+                };
+
+                match self.order_builder.build_chain_orders(
+                    chain,
+                    &messages,
+                    &self.base_assets,
+                    received_at,
+                ) {
+                    Ok(Some(chain_orders)) => report.record_chain(&chain_orders),
+                    Ok(None) => {}
+                    Err(e) => error!(error = ?e, "Error during process arbitrage"),
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use engine::{
+        ProfitSchedule,
+        enums::{OrderType, SymbolOrder},
+    };
+
+    use super::*;
+    use crate::libs::binance_client::{Filters, Symbol};
+
+    const BOOK_TICKERS: &str = include_str!("../../../fixtures/book_tickers.jsonl");
+
+    fn chain_symbol(
+        symbol: &str,
+        base_asset: &str,
+        quote_asset: &str,
+        order: SymbolOrder,
+        step_size: &str,
+        tick_size: &str,
+    ) -> ChainSymbol {
+        let step_size = Decimal::from_str(step_size).unwrap();
+        ChainSymbol::new(
+            Symbol {
+                symbol: symbol.to_owned(),
+                base_asset: base_asset.to_owned(),
+                base_asset_precision: 8,
+                quote_asset: quote_asset.to_owned(),
+                quote_precision: 8,
+                filters: vec![
+                    Filters::LotSize {
+                        min_qty: step_size,
+                        max_qty: Decimal::from(9_000),
+                        step_size,
+                    },
+                    Filters::PriceFilter {
+                        min_price: Decimal::from_str(tick_size).unwrap(),
+                        max_price: Decimal::from(1_000_000),
+                        tick_size: Decimal::from_str(tick_size).unwrap(),
+                    },
+                ],
+                ..Default::default()
+            },
+            order,
+        )
+    }
+
+    fn backtest() -> Backtest {
+        let chain = vec![
+            chain_symbol(
+                "BTCUSDT",
+                "BTC",
+                "USDT",
+                SymbolOrder::Asc,
+                "0.00001",
+                "0.01",
+            ),
+            chain_symbol(
+                "ETHUSDT",
+                "ETH",
+                "USDT",
+                SymbolOrder::Desc,
+                "0.0001",
+                "0.01",
+            ),
+            chain_symbol(
+                "ETHBTC",
+                "ETH",
+                "BTC",
+                SymbolOrder::Asc,
+                "0.0001",
+                "0.00001",
+            ),
+        ];
+        let btc = Asset {
+            asset: "BTC".to_owned(),
+            symbol: None,
+            min_profit_qty: Decimal::new(3, 5),
+            min_profit_percent: None,
+            max_order_qty: Decimal::new(3, 4),
+            min_ticker_qty_24h: Decimal::ZERO,
+            session_profit_target: None,
+            max_daily_loss: None,
+        };
+        let order_builder = OrderBuilder::new(
+            Decimal::new(75, 3),
+            OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+            ProfitSchedule::new(vec![]),
+            true,
+            OrderType::Market,
+        );
+        Backtest::new(order_builder, vec![chain], vec![btc])
+    }
+
+    #[test]
+    fn test_replay_aggregates_detected_chains() -> anyhow::Result<()> {
+        let report = backtest().replay(BOOK_TICKERS.as_bytes())?;
+
+        // The ETHBTC spike is the only profitable book, its duplicate is stale.
+        assert_eq!(report.events, 7);
+        assert_eq!(report.chains, 1);
+        assert_eq!(
+            report.profit,
+            [("BTC".to_owned(), Decimal::from_str("0.00006228")?)].into()
+        );
+        assert_eq!(
+            report.symbols,
+            [
+                ("BTCUSDT".to_owned(), 1),
+                ("ETHBTC".to_owned(), 1),
+                ("ETHUSDT".to_owned(), 1),
+            ]
+            .into()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_replay_reports_invalid_line() {
+        let input = format!("{BOOK_TICKERS}{{\"symbol\": \"BTCUSDT\"}}\n");
+
+        let error = backtest().replay(input.as_bytes()).unwrap_err();
+        assert_eq!(error.to_string(), "Invalid ticker event on line 9");
+    }
+}
@@ -1,8 +1,8 @@
-//! Chain builder module for constructing triangular arbitrage symbol chains.
+//! Chain builder module for constructing arbitrage symbol chains.
 //!
-//! This module provides utilities for building valid 3-symbol chains (e.g., BTC/USDT -> ETH/BTC ->
-//! ETH/USDT) from exchange symbols, ensuring connectivity (out asset of one matches in of next) and
-//! order direction (Asc/Desc).
+//! This module provides utilities for building valid chains of N symbols (e.g., BTC/USDT ->
+//! ETH/BTC -> ETH/USDT) from exchange symbols, ensuring connectivity (out asset of one matches in
+//! of next) and order direction (Asc/Desc).
 
 use std::{
     collections::{BTreeMap, HashMap, HashSet, btree_map},
@@ -10,11 +10,12 @@ use std::{
 };
 
 use anyhow::bail;
-use engine::enums::SymbolOrder;
+use engine::{MIN_CHAIN_LEGS, MonitoredSymbol, enums::SymbolOrder};
 use rust_decimal::{Decimal, prelude::Zero};
+use serde::Deserialize;
 use strum::IntoEnumIterator;
 use tokio::task::JoinSet;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use crate::{
     config::Asset,
@@ -23,6 +24,17 @@ use crate::{
     },
 };
 
+/// Handling of chains with a symbol that does not support the leg order type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnsupportedOrderType {
+    /// Drop the chain at startup.
+    #[default]
+    Skip,
+    /// Keep the chain and log the unsupported symbols.
+    Warn,
+}
+
 /// Wrapper for a trading symbol with directional order (Asc for base/quote, Desc for reversed
 /// quote/base).
 #[derive(Clone, Debug)]
@@ -31,6 +43,17 @@ pub struct ChainSymbol {
     pub order: SymbolOrder,
 }
 
+impl From<&ChainSymbol> for MonitoredSymbol {
+    fn from(chain_symbol: &ChainSymbol) -> Self {
+        Self {
+            symbol: chain_symbol.symbol.symbol.clone(),
+            base_asset: chain_symbol.symbol.base_asset.clone(),
+            quote_asset: chain_symbol.symbol.quote_asset.clone(),
+            order: chain_symbol.order,
+        }
+    }
+}
+
 impl ChainSymbol {
     #[must_use]
     pub fn new(symbol: Symbol, order: SymbolOrder) -> Self {
@@ -38,33 +61,53 @@ impl ChainSymbol {
     }
 }
 
-/// Builder for constructing valid triangular symbol chains from exchange data.
+/// Builder for constructing valid symbol chains from exchange data.
 #[derive(Clone)]
 pub struct ChainBuilder {
     general_api: General,
     market_api: Market,
     skip_assets: Vec<String>,
+    order_type: OrderType,
+    unsupported_order_type: UnsupportedOrderType,
+    chain_legs: usize,
 }
 
 impl ChainBuilder {
     #[must_use]
-    pub fn new(general_api: General, market_api: Market, skip_assets: Vec<String>) -> Self {
+    pub fn new(
+        general_api: General,
+        market_api: Market,
+        skip_assets: Vec<String>,
+        order_type: OrderType,
+        unsupported_order_type: UnsupportedOrderType,
+    ) -> Self {
         Self {
             general_api,
             market_api,
             skip_assets,
+            order_type,
+            unsupported_order_type,
+            chain_legs: MIN_CHAIN_LEGS,
         }
     }
 
-    /// Builds all valid 3-symbol chains for the given base assets.
+    /// Builds chains of `chain_legs` symbols instead of three.
+    #[must_use]
+    pub fn with_chain_legs(mut self, chain_legs: usize) -> Self {
+        self.chain_legs = chain_legs;
+        self
+    }
+
+    /// Builds all valid chains of `chain_legs` symbols for the given base assets.
     pub async fn build_symbols_chains(
         self: Arc<Self>,
         base_assets: Vec<Asset>,
-    ) -> anyhow::Result<Vec<[ChainSymbol; 3]>> {
+    ) -> anyhow::Result<Vec<Vec<ChainSymbol>>> {
         let exchange_info = match self.general_api.exchange_info().await {
             Ok(exchange_info) => exchange_info,
             Err(e) => bail!("Failed to get exchange info: {e:?}"),
         };
+        Self::check_asset_symbols(&exchange_info.symbols, &base_assets);
 
         // It is necessary to launch 2 cycles of chain formation for a case where one symbol can
         // contain 2 basic assets specified in the config at once.
@@ -91,6 +134,8 @@ impl ChainBuilder {
         }
 
         let unique_chains = Self::deduplicate_chains(&chains);
+        let unique_chains =
+            Self::check_order_types(unique_chains, &self.order_type, self.unsupported_order_type);
         let filter_chains = self
             .filter_chains_by_24h_vol(&base_assets, unique_chains)
             .await?;
@@ -99,89 +144,93 @@ impl ChainBuilder {
             count = filter_chains.len(),
             "🚀 [Engine] Chains built successfully"
         );
+        for (base_asset, count) in count_chains_by_base_asset(&filter_chains) {
+            info!(base_asset, count, "🚀 [Engine] Chains built for base asset");
+        }
 
         Ok(filter_chains)
     }
 
-    /// Discovers valid 3-symbol chains for a specific order direction.
+    /// Discovers valid chains of `chain_legs` symbols for a specific order direction.
     ///
-    /// Nested loops over symbols to find connected triangles:
+    /// Every first symbol holding a base asset is extended symbol by symbol:
     /// - A -> B (out A = in B)
     /// - B -> C (out B = in C)
-    /// - C -> A (out C = in A, via base asset match)
+    /// - ... -> A (out of the last = in A, via base asset match)
     async fn build_chains(
         &self,
         symbols: &[Symbol],
         order: SymbolOrder,
         base_assets: &[Asset],
         skip_assets: &[String],
-    ) -> Vec<[ChainSymbol; 3]> {
+    ) -> Vec<Vec<ChainSymbol>> {
         let sorted_symbols = Self::sort_symbols(symbols, skip_assets);
         let mut chains = vec![];
 
-        'outer_loop: for a_symbol in &sorted_symbols {
-            if !Self::check_order_type(&a_symbol.order_types) {
-                continue 'outer_loop;
-            }
-
+        for a_symbol in &sorted_symbols {
             let mut a_wrapper = ChainSymbol::new(a_symbol.clone(), Default::default());
             let Some(base_asset) = Self::define_base_asset(&mut a_wrapper, order, base_assets)
             else {
                 continue;
             };
 
-            for b_symbol in &sorted_symbols {
-                if !Self::check_order_type(&a_symbol.order_types) {
-                    continue 'outer_loop;
-                }
-
-                let mut b_wrapper = ChainSymbol::new(b_symbol.clone(), Default::default());
-
-                // Selection symbol for 1st symbol.
-                if !Self::compare_symbols(&a_wrapper, &mut b_wrapper) {
-                    continue;
-                }
+            let mut chain = vec![a_wrapper];
+            self.extend_chain(&sorted_symbols, &base_asset, &mut chain, &mut chains);
+        }
+        chains
+    }
 
-                for c_symbol in &sorted_symbols {
-                    if !Self::check_order_type(&a_symbol.order_types) {
-                        continue 'outer_loop;
-                    }
+    /// Appends to `chains` every chain of `chain_legs` symbols starting with `chain` and
+    /// returning to `base_asset`. Legs in between neither return to `base_asset` nor to an
+    /// asset the chain already received, which would close a shorter cycle.
+    fn extend_chain(
+        &self,
+        symbols: &[Symbol],
+        base_asset: &str,
+        chain: &mut Vec<ChainSymbol>,
+        chains: &mut Vec<Vec<ChainSymbol>>,
+    ) {
+        let last_leg = chain.len() + 1 == self.chain_legs;
 
-                    let mut c_wrapper = ChainSymbol::new(c_symbol.clone(), Default::default());
+        for symbol in symbols {
+            let mut wrapper = ChainSymbol::new(symbol.clone(), Default::default());
 
-                    // Selection symbol for 2nd symbol.
-                    if !Self::compare_symbols(&b_wrapper, &mut c_wrapper) {
-                        continue;
-                    }
+            // Selection symbol for the previous symbol.
+            if !Self::compare_symbols(&chain[chain.len() - 1], &mut wrapper) {
+                continue;
+            }
 
-                    // Define out asset of last symbol.
-                    let out_asset = if c_wrapper.order == SymbolOrder::Desc {
-                        // Ex: BTC:ETH - ETH:USDT - BTC:USDT(reversed) -> base asset of
-                        // last pair because reversed
-                        c_symbol.base_asset.as_str()
-                    } else {
-                        // BTC:ETH - ETH:USDT - USDT:BTC -> quote asset of last pair
-                        c_symbol.quote_asset.as_str()
-                    };
-
-                    // Exit from 3rd symbol must be into base asset from the 1st symbol.
-                    if base_asset != out_asset {
-                        continue;
-                    }
+            // Ex: BTC:ETH - ETH:USDT - BTC:USDT(reversed) -> base asset of last pair because
+            // reversed, BTC:ETH - ETH:USDT - USDT:BTC -> quote asset of last pair.
+            let out_asset = Self::find_out_asset(&wrapper);
 
-                    chains.push([a_wrapper.clone(), b_wrapper.clone(), c_wrapper.clone()]);
+            if last_leg {
+                // Exit from the last symbol must be into base asset from the 1st symbol.
+                if base_asset == out_asset {
+                    let mut full_chain = chain.clone();
+                    full_chain.push(wrapper);
+                    chains.push(full_chain);
                 }
+                continue;
             }
+
+            if base_asset == out_asset || chain.iter().any(|s| Self::find_out_asset(s) == out_asset)
+            {
+                continue;
+            }
+
+            chain.push(wrapper);
+            self.extend_chain(symbols, base_asset, chain, chains);
+            chain.pop();
         }
-        chains
     }
 
     /// Filters chains by minimum 24h volume thresholds, scaled by price and order direction.
     async fn filter_chains_by_24h_vol(
         &self,
         base_assets: &[Asset],
-        chains: Vec<[ChainSymbol; 3]>,
-    ) -> anyhow::Result<Vec<[ChainSymbol; 3]>> {
+        chains: Vec<Vec<ChainSymbol>>,
+    ) -> anyhow::Result<Vec<Vec<ChainSymbol>>> {
         let calc_volume_fn = |volume: Decimal, price: Decimal, order: SymbolOrder| -> Decimal {
             match order {
                 SymbolOrder::Asc => volume * price,
@@ -251,11 +300,46 @@ impl ChainBuilder {
         Ok(filter_chains)
     }
 
-    fn check_order_type(order_types: &[OrderType]) -> bool {
-        const REQUIRE_ORDER_TYPES: [OrderType; 2] = [OrderType::Limit, OrderType::Market];
-        REQUIRE_ORDER_TYPES
-            .iter()
-            .all(|order_type| order_types.contains(order_type))
+    /// Checks every chain symbol against the order type the sender places legs with.
+    /// Chains with an unsupported symbol are dropped or kept depending on `mode`, each such
+    /// symbol is logged once. Exchange info does not list the time in force per symbol, so
+    /// only the order type is checked.
+    fn check_order_types(
+        chains: Vec<Vec<ChainSymbol>>,
+        order_type: &OrderType,
+        mode: UnsupportedOrderType,
+    ) -> Vec<Vec<ChainSymbol>> {
+        let mut unsupported = BTreeMap::new();
+        let chains: Vec<_> = chains
+            .into_iter()
+            .filter(|chain| {
+                let mut supported = true;
+                for chain_symbol in chain {
+                    if !Self::supports_order_type(&chain_symbol.symbol, order_type) {
+                        *unsupported
+                            .entry(chain_symbol.symbol.symbol.clone())
+                            .or_insert(0) += 1;
+                        supported = false;
+                    }
+                }
+                supported || mode == UnsupportedOrderType::Warn
+            })
+            .collect();
+
+        for (symbol, chains_count) in unsupported {
+            warn!(
+                symbol,
+                %order_type,
+                chains_count,
+                mode = ?mode,
+                "⚠️ [Engine] Symbol does not support the leg order type"
+            );
+        }
+        chains
+    }
+
+    fn supports_order_type(symbol: &Symbol, order_type: &OrderType) -> bool {
+        symbol.order_types.contains(order_type)
     }
 
     fn find_base_asset(chain_symbol: &ChainSymbol) -> String {
@@ -267,6 +351,13 @@ impl ChainBuilder {
         }
     }
 
+    fn find_out_asset(chain_symbol: &ChainSymbol) -> &str {
+        match chain_symbol.order {
+            SymbolOrder::Asc => chain_symbol.symbol.quote_asset.as_str(),
+            SymbolOrder::Desc => chain_symbol.symbol.base_asset.as_str(),
+        }
+    }
+
     fn define_base_asset(
         wrapper: &mut ChainSymbol,
         order: SymbolOrder,
@@ -342,9 +433,9 @@ impl ChainBuilder {
         false
     }
 
-    fn deduplicate_chains(chains: &[[ChainSymbol; 3]]) -> Vec<[ChainSymbol; 3]> {
+    fn deduplicate_chains(chains: &[Vec<ChainSymbol>]) -> Vec<Vec<ChainSymbol>> {
         let mut m: BTreeMap<String, bool> = BTreeMap::new();
-        let mut unique_chains: Vec<[ChainSymbol; 3]> = Vec::new();
+        let mut unique_chains: Vec<Vec<ChainSymbol>> = Vec::new();
 
         let define_symbol = |x: &ChainSymbol| -> String {
             match x.order {
@@ -354,15 +445,11 @@ impl ChainBuilder {
         };
 
         for chain in chains.iter() {
-            let key = format!(
-                "{}({}):{}({}):{}({})",
-                define_symbol(&chain[0]),
-                &chain[0].order,
-                define_symbol(&chain[1]),
-                &chain[0].order,
-                define_symbol(&chain[2]),
-                &chain[0].order,
-            );
+            let key = chain
+                .iter()
+                .map(|x| format!("{}({})", define_symbol(x), &chain[0].order))
+                .collect::<Vec<_>>()
+                .join(":");
 
             if let btree_map::Entry::Vacant(e) = m.entry(key) {
                 e.insert(true);
@@ -373,6 +460,26 @@ impl ChainBuilder {
         unique_chains
     }
 
+    /// Warns about the base assets whose `symbol` is not listed on the exchange, their limits
+    /// cannot be converted at its price. Returns those symbols.
+    fn check_asset_symbols<'a>(symbols: &[Symbol], base_assets: &'a [Asset]) -> Vec<&'a str> {
+        let listed: HashSet<&str> = symbols.iter().map(|s| s.symbol.as_str()).collect();
+        base_assets
+            .iter()
+            .filter_map(|asset| {
+                let symbol = asset.symbol.as_deref()?;
+                if listed.contains(symbol) {
+                    return None;
+                }
+                warn!(
+                    asset = %asset.asset,
+                    symbol, "Base asset symbol is not listed on the exchange"
+                );
+                Some(symbol)
+            })
+            .collect()
+    }
+
     /// Sorts and filters a list of trading symbols from an exchange.
     ///
     /// This function:
@@ -408,3 +515,301 @@ pub fn extract_chain_symbols(chain_symbols: &[ChainSymbol]) -> Vec<&str> {
         .map(|v| v.symbol.symbol.as_str())
         .collect()
 }
+
+/// Counts the chains starting from each base asset.
+#[must_use]
+pub fn count_chains_by_base_asset(chains: &[Vec<ChainSymbol>]) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for chain in chains {
+        if let Some(first) = chain.first() {
+            *counts
+                .entry(ChainBuilder::find_base_asset(first))
+                .or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use engine::ProfitSchedule;
+
+    use super::*;
+    use crate::{
+        libs::binance_client::{self, Binance, Filters},
+        services::{exchange::order::OrderBuilder, storage::BookTickerEvent},
+    };
+
+    fn chain_symbol(name: &str, order_types: Vec<OrderType>) -> ChainSymbol {
+        let symbol = Symbol {
+            symbol: name.to_owned(),
+            order_types,
+            ..Default::default()
+        };
+        ChainSymbol::new(symbol, SymbolOrder::Asc)
+    }
+
+    fn chains() -> Vec<Vec<ChainSymbol>> {
+        let full = || vec![OrderType::Limit, OrderType::Market];
+        vec![
+            vec![
+                chain_symbol("BTCUSDT", full()),
+                chain_symbol("ETHBTC", full()),
+                chain_symbol("ETHUSDT", full()),
+            ],
+            vec![
+                chain_symbol("BTCUSDT", full()),
+                // Only the middle symbol lacks MARKET.
+                chain_symbol("XRPBTC", vec![OrderType::Limit, OrderType::LimitMaker]),
+                chain_symbol("XRPUSDT", full()),
+            ],
+        ]
+    }
+
+    #[test]
+    fn test_symbol_without_leg_order_type_is_flagged() {
+        let symbol = |chain: &[ChainSymbol]| chain[1].symbol.symbol.clone();
+
+        let skipped = ChainBuilder::check_order_types(
+            chains(),
+            &OrderType::Market,
+            UnsupportedOrderType::Skip,
+        );
+        assert_eq!(
+            skipped.iter().map(|c| symbol(c)).collect::<Vec<_>>(),
+            ["ETHBTC"]
+        );
+
+        let warned = ChainBuilder::check_order_types(
+            chains(),
+            &OrderType::Market,
+            UnsupportedOrderType::Warn,
+        );
+        assert_eq!(
+            warned.iter().map(|c| symbol(c)).collect::<Vec<_>>(),
+            ["ETHBTC", "XRPBTC"]
+        );
+        assert!(!ChainBuilder::supports_order_type(
+            &warned[1][1].symbol,
+            &OrderType::Market
+        ));
+    }
+
+    #[test]
+    fn test_asset_with_unlisted_symbol_is_reported() {
+        let symbols = [
+            chain_symbol("BTCUSDT", vec![]).symbol,
+            chain_symbol("ETHBTC", vec![]).symbol,
+        ];
+        let asset = |asset: &str, symbol: Option<&str>| Asset {
+            asset: asset.to_owned(),
+            symbol: symbol.map(ToOwned::to_owned),
+            min_profit_qty: Decimal::ZERO,
+            min_profit_percent: None,
+            max_order_qty: Decimal::ZERO,
+            min_ticker_qty_24h: Decimal::ZERO,
+            session_profit_target: None,
+            max_daily_loss: None,
+        };
+        let base_assets = [
+            asset("USDT", None),
+            asset("BTC", Some("BTCUSDT")),
+            asset("ETH", Some("ETHUSDC")),
+        ];
+
+        assert_eq!(
+            ChainBuilder::check_asset_symbols(&symbols, &base_assets),
+            ["ETHUSDC"]
+        );
+    }
+
+    fn chain_builder() -> anyhow::Result<ChainBuilder> {
+        let api_config = binance_client::ClientConfig {
+            api_url: String::new(),
+            api_token: String::new(),
+            api_secret_key: String::new(),
+            http_config: binance_client::HttpConfig::default(),
+        };
+        Ok(ChainBuilder::new(
+            Binance::new(api_config.clone())?,
+            Binance::new(api_config)?,
+            vec![],
+            OrderType::Market,
+            UnsupportedOrderType::Skip,
+        ))
+    }
+
+    async fn build_unique_chains(
+        builder: &ChainBuilder,
+        symbols: &[Symbol],
+        base_assets: &[Asset],
+    ) -> Vec<Vec<ChainSymbol>> {
+        let mut chains = vec![];
+        for order in SymbolOrder::iter() {
+            chains.extend(builder.build_chains(symbols, order, base_assets, &[]).await);
+        }
+        ChainBuilder::deduplicate_chains(&chains)
+    }
+
+    #[tokio::test]
+    async fn test_chains_of_every_base_asset_are_built() -> anyhow::Result<()> {
+        let builder = chain_builder()?;
+        let symbol = |symbol: &str, base_asset: &str, quote_asset: &str| Symbol {
+            symbol: symbol.to_owned(),
+            base_asset: base_asset.to_owned(),
+            quote_asset: quote_asset.to_owned(),
+            ..Default::default()
+        };
+        let symbols = [
+            symbol("BTCUSDT", "BTC", "USDT"),
+            symbol("ETHBTC", "ETH", "BTC"),
+            symbol("ETHUSDT", "ETH", "USDT"),
+        ];
+        let asset = |asset: &str| Asset {
+            asset: asset.to_owned(),
+            symbol: None,
+            min_profit_qty: Decimal::ZERO,
+            min_profit_percent: None,
+            max_order_qty: Decimal::ONE,
+            min_ticker_qty_24h: Decimal::ZERO,
+            session_profit_target: None,
+            max_daily_loss: None,
+        };
+        let base_assets = [asset("USDT"), asset("BTC")];
+
+        let chains = build_unique_chains(&builder, &symbols, &base_assets).await;
+
+        // Both base assets fund chains of the same run, each chain returning to its own.
+        let counts = count_chains_by_base_asset(&chains);
+        assert_eq!(
+            counts,
+            BTreeMap::from([("BTC".to_owned(), 2), ("USDT".to_owned(), 2)])
+        );
+        for chain in &chains {
+            let last = &chain[chain.len() - 1];
+            let out_asset = match last.order {
+                SymbolOrder::Asc => &last.symbol.quote_asset,
+                SymbolOrder::Desc => &last.symbol.base_asset,
+            };
+            assert_eq!(*out_asset, ChainBuilder::find_base_asset(&chain[0]));
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_four_leg_chain_is_built_and_priced() -> anyhow::Result<()> {
+        let symbol = |symbol: &str, base_asset: &str, quote_asset: &str, lot_step, tick_size| {
+            let filters: Vec<Filters> = serde_json::from_str(&format!(
+                r#"[
+                    {{"filterType": "PRICE_FILTER", "minPrice": "0.00001", "maxPrice": "1000000", "tickSize": "{tick_size}"}},
+                    {{"filterType": "LOT_SIZE", "minQty": "{lot_step}", "maxQty": "9000", "stepSize": "{lot_step}"}}
+                ]"#
+            ))?;
+            anyhow::Ok(Symbol {
+                symbol: symbol.to_owned(),
+                base_asset: base_asset.to_owned(),
+                base_asset_precision: 8,
+                quote_asset: quote_asset.to_owned(),
+                quote_precision: 8,
+                order_types: vec![OrderType::Market],
+                filters,
+                ..Default::default()
+            })
+        };
+        let symbols = [
+            symbol("BTCUSDT", "BTC", "USDT", "0.00001", "0.01")?,
+            symbol("ETHBTC", "ETH", "BTC", "0.0001", "0.00001")?,
+            symbol("SOLETH", "SOL", "ETH", "0.001", "0.00001")?,
+            symbol("SOLUSDT", "SOL", "USDT", "0.001", "0.01")?,
+        ];
+        let usdt = Asset {
+            asset: "USDT".to_owned(),
+            symbol: None,
+            min_profit_qty: Decimal::new(616, 2),
+            min_profit_percent: None,
+            max_order_qty: Decimal::from(100),
+            min_ticker_qty_24h: Decimal::ZERO,
+            session_profit_target: None,
+            max_daily_loss: None,
+        };
+        let base_assets = [usdt];
+
+        // No 3-leg chain cycles back to USDT.
+        let chains = build_unique_chains(&chain_builder()?, &symbols, &base_assets).await;
+        assert!(chains.is_empty());
+
+        // USDT -> BTC -> ETH -> SOL -> USDT, and the same cycle the other way round.
+        let builder = chain_builder()?.with_chain_legs(4);
+        let chains = build_unique_chains(&builder, &symbols, &base_assets).await;
+        let legs: Vec<Vec<_>> = chains
+            .iter()
+            .map(|chain| {
+                chain
+                    .iter()
+                    .map(|s| (s.symbol.symbol.as_str(), s.order))
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            legs,
+            [
+                vec![
+                    ("BTCUSDT", SymbolOrder::Desc),
+                    ("ETHBTC", SymbolOrder::Desc),
+                    ("SOLETH", SymbolOrder::Desc),
+                    ("SOLUSDT", SymbolOrder::Asc),
+                ],
+                vec![
+                    ("SOLUSDT", SymbolOrder::Desc),
+                    ("SOLETH", SymbolOrder::Asc),
+                    ("ETHBTC", SymbolOrder::Asc),
+                    ("BTCUSDT", SymbolOrder::Asc),
+                ],
+            ]
+        );
+
+        let book = |symbol: &str, bid_price: Decimal, ask_price: Decimal| BookTickerEvent {
+            update_id: 1,
+            symbol: symbol.to_owned(),
+            bid_price,
+            bid_qty: Decimal::from(1000),
+            ask_price,
+            ask_qty: Decimal::from(1000),
+            ..Default::default()
+        };
+        let order_book = [
+            book("BTCUSDT", Decimal::from(99_999), Decimal::from(100_000)),
+            book("ETHBTC", Decimal::new(2999, 5), Decimal::new(3, 2)),
+            book("SOLETH", Decimal::new(4999, 5), Decimal::new(5, 2)),
+            book("SOLUSDT", Decimal::from(160), Decimal::new(16_001, 2)),
+        ];
+        let order_builder = OrderBuilder::new(
+            Decimal::new(1, 1),
+            OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+            ProfitSchedule::new(vec![]),
+            true,
+            engine::enums::OrderType::Market,
+        );
+
+        // 100 USDT come back as 106.56, 6.16 after the fees of four legs.
+        let chain_orders = order_builder
+            .build_chain_orders(&chains[0], &order_book, &base_assets, None)?
+            .expect("chain passes the profit gate");
+        assert_eq!(chain_orders.base_asset, "USDT");
+        let orders: Vec<_> = chain_orders
+            .orders
+            .iter()
+            .map(|order| (order.symbol.as_str(), order.base_qty, order.quote_qty))
+            .collect();
+        assert_eq!(
+            orders,
+            [
+                ("BTCUSDT", Decimal::from(100), Decimal::new(1, 3)),
+                ("ETHBTC", Decimal::new(1, 3), Decimal::new(333, 4)),
+                ("SOLETH", Decimal::new(333, 4), Decimal::new(666, 3)),
+                ("SOLUSDT", Decimal::new(666, 3), Decimal::new(10_656, 2)),
+            ]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,186 @@
+//! Dust conversion module.
+//!
+//! Rounding quantities across many chains leaves small balances of intermediate assets that
+//! are below the lot size and can no longer be traded. The converter periodically, and once
+//! more on shutdown, converts such balances to BNB to keep the account clean.
+
+use std::time::Duration;
+
+use anyhow::Context;
+use rust_decimal::Decimal;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::libs::binance_client::Account;
+
+/// Converts dust balances to BNB.
+pub struct DustConverter {
+    account_api: Account,
+    enabled: bool,
+    interval: Option<Duration>,
+    max_dust_btc: Decimal,
+    keep_assets: Vec<String>,
+}
+
+impl DustConverter {
+    /// Asset dust is converted to, never converted itself.
+    const DUST_TARGET: &str = "BNB";
+
+    /// Creates a converter of balances worth less than `max_dust_btc`, run every `interval`
+    /// (only on shutdown if unset). `keep_assets` are never converted.
+    #[must_use]
+    pub fn new(
+        account_api: Account,
+        enabled: bool,
+        interval: Option<Duration>,
+        max_dust_btc: Decimal,
+        keep_assets: Vec<String>,
+    ) -> Self {
+        Self {
+            account_api,
+            enabled,
+            interval,
+            max_dust_btc,
+            keep_assets,
+        }
+    }
+
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Converts dust every interval until cancelled, then once more before returning.
+    /// Failed conversions are logged and retried on the next run.
+    pub async fn run(&self, token: CancellationToken) -> anyhow::Result<()> {
+        if let Some(interval) = self.interval {
+            let mut interval = tokio::time::interval(interval);
+            interval.tick().await;
+            loop {
+                tokio::select! {
+                    () = token.cancelled() => break,
+                    _ = interval.tick() => self.convert_logged().await,
+                }
+            }
+        } else {
+            token.cancelled().await;
+        }
+
+        self.convert_logged().await;
+        Ok(())
+    }
+
+    async fn convert_logged(&self) {
+        if let Err(e) = self.convert().await {
+            warn!(error = ?e, "Failed to convert dust");
+        }
+    }
+
+    /// Converts the convertible assets worth less than the threshold to BNB.
+    /// Returns the converted assets.
+    pub async fn convert(&self) -> anyhow::Result<Vec<String>> {
+        let dust = self
+            .account_api
+            .dust_assets()
+            .await
+            .context("Failed to get dust assets")?;
+
+        let assets: Vec<String> = dust
+            .details
+            .into_iter()
+            .filter(|asset| {
+                asset.to_btc < self.max_dust_btc
+                    && asset.asset != Self::DUST_TARGET
+                    && !self.keep_assets.contains(&asset.asset)
+            })
+            .map(|asset| asset.asset)
+            .collect();
+
+        if assets.is_empty() {
+            return Ok(assets);
+        }
+
+        let transfer = self
+            .account_api
+            .convert_dust(&assets)
+            .await
+            .with_context(|| format!("Failed to convert dust of {assets:?}"))?;
+        info!(
+            ?assets,
+            bnb = %transfer.total_transfered,
+            fee = %transfer.total_service_charge,
+            "🧹 [Engine] Dust converted to BNB"
+        );
+
+        Ok(assets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockito::{Matcher, Server};
+
+    use super::*;
+    use crate::libs::binance_client::{self, Binance};
+
+    #[tokio::test]
+    async fn test_dust_below_threshold_is_converted() -> anyhow::Result<()> {
+        let mut server = Server::new_async().await;
+        let dust_assets = server
+            .mock("POST", "/sapi/v1/asset/dust-btc")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "details": [
+                        {"asset": "ADA", "amountFree": "6.21", "toBTC": "0.00016848", "toBNB": "0.01777302"},
+                        {"asset": "XRP", "amountFree": "120", "toBTC": "0.0021", "toBNB": "0.21"},
+                        {"asset": "USDT", "amountFree": "0.5", "toBTC": "0.000005", "toBNB": "0.0008"},
+                        {"asset": "ETH", "amountFree": "0.0001", "toBTC": "0.000003", "toBNB": "0.0005"}
+                    ],
+                    "totalTransferBtc": "0.00217151",
+                    "totalTransferBNB": "0.22537302",
+                    "dribbletPercentage": "0.02"
+                }"#,
+            )
+            .create_async()
+            .await;
+        // Only ADA and ETH: XRP is above the threshold and USDT is a base asset.
+        let convert = server
+            .mock("POST", "/sapi/v1/asset/dust")
+            .match_query(Matcher::Regex(
+                "^asset=ADA&asset=ETH&timestamp=[0-9]+&signature=".to_owned(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"totalServiceCharge": "0.00004556", "totalTransfered": "0.00227807", "transferResult": []}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let account_api: Account = Binance::new(binance_client::ClientConfig {
+            api_url: server.url(),
+            api_token: "test_api_key".to_owned(),
+            api_secret_key: "test_secret_key".to_owned(),
+            http_config: binance_client::HttpConfig::default(),
+        })?;
+        let converter = DustConverter::new(
+            account_api,
+            true,
+            None,
+            Decimal::new(1, 3),
+            vec!["USDT".to_owned()],
+        );
+
+        let token = CancellationToken::new();
+        token.cancel();
+        converter.run(token).await?;
+
+        dust_assets.assert_async().await;
+        convert.assert_async().await;
+        Ok(())
+    }
+}
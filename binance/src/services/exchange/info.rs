@@ -0,0 +1,251 @@
+//! Exchange info refresh module.
+//!
+//! Chains are built from the exchange info fetched at startup, but symbol filters and statuses
+//! change over time. The refresher re-fetches exchange info once it is older than the configured
+//! age and publishes the symbols, so running chains can swap in the fresh filters in place.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use anyhow::Context;
+use engine::REQUEST_WEIGHT;
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::{
+    libs::binance_client::{General, Symbol},
+    services::exchange::chain::ChainSymbol,
+};
+
+/// Trading symbols from the latest exchange info, keyed by symbol name.
+pub type SymbolsInfo = Arc<HashMap<String, Symbol>>;
+
+/// Periodically re-fetches exchange info and publishes the refreshed symbols.
+pub struct ExchangeInfoRefresher {
+    general_api: General,
+    max_age: Option<Duration>,
+    tx: watch::Sender<SymbolsInfo>,
+}
+
+impl ExchangeInfoRefresher {
+    /// Request weight of the exchange info endpoint.
+    const EXCHANGE_INFO_WEIGHT: usize = 20;
+    /// Delay before retrying a failed refresh.
+    const RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+    /// Creates a refresher; with no `max_age` exchange info is never refreshed.
+    #[must_use]
+    pub fn new(general_api: General, max_age: Option<Duration>) -> Self {
+        let (tx, _) = watch::channel(SymbolsInfo::default());
+        Self {
+            general_api,
+            max_age,
+            tx,
+        }
+    }
+
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.max_age.is_some()
+    }
+
+    /// Subscribes to refreshed symbols.
+    #[must_use]
+    pub fn subscribe(&self) -> watch::Receiver<SymbolsInfo> {
+        self.tx.subscribe()
+    }
+
+    /// Refreshes exchange info every time it reaches the maximum age, until cancelled.
+    /// Failed refreshes are logged and retried sooner.
+    pub async fn run(&self, token: CancellationToken) -> anyhow::Result<()> {
+        let Some(max_age) = self.max_age else {
+            return Ok(());
+        };
+
+        let mut delay = max_age;
+        loop {
+            tokio::select! {
+                () = token.cancelled() => return Ok(()),
+                () = tokio::time::sleep(delay) => {}
+            }
+
+            delay = match self.refresh().await {
+                Ok(count) => {
+                    info!(count, "🔄 [Engine] Exchange info refreshed");
+                    max_age
+                }
+                Err(e) => {
+                    warn!(error = ?e, "Failed to refresh exchange info");
+                    Self::RETRY_INTERVAL.min(max_age)
+                }
+            };
+        }
+    }
+
+    /// Fetches exchange info within the request weight limit and publishes its symbols.
+    /// Returns the number of symbols.
+    async fn refresh(&self) -> anyhow::Result<usize> {
+        loop {
+            if REQUEST_WEIGHT.lock().await.add(Self::EXCHANGE_INFO_WEIGHT) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+
+        let exchange_info = self
+            .general_api
+            .exchange_info()
+            .await
+            .context("Failed to get exchange info")?;
+
+        let symbols: HashMap<String, Symbol> = exchange_info
+            .symbols
+            .into_iter()
+            .map(|symbol| (symbol.symbol.clone(), symbol))
+            .collect();
+        let count = symbols.len();
+        self.tx.send_replace(Arc::new(symbols));
+
+        Ok(count)
+    }
+}
+
+/// Replaces the chain's symbol data with the refreshed one, keeping the symbol order.
+/// Returns false if any symbol is no longer trading, in which case the chain must be paused.
+pub fn apply_symbols_info(chain: &mut [ChainSymbol], symbols: &HashMap<String, Symbol>) -> bool {
+    let mut listed = true;
+    for chain_symbol in chain.iter_mut() {
+        match symbols.get(&chain_symbol.symbol.symbol) {
+            Some(symbol) => chain_symbol.symbol = symbol.clone(),
+            None => listed = false,
+        }
+    }
+    listed
+}
+
+#[cfg(test)]
+mod tests {
+    use engine::enums::SymbolOrder;
+    use mockito::{Matcher, Server};
+    use rust_decimal::Decimal;
+
+    use super::*;
+    use crate::libs::binance_client::{self, Binance, Filters};
+
+    fn symbol(name: &str, step_size: Decimal) -> Symbol {
+        Symbol {
+            symbol: name.to_owned(),
+            filters: vec![Filters::LotSize {
+                min_qty: step_size,
+                max_qty: Decimal::from(9000),
+                step_size,
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn lot_step(chain_symbol: &ChainSymbol) -> Option<Decimal> {
+        chain_symbol
+            .symbol
+            .filters
+            .iter()
+            .find_map(|filter| match filter {
+                Filters::LotSize { step_size, .. } => Some(*step_size),
+                _ => None,
+            })
+    }
+
+    #[tokio::test]
+    async fn test_refresh_after_max_age() -> anyhow::Result<()> {
+        REQUEST_WEIGHT.lock().await.set_weight_limit(6000);
+
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v3/exchangeInfo")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "timezone": "UTC",
+                    "serverTime": 1753314650438,
+                    "symbols": [{
+                        "symbol": "BTCUSDT",
+                        "status": "TRADING",
+                        "baseAsset": "BTC",
+                        "baseAssetPrecision": 8,
+                        "quoteAsset": "USDT",
+                        "quotePrecision": 8,
+                        "baseCommissionPrecision": 8,
+                        "quoteCommissionPrecision": 8,
+                        "orderTypes": ["LIMIT", "MARKET"],
+                        "icebergAllowed": true,
+                        "isSpotTradingAllowed": true,
+                        "isMarginTradingAllowed": true,
+                        "filters": [{
+                            "filterType": "LOT_SIZE",
+                            "minQty": "0.00010000",
+                            "maxQty": "9000.00000000",
+                            "stepSize": "0.00010000"
+                        }]
+                    }]
+                }"#,
+            )
+            .create_async()
+            .await;
+
+        let general_api: General = Binance::new(binance_client::ClientConfig {
+            api_url: server.url(),
+            api_token: "test_api_key".to_owned(),
+            api_secret_key: "test_secret_key".to_owned(),
+            http_config: binance_client::HttpConfig::default(),
+        })?;
+        let refresher = ExchangeInfoRefresher::new(general_api, Some(Duration::from_millis(50)));
+        let mut symbols_rx = refresher.subscribe();
+
+        let token = CancellationToken::new();
+        let refreshed = tokio::select! {
+            result = refresher.run(token.clone()) => anyhow::bail!("Refresher stopped: {result:?}"),
+            result = tokio::time::timeout(Duration::from_secs(5), symbols_rx.changed()) => result,
+        };
+        token.cancel();
+        refreshed??;
+        mock.assert_async().await;
+
+        let symbols = symbols_rx.borrow_and_update().clone();
+        let mut chain = [
+            ChainSymbol::new(symbol("BTCUSDT", Decimal::new(1, 5)), SymbolOrder::Asc),
+            ChainSymbol::new(symbol("ETHBTC", Decimal::new(1, 4)), SymbolOrder::Desc),
+            ChainSymbol::new(symbol("ETHUSDT", Decimal::new(1, 4)), SymbolOrder::Asc),
+        ];
+
+        // ETHBTC and ETHUSDT are missing from the refreshed info, so the chain is paused,
+        // while BTCUSDT still picks up its new step size.
+        assert!(!apply_symbols_info(&mut chain, &symbols));
+        assert_eq!(lot_step(&chain[0]), Some(Decimal::new(1, 4)));
+        assert_eq!(chain[0].order, SymbolOrder::Asc);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_updated_filters() {
+        let mut chain = [
+            ChainSymbol::new(symbol("BTCUSDT", Decimal::new(1, 5)), SymbolOrder::Asc),
+            ChainSymbol::new(symbol("ETHBTC", Decimal::new(1, 4)), SymbolOrder::Desc),
+            ChainSymbol::new(symbol("ETHUSDT", Decimal::new(1, 4)), SymbolOrder::Asc),
+        ];
+        let symbols: HashMap<String, Symbol> = [
+            symbol("BTCUSDT", Decimal::new(1, 5)),
+            symbol("ETHBTC", Decimal::new(1, 3)),
+            symbol("ETHUSDT", Decimal::new(1, 4)),
+        ]
+        .into_iter()
+        .map(|symbol| (symbol.symbol.clone(), symbol))
+        .collect();
+
+        assert!(apply_symbols_info(&mut chain, &symbols));
+        assert_eq!(lot_step(&chain[1]), Some(Decimal::new(1, 3)));
+        assert_eq!(chain[1].order, SymbolOrder::Desc);
+    }
+}
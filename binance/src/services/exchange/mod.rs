@@ -1,5 +1,10 @@
 pub mod asset;
+pub mod backtest;
 pub mod chain;
+pub mod dust;
+pub mod info;
 pub mod order;
+pub mod recorder;
 pub mod service;
+pub mod snapshot;
 pub mod ticker;
@@ -1,32 +1,42 @@
 //! Order builder module for arbitrage chain processing and profit calculation.
 //!
-//! This module provides an `OrderBuilder` for monitoring ticker updates in triangular chains,
+//! This module provides an `OrderBuilder` for monitoring ticker updates in arbitrage chains,
 //! calculating potential arbitrage profits by simulating order fills (considering depth, fees,
 //! filters), and generating executable `Order` chains when thresholds are met. It uses broadcast
 //! channels for real-time events, scales quantities by precision/tick sizes, and propagates qty
 //! limits across the chain. Supports Asc/Desc symbol orders with lot/tick filters from exchange
 //! info.
 
-use std::{ops::Sub, sync::Arc};
+use std::{collections::BTreeMap, num::NonZeroU64, ops::Sub, str::FromStr, sync::Arc};
 
-use engine::{ChainOrder, ChainOrders, METRICS, ORDERS_CHANNEL, enums::SymbolOrder};
-use itertools::Itertools;
+use anyhow::{Context, bail};
+use engine::{
+    BalanceCap, ChainOrder, ChainOrders, ComputePool, DisabledSymbols, FeeSchedule, METRICS,
+    MinNotional, ORDERS_CHANNEL, ProcessedChainCounter, ProfitSchedule, TimeOfDay,
+    UpdateLatencySampler, UsdProfitFloor, check_chain_cycle, check_chain_length,
+    enums::{OrderType, SymbolOrder},
+};
 use rust_decimal::{
-    Decimal,
+    Decimal, RoundingStrategy,
     prelude::{FromPrimitive, Zero},
 };
-use tokio::task::JoinSet;
+use serde::Deserialize;
+use tokio::{sync::watch, task::JoinSet, time::Instant};
 use tokio_util::sync::CancellationToken;
 use tools::misc;
-use tracing::error;
+use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
 use crate::{
-    config::Asset,
+    config::{Asset, Config},
     libs::binance_client::Filters,
     services::{
-        broadcast::TICKER_BROADCAST,
-        exchange::{chain, chain::ChainSymbol},
+        broadcast::{TICKER_BROADCAST, next_changed},
+        exchange::{
+            chain,
+            chain::ChainSymbol,
+            info::{SymbolsInfo, apply_symbols_info},
+        },
         storage::{BookTickerEvent, BookTickerStore},
     },
 };
@@ -38,7 +48,11 @@ pub struct OrderSymbol<'a> {
     pub base_asset_precision: u32,
     pub quote_precision: u32,
     pub symbol_order: SymbolOrder,
+    /// Order type used for this leg, selects the applicable lot size filter.
+    pub order_type: OrderType,
     pub min_profit_qty: Option<Decimal>,
+    /// Minimum profit as a percentage of the quantity spent by the first leg, set on it.
+    pub min_profit_percent: Option<Decimal>,
     pub max_order_qty: Option<Decimal>,
     pub order_book: &'a BookTickerEvent,
     pub symbol_filter: SymbolFilter,
@@ -54,6 +68,7 @@ pub struct PreOrder {
     base_precision: u32,
     quote_qty: Decimal,
     quote_precision: u32,
+    order_type: OrderType,
     symbol_filter: SymbolFilter,
 }
 
@@ -63,6 +78,26 @@ pub struct SymbolFilter {
     pub lot_size_step: u32,
     pub tick_size: u32,
     pub lot_size_min_qty: Decimal,
+    /// Step from `MARKET_LOT_SIZE`, if the symbol defines a market-specific one.
+    pub market_lot_size_step: Option<u32>,
+    pub market_lot_size_min_qty: Decimal,
+    /// Minimum order value in quote asset from `NOTIONAL` (or legacy `MIN_NOTIONAL`).
+    pub min_notional: Decimal,
+}
+
+impl SymbolFilter {
+    /// Returns the step scale and minimum quantity that apply to an order of the given type.
+    /// Market orders follow `MARKET_LOT_SIZE` when it is set, everything else `LOT_SIZE`.
+    #[must_use]
+    pub fn lot_size(&self, order_type: &OrderType) -> (u32, Decimal) {
+        match (order_type, self.market_lot_size_step) {
+            (OrderType::Market, Some(step)) => (
+                step,
+                self.lot_size_min_qty.max(self.market_lot_size_min_qty),
+            ),
+            _ => (self.lot_size_step, self.lot_size_min_qty),
+        }
+    }
 }
 
 pub struct OrderBookUnit {
@@ -70,52 +105,311 @@ pub struct OrderBookUnit {
     pub qty: Decimal,
 }
 
+/// Rounding of a limit leg price that is off the tick grid.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PriceRounding {
+    /// Toward the aggressive side so the leg stays marketable: up for Desc (buy) legs, down
+    /// for Asc (sell) legs.
+    #[default]
+    Aggressive,
+    /// Down whatever the side, which may leave a buy leg below the ask.
+    Truncate,
+}
+
+/// Placement of limit leg prices relative to the book.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LimitPricing {
+    pub rounding: PriceRounding,
+    /// Ticks the price is moved toward the aggressive side, past the touch.
+    pub offset_ticks: u32,
+}
+
+impl LimitPricing {
+    /// Returns the price a leg is placed and sized at, on the grid of `tick_size` decimals.
+    /// Market legs are truncated as their price only sizes the order, limit legs are rounded
+    /// as set by `rounding` and moved `offset_ticks` toward the aggressive side.
+    fn leg_price(
+        self,
+        price: Decimal,
+        tick_size: u32,
+        order_type: OrderType,
+        symbol_order: SymbolOrder,
+    ) -> Decimal {
+        if order_type == OrderType::Market {
+            return price.trunc_with_scale(tick_size);
+        }
+
+        let rounded = match (symbol_order, self.rounding) {
+            (SymbolOrder::Desc, PriceRounding::Aggressive) => {
+                // Rounded up, at the same scale as a truncated price.
+                price
+                    .round_dp_with_strategy(tick_size, RoundingStrategy::ToPositiveInfinity)
+                    .trunc_with_scale(tick_size)
+            }
+            (SymbolOrder::Desc, PriceRounding::Truncate) | (SymbolOrder::Asc, _) => {
+                price.trunc_with_scale(tick_size)
+            }
+        };
+
+        let tick = Decimal::new(1, tick_size);
+        let offset = tick * Decimal::from(self.offset_ticks);
+        match symbol_order {
+            SymbolOrder::Desc => rounded + offset,
+            // A sell leg never goes below a single tick.
+            SymbolOrder::Asc => (rounded - offset).max(tick),
+        }
+    }
+}
+
 /// Builder for processing arbitrage chains and generating profitable orders.
 pub struct OrderBuilder {
     market_depth_limit: usize,
-    fee_percent: Decimal,
+    max_depth_iterations: usize,
+    fees: FeeSchedule,
+    min_notional: MinNotional,
+    usd_profit_floor: Option<&'static UsdProfitFloor>,
+    compute_pool: Option<Arc<ComputePool>>,
+    balance_cap: Option<Arc<BalanceCap>>,
+    /// Chains whose every decision is logged.
+    debug_chains: Vec<Vec<String>>,
+    profit_schedule: ProfitSchedule,
+    verify_chain_cycle: bool,
+    order_type: OrderType,
+    limit_pricing: LimitPricing,
+    allow_crossed_books: bool,
+    /// Symbols whose chains are paused at runtime.
+    disabled_symbols: Arc<DisabledSymbols>,
+    update_latency: UpdateLatencySampler,
+}
+
+/// Symbol a base asset is priced in USD from.
+#[derive(Clone, Debug, PartialEq)]
+struct UsdPriceSymbol {
+    symbol: String,
+    asset: String,
+    /// The USD asset is the base of the symbol, so its price is inverted.
+    inverted: bool,
 }
 
 impl OrderBuilder {
+    pub const DEFAULT_MAX_DEPTH_ITERATIONS: usize = 10;
+
     #[must_use]
-    pub fn new(fee_percent: Decimal) -> Self {
+    pub fn new(
+        fee_percent: Decimal,
+        max_depth_iterations: usize,
+        profit_schedule: ProfitSchedule,
+        verify_chain_cycle: bool,
+        order_type: OrderType,
+    ) -> Self {
         Self {
-            market_depth_limit: 1, // always 1
-            fee_percent,
+            market_depth_limit: 1,
+            max_depth_iterations,
+            fees: FeeSchedule::flat(fee_percent),
+            min_notional: MinNotional::default(),
+            usd_profit_floor: None,
+            compute_pool: None,
+            balance_cap: None,
+            debug_chains: vec![],
+            profit_schedule,
+            verify_chain_cycle,
+            order_type,
+            limit_pricing: LimitPricing::default(),
+            allow_crossed_books: false,
+            disabled_symbols: Arc::default(),
+            update_latency: UpdateLatencySampler::default(),
         }
     }
 
-    /// Builds and monitors order processing tasks for the given chains.
+    /// Builds the chain evaluation settings of `config`, without the runtime dependencies
+    /// (USD profit floor, compute pool and balance cap).
+    #[must_use]
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(
+            config.fee_percent,
+            config.max_depth_iterations,
+            ProfitSchedule::new(config.min_profit_schedule.clone()),
+            config.verify_chain_cycle,
+            config.order_type,
+        )
+        .with_market_depth_limit(config.market_depth_levels.map_or(1, usize::from))
+        .with_fee_schedule(config.fee_schedule())
+        .with_min_notional(MinNotional::new(config.min_notional.clone()))
+        .with_debug_chains(config.debug_chains.clone())
+        .with_limit_pricing(LimitPricing {
+            rounding: config.limit_price_rounding,
+            offset_ticks: config.limit_price_offset_ticks,
+        })
+        .with_crossed_books_allowed(config.allow_crossed_books)
+        .with_update_latency_sampling(config.update_latency_sample_every.and_then(NonZeroU64::new))
+    }
+
+    /// Walks up to `market_depth_limit` order book levels per symbol instead of only the top of
+    /// book. Deeper levels come from partial depth streams, symbols without them fall back to
+    /// the best bid/ask.
+    #[must_use]
+    pub fn with_market_depth_limit(mut self, market_depth_limit: usize) -> Self {
+        self.market_depth_limit = market_depth_limit;
+        self
+    }
+
+    /// Charges the chain legs by `fees` instead of the flat fee, a negative maker fee
+    /// crediting the rebate to the chain profit.
+    #[must_use]
+    pub fn with_fee_schedule(mut self, fees: FeeSchedule) -> Self {
+        self.fees = fees;
+        self
+    }
+
+    /// Raises the `NOTIONAL` filter minimum of the symbols configured in `min_notional`.
+    #[must_use]
+    pub fn with_min_notional(mut self, min_notional: MinNotional) -> Self {
+        self.min_notional = min_notional;
+        self
+    }
+
+    /// Holds every chain to the USD `floor` instead of the minimum profit of its base asset.
+    #[must_use]
+    pub fn with_usd_profit_floor(mut self, floor: Option<&'static UsdProfitFloor>) -> Self {
+        self.usd_profit_floor = floor;
+        self
+    }
+
+    /// Calculates chain profits on `pool` instead of the async runtime threads.
+    #[must_use]
+    pub fn with_compute_pool(mut self, pool: Option<Arc<ComputePool>>) -> Self {
+        self.compute_pool = pool;
+        self
+    }
+
+    /// Caps the `max_order_qty` of the base assets at their free balance in `cap`.
+    #[must_use]
+    pub fn with_balance_cap(mut self, cap: Option<Arc<BalanceCap>>) -> Self {
+        self.balance_cap = cap;
+        self
+    }
+
+    /// Logs every price update, profit calculation and gate decision of the chains listed in
+    /// `debug_chains`, as their symbols in order.
+    #[must_use]
+    pub fn with_debug_chains(mut self, debug_chains: Vec<Vec<String>>) -> Self {
+        self.debug_chains = debug_chains;
+        self
+    }
+
+    /// Rounds and offsets the prices of limit legs as set by `limit_pricing`.
+    #[must_use]
+    pub fn with_limit_pricing(mut self, limit_pricing: LimitPricing) -> Self {
+        self.limit_pricing = limit_pricing;
+        self
+    }
+
+    /// Evaluates chains on crossed books instead of skipping them until they uncross.
+    #[must_use]
+    pub fn with_crossed_books_allowed(mut self, allow_crossed_books: bool) -> Self {
+        self.allow_crossed_books = allow_crossed_books;
+        self
+    }
+
+    /// Times the processing of one book ticker update out of every `sample_every`, from
+    /// storing it to queuing the chain, including the wait for the compute pool.
+    #[must_use]
+    pub fn with_update_latency_sampling(mut self, sample_every: Option<NonZeroU64>) -> Self {
+        self.update_latency = UpdateLatencySampler::new(sample_every);
+        self
+    }
+
+    /// Pauses the chains containing a symbol of `disabled_symbols` for as long as it is there.
+    #[must_use]
+    pub fn with_disabled_symbols(mut self, disabled_symbols: Arc<DisabledSymbols>) -> Self {
+        self.disabled_symbols = disabled_symbols;
+        self
+    }
+
+    /// Returns the symbols whose chains are paused.
+    #[must_use]
+    pub(crate) fn disabled_symbols(&self) -> &DisabledSymbols {
+        &self.disabled_symbols
+    }
+
+    /// Creates the book store of a chain, reporting crossed books unless they are allowed.
+    #[must_use]
+    pub(crate) fn book_store(&self) -> BookTickerStore {
+        BookTickerStore::new().with_crossed_books_allowed(self.allow_crossed_books)
+    }
+
+    /// Returns whether the decisions taken on `chain` are logged.
+    #[must_use]
+    pub fn is_traced(&self, chain: &[ChainSymbol]) -> bool {
+        self.debug_chains.iter().any(|symbols| {
+            symbols
+                .iter()
+                .map(String::as_str)
+                .eq(chain.iter().map(|s| s.symbol.symbol.as_str()))
+        })
+    }
+
+    /// Builds and monitors order processing tasks for the given chains, of any length.
     pub async fn build_chains_orders(
         self: Arc<Self>,
         token: CancellationToken,
-        chains: Vec<[ChainSymbol; 3]>,
+        chains: Vec<Vec<ChainSymbol>>,
         base_assets: Vec<Asset>,
+        symbols_rx: watch::Receiver<SymbolsInfo>,
     ) -> anyhow::Result<()> {
         let mut tasks_set: JoinSet<anyhow::Result<()>> = JoinSet::new();
 
+        if let Some(floor) = self.usd_profit_floor {
+            let price_symbols = usd_price_symbols(&chains, &base_assets, floor.usd_asset());
+            if !price_symbols.is_empty() {
+                tasks_set.spawn({
+                    let token = token.clone();
+                    async move {
+                        track_usd_prices(token, floor, price_symbols).await;
+                        Ok(())
+                    }
+                });
+            }
+        }
+
         for chain in chains.iter() {
+            // A malformed chain is left out rather than failing the others.
+            if let Err(e) = check_chain_length(chain.len()) {
+                error!(
+                    chain = ?chain::extract_chain_symbols(chain),
+                    error = ?e,
+                    "Invalid chain skipped"
+                );
+                continue;
+            }
+
             tasks_set.spawn({
                 let this = self.clone();
-                let chain = chain.clone();
+                let mut chain = chain.clone();
                 let base_assets = base_assets.clone();
                 let token = token.clone();
+                let mut symbols_rx = symbols_rx.clone();
 
                 async move {
-                    let (mut rx1, mut rx2, mut rx3) = chain
+                    let mut receivers: Vec<_> = chain
                         .iter()
                         .map(|s| TICKER_BROADCAST.subscribe(s.symbol.symbol.as_str()))
-                        .collect_tuple()
-                        .expect("Invalid chain length");
+                        .collect();
 
-                    let mut storage = BookTickerStore::new();
+                    let mut storage = this.book_store();
                     let mut last_prices: Vec<Decimal> = vec![];
+                    let base_asset = find_base_asset(&base_assets, &chain[0]).map(|base| base.asset);
+                    let processed_chains = METRICS.processed_chain_counter(
+                        &chain::extract_chain_symbols(&chain),
+                        base_asset.as_deref(),
+                    );
+                    // Cleared while a symbol of the chain is missing from refreshed exchange info.
+                    let mut listed = true;
 
                     // Read initial values from watch channel
-                    {
-                        _ = rx1.borrow().clone();
-                        _ = rx2.borrow().clone();
-                        _ = rx3.borrow().clone();
+                    for rx in &receivers {
+                        _ = rx.borrow().clone();
                     }
 
                     loop {
@@ -124,19 +418,31 @@ impl OrderBuilder {
                                 break;
                             },
 
-                            _ = rx1.changed() => {
-                                let msg = rx1.borrow().clone();
-                                this.handle_ticker_event(&mut storage, &chain, msg, &mut last_prices, &base_assets);
+                            idx = next_changed(&mut receivers), if listed => {
+                                let msg = receivers[idx].borrow().clone();
+                                let Some(pool) = &this.compute_pool else {
+                                    this.handle_ticker_event(&mut storage, &chain, msg, &mut last_prices, &base_assets, &processed_chains);
+                                    continue;
+                                };
+                                let started = this.update_latency.start();
+                                if let Some((messages, received_at)) = Self::chain_books(&mut storage, &chain, msg, &mut last_prices, &this.disabled_symbols, this.is_traced(&chain)) {
+                                    let (this, chain, base_assets, processed_chains) = (this.clone(), chain.clone(), base_assets.clone(), processed_chains.clone());
+                                    let evaluate = move || this.evaluate_chain(&chain, &messages, &base_assets, &processed_chains, received_at);
+                                    if let Err(e) = pool.run(evaluate).await {
+                                        error!(error = ?e, "Error during process arbitrage");
+                                    }
+                                }
+                                this.update_latency.finish(started);
                             },
 
-                            _ = rx2.changed() => {
-                                let msg = rx2.borrow().clone();
-                                this.handle_ticker_event(&mut storage, &chain, msg, &mut last_prices, &base_assets);
-                            },
-
-                            _ = rx3.changed() => {
-                                let msg = rx3.borrow().clone();
-                                this.handle_ticker_event(&mut storage, &chain, msg, &mut last_prices, &base_assets);
+                            Ok(()) = symbols_rx.changed() => {
+                                listed = apply_symbols_info(&mut chain, &symbols_rx.borrow_and_update());
+                                if !listed {
+                                    warn!(
+                                        chain = ?chain::extract_chain_symbols(&chain),
+                                        "⏸️ [Engine] Chain symbol is no longer trading, chain paused"
+                                    );
+                                }
                             },
                         }
                     }
@@ -168,12 +474,51 @@ impl OrderBuilder {
     pub fn handle_ticker_event(
         &self,
         storage: &mut BookTickerStore,
-        chain: &[ChainSymbol; 3],
+        chain: &[ChainSymbol],
         msg: BookTickerEvent,
         last_prices: &mut Vec<Decimal>,
         base_assets: &[Asset],
+        processed_chains: &ProcessedChainCounter,
     ) {
-        storage.update(msg);
+        let started = self.update_latency.start();
+        if let Some((messages, received_at)) = Self::chain_books(
+            storage,
+            chain,
+            msg,
+            last_prices,
+            &self.disabled_symbols,
+            self.is_traced(chain),
+        ) {
+            self.evaluate_chain(chain, &messages, base_assets, processed_chains, received_at);
+        }
+        self.update_latency.finish(started);
+    }
+
+    /// Stores `msg` and returns the books of the chain legs with the time `msg` was received,
+    /// or `None` if `msg` is stale, a book is missing or crossed, a symbol is among
+    /// `disabled_symbols`, or the chain prices have not changed.
+    /// With `trace` the outcome is logged.
+    pub(crate) fn chain_books(
+        storage: &mut BookTickerStore,
+        chain: &[ChainSymbol],
+        msg: BookTickerEvent,
+        last_prices: &mut Vec<Decimal>,
+        disabled_symbols: &DisabledSymbols,
+        trace: bool,
+    ) -> Option<(Vec<BookTickerEvent>, Option<Instant>)> {
+        let received_at = msg.received_at;
+        let (symbol, update_id) = (msg.symbol.clone(), msg.update_id);
+        if !storage.update(msg) {
+            if trace {
+                info!(
+                    chain = ?chain::extract_chain_symbols(chain),
+                    symbol,
+                    update_id,
+                    "🔎 [Trace] Stale book update skipped"
+                );
+            }
+            return None;
+        }
 
         // Early return if not all data is available
         let messages: Vec<BookTickerEvent> = chain
@@ -182,7 +527,50 @@ impl OrderBuilder {
             .collect();
 
         if messages.len() != chain.len() {
-            return;
+            if trace {
+                info!(
+                    chain = ?chain::extract_chain_symbols(chain),
+                    symbol,
+                    update_id,
+                    "🔎 [Trace] Waiting for the books of every leg"
+                );
+            }
+            return None;
+        }
+
+        // Skip until every book uncrosses, the prices are left as they were so the chain is
+        // evaluated again once it does.
+        if let Some(crossed) = messages.iter().find(|m| storage.is_crossed(&m.symbol)) {
+            debug!(
+                chain = ?chain::extract_chain_symbols(chain),
+                symbol = crossed.symbol,
+                bid_price = %crossed.bid_price,
+                ask_price = %crossed.ask_price,
+                "Crossed book, chain skipped until it uncrosses"
+            );
+            if trace {
+                info!(
+                    chain = ?chain::extract_chain_symbols(chain),
+                    symbol = crossed.symbol,
+                    bid_price = %crossed.bid_price,
+                    ask_price = %crossed.ask_price,
+                    "🔎 [Trace] Crossed book, chain skipped until it uncrosses"
+                );
+            }
+            return None;
+        }
+
+        // Paused while a symbol is disabled, the prices are left as they were for the same
+        // reason.
+        if let Some(disabled) = disabled_symbols.find(chain::extract_chain_symbols(chain)) {
+            if trace {
+                info!(
+                    chain = ?chain::extract_chain_symbols(chain),
+                    symbol = disabled,
+                    "🔎 [Trace] Symbol disabled, chain skipped"
+                );
+            }
+            return None;
         }
 
         // Calculate prices
@@ -197,43 +585,151 @@ impl OrderBuilder {
 
         // Skip if prices haven't changed
         if *last_prices == prices {
-            return;
+            if trace {
+                info!(
+                    chain = ?chain::extract_chain_symbols(chain),
+                    symbol,
+                    update_id,
+                    "🔎 [Trace] Chain prices unchanged, skipped"
+                );
+            }
+            return None;
         }
 
+        if trace {
+            info!(
+                chain = ?chain::extract_chain_symbols(chain),
+                symbol,
+                update_id,
+                ?prices,
+                "🔎 [Trace] Chain prices updated"
+            );
+        }
         *last_prices = prices;
+        Some((messages, received_at))
+    }
 
-        // Process the chain
-        if let Err(e) = Self::process_chain(
-            base_assets,
-            chain,
-            &messages,
-            self.market_depth_limit,
-            self.fee_percent,
-        ) {
-            error!(error = ?e, "Error during process arbitrage");
+    /// Processes the chain on the books of its legs and queues its orders for the sender.
+    fn evaluate_chain(
+        &self,
+        chain: &[ChainSymbol],
+        messages: &[BookTickerEvent],
+        base_assets: &[Asset],
+        processed_chains: &ProcessedChainCounter,
+        received_at: Option<Instant>,
+    ) {
+        match self.build_chain_orders(chain, messages, base_assets, received_at) {
+            Ok(chain_orders) => {
+                if let Some(chain_orders) = chain_orders
+                    && let Err(e) = ORDERS_CHANNEL.publish(chain_orders)
+                {
+                    error!(error = ?e, "Failed to send chain to channel");
+                }
+                processed_chains.increment(1);
+            }
+            Err(e) => error!(error = ?e, "Error during process arbitrage"),
         }
     }
 
-    /// Builds orders for the chain and calculates profit.
-    pub fn process_chain(
-        base_assets: &[Asset],
-        chain: &[ChainSymbol; 3],
+    /// Builds the orders of the chain on the books of its legs with the builder settings,
+    /// returning `None` if the chain is not profitable.
+    ///
+    /// Chains shorter than [`engine::MIN_CHAIN_LEGS`] or without a book per leg are refused.
+    /// With `verify_chain_cycle` a chain that does not return to its starting asset is
+    /// refused before any order is built. With a `usd_profit_floor` a chain is skipped until
+    /// the USD price of its base asset is known. `received_at` is when the ticker event the
+    /// chain is processed on was received. Every decision on a traced chain is logged.
+    pub fn build_chain_orders(
+        &self,
+        chain: &[ChainSymbol],
         order_book: &[BookTickerEvent],
-        market_depth_limit: usize,
-        fee_percent: Decimal,
-    ) -> anyhow::Result<()> {
+        base_assets: &[Asset],
+        received_at: Option<Instant>,
+    ) -> anyhow::Result<Option<ChainOrders>> {
+        let min_profit_scale = self.profit_schedule.min_profit_scale(TimeOfDay::now());
+        let trace = self.is_traced(chain);
+        let chain_symbols = || chain::extract_chain_symbols(chain);
+
+        check_chain_length(chain.len())
+            .with_context(|| format!("Invalid chain {:?}", chain_symbols()))?;
+        if order_book.len() != chain.len() {
+            bail!(
+                "Chain {:?} has {} books for {} legs",
+                chain_symbols(),
+                order_book.len(),
+                chain.len()
+            );
+        }
+
+        if self.verify_chain_cycle {
+            let cycle = check_chain_cycle(chain.iter().map(|chain_symbol| {
+                let symbol = &chain_symbol.symbol;
+                (
+                    symbol.symbol.as_str(),
+                    symbol.base_asset.as_str(),
+                    symbol.quote_asset.as_str(),
+                    chain_symbol.order,
+                )
+            }))
+            .with_context(|| {
+                format!(
+                    "Chain {:?} does not return to its starting asset",
+                    chain_symbols()
+                )
+            });
+            if trace {
+                info!(
+                    chain = ?chain_symbols(),
+                    passed = cycle.is_ok(),
+                    "🔎 [Trace] Chain cycle gate"
+                );
+            }
+            cycle?;
+        }
+
         let mut order_symbols = vec![];
 
         for (i, chain_symbol) in chain.iter().enumerate() {
-            // Define limits for 1st pair.
-            let min_profit_qty = if i == 0 {
-                find_base_asset(base_assets, chain_symbol).map(|base| base.min_profit_qty)
+            // Define limits for 1st pair, the profit thresholds scaled by the active schedule.
+            let (min_profit_qty, min_profit_percent) = if i == 0
+                && let Some(base) = find_base_asset(base_assets, chain_symbol)
+            {
+                // The chain profit can't be valued before the base asset's USD price is.
+                let Some(min_profit_qty) = base_min_profit_qty(&base, self.usd_profit_floor) else {
+                    if trace {
+                        info!(
+                            chain = ?chain_symbols(),
+                            asset = base.asset,
+                            "🔎 [Trace] USD price of the base asset unknown, skipped"
+                        );
+                    }
+                    return Ok(None);
+                };
+                (
+                    Some(min_profit_qty * min_profit_scale),
+                    base.min_profit_percent
+                        .map(|percent| percent * min_profit_scale),
+                )
             } else {
-                None
+                (None, None)
             };
 
-            let max_order_qty = if i == 0 {
-                find_base_asset(base_assets, chain_symbol).map(|base| base.max_order_qty)
+            let max_order_qty = if i == 0
+                && let Some(base) = find_base_asset(base_assets, chain_symbol)
+            {
+                // Nothing to trade while the base asset has no free balance.
+                let max_order_qty = base_max_order_qty(&base, self.balance_cap.as_deref());
+                if max_order_qty <= Decimal::ZERO {
+                    if trace {
+                        info!(
+                            chain = ?chain_symbols(),
+                            asset = base.asset,
+                            "🔎 [Trace] No free balance of the base asset, skipped"
+                        );
+                    }
+                    return Ok(None);
+                }
+                Some(max_order_qty)
             } else {
                 None
             };
@@ -244,7 +740,9 @@ impl OrderBuilder {
                 base_asset_precision: symbol.base_asset_precision,
                 quote_precision: symbol.quote_precision,
                 symbol_order: chain_symbol.order,
+                order_type: self.order_type,
                 min_profit_qty,
+                min_profit_percent,
                 max_order_qty,
                 order_book: &order_book[i],
                 symbol_filter: define_symbol_filter(&symbol.filters),
@@ -252,34 +750,58 @@ impl OrderBuilder {
             order_symbols.push(order_symbol);
         }
 
-        let orders = Self::calculate_chain_profit(&order_symbols, market_depth_limit, fee_percent);
-        METRICS.record_processed_chain(&chain::extract_chain_symbols(chain));
+        let orders = Self::calculate_chain_profit(
+            &order_symbols,
+            self.market_depth_limit,
+            self.max_depth_iterations,
+            &self.fees,
+            &self.min_notional,
+            self.limit_pricing,
+            trace,
+        );
 
         if orders.is_empty() {
-            return Ok(());
+            if trace {
+                info!(chain = ?chain_symbols(), "🔎 [Trace] No profitable pass, chain skipped");
+            }
+            return Ok(None);
         }
 
-        let chain_orders = ChainOrders {
+        Ok(Some(ChainOrders {
             ts: misc::time::get_current_timestamp().as_millis(),
             chain_id: Uuid::new_v4(),
-            fee_percent,
+            base_asset: find_base_asset(base_assets, &chain[0])
+                .map(|base| base.asset)
+                .unwrap_or_default(),
+            // Average fee of the legs, the chain fee stays their sum.
+            fee_percent: self.fees.chain_fee_percent(
+                orders
+                    .iter()
+                    .map(|order| (order.symbol.as_str(), order.symbol_order)),
+            ) / Decimal::from_usize(orders.len()).unwrap_or(Decimal::ONE),
             orders,
-        };
-
-        if let Err(e) = ORDERS_CHANNEL.tx.send(chain_orders) {
-            error!(error = ?e, "Failed to send chain to channel");
-        }
-
-        Ok(())
+            received_at,
+            queued_at: None,
+        }))
     }
 
     /// Builds orders for the chain and calculates profit.
+    ///
+    /// The depth search stops after `max_depth_iterations` passes, or earlier once no
+    /// symbol has book levels beyond the current depth. Limit legs are priced by
+    /// `limit_pricing`, the quantities and the profit gate following the placed price. With
+    /// `trace` the gates of every pass are logged.
     #[must_use]
     pub fn calculate_chain_profit(
         chain: &[OrderSymbol],
         market_depth_limit: usize,
-        fee_percent: Decimal,
+        max_depth_iterations: usize,
+        fees: &FeeSchedule,
+        min_notional: &MinNotional,
+        limit_pricing: LimitPricing,
+        trace: bool,
     ) -> Vec<ChainOrder> {
+        let chain_symbols = || chain.iter().map(|s| s.symbol.as_str()).collect::<Vec<_>>();
         let mut orders: Vec<PreOrder> = vec![];
         let mut start_depth_limit = 0;
 
@@ -287,18 +809,24 @@ impl OrderBuilder {
         let max_order_qty = get_max_order_qty(chain.first().unwrap());
 
         while start_depth_limit < market_depth_limit {
+            if start_depth_limit >= max_depth_iterations {
+                warn!(
+                    chain = ?chain_symbols(),
+                    market_depth_limit,
+                    max_depth_iterations,
+                    "Depth iteration cap hit, order book data looks suspicious"
+                );
+                break;
+            }
+
+            // Whether any symbol still has levels beyond the current depth.
+            let mut has_deeper_levels = false;
+
             for (i, order_symbol) in chain.iter().enumerate() {
                 // Define list of orders according to the order of assets in symbol.
-                let order_units: &Vec<OrderBookUnit> = match order_symbol.symbol_order {
-                    SymbolOrder::Asc => &vec![OrderBookUnit {
-                        price: order_symbol.order_book.bid_price,
-                        qty: order_symbol.order_book.bid_qty,
-                    }],
-                    SymbolOrder::Desc => &vec![OrderBookUnit {
-                        price: order_symbol.order_book.ask_price,
-                        qty: order_symbol.order_book.ask_qty,
-                    }],
-                };
+                let order_units = book_levels(order_symbol.order_book, order_symbol.symbol_order);
+
+                has_deeper_levels |= order_units.len() > start_depth_limit + 1;
 
                 // Define qty limit for current symbol.
                 let max_order_qty = if i == 0 {
@@ -345,11 +873,12 @@ impl OrderBuilder {
                     base_precision: order_symbol.base_asset_precision,
                     quote_qty,
                     quote_precision: order_symbol.quote_precision,
+                    order_type: order_symbol.order_type,
                     symbol_filter: order_symbol.symbol_filter.clone(),
                 });
 
                 // If first symbol and base qty does not match the max order qty, where max order
-                // qty for 2nd and 3rd symbol is previous symbol quote qty, it is necessary to
+                // qty for the following symbols is previous symbol quote qty, it is necessary to
                 // recalculate the qty of previous orders.
                 if i != 0 && base_qty < max_order_qty {
                     Self::recalculate_orders_qty(&mut orders, i);
@@ -362,12 +891,18 @@ impl OrderBuilder {
                 break;
             }
 
+            // Further passes would only re-read the same levels.
+            if !has_deeper_levels {
+                break;
+            }
+
             start_depth_limit += 1;
         }
 
         // Round and recalculate quantities according to binance api rules.
         let mut profit_orders = vec![];
         let mut min_profit_qty = get_min_profit_qty(chain.first().unwrap());
+        let min_profit_percent = chain.first().unwrap().min_profit_percent;
 
         // Iterate over every first order in chain.
         'outer_loop: for i in (0..).take(orders.len() - 1).step_by(chain.len()) {
@@ -375,9 +910,14 @@ impl OrderBuilder {
             let mut tmp_orders: Vec<ChainOrder> = vec![];
 
             while count < chain.len() {
-                let price = orders[count]
-                    .price
-                    .trunc_with_scale(orders[count].symbol_filter.tick_size);
+                // Orders of the depth pass starting at `i`.
+                let order = &orders[i + count];
+                let price = limit_pricing.leg_price(
+                    order.price,
+                    order.symbol_filter.tick_size,
+                    order.order_type,
+                    order.symbol_order,
+                );
 
                 let base_qty = if count == 0 {
                     orders[i].base_qty
@@ -385,24 +925,52 @@ impl OrderBuilder {
                     tmp_orders[count - 1].quote_qty
                 };
 
-                let (rounded_base_qty, rounded_quote_qty) = match orders[count].symbol_order {
+                // Market and limit orders may have different lot size filters.
+                let (lot_size_step, lot_size_min_qty) =
+                    order.symbol_filter.lot_size(&order.order_type);
+
+                let (rounded_base_qty, rounded_quote_qty) = match order.symbol_order {
                     SymbolOrder::Asc => {
-                        let base_qty =
-                            base_qty.trunc_with_scale(orders[count].symbol_filter.lot_size_step);
+                        let base_qty = base_qty.trunc_with_scale(lot_size_step);
 
                         // If at least one order from the chain does not have enough quantity to
                         // reach the minimum, then skip the entire chain of orders.
-                        if orders[count].symbol_filter.lot_size_min_qty > base_qty {
+                        if lot_size_min_qty > base_qty {
+                            if trace {
+                                info!(
+                                    chain = ?chain_symbols(),
+                                    symbol = order.symbol,
+                                    qty = %base_qty,
+                                    min_qty = %lot_size_min_qty,
+                                    "🔎 [Trace] Lot size gate failed"
+                                );
+                            }
                             continue 'outer_loop;
                         }
 
-                        (base_qty, base_qty * price)
+                        // The last leg returns the starting asset, credited at the quote asset
+                        // precision: the realizable return checked by the profit gate below.
+                        let quote_qty = if count == chain.len() - 1 {
+                            floor_to_precision(base_qty * price, order.quote_precision)
+                        } else {
+                            base_qty * price
+                        };
+
+                        (base_qty, quote_qty)
                     }
                     SymbolOrder::Desc => {
-                        let quote_qty = (base_qty / price)
-                            .trunc_with_scale(orders[count].symbol_filter.lot_size_step);
-
-                        if orders[count].symbol_filter.lot_size_min_qty > quote_qty {
+                        let quote_qty = (base_qty / price).trunc_with_scale(lot_size_step);
+
+                        if lot_size_min_qty > quote_qty {
+                            if trace {
+                                info!(
+                                    chain = ?chain_symbols(),
+                                    symbol = order.symbol,
+                                    qty = %quote_qty,
+                                    min_qty = %lot_size_min_qty,
+                                    "🔎 [Trace] Lot size gate failed"
+                                );
+                            }
                             continue 'outer_loop;
                         }
 
@@ -411,33 +979,65 @@ impl OrderBuilder {
                 };
 
                 tmp_orders.push(ChainOrder {
-                    symbol: orders[count].symbol.clone(),
-                    symbol_order: orders[count].symbol_order,
+                    symbol: order.symbol.clone(),
+                    symbol_order: order.symbol_order,
+                    order_type: order.order_type,
                     price,
                     base_qty: rounded_base_qty,
                     quote_qty: rounded_quote_qty,
-                    base_increment: Decimal::new(1i64, orders[count].symbol_filter.lot_size_step),
+                    base_increment: Decimal::new(1i64, lot_size_step),
                     quote_increment: Decimal::zero(), // set default because not used
+                    min_qty: lot_size_min_qty,
                 });
 
                 count += 1;
             }
 
+            // Skip the pass if the exchange would reject a leg for its value.
+            if let Some(below) =
+                min_notional.find_below(&tmp_orders, |idx| chain[idx].symbol_filter.min_notional)
+            {
+                if trace {
+                    info!(
+                        chain = ?chain_symbols(),
+                        symbol = below.symbol,
+                        "🔎 [Trace] Min notional gate failed"
+                    );
+                }
+                continue;
+            }
+
             // Check profit.
-            let fee = calculate_fee(tmp_orders.first().unwrap().base_qty, fee_percent);
+            let fee = calculate_fee(tmp_orders.first().unwrap().base_qty, fees, chain);
 
             // Difference between the outbound volume of the last symbol in chain and the inbound
             // volume of the first symbol in chain.
             let diff_qty =
                 tmp_orders.last().unwrap().quote_qty - tmp_orders.first().unwrap().base_qty;
 
-            if (diff_qty - fee) >= min_profit_qty {
+            let input_qty = tmp_orders.first().unwrap().base_qty;
+            let passed = (diff_qty - fee) >= min_profit_qty
+                && reaches_min_profit_percent(diff_qty - fee, input_qty, min_profit_percent);
+            if trace {
+                info!(
+                    chain = ?chain_symbols(),
+                    input = %input_qty,
+                    output = %tmp_orders.last().unwrap().quote_qty,
+                    %fee,
+                    profit = %(diff_qty - fee),
+                    %min_profit_qty,
+                    min_profit_percent = min_profit_percent.map(tracing::field::display),
+                    passed,
+                    "🔎 [Trace] Profit gate"
+                );
+            }
+            if passed {
                 min_profit_qty = diff_qty - fee;
                 profit_orders.extend_from_slice(&tmp_orders);
             }
         }
 
-        // Return 3 last profit orders.
+        // Return the orders of the last profitable chain.
         if profit_orders.len() >= chain.len() {
             let idx = profit_orders.len().sub(chain.len());
             profit_orders[idx..].to_vec()
@@ -483,6 +1083,27 @@ impl OrderBuilder {
     }
 }
 
+/// Returns the book side a symbol order fills against, best level first. Books without depth
+/// levels yield only the best bid/ask.
+fn book_levels(order_book: &BookTickerEvent, symbol_order: SymbolOrder) -> Vec<OrderBookUnit> {
+    let (levels, price, qty) = match symbol_order {
+        SymbolOrder::Asc => (&order_book.bids, order_book.bid_price, order_book.bid_qty),
+        SymbolOrder::Desc => (&order_book.asks, order_book.ask_price, order_book.ask_qty),
+    };
+
+    if levels.is_empty() {
+        return vec![OrderBookUnit { price, qty }];
+    }
+
+    levels
+        .iter()
+        .map(|level| OrderBookUnit {
+            price: level.price,
+            qty: level.qty,
+        })
+        .collect()
+}
+
 fn find_base_asset(base_assets: &[Asset], chain_symbol: &ChainSymbol) -> Option<Asset> {
     base_assets
         .iter()
@@ -496,6 +1117,104 @@ fn find_base_asset(base_assets: &[Asset], chain_symbol: &ChainSymbol) -> Option<
         .cloned()
 }
 
+/// Minimum profit of a chain starting from `base`: the USD floor converted into the asset if
+/// set, otherwise the asset's own. `None` while the USD price of the asset is unknown.
+fn base_min_profit_qty(base: &Asset, usd_profit_floor: Option<&UsdProfitFloor>) -> Option<Decimal> {
+    match usd_profit_floor {
+        Some(floor) => floor.min_profit_qty(&base.asset),
+        None => Some(base.min_profit_qty),
+    }
+}
+
+/// Returns the configured `max_order_qty` of the base asset, capped at its free balance.
+fn base_max_order_qty(base: &Asset, balance_cap: Option<&BalanceCap>) -> Decimal {
+    balance_cap.map_or(base.max_order_qty, |cap| {
+        cap.cap(&base.asset, base.max_order_qty)
+    })
+}
+
+/// Returns a symbol pricing each base asset in `usd_asset` among the chain symbols. Base
+/// assets without one are logged, their chains never pass the USD floor.
+fn usd_price_symbols(
+    chains: &[Vec<ChainSymbol>],
+    base_assets: &[Asset],
+    usd_asset: &str,
+) -> Vec<UsdPriceSymbol> {
+    let is_base_asset = |asset: &str| base_assets.iter().any(|base| base.asset == asset);
+    let mut price_symbols = BTreeMap::new();
+
+    for symbol in chains
+        .iter()
+        .flatten()
+        .map(|chain_symbol| &chain_symbol.symbol)
+    {
+        let (asset, inverted) = if symbol.quote_asset == usd_asset {
+            (&symbol.base_asset, false)
+        } else if symbol.base_asset == usd_asset {
+            (&symbol.quote_asset, true)
+        } else {
+            continue;
+        };
+        if is_base_asset(asset) {
+            price_symbols
+                .entry(asset.clone())
+                .or_insert_with(|| UsdPriceSymbol {
+                    symbol: symbol.symbol.clone(),
+                    asset: asset.clone(),
+                    inverted,
+                });
+        }
+    }
+
+    for base in base_assets {
+        if base.asset != usd_asset && !price_symbols.contains_key(&base.asset) {
+            warn!(
+                asset = %base.asset,
+                usd_asset,
+                "⚠️ [Engine] No symbol prices the base asset in USD, its chains are skipped"
+            );
+        }
+    }
+
+    price_symbols.into_values().collect()
+}
+
+/// Keeps the USD prices of the base assets up to date from the mid price of their symbols.
+async fn track_usd_prices(
+    token: CancellationToken,
+    floor: &UsdProfitFloor,
+    price_symbols: Vec<UsdPriceSymbol>,
+) {
+    let mut receivers: Vec<_> = price_symbols
+        .iter()
+        .map(|price_symbol| TICKER_BROADCAST.subscribe(price_symbol.symbol.as_str()))
+        .collect();
+
+    loop {
+        tokio::select! {
+            () = token.cancelled() => break,
+
+            idx = next_changed(&mut receivers) => {
+                let (bid_price, ask_price) = {
+                    let msg = receivers[idx].borrow_and_update();
+                    (msg.bid_price, msg.ask_price)
+                };
+                let mid_price = (bid_price + ask_price) / Decimal::TWO;
+                let price_symbol = &price_symbols[idx];
+                let price = if price_symbol.inverted {
+                    if mid_price.is_zero() {
+                        continue;
+                    }
+                    Decimal::ONE / mid_price
+                } else {
+                    mid_price
+                };
+                floor.set_price(&price_symbol.asset, price);
+            },
+        }
+    }
+}
+
 fn define_symbol_filter(filters: &Vec<Filters>) -> SymbolFilter {
     let mut symbol_filter = SymbolFilter::default();
     for filter in filters {
@@ -508,6 +1227,20 @@ fn define_symbol_filter(filters: &Vec<Filters>) -> SymbolFilter {
                 symbol_filter.lot_size_step = step_size.normalize().scale();
                 symbol_filter.lot_size_min_qty = *min_qty;
             }
+            Filters::MarketLotSize {
+                min_qty,
+                max_qty: _max_qty,
+                step_size,
+            } => {
+                // A zero step means the symbol has no market-specific rule.
+                if let Ok(step_size) = Decimal::from_str(step_size)
+                    && !step_size.is_zero()
+                {
+                    symbol_filter.market_lot_size_step = Some(step_size.normalize().scale());
+                    symbol_filter.market_lot_size_min_qty =
+                        Decimal::from_str(min_qty).unwrap_or_default();
+                }
+            }
             Filters::PriceFilter {
                 min_price: _min_price,
                 max_price: _max_price,
@@ -515,6 +1248,16 @@ fn define_symbol_filter(filters: &Vec<Filters>) -> SymbolFilter {
             } => {
                 symbol_filter.tick_size = tick_size.normalize().scale();
             }
+            Filters::Notional {
+                min_notional: Some(min_notional),
+                ..
+            }
+            | Filters::MinNotional {
+                min_notional: Some(min_notional),
+                ..
+            } => {
+                symbol_filter.min_notional = *min_notional;
+            }
             _ => {}
         };
     }
@@ -522,6 +1265,37 @@ fn define_symbol_filter(filters: &Vec<Filters>) -> SymbolFilter {
     symbol_filter
 }
 
+/// Logs the parsed filters of every symbol the chains trade, to verify the exchange info was
+/// read as expected.
+pub fn log_symbol_filters(chains: &[Vec<ChainSymbol>]) {
+    for (symbol, filter) in collect_symbol_filters(chains) {
+        info!(
+            symbol,
+            lot_step = %Decimal::new(1, filter.lot_size_step),
+            lot_min_qty = %filter.lot_size_min_qty,
+            market_lot_step = ?filter.market_lot_size_step.map(|step| Decimal::new(1, step)),
+            market_lot_min_qty = %filter.market_lot_size_min_qty,
+            tick_size = %Decimal::new(1, filter.tick_size),
+            min_notional = %filter.min_notional,
+            "🔎 [Engine] Symbol filters"
+        );
+    }
+}
+
+/// Parses the filters of every distinct symbol in the chains, ordered by symbol.
+fn collect_symbol_filters(chains: &[Vec<ChainSymbol>]) -> BTreeMap<&str, SymbolFilter> {
+    chains
+        .iter()
+        .flatten()
+        .map(|chain_symbol| {
+            (
+                chain_symbol.symbol.symbol.as_str(),
+                define_symbol_filter(&chain_symbol.symbol.filters),
+            )
+        })
+        .collect()
+}
+
 fn define_precision(order_symbol: &OrderSymbol) -> u32 {
     match order_symbol.symbol_order {
         SymbolOrder::Asc => order_symbol.base_asset_precision,
@@ -543,20 +1317,54 @@ fn get_min_profit_qty(order_symbol: &OrderSymbol) -> Decimal {
         .trunc_with_scale(define_precision(order_symbol))
 }
 
-fn calculate_fee(qty: Decimal, fee_percent: Decimal) -> Decimal {
-    let orders_count = Decimal::from_usize(3).unwrap();
+/// Returns true if `profit` reaches `min_profit_percent` of the `spent` quantity, always
+/// without a percentage threshold.
+fn reaches_min_profit_percent(
+    profit: Decimal,
+    spent: Decimal,
+    min_profit_percent: Option<Decimal>,
+) -> bool {
+    min_profit_percent.is_none_or(|min_percent| {
+        !spent.is_zero() && profit / spent * Decimal::ONE_HUNDRED >= min_percent
+    })
+}
+
+/// Truncates `qty` to `precision` decimal places, leaving values that already fit unchanged.
+fn floor_to_precision(qty: Decimal, precision: u32) -> Decimal {
+    if qty.scale() > precision {
+        qty.trunc_with_scale(precision)
+    } else {
+        qty
+    }
+}
+
+/// Fee of executing every leg of `chain` on `qty`, each leg charged by the schedule.
+/// Maker rebates reduce the fee and may turn it negative.
+fn calculate_fee(qty: Decimal, fees: &FeeSchedule, chain: &[OrderSymbol]) -> Decimal {
     let delimiter = Decimal::from_usize(100).unwrap();
-    (qty * fee_percent * orders_count) / delimiter
+    let fee_percent = fees.chain_fee_percent(
+        chain
+            .iter()
+            .map(|order| (order.symbol.as_str(), order.symbol_order)),
+    );
+    (qty * fee_percent) / delimiter
 }
 
 #[cfg(test)]
 mod tests {
-    use std::str::FromStr;
+    use std::{collections::HashMap, str::FromStr};
 
-    use engine::enums::SymbolOrder;
+    use engine::{
+        FeeRates,
+        enums::{LiquidityRole, SymbolOrder},
+    };
     use rust_decimal::prelude::FromPrimitive;
 
     use super::*;
+    use crate::{
+        libs::binance_client::{ExchangeInformation, Symbol},
+        services::storage::BookLevel,
+    };
 
     // Case #1: all orders of the 1st depth have volumes greater than the volume limit.
     // (order - ASC/DESC/ASC)
@@ -572,6 +1380,7 @@ mod tests {
             bid_qty: Decimal::from_f64(7.27795000).unwrap(),
             ask_price: Decimal::from_f64(109615.47000000).unwrap(),
             ask_qty: Decimal::from_f64(2.22969000).unwrap(),
+            ..Default::default()
         };
 
         let order_book_2 = BookTickerEvent {
@@ -581,6 +1390,7 @@ mod tests {
             bid_qty: Decimal::from_f64(14.64600000).unwrap(),
             ask_price: Decimal::from_f64(2585.71000000).unwrap(),
             ask_qty: Decimal::from_f64(19.28810000).unwrap(),
+            ..Default::default()
         };
 
         let order_book_3 = BookTickerEvent {
@@ -590,6 +1400,7 @@ mod tests {
             bid_qty: Decimal::from_f64(105.74550000).unwrap(),
             ask_price: Decimal::from_f64(0.02359000).unwrap(),
             ask_qty: Decimal::from_f64(25.63400000).unwrap(),
+            ..Default::default()
         };
 
         let order_symbols = vec![
@@ -598,13 +1409,16 @@ mod tests {
                 base_asset_precision: 8,
                 quote_precision: 8,
                 symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
                 min_profit_qty: Decimal::from_f64(0.000030),
+                min_profit_percent: None,
                 max_order_qty: Decimal::from_f64(0.00030),
                 order_book: &order_book_1,
                 symbol_filter: SymbolFilter {
                     lot_size_step: 5,
                     tick_size: 2,
                     lot_size_min_qty: Decimal::from_f64(0.00001000).unwrap(),
+                    ..Default::default()
                 },
             },
             OrderSymbol {
@@ -612,13 +1426,16 @@ mod tests {
                 base_asset_precision: 8,
                 quote_precision: 8,
                 symbol_order: SymbolOrder::Desc,
+                order_type: OrderType::Market,
                 min_profit_qty: None,
+                min_profit_percent: None,
                 max_order_qty: None,
                 order_book: &order_book_2,
                 symbol_filter: SymbolFilter {
                     lot_size_step: 4,
                     tick_size: 2,
                     lot_size_min_qty: Decimal::from_f64(0.00010000).unwrap(),
+                    ..Default::default()
                 },
             },
             OrderSymbol {
@@ -626,19 +1443,29 @@ mod tests {
                 base_asset_precision: 8,
                 quote_precision: 8,
                 symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
                 min_profit_qty: None,
+                min_profit_percent: None,
                 max_order_qty: None,
                 order_book: &order_book_3,
                 symbol_filter: SymbolFilter {
                     lot_size_step: 4,
                     tick_size: 5,
                     lot_size_min_qty: Decimal::from_f64(0.00010000).unwrap(),
+                    ..Default::default()
                 },
             },
         ];
 
-        let orders =
-            OrderBuilder::calculate_chain_profit(&order_symbols, market_depth_limit, fee_percent);
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            market_depth_limit,
+            OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+            &FeeSchedule::flat(fee_percent),
+            &MinNotional::default(),
+            LimitPricing::default(),
+            false,
+        );
 
         assert_eq!(orders.len(), 3);
 
@@ -658,7 +1485,7 @@ mod tests {
         assert_eq!(orders[2].symbol_order, SymbolOrder::Asc);
         assert_eq!(orders[2].price.to_string(), "0.02858");
         assert_eq!(orders[2].base_qty.to_string(), "0.0127");
-        assert_eq!(orders[2].quote_qty.to_string(), "0.000362966");
+        assert_eq!(orders[2].quote_qty.to_string(), "0.00036296");
 
         Ok(())
     }
@@ -677,6 +1504,7 @@ mod tests {
             bid_qty: Decimal::from_f64(0.00020000).unwrap(), // <---- here,
             ask_price: Decimal::from_f64(109615.47000000).unwrap(),
             ask_qty: Decimal::from_f64(2.22969000).unwrap(),
+            ..Default::default()
         };
 
         let order_book_2 = BookTickerEvent {
@@ -686,6 +1514,7 @@ mod tests {
             bid_qty: Decimal::from_f64(14.64600000).unwrap(),
             ask_price: Decimal::from_f64(2585.71000000).unwrap(),
             ask_qty: Decimal::from_f64(19.28810000).unwrap(),
+            ..Default::default()
         };
 
         let order_book_3 = BookTickerEvent {
@@ -695,6 +1524,7 @@ mod tests {
             bid_qty: Decimal::from_f64(105.74550000).unwrap(),
             ask_price: Decimal::from_f64(0.02359000).unwrap(),
             ask_qty: Decimal::from_f64(25.63400000).unwrap(),
+            ..Default::default()
         };
 
         let order_symbols = vec![
@@ -703,13 +1533,16 @@ mod tests {
                 base_asset_precision: 8,
                 quote_precision: 8,
                 symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
                 min_profit_qty: Decimal::from_f64(0.0),
+                min_profit_percent: None,
                 max_order_qty: Decimal::from_f64(0.00030),
                 order_book: &order_book_1,
                 symbol_filter: SymbolFilter {
                     lot_size_step: 5,
                     tick_size: 2,
                     lot_size_min_qty: Decimal::from_f64(0.00001000).unwrap(),
+                    ..Default::default()
                 },
             },
             OrderSymbol {
@@ -717,13 +1550,16 @@ mod tests {
                 base_asset_precision: 8,
                 quote_precision: 8,
                 symbol_order: SymbolOrder::Desc,
+                order_type: OrderType::Market,
                 min_profit_qty: None,
+                min_profit_percent: None,
                 max_order_qty: None,
                 order_book: &order_book_2,
                 symbol_filter: SymbolFilter {
                     lot_size_step: 4,
                     tick_size: 2,
                     lot_size_min_qty: Decimal::from_f64(0.00010000).unwrap(),
+                    ..Default::default()
                 },
             },
             OrderSymbol {
@@ -731,19 +1567,29 @@ mod tests {
                 base_asset_precision: 8,
                 quote_precision: 8,
                 symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
                 min_profit_qty: None,
+                min_profit_percent: None,
                 max_order_qty: None,
                 order_book: &order_book_3,
                 symbol_filter: SymbolFilter {
                     lot_size_step: 4,
                     tick_size: 5,
                     lot_size_min_qty: Decimal::from_f64(0.00010000).unwrap(),
+                    ..Default::default()
                 },
             },
         ];
 
-        let orders =
-            OrderBuilder::calculate_chain_profit(&order_symbols, market_depth_limit, fee_percent);
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            market_depth_limit,
+            OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+            &FeeSchedule::flat(fee_percent),
+            &MinNotional::default(),
+            LimitPricing::default(),
+            false,
+        );
 
         assert_eq!(orders.len(), 3);
 
@@ -763,7 +1609,7 @@ mod tests {
         assert_eq!(orders[2].symbol_order, SymbolOrder::Asc);
         assert_eq!(orders[2].price.to_string(), "0.02858");
         assert_eq!(orders[2].base_qty.to_string(), "0.0084");
-        assert_eq!(orders[2].quote_qty.to_string(), "0.000240072");
+        assert_eq!(orders[2].quote_qty.to_string(), "0.00024007");
 
         Ok(())
     }
@@ -782,6 +1628,7 @@ mod tests {
             bid_qty: Decimal::from_f64(0.20000000).unwrap(),
             ask_price: Decimal::from_f64(109615.47000000).unwrap(),
             ask_qty: Decimal::from_f64(2.22969000).unwrap(),
+            ..Default::default()
         };
 
         let order_book_2 = BookTickerEvent {
@@ -791,6 +1638,7 @@ mod tests {
             bid_qty: Decimal::from_f64(19.28810000).unwrap(),
             ask_price: Decimal::from_f64(1585.71000000).unwrap(),
             ask_qty: Decimal::from_f64(0.0033).unwrap(), // <---- here
+            ..Default::default()
         };
 
         let order_book_3 = BookTickerEvent {
@@ -800,6 +1648,7 @@ mod tests {
             bid_qty: Decimal::from_f64(105.74550000).unwrap(),
             ask_price: Decimal::from_f64(0.02359000).unwrap(),
             ask_qty: Decimal::from_f64(25.63400000).unwrap(),
+            ..Default::default()
         };
 
         let order_symbols = vec![
@@ -808,13 +1657,16 @@ mod tests {
                 base_asset_precision: 8,
                 quote_precision: 8,
                 symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
                 min_profit_qty: Decimal::from_f64(0.000030),
+                min_profit_percent: None,
                 max_order_qty: Decimal::from_f64(0.00030),
                 order_book: &order_book_1,
                 symbol_filter: SymbolFilter {
                     lot_size_step: 5,
                     tick_size: 2,
                     lot_size_min_qty: Decimal::from_f64(0.00001000).unwrap(),
+                    ..Default::default()
                 },
             },
             OrderSymbol {
@@ -822,13 +1674,16 @@ mod tests {
                 base_asset_precision: 8,
                 quote_precision: 8,
                 symbol_order: SymbolOrder::Desc,
+                order_type: OrderType::Market,
                 min_profit_qty: None,
+                min_profit_percent: None,
                 max_order_qty: None,
                 order_book: &order_book_2,
                 symbol_filter: SymbolFilter {
                     lot_size_step: 4,
                     tick_size: 2,
                     lot_size_min_qty: Decimal::from_f64(0.00010000).unwrap(),
+                    ..Default::default()
                 },
             },
             OrderSymbol {
@@ -836,19 +1691,29 @@ mod tests {
                 base_asset_precision: 8,
                 quote_precision: 8,
                 symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
                 min_profit_qty: None,
+                min_profit_percent: None,
                 max_order_qty: None,
                 order_book: &order_book_3,
                 symbol_filter: SymbolFilter {
                     lot_size_step: 4,
                     tick_size: 5,
                     lot_size_min_qty: Decimal::from_f64(0.00010000).unwrap(),
+                    ..Default::default()
                 },
             },
         ];
 
-        let orders =
-            OrderBuilder::calculate_chain_profit(&order_symbols, market_depth_limit, fee_percent);
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            market_depth_limit,
+            OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+            &FeeSchedule::flat(fee_percent),
+            &MinNotional::default(),
+            LimitPricing::default(),
+            false,
+        );
 
         assert_eq!(orders.len(), 3);
 
@@ -868,7 +1733,7 @@ mod tests {
         assert_eq!(orders[2].symbol_order, SymbolOrder::Asc);
         assert_eq!(orders[2].price.to_string(), "0.02858");
         assert_eq!(orders[2].base_qty.to_string(), "0.0027");
-        assert_eq!(orders[2].quote_qty.to_string(), "0.000077166");
+        assert_eq!(orders[2].quote_qty.to_string(), "0.00007716");
 
         Ok(())
     }
@@ -887,6 +1752,7 @@ mod tests {
             bid_qty: Decimal::from_f64(0.20000000).unwrap(),
             ask_price: Decimal::from_f64(109615.47000000).unwrap(),
             ask_qty: Decimal::from_f64(2.22969000).unwrap(),
+            ..Default::default()
         };
 
         let order_book_2 = BookTickerEvent {
@@ -896,6 +1762,7 @@ mod tests {
             bid_qty: Decimal::from_f64(19.28810000).unwrap(),
             ask_price: Decimal::from_f64(2585.71000000).unwrap(),
             ask_qty: Decimal::from_f64(0.9).unwrap(),
+            ..Default::default()
         };
 
         let order_book_3 = BookTickerEvent {
@@ -905,6 +1772,7 @@ mod tests {
             bid_qty: Decimal::from_f64(0.01).unwrap(), // <---- here,
             ask_price: Decimal::from_f64(0.02359000).unwrap(),
             ask_qty: Decimal::from_f64(25.63400000).unwrap(),
+            ..Default::default()
         };
 
         let order_symbols = vec![
@@ -913,13 +1781,16 @@ mod tests {
                 base_asset_precision: 8,
                 quote_precision: 8,
                 symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
                 min_profit_qty: Decimal::from_f64(0.000030),
+                min_profit_percent: None,
                 max_order_qty: Decimal::from_f64(0.00030),
                 order_book: &order_book_1,
                 symbol_filter: SymbolFilter {
                     lot_size_step: 5,
                     tick_size: 2,
                     lot_size_min_qty: Decimal::from_f64(0.00001000).unwrap(),
+                    ..Default::default()
                 },
             },
             OrderSymbol {
@@ -927,13 +1798,16 @@ mod tests {
                 base_asset_precision: 8,
                 quote_precision: 8,
                 symbol_order: SymbolOrder::Desc,
+                order_type: OrderType::Market,
                 min_profit_qty: None,
+                min_profit_percent: None,
                 max_order_qty: None,
                 order_book: &order_book_2,
                 symbol_filter: SymbolFilter {
                     lot_size_step: 4,
                     tick_size: 2,
                     lot_size_min_qty: Decimal::from_f64(0.00010000).unwrap(),
+                    ..Default::default()
                 },
             },
             OrderSymbol {
@@ -941,19 +1815,29 @@ mod tests {
                 base_asset_precision: 8,
                 quote_precision: 8,
                 symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
                 min_profit_qty: None,
+                min_profit_percent: None,
                 max_order_qty: None,
                 order_book: &order_book_3,
                 symbol_filter: SymbolFilter {
                     lot_size_step: 4,
                     tick_size: 5,
                     lot_size_min_qty: Decimal::from_f64(0.00010000).unwrap(),
+                    ..Default::default()
                 },
             },
         ];
 
-        let orders =
-            OrderBuilder::calculate_chain_profit(&order_symbols, market_depth_limit, fee_percent);
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            market_depth_limit,
+            OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+            &FeeSchedule::flat(fee_percent),
+            &MinNotional::default(),
+            LimitPricing::default(),
+            false,
+        );
 
         assert_eq!(orders.len(), 3);
 
@@ -973,7 +1857,7 @@ mod tests {
         assert_eq!(orders[2].symbol_order, SymbolOrder::Asc);
         assert_eq!(orders[2].price.to_string(), "0.02858");
         assert_eq!(orders[2].base_qty.to_string(), "0.0097");
-        assert_eq!(orders[2].quote_qty.to_string(), "0.000277226");
+        assert_eq!(orders[2].quote_qty.to_string(), "0.00027722");
 
         Ok(())
     }
@@ -991,6 +1875,7 @@ mod tests {
             bid_qty: Decimal::from_f64(0.20000000).unwrap(),
             ask_price: Decimal::from_f64(0.03216000).unwrap(),
             ask_qty: Decimal::from_f64(2.22969000).unwrap(),
+            ..Default::default()
         };
 
         let order_book_2 = BookTickerEvent {
@@ -1000,6 +1885,7 @@ mod tests {
             bid_qty: Decimal::from_f64(19.28810000).unwrap(),
             ask_price: Decimal::from_f64(0.99930000).unwrap(),
             ask_qty: Decimal::from_f64(0.9).unwrap(),
+            ..Default::default()
         };
 
         let order_book_3 = BookTickerEvent {
@@ -1009,6 +1895,7 @@ mod tests {
             bid_qty: Decimal::from_f64(1.5).unwrap(), // <---- here
             ask_price: Decimal::from_f64(31.08000000).unwrap(),
             ask_qty: Decimal::from_f64(25.63400000).unwrap(),
+            ..Default::default()
         };
 
         let order_symbols = vec![
@@ -1017,13 +1904,16 @@ mod tests {
                 base_asset_precision: 8,
                 quote_precision: 8,
                 symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
                 min_profit_qty: Decimal::from_f64(0.0),
+                min_profit_percent: None,
                 max_order_qty: Decimal::from_f64(0.0079),
                 order_book: &order_book_1,
                 symbol_filter: SymbolFilter {
                     lot_size_step: 4,
                     tick_size: 5,
                     lot_size_min_qty: Decimal::from_f64(0.00010000).unwrap(),
+                    ..Default::default()
                 },
             },
             OrderSymbol {
@@ -1031,13 +1921,16 @@ mod tests {
                 base_asset_precision: 8,
                 quote_precision: 8,
                 symbol_order: SymbolOrder::Desc,
+                order_type: OrderType::Market,
                 min_profit_qty: None,
+                min_profit_percent: None,
                 max_order_qty: None,
                 order_book: &order_book_2,
                 symbol_filter: SymbolFilter {
                     lot_size_step: 5,
                     tick_size: 4,
                     lot_size_min_qty: Decimal::from_f64(0.00010000).unwrap(),
+                    ..Default::default()
                 },
             },
             OrderSymbol {
@@ -1045,19 +1938,29 @@ mod tests {
                 base_asset_precision: 8,
                 quote_precision: 8,
                 symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
                 min_profit_qty: None,
+                min_profit_percent: None,
                 max_order_qty: None,
                 order_book: &order_book_3,
                 symbol_filter: SymbolFilter {
                     lot_size_step: 5,
                     tick_size: 2,
                     lot_size_min_qty: Decimal::from_f64(0.00100000).unwrap(),
+                    ..Default::default()
                 },
             },
         ];
 
-        let orders =
-            OrderBuilder::calculate_chain_profit(&order_symbols, market_depth_limit, fee_percent);
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            market_depth_limit,
+            OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+            &FeeSchedule::flat(fee_percent),
+            &MinNotional::default(),
+            LimitPricing::default(),
+            false,
+        );
         assert_eq!(orders.len(), 0);
 
         Ok(())
@@ -1075,6 +1978,7 @@ mod tests {
             bid_qty: Decimal::from_f64(23.09700000).unwrap(),
             ask_price: Decimal::from_f64(0.03203000).unwrap(),
             ask_qty: Decimal::from_f64(23.09700000).unwrap(),
+            ..Default::default()
         };
 
         let order_book_2 = BookTickerEvent {
@@ -1084,6 +1988,7 @@ mod tests {
             bid_qty: Decimal::from_f64(1.62000000).unwrap(),
             ask_price: Decimal::from_f64(0.00007810).unwrap(),
             ask_qty: Decimal::from_f64(1.62000000).unwrap(),
+            ..Default::default()
         };
 
         let order_book_3 = BookTickerEvent {
@@ -1093,6 +1998,7 @@ mod tests {
             bid_qty: Decimal::from_f64(0.54000000).unwrap(), // <---- here
             ask_price: Decimal::from_f64(0.00243300).unwrap(),
             ask_qty: Decimal::from_f64(0.54000000).unwrap(),
+            ..Default::default()
         };
         let order_symbols = vec![
             OrderSymbol {
@@ -1100,13 +2006,16 @@ mod tests {
                 base_asset_precision: 8,
                 quote_precision: 8,
                 symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
                 min_profit_qty: Decimal::from_f64(0.0),
+                min_profit_percent: None,
                 max_order_qty: Decimal::from_f64(0.0079),
                 order_book: &order_book_1,
                 symbol_filter: SymbolFilter {
                     lot_size_step: 4,
                     tick_size: 5,
                     lot_size_min_qty: Decimal::from_f64(0.00010000).unwrap(),
+                    ..Default::default()
                 },
             },
             OrderSymbol {
@@ -1114,13 +2023,16 @@ mod tests {
                 base_asset_precision: 8,
                 quote_precision: 8,
                 symbol_order: SymbolOrder::Desc,
+                order_type: OrderType::Market,
                 min_profit_qty: None,
+                min_profit_percent: None,
                 max_order_qty: None,
                 order_book: &order_book_2,
                 symbol_filter: SymbolFilter {
                     lot_size_step: 2,
                     tick_size: 7,
                     lot_size_min_qty: Decimal::from_f64(0.00010000).unwrap(),
+                    ..Default::default()
                 },
             },
             OrderSymbol {
@@ -1128,25 +2040,36 @@ mod tests {
                 base_asset_precision: 8,
                 quote_precision: 8,
                 symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
                 min_profit_qty: None,
+                min_profit_percent: None,
                 max_order_qty: None,
                 order_book: &order_book_3,
                 symbol_filter: SymbolFilter {
                     lot_size_step: 2,
                     tick_size: 6,
                     lot_size_min_qty: Decimal::from_f64(0.00100000).unwrap(),
+                    ..Default::default()
                 },
             },
         ];
 
-        let orders =
-            OrderBuilder::calculate_chain_profit(&order_symbols, market_depth_limit, fee_percent);
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            market_depth_limit,
+            OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+            &FeeSchedule::flat(fee_percent),
+            &MinNotional::default(),
+            LimitPricing::default(),
+            false,
+        );
         assert_eq!(orders.len(), 3);
 
         assert_eq!(orders[0].symbol, "ETHBTC");
         assert_eq!(orders[0].symbol_order, SymbolOrder::Asc);
         assert_eq!(orders[0].price.to_string(), "0.03402");
         assert_eq!(orders[0].base_qty.to_string(), "0.0012");
+        // Only the last leg's return is floored, an earlier Asc leg passes its quote on whole.
         assert_eq!(orders[0].quote_qty.to_string(), "0.000040824");
 
         assert_eq!(orders[1].symbol, "SSVBTC");
@@ -1163,4 +2086,1775 @@ mod tests {
 
         Ok(())
     }
+
+    // Case #7: an unbounded depth limit stops once the book has no deeper levels
+    // and never runs past the iteration cap.
+    #[tokio::test]
+    async fn test_calculate_chain_profit_depth_capped() -> anyhow::Result<()> {
+        let fee_percent: Decimal = Decimal::from_str("0.075").unwrap();
+
+        let order_book_1 = BookTickerEvent {
+            update_id: 1,
+            symbol: "BTCUSDT".to_owned(),
+            bid_price: Decimal::from_f64(109615.46000000).unwrap(),
+            bid_qty: Decimal::from_f64(0.00020000).unwrap(), // <---- here,
+            ask_price: Decimal::from_f64(109615.47000000).unwrap(),
+            ask_qty: Decimal::from_f64(2.22969000).unwrap(),
+            ..Default::default()
+        };
+
+        let order_book_2 = BookTickerEvent {
+            update_id: 1,
+            symbol: "ETHUSDT".to_owned(),
+            bid_price: Decimal::from_f64(2585.70000000).unwrap(),
+            bid_qty: Decimal::from_f64(14.64600000).unwrap(),
+            ask_price: Decimal::from_f64(2585.71000000).unwrap(),
+            ask_qty: Decimal::from_f64(19.28810000).unwrap(),
+            ..Default::default()
+        };
+
+        let order_book_3 = BookTickerEvent {
+            update_id: 1,
+            symbol: "ETHBTC".to_owned(),
+            bid_price: Decimal::from_f64(0.02858000).unwrap(),
+            bid_qty: Decimal::from_f64(105.74550000).unwrap(),
+            ask_price: Decimal::from_f64(0.02359000).unwrap(),
+            ask_qty: Decimal::from_f64(25.63400000).unwrap(),
+            ..Default::default()
+        };
+
+        let order_symbols = vec![
+            OrderSymbol {
+                symbol: "BTCUSDT".to_owned(),
+                base_asset_precision: 8,
+                quote_precision: 8,
+                symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
+                min_profit_qty: Decimal::from_f64(0.0),
+                min_profit_percent: None,
+                max_order_qty: Decimal::from_f64(0.00030),
+                order_book: &order_book_1,
+                symbol_filter: SymbolFilter {
+                    lot_size_step: 5,
+                    tick_size: 2,
+                    lot_size_min_qty: Decimal::from_f64(0.00001000).unwrap(),
+                    ..Default::default()
+                },
+            },
+            OrderSymbol {
+                symbol: "ETHUSDT".to_owned(),
+                base_asset_precision: 8,
+                quote_precision: 8,
+                symbol_order: SymbolOrder::Desc,
+                order_type: OrderType::Market,
+                min_profit_qty: None,
+                min_profit_percent: None,
+                max_order_qty: None,
+                order_book: &order_book_2,
+                symbol_filter: SymbolFilter {
+                    lot_size_step: 4,
+                    tick_size: 2,
+                    lot_size_min_qty: Decimal::from_f64(0.00010000).unwrap(),
+                    ..Default::default()
+                },
+            },
+            OrderSymbol {
+                symbol: "ETHBTC".to_owned(),
+                base_asset_precision: 8,
+                quote_precision: 8,
+                symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
+                min_profit_qty: None,
+                min_profit_percent: None,
+                max_order_qty: None,
+                order_book: &order_book_3,
+                symbol_filter: SymbolFilter {
+                    lot_size_step: 4,
+                    tick_size: 5,
+                    lot_size_min_qty: Decimal::from_f64(0.00010000).unwrap(),
+                    ..Default::default()
+                },
+            },
+        ];
+
+        let expected = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            1,
+            OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+            &FeeSchedule::flat(fee_percent),
+            &MinNotional::default(),
+            LimitPricing::default(),
+            false,
+        );
+        assert_eq!(expected.len(), 3);
+
+        for max_depth_iterations in [1, 3, OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS] {
+            let orders = OrderBuilder::calculate_chain_profit(
+                &order_symbols,
+                usize::MAX,
+                max_depth_iterations,
+                &FeeSchedule::flat(fee_percent),
+                &MinNotional::default(),
+                LimitPricing::default(),
+                false,
+            );
+            assert_eq!(orders, expected);
+        }
+
+        Ok(())
+    }
+
+    // Case #8: the best bid of the 1st pair does not cover the max order qty. Top of book
+    // sizes the chain down to the best level, depth levels fill it at the deeper price.
+    // (order - ASC/DESC/ASC)
+    #[tokio::test]
+    async fn test_calculate_chain_profit_depth_levels() -> anyhow::Result<()> {
+        let fee_percent: Decimal = Decimal::from_str("0.075").unwrap();
+
+        let order_book_1 = BookTickerEvent {
+            update_id: 1,
+            symbol: "BTCUSDT".to_owned(),
+            bid_price: Decimal::from_f64(109615.46000000).unwrap(),
+            bid_qty: Decimal::from_f64(0.00020000).unwrap(),
+            ask_price: Decimal::from_f64(109615.47000000).unwrap(),
+            ask_qty: Decimal::from_f64(2.22969000).unwrap(),
+            bids: vec![
+                BookLevel {
+                    price: Decimal::from_f64(109615.46000000).unwrap(),
+                    qty: Decimal::from_f64(0.00020000).unwrap(),
+                },
+                BookLevel {
+                    price: Decimal::from_f64(109600.00000000).unwrap(),
+                    qty: Decimal::from_f64(1.50000000).unwrap(),
+                },
+            ],
+            ..Default::default()
+        };
+
+        // No depth levels, the best ask is used at any depth.
+        let order_book_2 = BookTickerEvent {
+            update_id: 1,
+            symbol: "ETHUSDT".to_owned(),
+            bid_price: Decimal::from_f64(2585.70000000).unwrap(),
+            bid_qty: Decimal::from_f64(14.64600000).unwrap(),
+            ask_price: Decimal::from_f64(2585.71000000).unwrap(),
+            ask_qty: Decimal::from_f64(19.28810000).unwrap(),
+            ..Default::default()
+        };
+
+        let order_book_3 = BookTickerEvent {
+            update_id: 1,
+            symbol: "ETHBTC".to_owned(),
+            bid_price: Decimal::from_f64(0.02858000).unwrap(),
+            bid_qty: Decimal::from_f64(105.74550000).unwrap(),
+            ask_price: Decimal::from_f64(0.02359000).unwrap(),
+            ask_qty: Decimal::from_f64(25.63400000).unwrap(),
+            ..Default::default()
+        };
+
+        let order_symbols = vec![
+            OrderSymbol {
+                symbol: "BTCUSDT".to_owned(),
+                base_asset_precision: 8,
+                quote_precision: 8,
+                symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
+                min_profit_qty: Decimal::from_f64(0.000030),
+                min_profit_percent: None,
+                max_order_qty: Decimal::from_f64(0.00030),
+                order_book: &order_book_1,
+                symbol_filter: SymbolFilter {
+                    lot_size_step: 5,
+                    tick_size: 2,
+                    lot_size_min_qty: Decimal::from_f64(0.00001000).unwrap(),
+                    ..Default::default()
+                },
+            },
+            OrderSymbol {
+                symbol: "ETHUSDT".to_owned(),
+                base_asset_precision: 8,
+                quote_precision: 8,
+                symbol_order: SymbolOrder::Desc,
+                order_type: OrderType::Market,
+                min_profit_qty: None,
+                min_profit_percent: None,
+                max_order_qty: None,
+                order_book: &order_book_2,
+                symbol_filter: SymbolFilter {
+                    lot_size_step: 4,
+                    tick_size: 2,
+                    lot_size_min_qty: Decimal::from_f64(0.00010000).unwrap(),
+                    ..Default::default()
+                },
+            },
+            OrderSymbol {
+                symbol: "ETHBTC".to_owned(),
+                base_asset_precision: 8,
+                quote_precision: 8,
+                symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
+                min_profit_qty: None,
+                min_profit_percent: None,
+                max_order_qty: None,
+                order_book: &order_book_3,
+                symbol_filter: SymbolFilter {
+                    lot_size_step: 4,
+                    tick_size: 5,
+                    lot_size_min_qty: Decimal::from_f64(0.00010000).unwrap(),
+                    ..Default::default()
+                },
+            },
+        ];
+
+        let top_of_book = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            1,
+            OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+            &FeeSchedule::flat(fee_percent),
+            &MinNotional::default(),
+            LimitPricing::default(),
+            false,
+        );
+        let depth = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            5,
+            OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+            &FeeSchedule::flat(fee_percent),
+            &MinNotional::default(),
+            LimitPricing::default(),
+            false,
+        );
+
+        assert_eq!(top_of_book.len(), 3);
+        assert_eq!(top_of_book[0].price.to_string(), "109615.46");
+        assert_eq!(top_of_book[0].base_qty.to_string(), "0.00020");
+        assert_eq!(top_of_book[0].quote_qty.to_string(), "21.9230920");
+
+        // The whole size is priced at the deepest level it reaches.
+        assert_eq!(depth.len(), 3);
+        assert_eq!(depth[0].price.to_string(), "109600.00");
+        assert_eq!(depth[0].base_qty.to_string(), "0.00030");
+        assert_eq!(depth[0].quote_qty.to_string(), "32.8800000");
+        assert_eq!(depth[1].price.to_string(), "2585.71");
+        assert_eq!(depth[2].price.to_string(), "0.02858");
+
+        assert_eq!(top_of_book[2].quote_qty.to_string(), "0.00024007");
+        assert_eq!(depth[2].quote_qty.to_string(), "0.00036296");
+        // Priced at the best bid, the same size would overstate the first leg's return.
+        assert!(depth[0].quote_qty < depth[0].base_qty * top_of_book[0].price);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lot_size_depends_on_order_type() {
+        let filter = define_symbol_filter(&vec![
+            Filters::LotSize {
+                min_qty: Decimal::new(1, 5),
+                max_qty: Decimal::from(9000),
+                step_size: Decimal::new(1, 5),
+            },
+            Filters::MarketLotSize {
+                min_qty: "0.00100000".to_owned(),
+                max_qty: "77.94145208".to_owned(),
+                step_size: "0.00100000".to_owned(),
+            },
+        ]);
+
+        assert_eq!(filter.lot_size(&OrderType::Limit), (5, Decimal::new(1, 5)));
+        assert_eq!(filter.lot_size(&OrderType::Market), (3, Decimal::new(1, 3)));
+
+        // A zero market step falls back to LOT_SIZE.
+        let filter = define_symbol_filter(&vec![Filters::MarketLotSize {
+            min_qty: "0.00000000".to_owned(),
+            max_qty: "77.94145208".to_owned(),
+            step_size: "0.00000000".to_owned(),
+        }]);
+        assert_eq!(filter.market_lot_size_step, None);
+    }
+
+    // Case #8: the 2nd pair has a coarser market step than its limit step, so the rounding
+    // of that leg depends on the order type.
+    #[tokio::test]
+    async fn test_calculate_chain_profit_market_lot_size() -> anyhow::Result<()> {
+        let fee_percent: Decimal = Decimal::from_str("0.075").unwrap();
+
+        let order_book_1 = BookTickerEvent {
+            update_id: 1,
+            symbol: "BTCUSDT".to_owned(),
+            bid_price: Decimal::from_f64(109615.46000000).unwrap(),
+            bid_qty: Decimal::from_f64(0.00020000).unwrap(), // <---- here,
+            ask_price: Decimal::from_f64(109615.47000000).unwrap(),
+            ask_qty: Decimal::from_f64(2.22969000).unwrap(),
+            ..Default::default()
+        };
+
+        let order_book_2 = BookTickerEvent {
+            update_id: 1,
+            symbol: "ETHUSDT".to_owned(),
+            bid_price: Decimal::from_f64(2585.70000000).unwrap(),
+            bid_qty: Decimal::from_f64(14.64600000).unwrap(),
+            ask_price: Decimal::from_f64(2585.71000000).unwrap(),
+            ask_qty: Decimal::from_f64(19.28810000).unwrap(),
+            ..Default::default()
+        };
+
+        let order_book_3 = BookTickerEvent {
+            update_id: 1,
+            symbol: "ETHBTC".to_owned(),
+            bid_price: Decimal::from_f64(0.02858000).unwrap(),
+            bid_qty: Decimal::from_f64(105.74550000).unwrap(),
+            ask_price: Decimal::from_f64(0.02359000).unwrap(),
+            ask_qty: Decimal::from_f64(25.63400000).unwrap(),
+            ..Default::default()
+        };
+
+        let mut order_symbols = vec![
+            OrderSymbol {
+                symbol: "BTCUSDT".to_owned(),
+                base_asset_precision: 8,
+                quote_precision: 8,
+                symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
+                min_profit_qty: Decimal::from_f64(0.0),
+                min_profit_percent: None,
+                max_order_qty: Decimal::from_f64(0.00030),
+                order_book: &order_book_1,
+                symbol_filter: SymbolFilter {
+                    lot_size_step: 5,
+                    tick_size: 2,
+                    lot_size_min_qty: Decimal::from_f64(0.00001000).unwrap(),
+                    ..Default::default()
+                },
+            },
+            OrderSymbol {
+                symbol: "ETHUSDT".to_owned(),
+                base_asset_precision: 8,
+                quote_precision: 8,
+                symbol_order: SymbolOrder::Desc,
+                order_type: OrderType::Market,
+                min_profit_qty: None,
+                min_profit_percent: None,
+                max_order_qty: None,
+                order_book: &order_book_2,
+                symbol_filter: SymbolFilter {
+                    lot_size_step: 4,
+                    tick_size: 2,
+                    lot_size_min_qty: Decimal::from_f64(0.00010000).unwrap(),
+                    ..Default::default()
+                },
+            },
+            OrderSymbol {
+                symbol: "ETHBTC".to_owned(),
+                base_asset_precision: 8,
+                quote_precision: 8,
+                symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
+                min_profit_qty: None,
+                min_profit_percent: None,
+                max_order_qty: None,
+                order_book: &order_book_3,
+                symbol_filter: SymbolFilter {
+                    lot_size_step: 4,
+                    tick_size: 5,
+                    lot_size_min_qty: Decimal::from_f64(0.00010000).unwrap(),
+                    ..Default::default()
+                },
+            },
+        ];
+
+        order_symbols[1].symbol_filter.market_lot_size_step = Some(3);
+        order_symbols[1].symbol_filter.market_lot_size_min_qty = Decimal::new(1, 3);
+
+        for order_symbol in &mut order_symbols {
+            order_symbol.order_type = OrderType::Limit;
+        }
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            1,
+            OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+            &FeeSchedule::flat(fee_percent),
+            &MinNotional::default(),
+            LimitPricing::default(),
+            false,
+        );
+        assert_eq!(orders.len(), 3);
+        assert_eq!(orders[1].quote_qty.to_string(), "0.0084");
+        assert_eq!(orders[1].base_increment, Decimal::new(1, 4));
+        assert_eq!(orders[2].base_qty.to_string(), "0.0084");
+
+        for order_symbol in &mut order_symbols {
+            order_symbol.order_type = OrderType::Market;
+        }
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            1,
+            OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+            &FeeSchedule::flat(fee_percent),
+            &MinNotional::default(),
+            LimitPricing::default(),
+            false,
+        );
+        assert_eq!(orders.len(), 3);
+        assert_eq!(orders[0].base_qty.to_string(), "0.00020");
+        assert_eq!(orders[1].quote_qty.to_string(), "0.008");
+        assert_eq!(orders[1].base_increment, Decimal::new(1, 3));
+        assert_eq!(orders[2].base_qty.to_string(), "0.0080");
+        assert_eq!(orders[2].quote_qty.to_string(), "0.00022864");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_limit_price_rounds_toward_aggressive_side() {
+        let price = Decimal::from_str("100.005").unwrap();
+        let leg_price = |rounding, order_type, symbol_order| {
+            LimitPricing {
+                rounding,
+                offset_ticks: 0,
+            }
+            .leg_price(price, 2, order_type, symbol_order)
+            .to_string()
+        };
+
+        // Buy legs round up, sell legs down.
+        let aggressive = PriceRounding::Aggressive;
+        assert_eq!(
+            leg_price(aggressive, OrderType::Limit, SymbolOrder::Desc),
+            "100.01"
+        );
+        assert_eq!(
+            leg_price(aggressive, OrderType::Limit, SymbolOrder::Asc),
+            "100.00"
+        );
+
+        let truncate = PriceRounding::Truncate;
+        assert_eq!(
+            leg_price(truncate, OrderType::Limit, SymbolOrder::Desc),
+            "100.00"
+        );
+        assert_eq!(
+            leg_price(truncate, OrderType::Limit, SymbolOrder::Asc),
+            "100.00"
+        );
+
+        // Market legs are only truncated.
+        assert_eq!(
+            leg_price(aggressive, OrderType::Market, SymbolOrder::Desc),
+            "100.00"
+        );
+        assert_eq!(
+            leg_price(aggressive, OrderType::Market, SymbolOrder::Asc),
+            "100.00"
+        );
+    }
+
+    // Case #10: USDT -> BTC -> ETH -> USDT on limit legs, the BTCUSDT ask off the tick grid.
+    #[test]
+    fn test_calculate_chain_profit_at_limit_leg_prices() {
+        let book = |symbol: &str, bid_price: &str, ask_price: &str| BookTickerEvent {
+            update_id: 1,
+            symbol: symbol.to_owned(),
+            bid_price: Decimal::from_str(bid_price).unwrap(),
+            bid_qty: Decimal::from(1000),
+            ask_price: Decimal::from_str(ask_price).unwrap(),
+            ask_qty: Decimal::from(1000),
+            ..Default::default()
+        };
+        let order_books = [
+            book("BTCUSDT", "100.004", "100.005"),
+            book("ETHBTC", "0.04999", "0.05"),
+            book("ETHUSDT", "5.2", "5.21"),
+        ];
+        let order_symbol = |i: usize, symbol_order, lot_size_step, tick_size| OrderSymbol {
+            symbol: order_books[i].symbol.clone(),
+            base_asset_precision: 8,
+            quote_precision: 8,
+            symbol_order,
+            order_type: OrderType::Limit,
+            min_profit_qty: (i == 0).then(Decimal::zero),
+            min_profit_percent: None,
+            max_order_qty: (i == 0).then(|| Decimal::from(100)),
+            order_book: &order_books[i],
+            symbol_filter: SymbolFilter {
+                lot_size_step,
+                tick_size,
+                ..Default::default()
+            },
+        };
+        let order_symbols = [
+            order_symbol(0, SymbolOrder::Desc, 5, 2),
+            order_symbol(1, SymbolOrder::Desc, 4, 5),
+            order_symbol(2, SymbolOrder::Asc, 4, 2),
+        ];
+        let calculate = |limit_pricing| {
+            OrderBuilder::calculate_chain_profit(
+                &order_symbols,
+                1,
+                OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+                &FeeSchedule::flat(Decimal::ZERO),
+                &MinNotional::default(),
+                limit_pricing,
+                false,
+            )
+        };
+        let prices = |orders: &[ChainOrder]| {
+            orders
+                .iter()
+                .map(|order| order.price.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        // The buy leg is placed a tick above the ask and sized at that price: 100 USDT buy
+        // 0.99990 BTC, 19.998 ETH and sell back for 103.9896 USDT.
+        let orders = calculate(LimitPricing {
+            rounding: PriceRounding::Aggressive,
+            offset_ticks: 0,
+        });
+        assert_eq!(prices(&orders), ["100.01", "0.05000", "5.20"]);
+        assert_eq!(orders[0].quote_qty.to_string(), "0.99990");
+        assert_eq!(orders[2].quote_qty, Decimal::from_str("103.9896").unwrap());
+
+        // Truncated below the ask, the leg would buy 1 BTC and the chain return 104 USDT.
+        let orders = calculate(LimitPricing {
+            rounding: PriceRounding::Truncate,
+            offset_ticks: 0,
+        });
+        assert_eq!(prices(&orders), ["100.00", "0.05000", "5.20"]);
+        assert_eq!(orders[2].quote_qty, Decimal::from(104));
+
+        // Two ticks past the touch the legs are placed and sized at the offset prices and
+        // the chain returns 103.52748 USDT.
+        let offset = LimitPricing {
+            rounding: PriceRounding::Aggressive,
+            offset_ticks: 2,
+        };
+        let orders = calculate(offset);
+        assert_eq!(prices(&orders), ["100.03", "0.05002", "5.18"]);
+        assert_eq!(orders[2].quote_qty, Decimal::from_str("103.52748").unwrap());
+
+        // The profit gate checks that return: 3.52748 USDT is short of a 3.6 USDT minimum
+        // the chain passes without the offset.
+        let mut order_symbols = order_symbols.clone();
+        order_symbols[0].min_profit_qty = Some(Decimal::from_str("3.6").unwrap());
+        let calculate = |limit_pricing| {
+            OrderBuilder::calculate_chain_profit(
+                &order_symbols,
+                1,
+                OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+                &FeeSchedule::flat(Decimal::ZERO),
+                &MinNotional::default(),
+                limit_pricing,
+                false,
+            )
+        };
+        assert!(calculate(offset).is_empty());
+        assert_eq!(
+            calculate(LimitPricing {
+                rounding: PriceRounding::Aggressive,
+                offset_ticks: 0,
+            })
+            .len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_limit_price_offset_moves_toward_aggressive_side() {
+        let leg_price = |price: &str, offset_ticks, order_type, symbol_order| {
+            LimitPricing {
+                rounding: PriceRounding::Aggressive,
+                offset_ticks,
+            }
+            .leg_price(
+                Decimal::from_str(price).unwrap(),
+                2,
+                order_type,
+                symbol_order,
+            )
+            .to_string()
+        };
+
+        // Buy legs are moved up, sell legs down.
+        assert_eq!(
+            leg_price("100.005", 3, OrderType::Limit, SymbolOrder::Desc),
+            "100.04"
+        );
+        assert_eq!(
+            leg_price("100.005", 3, OrderType::Limit, SymbolOrder::Asc),
+            "99.97"
+        );
+        // A sell leg stops at a single tick.
+        assert_eq!(
+            leg_price("0.02", 5, OrderType::Limit, SymbolOrder::Asc),
+            "0.01"
+        );
+        // Market legs are not offset.
+        assert_eq!(
+            leg_price("100.005", 3, OrderType::Market, SymbolOrder::Desc),
+            "100.00"
+        );
+    }
+
+    // Case #11: USDT -> ETH -> BTC -> USDT, the middle leg selling ETH for BTC.
+    #[test]
+    fn test_calculate_chain_profit_floors_only_the_last_leg() {
+        let book = |symbol: &str, price: &str| BookTickerEvent {
+            update_id: 1,
+            symbol: symbol.to_owned(),
+            bid_price: Decimal::from_str(price).unwrap(),
+            bid_qty: Decimal::from(1000),
+            ask_price: Decimal::from_str(price).unwrap(),
+            ask_qty: Decimal::from(1000),
+            ..Default::default()
+        };
+        let order_books = [
+            book("ETHUSDT", "2000"),
+            book("ETHBTC", "0.033333"),
+            book("BTCUSDT", "61000"),
+        ];
+        let order_symbol =
+            |i: usize, symbol_order, quote_precision, lot_size_step, tick_size| OrderSymbol {
+                symbol: order_books[i].symbol.clone(),
+                base_asset_precision: 8,
+                quote_precision,
+                symbol_order,
+                order_type: OrderType::Market,
+                min_profit_qty: (i == 0).then(Decimal::zero),
+                min_profit_percent: None,
+                max_order_qty: (i == 0).then(|| Decimal::from(100)),
+                order_book: &order_books[i],
+                symbol_filter: SymbolFilter {
+                    lot_size_step,
+                    tick_size,
+                    ..Default::default()
+                },
+            };
+        let order_symbols = [
+            order_symbol(0, SymbolOrder::Desc, 2, 4, 2),
+            order_symbol(1, SymbolOrder::Asc, 5, 4, 6),
+            order_symbol(2, SymbolOrder::Asc, 2, 5, 2),
+        ];
+
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            1,
+            OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+            &FeeSchedule::flat(Decimal::ZERO),
+            &MinNotional::default(),
+            LimitPricing::default(),
+            false,
+        );
+        assert_eq!(orders.len(), 3);
+
+        // 0.05 ETH sell for 0.00166665 BTC, passed on whole though finer than the BTC
+        // precision of ETHBTC. The last leg sells 0.00166 BTC for 101.26 USDT.
+        assert_eq!(
+            orders[1].quote_qty,
+            Decimal::from_str("0.00166665").unwrap()
+        );
+        assert_eq!(orders[2].base_qty.to_string(), "0.00166");
+        assert_eq!(orders[2].quote_qty, Decimal::from_str("101.26").unwrap());
+    }
+
+    // Case #9: the last leg returns 0.000240072 BTC, but only 0.00024 BTC is credited at
+    // the quote precision, so the profit gate must use the rounded return.
+    #[tokio::test]
+    async fn test_calculate_chain_profit_gate_uses_rounded_return() -> anyhow::Result<()> {
+        let fee_percent: Decimal = Decimal::from_str("0.075").unwrap();
+
+        let order_book_1 = BookTickerEvent {
+            update_id: 1,
+            symbol: "BTCUSDT".to_owned(),
+            bid_price: Decimal::from_f64(109615.46000000).unwrap(),
+            bid_qty: Decimal::from_f64(0.00020000).unwrap(), // <---- here,
+            ask_price: Decimal::from_f64(109615.47000000).unwrap(),
+            ask_qty: Decimal::from_f64(2.22969000).unwrap(),
+            ..Default::default()
+        };
+
+        let order_book_2 = BookTickerEvent {
+            update_id: 1,
+            symbol: "ETHUSDT".to_owned(),
+            bid_price: Decimal::from_f64(2585.70000000).unwrap(),
+            bid_qty: Decimal::from_f64(14.64600000).unwrap(),
+            ask_price: Decimal::from_f64(2585.71000000).unwrap(),
+            ask_qty: Decimal::from_f64(19.28810000).unwrap(),
+            ..Default::default()
+        };
+
+        let order_book_3 = BookTickerEvent {
+            update_id: 1,
+            symbol: "ETHBTC".to_owned(),
+            bid_price: Decimal::from_f64(0.02858000).unwrap(),
+            bid_qty: Decimal::from_f64(105.74550000).unwrap(),
+            ask_price: Decimal::from_f64(0.02359000).unwrap(),
+            ask_qty: Decimal::from_f64(25.63400000).unwrap(),
+            ..Default::default()
+        };
+
+        let mut order_symbols = vec![
+            OrderSymbol {
+                symbol: "BTCUSDT".to_owned(),
+                base_asset_precision: 8,
+                quote_precision: 8,
+                symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
+                min_profit_qty: Decimal::from_f64(0.0),
+                min_profit_percent: None,
+                max_order_qty: Decimal::from_f64(0.00030),
+                order_book: &order_book_1,
+                symbol_filter: SymbolFilter {
+                    lot_size_step: 5,
+                    tick_size: 2,
+                    lot_size_min_qty: Decimal::from_f64(0.00001000).unwrap(),
+                    ..Default::default()
+                },
+            },
+            OrderSymbol {
+                symbol: "ETHUSDT".to_owned(),
+                base_asset_precision: 8,
+                quote_precision: 8,
+                symbol_order: SymbolOrder::Desc,
+                order_type: OrderType::Market,
+                min_profit_qty: None,
+                min_profit_percent: None,
+                max_order_qty: None,
+                order_book: &order_book_2,
+                symbol_filter: SymbolFilter {
+                    lot_size_step: 4,
+                    tick_size: 2,
+                    lot_size_min_qty: Decimal::from_f64(0.00010000).unwrap(),
+                    ..Default::default()
+                },
+            },
+            OrderSymbol {
+                symbol: "ETHBTC".to_owned(),
+                base_asset_precision: 8,
+                quote_precision: 8,
+                symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
+                min_profit_qty: None,
+                min_profit_percent: None,
+                max_order_qty: None,
+                order_book: &order_book_3,
+                symbol_filter: SymbolFilter {
+                    lot_size_step: 4,
+                    tick_size: 5,
+                    lot_size_min_qty: Decimal::from_f64(0.00010000).unwrap(),
+                    ..Default::default()
+                },
+            },
+        ];
+
+        order_symbols[2].quote_precision = 5;
+
+        // Profit after fee: 0.000039622 unrounded, 0.00003955 realizable.
+        order_symbols[0].min_profit_qty = Some(Decimal::new(3960, 8));
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            1,
+            OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+            &FeeSchedule::flat(fee_percent),
+            &MinNotional::default(),
+            LimitPricing::default(),
+            false,
+        );
+        assert!(orders.is_empty());
+
+        order_symbols[0].min_profit_qty = Some(Decimal::new(3955, 8));
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            1,
+            OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+            &FeeSchedule::flat(fee_percent),
+            &MinNotional::default(),
+            LimitPricing::default(),
+            false,
+        );
+        assert_eq!(orders.len(), 3);
+        assert_eq!(orders[2].quote_qty.to_string(), "0.00024");
+
+        Ok(())
+    }
+
+    // Case #10: four legs, USDT -> BTC -> ETH -> SOL -> USDT (order - DESC/DESC/DESC/ASC).
+    // 100 USDT returns 106.56 USDT, the fee is charged for all four orders.
+    #[tokio::test]
+    async fn test_calculate_chain_profit_four_legs() -> anyhow::Result<()> {
+        let fee_percent = Decimal::new(1, 1);
+
+        let order_book = |symbol: &str, bid_price: Decimal, ask_price: Decimal| BookTickerEvent {
+            update_id: 1,
+            symbol: symbol.to_owned(),
+            bid_price,
+            bid_qty: Decimal::from(1000),
+            ask_price,
+            ask_qty: Decimal::from(1000),
+            ..Default::default()
+        };
+        let order_book_1 = order_book("BTCUSDT", Decimal::from(99_999), Decimal::from(100_000));
+        let order_book_2 = order_book("ETHBTC", Decimal::new(2999, 5), Decimal::new(3, 2));
+        let order_book_3 = order_book("SOLETH", Decimal::new(4999, 5), Decimal::new(5, 2));
+        let order_book_4 = order_book("SOLUSDT", Decimal::from(160), Decimal::new(16_001, 2));
+
+        let order_symbol = |order_book, symbol_order, lot_size_step, tick_size| OrderSymbol {
+            symbol: String::new(),
+            base_asset_precision: 8,
+            quote_precision: 8,
+            symbol_order,
+            order_type: OrderType::Market,
+            min_profit_qty: None,
+            min_profit_percent: None,
+            max_order_qty: None,
+            order_book,
+            symbol_filter: SymbolFilter {
+                lot_size_step,
+                tick_size,
+                lot_size_min_qty: Decimal::new(1, 5),
+                ..Default::default()
+            },
+        };
+        let mut order_symbols = vec![
+            OrderSymbol {
+                symbol: "BTCUSDT".to_owned(),
+                max_order_qty: Some(Decimal::from(100)),
+                ..order_symbol(&order_book_1, SymbolOrder::Desc, 5, 2)
+            },
+            OrderSymbol {
+                symbol: "ETHBTC".to_owned(),
+                ..order_symbol(&order_book_2, SymbolOrder::Desc, 4, 5)
+            },
+            OrderSymbol {
+                symbol: "SOLETH".to_owned(),
+                ..order_symbol(&order_book_3, SymbolOrder::Desc, 3, 5)
+            },
+            OrderSymbol {
+                symbol: "SOLUSDT".to_owned(),
+                ..order_symbol(&order_book_4, SymbolOrder::Asc, 3, 2)
+            },
+        ];
+
+        // Profit after fee: 6.56 - 0.4 = 6.16, a three-order fee would leave 6.26.
+        order_symbols[0].min_profit_qty = Some(Decimal::new(62, 1));
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            1,
+            OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+            &FeeSchedule::flat(fee_percent),
+            &MinNotional::default(),
+            LimitPricing::default(),
+            false,
+        );
+        assert!(orders.is_empty());
+
+        order_symbols[0].min_profit_qty = Some(Decimal::new(616, 2));
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            1,
+            OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+            &FeeSchedule::flat(fee_percent),
+            &MinNotional::default(),
+            LimitPricing::default(),
+            false,
+        );
+        let legs: Vec<_> = orders
+            .iter()
+            .map(|order| (order.symbol.as_str(), order.base_qty, order.quote_qty))
+            .collect();
+        assert_eq!(
+            legs,
+            [
+                ("BTCUSDT", Decimal::from(100), Decimal::new(1, 3)),
+                ("ETHBTC", Decimal::new(1, 3), Decimal::new(333, 4)),
+                ("SOLETH", Decimal::new(333, 4), Decimal::new(666, 3)),
+                ("SOLUSDT", Decimal::new(666, 3), Decimal::new(10_656, 2)),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_maker_rebate_reduces_fee() {
+        let order_book = BookTickerEvent::default();
+        let leg = |symbol: &str, symbol_order| OrderSymbol {
+            symbol: symbol.to_owned(),
+            base_asset_precision: 8,
+            quote_precision: 8,
+            symbol_order,
+            order_type: OrderType::Market,
+            min_profit_qty: None,
+            min_profit_percent: None,
+            max_order_qty: None,
+            order_book: &order_book,
+            symbol_filter: SymbolFilter::default(),
+        };
+        let chain = [
+            leg("BTCUSDT", SymbolOrder::Desc),
+            leg("ETHBTC", SymbolOrder::Desc),
+            leg("ETHUSDT", SymbolOrder::Asc),
+        ];
+        let qty = Decimal::from(100);
+        let taker = FeeSchedule::flat(Decimal::new(1, 1));
+        assert_eq!(calculate_fee(qty, &taker, &chain), Decimal::new(3, 1));
+
+        let rates = FeeRates {
+            maker_fee_percent: Decimal::new(-2, 2),
+            taker_fee_percent: Decimal::new(1, 1),
+        };
+        let maker = FeeSchedule::new(rates).with_leg_roles(vec![LiquidityRole::Maker]);
+        // The maker leg earns 0.02% instead of paying 0.1%.
+        assert_eq!(calculate_fee(qty, &maker, &chain), Decimal::new(18, 2));
+
+        // Rebates exceeding the taker fees make the chain fee negative.
+        let all_makers = FeeSchedule::new(rates).with_leg_roles(vec![LiquidityRole::Maker; 3]);
+        assert_eq!(calculate_fee(qty, &all_makers, &chain), Decimal::new(-6, 2));
+    }
+
+    #[test]
+    fn test_leg_fees_differ_by_side_and_symbol() {
+        let order_book = BookTickerEvent::default();
+        let leg = |symbol: &str, symbol_order| OrderSymbol {
+            symbol: symbol.to_owned(),
+            base_asset_precision: 8,
+            quote_precision: 8,
+            symbol_order,
+            order_type: OrderType::Market,
+            min_profit_qty: None,
+            min_profit_percent: None,
+            max_order_qty: None,
+            order_book: &order_book,
+            symbol_filter: SymbolFilter::default(),
+        };
+        let chain = [
+            leg("BTCUSDT", SymbolOrder::Desc),
+            leg("ETHBTC", SymbolOrder::Desc),
+            leg("ETHUSDT", SymbolOrder::Asc),
+        ];
+        // Buys pay the default 0.1%, sells 0.075% and ETHBTC 0.05% whatever its side.
+        let fees = FeeSchedule::flat(Decimal::new(1, 1))
+            .with_sell_fees(FeeRates::flat(Decimal::new(75, 3)))
+            .with_symbol_fees("ETHBTC", FeeRates::flat(Decimal::new(5, 2)));
+
+        assert_eq!(
+            calculate_fee(Decimal::from(100), &fees, &chain),
+            Decimal::new(225, 3)
+        );
+    }
+
+    // The chain of case #10 against every combination of profit thresholds: its 6.16 USDT
+    // profit after fees is 6.16% of the 100 USDT spent.
+    #[tokio::test]
+    async fn test_calculate_chain_profit_percent_threshold() -> anyhow::Result<()> {
+        let order_book = |symbol: &str, bid_price: Decimal, ask_price: Decimal| BookTickerEvent {
+            update_id: 1,
+            symbol: symbol.to_owned(),
+            bid_price,
+            bid_qty: Decimal::from(1000),
+            ask_price,
+            ask_qty: Decimal::from(1000),
+            ..Default::default()
+        };
+        let order_book_1 = order_book("BTCUSDT", Decimal::from(99_999), Decimal::from(100_000));
+        let order_book_2 = order_book("ETHBTC", Decimal::new(2999, 5), Decimal::new(3, 2));
+        let order_book_3 = order_book("SOLETH", Decimal::new(4999, 5), Decimal::new(5, 2));
+        let order_book_4 = order_book("SOLUSDT", Decimal::from(160), Decimal::new(16_001, 2));
+
+        let order_symbol = |order_book, symbol_order, lot_size_step, tick_size| OrderSymbol {
+            symbol: String::new(),
+            base_asset_precision: 8,
+            quote_precision: 8,
+            symbol_order,
+            order_type: OrderType::Market,
+            min_profit_qty: None,
+            min_profit_percent: None,
+            max_order_qty: None,
+            order_book,
+            symbol_filter: SymbolFilter {
+                lot_size_step,
+                tick_size,
+                lot_size_min_qty: Decimal::new(1, 5),
+                ..Default::default()
+            },
+        };
+        let passes = |min_profit_qty, min_profit_percent| {
+            let order_symbols = [
+                OrderSymbol {
+                    symbol: "BTCUSDT".to_owned(),
+                    max_order_qty: Some(Decimal::from(100)),
+                    min_profit_qty: Some(min_profit_qty),
+                    min_profit_percent,
+                    ..order_symbol(&order_book_1, SymbolOrder::Desc, 5, 2)
+                },
+                OrderSymbol {
+                    symbol: "ETHBTC".to_owned(),
+                    ..order_symbol(&order_book_2, SymbolOrder::Desc, 4, 5)
+                },
+                OrderSymbol {
+                    symbol: "SOLETH".to_owned(),
+                    ..order_symbol(&order_book_3, SymbolOrder::Desc, 3, 5)
+                },
+                OrderSymbol {
+                    symbol: "SOLUSDT".to_owned(),
+                    ..order_symbol(&order_book_4, SymbolOrder::Asc, 3, 2)
+                },
+            ];
+            !OrderBuilder::calculate_chain_profit(
+                &order_symbols,
+                1,
+                OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+                &FeeSchedule::flat(Decimal::new(1, 1)),
+                &MinNotional::default(),
+                LimitPricing::default(),
+                false,
+            )
+            .is_empty()
+        };
+
+        // Absolute threshold only.
+        assert!(passes(Decimal::new(616, 2), None));
+        assert!(!passes(Decimal::new(62, 1), None));
+        // Percentage threshold only.
+        assert!(passes(Decimal::ZERO, Some(Decimal::new(616, 2))));
+        assert!(!passes(Decimal::ZERO, Some(Decimal::new(62, 1))));
+        // Both thresholds, each failing on its own.
+        assert!(passes(Decimal::from(6), Some(Decimal::from(6))));
+        assert!(!passes(Decimal::from(6), Some(Decimal::new(62, 1))));
+        assert!(!passes(Decimal::new(62, 1), Some(Decimal::from(6))));
+
+        Ok(())
+    }
+
+    // Case #11: the chain of case #10 misses the profit gate with taker fees only, a maker
+    // rebate on the first leg lifts its net profit above it.
+    #[tokio::test]
+    async fn test_calculate_chain_profit_maker_rebate() -> anyhow::Result<()> {
+        let order_book = |symbol: &str, bid_price: Decimal, ask_price: Decimal| BookTickerEvent {
+            update_id: 1,
+            symbol: symbol.to_owned(),
+            bid_price,
+            bid_qty: Decimal::from(1000),
+            ask_price,
+            ask_qty: Decimal::from(1000),
+            ..Default::default()
+        };
+        let order_book_1 = order_book("BTCUSDT", Decimal::from(99_999), Decimal::from(100_000));
+        let order_book_2 = order_book("ETHBTC", Decimal::new(2999, 5), Decimal::new(3, 2));
+        let order_book_3 = order_book("SOLETH", Decimal::new(4999, 5), Decimal::new(5, 2));
+        let order_book_4 = order_book("SOLUSDT", Decimal::from(160), Decimal::new(16_001, 2));
+
+        let order_symbol = |order_book, symbol_order, lot_size_step, tick_size| OrderSymbol {
+            symbol: String::new(),
+            base_asset_precision: 8,
+            quote_precision: 8,
+            symbol_order,
+            order_type: OrderType::Market,
+            min_profit_qty: None,
+            min_profit_percent: None,
+            max_order_qty: None,
+            order_book,
+            symbol_filter: SymbolFilter {
+                lot_size_step,
+                tick_size,
+                lot_size_min_qty: Decimal::new(1, 5),
+                ..Default::default()
+            },
+        };
+        let order_symbols = vec![
+            OrderSymbol {
+                symbol: "BTCUSDT".to_owned(),
+                max_order_qty: Some(Decimal::from(100)),
+                // Profit before fee: 6.56.
+                min_profit_qty: Some(Decimal::new(62, 1)),
+                min_profit_percent: None,
+                ..order_symbol(&order_book_1, SymbolOrder::Desc, 5, 2)
+            },
+            OrderSymbol {
+                symbol: "ETHBTC".to_owned(),
+                ..order_symbol(&order_book_2, SymbolOrder::Desc, 4, 5)
+            },
+            OrderSymbol {
+                symbol: "SOLETH".to_owned(),
+                ..order_symbol(&order_book_3, SymbolOrder::Desc, 3, 5)
+            },
+            OrderSymbol {
+                symbol: "SOLUSDT".to_owned(),
+                ..order_symbol(&order_book_4, SymbolOrder::Asc, 3, 2)
+            },
+        ];
+
+        // Four taker legs at 0.1%: 6.56 - 0.4 = 6.16.
+        let taker = FeeSchedule::flat(Decimal::new(1, 1));
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            1,
+            OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+            &taker,
+            &MinNotional::default(),
+            LimitPricing::default(),
+            false,
+        );
+        assert!(orders.is_empty());
+
+        // First leg as maker with a 0.02% rebate: 6.56 - 0.28 = 6.28.
+        let maker = FeeSchedule::new(FeeRates {
+            maker_fee_percent: Decimal::new(-2, 2),
+            taker_fee_percent: Decimal::new(1, 1),
+        })
+        .with_leg_roles(vec![LiquidityRole::Maker]);
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            1,
+            OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+            &maker,
+            &MinNotional::default(),
+            LimitPricing::default(),
+            false,
+        );
+        assert_eq!(orders.len(), 4);
+        assert_eq!(orders[3].quote_qty, Decimal::new(10_656, 2));
+
+        // Taker legs again, SOLETH free of fees: 6.56 - 0.3 = 6.26.
+        let free_symbol = FeeSchedule::flat(Decimal::new(1, 1))
+            .with_symbol_fees("SOLETH", FeeRates::flat(Decimal::ZERO));
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            1,
+            OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+            &free_symbol,
+            &MinNotional::default(),
+            LimitPricing::default(),
+            false,
+        );
+        assert_eq!(orders.len(), 4);
+
+        Ok(())
+    }
+
+    // Case #12: the chain of case #10 is skipped once a leg is worth less than the minimum
+    // notional of its symbol. Leg notionals: 100 USDT, 0.001 BTC, 0.0333 ETH, 106.56 USDT.
+    #[tokio::test]
+    async fn test_calculate_chain_profit_min_notional() -> anyhow::Result<()> {
+        let order_book = |symbol: &str, bid_price: Decimal, ask_price: Decimal| BookTickerEvent {
+            update_id: 1,
+            symbol: symbol.to_owned(),
+            bid_price,
+            bid_qty: Decimal::from(1000),
+            ask_price,
+            ask_qty: Decimal::from(1000),
+            ..Default::default()
+        };
+        let order_book_1 = order_book("BTCUSDT", Decimal::from(99_999), Decimal::from(100_000));
+        let order_book_2 = order_book("ETHBTC", Decimal::new(2999, 5), Decimal::new(3, 2));
+        let order_book_3 = order_book("SOLETH", Decimal::new(4999, 5), Decimal::new(5, 2));
+        let order_book_4 = order_book("SOLUSDT", Decimal::from(160), Decimal::new(16_001, 2));
+
+        let order_symbol = |order_book, symbol_order, lot_size_step, tick_size| OrderSymbol {
+            symbol: String::new(),
+            base_asset_precision: 8,
+            quote_precision: 8,
+            symbol_order,
+            order_type: OrderType::Market,
+            min_profit_qty: None,
+            min_profit_percent: None,
+            max_order_qty: None,
+            order_book,
+            symbol_filter: SymbolFilter {
+                lot_size_step,
+                tick_size,
+                lot_size_min_qty: Decimal::new(1, 5),
+                ..Default::default()
+            },
+        };
+        let order_symbols = |eth_btc_min_notional| {
+            let eth_btc = order_symbol(&order_book_2, SymbolOrder::Desc, 4, 5);
+            vec![
+                OrderSymbol {
+                    symbol: "BTCUSDT".to_owned(),
+                    max_order_qty: Some(Decimal::from(100)),
+                    min_profit_qty: Some(Decimal::from(6)),
+                    min_profit_percent: None,
+                    ..order_symbol(&order_book_1, SymbolOrder::Desc, 5, 2)
+                },
+                OrderSymbol {
+                    symbol: "ETHBTC".to_owned(),
+                    symbol_filter: SymbolFilter {
+                        min_notional: eth_btc_min_notional,
+                        ..eth_btc.symbol_filter
+                    },
+                    ..eth_btc
+                },
+                OrderSymbol {
+                    symbol: "SOLETH".to_owned(),
+                    ..order_symbol(&order_book_3, SymbolOrder::Desc, 3, 5)
+                },
+                OrderSymbol {
+                    symbol: "SOLUSDT".to_owned(),
+                    ..order_symbol(&order_book_4, SymbolOrder::Asc, 3, 2)
+                },
+            ]
+        };
+        let calculate = |order_symbols: &[OrderSymbol], min_notional: &MinNotional| {
+            OrderBuilder::calculate_chain_profit(
+                order_symbols,
+                1,
+                OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+                &FeeSchedule::flat(Decimal::new(1, 1)),
+                min_notional,
+                LimitPricing::default(),
+                false,
+            )
+        };
+
+        let passing = order_symbols(Decimal::new(1, 4));
+        assert_eq!(calculate(&passing, &MinNotional::default()).len(), 4);
+
+        // ETHBTC spends 0.001 BTC, below its 0.002 BTC filter.
+        let below_filter = order_symbols(Decimal::new(2, 3));
+        assert!(calculate(&below_filter, &MinNotional::default()).is_empty());
+
+        // SOLUSDT returns 106.56 USDT, below the configured 110 USDT.
+        let configured =
+            MinNotional::new(HashMap::from([("SOLUSDT".to_owned(), Decimal::from(110))]));
+        assert!(calculate(&passing, &configured).is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_usd_floor_overrides_base_min_profit() {
+        let asset = |asset: &str, min_profit_qty| Asset {
+            asset: asset.to_owned(),
+            symbol: None,
+            min_profit_qty,
+            min_profit_percent: None,
+            max_order_qty: Decimal::ONE,
+            min_ticker_qty_24h: Decimal::ZERO,
+            session_profit_target: None,
+            max_daily_loss: None,
+        };
+        let chain_symbol = |symbol: &str, base_asset: &str, quote_asset: &str, order| {
+            ChainSymbol::new(
+                Symbol {
+                    symbol: symbol.to_owned(),
+                    base_asset: base_asset.to_owned(),
+                    quote_asset: quote_asset.to_owned(),
+                    ..Default::default()
+                },
+                order,
+            )
+        };
+        let btc = asset("BTC", Decimal::new(3, 5));
+        let eth = asset("ETH", Decimal::new(1, 3));
+        let sol = asset("SOL", Decimal::new(1, 2));
+
+        // A 3 USD floor, with the ETH price not seen yet.
+        let floor = UsdProfitFloor::new(Decimal::from(3), "USDT");
+        floor.set_price("BTC", Decimal::from(60_000));
+
+        assert_eq!(base_min_profit_qty(&eth, None), Some(Decimal::new(1, 3)));
+        assert_eq!(
+            base_min_profit_qty(&btc, Some(&floor)),
+            Some(Decimal::new(5, 5))
+        );
+        assert_eq!(base_min_profit_qty(&eth, Some(&floor)), None);
+
+        // BTC is priced from BTCUSDT, ETH from the inverted USDTETH, SOL from nothing.
+        let chains = [
+            vec![
+                chain_symbol("BTCUSDT", "BTC", "USDT", SymbolOrder::Asc),
+                chain_symbol("SOLUSDT", "SOL", "USDT", SymbolOrder::Desc),
+                chain_symbol("SOLBTC", "SOL", "BTC", SymbolOrder::Asc),
+            ],
+            vec![
+                chain_symbol("USDTETH", "USDT", "ETH", SymbolOrder::Desc),
+                chain_symbol("SOLUSDT", "SOL", "USDT", SymbolOrder::Desc),
+                chain_symbol("SOLETH", "SOL", "ETH", SymbolOrder::Asc),
+            ],
+        ];
+        let price_symbol = |symbol: &str, asset: &str, inverted| UsdPriceSymbol {
+            symbol: symbol.to_owned(),
+            asset: asset.to_owned(),
+            inverted,
+        };
+        assert_eq!(
+            usd_price_symbols(&chains, &[btc, eth], "USDT"),
+            [
+                price_symbol("BTCUSDT", "BTC", false),
+                price_symbol("USDTETH", "ETH", true),
+            ]
+        );
+        assert!(usd_price_symbols(&chains, std::slice::from_ref(&sol), "USDC").is_empty());
+        assert_eq!(
+            usd_price_symbols(&chains, &[sol], "USDT"),
+            [price_symbol("SOLUSDT", "SOL", false)]
+        );
+    }
+
+    #[test]
+    fn test_chain_not_returning_to_start_is_refused() {
+        let chain_symbol = |symbol: &str, base_asset: &str, quote_asset: &str, order| {
+            ChainSymbol::new(
+                Symbol {
+                    symbol: symbol.to_owned(),
+                    base_asset: base_asset.to_owned(),
+                    quote_asset: quote_asset.to_owned(),
+                    ..Default::default()
+                },
+                order,
+            )
+        };
+        // BTC -> USDT -> ETH -> SOL never gets back to BTC.
+        let chain = [
+            chain_symbol("BTCUSDT", "BTC", "USDT", SymbolOrder::Asc),
+            chain_symbol("ETHUSDT", "ETH", "USDT", SymbolOrder::Desc),
+            chain_symbol("SOLETH", "SOL", "ETH", SymbolOrder::Desc),
+        ];
+        let order_book: Vec<_> = chain
+            .iter()
+            .map(|chain_symbol| BookTickerEvent {
+                symbol: chain_symbol.symbol.symbol.clone(),
+                ..Default::default()
+            })
+            .collect();
+
+        let builder = OrderBuilder::new(
+            Decimal::new(1, 1),
+            OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+            ProfitSchedule::new(vec![]),
+            true,
+            OrderType::Market,
+        );
+
+        // Refused up front: no base asset is configured, so building orders would fail.
+        let error = builder
+            .build_chain_orders(&chain, &order_book, &[], None)
+            .unwrap_err();
+        assert_eq!(
+            format!("{error:#}"),
+            "Chain [\"BTCUSDT\", \"ETHUSDT\", \"SOLETH\"] does not return to its starting \
+             asset: SOLETH returns SOL instead of the starting BTC"
+        );
+    }
+
+    /// Collects the log lines written while it is the writer of a test subscriber.
+    #[derive(Clone, Default)]
+    struct LogCapture(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for LogCapture {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_only_debug_chains_are_traced() {
+        let chain_symbol = |symbol: &str, base_asset: &str, quote_asset: &str, order| {
+            ChainSymbol::new(
+                Symbol {
+                    symbol: symbol.to_owned(),
+                    base_asset: base_asset.to_owned(),
+                    quote_asset: quote_asset.to_owned(),
+                    ..Default::default()
+                },
+                order,
+            )
+        };
+        let traced = [
+            chain_symbol("BTCUSDT", "BTC", "USDT", SymbolOrder::Asc),
+            chain_symbol("ETHUSDT", "ETH", "USDT", SymbolOrder::Desc),
+            chain_symbol("ETHBTC", "ETH", "BTC", SymbolOrder::Asc),
+        ];
+        let quiet = [
+            chain_symbol("BTCUSDT", "BTC", "USDT", SymbolOrder::Asc),
+            chain_symbol("SOLUSDT", "SOL", "USDT", SymbolOrder::Desc),
+            chain_symbol("SOLBTC", "SOL", "BTC", SymbolOrder::Asc),
+        ];
+        let base_assets = [Asset {
+            asset: "BTC".to_owned(),
+            symbol: None,
+            min_profit_qty: Decimal::new(1, 8),
+            min_profit_percent: None,
+            max_order_qty: Decimal::ONE,
+            min_ticker_qty_24h: Decimal::ZERO,
+            session_profit_target: None,
+            max_daily_loss: None,
+        }];
+
+        let builder = OrderBuilder::new(
+            Decimal::new(1, 1),
+            OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+            ProfitSchedule::new(vec![]),
+            true,
+            OrderType::Market,
+        )
+        .with_debug_chains(vec![
+            ["BTCUSDT", "ETHUSDT", "ETHBTC"].map(str::to_owned).to_vec(),
+        ]);
+        assert!(builder.is_traced(&traced));
+        assert!(!builder.is_traced(&quiet));
+
+        let logs = LogCapture::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer({
+                let logs = logs.clone();
+                move || logs.clone()
+            })
+            .with_ansi(false)
+            .finish();
+        tracing::subscriber::with_default(subscriber, || {
+            for chain in [&traced, &quiet] {
+                let mut storage = BookTickerStore::new();
+                let mut last_prices = vec![];
+                for chain_symbol in chain {
+                    let event = BookTickerEvent {
+                        update_id: 1,
+                        symbol: chain_symbol.symbol.symbol.clone(),
+                        bid_price: Decimal::ONE,
+                        bid_qty: Decimal::TEN,
+                        ask_price: Decimal::new(101, 2),
+                        ask_qty: Decimal::TEN,
+                        ..Default::default()
+                    };
+                    let trace = builder.is_traced(chain);
+                    if let Some((messages, received_at)) = OrderBuilder::chain_books(
+                        &mut storage,
+                        chain,
+                        event,
+                        &mut last_prices,
+                        builder.disabled_symbols(),
+                        trace,
+                    ) {
+                        let chain_orders = builder
+                            .build_chain_orders(chain, &messages, &base_assets, received_at)
+                            .unwrap();
+                        assert!(chain_orders.is_none());
+                    }
+                }
+            }
+        });
+
+        let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        for decision in [
+            "Waiting for the books of every leg",
+            "Chain prices updated",
+            "Chain cycle gate",
+            "Profit gate",
+            "No profitable pass, chain skipped",
+        ] {
+            assert!(logs.contains(decision), "{decision} not traced in:\n{logs}");
+        }
+        assert!(!logs.contains("SOLUSDT"), "untraced chain logged:\n{logs}");
+    }
+
+    #[test]
+    fn test_crossed_book_skips_chain_until_it_uncrosses() {
+        let chain_symbol = |symbol: &str, base_asset: &str, quote_asset: &str, order| {
+            ChainSymbol::new(
+                Symbol {
+                    symbol: symbol.to_owned(),
+                    base_asset: base_asset.to_owned(),
+                    quote_asset: quote_asset.to_owned(),
+                    ..Default::default()
+                },
+                order,
+            )
+        };
+        let chain = [
+            chain_symbol("BTCUSDT", "BTC", "USDT", SymbolOrder::Asc),
+            chain_symbol("ETHUSDT", "ETH", "USDT", SymbolOrder::Desc),
+            chain_symbol("ETHBTC", "ETH", "BTC", SymbolOrder::Asc),
+        ];
+        let event = |update_id, symbol: &str, bid_price, ask_price| BookTickerEvent {
+            update_id,
+            symbol: symbol.to_owned(),
+            bid_price: Decimal::from_str(bid_price).unwrap(),
+            bid_qty: Decimal::TEN,
+            ask_price: Decimal::from_str(ask_price).unwrap(),
+            ask_qty: Decimal::TEN,
+            ..Default::default()
+        };
+        let builder = OrderBuilder::new(
+            Decimal::new(1, 1),
+            OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+            ProfitSchedule::new(vec![]),
+            true,
+            OrderType::Market,
+        );
+
+        let mut storage = builder.book_store();
+        let mut last_prices = vec![];
+        let disabled_symbols = DisabledSymbols::default();
+        let mut feed = |event| {
+            OrderBuilder::chain_books(
+                &mut storage,
+                &chain,
+                event,
+                &mut last_prices,
+                &disabled_symbols,
+                false,
+            )
+        };
+        assert!(feed(event(1, "BTCUSDT", "109615.46", "109615.47")).is_none());
+        assert!(feed(event(1, "ETHUSDT", "2585.70", "2585.71")).is_none());
+        // ETHBTC bid above its ask.
+        assert!(feed(event(1, "ETHBTC", "0.02360", "0.02359")).is_none());
+        assert!(feed(event(2, "BTCUSDT", "109615.50", "109615.51")).is_none());
+        let (messages, _) = feed(event(2, "ETHBTC", "0.02358", "0.02359")).unwrap();
+        assert_eq!(messages[2].bid_price, Decimal::from_str("0.02358").unwrap());
+
+        // Allowed crossed books are evaluated.
+        let mut storage = builder.with_crossed_books_allowed(true).book_store();
+        let mut last_prices = vec![];
+        for event in [
+            event(1, "BTCUSDT", "109615.46", "109615.47"),
+            event(1, "ETHUSDT", "2585.70", "2585.71"),
+        ] {
+            OrderBuilder::chain_books(
+                &mut storage,
+                &chain,
+                event,
+                &mut last_prices,
+                &disabled_symbols,
+                false,
+            );
+        }
+        let crossed = event(1, "ETHBTC", "0.02360", "0.02359");
+        assert!(
+            OrderBuilder::chain_books(
+                &mut storage,
+                &chain,
+                crossed,
+                &mut last_prices,
+                &disabled_symbols,
+                false
+            )
+            .is_some()
+        );
+    }
+
+    #[test]
+    fn test_short_chain_is_refused() {
+        let chain_symbol = |symbol: &str, base_asset: &str, quote_asset: &str, order| {
+            ChainSymbol::new(
+                Symbol {
+                    symbol: symbol.to_owned(),
+                    base_asset: base_asset.to_owned(),
+                    quote_asset: quote_asset.to_owned(),
+                    ..Default::default()
+                },
+                order,
+            )
+        };
+        let chain = [
+            chain_symbol("BTCUSDT", "BTC", "USDT", SymbolOrder::Asc),
+            chain_symbol("BTCUSDT", "BTC", "USDT", SymbolOrder::Desc),
+        ];
+        let messages = chain.each_ref().map(|chain_symbol| BookTickerEvent {
+            update_id: 1,
+            symbol: chain_symbol.symbol.symbol.clone(),
+            bid_price: Decimal::ONE,
+            bid_qty: Decimal::TEN,
+            ask_price: Decimal::new(101, 2),
+            ask_qty: Decimal::TEN,
+            ..Default::default()
+        });
+        let builder = OrderBuilder::new(
+            Decimal::new(1, 1),
+            OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+            ProfitSchedule::new(vec![]),
+            true,
+            OrderType::Market,
+        );
+
+        let error = builder
+            .build_chain_orders(&chain, &messages, &[], None)
+            .unwrap_err();
+        assert_eq!(
+            format!("{error:#}"),
+            "Invalid chain [\"BTCUSDT\", \"BTCUSDT\"]: Chain has 2 legs, at least 3 are needed"
+        );
+
+        // A book missing for a leg is refused as well.
+        let chain = [
+            chain_symbol("BTCUSDT", "BTC", "USDT", SymbolOrder::Asc),
+            chain_symbol("ETHUSDT", "ETH", "USDT", SymbolOrder::Desc),
+            chain_symbol("ETHBTC", "ETH", "BTC", SymbolOrder::Asc),
+        ];
+        let error = builder
+            .build_chain_orders(&chain, &messages, &[], None)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Chain [\"BTCUSDT\", \"ETHUSDT\", \"ETHBTC\"] has 2 books for 3 legs"
+        );
+    }
+
+    #[test]
+    fn test_collect_symbol_filters_from_exchange_info() -> anyhow::Result<()> {
+        let filters = |lot_step: &str, tick_size: &str, market_step: &str, notional: &str| {
+            format!(
+                r#"[
+                    {{"filterType": "PRICE_FILTER", "minPrice": "0.01", "maxPrice": "1000000", "tickSize": "{tick_size}"}},
+                    {{"filterType": "LOT_SIZE", "minQty": "{lot_step}", "maxQty": "9000", "stepSize": "{lot_step}"}},
+                    {{"filterType": "MARKET_LOT_SIZE", "minQty": "0", "maxQty": "100", "stepSize": "{market_step}"}},
+                    {{"filterType": "NOTIONAL", "minNotional": "{notional}", "applyMinToMarket": true, "maxNotional": "9000000", "applyMaxToMarket": false, "avgPriceMins": 5}}
+                ]"#
+            )
+        };
+        let symbol = |name: &str, base: &str, quote: &str, filters: String| {
+            format!(
+                r#"{{
+                    "symbol": "{name}", "status": "TRADING", "baseAsset": "{base}",
+                    "baseAssetPrecision": 8, "quoteAsset": "{quote}", "quotePrecision": 8,
+                    "baseCommissionPrecision": 8, "quoteCommissionPrecision": 8,
+                    "orderTypes": ["LIMIT", "MARKET"], "icebergAllowed": true,
+                    "isSpotTradingAllowed": true, "isMarginTradingAllowed": false,
+                    "filters": {filters}
+                }}"#
+            )
+        };
+        let exchange_info: ExchangeInformation = serde_json::from_str(&format!(
+            r#"{{"timezone": "UTC", "serverTime": 1753314650438, "symbols": [{}, {}, {}]}}"#,
+            symbol(
+                "BTCUSDT",
+                "BTC",
+                "USDT",
+                filters("0.00001", "0.01", "0", "5")
+            ),
+            symbol(
+                "ETHBTC",
+                "ETH",
+                "BTC",
+                filters("0.0001", "0.00001", "0.001", "0.0001")
+            ),
+            symbol(
+                "ETHUSDT",
+                "ETH",
+                "USDT",
+                filters("0.0001", "0.01", "0", "5")
+            ),
+        ))?;
+
+        let [btc_usdt, eth_btc, eth_usdt] = <[Symbol; 3]>::try_from(exchange_info.symbols)
+            .map_err(|_| anyhow::anyhow!("expected 3 symbols"))?;
+        let chains = [vec![
+            ChainSymbol::new(btc_usdt, SymbolOrder::Desc),
+            ChainSymbol::new(eth_btc, SymbolOrder::Desc),
+            ChainSymbol::new(eth_usdt, SymbolOrder::Asc),
+        ]];
+
+        let filters = collect_symbol_filters(&chains);
+        assert_eq!(
+            filters.keys().copied().collect::<Vec<_>>(),
+            ["BTCUSDT", "ETHBTC", "ETHUSDT"]
+        );
+
+        let btc_usdt = &filters["BTCUSDT"];
+        assert_eq!(btc_usdt.lot_size_step, 5);
+        assert_eq!(btc_usdt.lot_size_min_qty, Decimal::new(1, 5));
+        assert_eq!(btc_usdt.tick_size, 2);
+        assert_eq!(btc_usdt.market_lot_size_step, None);
+        assert_eq!(btc_usdt.min_notional, Decimal::from(5));
+
+        let eth_btc = &filters["ETHBTC"];
+        assert_eq!(eth_btc.lot_size_step, 4);
+        assert_eq!(eth_btc.tick_size, 5);
+        assert_eq!(eth_btc.market_lot_size_step, Some(3));
+        assert_eq!(eth_btc.min_notional, Decimal::new(1, 4));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_disabled_symbol_pauses_its_chains_only() {
+        let chain_symbol = |symbol: &str, base_asset: &str, quote_asset: &str, order| {
+            ChainSymbol::new(
+                Symbol {
+                    symbol: symbol.to_owned(),
+                    base_asset: base_asset.to_owned(),
+                    quote_asset: quote_asset.to_owned(),
+                    ..Default::default()
+                },
+                order,
+            )
+        };
+        let eth_chain = [
+            chain_symbol("BTCUSDT", "BTC", "USDT", SymbolOrder::Asc),
+            chain_symbol("ETHUSDT", "ETH", "USDT", SymbolOrder::Desc),
+            chain_symbol("ETHBTC", "ETH", "BTC", SymbolOrder::Asc),
+        ];
+        let sol_chain = [
+            chain_symbol("BTCUSDT", "BTC", "USDT", SymbolOrder::Asc),
+            chain_symbol("SOLUSDT", "SOL", "USDT", SymbolOrder::Desc),
+            chain_symbol("SOLBTC", "SOL", "BTC", SymbolOrder::Asc),
+        ];
+        let event = |update_id, symbol: &str, bid_price, ask_price| BookTickerEvent {
+            update_id,
+            symbol: symbol.to_owned(),
+            bid_price: Decimal::from_str(bid_price).unwrap(),
+            bid_qty: Decimal::TEN,
+            ask_price: Decimal::from_str(ask_price).unwrap(),
+            ask_qty: Decimal::TEN,
+            ..Default::default()
+        };
+        let disabled_symbols = Arc::new(DisabledSymbols::default());
+        let builder = OrderBuilder::new(
+            Decimal::new(1, 1),
+            OrderBuilder::DEFAULT_MAX_DEPTH_ITERATIONS,
+            ProfitSchedule::new(vec![]),
+            true,
+            OrderType::Market,
+        )
+        .with_disabled_symbols(Arc::clone(&disabled_symbols));
+
+        let mut books = [
+            (&eth_chain, builder.book_store(), vec![]),
+            (&sol_chain, builder.book_store(), vec![]),
+        ];
+        let mut feed = |events: &[BookTickerEvent]| {
+            books
+                .iter_mut()
+                .map(|(chain, storage, last_prices)| {
+                    events
+                        .iter()
+                        .filter(|event| chain.iter().any(|s| s.symbol.symbol == event.symbol))
+                        .filter_map(|event| {
+                            OrderBuilder::chain_books(
+                                storage,
+                                chain.as_slice(),
+                                event.clone(),
+                                last_prices,
+                                builder.disabled_symbols(),
+                                false,
+                            )
+                        })
+                        .count()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert!(disabled_symbols.disable("ethbtc"));
+        let evaluated = feed(&[
+            event(1, "BTCUSDT", "109615.46", "109615.47"),
+            event(1, "ETHUSDT", "2585.70", "2585.71"),
+            event(1, "ETHBTC", "0.02358", "0.02359"),
+            event(1, "SOLUSDT", "180.10", "180.11"),
+            event(1, "SOLBTC", "0.00164", "0.00165"),
+        ]);
+        assert_eq!(evaluated, [0, 1]);
+        let evaluated = feed(&[event(2, "BTCUSDT", "109615.50", "109615.51")]);
+        assert_eq!(evaluated, [0, 1]);
+
+        // Enabled again, the chain is evaluated on its next update.
+        assert!(disabled_symbols.enable("ETHBTC"));
+        let evaluated = feed(&[event(2, "ETHUSDT", "2585.70", "2585.71")]);
+        assert_eq!(evaluated, [1, 0]);
+    }
 }
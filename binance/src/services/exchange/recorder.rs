@@ -0,0 +1,291 @@
+//! Ticker recording module.
+//!
+//! Records every book ticker event the streams receive to a JSON lines file, so real market
+//! conditions can be replayed by the backtest later. The streams only queue events on a
+//! bounded channel; a dedicated writer task does the IO, dropping events rather than slowing
+//! the streams down when it falls behind.
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tools::misc;
+use tracing::{info, warn};
+
+use crate::services::storage::BookTickerEvent;
+
+/// Book ticker event as recorded, one line of the recording. The event fields are inlined,
+/// so the line also replays as a plain [`BookTickerEvent`].
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecordedTicker {
+    /// Unix timestamp the event was received at, in milliseconds.
+    pub ts: u128,
+    #[serde(flatten)]
+    pub event: BookTickerEvent,
+}
+
+/// Recording settings, a recorder and writer pair is created from them on every start of
+/// the exchange service.
+#[derive(Debug, Clone)]
+pub struct TickerRecording {
+    path: PathBuf,
+    max_file_bytes: u64,
+}
+
+impl TickerRecording {
+    /// Events queued for the writer at most, the next ones are dropped.
+    const CHANNEL_CAPACITY: usize = 65_536;
+
+    /// Records to `path`, rotated once it reaches `max_file_bytes`.
+    #[must_use]
+    pub fn new(path: PathBuf, max_file_bytes: u64) -> Self {
+        Self {
+            path,
+            max_file_bytes,
+        }
+    }
+
+    /// Creates the recorder the streams queue events on and the writer draining it.
+    #[must_use]
+    pub fn channel(&self) -> (TickerRecorder, TickerRecordWriter) {
+        let (tx, rx) = mpsc::channel(Self::CHANNEL_CAPACITY);
+        let dropped = Arc::new(AtomicU64::new(0));
+        (
+            TickerRecorder {
+                tx,
+                dropped: dropped.clone(),
+            },
+            TickerRecordWriter {
+                rx,
+                dropped,
+                path: self.path.clone(),
+                max_file_bytes: self.max_file_bytes,
+            },
+        )
+    }
+}
+
+/// Queues received events for the writer, cheap to clone into every stream.
+#[derive(Clone)]
+pub struct TickerRecorder {
+    tx: mpsc::Sender<RecordedTicker>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl TickerRecorder {
+    /// Queues `event` without waiting, dropping it if the writer is behind or stopped.
+    pub fn record(&self, event: &BookTickerEvent) {
+        let ticker = RecordedTicker {
+            ts: misc::time::get_current_timestamp().as_millis(),
+            event: event.clone(),
+        };
+        if self.tx.try_send(ticker).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Appends the queued events to the recording file.
+pub struct TickerRecordWriter {
+    rx: mpsc::Receiver<RecordedTicker>,
+    dropped: Arc<AtomicU64>,
+    path: PathBuf,
+    max_file_bytes: u64,
+}
+
+impl TickerRecordWriter {
+    /// Writes the queued events until cancelled, then writes the ones still queued and
+    /// flushes. Every batch of events is flushed once written. Failed writes are logged and
+    /// the batch is dropped.
+    pub async fn run(mut self, token: CancellationToken) -> anyhow::Result<()> {
+        let mut file = RecordFile::open(&self.path)?;
+        info!(path = %self.path.display(), "⏺️ [Engine] Recording book tickers");
+
+        let mut batch = vec![];
+        loop {
+            tokio::select! {
+                () = token.cancelled() => break,
+                received = self.rx.recv() => match received {
+                    Some(ticker) => batch.push(ticker),
+                    None => break,
+                },
+            }
+            while let Ok(ticker) = self.rx.try_recv() {
+                batch.push(ticker);
+            }
+            self.write(&mut file, &mut batch);
+        }
+
+        self.rx.close();
+        while let Ok(ticker) = self.rx.try_recv() {
+            batch.push(ticker);
+        }
+        self.write(&mut file, &mut batch);
+
+        let dropped = self.dropped.load(Ordering::Relaxed);
+        if dropped > 0 {
+            warn!(
+                dropped,
+                "Book tickers dropped while the recording fell behind"
+            );
+        }
+        Ok(())
+    }
+
+    fn write(&self, file: &mut RecordFile, batch: &mut Vec<RecordedTicker>) {
+        if batch.is_empty() {
+            return;
+        }
+        if let Err(e) = file.write_batch(batch, &self.path, self.max_file_bytes) {
+            warn!(error = ?e, "Failed to record book tickers");
+        }
+        batch.clear();
+    }
+}
+
+/// Recording file being appended to, with the number of bytes it holds.
+struct RecordFile {
+    writer: BufWriter<File>,
+    len: u64,
+}
+
+impl RecordFile {
+    fn open(path: &Path) -> anyhow::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open ticker recording {}", path.display()))?;
+        let len = file.metadata()?.len();
+        Ok(Self {
+            writer: BufWriter::new(file),
+            len,
+        })
+    }
+
+    /// Appends `batch`, moving the file aside to `<path>.<n>` first whenever it has reached
+    /// `max_file_bytes`, `n` being the lowest index not taken by an earlier rotation.
+    fn write_batch(
+        &mut self,
+        batch: &[RecordedTicker],
+        path: &Path,
+        max_file_bytes: u64,
+    ) -> anyhow::Result<()> {
+        for ticker in batch {
+            if self.len >= max_file_bytes {
+                self.rotate(path)?;
+            }
+            let mut line = serde_json::to_vec(ticker)?;
+            line.push(b'\n');
+            self.writer.write_all(&line)?;
+            self.len += line.len() as u64;
+        }
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    fn rotate(&mut self, path: &Path) -> anyhow::Result<()> {
+        self.writer.flush()?;
+        let rotated = (1..)
+            .map(|n| {
+                let mut rotated = path.as_os_str().to_owned();
+                rotated.push(format!(".{n}"));
+                PathBuf::from(rotated)
+            })
+            .find(|rotated| !rotated.exists())
+            .context("No free rotation index")?;
+        std::fs::rename(path, &rotated)
+            .with_context(|| format!("Failed to rotate ticker recording {}", path.display()))?;
+        *self = Self::open(path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rust_decimal::Decimal;
+
+    use super::*;
+
+    fn event(update_id: u64, symbol: &str) -> BookTickerEvent {
+        BookTickerEvent {
+            update_id,
+            symbol: symbol.to_owned(),
+            bid_price: Decimal::new(10_961_546, 2),
+            bid_qty: Decimal::ONE,
+            ask_price: Decimal::new(10_961_547, 2),
+            ask_qty: Decimal::TWO,
+            ..Default::default()
+        }
+    }
+
+    fn read_tickers(path: &Path) -> anyhow::Result<Vec<RecordedTicker>> {
+        std::fs::read_to_string(path)?
+            .lines()
+            .map(|line| serde_json::from_str(line).map_err(Into::into))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_recorded_tickers_read_back_and_rotate() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("ticker-recording-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir(&dir)?;
+        let path = dir.join("tickers.jsonl");
+
+        // A file is rotated once it holds more than a line.
+        let (recorder, writer) = TickerRecording::new(path.clone(), 100).channel();
+        let started = misc::time::get_current_timestamp().as_millis();
+        for (update_id, symbol) in [(1, "BTCUSDT"), (2, "ETHUSDT"), (3, "ETHBTC")] {
+            recorder.record(&event(update_id, symbol));
+        }
+
+        let token = CancellationToken::new();
+        token.cancel();
+        tokio::time::timeout(Duration::from_secs(5), writer.run(token)).await??;
+        // Stopped writers drop what is still recorded.
+        recorder.record(&event(4, "BTCUSDT"));
+        assert_eq!(recorder.dropped.load(Ordering::Relaxed), 1);
+
+        let mut files: Vec<_> = std::fs::read_dir(&dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<_, _>>()?;
+        files.sort();
+        let mut tickers = vec![];
+        for file in &files {
+            tickers.extend(read_tickers(file)?);
+        }
+        std::fs::remove_dir_all(&dir)?;
+
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[0], path);
+        assert_eq!(files[1], dir.join("tickers.jsonl.1"));
+        let recorded: Vec<_> = tickers
+            .iter()
+            .map(|ticker| (ticker.event.update_id, ticker.event.symbol.as_str()))
+            .collect();
+        // The live file holds the latest event, the rotated ones the earlier ones in order.
+        assert_eq!(recorded, [(3, "ETHBTC"), (1, "BTCUSDT"), (2, "ETHUSDT")]);
+        assert!(tickers.iter().all(|ticker| ticker.ts >= started));
+        assert_eq!(tickers[1].event.ask_qty, Decimal::TWO);
+
+        // Recorded lines replay as plain book ticker events.
+        let line = serde_json::to_string(&tickers[1])?;
+        let replayed: BookTickerEvent = serde_json::from_str(&line)?;
+        assert_eq!(replayed.symbol, "BTCUSDT");
+        assert_eq!(replayed.bid_price, Decimal::new(10_961_546, 2));
+
+        Ok(())
+    }
+}
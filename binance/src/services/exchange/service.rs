@@ -1,22 +1,34 @@
 //! Binance exchange service module for arbitrage operations.
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use anyhow::Context;
 use async_trait::async_trait;
-use engine::{Exchange, REQUEST_WEIGHT, service::traits::ArbitrageService};
-use tokio::task::JoinSet;
+use engine::{
+    BalanceCache, BalanceCap, Balances, ComputePool, DISABLED_SYMBOLS, Exchange, MonitoredSymbol,
+    REQUEST_WEIGHT, USD_PROFIT_FLOOR, enums::OrderType, publish_chains,
+    service::traits::ArbitrageService,
+};
+use rust_decimal::Decimal;
+use tokio::{sync::oneshot, task::JoinSet};
 use tokio_util::sync::CancellationToken;
-use tracing::error;
+use tracing::{error, info, warn};
 
 use crate::{
     config::Config,
     libs::{
         binance_client,
-        binance_client::{Binance, General, Market},
+        binance_client::{Account, Binance, General, Market},
     },
     services::exchange::{
-        asset::AssetBuilder, chain::ChainBuilder, order::OrderBuilder, ticker::TickerBuilder,
+        asset::AssetBuilder,
+        chain::ChainBuilder,
+        dust::DustConverter,
+        info::ExchangeInfoRefresher,
+        order::{OrderBuilder, log_symbol_filters},
+        recorder::TickerRecording,
+        snapshot::BookSnapshotter,
+        ticker::{PriceSource, TickerBuilder},
     },
 };
 
@@ -26,14 +38,41 @@ pub struct ExchangeService {
     ticker_builder: TickerBuilder,
     chain_builder: Arc<ChainBuilder>,
     order_builder: Arc<OrderBuilder>,
+    exchange_info_refresher: Arc<ExchangeInfoRefresher>,
+    dust_converter: Arc<DustConverter>,
+    book_snapshotter: Arc<BookSnapshotter>,
+    ticker_recording: Option<TickerRecording>,
+    log_symbol_filters: bool,
+    account_api: Account,
+    balance_cache: BalanceCache,
+    balance_cap: Arc<BalanceCap>,
+    balance_refresh_interval: Option<Duration>,
+    /// Check the API key against the testnet before building chains.
+    testnet: bool,
 }
 
-impl Exchange for ExchangeService {}
+#[async_trait]
+impl Exchange for ExchangeService {
+    async fn balances(&self) -> anyhow::Result<Balances> {
+        self.balance_cache
+            .get_or_refresh(|| fetch_balances(&self.account_api))
+            .await
+    }
+}
 
 #[async_trait]
 impl ArbitrageService for ExchangeService {
     /// Starts the arbitrage process.
     async fn start(&self, token: CancellationToken) -> anyhow::Result<()> {
+        // Testnet keys are issued separately, so a mainnet key fails here rather than on
+        // the first order.
+        if self.testnet {
+            fetch_balances(&self.account_api)
+                .await
+                .context("API key rejected by the testnet")?;
+            info!("🧪 [Engine] Connected to the Binance spot testnet");
+        }
+
         // Get and update base assets limits.
         let base_assets = self
             .asset_builder
@@ -49,28 +88,104 @@ impl ArbitrageService for ExchangeService {
             .await
             .context("Failed to build symbols chains")?;
 
+        if self.log_symbol_filters {
+            log_symbol_filters(&chains);
+        }
+
         let mut tasks_set = JoinSet::new();
 
-        // Get order books per chain and calculate profit.
-        tasks_set.spawn({
-            let order_builder = self.order_builder.clone();
-            let token = token.clone();
-            let chains = chains.clone();
-            async move {
-                order_builder
-                    .build_chains_orders(token, chains, base_assets)
+        // Cap order sizes at the free balances before any chain is built, then keep them fresh.
+        if let Some(interval) = self.balance_refresh_interval {
+            self.balance_cap.set(
+                fetch_balances(&self.account_api)
                     .await
-            }
+                    .context("Failed to fetch balances")?,
+            );
+            tasks_set.spawn({
+                let balance_cap = self.balance_cap.clone();
+                let account_api = self.account_api.clone();
+                let token = token.clone();
+                async move {
+                    balance_cap
+                        .refresh_every(token, interval, || fetch_balances(&account_api))
+                        .await
+                }
+            });
+        }
+
+        // Refresh exchange info once it gets older than the configured age.
+        if self.exchange_info_refresher.is_enabled() {
+            tasks_set.spawn({
+                let refresher = self.exchange_info_refresher.clone();
+                let token = token.clone();
+                async move { refresher.run(token).await }
+            });
+        }
+
+        // Convert dust periodically and on shutdown.
+        if self.dust_converter.is_enabled() {
+            tasks_set.spawn({
+                let converter = self.dust_converter.clone();
+                let token = token.clone();
+                async move { converter.run(token).await }
+            });
+        }
+
+        // Snapshot the books of the configured symbols.
+        if self.book_snapshotter.is_enabled() {
+            tasks_set.spawn({
+                let snapshotter = self.book_snapshotter.clone();
+                let token = token.clone();
+                async move { snapshotter.run(token).await }
+            });
+        }
+
+        // Record the received tickers for replay, flushed once the streams stop.
+        let recorder = self.ticker_recording.as_ref().map(|recording| {
+            let (recorder, writer) = recording.channel();
+            tasks_set.spawn({
+                let token = token.clone();
+                async move { writer.run(token).await }
+            });
+            recorder
         });
 
         // Get and update tickers order books.
+        let (ready_tx, ready_rx) = oneshot::channel();
         tasks_set.spawn({
-            let ticker_builder = self.ticker_builder.clone();
+            let ticker_builder = self.ticker_builder.clone().with_recorder(recorder);
             let token = token.clone();
-            let chains = chains.clone();
-            async move { ticker_builder.build_order_books(token, chains).await }
+            async move {
+                ticker_builder
+                    .build_order_books(token, chains, ready_tx)
+                    .await
+            }
         });
 
+        // Only the chains whose streams started are evaluated, none if startup failed.
+        if let Ok(chains) = ready_rx.await {
+            // Expose the chains on the /chains endpoint.
+            publish_chains(
+                chains
+                    .iter()
+                    .map(|chain| chain.iter().map(MonitoredSymbol::from).collect())
+                    .collect(),
+            )
+            .await;
+
+            // Get order books per chain and calculate profit.
+            tasks_set.spawn({
+                let order_builder = self.order_builder.clone();
+                let token = token.clone();
+                let symbols_rx = self.exchange_info_refresher.subscribe();
+                async move {
+                    order_builder
+                        .build_chains_orders(token, chains, base_assets, symbols_rx)
+                        .await
+                }
+            });
+        }
+
         // Wait for tasks, cancel on first error
         while let Some(result) = tasks_set.join_next().await {
             match result {
@@ -113,12 +228,52 @@ impl ExchangeService {
         let general_api: General =
             Binance::new(api_config.clone()).context("Failed to init general binance client")?;
         let market_api: Market =
-            Binance::new(api_config).context("Failed to init market binance client")?;
+            Binance::new(api_config.clone()).context("Failed to init market binance client")?;
+        let account_api: Account =
+            Binance::new(api_config).context("Failed to init account binance client")?;
 
         // Configure global request weight limit for API rate limiting.
         {
             let mut weight_lock = REQUEST_WEIGHT.lock().await;
             weight_lock.set_weight_limit(config.api_weight_limit);
+            weight_lock.set_backoff_percent(config.weight_backoff_percent);
+            weight_lock
+                .set_rate_limit_cooldown(config.rate_limit_cooldown_secs.map(Duration::from_secs));
+        }
+
+        if config.send_orders && config.order_type == OrderType::Market {
+            warn!(
+                "⚠️ [Engine] Legs are placed as market orders, realized profit may differ from \
+                 the top-of-book estimate"
+            );
+        }
+
+        let balance_cap = Arc::new(BalanceCap::default());
+
+        let compute_pool = config
+            .compute_threads
+            .map(ComputePool::new)
+            .transpose()?
+            .map(Arc::new);
+
+        let mut ticker_builder = TickerBuilder::new(
+            config.ws_streams_url.clone(),
+            config.ws_max_connections,
+            config.fail_on_missing_subscriptions,
+            config.reconnect_strategy,
+        );
+        if let Some(limit) = config.max_concurrent_reconnects {
+            ticker_builder = ticker_builder.with_max_concurrent_reconnects(limit);
+        }
+        if let Some(levels) = config.market_depth_levels {
+            ticker_builder = ticker_builder.with_market_depth(levels);
+        }
+        ticker_builder = ticker_builder.with_max_failed_streams(config.max_failed_streams);
+        if config.price_source == PriceSource::Rest {
+            ticker_builder = ticker_builder.with_rest_polling(
+                market_api.clone(),
+                Duration::from_millis(config.rest_poll_interval_ms),
+            );
         }
 
         Ok(Self {
@@ -129,16 +284,320 @@ impl ExchangeService {
                 config.max_order_qty,
                 config.min_ticker_qty_24h,
             ),
-            ticker_builder: TickerBuilder::new(
-                config.ws_streams_url.clone(),
-                config.ws_max_connections,
+            ticker_builder,
+            chain_builder: Arc::new(
+                ChainBuilder::new(
+                    general_api.clone(),
+                    market_api,
+                    config.skip_assets.clone(),
+                    config.order_type.into(),
+                    config.unsupported_order_type,
+                )
+                .with_chain_legs(config.chain_legs),
             ),
-            chain_builder: Arc::new(ChainBuilder::new(
+            order_builder: Arc::new(
+                OrderBuilder::from_config(config)
+                    .with_disabled_symbols(Arc::clone(&DISABLED_SYMBOLS))
+                    .with_usd_profit_floor(USD_PROFIT_FLOOR.get())
+                    .with_compute_pool(compute_pool)
+                    .with_balance_cap(
+                        config
+                            .balance_refresh_interval_secs
+                            .map(|_| balance_cap.clone()),
+                    ),
+            ),
+            exchange_info_refresher: Arc::new(ExchangeInfoRefresher::new(
                 general_api,
+                config.exchange_info_max_age_secs.map(Duration::from_secs),
+            )),
+            dust_converter: Arc::new(DustConverter::new(
+                account_api.clone(),
+                config.convert_dust,
+                config
+                    .dust_conversion_interval_secs
+                    .map(Duration::from_secs),
+                config.dust_threshold_btc,
+                config
+                    .assets
+                    .iter()
+                    .map(|asset| asset.asset.clone())
+                    .collect(),
+            )),
+            book_snapshotter: Arc::new(BookSnapshotter::new(
+                config.book_snapshot_symbols.clone(),
+                Duration::from_secs(config.book_snapshot_interval_secs),
+                config.book_snapshot_path.clone(),
+            )),
+            ticker_recording: config
+                .record_path
+                .clone()
+                .map(|path| TickerRecording::new(path, config.record_max_file_mb * 1024 * 1024)),
+            log_symbol_filters: config.log_symbol_filters,
+            account_api,
+            balance_cache: BalanceCache::default(),
+            balance_cap,
+            balance_refresh_interval: config
+                .balance_refresh_interval_secs
+                .map(Duration::from_secs),
+            testnet: config.testnet,
+        })
+    }
+}
+
+/// Fetches the free balance of every non-empty asset on the spot account.
+async fn fetch_balances(account_api: &Account) -> anyhow::Result<Balances> {
+    let account = account_api
+        .get_account(true, 5000)
+        .await
+        .context("Failed to get account information")?;
+
+    account
+        .balances
+        .into_iter()
+        .map(|balance| {
+            let free = balance
+                .free
+                .parse::<Decimal>()
+                .with_context(|| format!("Invalid {} balance: {}", balance.asset, balance.free))?;
+            Ok((balance.asset, free))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use engine::{ProfitSchedule, enums::ReconnectStrategy};
+    use futures_util::SinkExt;
+    use mockito::{Matcher, Server};
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::{accept_async, tungstenite::Message};
+
+    use super::*;
+    use crate::{config::Asset, services::exchange::chain::UnsupportedOrderType};
+
+    fn exchange_info_body() -> String {
+        let symbol = |symbol: &str, base: &str, quote: &str| {
+            format!(
+                r#"{{
+                    "symbol": "{symbol}",
+                    "status": "TRADING",
+                    "baseAsset": "{base}",
+                    "baseAssetPrecision": 8,
+                    "quoteAsset": "{quote}",
+                    "quotePrecision": 8,
+                    "baseCommissionPrecision": 8,
+                    "quoteCommissionPrecision": 8,
+                    "orderTypes": ["LIMIT", "MARKET"],
+                    "icebergAllowed": true,
+                    "isSpotTradingAllowed": true,
+                    "isMarginTradingAllowed": false,
+                    "filters": []
+                }}"#
+            )
+        };
+        format!(
+            r#"{{"timezone": "UTC", "serverTime": 1753314650438, "symbols": [{}, {}, {}]}}"#,
+            symbol("BTCUSDT", "BTC", "USDT"),
+            symbol("ETHBTC", "ETH", "BTC"),
+            symbol("ETHUSDT", "ETH", "USDT"),
+        )
+    }
+
+    const TICKER_24H: &str = r#"[
+        {"symbol": "BTCUSDT", "lastPrice": "100000", "volume": "1000", "quoteVolume": "100000000"},
+        {"symbol": "ETHBTC", "lastPrice": "0.03", "volume": "1000", "quoteVolume": "30"},
+        {"symbol": "ETHUSDT", "lastPrice": "3000", "volume": "1000", "quoteVolume": "3000000"}
+    ]"#;
+
+    /// Serves websocket connections that are closed right after the handshake.
+    async fn spawn_closing_ws_server() -> anyhow::Result<String> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("ws://{}", listener.local_addr()?);
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                if let Ok(mut ws) = accept_async(stream).await {
+                    _ = ws.send(Message::Close(None)).await;
+                }
+            }
+        });
+        Ok(url)
+    }
+
+    fn exchange_service(
+        api_url: String,
+        ws_url: String,
+        reconnect_strategy: ReconnectStrategy,
+    ) -> anyhow::Result<ExchangeService> {
+        let api_config = binance_client::ClientConfig {
+            api_url,
+            api_token: "test_api_key".to_owned(),
+            api_secret_key: "test_secret_key".to_owned(),
+            http_config: binance_client::HttpConfig::default(),
+        };
+        let general_api: General = Binance::new(api_config.clone())?;
+        let market_api: Market = Binance::new(api_config.clone())?;
+        let account_api: Account = Binance::new(api_config)?;
+
+        let assets = vec![Asset {
+            asset: "USDT".to_owned(),
+            symbol: None,
+            min_profit_qty: Decimal::ONE,
+            min_profit_percent: None,
+            max_order_qty: Decimal::from(100),
+            min_ticker_qty_24h: Decimal::ONE,
+            session_profit_target: None,
+            max_daily_loss: None,
+        }];
+
+        Ok(ExchangeService {
+            asset_builder: AssetBuilder::new(
+                market_api.clone(),
+                assets,
+                Decimal::ONE,
+                Decimal::from(100),
+                Decimal::ONE,
+            ),
+            ticker_builder: TickerBuilder::new(ws_url, 1, false, reconnect_strategy),
+            chain_builder: Arc::new(ChainBuilder::new(
+                general_api.clone(),
                 market_api,
-                config.skip_assets.clone(),
+                vec![],
+                OrderType::Market.into(),
+                UnsupportedOrderType::default(),
             )),
-            order_builder: Arc::new(OrderBuilder::new(config.fee_percent)),
+            order_builder: Arc::new(OrderBuilder::new(
+                Decimal::new(1, 1),
+                1,
+                ProfitSchedule::new(vec![]),
+                true,
+                OrderType::Market,
+            )),
+            exchange_info_refresher: Arc::new(ExchangeInfoRefresher::new(general_api, None)),
+            dust_converter: Arc::new(DustConverter::new(
+                account_api.clone(),
+                false,
+                None,
+                Decimal::new(1, 3),
+                vec![],
+            )),
+            book_snapshotter: Arc::new(BookSnapshotter::new(
+                vec![],
+                Duration::from_secs(60),
+                PathBuf::new(),
+            )),
+            ticker_recording: None,
+            log_symbol_filters: false,
+            account_api,
+            balance_cache: BalanceCache::default(),
+            balance_cap: Arc::new(BalanceCap::default()),
+            balance_refresh_interval: None,
+            testnet: false,
         })
     }
+
+    #[tokio::test]
+    async fn test_rebuild_refetches_exchange_info() -> anyhow::Result<()> {
+        let mut server = Server::new_async().await;
+        let exchange_info = server
+            .mock("GET", "/api/v3/exchangeInfo")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(exchange_info_body())
+            .expect(2)
+            .create_async()
+            .await;
+        let _ticker = server
+            .mock("GET", "/api/v3/ticker/24hr")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(TICKER_24H)
+            .create_async()
+            .await;
+        let ws_url = spawn_closing_ws_server().await?;
+
+        // Every dropped connection ends the service, so each restart by the process rebuilds
+        // the chains from freshly fetched exchange info.
+        let service = exchange_service(server.url(), ws_url.clone(), ReconnectStrategy::Rebuild)?;
+        for _ in 0..2 {
+            tokio::time::timeout(
+                Duration::from_secs(5),
+                service.start(CancellationToken::new()),
+            )
+            .await??;
+        }
+        exchange_info.assert_async().await;
+        exchange_info.remove_async().await;
+
+        // Resubscribing keeps the service running on the chains it has built.
+        let exchange_info = server
+            .mock("GET", "/api/v3/exchangeInfo")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(exchange_info_body())
+            .expect(1)
+            .create_async()
+            .await;
+        let service = exchange_service(server.url(), ws_url, ReconnectStrategy::Resubscribe)?;
+        let token = CancellationToken::new();
+        let result =
+            tokio::time::timeout(Duration::from_secs(2), service.start(token.clone())).await;
+        assert!(result.is_err(), "service stopped on disconnect");
+        exchange_info.assert_async().await;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_balances_parses_account() -> anyhow::Result<()> {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock(
+                "GET",
+                Matcher::Regex(r"^/api/v3/account\?.*omitZeroBalances=true.*signature=".to_owned()),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "makerCommission": 15,
+                    "takerCommission": 15,
+                    "buyerCommission": 0,
+                    "sellerCommission": 0,
+                    "commissionRates": {
+                        "maker": "0.00150000",
+                        "taker": "0.00150000",
+                        "buyer": "0.00000000",
+                        "seller": "0.00000000"
+                    },
+                    "canTrade": true,
+                    "canWithdraw": true,
+                    "canDeposit": true,
+                    "balances": [
+                        {"asset": "BTC", "free": "0.01250000", "locked": "0.00100000"},
+                        {"asset": "USDT", "free": "1520.75000000", "locked": "0.00000000"}
+                    ]
+                }"#,
+            )
+            .create_async()
+            .await;
+
+        let account_api: Account = Binance::new(binance_client::ClientConfig {
+            api_url: server.url(),
+            api_token: "test_api_key".to_owned(),
+            api_secret_key: "test_secret_key".to_owned(),
+            http_config: binance_client::HttpConfig::default(),
+        })?;
+        let balances = fetch_balances(&account_api).await?;
+
+        mock.assert_async().await;
+        assert_eq!(balances.len(), 2);
+        assert_eq!(balances["BTC"], Decimal::new(125, 4));
+        assert_eq!(balances["USDT"], Decimal::new(152_075, 2));
+        Ok(())
+    }
 }
@@ -0,0 +1,177 @@
+//! Book snapshot module.
+//!
+//! Reproducing a reported chain needs the book state it was calculated from. Instead of
+//! recording every tick, the snapshotter periodically appends the latest book of a configured
+//! subset of symbols to a JSON lines file.
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use tokio::time::MissedTickBehavior;
+use tokio_util::sync::CancellationToken;
+use tools::misc;
+use tracing::warn;
+
+use crate::services::{broadcast::TICKER_BROADCAST, storage::BookTickerEvent};
+
+/// Books of the snapshot symbols at a point in time, one line of the snapshot file.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BookSnapshot {
+    /// Unix timestamp of the snapshot in milliseconds.
+    pub ts: u128,
+    /// Latest book of every symbol that has received one.
+    pub books: Vec<BookTickerEvent>,
+}
+
+/// Periodically writes book snapshots of the configured symbols.
+pub struct BookSnapshotter {
+    symbols: Vec<String>,
+    interval: Duration,
+    path: PathBuf,
+}
+
+impl BookSnapshotter {
+    /// Creates a snapshotter of `symbols` appending to `path` every `interval`.
+    #[must_use]
+    pub fn new(symbols: Vec<String>, interval: Duration, path: PathBuf) -> Self {
+        Self {
+            symbols,
+            interval,
+            path,
+        }
+    }
+
+    /// Snapshots are only taken with at least one symbol configured.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        !self.symbols.is_empty()
+    }
+
+    /// Appends a snapshot every interval until cancelled. Failed writes are logged and the
+    /// snapshot is dropped.
+    pub async fn run(&self, token: CancellationToken) -> anyhow::Result<()> {
+        let mut writer = BufWriter::new(Self::open(&self.path)?);
+        let mut interval = tokio::time::interval(self.interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        interval.tick().await;
+
+        loop {
+            tokio::select! {
+                () = token.cancelled() => break,
+                _ = interval.tick() => {
+                    if let Err(e) = self.write_snapshot(&mut writer) {
+                        warn!(error = ?e, "Failed to write book snapshot");
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Takes the latest book of every configured symbol.
+    #[must_use]
+    pub fn snapshot(&self) -> BookSnapshot {
+        BookSnapshot {
+            ts: misc::time::get_current_timestamp().as_millis(),
+            books: self
+                .symbols
+                .iter()
+                .map(|symbol| TICKER_BROADCAST.subscribe(symbol).borrow().clone())
+                .filter(|book| !book.symbol.is_empty())
+                .collect(),
+        }
+    }
+
+    fn write_snapshot(&self, writer: &mut BufWriter<File>) -> anyhow::Result<()> {
+        serde_json::to_writer(&mut *writer, &self.snapshot())?;
+        writer.write_all(b"\n")?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn open(path: &Path) -> anyhow::Result<File> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open book snapshots {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_snapshots_written_every_interval() -> anyhow::Result<()> {
+        let interval = Duration::from_millis(50);
+        let path =
+            std::env::temp_dir().join(format!("book-snapshots-{}.jsonl", uuid::Uuid::new_v4()));
+        let snapshotter = BookSnapshotter::new(
+            vec!["SNAPBTCUSDT".to_owned(), "SNAPETHUSDT".to_owned()],
+            interval,
+            path.clone(),
+        );
+        // Keep a receiver alive so the broadcast is not rejected.
+        let _rx = TICKER_BROADCAST.subscribe("SNAPBTCUSDT");
+        TICKER_BROADCAST
+            .broadcast_event(BookTickerEvent {
+                update_id: 7,
+                symbol: "SNAPBTCUSDT".to_owned(),
+                bid_price: Decimal::from(109_615),
+                bid_qty: Decimal::ONE,
+                ask_price: Decimal::from(109_616),
+                ask_qty: Decimal::TWO,
+                ..Default::default()
+            })
+            .map_err(anyhow::Error::msg)?;
+
+        let read_snapshots = || -> anyhow::Result<Vec<BookSnapshot>> {
+            std::fs::read_to_string(&path)?
+                .lines()
+                .map(|line| serde_json::from_str(line).map_err(Into::into))
+                .collect()
+        };
+        let started = misc::time::get_current_timestamp().as_millis();
+        let token = CancellationToken::new();
+        let written = async {
+            loop {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                let snapshots = read_snapshots()?;
+                if snapshots.len() >= 3 {
+                    return anyhow::Ok(snapshots);
+                }
+            }
+        };
+        let result = tokio::select! {
+            result = snapshotter.run(token.clone()) => anyhow::bail!("Snapshotter stopped: {result:?}"),
+            result = tokio::time::timeout(Duration::from_secs(5), written) => result,
+        };
+        token.cancel();
+        std::fs::remove_file(&path)?;
+        let snapshots = result??;
+
+        // No snapshot is taken at startup and the n-th one is due n intervals after it.
+        for (n, snapshot) in (1..).zip(&snapshots) {
+            assert!(snapshot.ts - started >= n * interval.as_millis());
+        }
+        // SNAPETHUSDT has not received a book yet.
+        for snapshot in &snapshots {
+            assert_eq!(snapshot.books.len(), 1);
+            assert_eq!(snapshot.books[0].symbol, "SNAPBTCUSDT");
+            assert_eq!(snapshot.books[0].update_id, 7);
+            assert_eq!(snapshot.books[0].ask_qty, Decimal::TWO);
+        }
+
+        Ok(())
+    }
+}
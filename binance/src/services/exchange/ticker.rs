@@ -1,70 +1,243 @@
 //! Ticker builder module for WebSocket stream management in arbitrage chains.
 //!
-//! This module provides a `TickerBuilder` for collecting unique symbols from triangular chains,
+//! This module provides a `TickerBuilder` for collecting unique symbols from arbitrage chains,
 //! creating book ticker streams, chunking them across multiple WebSocket connections (to respect
 //! limits), and spawning concurrent tasks to listen for real-time bid/ask updates. Events are
-//! broadcast via a channel.
+//! broadcast via a channel. With a market depth configured, partial depth streams replace the
+//! book tickers so that several levels of every book are broadcast. A dropped connection is either
+//! resubscribed in place or ends the streams, so the exchange service is rebuilt, depending on the
+//! reconnect strategy. Where websockets are blocked, book tickers can be polled over REST instead.
+//! Up to a configured number of streams may fail to connect at startup, the chains depending on
+//! them are then dropped.
 
-use std::collections::HashSet;
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
 
-use anyhow::Context;
-use engine::METRICS;
-use tokio::task::JoinSet;
+use anyhow::{Context, bail};
+use engine::{METRICS, REQUEST_WEIGHT, RUN_STATS, TICKS, enums::ReconnectStrategy};
+use serde::Deserialize;
+use tokio::{
+    sync::{Semaphore, oneshot},
+    task::JoinSet,
+    time::{Instant, MissedTickBehavior},
+};
 use tokio_util::sync::CancellationToken;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 use crate::{
-    libs::binance_client::stream::{Events, StreamEvent, WebsocketStream, book_ticker_stream},
+    libs::binance_client::{
+        Market, OrderBookUnit,
+        stream::{
+            Events, OrderBook, StreamEvent, WebsocketStream, book_ticker_stream,
+            partial_book_depth_stream,
+        },
+    },
     services::{
-        broadcast::TICKER_BROADCAST, exchange::chain::ChainSymbol, storage::BookTickerEvent,
+        broadcast::TICKER_BROADCAST,
+        exchange::{chain, chain::ChainSymbol, recorder::TickerRecorder},
+        storage::{BookLevel, BookTickerEvent},
     },
 };
 
+/// Source of book ticker prices.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PriceSource {
+    /// Book ticker websocket streams.
+    #[default]
+    Ws,
+    /// Polling the book ticker REST endpoint, with higher latency.
+    Rest,
+}
+
 /// Builder for managing book ticker WebSocket streams across symbol chains.
 #[derive(Clone)]
 pub struct TickerBuilder {
     ws_streams_url: String,
     ws_max_connections: usize,
+    fail_on_missing_subscriptions: bool,
+    reconnect_strategy: ReconnectStrategy,
+    rest_polling: Option<RestPolling>,
+    market_depth_levels: Option<u16>,
+    /// Bounds the connections being established at once, shared by all chunks.
+    connect_limiter: Option<Arc<Semaphore>>,
+    /// Records every received event for later replay.
+    recorder: Option<TickerRecorder>,
+    /// Streams allowed to fail their first connection, see [`Self::with_max_failed_streams`].
+    max_failed_streams: Option<usize>,
+}
+
+/// Polls book tickers over REST in place of the websocket streams.
+#[derive(Clone)]
+struct RestPolling {
+    market_api: Market,
+    interval: Duration,
 }
 
 impl TickerBuilder {
+    /// Delay before a dropped connection is resubscribed.
+    const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(1);
+    /// Update speed of the partial depth streams in milliseconds.
+    const DEPTH_UPDATE_SPEED_MS: u16 = 100;
+
     #[must_use]
-    pub fn new(ws_streams_url: String, ws_max_connections: usize) -> Self {
+    pub fn new(
+        ws_streams_url: String,
+        ws_max_connections: usize,
+        fail_on_missing_subscriptions: bool,
+        reconnect_strategy: ReconnectStrategy,
+    ) -> Self {
         Self {
             ws_streams_url,
             ws_max_connections,
+            fail_on_missing_subscriptions,
+            reconnect_strategy,
+            rest_polling: None,
+            market_depth_levels: None,
+            connect_limiter: None,
+            recorder: None,
+            max_failed_streams: None,
         }
     }
 
-    /// Builds and starts book ticker streams for the given chains.
+    /// Drops the streams whose first connection fails, with the chains depending on them,
+    /// instead of retrying them. Startup fails once more than `max_failed_streams` streams do.
+    #[must_use]
+    pub fn with_max_failed_streams(mut self, max_failed_streams: Option<usize>) -> Self {
+        self.max_failed_streams = max_failed_streams;
+        self
+    }
+
+    /// Establishes at most `limit` stream connections at once, the others wait for their turn.
+    /// Keeps a mass disconnect from reconnecting every chunk at the same time.
+    #[must_use]
+    pub fn with_max_concurrent_reconnects(mut self, limit: usize) -> Self {
+        self.connect_limiter = Some(Arc::new(Semaphore::new(limit)));
+        self
+    }
+
+    /// Streams `levels` levels of every book from partial depth streams instead of only the
+    /// best bid/ask. REST polling still yields the top of book only.
+    #[must_use]
+    pub fn with_market_depth(mut self, levels: u16) -> Self {
+        self.market_depth_levels = Some(levels);
+        self
+    }
+
+    /// Polls book tickers over REST every `interval` instead of streaming them.
+    #[must_use]
+    pub fn with_rest_polling(mut self, market_api: Market, interval: Duration) -> Self {
+        self.rest_polling = Some(RestPolling {
+            market_api,
+            interval,
+        });
+        self
+    }
+
+    /// Queues every received event on `recorder` before broadcasting it.
+    #[must_use]
+    pub fn with_recorder(mut self, recorder: Option<TickerRecorder>) -> Self {
+        self.recorder = recorder;
+        self
+    }
+
+    /// Builds and starts book ticker streams for the given chains, sending the chains they
+    /// serve on `ready` once the streams are started.
     pub async fn build_order_books(
         &self,
         token: CancellationToken,
-        chains: Vec<[ChainSymbol; 3]>,
+        chains: Vec<Vec<ChainSymbol>>,
+        ready: oneshot::Sender<Vec<Vec<ChainSymbol>>>,
     ) -> anyhow::Result<()> {
+        if let Some(rest_polling) = &self.rest_polling {
+            let symbols = chains
+                .iter()
+                .flatten()
+                .map(|chain_symbol| chain_symbol.symbol.symbol.clone())
+                .collect::<HashSet<_>>();
+            METRICS.record_symbol_subscriptions(symbols.len(), Self::chain_subscriptions(&chains));
+            _ = ready.send(chains);
+            return rest_polling
+                .poll_book_tickers(token, symbols, self.recorder.as_ref())
+                .await;
+        }
+
+        // Every symbol is streamed once, however many chains share it: the chains subscribe
+        // to its broadcast instead.
         let symbols = Self::collect_unique_symbols(&chains);
-        let streams = Self::create_streams(&symbols);
+        let streams = self.create_streams(&symbols);
 
         info!(
             streams = streams.len(),
+            chain_subscriptions = Self::chain_subscriptions(&chains),
             "📡 [Network] WebSocket streams active"
         );
 
         let chunk_size = (streams.len() as f64 / self.ws_max_connections as f64).ceil() as usize;
+        let chunks: Vec<Vec<String>> = streams.chunks(chunk_size).map(<[String]>::to_vec).collect();
+        self.check_subscriptions(&chains, &chunks.concat())?;
+
         let mut tasks_set: JoinSet<anyhow::Result<()>> = JoinSet::new();
+        let mut startups = vec![];
 
-        for chunk in streams.chunks(chunk_size) {
+        for (idx, streams_chunk) in chunks.into_iter().enumerate() {
             let ws_url = self.ws_streams_url.clone();
-            let streams_chunk = chunk.to_vec();
             let token = token.clone();
+            let reconnect_strategy = self.reconnect_strategy;
+            let connect_limiter = self.connect_limiter.clone();
+            let recorder = self.recorder.clone();
+            let started = self.max_failed_streams.map(|_| {
+                let (started_tx, started_rx) = oneshot::channel();
+                startups.push((streams_chunk.clone(), started_rx));
+                started_tx
+            });
 
             tasks_set.spawn(async move {
-                Self::handle_ticker_events(ws_url, streams_chunk, token)
-                    .await
-                    .context("WS chunk task failed")
+                Self::run_ticker_events(
+                    ws_url,
+                    format!("ws-{idx}"),
+                    streams_chunk,
+                    token,
+                    reconnect_strategy,
+                    connect_limiter,
+                    recorder,
+                    started,
+                )
+                .await
             });
         }
 
+        let mut failed_streams = vec![];
+        for (streams, started) in startups {
+            if let Ok(Err(e)) = started.await {
+                warn!(
+                    ?streams,
+                    error = ?e,
+                    "⚠️ [Network] Streams failed to connect, dropped"
+                );
+                failed_streams.extend(streams);
+            }
+        }
+        if let Some(max_failed_streams) = self.max_failed_streams
+            && failed_streams.len() > max_failed_streams
+        {
+            bail!(
+                "{} of {} streams failed to connect, more than max_failed_streams \
+                 ({max_failed_streams})",
+                failed_streams.len(),
+                streams.len()
+            );
+        }
+        let chains = Self::drop_failed_chains(chains, &failed_streams);
+        METRICS.record_symbol_subscriptions(
+            streams.len() - failed_streams.len(),
+            Self::chain_subscriptions(&chains),
+        );
+        _ = ready.send(chains);
+
         while let Some(result) = tasks_set.join_next().await {
             match result {
                 Ok(Err(e)) => {
@@ -84,11 +257,77 @@ impl TickerBuilder {
         Ok(())
     }
 
-    /// Handles a chunk of book ticker streams in a dedicated WebSocket connection.
+    /// Keeps a chunk of book ticker streams subscribed until cancelled. With
+    /// [`ReconnectStrategy::Rebuild`] a dropped connection ends the task instead, which stops
+    /// all streams so the exchange service is restarted.
+    ///
+    /// With `started` the outcome of the first connection is sent on it, and a failed one
+    /// ends the task instead of being retried.
+    #[allow(clippy::too_many_arguments)]
+    async fn run_ticker_events(
+        ws_url: String,
+        connection: String,
+        streams_chunk: Vec<String>,
+        token: CancellationToken,
+        reconnect_strategy: ReconnectStrategy,
+        connect_limiter: Option<Arc<Semaphore>>,
+        recorder: Option<TickerRecorder>,
+        mut started: Option<oneshot::Sender<anyhow::Result<()>>>,
+    ) -> anyhow::Result<()> {
+        loop {
+            let result = Self::handle_ticker_events(
+                ws_url.clone(),
+                &connection,
+                streams_chunk.clone(),
+                token.clone(),
+                connect_limiter.as_deref(),
+                recorder.as_ref(),
+                || {
+                    if let Some(started) = started.take() {
+                        _ = started.send(Ok(()));
+                    }
+                },
+            )
+            .await
+            .context("WS chunk task failed");
+            if let Some(started) = started.take() {
+                _ = started.send(result);
+                return Ok(());
+            }
+            if token.is_cancelled() {
+                return result;
+            }
+
+            RUN_STATS.lock().await.record_reconnection();
+            match reconnect_strategy {
+                ReconnectStrategy::Rebuild => {
+                    warn!("🔌 [Network] WebSocket disconnected, rebuilding exchange service");
+                    return result;
+                }
+                ReconnectStrategy::Resubscribe => {
+                    warn!(error = ?result.err(), "🔌 [Network] WebSocket disconnected, resubscribing");
+                }
+            }
+
+            tokio::select! {
+                () = token.cancelled() => return Ok(()),
+                () = tokio::time::sleep(Self::RESUBSCRIBE_DELAY) => {}
+            }
+        }
+    }
+
+    /// Handles a chunk of book ticker streams in a dedicated WebSocket connection, whose ticks
+    /// are published to [`TICKS`] as `connection`, calling `on_connected` once it is
+    /// established. With a connect limiter the connection waits for a permit, held until it
+    /// is established.
     async fn handle_ticker_events(
         ws_url: String,
+        connection: &str,
         streams_chunk: Vec<String>,
         token: CancellationToken,
+        connect_limiter: Option<&Semaphore>,
+        recorder: Option<&TickerRecorder>,
+        on_connected: impl FnOnce(),
     ) -> anyhow::Result<()> {
         let mut ws: WebsocketStream<'_, StreamEvent<_>> = WebsocketStream::new(ws_url.clone())
             .with_callback(|event: StreamEvent<Events>| {
@@ -100,22 +339,59 @@ impl TickerBuilder {
                         bid_qty: event.best_bid_qty,
                         ask_price: event.best_ask_price,
                         ask_qty: event.best_ask_qty,
+                        received_at: Some(Instant::now()),
+                        ..Default::default()
                     };
 
+                    if let Some(recorder) = recorder {
+                        recorder.record(&ticker);
+                    }
                     if let Err(e) = TICKER_BROADCAST.broadcast_event(ticker) {
                         error!(error = ?e, symbol = ?event.symbol, "Failed to broadcast ticker price");
                         return Err(anyhow::anyhow!("Failed to broadcast ticker price: {e}"));
                     }
 
                     METRICS.record_book_ticker_event(event.symbol.as_str());
-                };
+                    TICKS.record(connection, Instant::now());
+                } else if let Events::PartialBookDepth(book) = event.data {
+                    // Depth payloads carry no symbol, it is the prefix of the stream name.
+                    let symbol = event
+                        .stream
+                        .split('@')
+                        .next()
+                        .unwrap_or_default()
+                        .to_uppercase();
+                    let Some(ticker) = Self::depth_to_ticker(&symbol, &book) else {
+                        return Ok(());
+                    };
+
+                    if let Some(recorder) = recorder {
+                        recorder.record(&ticker);
+                    }
+                    if let Err(e) = TICKER_BROADCAST.broadcast_event(ticker) {
+                        error!(error = ?e, symbol, "Failed to broadcast order book depth");
+                        return Err(anyhow::anyhow!("Failed to broadcast order book depth: {e}"));
+                    }
+
+                    METRICS.record_book_ticker_event(symbol.as_str());
+                    TICKS.record(connection, Instant::now());
+                }
 
                 Ok(())
             });
 
+        let permit = match connect_limiter {
+            Some(limiter) => tokio::select! {
+                () = token.cancelled() => return Ok(()),
+                permit = limiter.acquire() => Some(permit?),
+            },
+            None => None,
+        };
         ws.connect_multiple(&streams_chunk)
             .await
             .context("Failed to connect WS")?;
+        drop(permit);
+        on_connected();
 
         ws.handle_messages(token)
             .await
@@ -126,7 +402,100 @@ impl TickerBuilder {
         Ok(())
     }
 
-    fn collect_unique_symbols(chains: &[[ChainSymbol; 3]]) -> Vec<String> {
+    /// Converts a partial depth update into a book ticker event carrying all levels, or `None`
+    /// while a side of the book is empty.
+    fn depth_to_ticker(symbol: &str, book: &OrderBook) -> Option<BookTickerEvent> {
+        let levels = |units: &[OrderBookUnit]| {
+            units
+                .iter()
+                .map(|unit| BookLevel {
+                    price: unit.price,
+                    qty: unit.qty,
+                })
+                .collect::<Vec<_>>()
+        };
+        let (best_bid, best_ask) = (book.bids.first()?, book.asks.first()?);
+
+        Some(BookTickerEvent {
+            update_id: book.last_update_id,
+            symbol: symbol.to_owned(),
+            bid_price: best_bid.price,
+            bid_qty: best_bid.qty,
+            ask_price: best_ask.price,
+            ask_qty: best_ask.qty,
+            bids: levels(&book.bids),
+            asks: levels(&book.asks),
+            received_at: Some(Instant::now()),
+        })
+    }
+
+    /// Verifies that every chain symbol has a subscribed stream, since a chain missing one
+    /// ticker never completes. Fails or only warns, as configured.
+    fn check_subscriptions(
+        &self,
+        chains: &[Vec<ChainSymbol>],
+        streams: &[String],
+    ) -> anyhow::Result<()> {
+        let missing = self.find_unsubscribed_symbols(chains, streams);
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        if self.fail_on_missing_subscriptions {
+            bail!(
+                "No stream subscribed for chain symbols: {}",
+                missing.join(", ")
+            );
+        }
+        warn!(
+            ?missing,
+            "⚠️ [Network] Chain symbols without a stream subscription, their chains never complete"
+        );
+        Ok(())
+    }
+
+    /// Returns the chain symbols without a book ticker stream among `streams`, sorted.
+    fn find_unsubscribed_symbols<'a>(
+        &self,
+        chains: &'a [Vec<ChainSymbol>],
+        streams: &[String],
+    ) -> Vec<&'a str> {
+        let subscribed: HashSet<&str> = streams.iter().map(String::as_str).collect();
+        chains
+            .iter()
+            .flatten()
+            .map(|chain_symbol| chain_symbol.symbol.symbol.as_str())
+            .filter(|symbol| !subscribed.contains(self.stream(&symbol.to_lowercase()).as_str()))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Drops the chains with a symbol among `failed_streams`, logging them.
+    fn drop_failed_chains(
+        chains: Vec<Vec<ChainSymbol>>,
+        failed_streams: &[String],
+    ) -> Vec<Vec<ChainSymbol>> {
+        // Streams are named after the lowercase symbol.
+        let failed: HashSet<String> = failed_streams
+            .iter()
+            .map(|stream| stream.split('@').next().unwrap_or_default().to_uppercase())
+            .collect();
+        let (kept, dropped): (Vec<_>, Vec<_>) = chains.into_iter().partition(|chain| {
+            chain
+                .iter()
+                .all(|chain_symbol| !failed.contains(&chain_symbol.symbol.symbol))
+        });
+        for chain in &dropped {
+            warn!(
+                chain = ?chain::extract_chain_symbols(chain),
+                "⚠️ [Network] Chain dropped, a stream it depends on failed to connect"
+            );
+        }
+        kept
+    }
+
+    fn collect_unique_symbols(chains: &[Vec<ChainSymbol>]) -> Vec<String> {
         chains
             .iter()
             .flat_map(|chain| chain.iter())
@@ -136,10 +505,480 @@ impl TickerBuilder {
             .collect()
     }
 
-    fn create_streams(symbols: &[String]) -> Vec<String> {
-        symbols
+    /// Returns the ticker subscriptions of the chains, one per chain symbol.
+    fn chain_subscriptions(chains: &[Vec<ChainSymbol>]) -> usize {
+        chains.iter().map(|chain| chain.len()).sum()
+    }
+
+    fn create_streams(&self, symbols: &[String]) -> Vec<String> {
+        symbols.iter().map(|symbol| self.stream(symbol)).collect()
+    }
+
+    /// Returns the stream of a lowercase symbol: its partial depth with a market depth
+    /// configured, its book ticker otherwise.
+    fn stream(&self, symbol: &str) -> String {
+        match self.market_depth_levels {
+            Some(levels) => partial_book_depth_stream(symbol, levels, Self::DEPTH_UPDATE_SPEED_MS),
+            None => book_ticker_stream(symbol),
+        }
+    }
+}
+
+impl RestPolling {
+    /// Request weight of the book ticker endpoint for all symbols.
+    const BOOK_TICKER_WEIGHT: usize = 4;
+    /// Stream the successful polls are published to [`TICKS`] as.
+    const TICKS_STREAM: &str = "rest";
+
+    /// Polls the book tickers of all symbols every interval until cancelled and broadcasts
+    /// the changed ones among `symbols`, queuing them on `recorder` first. Failed polls are
+    /// logged and retried on the next tick.
+    async fn poll_book_tickers(
+        &self,
+        token: CancellationToken,
+        symbols: HashSet<String>,
+        recorder: Option<&TickerRecorder>,
+    ) -> anyhow::Result<()> {
+        info!(
+            symbols = symbols.len(),
+            interval = ?self.interval,
+            "📡 [Network] Polling book tickers over REST"
+        );
+
+        let mut interval = tokio::time::interval(self.interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut last_events: HashMap<String, BookTickerEvent> = HashMap::new();
+        // REST book tickers carry no update id, so polls are numbered instead.
+        let mut update_id = 0;
+
+        loop {
+            tokio::select! {
+                () = token.cancelled() => return Ok(()),
+                _ = interval.tick() => {}
+            }
+
+            while !REQUEST_WEIGHT.lock().await.add(Self::BOOK_TICKER_WEIGHT) {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+
+            let tickers = match self.market_api.get_book_tickers().await {
+                Ok(tickers) => tickers,
+                Err(e) => {
+                    warn!(error = ?e, "Failed to poll book tickers");
+                    continue;
+                }
+            };
+            update_id += 1;
+            TICKS.record(Self::TICKS_STREAM, Instant::now());
+
+            for ticker in tickers {
+                if !symbols.contains(&ticker.symbol) {
+                    continue;
+                }
+                let unchanged = last_events.get(&ticker.symbol).is_some_and(|last| {
+                    (last.bid_price, last.bid_qty, last.ask_price, last.ask_qty)
+                        == (
+                            ticker.bid_price,
+                            ticker.bid_qty,
+                            ticker.ask_price,
+                            ticker.ask_qty,
+                        )
+                });
+                if unchanged {
+                    continue;
+                }
+
+                let event = BookTickerEvent {
+                    update_id,
+                    symbol: ticker.symbol,
+                    bid_price: ticker.bid_price,
+                    bid_qty: ticker.bid_qty,
+                    ask_price: ticker.ask_price,
+                    ask_qty: ticker.ask_qty,
+                    received_at: Some(Instant::now()),
+                    ..Default::default()
+                };
+                if let Some(recorder) = recorder {
+                    recorder.record(&event);
+                }
+                if let Err(e) = TICKER_BROADCAST.broadcast_event(event.clone()) {
+                    error!(error = ?e, symbol = ?event.symbol, "Failed to broadcast ticker price");
+                    continue;
+                }
+                METRICS.record_book_ticker_event(event.symbol.as_str());
+                last_events.insert(event.symbol.clone(), event);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use engine::enums::SymbolOrder;
+    use futures_util::{SinkExt, StreamExt};
+    use mockito::{Matcher, Server};
+    use rust_decimal::Decimal;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::{
+        accept_hdr_async,
+        tungstenite::{
+            Message,
+            handshake::server::{ErrorResponse, Request, Response},
+            http::StatusCode,
+        },
+    };
+
+    use super::*;
+    use crate::{
+        libs::binance_client::{self, Binance, Symbol},
+        services::storage::BookTickerStore,
+    };
+
+    fn chain_symbol(symbol: &str, order: SymbolOrder) -> ChainSymbol {
+        ChainSymbol::new(
+            Symbol {
+                symbol: symbol.to_owned(),
+                ..Default::default()
+            },
+            order,
+        )
+    }
+
+    #[test]
+    fn test_missing_subscription_detected() {
+        let chains = [vec![
+            chain_symbol("BTCUSDT", SymbolOrder::Desc),
+            chain_symbol("ETHBTC", SymbolOrder::Desc),
+            chain_symbol("ETHUSDT", SymbolOrder::Asc),
+        ]];
+        let strict = TickerBuilder::new(String::new(), 1, true, ReconnectStrategy::default());
+        let all_streams = strict.create_streams(&TickerBuilder::collect_unique_symbols(&chains));
+        let streams: Vec<String> = all_streams
+            .into_iter()
+            .filter(|stream| stream != "ethbtc@bookTicker")
+            .collect();
+
+        assert_eq!(
+            strict.find_unsubscribed_symbols(&chains, &streams),
+            ["ETHBTC"]
+        );
+        assert!(strict.check_subscriptions(&chains, &streams).is_err());
+
+        let lenient = TickerBuilder::new(String::new(), 1, false, ReconnectStrategy::default());
+        assert!(lenient.check_subscriptions(&chains, &streams).is_ok());
+
+        // With a market depth every symbol needs its partial depth stream instead.
+        let depth = strict.with_market_depth(10);
+        assert_eq!(
+            depth.find_unsubscribed_symbols(&chains, &streams),
+            ["BTCUSDT", "ETHBTC", "ETHUSDT"]
+        );
+        assert_eq!(depth.stream("ethbtc"), "ethbtc@depth10@100ms");
+    }
+
+    #[tokio::test]
+    async fn test_rest_polling_feeds_store() -> anyhow::Result<()> {
+        REQUEST_WEIGHT.lock().await.set_weight_limit(6000);
+
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v3/ticker/bookTicker")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[
+                    {"symbol": "RESTBTC", "bidPrice": "0.5", "bidQty": "10", "askPrice": "0.51", "askQty": "12"},
+                    {"symbol": "OTHERBTC", "bidPrice": "1", "bidQty": "1", "askPrice": "1.1", "askQty": "1"}
+                ]"#,
+            )
+            .expect_at_least(1)
+            .create_async()
+            .await;
+
+        let market_api: Market = Binance::new(binance_client::ClientConfig {
+            api_url: server.url(),
+            api_token: "test_api_key".to_owned(),
+            api_secret_key: "test_secret_key".to_owned(),
+            http_config: binance_client::HttpConfig::default(),
+        })?;
+        let ticker_builder =
+            TickerBuilder::new(String::new(), 1, true, ReconnectStrategy::default())
+                .with_rest_polling(market_api, Duration::from_millis(10));
+        let chains = vec![vec![
+            chain_symbol("RESTBTC", SymbolOrder::Asc),
+            chain_symbol("RESTUSDT", SymbolOrder::Desc),
+            chain_symbol("BTCUSDT", SymbolOrder::Asc),
+        ]];
+
+        let mut rx = TICKER_BROADCAST.subscribe("RESTBTC");
+        let token = CancellationToken::new();
+        let polled = tokio::select! {
+            result = ticker_builder.build_order_books(token.clone(), chains, oneshot::channel().0) => {
+                anyhow::bail!("Polling stopped: {result:?}")
+            }
+            result = tokio::time::timeout(Duration::from_secs(5), rx.changed()) => result,
+        };
+        token.cancel();
+        polled??;
+        mock.assert_async().await;
+
+        let mut storage = BookTickerStore::new();
+        storage.update(rx.borrow_and_update().clone());
+        let event = storage.get("RESTBTC").expect("RESTBTC ticker stored");
+        assert_eq!(event.bid_price, Decimal::new(5, 1));
+        assert_eq!(event.ask_qty, Decimal::from(12));
+        // Symbols outside the chains are not broadcast.
+        assert_eq!(
+            TICKER_BROADCAST.subscribe("OTHERBTC").borrow().symbol,
+            String::new()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reconnects_limited() -> anyhow::Result<()> {
+        let handshakes = Arc::new(AtomicUsize::new(0));
+        let connecting = Arc::new(AtomicUsize::new(0));
+        let max_connecting = Arc::new(AtomicUsize::new(0));
+
+        // Slow handshakes followed by an immediate close, so every connection drops at once.
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let ws_url = format!("ws://{}", listener.local_addr()?);
+        let server = {
+            let (handshakes, connecting, max_connecting) = (
+                handshakes.clone(),
+                connecting.clone(),
+                max_connecting.clone(),
+            );
+            tokio::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    let (handshakes, connecting, max_connecting) = (
+                        handshakes.clone(),
+                        connecting.clone(),
+                        max_connecting.clone(),
+                    );
+                    tokio::spawn(async move {
+                        let count = connecting.fetch_add(1, Ordering::SeqCst) + 1;
+                        max_connecting.fetch_max(count, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                        // Released before the response is sent, while the client still holds
+                        // its permit.
+                        #[allow(clippy::result_large_err)]
+                        let callback =
+                            |_: &Request, response: Response| -> Result<Response, ErrorResponse> {
+                                connecting.fetch_sub(1, Ordering::SeqCst);
+                                handshakes.fetch_add(1, Ordering::SeqCst);
+                                Ok(response)
+                            };
+                        if let Ok(mut ws) = accept_hdr_async(stream, callback).await {
+                            _ = ws.send(Message::Close(None)).await;
+                        }
+                    });
+                }
+            })
+        };
+
+        let ticker_builder = TickerBuilder::new(ws_url, 9, false, ReconnectStrategy::Resubscribe)
+            .with_max_concurrent_reconnects(2);
+        let chains = ["LIMA", "LIMB", "LIMC"]
+            .into_iter()
+            .map(|base| {
+                vec![
+                    chain_symbol(&format!("{base}USDT"), SymbolOrder::Desc),
+                    chain_symbol(&format!("{base}BTC"), SymbolOrder::Desc),
+                    chain_symbol(&format!("{base}ETH"), SymbolOrder::Asc),
+                ]
+            })
+            .collect();
+
+        let token = CancellationToken::new();
+        tokio::select! {
+            result = ticker_builder.build_order_books(token.clone(), chains, oneshot::channel().0) => {
+                anyhow::bail!("Streams stopped: {result:?}")
+            }
+            // Initial connects and the first round of resubscriptions.
+            () = tokio::time::sleep(TickerBuilder::RESUBSCRIBE_DELAY * 2) => {}
+        }
+        token.cancel();
+        server.abort();
+
+        assert!(handshakes.load(Ordering::SeqCst) > 9);
+        assert_eq!(max_connecting.load(Ordering::SeqCst), 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_failed_streams_drop_their_chains() -> anyhow::Result<()> {
+        // Handshakes for the BADBTC stream are refused, the other connections are held open.
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let ws_url = format!("ws://{}", listener.local_addr()?);
+        let server = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    #[allow(clippy::result_large_err)]
+                    let callback = |request: &Request,
+                                    response: Response|
+                     -> Result<Response, ErrorResponse> {
+                        if request.uri().query().unwrap_or_default().contains("badbtc") {
+                            let mut error = ErrorResponse::new(Some("Invalid symbol".into()));
+                            *error.status_mut() = StatusCode::BAD_REQUEST;
+                            return Err(error);
+                        }
+                        Ok(response)
+                    };
+                    if let Ok(mut ws) = accept_hdr_async(stream, callback).await {
+                        while let Some(Ok(_)) = ws.next().await {}
+                    }
+                });
+            }
+        });
+
+        let chains = || {
+            vec![
+                vec![
+                    chain_symbol("OKAUSDT", SymbolOrder::Desc),
+                    chain_symbol("OKABTC", SymbolOrder::Asc),
+                    chain_symbol("BTCUSDT", SymbolOrder::Asc),
+                ],
+                vec![
+                    chain_symbol("BADUSDT", SymbolOrder::Desc),
+                    chain_symbol("BADBTC", SymbolOrder::Asc),
+                    chain_symbol("BTCUSDT", SymbolOrder::Asc),
+                ],
+            ]
+        };
+        // A connection per stream.
+        let ticker_builder = TickerBuilder::new(ws_url, 5, false, ReconnectStrategy::Resubscribe);
+        let wait = Duration::from_secs(5);
+
+        let (ready_tx, ready_rx) = oneshot::channel();
+        let token = CancellationToken::new();
+        let streams = tokio::spawn({
+            let ticker_builder = ticker_builder.clone().with_max_failed_streams(Some(1));
+            let token = token.clone();
+            async move {
+                ticker_builder
+                    .build_order_books(token, chains(), ready_tx)
+                    .await
+            }
+        });
+        let ready = tokio::time::timeout(wait, ready_rx).await??;
+        token.cancel();
+        tokio::time::timeout(wait, streams).await???;
+        let ready: Vec<_> = ready
             .iter()
-            .map(|symbol| book_ticker_stream(symbol))
-            .collect()
+            .map(|chain| chain::extract_chain_symbols(chain))
+            .collect();
+        assert_eq!(ready, [["OKAUSDT", "OKABTC", "BTCUSDT"]]);
+
+        // Above the threshold startup fails, and no chain is served.
+        let (ready_tx, ready_rx) = oneshot::channel();
+        let error = tokio::time::timeout(
+            wait,
+            ticker_builder
+                .with_max_failed_streams(Some(0))
+                .build_order_books(CancellationToken::new(), chains(), ready_tx),
+        )
+        .await?
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "1 of 5 streams failed to connect, more than max_failed_streams (0)"
+        );
+        assert!(ready_rx.await.is_err());
+
+        server.abort();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shared_symbols_are_streamed_once() -> anyhow::Result<()> {
+        // Records the streams of every handshake and holds the connections open.
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let ws_url = format!("ws://{}", listener.local_addr()?);
+        let (streams_tx, mut streams_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+        let server = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let streams_tx = streams_tx.clone();
+                tokio::spawn(async move {
+                    #[allow(clippy::result_large_err)]
+                    let callback = |request: &Request,
+                                    response: Response|
+                     -> Result<Response, ErrorResponse> {
+                        let query = request.uri().query().unwrap_or_default();
+                        for (key, streams) in url::form_urlencoded::parse(query.as_bytes()) {
+                            if key == "streams" {
+                                for stream in streams.split('/') {
+                                    _ = streams_tx.send(stream.to_owned());
+                                }
+                            }
+                        }
+                        Ok(response)
+                    };
+                    if let Ok(mut ws) = accept_hdr_async(stream, callback).await {
+                        while let Some(Ok(_)) = ws.next().await {}
+                    }
+                });
+            }
+        });
+
+        // Every chain shares BTCUSDT, and the ETH ones ETHBTC as well, the 4-leg one both.
+        let chains: Vec<_> = [
+            &["ETHUSDT", "ETHBTC", "BTCUSDT"][..],
+            &["BTCUSDT", "ETHBTC", "ETHUSDT"],
+            &["SOLUSDT", "SOLBTC", "BTCUSDT"],
+            &["BTCUSDT", "SOLBTC", "SOLUSDT"],
+            &["SOLUSDT", "SOLETH", "ETHBTC", "BTCUSDT"],
+        ]
+        .into_iter()
+        .map(|symbols| {
+            symbols
+                .iter()
+                .map(|symbol| chain_symbol(symbol, SymbolOrder::Asc))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+        let ticker_builder = TickerBuilder::new(ws_url, 3, false, ReconnectStrategy::Resubscribe)
+            .with_max_failed_streams(Some(0));
+
+        let (ready_tx, ready_rx) = oneshot::channel();
+        let token = CancellationToken::new();
+        let streams = tokio::spawn({
+            let token = token.clone();
+            async move {
+                ticker_builder
+                    .build_order_books(token, chains, ready_tx)
+                    .await
+            }
+        });
+        let ready = tokio::time::timeout(Duration::from_secs(5), ready_rx).await??;
+        token.cancel();
+        streams.await??;
+        server.abort();
+
+        let mut subscribed = vec![];
+        while let Ok(stream) = streams_rx.try_recv() {
+            subscribed.push(stream);
+        }
+        subscribed.sort();
+        assert_eq!(
+            subscribed,
+            [
+                "btcusdt@bookTicker",
+                "ethbtc@bookTicker",
+                "ethusdt@bookTicker",
+                "solbtc@bookTicker",
+                "soleth@bookTicker",
+                "solusdt@bookTicker",
+            ]
+        );
+        assert_eq!(TickerBuilder::chain_subscriptions(&ready), 16);
+
+        Ok(())
     }
 }
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use engine::{Exchange, Sender, ServiceFactory};
+use engine::{Exchange, ForwardSender, Sender, ServiceFactory};
 
 use crate::{
     Config,
@@ -29,6 +29,9 @@ impl ServiceFactory<dyn Sender> for Provider {
     type Config = Config;
 
     async fn from_config(config: &Config) -> anyhow::Result<Arc<dyn Sender>> {
+        if let Some(url) = &config.forward_orders_url {
+            return Ok(Arc::new(ForwardSender::new(url)?));
+        }
         Ok(Arc::new(SenderService::from_config(config).await?))
     }
 }
@@ -1,38 +1,107 @@
 //! Binance order sender service for executing arbitrage chains.
 
-use std::time::Duration;
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
-use anyhow::Context;
+use anyhow::{Context, bail};
 use async_trait::async_trait;
 use engine::{
-    ChainOrder, ChainOrders, METRICS, ORDERS_CHANNEL, REQUEST_WEIGHT, Sender,
-    enums::{ChainStatus, SymbolOrder},
+    ChainOrder, ChainOrders, ClockDrift, ExecutionCap, FILLED_CHAINS, FilledChain,
+    IN_FLIGHT_CHAINS, KILL_SWITCH, KillSwitch, METRICS, ORDERS_CHANNEL, OpenOrders,
+    OpportunityWatchdog, OrderSizeRamp, ProfitPersistence, REQUEST_WEIGHT, RUN_STATS, RealizedPnl,
+    RepeatGuard, Sender, SizeRamp,
+    enums::{self, ChainStatus, SymbolOrder},
     service::traits::ArbitrageService,
 };
 use rust_decimal::Decimal;
+use serde::Deserialize;
 use tokio::{sync::oneshot, task::JoinSet, time::Instant};
 use tokio_util::sync::CancellationToken;
-use tracing::{error, info};
+use tools::misc;
+use tracing::{error, info, warn};
 
 use crate::{
     config::Config,
-    libs::binance_client::{
-        OrderSide, OrderType, ws,
-        ws::{PlaceOrderRequest, WebsocketApi, WebsocketWriter, connect_ws},
+    libs::{
+        binance_client,
+        binance_client::{
+            Api, Binance, General, OrderSide, OrderStatus, OrderType, SelfTradePreventionMode,
+            SendOrderRequest, Spot, TimeInForce, Trade, utils, ws,
+            ws::{PlaceOrderRequest, WebsocketApi, WebsocketWriter, connect_ws, sign_place_order},
+        },
     },
 };
 
+/// Balance left in an intermediate asset when unwinding a failed chain fails.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrandedBalance {
+    pub asset: String,
+    pub qty: Decimal,
+}
+
+/// Quantity field used for Desc (buy) legs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DescLegQuantity {
+    /// Spend the computed quote amount via `quoteOrderQty` (market orders only).
+    #[default]
+    Quote,
+    /// Buy the base amount the quote covers at the chain price via `quantity`.
+    Base,
+}
+
 /// Service for sending and polling Binance orders from arbitrage chains.
 #[derive(Clone)]
 pub struct SenderService {
     send_orders: bool,
+    shadow_orders: bool,
     process_chain_interval: Duration,
+    min_profit_persist: Duration,
+    min_price_change_bps: Option<u32>,
+    max_chain_executions: Option<u32>,
+    chain_executions_window: Duration,
+    profit_targets: HashMap<String, Decimal>,
+    max_daily_losses: HashMap<String, Decimal>,
+    size_ramp: Option<SizeRamp>,
+    exit_on_session_profit_target: bool,
     ws_url: String,
     api_token: String,
     api_secret_key: String,
+    self_trade_prevention_mode: Option<SelfTradePreventionMode>,
+    desc_leg_quantity: DescLegQuantity,
+    unwind_on_failure: bool,
+    max_chain_execution: Option<Duration>,
+    verify_leg_input: bool,
+    /// Skip chains while a rate limited response pauses requests.
+    pause_on_rate_limit: bool,
+    trade_api: Trade,
+    general_api: General,
+    clock_drift: Option<Arc<ClockDrift>>,
+    opportunity_watchdog: Option<Arc<OpportunityWatchdog>>,
+    open_orders: Arc<OpenOrders>,
+    kill_switch: Arc<KillSwitch>,
 }
 
-impl Sender for SenderService {}
+#[async_trait]
+impl Sender for SenderService {
+    async fn query_order(
+        &self,
+        symbol: &str,
+        client_order_id: &str,
+    ) -> anyhow::Result<enums::OrderStatus> {
+        Self::wait_for_weight(Api::Spot(Spot::Order).weight()).await?;
+        let response = self
+            .trade_api
+            .query_order(symbol, client_order_id)
+            .await
+            .with_context(|| format!("Failed to query order {client_order_id}"))?;
+
+        Ok(order_status(&response.status))
+    }
+}
 
 #[async_trait]
 impl ArbitrageService for SenderService {
@@ -45,6 +114,39 @@ impl ArbitrageService for SenderService {
             async move { this.receive_and_send_orders(token).await }
         });
 
+        if self.clock_drift.is_some() {
+            tasks.spawn({
+                let this = self.clone();
+                let token = token.clone();
+                async move {
+                    this.monitor_clock_drift(token).await;
+                    Ok(())
+                }
+            });
+        }
+
+        if self.trade_api.client.warm_connections() > 0 {
+            tasks.spawn({
+                let this = self.clone();
+                let token = token.clone();
+                async move {
+                    this.keep_connections_warm(token).await;
+                    Ok(())
+                }
+            });
+        }
+
+        if let Some(watchdog) = &self.opportunity_watchdog {
+            tasks.spawn({
+                let watchdog = watchdog.clone();
+                let token = token.clone();
+                async move {
+                    watchdog.monitor(token).await;
+                    Ok(())
+                }
+            });
+        }
+
         while let Some(result) = tasks.join_next().await {
             match result {
                 Ok(Err(e)) => {
@@ -71,32 +173,120 @@ impl SenderService {
         {
             let mut weight_lock = REQUEST_WEIGHT.lock().await;
             weight_lock.set_weight_limit(config.api_weight_limit);
+            weight_lock.set_backoff_percent(config.weight_backoff_percent);
+            weight_lock
+                .set_rate_limit_cooldown(config.rate_limit_cooldown_secs.map(Duration::from_secs));
+        }
+        KILL_SWITCH.set_max_failures(config.max_consecutive_failed_chains);
+
+        let api_config = binance_client::ClientConfig {
+            api_url: config.api_url.clone(),
+            api_token: config.api_token.clone(),
+            api_secret_key: config.api_secret_key.clone(),
+            http_config: binance_client::HttpConfig::default(),
+        };
+        // Orders go through their own client, on the dedicated trade API if configured.
+        let trade_config = config.trade_client_config();
+        let trade_api: Trade =
+            Binance::new(trade_config.clone()).context("Failed to init trade binance client")?;
+        let general_api: General =
+            Binance::new(api_config).context("Failed to init general binance client")?;
+
+        if trade_api.client.warm_connections() > 0 {
+            let warmed = trade_api.client.warm_up().await;
+            info!(warmed, "🔥 [Engine] Trade API connections warmed");
         }
 
         Ok(Self {
             send_orders: config.send_orders,
+            shadow_orders: config.shadow_orders,
             process_chain_interval: Duration::from_secs(10),
-            ws_url: config.ws_url.clone(),
-            api_token: config.api_token.clone(),
-            api_secret_key: config.api_secret_key.clone(),
+            min_profit_persist: Duration::from_millis(config.min_profit_persist_ms),
+            min_price_change_bps: config.min_price_change_bps,
+            max_chain_executions: config.max_chain_executions,
+            chain_executions_window: Duration::from_secs(config.chain_executions_window_secs),
+            profit_targets: config
+                .assets
+                .iter()
+                .filter_map(|asset| Some((asset.asset.clone(), asset.session_profit_target?)))
+                .collect(),
+            max_daily_losses: config
+                .assets
+                .iter()
+                .filter_map(|asset| Some((asset.asset.clone(), asset.max_daily_loss?)))
+                .collect(),
+            size_ramp: config.size_ramp,
+            exit_on_session_profit_target: config.exit_on_session_profit_target,
+            ws_url: config.trade_ws_url().to_owned(),
+            api_token: trade_config.api_token,
+            api_secret_key: trade_config.api_secret_key,
+            self_trade_prevention_mode: config.self_trade_prevention_mode.clone(),
+            desc_leg_quantity: config.desc_leg_quantity,
+            unwind_on_failure: config.unwind_on_failure,
+            max_chain_execution: config.max_chain_execution_ms.map(Duration::from_millis),
+            verify_leg_input: config.verify_leg_input,
+            pause_on_rate_limit: config.rate_limit_cooldown_secs.is_some(),
+            trade_api,
+            general_api,
+            clock_drift: config
+                .max_clock_drift_ms
+                .map(|ms| Arc::new(ClockDrift::new(Duration::from_millis(ms)))),
+            opportunity_watchdog: config.no_opportunity_alert_after_secs.map(|secs| {
+                Arc::new(OpportunityWatchdog::new(
+                    Duration::from_secs(secs),
+                    Instant::now(),
+                ))
+            }),
+            open_orders: Arc::new(OpenOrders::new(config.max_open_orders)),
+            kill_switch: Arc::clone(&KILL_SWITCH),
         })
     }
 
+    /// Pings the warm trade API connections before they idle out, until cancelled.
+    async fn keep_connections_warm(&self, token: CancellationToken) {
+        let mut interval = tokio::time::interval(self.trade_api.client.warm_interval());
+        interval.tick().await;
+        loop {
+            tokio::select! {
+                () = token.cancelled() => return,
+                _ = interval.tick() => {}
+            }
+            let warmed = self.trade_api.client.warm_up().await;
+            if warmed < self.trade_api.client.warm_connections() {
+                warn!(warmed, "Some trade API connections could not be kept warm");
+            }
+        }
+    }
+
     /// Main loop for receiving arbitrage chains and sending corresponding orders.
     /// Monitors a watch channel for new chains, processes them with rate limiting,
     /// and handles WebSocket messages in parallel.
     async fn receive_and_send_orders(&self, token: CancellationToken) -> anyhow::Result<()> {
+        // Order responses keep being read after cancellation, so that a chain being placed on
+        // shutdown can resolve. The reader is stopped on return.
+        let reader_token = CancellationToken::new();
+        let _stop_reader = reader_token.clone().drop_guard();
         let (mut ws_writer, message_handler, mut message_done_rx) =
-            self.setup_websocket(token.clone()).await?;
+            self.setup_websocket(reader_token).await?;
 
         let mut orders_rx = ORDERS_CHANNEL.rx.lock().await;
         let mut last_chain_exec_ts: Option<Instant> = None;
+        let mut persistence = ProfitPersistence::new(self.min_profit_persist);
+        let mut repeat_guard = self.min_price_change_bps.map(RepeatGuard::new);
+        let mut execution_cap = self
+            .max_chain_executions
+            .map(|max| ExecutionCap::new(max, self.chain_executions_window));
+        let mut pnl = RealizedPnl::new(self.profit_targets.clone(), self.max_daily_losses.clone());
+        let mut size_ramp = self.size_ramp.map(OrderSizeRamp::new);
 
         // Get the initial value from watch channel
         _ = orders_rx.borrow().clone();
 
         loop {
             tokio::select! {
+                // Checked first: no new chain is taken once shutting down.
+                biased;
+
                 _ = token.cancelled() => {
                     break;
                 }
@@ -105,31 +295,143 @@ impl SenderService {
                     let chain = orders_rx.borrow().clone();
                     let chain_symbols = chain.extract_symbols();
 
+                    // Every chain in the channel has passed the profit gate.
+                    if let Some(watchdog) = &self.opportunity_watchdog {
+                        watchdog.record(Instant::now()).await;
+                    }
+                    RUN_STATS.lock().await.record_processed();
+
                     if !self.send_orders {
                         chain.print_info(self.send_orders);
                         return Ok(());
                     }
 
+                    // Skip opportunities that have not stayed profitable long enough yet.
+                    if !persistence.observe(&chain, Instant::now()) {
+                        RUN_STATS.lock().await.record_rejection("not_persisted");
+                        continue;
+                    }
+
                     if last_chain_exec_ts
                         .as_ref()
                         .is_some_and(|t| t.elapsed() < self.process_chain_interval)
                     {
+                        RUN_STATS.lock().await.record_rejection("interval");
                         continue;
                     }
 
-                    chain.print_info(self.send_orders);
-                    METRICS.record_chain_status(&chain_symbols, &ChainStatus::New);
+                    // Same prices as the last execution of this chain: nothing new to capture.
+                    if repeat_guard.as_ref().is_some_and(|guard| guard.is_repeat(&chain)) {
+                        RUN_STATS.lock().await.record_rejection("repeat");
+                        continue;
+                    }
 
-                    if let Err(e) =
-                        self.process_chain_orders(&mut ws_writer, chain.clone()).await
+                    // Executed as often as allowed within the chain's current window.
+                    if execution_cap
+                        .as_ref()
+                        .is_some_and(|cap| cap.is_capped(&chain, Instant::now()))
                     {
-                        METRICS.record_chain_status(&chain_symbols, &ChainStatus::Cancelled);
-                        error!(error = ?e, "❌ [Engine] Error processing chain orders");
-                        break;
+                        RUN_STATS.lock().await.record_rejection("execution_cap");
+                        continue;
+                    }
+
+                    if !self.trading_allowed() {
+                        RUN_STATS.lock().await.record_rejection("clock_drift");
+                        continue;
                     }
 
+                    // Too many chains failed in a row, halted until restart.
+                    if self.kill_switch.is_halted() {
+                        RUN_STATS.lock().await.record_rejection("kill_switch");
+                        continue;
+                    }
+
+                    // Rate limited by Binance: orders would only extend the ban.
+                    if self.pause_on_rate_limit && REQUEST_WEIGHT.lock().await.paused_for().is_some()
+                    {
+                        RUN_STATS.lock().await.record_rejection("rate_limit");
+                        continue;
+                    }
+
+                    // Session profit target reached or daily loss limit exceeded.
+                    if pnl.check(misc::time::get_current_timestamp().as_secs()).is_err() {
+                        RUN_STATS.lock().await.record_rejection("pnl_limit");
+                        continue;
+                    }
+
+                    if !self.has_open_orders_capacity(chain.orders.len()).await {
+                        RUN_STATS.lock().await.record_rejection("open_orders");
+                        warn!(
+                            chain_id = %chain.chain_id,
+                            "⏸️ [Engine] Open orders limit reached, chain deferred"
+                        );
+                        continue;
+                    }
+
+                    // Sized down while ramping up.
+                    let chain = size_ramp
+                        .as_ref()
+                        .map_or_else(|| chain.clone(), |ramp| ramp.apply(&chain));
+                    chain.print_info(self.send_orders);
+
+                    // Log the signed requests instead of placing them.
+                    if self.shadow_orders {
+                        if let Err(e) = self.log_shadow_orders(&chain) {
+                            error!(error = ?e, "❌ [Engine] Error building shadow orders");
+                        }
+                        last_chain_exec_ts = Some(Instant::now());
+                        if let Some(guard) = repeat_guard.as_mut() {
+                            guard.record(&chain);
+                        }
+                        if let Some(cap) = execution_cap.as_mut() {
+                            cap.record(&chain, Instant::now());
+                        }
+                        continue;
+                    }
+
+                    METRICS.record_chain_status(&chain_symbols, &ChainStatus::New);
+
+                    let profit = match self.send_chain(&mut ws_writer, chain.clone()).await {
+                        Ok(profit) => profit,
+                        Err(e) => {
+                            METRICS.record_chain_status(&chain_symbols, &ChainStatus::Cancelled);
+                            error!(error = ?e, "❌ [Engine] Error processing chain orders");
+                            // Without a kill switch the first failed chain stops sending,
+                            // otherwise the switch decides when to stop trading.
+                            if !self.kill_switch.is_enabled() {
+                                break;
+                            }
+                            last_chain_exec_ts = Some(Instant::now());
+                            continue;
+                        }
+                    };
+
                     last_chain_exec_ts = Some(Instant::now());
+                    if let Some(guard) = repeat_guard.as_mut() {
+                        guard.record(&chain);
+                    }
+                    if let Some(cap) = execution_cap.as_mut() {
+                        cap.record(&chain, Instant::now());
+                    }
                     METRICS.record_chain_status(&chain_symbols, &ChainStatus::Filled);
+
+                    RUN_STATS.lock().await.record_execution(&chain.base_asset, profit);
+                    if let Some(ramp) = size_ramp.as_mut() {
+                        ramp.record(profit);
+                    }
+                    pnl.record(
+                        &chain.base_asset,
+                        profit,
+                        misc::time::get_current_timestamp().as_secs(),
+                    );
+                    METRICS.record_realized_profit(
+                        &chain.base_asset,
+                        pnl.session_profit(&chain.base_asset),
+                    );
+                    if self.exit_on_session_profit_target && pnl.profit_target_reached() {
+                        info!("🛑 [Engine] Session profit target reached, shutting down");
+                        break;
+                    }
                 }
 
                 result = &mut message_done_rx => match result {
@@ -153,6 +455,52 @@ impl SenderService {
         Ok(())
     }
 
+    /// Periodically compares the local clock with the Binance server time.
+    async fn monitor_clock_drift(&self, token: CancellationToken) {
+        let Some(clock_drift) = &self.clock_drift else {
+            return;
+        };
+
+        clock_drift
+            .monitor(token, ClockDrift::MEASURE_INTERVAL, || async {
+                let server_time = self.general_api.server_time().await?;
+                Ok(u128::from(server_time.server_time))
+            })
+            .await;
+    }
+
+    /// Returns false while the clock drift is too large to place signed orders.
+    fn trading_allowed(&self) -> bool {
+        if let Some(clock_drift) = &self.clock_drift
+            && let Err(e) = clock_drift.check()
+        {
+            warn!(error = %e, "⏰ [Engine] Refusing to send chain orders");
+            return false;
+        }
+        true
+    }
+
+    /// Returns true if `count` more orders fit under the open orders cap.
+    /// At the cap, tracked orders are re-queried first so final ones free their slots.
+    async fn has_open_orders_capacity(&self, count: usize) -> bool {
+        if self.open_orders.has_capacity(count).await {
+            return true;
+        }
+
+        for (symbol, client_order_id) in self.open_orders.tracked().await {
+            match self.query_order(&symbol, &client_order_id).await {
+                Ok(status) => {
+                    self.open_orders
+                        .update(&symbol, &client_order_id, status)
+                        .await;
+                }
+                Err(e) => warn!(error = ?e, client_order_id, "Failed to refresh open order"),
+            }
+        }
+
+        self.open_orders.has_capacity(count).await
+    }
+
     /// Sets up the WebSocket connection and spawns a message handler task.
     async fn setup_websocket(
         &self,
@@ -183,26 +531,135 @@ impl SenderService {
         Ok((ws_writer, message_handler, message_done_rx))
     }
 
+    /// Places the chain orders, counting failed chains towards the kill switch.
+    async fn send_chain(
+        &self,
+        ws_writer: &mut WebsocketWriter,
+        chain: ChainOrders,
+    ) -> anyhow::Result<Decimal> {
+        let in_flight = IN_FLIGHT_CHAINS.enter();
+        let result = self.process_chain_orders(ws_writer, chain).await;
+        drop(in_flight);
+
+        if result.is_ok() {
+            self.kill_switch.record_success();
+        } else {
+            self.kill_switch.record_failure();
+        }
+        result
+    }
+
     /// Processes an entire arbitrage chain by sequentially placing orders.
-    /// Computes quantities based on previous fills and logs the final profit.
+    /// Computes quantities based on previous fills and returns the realized profit.
+    /// If a leg fails after earlier legs filled, those are unwound when enabled.
     async fn process_chain_orders(
         &self,
         ws_writer: &mut WebsocketWriter,
         chain: ChainOrders,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<Decimal> {
         let mut filled_sizes = Vec::with_capacity(chain.orders.len());
         let mut last_filled_qty: Option<Decimal> = None;
+        let started = Instant::now();
 
         for (idx, order) in chain.orders.iter().enumerate() {
+            if let Some(held_qty) = last_filled_qty
+                && let Some(max_execution) = self.max_chain_execution
+                && started.elapsed() > max_execution
+            {
+                warn!(
+                    chain_id = %chain.chain_id,
+                    order_index = idx + 1,
+                    elapsed_ms = started.elapsed().as_millis(),
+                    max_execution_ms = max_execution.as_millis(),
+                    "⏱️ [Engine] Chain execution deadline exceeded, aborting"
+                );
+                if self.unwind_on_failure {
+                    // Failure is logged with the stranded balance inside.
+                    _ = self.unwind_filled_legs(&chain, idx, held_qty).await;
+                }
+                bail!(
+                    "Chain {} exceeded execution deadline of {} ms before leg {}",
+                    chain.chain_id,
+                    max_execution.as_millis(),
+                    idx + 1
+                );
+            }
+
+            // A short fill may leave the next leg below the symbol's minimum quantity.
+            if let Some(held_qty) = last_filled_qty
+                && let Err(e) = check_leg_min_qty(order, held_qty)
+            {
+                warn!(
+                    chain_id = %chain.chain_id,
+                    order_index = idx + 1,
+                    symbol = %order.symbol,
+                    held_qty = %held_qty,
+                    error = %e,
+                    "🛑 [Engine] Leg below the minimum quantity, aborting"
+                );
+                if self.unwind_on_failure {
+                    // Failure is logged with the stranded balance inside.
+                    _ = self.unwind_filled_legs(&chain, idx, held_qty).await;
+                }
+                return Err(e.context(format!(
+                    "Chain {} aborted before leg {}",
+                    chain.chain_id,
+                    idx + 1
+                )));
+            }
+
+            let order_type = OrderType::from(order.order_type);
             let (base_qty, quote_qty) = if let Some(filled_size) = last_filled_qty {
-                Self::compute_order_quantities(order, filled_size)
+                Self::compute_order_quantities(
+                    order,
+                    filled_size,
+                    &order_type,
+                    self.desc_leg_quantity,
+                )
             } else {
-                define_order_quantities(order)
+                define_order_quantities(order, &order_type, self.desc_leg_quantity)
             };
 
-            let request = Self::build_place_order_request(order, base_qty, quote_qty);
-            let (filled_size, stats_filled_size) =
-                Self::process_order_request(ws_writer, chain.clone(), idx, request).await?;
+            if self.verify_leg_input
+                && let Some(held_qty) = last_filled_qty
+                && let Err(e) =
+                    check_leg_input(order, base_qty.as_deref(), quote_qty.as_deref(), held_qty)
+            {
+                warn!(
+                    chain_id = %chain.chain_id,
+                    order_index = idx + 1,
+                    symbol = %order.symbol,
+                    held_qty = %held_qty,
+                    error = %e,
+                    "🛑 [Engine] Leg input not available, aborting"
+                );
+                if self.unwind_on_failure {
+                    // Failure is logged with the stranded balance inside.
+                    _ = self.unwind_filled_legs(&chain, idx, held_qty).await;
+                }
+                return Err(e.context(format!(
+                    "Chain {} aborted before leg {}",
+                    chain.chain_id,
+                    idx + 1
+                )));
+            }
+
+            let request = self.build_place_order_request(order, base_qty, quote_qty);
+            let (filled_size, stats_filled_size) = match self
+                .process_order_request(ws_writer, chain.clone(), idx, request)
+                .await
+            {
+                Ok(filled) => filled,
+                Err(e) => {
+                    if let Some(held_qty) = last_filled_qty
+                        && self.unwind_on_failure
+                    {
+                        // Failure is logged with the stranded balance inside.
+                        _ = self.unwind_filled_legs(&chain, idx, held_qty).await;
+                    }
+                    return Err(e);
+                }
+            };
 
             last_filled_qty = Some(filled_size);
             filled_sizes.push(stats_filled_size);
@@ -220,26 +677,220 @@ impl SenderService {
             "✅ [Engine] Chain completed: profit calculated"
         );
 
+        // Nobody may be listening for filled chains, which is fine.
+        _ = FILLED_CHAINS.send(FilledChain {
+            chain,
+            fills: filled_sizes,
+            profit,
+        });
+
+        Ok(profit)
+    }
+
+    /// Reverses the first `filled_legs` legs of a chain, last leg first, with market orders,
+    /// starting from `held_qty` of the asset the last filled leg bought. Returns the quantity
+    /// of the base asset recovered, or the balance stranded by a failed unwind order.
+    async fn unwind_filled_legs(
+        &self,
+        chain: &ChainOrders,
+        filled_legs: usize,
+        held_qty: Decimal,
+    ) -> Result<Decimal, StrandedBalance> {
+        let assets = held_assets(chain);
+        let mut held_qty = held_qty;
+
+        for idx in (0..filled_legs).rev() {
+            let order = &chain.orders[idx];
+            let request = self.build_unwind_order_request(order, held_qty);
+            let order_side = request.order_side.clone();
+
+            let result = async {
+                Self::wait_for_weight(WebsocketApi::PlaceOrder.weight()).await?;
+                self.trade_api.send_order(request).await
+            }
+            .await;
+
+            let response = match result {
+                Ok(response) => response,
+                Err(e) => {
+                    let stranded = StrandedBalance {
+                        asset: assets[idx].clone(),
+                        qty: held_qty,
+                    };
+                    error!(
+                        chain_id = %chain.chain_id,
+                        order_index = idx + 1,
+                        symbol = %order.symbol,
+                        asset = %stranded.asset,
+                        stranded_qty = %stranded.qty,
+                        error = ?e,
+                        "❌ [Engine] Failed to unwind leg, balance stranded"
+                    );
+                    return Err(stranded);
+                }
+            };
+
+            // Undoing a sell buys the base asset back, undoing a buy returns the quote.
+            held_qty = match order.symbol_order {
+                SymbolOrder::Asc => response.executed_qty,
+                SymbolOrder::Desc => response.cummulative_quote_qty,
+            };
+
+            info!(
+                chain_id = %chain.chain_id,
+                order_index = idx + 1,
+                symbol = %order.symbol,
+                order_id = response.order_id,
+                order_side = %order_side,
+                held_qty = %held_qty,
+                "↩️ [Engine] Leg unwound"
+            );
+        }
+
+        Ok(held_qty)
+    }
+
+    /// Builds the market order reversing a filled leg with `held_qty` of the asset it bought.
+    fn build_unwind_order_request(
+        &self,
+        order: &ChainOrder,
+        held_qty: Decimal,
+    ) -> SendOrderRequest {
+        let (order_side, quantity, quote_order_qty) = match order.symbol_order {
+            // The leg sold the base asset, buy it back spending the quote it returned.
+            SymbolOrder::Asc => (OrderSide::Buy, None, Some(held_qty)),
+            // The leg bought the base asset, sell it rounded down to the lot step.
+            SymbolOrder::Desc => {
+                let qty = (held_qty / order.base_increment).floor() * order.base_increment;
+                (OrderSide::Sell, Some(qty.normalize()), None)
+            }
+        };
+
+        SendOrderRequest {
+            symbol: order.symbol.clone(),
+            order_side,
+            order_type: OrderType::Market,
+            time_in_force: None,
+            quantity,
+            quote_order_qty,
+            price: None,
+            new_client_order_id: None,
+            strategy_id: None,
+            strategy_type: None,
+            stop_price: None,
+            trailing_delta: None,
+            iceberg_qty: None,
+            new_order_resp_type: None,
+            self_trade_prevention_mode: self.self_trade_prevention_mode.clone(),
+            recv_window: None,
+        }
+    }
+
+    /// Logs the signed request of every chain leg, as it would be sent, without sending it.
+    fn log_shadow_orders(&self, chain: &ChainOrders) -> anyhow::Result<()> {
+        let timestamp = utils::get_timestamp(SystemTime::now())?;
+        for (idx, payload) in self
+            .shadow_order_payloads(chain, timestamp)?
+            .iter()
+            .enumerate()
+        {
+            info!(
+                chain_id = %chain.chain_id,
+                order_index = idx + 1,
+                ws_url = %self.ws_url,
+                payload = %payload,
+                "🕶️ [Engine] Shadow order, not sent"
+            );
+        }
         Ok(())
     }
 
+    /// Builds the signed `order.place` payload of every leg at the chain's planned quantities.
+    /// Later legs assume the previous leg fills exactly as planned.
+    fn shadow_order_payloads(
+        &self,
+        chain: &ChainOrders,
+        timestamp: u64,
+    ) -> anyhow::Result<Vec<String>> {
+        chain
+            .orders
+            .iter()
+            .map(|order| {
+                let (base_qty, quote_qty) = define_order_quantities(
+                    order,
+                    &order.order_type.into(),
+                    self.desc_leg_quantity,
+                );
+                let request = sign_place_order(
+                    self.build_place_order_request(order, base_qty, quote_qty),
+                    &self.api_token,
+                    &self.api_secret_key,
+                    timestamp,
+                );
+                serde_json::to_string(&serde_json::json!({
+                    "method": String::from(WebsocketApi::PlaceOrder),
+                    "params": request,
+                }))
+                .context("Failed to serialize shadow order")
+            })
+            .collect()
+    }
+
     /// Places a single order via WebSocket and extracts filled quantities.
     /// Handles special logic for the first order in ascending chains.
     async fn process_order_request(
+        &self,
         ws_writer: &mut WebsocketWriter,
         chain: ChainOrders,
         order_idx: usize,
         request: PlaceOrderRequest,
     ) -> anyhow::Result<(Decimal, Decimal)> {
-        Self::wait_for_weight(WebsocketApi::PlaceOrder).await?;
+        Self::wait_for_weight(WebsocketApi::PlaceOrder.weight()).await?;
         let response = ws_writer
             .place_order(request.clone())
             .await
             .with_context(|| "Failed to place order")?;
 
+        if order_idx == 0
+            && let Some(queued_at) = chain.queued_at
+        {
+            METRICS.record_first_leg_ack_latency(queued_at.elapsed());
+        }
+
+        // Market orders are normally final here, anything else keeps an open order slot.
+        self.open_orders
+            .update(
+                &response.symbol,
+                &response.client_order_id,
+                order_status(&response.status),
+            )
+            .await;
+
+        // An IOC limit leg expires unfilled once the book moves past the chain price.
+        if response.executed_qty.is_zero() {
+            bail!(
+                "Order {} on {} was not filled, status {}",
+                response.order_id,
+                response.symbol,
+                response.status
+            );
+        }
+
         let executed_qty = response.executed_qty;
         let cummulative_quote_qty = response.cummulative_quote_qty;
 
+        // The rest of a partially filled IOC leg is cancelled, the next leg spends what filled.
+        if response.status == OrderStatus::Expired {
+            warn!(
+                chain_id = %chain.chain_id,
+                order_index = order_idx + 1,
+                symbol = %response.symbol,
+                order_id = response.order_id,
+                executed_qty = %executed_qty,
+                "✂️ [Engine] Leg partially filled, the rest was cancelled"
+            );
+        }
+
         let filled_qty = match chain.orders[order_idx].symbol_order {
             SymbolOrder::Asc => cummulative_quote_qty,
             SymbolOrder::Desc => executed_qty,
@@ -268,24 +919,27 @@ impl SenderService {
         Ok((filled_qty, stats_filled_qty))
     }
 
-    /// Computes order quantities based on the previous filled size and symbol direction.
+    /// Computes order quantities from the amount the previous leg actually filled, truncated
+    /// to the lot step so the leg never spends more than was received.
     fn compute_order_quantities(
         order: &ChainOrder,
         filled_size: Decimal,
+        order_type: &OrderType,
+        desc_quantity: DescLegQuantity,
     ) -> (Option<String>, Option<String>) {
-        let size = (filled_size / order.base_increment).round() * order.base_increment;
-
-        match order.symbol_order {
-            SymbolOrder::Asc => (Some(size.to_string()), None),
-            SymbolOrder::Desc => (None, Some(size.to_string())),
-        }
+        leg_quantities(
+            order,
+            truncate_to_step(filled_size, order.base_increment),
+            order_type,
+            desc_quantity,
+        )
     }
 
     /// Waits for available API weight before proceeding with a request.
     /// Uses a global mutex to track and increment weights.
-    async fn wait_for_weight(api: WebsocketApi) -> anyhow::Result<()> {
+    async fn wait_for_weight(weight: u16) -> anyhow::Result<()> {
         loop {
-            if REQUEST_WEIGHT.lock().await.add(api.weight() as usize) {
+            if REQUEST_WEIGHT.lock().await.add(weight as usize) {
                 break;
             }
             tokio::time::sleep(Duration::from_secs(1)).await;
@@ -294,19 +948,26 @@ impl SenderService {
     }
 
     /// Builds a `PlaceOrderRequest` payload from order details and quantities.
+    /// Limit legs are immediate-or-cancel at the chain price, market legs carry no price.
     fn build_place_order_request(
+        &self,
         order: &ChainOrder,
         base_qty: Option<String>,
         quote_qty: Option<String>,
     ) -> PlaceOrderRequest {
+        let (time_in_force, price) = match order.order_type {
+            enums::OrderType::Market => (None, None),
+            enums::OrderType::Limit => (Some(TimeInForce::Ioc), Some(order.price.to_string())),
+        };
+
         PlaceOrderRequest {
             symbol: order.symbol.clone(),
             order_side: define_order_side(order),
-            order_type: OrderType::Market,
-            time_in_force: None,
+            order_type: order.order_type.into(),
+            time_in_force,
             quantity: base_qty,
             quote_order_qty: quote_qty,
-            price: None,
+            price,
             new_client_order_id: None,
             strategy_id: None,
             strategy_type: None,
@@ -314,7 +975,7 @@ impl SenderService {
             trailing_delta: None,
             iceberg_qty: None,
             new_order_resp_type: None,
-            self_trade_prevention_mode: None,
+            self_trade_prevention_mode: self.self_trade_prevention_mode.clone(),
             recv_window: None,
             timestamp: None,
             api_key: None,
@@ -347,10 +1008,1134 @@ fn define_order_side(order: &ChainOrder) -> OrderSide {
     }
 }
 
+/// Returns the asset held after each leg, walking the chain from its base asset. Binance
+/// symbols concatenate base and quote, so a leg's other asset is its symbol without the
+/// asset it spends.
+fn held_assets(chain: &ChainOrders) -> Vec<String> {
+    let mut spent = chain.base_asset.clone();
+    chain
+        .orders
+        .iter()
+        .map(|order| {
+            let bought = match order.symbol_order {
+                SymbolOrder::Asc => order.symbol.strip_prefix(spent.as_str()),
+                SymbolOrder::Desc => order.symbol.strip_suffix(spent.as_str()),
+            };
+            spent = bought.unwrap_or(order.symbol.as_str()).to_owned();
+            spent.clone()
+        })
+        .collect()
+}
+
+/// Maps a Binance order status onto the exchange-agnostic one.
+fn order_status(status: &OrderStatus) -> enums::OrderStatus {
+    match status {
+        OrderStatus::New | OrderStatus::PendingCancel => enums::OrderStatus::New,
+        OrderStatus::PartiallyFilled => enums::OrderStatus::PartiallyFilled,
+        OrderStatus::Filled => enums::OrderStatus::Filled,
+        OrderStatus::Canceled => enums::OrderStatus::Canceled,
+        OrderStatus::Rejected => enums::OrderStatus::Rejected,
+        OrderStatus::Expired => enums::OrderStatus::Expired,
+    }
+}
+
 /// Defines initial quantities for the first order in a chain.
-fn define_order_quantities(order: &ChainOrder) -> (Option<String>, Option<String>) {
+fn define_order_quantities(
+    order: &ChainOrder,
+    order_type: &OrderType,
+    desc_quantity: DescLegQuantity,
+) -> (Option<String>, Option<String>) {
+    leg_quantities(order, order.base_qty, order_type, desc_quantity)
+}
+
+/// Rounds `qty` down to a multiple of `step`.
+fn truncate_to_step(qty: Decimal, step: Decimal) -> Decimal {
+    (qty / step).floor() * step
+}
+
+/// Checks that the `held_qty` the previous leg returned still sizes the leg at or above the
+/// symbol's minimum quantity. Desc legs buy the held quote at the chain price.
+fn check_leg_min_qty(order: &ChainOrder, held_qty: Decimal) -> anyhow::Result<()> {
+    let input_qty = truncate_to_step(held_qty, order.base_increment);
+    let base_qty = match order.symbol_order {
+        SymbolOrder::Asc => input_qty,
+        SymbolOrder::Desc => input_qty / order.price,
+    };
+
+    if base_qty < order.min_qty {
+        bail!(
+            "{} would trade {} with {held_qty} held, below the minimum quantity {}",
+            order.symbol,
+            base_qty.normalize(),
+            order.min_qty
+        );
+    }
+    Ok(())
+}
+
+/// Checks that a leg placed with `(quantity, quoteOrderQty)` spends no more than the
+/// `held_qty` of its input asset the previous leg returned. Desc legs sized in base spend
+/// their quantity at the chain price.
+fn check_leg_input(
+    order: &ChainOrder,
+    base_qty: Option<&str>,
+    quote_qty: Option<&str>,
+    held_qty: Decimal,
+) -> anyhow::Result<()> {
+    let parse = |qty: Option<&str>| -> anyhow::Result<Decimal> {
+        qty.context("Leg quantity is missing")?
+            .parse::<Decimal>()
+            .with_context(|| format!("Invalid leg quantity {qty:?}"))
+    };
+
+    let required = match (order.symbol_order, quote_qty) {
+        (SymbolOrder::Desc, Some(_)) => parse(quote_qty)?,
+        (SymbolOrder::Desc, None) => parse(base_qty)? * order.price,
+        (SymbolOrder::Asc, _) => parse(base_qty)?,
+    };
+
+    if required > held_qty {
+        bail!(
+            "{} needs {required} of its input asset, only {held_qty} is held",
+            order.symbol
+        );
+    }
+    Ok(())
+}
+
+/// Maps the amount a leg spends onto `(quantity, quoteOrderQty)`.
+///
+/// Asc legs sell `input_qty` of the base asset. Desc legs spend `input_qty` of the quote asset,
+/// either directly as `quoteOrderQty` or, since that is only accepted for market orders, as the
+/// base quantity it buys at the chain price, rounded down to the lot step.
+fn leg_quantities(
+    order: &ChainOrder,
+    input_qty: Decimal,
+    order_type: &OrderType,
+    desc_quantity: DescLegQuantity,
+) -> (Option<String>, Option<String>) {
     match order.symbol_order {
-        SymbolOrder::Asc => (Some(order.base_qty.to_string()), None),
-        SymbolOrder::Desc => (None, Some(order.base_qty.to_string())),
+        SymbolOrder::Asc => (Some(input_qty.to_string()), None),
+        SymbolOrder::Desc
+            if *order_type == OrderType::Market && desc_quantity == DescLegQuantity::Quote =>
+        {
+            (None, Some(input_qty.to_string()))
+        }
+        SymbolOrder::Desc => {
+            let base_qty =
+                (input_qty / order.price / order.base_increment).floor() * order.base_increment;
+            (Some(base_qty.normalize().to_string()), None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::{SinkExt, StreamExt};
+    use mockito::{Matcher, Server};
+    use tokio::{net::TcpListener, sync::mpsc};
+    use tokio_tungstenite::{accept_async, tungstenite::Message};
+
+    use super::*;
+
+    async fn sender(api_url: String) -> SenderService {
+        REQUEST_WEIGHT.lock().await.set_weight_limit(6000);
+
+        let api_config = binance_client::ClientConfig {
+            api_url,
+            api_token: "test_api_key".to_owned(),
+            api_secret_key: "test_secret_key".to_owned(),
+            http_config: binance_client::HttpConfig::default(),
+        };
+
+        SenderService {
+            send_orders: true,
+            shadow_orders: false,
+            process_chain_interval: Duration::from_secs(10),
+            min_profit_persist: Duration::ZERO,
+            min_price_change_bps: None,
+            max_chain_executions: None,
+            chain_executions_window: Duration::from_secs(3600),
+            profit_targets: HashMap::new(),
+            max_daily_losses: HashMap::new(),
+            size_ramp: None,
+            exit_on_session_profit_target: false,
+            ws_url: String::new(),
+            api_token: "test_api_key".to_owned(),
+            api_secret_key: "test_secret_key".to_owned(),
+            self_trade_prevention_mode: None,
+            desc_leg_quantity: DescLegQuantity::Quote,
+            unwind_on_failure: false,
+            max_chain_execution: None,
+            verify_leg_input: false,
+            pause_on_rate_limit: false,
+            trade_api: Binance::new(api_config.clone()).unwrap(),
+            general_api: Binance::new(api_config).unwrap(),
+            clock_drift: None,
+            opportunity_watchdog: None,
+            open_orders: Arc::new(OpenOrders::new(None)),
+            kill_switch: Arc::default(),
+        }
+    }
+
+    fn order_body(status: &str) -> String {
+        format!(
+            r#"{{
+                "symbol": "BTCUSDT",
+                "orderId": 12569099453,
+                "orderListId": -1,
+                "clientOrderId": "arb-1",
+                "price": "0.00000000",
+                "origQty": "0.00100000",
+                "executedQty": "0.00050000",
+                "cummulativeQuoteQty": "50.00000000",
+                "status": "{status}",
+                "timeInForce": "GTC",
+                "type": "MARKET",
+                "side": "BUY",
+                "stopPrice": "0.00000000",
+                "time": 1660801715639,
+                "updateTime": 1660801717945,
+                "isWorking": true,
+                "workingTime": 1660801715639,
+                "origQuoteOrderQty": "0.00000000"
+            }}"#
+        )
+    }
+
+    #[tokio::test]
+    async fn test_query_order_maps_status() -> anyhow::Result<()> {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock(
+                "GET",
+                Matcher::Regex(
+                    r"^/api/v3/order\?.*symbol=BTCUSDT.*origClientOrderId=arb-1.*signature="
+                        .to_owned(),
+                ),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(order_body("PARTIALLY_FILLED"))
+            .create_async()
+            .await;
+
+        let status = sender(server.url())
+            .await
+            .query_order("BTCUSDT", "arb-1")
+            .await?;
+
+        mock.assert_async().await;
+        assert_eq!(status, enums::OrderStatus::PartiallyFilled);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_order_requests_use_dedicated_trade_client() -> anyhow::Result<()> {
+        let mut market_server = Server::new_async().await;
+        let market = market_server
+            .mock("POST", Matcher::Any)
+            .expect(0)
+            .create_async()
+            .await;
+        let mut trade_server = Server::new_async().await;
+        let trade = trade_server
+            .mock("POST", "/api/v3/order")
+            .match_query(Matcher::UrlEncoded(
+                "symbol".to_owned(),
+                "BTCUSDT".to_owned(),
+            ))
+            .match_header("x-mbx-apikey", "trade_api_key")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(place_order_body("BTCUSDT", "SELL", "0.00123", "122.9"))
+            .expect(1)
+            .create_async()
+            .await;
+
+        let mut sender = sender(market_server.url()).await;
+        sender.trade_api = Binance::new(binance_client::ClientConfig {
+            api_url: trade_server.url(),
+            api_token: "trade_api_key".to_owned(),
+            api_secret_key: "trade_secret_key".to_owned(),
+            http_config: binance_client::HttpConfig::default(),
+        })?;
+
+        let recovered = sender
+            .unwind_filled_legs(&unwind_chain(), 1, Decimal::new(123, 5))
+            .await;
+
+        assert_eq!(recovered, Ok(Decimal::new(1229, 1)));
+        trade.assert_async().await;
+        market.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_query_order_unknown_order_fails() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", Matcher::Regex(r"^/api/v3/order\?".to_owned()))
+            .with_status(400)
+            .with_body(r#"{"code": -2013, "msg": "Order does not exist."}"#)
+            .create_async()
+            .await;
+
+        let result = sender(server.url())
+            .await
+            .query_order("BTCUSDT", "missing")
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    fn chain_order() -> ChainOrder {
+        ChainOrder {
+            symbol: "BTCUSDT".to_owned(),
+            symbol_order: SymbolOrder::Desc,
+            order_type: enums::OrderType::Market,
+            price: Decimal::from(100_000),
+            base_qty: Decimal::from(100),
+            quote_qty: Decimal::new(1, 3),
+            base_increment: Decimal::new(1, 5),
+            quote_increment: Decimal::new(1, 2),
+            min_qty: Decimal::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_leg_quantity_field_per_direction_and_order_type() {
+        let desc = chain_order();
+        let asc = ChainOrder {
+            symbol_order: SymbolOrder::Asc,
+            base_qty: Decimal::new(1, 3),
+            ..chain_order()
+        };
+        let quantities = |order: &ChainOrder, order_type: OrderType, mode: DescLegQuantity| {
+            define_order_quantities(order, &order_type, mode)
+        };
+
+        // Asc legs always sell a base quantity.
+        assert_eq!(
+            quantities(&asc, OrderType::Market, DescLegQuantity::Quote),
+            (Some("0.001".to_owned()), None)
+        );
+
+        // Desc market legs spend the quote amount directly.
+        assert_eq!(
+            quantities(&desc, OrderType::Market, DescLegQuantity::Quote),
+            (None, Some("100".to_owned()))
+        );
+
+        // 100 USDT at 100000 buys 0.001 BTC.
+        assert_eq!(
+            quantities(&desc, OrderType::Market, DescLegQuantity::Base),
+            (Some("0.001".to_owned()), None)
+        );
+
+        // quoteOrderQty is market-only, limit legs fall back to the base quantity.
+        assert_eq!(
+            quantities(&desc, OrderType::Limit, DescLegQuantity::Quote),
+            (Some("0.001".to_owned()), None)
+        );
+
+        // Later legs spend the previous fill, rounded down to the lot step when converted.
+        assert_eq!(
+            SenderService::compute_order_quantities(
+                &desc,
+                Decimal::new(9_999_999, 5),
+                &OrderType::Market,
+                DescLegQuantity::Base,
+            ),
+            (Some("0.00099".to_owned()), None)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_place_order_request_includes_configured_stp() -> anyhow::Result<()> {
+        let mut sender = sender(String::new()).await;
+        let (base_qty, quote_qty) =
+            define_order_quantities(&chain_order(), &OrderType::Market, DescLegQuantity::Quote);
+
+        let request =
+            sender.build_place_order_request(&chain_order(), base_qty.clone(), quote_qty.clone());
+        assert!(!serde_json::to_string(&request)?.contains("selfTradePreventionMode"));
+
+        sender.self_trade_prevention_mode = Some(SelfTradePreventionMode::ExpireMaker);
+        let request = sender.build_place_order_request(&chain_order(), base_qty, quote_qty);
+        assert!(
+            serde_json::to_string(&request)?
+                .contains(r#""selfTradePreventionMode":"EXPIRE_MAKER""#)
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_limit_leg_is_placed_at_chain_price() -> anyhow::Result<()> {
+        let sender = sender(String::new()).await;
+        let order = ChainOrder {
+            order_type: enums::OrderType::Limit,
+            ..chain_order()
+        };
+        let (base_qty, quote_qty) =
+            define_order_quantities(&order, &order.order_type.into(), DescLegQuantity::Quote);
+
+        let request = sender.build_place_order_request(&order, base_qty, quote_qty);
+        let json: serde_json::Value = serde_json::to_value(&request)?;
+        assert_eq!(json["type"], "LIMIT");
+        assert_eq!(json["timeInForce"], "IOC");
+        assert_eq!(json["price"], "100000");
+        assert_eq!(json["quantity"], "0.001");
+        assert!(json.get("quoteOrderQty").is_none());
+
+        let request =
+            sender.build_place_order_request(&chain_order(), None, Some("100".to_owned()));
+        let json: serde_json::Value = serde_json::to_value(&request)?;
+        assert_eq!(json["type"], "MARKET");
+        assert!(json.get("price").is_none());
+        assert!(json.get("timeInForce").is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shadow_order_payloads_match_chain() -> anyhow::Result<()> {
+        let sender = sender(String::new()).await;
+        let chain = ChainOrders {
+            orders: vec![
+                chain_order(),
+                ChainOrder {
+                    symbol: "ETHBTC".to_owned(),
+                    price: Decimal::new(3, 2),
+                    base_qty: Decimal::new(1, 3),
+                    quote_qty: Decimal::new(333, 4),
+                    base_increment: Decimal::new(1, 4),
+                    ..chain_order()
+                },
+                ChainOrder {
+                    symbol: "ETHUSDT".to_owned(),
+                    symbol_order: SymbolOrder::Asc,
+                    price: Decimal::from(3_010),
+                    base_qty: Decimal::new(333, 4),
+                    quote_qty: Decimal::new(10_023, 2),
+                    base_increment: Decimal::new(1, 4),
+                    ..chain_order()
+                },
+            ],
+            ..Default::default()
+        };
+        let timestamp = 1_760_000_000_000;
+
+        let expected = |symbol: &str, side: &str, qty_field: &str, qty: &str| {
+            let query = format!(
+                "apiKey=test_api_key&{qty_field}={qty}&side={side}&symbol={symbol}\
+                 &timestamp={timestamp}&type=MARKET"
+            );
+            let mut params = serde_json::json!({
+                "symbol": symbol,
+                "side": side,
+                "type": "MARKET",
+                "apiKey": "test_api_key",
+                "timestamp": timestamp,
+                "signature": utils::generate_signature("test_secret_key", Some(&query)),
+            });
+            params[qty_field] = qty.into();
+            serde_json::json!({"method": "order.place", "params": params})
+        };
+
+        let payloads = sender.shadow_order_payloads(&chain, timestamp)?;
+        let payloads: Vec<serde_json::Value> = payloads
+            .iter()
+            .map(|payload| serde_json::from_str(payload))
+            .collect::<Result<_, _>>()?;
+        assert_eq!(
+            payloads,
+            [
+                expected("BTCUSDT", "BUY", "quoteOrderQty", "100"),
+                expected("ETHBTC", "BUY", "quoteOrderQty", "0.001"),
+                expected("ETHUSDT", "SELL", "quantity", "0.0333"),
+            ]
+        );
+        assert!(
+            !payloads
+                .iter()
+                .any(|payload| payload.to_string().contains("test_secret_key"))
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_excessive_clock_drift_blocks_trading() -> anyhow::Result<()> {
+        let mut server = Server::new_async().await;
+        let server_time = tools::misc::time::get_current_timestamp().as_millis() + 60_000;
+        let mock = server
+            .mock("GET", "/api/v3/time")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_body(format!(r#"{{"serverTime": {server_time}}}"#))
+            .create_async()
+            .await;
+
+        let mut sender = sender(server.url()).await;
+        assert!(sender.trading_allowed());
+
+        sender.clock_drift = Some(Arc::new(ClockDrift::new(Duration::from_secs(1))));
+        let token = CancellationToken::new();
+        token.cancel();
+        sender.monitor_clock_drift(token).await;
+
+        mock.assert_async().await;
+        assert!(!sender.trading_allowed());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_chain_deferred_at_open_orders_cap() -> anyhow::Result<()> {
+        let mut server = Server::new_async().await;
+        let mut sender = sender(server.url()).await;
+        sender.open_orders = Arc::new(OpenOrders::new(Some(3)));
+        sender
+            .open_orders
+            .update("BTCUSDT", "arb-1", enums::OrderStatus::New)
+            .await;
+
+        let open = server
+            .mock("GET", Matcher::Regex(r"^/api/v3/order\?".to_owned()))
+            .with_status(200)
+            .with_body(order_body("NEW"))
+            .create_async()
+            .await;
+        assert!(!sender.has_open_orders_capacity(3).await);
+        open.assert_async().await;
+        open.remove_async().await;
+
+        // The order got filled in the meantime, so the chain can go.
+        server
+            .mock("GET", Matcher::Regex(r"^/api/v3/order\?".to_owned()))
+            .with_status(200)
+            .with_body(order_body("FILLED"))
+            .create_async()
+            .await;
+        assert!(sender.has_open_orders_capacity(3).await);
+        Ok(())
+    }
+
+    fn place_order_body(symbol: &str, side: &str, executed_qty: &str, quote_qty: &str) -> String {
+        order_fill_body(symbol, side, "FILLED", executed_qty, quote_qty)
+    }
+
+    fn order_fill_body(
+        symbol: &str,
+        side: &str,
+        status: &str,
+        executed_qty: &str,
+        quote_qty: &str,
+    ) -> String {
+        format!(
+            r#"{{
+                "symbol": "{symbol}",
+                "orderId": 28,
+                "orderListId": -1,
+                "clientOrderId": "arb-2",
+                "transactTime": 1507725176595,
+                "price": "0.00000000",
+                "origQty": "{executed_qty}",
+                "executedQty": "{executed_qty}",
+                "origQuoteOrderQty": "0.00000000",
+                "cummulativeQuoteQty": "{quote_qty}",
+                "status": "{status}",
+                "timeInForce": "GTC",
+                "type": "MARKET",
+                "side": "{side}",
+                "workingTime": 1507725176595,
+                "selfTradePreventionMode": "NONE",
+                "fills": []
+            }}"#
+        )
+    }
+
+    /// Serves the websocket API, answering every `order.place` request with the next result:
+    /// `Ok` with an order body or `Err` with an HTTP-like error status.
+    async fn spawn_order_ws_server(results: Vec<Result<String, u16>>) -> anyhow::Result<String> {
+        spawn_slow_order_ws_server(results, Duration::ZERO).await
+    }
+
+    /// Same as [`spawn_order_ws_server`], answering every request after `delay`.
+    async fn spawn_slow_order_ws_server(
+        results: Vec<Result<String, u16>>,
+        delay: Duration,
+    ) -> anyhow::Result<String> {
+        let (url, _) = spawn_recording_order_ws_server(results, delay).await?;
+        Ok(url)
+    }
+
+    /// Same as [`spawn_slow_order_ws_server`], also forwarding the params of every request.
+    async fn spawn_recording_order_ws_server(
+        results: Vec<Result<String, u16>>,
+        delay: Duration,
+    ) -> anyhow::Result<(String, mpsc::UnboundedReceiver<serde_json::Value>)> {
+        let (requests_tx, requests_rx) = mpsc::unbounded_channel();
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("ws://{}", listener.local_addr()?);
+        tokio::spawn(async move {
+            let Ok((stream, _)) = listener.accept().await else {
+                return;
+            };
+            let Ok(mut ws) = accept_async(stream).await else {
+                return;
+            };
+            let mut results = results.into_iter();
+            while let Some(Ok(Message::Text(text))) = ws.next().await {
+                let request: serde_json::Value = serde_json::from_str(&text).unwrap();
+                _ = requests_tx.send(request["params"].clone());
+                let id = &request["id"];
+                let response = match results.next() {
+                    Some(Ok(body)) => format!(r#"{{"id": {id}, "status": 200, "result": {body}}}"#),
+                    Some(Err(status)) => format!(
+                        r#"{{"id": {id}, "status": {status},
+                            "error": {{"code": -2010, "msg": "Order rejected"}}}}"#
+                    ),
+                    None => break,
+                };
+                tokio::time::sleep(delay).await;
+                if ws.send(Message::Text(response.into())).await.is_err() {
+                    break;
+                }
+            }
+        });
+        Ok((url, requests_rx))
+    }
+
+    /// USDT -> BTC -> ETH -> USDT, every leg a buy except the last.
+    fn unwind_chain() -> ChainOrders {
+        let leg = |symbol: &str, symbol_order, base_increment| ChainOrder {
+            symbol: symbol.to_owned(),
+            symbol_order,
+            order_type: enums::OrderType::Market,
+            price: Decimal::ONE,
+            base_qty: Decimal::from(100),
+            quote_qty: Decimal::ONE,
+            base_increment,
+            quote_increment: Decimal::ZERO,
+            min_qty: Decimal::ZERO,
+        };
+        ChainOrders {
+            base_asset: "USDT".to_owned(),
+            orders: vec![
+                leg("BTCUSDT", SymbolOrder::Desc, Decimal::new(1, 5)),
+                leg("ETHBTC", SymbolOrder::Desc, Decimal::new(1, 4)),
+                leg("ETHUSDT", SymbolOrder::Asc, Decimal::new(1, 4)),
+            ],
+            ..Default::default()
+        }
+    }
+
+    /// [`unwind_chain`] with every leg placed as an IOC limit order.
+    fn ioc_chain() -> ChainOrders {
+        let mut chain = unwind_chain();
+        for order in &mut chain.orders {
+            order.order_type = enums::OrderType::Limit;
+        }
+        chain
+    }
+
+    /// Runs [`ioc_chain`] against `results`, returning its outcome and the placed legs.
+    async fn run_ioc_chain(
+        results: Vec<Result<String, u16>>,
+    ) -> anyhow::Result<(anyhow::Result<Decimal>, Vec<serde_json::Value>)> {
+        let (ws_url, mut requests_rx) =
+            spawn_recording_order_ws_server(results, Duration::ZERO).await?;
+
+        let mut sender = sender(String::new()).await;
+        sender.ws_url = ws_url;
+
+        let token = CancellationToken::new();
+        let (mut ws_writer, message_handler, _) = sender.setup_websocket(token.clone()).await?;
+        let result = sender
+            .process_chain_orders(&mut ws_writer, ioc_chain())
+            .await;
+        token.cancel();
+        message_handler.abort();
+
+        let mut requests = vec![];
+        while let Ok(request) = requests_rx.try_recv() {
+            requests.push(request);
+        }
+        Ok((result, requests))
+    }
+
+    #[tokio::test]
+    async fn test_ioc_chain_fully_filled() -> anyhow::Result<()> {
+        let (result, requests) = run_ioc_chain(vec![
+            Ok(order_fill_body("BTCUSDT", "BUY", "FILLED", "100", "100")),
+            Ok(order_fill_body("ETHBTC", "BUY", "FILLED", "100", "100")),
+            Ok(order_fill_body("ETHUSDT", "SELL", "FILLED", "100", "101")),
+        ])
+        .await?;
+
+        assert_eq!(result?, Decimal::ONE);
+        assert_eq!(requests.len(), 3);
+        for request in &requests {
+            assert_eq!(request["type"], "LIMIT");
+            assert_eq!(request["timeInForce"], "IOC");
+        }
+        assert_eq!(requests[1]["quantity"], "100");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ioc_partial_fill_sizes_next_leg_from_executed_qty() -> anyhow::Result<()> {
+        // Leg 1 fills 40 of 100 BTC before its rest is cancelled.
+        let (result, requests) = run_ioc_chain(vec![
+            Ok(order_fill_body("BTCUSDT", "BUY", "EXPIRED", "40", "40")),
+            Ok(order_fill_body("ETHBTC", "BUY", "FILLED", "40", "40")),
+            Ok(order_fill_body("ETHUSDT", "SELL", "FILLED", "40", "40.4")),
+        ])
+        .await?;
+
+        assert_eq!(result?, Decimal::new(4, 1));
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0]["quantity"], "100");
+        assert_eq!(requests[1]["quantity"], "40");
+        assert_eq!(requests[2]["quantity"], "40.0000");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ioc_unfilled_leg_aborts_chain() -> anyhow::Result<()> {
+        let (result, requests) = run_ioc_chain(vec![
+            Ok(order_fill_body("BTCUSDT", "BUY", "EXPIRED", "0", "0")),
+            Ok(order_fill_body("ETHBTC", "BUY", "FILLED", "100", "100")),
+        ])
+        .await?;
+
+        let err = result.expect_err("an unfilled IOC leg must abort the chain");
+        assert!(err.to_string().contains("was not filled"), "{err}");
+        assert_eq!(requests.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_failed_middle_leg_is_unwound() -> anyhow::Result<()> {
+        let mut server = Server::new_async().await;
+        let unwind = server
+            .mock("POST", "/api/v3/order")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("symbol".to_owned(), "BTCUSDT".to_owned()),
+                Matcher::UrlEncoded("side".to_owned(), "SELL".to_owned()),
+                Matcher::UrlEncoded("type".to_owned(), "MARKET".to_owned()),
+                Matcher::UrlEncoded("quantity".to_owned(), "0.00123".to_owned()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(place_order_body("BTCUSDT", "SELL", "0.00123", "122.9"))
+            .expect(1)
+            .create_async()
+            .await;
+
+        // Leg 1 buys 0.001234 BTC for 123 USDT, leg 2 is rejected.
+        let ws_url = spawn_order_ws_server(vec![
+            Ok(place_order_body("BTCUSDT", "BUY", "0.001234", "123")),
+            Err(400),
+        ])
+        .await?;
+
+        let mut sender = sender(server.url()).await;
+        sender.ws_url = ws_url;
+        sender.unwind_on_failure = true;
+
+        let token = CancellationToken::new();
+        let (mut ws_writer, message_handler, _) = sender.setup_websocket(token.clone()).await?;
+        let result = sender
+            .process_chain_orders(&mut ws_writer, unwind_chain())
+            .await;
+        token.cancel();
+        message_handler.abort();
+
+        assert!(result.is_err());
+        unwind.assert_async().await;
+        Ok(())
+    }
+
+    /// Serializes the tests driving the sender through the global orders channel.
+    static ORDERS_CHANNEL_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+    #[tokio::test]
+    async fn test_failed_chain_stops_sending_without_kill_switch() -> anyhow::Result<()> {
+        let _channel = ORDERS_CHANNEL_LOCK.lock().await;
+        let (ws_url, mut requests_rx) =
+            spawn_recording_order_ws_server(vec![Err(400)], Duration::ZERO).await?;
+
+        let mut sender = sender(String::new()).await;
+        sender.ws_url = ws_url;
+        sender.process_chain_interval = Duration::ZERO;
+
+        let sending = tokio::spawn({
+            let sender = sender.clone();
+            async move {
+                sender
+                    .receive_and_send_orders(CancellationToken::new())
+                    .await
+            }
+        });
+
+        ORDERS_CHANNEL.publish(ioc_chain())?;
+        tokio::time::timeout(Duration::from_secs(5), requests_rx.recv())
+            .await?
+            .expect("leg placed");
+
+        // The default config sets no kill switch: the failed chain stops the sender.
+        tokio::time::timeout(Duration::from_secs(5), sending).await???;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_failed_chains_trip_the_kill_switch() -> anyhow::Result<()> {
+        let _channel = ORDERS_CHANNEL_LOCK.lock().await;
+        // First legs rejected twice, then a chain filled, then rejected three times.
+        let (ws_url, mut requests_rx) = spawn_recording_order_ws_server(
+            vec![
+                Err(400),
+                Err(400),
+                Ok(order_fill_body("BTCUSDT", "BUY", "FILLED", "100", "100")),
+                Ok(order_fill_body("ETHBTC", "BUY", "FILLED", "100", "100")),
+                Ok(order_fill_body("ETHUSDT", "SELL", "FILLED", "100", "101")),
+                Err(400),
+                Err(400),
+                Err(400),
+            ],
+            Duration::ZERO,
+        )
+        .await?;
+
+        let mut sender = sender(String::new()).await;
+        sender.ws_url = ws_url;
+        sender.process_chain_interval = Duration::ZERO;
+        sender.kill_switch = Arc::new(KillSwitch::new(Some(3)));
+
+        let token = CancellationToken::new();
+        let sending = tokio::spawn({
+            let sender = sender.clone();
+            let token = token.clone();
+            async move { sender.receive_and_send_orders(token).await }
+        });
+        let kill_switch_rejections = || async {
+            let stats = RUN_STATS.lock().await.summary(Instant::now());
+            stats
+                .rejections
+                .get("kill_switch")
+                .copied()
+                .unwrap_or_default()
+        };
+
+        // A failed chain no longer stops the sender: every chain is placed until the kill
+        // switch trips, the filled one starting the count over.
+        for legs in [1, 1, 3, 1, 1] {
+            ORDERS_CHANNEL.publish(ioc_chain())?;
+            for _ in 0..legs {
+                tokio::time::timeout(Duration::from_secs(5), requests_rx.recv())
+                    .await?
+                    .expect("leg placed");
+            }
+            assert!(!sending.is_finished());
+        }
+        assert!(!sender.kill_switch.is_halted());
+
+        ORDERS_CHANNEL.publish(ioc_chain())?;
+        tokio::time::timeout(Duration::from_secs(5), requests_rx.recv()).await?;
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !sender.kill_switch.is_halted() {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await?;
+
+        // Halted: the next chain is rejected without placing anything.
+        ORDERS_CHANNEL.publish(ioc_chain())?;
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while kill_switch_rejections().await == 0 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await?;
+        assert!(requests_rx.try_recv().is_err());
+
+        token.cancel();
+        sending.await??;
+        Ok(())
+    }
+
+    #[test]
+    fn test_leg_input_checked_against_held_qty() {
+        let chain = unwind_chain();
+        let held_qty = Decimal::new(1_276, 6);
+
+        // 0.001276 BTC truncated to the 0.0001 step spends 0.0012 BTC, never more.
+        let (base_qty, quote_qty) = SenderService::compute_order_quantities(
+            &chain.orders[1],
+            held_qty,
+            &OrderType::Market,
+            DescLegQuantity::Quote,
+        );
+        assert_eq!(quote_qty.as_deref(), Some("0.0012"));
+        assert!(
+            check_leg_input(
+                &chain.orders[1],
+                base_qty.as_deref(),
+                quote_qty.as_deref(),
+                held_qty
+            )
+            .is_ok()
+        );
+        let error = check_leg_input(&chain.orders[1], None, Some("0.0013"), held_qty).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "ETHBTC needs 0.0013 of its input asset, only 0.001276 is held"
+        );
+
+        // Sized in base, the leg buys 0.0012 ETH at 1 BTC.
+        let held_qty = Decimal::new(1_234, 6);
+        let (base_qty, quote_qty) = SenderService::compute_order_quantities(
+            &chain.orders[1],
+            held_qty,
+            &OrderType::Market,
+            DescLegQuantity::Base,
+        );
+        assert_eq!(base_qty.as_deref(), Some("0.0012"));
+        assert!(
+            check_leg_input(
+                &chain.orders[1],
+                base_qty.as_deref(),
+                quote_qty.as_deref(),
+                held_qty
+            )
+            .is_ok()
+        );
+
+        assert!(
+            check_leg_input(&chain.orders[2], Some("0.0411"), None, Decimal::new(4, 2)).is_err()
+        );
+        assert!(check_leg_input(&chain.orders[2], Some("0.04"), None, Decimal::new(4, 2)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_partial_fill_sizes_next_leg_from_executed_qty() -> anyhow::Result<()> {
+        // Leg 1 buys 0.001276 BTC of the planned 100, leg 2 spends it truncated to 0.0012.
+        let (ws_url, mut requests_rx) = spawn_recording_order_ws_server(
+            vec![
+                Ok(place_order_body("BTCUSDT", "BUY", "0.001276", "127")),
+                Ok(place_order_body("ETHBTC", "BUY", "0.0425", "0.0012")),
+                Ok(place_order_body("ETHUSDT", "SELL", "0.0425", "128")),
+            ],
+            Duration::ZERO,
+        )
+        .await?;
+
+        let mut sender = sender(String::new()).await;
+        sender.ws_url = ws_url;
+        sender.verify_leg_input = true;
+
+        let token = CancellationToken::new();
+        let (mut ws_writer, message_handler, _) = sender.setup_websocket(token.clone()).await?;
+        let result = sender
+            .process_chain_orders(&mut ws_writer, unwind_chain())
+            .await;
+        token.cancel();
+        message_handler.abort();
+
+        assert_eq!(result?, Decimal::ONE);
+        requests_rx.recv().await;
+        let leg_2 = requests_rx.recv().await.expect("leg 2 is placed");
+        assert_eq!(leg_2["quoteOrderQty"], "0.0012");
+        let leg_3 = requests_rx.recv().await.expect("leg 3 is placed");
+        assert_eq!(leg_3["quantity"], "0.0425");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fill_below_next_leg_min_qty_aborts_and_unwinds() -> anyhow::Result<()> {
+        let mut server = Server::new_async().await;
+        let unwind = server
+            .mock("POST", "/api/v3/order")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("symbol".to_owned(), "BTCUSDT".to_owned()),
+                Matcher::UrlEncoded("side".to_owned(), "SELL".to_owned()),
+                Matcher::UrlEncoded("quantity".to_owned(), "0.00008".to_owned()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(place_order_body("BTCUSDT", "SELL", "0.00008", "7.9"))
+            .expect(1)
+            .create_async()
+            .await;
+
+        // Leg 1 buys only 0.00008 BTC, leg 2 needs at least 0.001 ETH at 1 BTC.
+        let (ws_url, mut requests_rx) = spawn_recording_order_ws_server(
+            vec![
+                Ok(place_order_body("BTCUSDT", "BUY", "0.00008", "8")),
+                Ok(place_order_body("ETHBTC", "BUY", "0.0001", "0.0001")),
+            ],
+            Duration::ZERO,
+        )
+        .await?;
+
+        let mut sender = sender(server.url()).await;
+        sender.ws_url = ws_url;
+        sender.unwind_on_failure = true;
+        let mut chain = unwind_chain();
+        chain.orders[1].min_qty = Decimal::new(1, 3);
+
+        let token = CancellationToken::new();
+        let (mut ws_writer, message_handler, _) = sender.setup_websocket(token.clone()).await?;
+        let result = sender.process_chain_orders(&mut ws_writer, chain).await;
+        token.cancel();
+        message_handler.abort();
+
+        let err = result.expect_err("a fill below the next leg's minimum must abort");
+        assert_eq!(
+            format!("{err:#}"),
+            format!(
+                "Chain {} aborted before leg 2: ETHBTC would trade 0 with 0.00008 held, below \
+                 the minimum quantity 0.001",
+                uuid::Uuid::nil()
+            )
+        );
+        unwind.assert_async().await;
+        requests_rx.recv().await;
+        assert!(requests_rx.try_recv().is_err(), "leg 2 must not be placed");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_slow_chain_aborted_past_deadline() -> anyhow::Result<()> {
+        let mut server = Server::new_async().await;
+        let sell_eth = server
+            .mock("POST", "/api/v3/order")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("symbol".to_owned(), "ETHBTC".to_owned()),
+                Matcher::UrlEncoded("side".to_owned(), "SELL".to_owned()),
+                Matcher::UrlEncoded("quantity".to_owned(), "0.0411".to_owned()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(place_order_body("ETHBTC", "SELL", "0.0411", "0.00123"))
+            .expect(1)
+            .create_async()
+            .await;
+        let sell_btc = server
+            .mock("POST", "/api/v3/order")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("symbol".to_owned(), "BTCUSDT".to_owned()),
+                Matcher::UrlEncoded("side".to_owned(), "SELL".to_owned()),
+                Matcher::UrlEncoded("quantity".to_owned(), "0.00123".to_owned()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(place_order_body("BTCUSDT", "SELL", "0.00123", "122.9"))
+            .expect(1)
+            .create_async()
+            .await;
+
+        // Both first legs fill, each taking 60 ms against a 100 ms deadline.
+        let ws_url = spawn_slow_order_ws_server(
+            vec![
+                Ok(place_order_body("BTCUSDT", "BUY", "0.001234", "123")),
+                Ok(place_order_body("ETHBTC", "BUY", "0.04115", "0.001234")),
+                Ok(place_order_body("ETHUSDT", "SELL", "0.04115", "124")),
+            ],
+            Duration::from_millis(60),
+        )
+        .await?;
+
+        let mut sender = sender(server.url()).await;
+        sender.ws_url = ws_url;
+        sender.unwind_on_failure = true;
+        sender.max_chain_execution = Some(Duration::from_millis(100));
+
+        let token = CancellationToken::new();
+        let (mut ws_writer, message_handler, _) = sender.setup_websocket(token.clone()).await?;
+        let result = sender
+            .process_chain_orders(&mut ws_writer, unwind_chain())
+            .await;
+        token.cancel();
+        message_handler.abort();
+
+        let err = result.expect_err("chain past its deadline must abort");
+        assert!(err.to_string().contains("before leg 3"), "{err}");
+        sell_eth.assert_async().await;
+        sell_btc.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_chain_within_deadline_completes() -> anyhow::Result<()> {
+        let ws_url = spawn_slow_order_ws_server(
+            vec![
+                Ok(place_order_body("BTCUSDT", "BUY", "0.001234", "123")),
+                Ok(place_order_body("ETHBTC", "BUY", "0.04115", "0.001234")),
+                Ok(place_order_body("ETHUSDT", "SELL", "0.04115", "124")),
+            ],
+            Duration::from_millis(10),
+        )
+        .await?;
+
+        let mut sender = sender(String::new()).await;
+        sender.ws_url = ws_url;
+        sender.max_chain_execution = Some(Duration::from_secs(5));
+
+        let token = CancellationToken::new();
+        let (mut ws_writer, message_handler, _) = sender.setup_websocket(token.clone()).await?;
+        let result = sender
+            .process_chain_orders(&mut ws_writer, unwind_chain())
+            .await;
+        token.cancel();
+        message_handler.abort();
+
+        assert!(result.is_ok(), "{result:?}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_failed_unwind_reports_stranded_balance() -> anyhow::Result<()> {
+        let mut server = Server::new_async().await;
+        let sell_eth = server
+            .mock("POST", "/api/v3/order")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("symbol".to_owned(), "ETHBTC".to_owned()),
+                Matcher::UrlEncoded("side".to_owned(), "SELL".to_owned()),
+                Matcher::UrlEncoded("quantity".to_owned(), "0.0411".to_owned()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(place_order_body("ETHBTC", "SELL", "0.0411", "0.00123"))
+            .create_async()
+            .await;
+        let sell_btc = server
+            .mock("POST", "/api/v3/order")
+            .match_query(Matcher::UrlEncoded(
+                "symbol".to_owned(),
+                "BTCUSDT".to_owned(),
+            ))
+            .with_status(400)
+            .with_body(r#"{"code": -2010, "msg": "Account has insufficient balance"}"#)
+            .create_async()
+            .await;
+
+        // Two legs filled, holding 0.04115 ETH: ETH goes back to BTC, BTC fails to sell.
+        let sender = sender(server.url()).await;
+        let result = sender
+            .unwind_filled_legs(&unwind_chain(), 2, Decimal::new(4115, 5))
+            .await;
+
+        sell_eth.assert_async().await;
+        sell_btc.assert_async().await;
+        assert_eq!(
+            result,
+            Err(StrandedBalance {
+                asset: "BTC".to_owned(),
+                qty: Decimal::new(123, 5),
+            })
+        );
+        assert_eq!(held_assets(&unwind_chain()), ["BTC", "ETH", "USDT"]);
+        Ok(())
     }
 }
@@ -1,9 +1,12 @@
 use std::collections::{HashMap, hash_map::Entry};
 
+use engine::METRICS;
 use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
 
 /// Changes in book ticker events (bid/ask updates for a symbol).
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct BookTickerEvent {
     /// Unique update ID for ordering events.
     pub update_id: u64,
@@ -17,6 +20,30 @@ pub struct BookTickerEvent {
     pub ask_price: Decimal,
     /// Ask quantity.
     pub ask_qty: Decimal,
+    /// Bid levels of a partial depth stream, best first (empty with top-of-book only).
+    #[serde(default)]
+    pub bids: Vec<BookLevel>,
+    /// Ask levels of a partial depth stream, best first (empty with top-of-book only).
+    #[serde(default)]
+    pub asks: Vec<BookLevel>,
+    /// When the event was received from the exchange.
+    #[serde(skip)]
+    pub received_at: Option<Instant>,
+}
+
+impl BookTickerEvent {
+    /// Returns whether the best bid is at or above the best ask.
+    #[must_use]
+    pub fn is_crossed(&self) -> bool {
+        !self.ask_price.is_zero() && self.bid_price >= self.ask_price
+    }
+}
+
+/// Price level of an order book side.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BookLevel {
+    pub price: Decimal,
+    pub qty: Decimal,
 }
 
 /// In-memory store for book ticker events, keyed by symbol.
@@ -24,6 +51,7 @@ pub struct BookTickerEvent {
 #[derive(Debug, Clone, Default)]
 pub struct BookTickerStore {
     data: HashMap<String, BookTickerEvent>,
+    allow_crossed: bool,
 }
 
 impl BookTickerStore {
@@ -32,21 +60,34 @@ impl BookTickerStore {
     pub fn new() -> Self {
         Self {
             data: HashMap::new(),
+            allow_crossed: false,
         }
     }
 
-    /// Updates the store with the given event if it has a newer update_id.
-    pub fn update(&mut self, event: BookTickerEvent) {
+    /// Stops reporting crossed books, see [`Self::is_crossed`].
+    #[must_use]
+    pub fn with_crossed_books_allowed(mut self, allow_crossed: bool) -> Self {
+        self.allow_crossed = allow_crossed;
+        self
+    }
+
+    /// Updates the store with the given event if it has a newer update_id, so frames arriving
+    /// out of order cannot regress the book. Returns whether the event was stored, stale
+    /// events are counted in [`METRICS`].
+    pub fn update(&mut self, event: BookTickerEvent) -> bool {
         match self.data.entry(event.symbol.clone()) {
             Entry::Occupied(mut entry) => {
-                if event.update_id > entry.get().update_id {
-                    entry.insert(event);
+                if event.update_id <= entry.get().update_id {
+                    METRICS.record_stale_book_ticker_event(&event.symbol);
+                    return false;
                 }
+                entry.insert(event);
             }
             Entry::Vacant(entry) => {
                 entry.insert(event);
             }
         }
+        true
     }
 
     /// Retrieves the latest event for a symbol.
@@ -55,6 +96,17 @@ impl BookTickerStore {
         self.data.get(symbol)
     }
 
+    /// Returns whether the latest book of `symbol` is crossed and crossed books are not
+    /// allowed.
+    #[must_use]
+    pub fn is_crossed(&self, symbol: &str) -> bool {
+        !self.allow_crossed
+            && self
+                .data
+                .get(symbol)
+                .is_some_and(BookTickerEvent::is_crossed)
+    }
+
     /// Returns the number of stored symbols.
     #[must_use]
     pub fn len(&self) -> usize {
@@ -67,3 +119,56 @@ impl BookTickerStore {
         self.data.is_empty()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(update_id: u64, bid_price: i64) -> BookTickerEvent {
+        BookTickerEvent {
+            update_id,
+            symbol: "BTCUSDT".to_owned(),
+            bid_price: Decimal::from(bid_price),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_out_of_order_events_dropped() {
+        let mut store = BookTickerStore::new();
+
+        assert!(store.update(event(2, 100)));
+        assert!(!store.update(event(1, 90)));
+        assert!(!store.update(event(2, 95)));
+        assert_eq!(store.get("BTCUSDT").unwrap().bid_price, Decimal::from(100));
+
+        assert!(store.update(event(3, 105)));
+        assert_eq!(store.get("BTCUSDT").unwrap().update_id, 3);
+    }
+
+    #[test]
+    fn test_crossed_books_are_reported_unless_allowed() {
+        let book = |update_id, bid_price, ask_price| BookTickerEvent {
+            update_id,
+            symbol: "BTCUSDT".to_owned(),
+            bid_price: Decimal::from(bid_price),
+            ask_price: Decimal::from(ask_price),
+            ..Default::default()
+        };
+        let mut store = BookTickerStore::new();
+        assert!(!store.is_crossed("BTCUSDT"));
+
+        store.update(book(1, 100, 101));
+        assert!(!store.is_crossed("BTCUSDT"));
+        store.update(book(2, 101, 101));
+        assert!(store.is_crossed("BTCUSDT"));
+        store.update(book(3, 102, 101));
+        assert!(store.is_crossed("BTCUSDT"));
+        store.update(book(4, 100, 102));
+        assert!(!store.is_crossed("BTCUSDT"));
+
+        let mut store = BookTickerStore::new().with_crossed_books_allowed(true);
+        store.update(book(1, 102, 101));
+        assert!(!store.is_crossed("BTCUSDT"));
+    }
+}
@@ -1,5 +1,7 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
+use anyhow::bail;
+use rust_decimal::Decimal;
 use serde::Deserialize;
 use tools::misc::toml;
 
@@ -23,6 +25,52 @@ pub struct Config {
 pub struct GeneralConfig {
     pub server_addr: String,
     pub metrics_addr: String,
+    /// File the end-of-run summary is written to as JSON (unset = only logged).
+    #[serde(default)]
+    pub summary_path: Option<PathBuf>,
+    /// Telegram bot posting a message for every filled chain (unset = only logged).
+    #[serde(default)]
+    pub telegram_bot_token: Option<String>,
+    /// Chat the filled chain messages are posted to.
+    #[serde(default)]
+    pub telegram_chat_id: Option<String>,
+    /// SQLite database every executed chain is written to (unset = no ledger).
+    #[serde(default)]
+    pub ledger_path: Option<PathBuf>,
+    /// SQLite database every detected chain is written to (unset = no history).
+    #[serde(default)]
+    pub history_path: Option<PathBuf>,
+    /// Time the chains being placed get to resolve on shutdown before they are aborted.
+    #[serde(default = "default_shutdown_grace_secs")]
+    pub shutdown_grace_secs: u64,
+    /// Minimum chain profit in USD, overriding the `min_profit_qty` of every base asset.
+    #[serde(default, with = "rust_decimal::serde::float_option")]
+    pub min_profit_usd: Option<Decimal>,
+    /// Asset taken as USD when pricing the base assets for `min_profit_usd`.
+    #[serde(default = "default_usd_asset")]
+    pub usd_asset: String,
+    /// Decimal places of the profits converted to USD in logs and the run summary.
+    #[serde(default = "default_usd_precision")]
+    pub usd_precision: u32,
+    /// `GET /health` fails once no book ticker was received for this long.
+    #[serde(default = "default_health_stale_after_secs")]
+    pub health_stale_after_secs: u64,
+}
+
+fn default_shutdown_grace_secs() -> u64 {
+    10
+}
+
+fn default_usd_asset() -> String {
+    "USDT".to_owned()
+}
+
+fn default_usd_precision() -> u32 {
+    2
+}
+
+fn default_health_stale_after_secs() -> u64 {
+    30
 }
 
 impl Default for Config {
@@ -40,6 +88,16 @@ impl Default for Config {
             general: GeneralConfig {
                 server_addr: "127.0.0.1:9000".to_owned(),
                 metrics_addr: "127.0.0.1:9007".to_owned(),
+                summary_path: None,
+                telegram_bot_token: None,
+                telegram_chat_id: None,
+                ledger_path: None,
+                history_path: None,
+                shutdown_grace_secs: default_shutdown_grace_secs(),
+                min_profit_usd: None,
+                usd_asset: default_usd_asset(),
+                usd_precision: default_usd_precision(),
+                health_stale_after_secs: default_health_stale_after_secs(),
             },
         }
     }
@@ -67,6 +125,20 @@ impl Config {
             cfg.validate()?;
         }
 
+        if self
+            .general
+            .min_profit_usd
+            .is_some_and(|min_profit| min_profit <= Decimal::ZERO)
+        {
+            bail!("min_profit_usd must be greater than 0");
+        }
+        if self.general.usd_precision > 28 {
+            bail!("usd_precision must be at most 28");
+        }
+        if self.general.health_stale_after_secs == 0 {
+            bail!("health_stale_after_secs must be greater than 0");
+        }
+
         Ok(self)
     }
 }
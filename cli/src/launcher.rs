@@ -1,6 +1,13 @@
+use std::time::Duration;
+
 use anyhow::{Context, Result};
-use engine::{Exchange, Sender, ServiceFactory, build_processes, build_services};
+use engine::{
+    Exchange, HistoryProcess, LedgerProcess, RUN_STATS, Sender, ServiceFactory, TelegramNotifier,
+    USD_PROFIT_FLOOR, UsdProfitFloor, build_processes, build_services,
+};
+use tokio::time::Instant;
 use tools::http::http_server::{HttpServer, HttpServerConfig};
+use tracing::error;
 
 use crate::{
     ExchangeType,
@@ -8,7 +15,11 @@ use crate::{
     ui,
 };
 
-pub async fn start(exchange: ExchangeType, config_path: std::path::PathBuf) -> Result<()> {
+pub async fn start(
+    exchange: ExchangeType,
+    config_path: std::path::PathBuf,
+    record: Option<std::path::PathBuf>,
+) -> Result<()> {
     let _cfg = match Config::load(&config_path) {
         Ok(cfg) => cfg,
         Err(e) => {
@@ -19,12 +30,19 @@ pub async fn start(exchange: ExchangeType, config_path: std::path::PathBuf) -> R
         }
     };
 
+    if record.is_some() && exchange != ExchangeType::Binance {
+        println!("🚧 Ticker recording is not supported on {exchange} yet");
+    }
+
     match exchange {
         ExchangeType::Binance => {
             #[cfg(feature = "binance")]
             {
-                bootstrap::<binance::Provider, _>(_cfg.binance.as_ref(), &_cfg.general, exchange)
-                    .await?
+                let mut config = _cfg.binance;
+                if let (Some(config), Some(path)) = (config.as_mut(), record) {
+                    config.record_path = Some(path);
+                }
+                bootstrap::<binance::Provider, _>(config.as_ref(), &_cfg.general, exchange).await?
             }
             #[cfg(not(feature = "binance"))]
             ui::print_feature_error("binance");
@@ -50,6 +68,47 @@ pub async fn start(exchange: ExchangeType, config_path: std::path::PathBuf) -> R
     Ok(())
 }
 
+#[cfg_attr(not(feature = "binance"), allow(unused_variables))]
+pub async fn backtest(
+    exchange: ExchangeType,
+    input_path: std::path::PathBuf,
+    config_path: std::path::PathBuf,
+) -> Result<()> {
+    let _cfg = match Config::load(&config_path) {
+        Ok(cfg) => cfg,
+        Err(e) => {
+            return {
+                ui::print_config_error(&config_path, &e);
+                Ok(())
+            };
+        }
+    };
+
+    match exchange {
+        ExchangeType::Binance => {
+            #[cfg(feature = "binance")]
+            {
+                let config = _cfg
+                    .binance
+                    .as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("{exchange} config not found"))?;
+                let input = std::fs::File::open(&input_path)
+                    .with_context(|| format!("Failed to open {}", input_path.display()))?;
+                let report = binance::services::exchange::backtest::Backtest::from_config(config)
+                    .await?
+                    .replay(std::io::BufReader::new(input))?;
+                ui::print_backtest(&report);
+            }
+            #[cfg(not(feature = "binance"))]
+            ui::print_feature_error("binance");
+        }
+        ExchangeType::Kucoin | ExchangeType::Solana => {
+            println!("🚧 Backtest is not supported on {exchange} yet");
+        }
+    }
+    Ok(())
+}
+
 #[allow(dead_code)]
 async fn bootstrap<P, C>(
     config: Option<&C>,
@@ -60,8 +119,39 @@ where
     P: ServiceFactory<dyn Exchange, Config = C> + ServiceFactory<dyn Sender, Config = C>,
 {
     let config = config.ok_or_else(|| anyhow::anyhow!("{exchange_type} config not found"))?;
+    // Start the run clock before services connect.
+    std::sync::LazyLock::force(&RUN_STATS);
+    // Read by the order builders, so it must be set before the services are built.
+    if let Some(min_profit_usd) = settings.min_profit_usd {
+        _ = USD_PROFIT_FLOOR.set(
+            UsdProfitFloor::new(min_profit_usd, &settings.usd_asset)
+                .with_precision(settings.usd_precision),
+        );
+    }
     let (exchange, sender) = build_services::<P, C>(config).await?;
-    let processes = build_processes(exchange, sender);
+    let notifier = TelegramNotifier::new(
+        settings.telegram_bot_token.clone(),
+        settings.telegram_chat_id.clone(),
+    )?;
+    let ledger = settings
+        .ledger_path
+        .as_deref()
+        .map(|path| LedgerProcess::open(path, &exchange_type.to_string()))
+        .transpose()?;
+    let history = settings
+        .history_path
+        .as_deref()
+        .map(|path| HistoryProcess::open(path, &exchange_type.to_string()))
+        .transpose()?;
+    let processes = build_processes(
+        exchange,
+        sender,
+        notifier,
+        ledger,
+        history,
+        Duration::from_secs(settings.shutdown_grace_secs),
+        Duration::from_secs(settings.health_stale_after_secs),
+    );
 
     let server_config = HttpServerConfig {
         addr: settings.server_addr.clone(),
@@ -69,9 +159,15 @@ where
         ..Default::default()
     };
 
-    HttpServer::from_config(server_config)
+    let result = HttpServer::from_config(server_config)
         .with_processes(processes)
         .run()
-        .await
-        .context("HTTP Server failed")
+        .await;
+
+    let summary = RUN_STATS.lock().await.summary(Instant::now());
+    if let Err(e) = summary.report(settings.summary_path.as_deref()) {
+        error!(error = ?e, "Failed to report run summary");
+    }
+
+    result.context("HTTP Server failed")
 }
@@ -33,6 +33,42 @@ enum Commands {
         /// Path to config.toml file
         #[arg(short, long, default_value = "config.toml")]
         config: std::path::PathBuf,
+
+        /// Append every received book ticker to this JSON lines file, for replay with
+        /// `backtest`
+        #[arg(long)]
+        record: Option<std::path::PathBuf>,
+    },
+
+    /// Summarize detected chains by day and triangle
+    History {
+        /// Exchange the chains were detected on
+        #[arg(short, long)]
+        exchange: ExchangeType,
+
+        /// Path to the history database
+        #[arg(short, long, default_value = "history.sqlite")]
+        path: std::path::PathBuf,
+
+        /// Days to summarize, today included
+        #[arg(short, long, default_value_t = 7)]
+        days: u32,
+    },
+
+    /// Replay recorded book ticker events and summarize the chains detected, without sending
+    /// orders
+    Backtest {
+        /// Exchange the events were recorded on
+        #[arg(short, long)]
+        exchange: ExchangeType,
+
+        /// Path to the newline-delimited JSON file of book ticker events
+        #[arg(short, long)]
+        input: std::path::PathBuf,
+
+        /// Path to config.toml file
+        #[arg(short, long, default_value = "config.toml")]
+        config: std::path::PathBuf,
     },
 }
 
@@ -53,8 +89,28 @@ async fn main() -> anyhow::Result<()> {
     match cli.commands {
         Commands::Version => ui::print_version(),
         Commands::List => ui::print_exchanges(),
-        Commands::Run { exchange, config } => {
-            launcher::start(exchange, config).await?;
+        Commands::Run {
+            exchange,
+            config,
+            record,
+        } => {
+            launcher::start(exchange, config, record).await?;
+        }
+        Commands::History {
+            exchange,
+            path,
+            days,
+        } => {
+            let history = engine::HistoryProcess::open(&path, &exchange.to_string())?;
+            let now_ts = tools::misc::time::get_current_timestamp().as_millis();
+            ui::print_history(&history.summary_of_days(days, now_ts)?);
+        }
+        Commands::Backtest {
+            exchange,
+            input,
+            config,
+        } => {
+            launcher::backtest(exchange, input, config).await?;
         }
     }
 
@@ -1,3 +1,4 @@
+use comfy_table::{Cell, Color, Table, modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL};
 use owo_colors::OwoColorize;
 
 use crate::ui::print_feature_error;
@@ -114,3 +115,98 @@ pub fn print_exchanges() {
 
     println!("  {}\n", "━".repeat(60).dimmed());
 }
+
+pub fn print_history(summaries: &[engine::TriangleSummary]) {
+    println!("\n  {}", "DETECTED CHAINS".dimmed().bold());
+
+    if summaries.is_empty() {
+        println!("  {}\n", "No chains detected in this period".dimmed());
+        return;
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_header(vec![
+            "Day",
+            "Triangle",
+            "Detected",
+            "Executed",
+            "Expected profit",
+            "Realized profit",
+        ]);
+
+    for summary in summaries {
+        let realized = Cell::new(format!(
+            "{} {}",
+            summary.realized_profit, summary.base_asset
+        ))
+        .fg(if summary.realized_profit.is_sign_negative() {
+            Color::Red
+        } else {
+            Color::Green
+        });
+        table.add_row(vec![
+            Cell::new(&summary.day),
+            Cell::new(&summary.triangle),
+            Cell::new(summary.detected),
+            Cell::new(summary.executed),
+            Cell::new(format!(
+                "{} {}",
+                summary.expected_profit, summary.base_asset
+            )),
+            realized,
+        ]);
+    }
+
+    for line in table.to_string().lines() {
+        println!("  {line}");
+    }
+    println!();
+}
+
+#[cfg(feature = "binance")]
+pub fn print_backtest(report: &engine::BacktestReport) {
+    println!("\n  {}", "BACKTEST".dimmed().bold());
+    println!(
+        "  {} events replayed, {} chains detected\n",
+        report.events, report.chains
+    );
+
+    if report.chains == 0 {
+        return;
+    }
+
+    let mut profit = Table::new();
+    profit
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_header(vec!["Base asset", "Theoretical profit"]);
+    for (asset, qty) in &report.profit {
+        profit.add_row(vec![
+            Cell::new(asset),
+            Cell::new(format!("{qty} {asset}")).fg(if qty.is_sign_negative() {
+                Color::Red
+            } else {
+                Color::Green
+            }),
+        ]);
+    }
+
+    let mut symbols = Table::new();
+    symbols
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_header(vec!["Symbol", "Chains"]);
+    for (symbol, count) in &report.symbols {
+        symbols.add_row(vec![Cell::new(symbol), Cell::new(count)]);
+    }
+
+    for table in [profit, symbols] {
+        for line in table.to_string().lines() {
+            println!("  {line}");
+        }
+        println!();
+    }
+}
@@ -0,0 +1,6 @@
+use criterion::criterion_main;
+mod runtime;
+
+criterion_main! {
+    runtime::metrics_benchmark::benches,
+}
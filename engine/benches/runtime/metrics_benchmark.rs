@@ -0,0 +1,23 @@
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group};
+use engine::METRICS;
+use metrics_exporter_prometheus::PrometheusBuilder;
+
+pub fn processed_chain_benchmark(c: &mut Criterion) {
+    let recorder = PrometheusBuilder::new().build_recorder();
+    let symbols = ["BTCUSDT", "ETHBTC", "ETHUSDT"];
+
+    metrics::with_local_recorder(&recorder, || {
+        c.bench_function("record_processed_chain", |b| {
+            b.iter(|| METRICS.record_processed_chain(black_box(&symbols)));
+        });
+
+        let counter = METRICS.processed_chain_counter(&symbols, Some("USDT"));
+        c.bench_function("processed_chain_counter", |b| {
+            b.iter(|| counter.increment(black_box(1)));
+        });
+    });
+}
+
+criterion_group!(benches, processed_chain_benchmark);
@@ -1,9 +1,11 @@
 use std::fmt::{Display, Formatter};
 
+use serde::{Deserialize, Serialize};
 use strum::EnumIter;
 
 /// Order direction for symbols in a trading chain (ascending/descending).
-#[derive(Clone, Debug, Copy, PartialEq, Eq, Default, EnumIter)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default, EnumIter, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
 pub enum SymbolOrder {
     #[default]
     Asc,
@@ -19,6 +21,50 @@ impl Display for SymbolOrder {
     }
 }
 
+/// How a dropped market data connection is recovered.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReconnectStrategy {
+    /// Reconnect the dropped connection and subscribe to the same streams again.
+    Resubscribe,
+    /// Restart the exchange service, rebuilding chains from freshly fetched exchange info.
+    #[default]
+    Rebuild,
+}
+
+/// Order type the legs of a chain are placed with.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderType {
+    /// Executes against the book at any price, so the realized profit may differ from the
+    /// top-of-book estimate.
+    #[default]
+    Market,
+    /// Executes at the chain price or better, or not at all. Placed immediate-or-cancel, so
+    /// the part that does not fill right away is cancelled instead of resting on the book.
+    #[serde(alias = "ioc")]
+    Limit,
+}
+
+impl Display for OrderType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Market => f.write_str("market"),
+            Self::Limit => f.write_str("limit"),
+        }
+    }
+}
+
+/// Whether an order adds liquidity to the book (maker) or takes it (taker), which decides
+/// the fee it pays.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LiquidityRole {
+    Maker,
+    #[default]
+    Taker,
+}
+
 /// Status of a trading chain.
 pub enum ChainStatus {
     /// Chain newly received.
@@ -38,3 +84,41 @@ impl Display for ChainStatus {
         }
     }
 }
+
+/// Execution status of an order placed by a sender, unified across exchanges.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OrderStatus {
+    /// Accepted but not executed yet (for Solana: sent, not landed yet).
+    New,
+    /// Partially executed and still open.
+    PartiallyFilled,
+    /// Fully executed (for Solana: landed and executed successfully).
+    Filled,
+    /// Cancelled before being fully executed.
+    Canceled,
+    /// Rejected by the exchange (for Solana: landed but failed on-chain).
+    Rejected,
+    /// Expired without being fully executed.
+    Expired,
+}
+
+impl OrderStatus {
+    /// Returns true while the order still occupies an open order slot on the exchange.
+    #[must_use]
+    pub const fn is_open(self) -> bool {
+        matches!(self, Self::New | Self::PartiallyFilled)
+    }
+}
+
+impl Display for OrderStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::New => write!(f, "new"),
+            Self::PartiallyFilled => write!(f, "partially_filled"),
+            Self::Filled => write!(f, "filled"),
+            Self::Canceled => write!(f, "canceled"),
+            Self::Rejected => write!(f, "rejected"),
+            Self::Expired => write!(f, "expired"),
+        }
+    }
+}
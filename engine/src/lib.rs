@@ -3,14 +3,44 @@ pub mod model;
 pub mod runtime;
 pub mod service;
 
-pub use model::orders::{ChainOrder, ChainOrders};
+pub use model::{
+    fees::{FeeRates, FeeSchedule},
+    notional::MinNotional,
+    orders::{ChainOrder, ChainOrders, FilledChain},
+};
 pub use runtime::{
-    channel::{ORDERS_CHANNEL, OrdersChannel},
-    metrics::{METRICS, Metrics},
+    backtest::BacktestReport,
+    balances::{BalanceCache, BalanceCap, Balances},
+    chains::{
+        CHAINS, ChainsProcess, MIN_CHAIN_LEGS, MonitoredChain, MonitoredSymbol, check_chain_cycle,
+        check_chain_length, publish_chains,
+    },
+    channel::{DETECTED_CHAINS, FILLED_CHAINS, ORDERS_CHANNEL, OrdersChannel},
+    clock::ClockDrift,
+    compute::ComputePool,
+    execution_cap::ExecutionCap,
+    health::{HealthProcess, HealthReport, TICKS, TickClock},
+    history::{HistoryProcess, TriangleSummary},
+    kill_switch::{KILL_SWITCH, KillSwitch},
+    ledger::{LedgerEntry, LedgerProcess},
+    metrics::{METRICS, Metrics, ProcessedChainCounter, UpdateLatencySampler},
+    notifier::TelegramNotifier,
+    open_orders::OpenOrders,
+    persistence::ProfitPersistence,
+    pnl::RealizedPnl,
+    ramp::{OrderSizeRamp, SizeRamp},
+    repeat::RepeatGuard,
+    schedule::{ProfitSchedule, ProfitWindow, TimeOfDay},
+    shutdown::{IN_FLIGHT_CHAINS, InFlightChains, InFlightGuard},
+    summary::{RUN_STATS, RunStats, RunSummary},
+    symbols::{DISABLED_SYMBOLS, DisabledSymbols, SymbolState, SymbolsProcess},
+    usd::{USD_PROFIT_FLOOR, UsdProfitFloor},
+    watchdog::OpportunityWatchdog,
     weight::{REQUEST_WEIGHT, RequestWeight},
 };
 pub use service::{
     builder::{build_processes, build_services},
     factory::ServiceFactory,
+    forward::ForwardSender,
     traits::{Exchange, Sender, Validatable},
 };
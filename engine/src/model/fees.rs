@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+use crate::enums::{LiquidityRole, SymbolOrder};
+
+/// Maker and taker fees in percent, a negative maker fee being a rebate.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct FeeRates {
+    #[serde(with = "rust_decimal::serde::float")]
+    pub maker_fee_percent: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub taker_fee_percent: Decimal,
+}
+
+impl FeeRates {
+    /// Same fee for makers and takers.
+    #[must_use]
+    pub const fn flat(fee_percent: Decimal) -> Self {
+        Self {
+            maker_fee_percent: fee_percent,
+            taker_fee_percent: fee_percent,
+        }
+    }
+
+    /// Returns the fee paid in `role`.
+    #[must_use]
+    pub const fn fee_percent(&self, role: LiquidityRole) -> Decimal {
+        match role {
+            LiquidityRole::Maker => self.maker_fee_percent,
+            LiquidityRole::Taker => self.taker_fee_percent,
+        }
+    }
+}
+
+/// Fees charged on the legs of a chain.
+///
+/// The rates of a leg are those of its symbol if overridden, otherwise those of its side
+/// (an ascending leg sells the base asset, a descending one buys it), otherwise the
+/// default. The leg then pays the maker or taker fee of those rates by its liquidity role.
+#[derive(Clone, Debug, Default)]
+pub struct FeeSchedule {
+    default: FeeRates,
+    buy: Option<FeeRates>,
+    sell: Option<FeeRates>,
+    symbols: HashMap<String, FeeRates>,
+    /// Liquidity role of the chain legs by position, legs past the end are takers.
+    leg_roles: Vec<LiquidityRole>,
+}
+
+impl FeeSchedule {
+    /// Schedule charging `default` on every leg without overrides.
+    #[must_use]
+    pub fn new(default: FeeRates) -> Self {
+        Self {
+            default,
+            ..Self::default()
+        }
+    }
+
+    /// Schedule charging every leg the same fee.
+    #[must_use]
+    pub fn flat(fee_percent: Decimal) -> Self {
+        Self::new(FeeRates::flat(fee_percent))
+    }
+
+    /// Charges the buying legs with `rates` (unless their symbol is overridden).
+    #[must_use]
+    pub fn with_buy_fees(mut self, rates: FeeRates) -> Self {
+        self.buy = Some(rates);
+        self
+    }
+
+    /// Charges the selling legs with `rates` (unless their symbol is overridden).
+    #[must_use]
+    pub fn with_sell_fees(mut self, rates: FeeRates) -> Self {
+        self.sell = Some(rates);
+        self
+    }
+
+    /// Charges every leg on `symbol` with `rates`, whatever its side.
+    #[must_use]
+    pub fn with_symbol_fees(mut self, symbol: impl Into<String>, rates: FeeRates) -> Self {
+        self.symbols.insert(symbol.into(), rates);
+        self
+    }
+
+    /// Sets the liquidity role of the chain legs by position.
+    #[must_use]
+    pub fn with_leg_roles(mut self, leg_roles: Vec<LiquidityRole>) -> Self {
+        self.leg_roles = leg_roles;
+        self
+    }
+
+    /// Returns the rates applying to a leg on `symbol` in `symbol_order`.
+    #[must_use]
+    pub fn rates(&self, symbol: &str, symbol_order: SymbolOrder) -> FeeRates {
+        let side = match symbol_order {
+            SymbolOrder::Asc => self.sell,
+            SymbolOrder::Desc => self.buy,
+        };
+        self.symbols
+            .get(symbol)
+            .copied()
+            .or(side)
+            .unwrap_or(self.default)
+    }
+
+    /// Returns the fee of the leg at `idx` on `symbol` in `symbol_order`.
+    #[must_use]
+    pub fn leg_fee_percent(&self, idx: usize, symbol: &str, symbol_order: SymbolOrder) -> Decimal {
+        let role = self.leg_roles.get(idx).copied().unwrap_or_default();
+        self.rates(symbol, symbol_order).fee_percent(role)
+    }
+
+    /// Returns the sum of the fees of the chain legs, given in chain order.
+    pub fn chain_fee_percent<'a>(
+        &self,
+        legs: impl IntoIterator<Item = (&'a str, SymbolOrder)>,
+    ) -> Decimal {
+        legs.into_iter()
+            .enumerate()
+            .map(|(idx, (symbol, symbol_order))| self.leg_fee_percent(idx, symbol, symbol_order))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbol_overrides_side_overrides_default() {
+        let schedule = FeeSchedule::flat(Decimal::new(1, 1))
+            .with_buy_fees(FeeRates::flat(Decimal::new(8, 2)))
+            .with_symbol_fees(
+                "BTCUSDT",
+                FeeRates {
+                    maker_fee_percent: Decimal::ZERO,
+                    taker_fee_percent: Decimal::new(5, 2),
+                },
+            );
+
+        assert_eq!(
+            schedule.leg_fee_percent(0, "ETHBTC", SymbolOrder::Asc),
+            Decimal::new(1, 1)
+        );
+        assert_eq!(
+            schedule.leg_fee_percent(0, "ETHBTC", SymbolOrder::Desc),
+            Decimal::new(8, 2)
+        );
+        assert_eq!(
+            schedule.leg_fee_percent(0, "BTCUSDT", SymbolOrder::Desc),
+            Decimal::new(5, 2)
+        );
+    }
+
+    #[test]
+    fn test_chain_fee_sums_legs_by_role() {
+        let schedule = FeeSchedule::new(FeeRates {
+            maker_fee_percent: Decimal::new(-2, 2),
+            taker_fee_percent: Decimal::new(1, 1),
+        })
+        .with_sell_fees(FeeRates {
+            maker_fee_percent: Decimal::ZERO,
+            taker_fee_percent: Decimal::new(75, 3),
+        })
+        .with_leg_roles(vec![LiquidityRole::Maker]);
+
+        // Maker buy -0.02, taker buy 0.1, taker sell 0.075.
+        let legs = [
+            ("BTCUSDT", SymbolOrder::Desc),
+            ("ETHBTC", SymbolOrder::Desc),
+            ("ETHUSDT", SymbolOrder::Asc),
+        ];
+        assert_eq!(schedule.chain_fee_percent(legs), Decimal::new(155, 3));
+        assert_eq!(
+            FeeSchedule::flat(Decimal::new(1, 1)).chain_fee_percent(legs),
+            Decimal::new(3, 1)
+        );
+    }
+}
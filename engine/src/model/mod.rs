@@ -1 +1,3 @@
+pub mod fees;
+pub mod notional;
 pub mod orders;
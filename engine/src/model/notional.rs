@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+
+use crate::model::orders::ChainOrder;
+
+/// Minimum notional of the chain legs, checked by the profit gate so that a chain the
+/// exchange would reject for a too small order is skipped before it is sent.
+///
+/// The minimum of a leg is the one of its symbol on the exchange, raised by the configured
+/// minimum of the symbol if any. Both are in the quote asset of the symbol.
+#[derive(Clone, Debug, Default)]
+pub struct MinNotional {
+    symbols: HashMap<String, Decimal>,
+}
+
+impl MinNotional {
+    /// Raises the exchange minimum of the given symbols.
+    #[must_use]
+    pub fn new(symbols: HashMap<String, Decimal>) -> Self {
+        Self { symbols }
+    }
+
+    /// Returns the minimum notional of `symbol` given its exchange minimum.
+    #[must_use]
+    pub fn min_notional(&self, symbol: &str, exchange_min: Decimal) -> Decimal {
+        self.symbols
+            .get(symbol)
+            .map_or(exchange_min, |min| exchange_min.max(*min))
+    }
+
+    /// Returns the first order below its minimum notional, `exchange_min` giving the
+    /// exchange minimum of the order at an index.
+    pub fn find_below<'a>(
+        &self,
+        orders: &'a [ChainOrder],
+        exchange_min: impl Fn(usize) -> Decimal,
+    ) -> Option<&'a ChainOrder> {
+        orders.iter().enumerate().find_map(|(idx, order)| {
+            (order.notional() < self.min_notional(&order.symbol, exchange_min(idx)))
+                .then_some(order)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::{OrderType, SymbolOrder};
+
+    fn order(symbol: &str, symbol_order: SymbolOrder, base_qty: i64, quote_qty: i64) -> ChainOrder {
+        ChainOrder {
+            symbol: symbol.to_owned(),
+            symbol_order,
+            order_type: OrderType::Market,
+            price: Decimal::ONE,
+            base_qty: Decimal::from(base_qty),
+            quote_qty: Decimal::from(quote_qty),
+            base_increment: Decimal::ONE,
+            quote_increment: Decimal::ONE,
+            min_qty: Decimal::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_leg_below_min_notional_found() {
+        // Notionals: 10 spent on BTCUSDT, 4 received on ETHUSDT.
+        let orders = [
+            order("BTCUSDT", SymbolOrder::Desc, 10, 1),
+            order("ETHUSDT", SymbolOrder::Asc, 2, 4),
+        ];
+
+        let exchange_only = MinNotional::default();
+        assert!(
+            exchange_only
+                .find_below(&orders, |_| Decimal::from(4))
+                .is_none()
+        );
+        assert_eq!(
+            exchange_only
+                .find_below(&orders, |_| Decimal::from(5))
+                .map(|order| order.symbol.as_str()),
+            Some("ETHUSDT")
+        );
+
+        // The configured minimum raises the exchange one, never lowers it.
+        let configured = MinNotional::new(HashMap::from([
+            ("BTCUSDT".to_owned(), Decimal::from(11)),
+            ("ETHUSDT".to_owned(), Decimal::ONE),
+        ]));
+        assert_eq!(
+            configured
+                .find_below(&orders, |_| Decimal::from(4))
+                .map(|order| order.symbol.as_str()),
+            Some("BTCUSDT")
+        );
+        assert_eq!(
+            configured.min_notional("ETHUSDT", Decimal::from(4)),
+            Decimal::from(4)
+        );
+    }
+}
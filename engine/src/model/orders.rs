@@ -1,18 +1,31 @@
 use std::fmt::{Display, Formatter};
 
 use rust_decimal::{Decimal, RoundingStrategy, prelude::FromPrimitive};
+use serde::Serialize;
+use tokio::time::Instant;
 use tracing::info;
 use uuid::Uuid;
 
-use crate::enums::SymbolOrder;
+use crate::{
+    enums::{OrderType, SymbolOrder},
+    runtime::usd::USD_PROFIT_FLOOR,
+};
 
 /// Chain of orders for arbitrage (buy/sell sequence).
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
 pub struct ChainOrders {
     pub ts: u128,
     pub chain_id: Uuid,
+    /// Asset the chain starts and ends in, the chain profit is denominated in it.
+    pub base_asset: String,
     pub fee_percent: Decimal,
     pub orders: Vec<ChainOrder>,
+    /// When the book ticker event the chain was detected on was received.
+    #[serde(skip)]
+    pub received_at: Option<Instant>,
+    /// When the chain was queued for the sender.
+    #[serde(skip)]
+    pub queued_at: Option<Instant>,
 }
 
 impl Display for ChainOrders {
@@ -74,26 +87,97 @@ impl ChainOrders {
         (profit, profit_percent)
     }
 
-    /// Logs information about the chain.
+    /// Logs information about the chain, with its profit in USD when base assets are priced.
     pub fn print_info(&self, send_orders: bool) {
+        let profit_usd = USD_PROFIT_FLOOR
+            .get()
+            .and_then(|floor| floor.to_usd(&self.base_asset, self.compute_profit().0));
         info!(
             ts = self.ts,
             chain_id = %self.chain_id,
             send_orders,
+            profit_usd = profit_usd.map(tracing::field::display),
             details = %self,
             "📦 [Engine] Chain processed"
         );
     }
 }
 
+/// Chain fully executed by a sender.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct FilledChain {
+    pub chain: ChainOrders,
+    /// Filled quantity of every leg, the first one in the base asset spent.
+    pub fills: Vec<Decimal>,
+    /// Realized profit in the base asset.
+    pub profit: Decimal,
+}
+
+impl FilledChain {
+    /// Realized profit relative to the quantity spent by the first leg, in percent.
+    #[must_use]
+    pub fn profit_percent(&self) -> Decimal {
+        match self.fills.first() {
+            Some(spent) if !spent.is_zero() => (self.profit / spent * Decimal::ONE_HUNDRED)
+                .round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero),
+            _ => Decimal::ZERO,
+        }
+    }
+}
+
 /// Order in a chain (buy/sell with qty/price).
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize)]
 pub struct ChainOrder {
     pub symbol: String,
     pub symbol_order: SymbolOrder,
+    pub order_type: OrderType,
     pub price: Decimal,
     pub base_qty: Decimal,
     pub quote_qty: Decimal,
     pub base_increment: Decimal,
     pub quote_increment: Decimal,
+    /// Minimum base quantity the exchange accepts for the order (zero = no minimum).
+    pub min_qty: Decimal,
+}
+
+impl ChainOrder {
+    /// Value of the order in the quote asset of its symbol: the quote received by an
+    /// ascending leg, the quote spent by a descending one.
+    #[must_use]
+    pub const fn notional(&self) -> Decimal {
+        match self.symbol_order {
+            SymbolOrder::Asc => self.quote_qty,
+            SymbolOrder::Desc => self.base_qty,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test_utils {
+    use super::*;
+
+    /// Ascending market order of one unit of `symbol` at a price of one.
+    #[must_use]
+    pub fn order(symbol: &str) -> ChainOrder {
+        ChainOrder {
+            symbol: symbol.to_owned(),
+            symbol_order: SymbolOrder::Asc,
+            order_type: OrderType::Market,
+            price: Decimal::ONE,
+            base_qty: Decimal::ONE,
+            quote_qty: Decimal::ONE,
+            base_increment: Decimal::ONE,
+            quote_increment: Decimal::ONE,
+            min_qty: Decimal::ZERO,
+        }
+    }
+
+    /// Chain of one unit [`order`]s on `symbols`.
+    #[must_use]
+    pub fn chain(symbols: &[&str]) -> ChainOrders {
+        ChainOrders {
+            orders: symbols.iter().map(|symbol| order(symbol)).collect(),
+            ..Default::default()
+        }
+    }
 }
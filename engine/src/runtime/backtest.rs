@@ -0,0 +1,92 @@
+use std::collections::BTreeMap;
+
+use rust_decimal::Decimal;
+
+use crate::model::orders::ChainOrders;
+
+/// Aggregate stats of a ticker replay: the chains that would have been sent had the events
+/// been received live.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BacktestReport {
+    /// Ticker events replayed.
+    pub events: usize,
+    /// Chains detected.
+    pub chains: usize,
+    /// Total theoretical profit per base asset.
+    pub profit: BTreeMap<String, Decimal>,
+    /// Detected chains per symbol, a chain counting once for each of its legs.
+    pub symbols: BTreeMap<String, usize>,
+}
+
+impl BacktestReport {
+    /// Records a replayed ticker event.
+    pub fn record_event(&mut self) {
+        self.events += 1;
+    }
+
+    /// Records a detected chain and its theoretical profit.
+    pub fn record_chain(&mut self, chain: &ChainOrders) {
+        self.chains += 1;
+        let (profit, _) = chain.compute_profit();
+        *self.profit.entry(chain.base_asset.clone()).or_default() += profit;
+        for symbol in chain.extract_symbols() {
+            *self.symbols.entry(symbol.to_owned()).or_default() += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ChainOrder, model::orders::test_utils::order};
+
+    fn chain(base_asset: &str, symbols: [&str; 3], input: Decimal, output: Decimal) -> ChainOrders {
+        let leg = |symbol: &str, base_qty, quote_qty| ChainOrder {
+            base_qty,
+            quote_qty,
+            ..order(symbol)
+        };
+        ChainOrders {
+            base_asset: base_asset.to_owned(),
+            orders: vec![
+                leg(symbols[0], input, input),
+                leg(symbols[1], input, input),
+                leg(symbols[2], input, output),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_chains_are_aggregated_per_base_asset_and_symbol() {
+        let mut report = BacktestReport::default();
+        report.record_event();
+        report.record_event();
+        report.record_chain(&chain(
+            "USDT",
+            ["BTCUSDT", "ETHBTC", "ETHUSDT"],
+            Decimal::from(100),
+            Decimal::from(101),
+        ));
+        report.record_chain(&chain(
+            "USDT",
+            ["BTCUSDT", "SOLBTC", "SOLUSDT"],
+            Decimal::from(100),
+            Decimal::new(1005, 1),
+        ));
+        report.record_chain(&chain(
+            "BTC",
+            ["BTCUSDT", "ETHUSDT", "ETHBTC"],
+            Decimal::ONE,
+            Decimal::new(101, 2),
+        ));
+
+        assert_eq!(report.events, 2);
+        assert_eq!(report.chains, 3);
+        assert_eq!(report.profit["USDT"], Decimal::new(15, 1));
+        assert_eq!(report.profit["BTC"], Decimal::new(1, 2));
+        assert_eq!(report.symbols["BTCUSDT"], 3);
+        assert_eq!(report.symbols["ETHBTC"], 2);
+        assert_eq!(report.symbols["SOLUSDT"], 1);
+    }
+}
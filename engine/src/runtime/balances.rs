@@ -0,0 +1,168 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{PoisonError, RwLock},
+    time::Duration,
+};
+
+use rust_decimal::Decimal;
+use tokio::{sync::Mutex, time::Instant};
+use tokio_util::sync::CancellationToken;
+use tracing::warn;
+
+/// Available balance per asset (ticker on CEXes, mint address on Solana).
+pub type Balances = HashMap<String, Decimal>;
+
+/// Balances fetched from an exchange, reused until they are older than the TTL.
+pub struct BalanceCache {
+    ttl: Duration,
+    state: Mutex<Option<(Instant, Balances)>>,
+}
+
+impl BalanceCache {
+    pub const DEFAULT_TTL: Duration = Duration::from_secs(5);
+
+    #[must_use]
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            state: Mutex::new(None),
+        }
+    }
+
+    /// Returns the cached balances, calling `fetch` first if they are missing or expired.
+    /// Concurrent callers wait for a single refresh.
+    pub async fn get_or_refresh<F, Fut>(&self, fetch: F) -> anyhow::Result<Balances>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = anyhow::Result<Balances>>,
+    {
+        let mut state = self.state.lock().await;
+
+        if let Some((fetched_at, balances)) = state.as_ref()
+            && fetched_at.elapsed() < self.ttl
+        {
+            return Ok(balances.clone());
+        }
+
+        let balances = fetch().await?;
+        *state = Some((Instant::now(), balances.clone()));
+        Ok(balances)
+    }
+
+    /// Applies a balance update pushed by a stream, keeping the cache fresh.
+    pub async fn update(&self, asset: &str, available: Decimal) {
+        if let Some((_, balances)) = self.state.lock().await.as_mut() {
+            balances.insert(asset.to_owned(), available);
+        }
+    }
+}
+
+impl Default for BalanceCache {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_TTL)
+    }
+}
+
+/// Free balances of the base assets, capping their configured `max_order_qty` so the first
+/// leg of a chain never asks for more than the account holds.
+#[derive(Debug, Default)]
+pub struct BalanceCap {
+    balances: RwLock<Option<Balances>>,
+}
+
+impl BalanceCap {
+    /// Replaces the free balances.
+    pub fn set(&self, balances: Balances) {
+        *self
+            .balances
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = Some(balances);
+    }
+
+    /// Returns `max_order_qty` capped at the free balance of `asset`. Nothing is capped
+    /// before the first balances are set; an asset missing from them has nothing free.
+    #[must_use]
+    pub fn cap(&self, asset: &str, max_order_qty: Decimal) -> Decimal {
+        match self
+            .balances
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_ref()
+        {
+            Some(balances) => balances
+                .get(asset)
+                .map_or(Decimal::ZERO, |free| max_order_qty.min(*free)),
+            None => max_order_qty,
+        }
+    }
+
+    /// Refreshes the balances with `fetch` every `interval` until cancelled. A failed
+    /// refresh is logged and the previous balances are kept.
+    pub async fn refresh_every<F, Fut>(
+        &self,
+        token: CancellationToken,
+        interval: Duration,
+        fetch: F,
+    ) -> anyhow::Result<()>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = anyhow::Result<Balances>>,
+    {
+        loop {
+            tokio::select! {
+                () = token.cancelled() => return Ok(()),
+                () = tokio::time::sleep(interval) => {}
+            }
+
+            match fetch().await {
+                Ok(balances) => self.set(balances),
+                Err(e) => warn!(error = ?e, "Failed to refresh balances"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_refreshes_only_when_expired() -> anyhow::Result<()> {
+        let fetches = AtomicUsize::new(0);
+        let fetch = || async {
+            fetches.fetch_add(1, Ordering::Relaxed);
+            Ok(Balances::from([("USDT".to_owned(), Decimal::from(100))]))
+        };
+
+        let cache = BalanceCache::new(Duration::from_secs(60));
+        cache.get_or_refresh(fetch).await?;
+        cache.update("USDT", Decimal::from(90)).await;
+        let balances = cache.get_or_refresh(fetch).await?;
+        assert_eq!(balances["USDT"], Decimal::from(90));
+        assert_eq!(fetches.load(Ordering::Relaxed), 1);
+
+        let expired = BalanceCache::new(Duration::ZERO);
+        expired.get_or_refresh(fetch).await?;
+        let balances = expired.get_or_refresh(fetch).await?;
+        assert_eq!(balances["USDT"], Decimal::from(100));
+        assert_eq!(fetches.load(Ordering::Relaxed), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cap_is_the_lower_of_config_and_free_balance() {
+        let cap = BalanceCap::default();
+        assert_eq!(cap.cap("BTC", Decimal::new(3, 4)), Decimal::new(3, 4));
+
+        cap.set(Balances::from([
+            ("BTC".to_owned(), Decimal::new(1, 4)),
+            ("USDT".to_owned(), Decimal::from(1_000)),
+        ]));
+        assert_eq!(cap.cap("BTC", Decimal::new(3, 4)), Decimal::new(1, 4));
+        assert_eq!(cap.cap("USDT", Decimal::from(50)), Decimal::from(50));
+        assert_eq!(cap.cap("ETH", Decimal::ONE), Decimal::ZERO);
+    }
+}
@@ -0,0 +1,271 @@
+use std::sync::{Arc, LazyLock};
+
+use anyhow::bail;
+use async_trait::async_trait;
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    routing::get,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
+use tools::http::http_server::HttpServerProcess;
+
+use crate::enums::SymbolOrder;
+
+/// Chains currently monitored by the exchange service, replaced every time it builds them.
+pub static CHAINS: LazyLock<Arc<RwLock<Vec<MonitoredChain>>>> = LazyLock::new(Arc::default);
+
+/// Symbols of a monitored chain, in trading order.
+pub type MonitoredChain = Vec<MonitoredSymbol>;
+
+/// Exchange-agnostic description of a chain symbol.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct MonitoredSymbol {
+    pub symbol: String,
+    pub base_asset: String,
+    pub quote_asset: String,
+    pub order: SymbolOrder,
+}
+
+/// Replaces the monitored chains.
+pub async fn publish_chains(chains: Vec<MonitoredChain>) {
+    *CHAINS.write().await = chains;
+}
+
+/// Fewest legs a chain can be traded with.
+pub const MIN_CHAIN_LEGS: usize = 3;
+
+/// Checks that a chain of `legs` legs is long enough to be traded.
+pub fn check_chain_length(legs: usize) -> anyhow::Result<()> {
+    if legs < MIN_CHAIN_LEGS {
+        bail!("Chain has {legs} legs, at least {MIN_CHAIN_LEGS} are needed");
+    }
+    Ok(())
+}
+
+/// Checks that the legs form a cycle: every leg spends the asset the previous one received
+/// and the last one returns the asset the first one spent. An Asc leg sells the base asset
+/// for the quote asset, a Desc leg buys it. Legs are `(symbol, base_asset, quote_asset, order)`.
+pub fn check_chain_cycle<'a>(
+    legs: impl IntoIterator<Item = (&'a str, &'a str, &'a str, SymbolOrder)>,
+) -> anyhow::Result<()> {
+    let mut start = None;
+    let mut held: Option<(&str, &str)> = None;
+
+    for (symbol, base_asset, quote_asset, order) in legs {
+        let (spent, received) = match order {
+            SymbolOrder::Asc => (base_asset, quote_asset),
+            SymbolOrder::Desc => (quote_asset, base_asset),
+        };
+        match held {
+            None => start = Some(spent),
+            Some((previous, asset)) if asset != spent => {
+                bail!("{symbol} spends {spent}, but {previous} returns {asset}")
+            }
+            Some(_) => {}
+        }
+        held = Some((symbol, received));
+    }
+
+    if let (Some(start), Some((last, asset))) = (start, held)
+        && asset != start
+    {
+        bail!("{last} returns {asset} instead of the starting {start}");
+    }
+    Ok(())
+}
+
+/// Pagination of `GET /chains`.
+#[derive(Debug, Deserialize)]
+pub struct ChainsQuery {
+    #[serde(default)]
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+/// A page of monitored chains.
+#[derive(Debug, Serialize)]
+pub struct ChainsPage {
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+    pub chains: Vec<MonitoredChain>,
+}
+
+/// Serves the monitored chains on `GET /chains?offset=&limit=`.
+pub struct ChainsProcess {
+    chains: Arc<RwLock<Vec<MonitoredChain>>>,
+}
+
+impl ChainsProcess {
+    /// Page size when no limit is given.
+    pub const DEFAULT_LIMIT: usize = 100;
+    /// Largest accepted page size, larger limits are clamped.
+    pub const MAX_LIMIT: usize = 1000;
+
+    #[must_use]
+    pub fn new(chains: Arc<RwLock<Vec<MonitoredChain>>>) -> Self {
+        Self { chains }
+    }
+}
+
+impl Default for ChainsProcess {
+    fn default() -> Self {
+        Self::new(Arc::clone(&CHAINS))
+    }
+}
+
+#[async_trait]
+impl HttpServerProcess for ChainsProcess {
+    async fn pre_run(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn run(&self, _token: CancellationToken) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn router(&self) -> Option<Router> {
+        Some(
+            Router::new()
+                .route("/chains", get(list_chains))
+                .with_state(Arc::clone(&self.chains)),
+        )
+    }
+}
+
+async fn list_chains(
+    State(chains): State<Arc<RwLock<Vec<MonitoredChain>>>>,
+    Query(query): Query<ChainsQuery>,
+) -> Json<ChainsPage> {
+    let limit = query
+        .limit
+        .unwrap_or(ChainsProcess::DEFAULT_LIMIT)
+        .min(ChainsProcess::MAX_LIMIT);
+
+    let chains = chains.read().await;
+    Json(ChainsPage {
+        total: chains.len(),
+        offset: query.offset,
+        limit,
+        chains: chains
+            .iter()
+            .skip(query.offset)
+            .take(limit)
+            .cloned()
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(symbols: [&str; 3]) -> MonitoredChain {
+        symbols
+            .iter()
+            .map(|symbol| MonitoredSymbol {
+                symbol: (*symbol).to_owned(),
+                base_asset: symbol[..3].to_owned(),
+                quote_asset: symbol[3..].to_owned(),
+                order: SymbolOrder::Asc,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_chain_must_return_to_start() {
+        use SymbolOrder::{Asc, Desc};
+
+        assert!(
+            check_chain_cycle([
+                ("BTCUSDT", "BTC", "USDT", Asc),
+                ("ETHUSDT", "ETH", "USDT", Desc),
+                ("ETHBTC", "ETH", "BTC", Asc),
+            ])
+            .is_ok()
+        );
+
+        // Every leg continues from the previous one, but the chain ends in ETH.
+        let error = check_chain_cycle([
+            ("BTCUSDT", "BTC", "USDT", Asc),
+            ("ETHUSDT", "ETH", "USDT", Desc),
+        ])
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "ETHUSDT returns ETH instead of the starting BTC"
+        );
+
+        let error = check_chain_cycle([
+            ("BTCUSDT", "BTC", "USDT", Asc),
+            ("ETHBTC", "ETH", "BTC", Desc),
+            ("ETHUSDT", "ETH", "USDT", Asc),
+        ])
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "ETHBTC spends BTC, but BTCUSDT returns USDT"
+        );
+    }
+
+    #[test]
+    fn test_short_chains_are_refused() {
+        assert!(check_chain_length(3).is_ok());
+        assert!(check_chain_length(4).is_ok());
+        assert_eq!(
+            check_chain_length(2).unwrap_err().to_string(),
+            "Chain has 2 legs, at least 3 are needed"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_chains_endpoint_paginates() -> anyhow::Result<()> {
+        let chains = Arc::new(RwLock::new(vec![
+            chain(["BTCUSDT", "ETHBTC", "ETHUSDT"]),
+            chain(["BTCUSDT", "SOLBTC", "SOLUSDT"]),
+            chain(["ETHUSDT", "SOLETH", "SOLUSDT"]),
+        ]));
+        let router = ChainsProcess::new(chains)
+            .router()
+            .expect("chains process serves routes");
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}/chains", listener.local_addr()?);
+        let server = tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let page: serde_json::Value = reqwest::get(format!("{url}?offset=1&limit=1"))
+            .await?
+            .json()
+            .await?;
+        assert_eq!(
+            page,
+            serde_json::json!({
+                "total": 3,
+                "offset": 1,
+                "limit": 1,
+                "chains": [[
+                    {"symbol": "BTCUSDT", "base_asset": "BTC", "quote_asset": "USDT", "order": "ASC"},
+                    {"symbol": "SOLBTC", "base_asset": "SOL", "quote_asset": "BTC", "order": "ASC"},
+                    {"symbol": "SOLUSDT", "base_asset": "SOL", "quote_asset": "USDT", "order": "ASC"}
+                ]]
+            })
+        );
+
+        let page: serde_json::Value = reqwest::get(&url).await?.json().await?;
+        assert_eq!(page["limit"], ChainsProcess::DEFAULT_LIMIT);
+        assert_eq!(page["chains"].as_array().map(Vec::len), Some(3));
+
+        let page: serde_json::Value = reqwest::get(format!("{url}?offset=5&limit=100000"))
+            .await?
+            .json()
+            .await?;
+        assert_eq!(page["limit"], ChainsProcess::MAX_LIMIT);
+        assert_eq!(page["chains"], serde_json::json!([]));
+
+        server.abort();
+        Ok(())
+    }
+}
@@ -1,8 +1,14 @@
 use std::sync::LazyLock;
 
-use tokio::sync::{Mutex, watch};
+use tokio::{
+    sync::{Mutex, broadcast, watch},
+    time::Instant,
+};
 
-use crate::model::orders::ChainOrders;
+use crate::{
+    model::orders::{ChainOrders, FilledChain},
+    runtime::metrics::METRICS,
+};
 
 // Global channel for distributing order chains.
 pub static ORDERS_CHANNEL: LazyLock<OrdersChannel> = LazyLock::new(|| {
@@ -17,3 +23,124 @@ pub struct OrdersChannel {
     pub tx: watch::Sender<ChainOrders>,
     pub rx: Mutex<watch::Receiver<ChainOrders>>,
 }
+
+impl OrdersChannel {
+    /// Queues `chain` for the sender, recording the time since its ticker event was received.
+    /// The chain is also broadcast to [`DETECTED_CHAINS`] while anything listens to it.
+    ///
+    /// # Errors
+    ///
+    /// Fails if nothing receives the chains.
+    pub fn publish(&self, mut chain: ChainOrders) -> anyhow::Result<()> {
+        let now = Instant::now();
+        if let Some(received_at) = chain.received_at {
+            METRICS.record_detection_latency(now - received_at);
+        }
+        chain.queued_at = Some(now);
+        if DETECTED_CHAINS.receiver_count() > 0 {
+            _ = DETECTED_CHAINS.send(chain.clone());
+        }
+        self.tx
+            .send(chain)
+            .map_err(|_| anyhow::anyhow!("Orders channel has no receiver"))
+    }
+}
+
+/// Capacity of [`FILLED_CHAINS`], receivers lagging further behind skip the oldest chains.
+const FILLED_CHAINS_CAPACITY: usize = 64;
+
+// Global broadcast of chains fully executed by a sender.
+pub static FILLED_CHAINS: LazyLock<broadcast::Sender<FilledChain>> =
+    LazyLock::new(|| broadcast::channel(FILLED_CHAINS_CAPACITY).0);
+
+/// Capacity of [`DETECTED_CHAINS`], receivers lagging further behind skip the oldest chains.
+const DETECTED_CHAINS_CAPACITY: usize = 256;
+
+// Global broadcast of every chain queued for the sender, whether it is executed or not.
+pub static DETECTED_CHAINS: LazyLock<broadcast::Sender<ChainOrders>> =
+    LazyLock::new(|| broadcast::channel(DETECTED_CHAINS_CAPACITY).0);
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex as StdMutex},
+        time::Duration,
+    };
+
+    use metrics::{
+        Counter, Gauge, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder, SharedString,
+        Unit,
+    };
+
+    use super::*;
+
+    type Samples = Arc<StdMutex<Vec<(String, f64)>>>;
+
+    /// Records the samples of every histogram with its name.
+    #[derive(Default)]
+    struct HistogramRecorder(Samples);
+
+    struct NamedHistogram {
+        name: String,
+        samples: Samples,
+    }
+
+    impl HistogramFn for NamedHistogram {
+        fn record(&self, value: f64) {
+            self.samples
+                .lock()
+                .unwrap()
+                .push((self.name.clone(), value));
+        }
+    }
+
+    impl Recorder for HistogramRecorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, _: &Key, _: &Metadata<'_>) -> Counter {
+            Counter::noop()
+        }
+
+        fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::from_arc(Arc::new(NamedHistogram {
+                name: key.name().to_owned(),
+                samples: Arc::clone(&self.0),
+            }))
+        }
+    }
+
+    #[test]
+    fn test_publish_records_detection_latency() {
+        let (tx, rx) = watch::channel(ChainOrders::default());
+        let channel = OrdersChannel {
+            tx,
+            rx: Mutex::new(rx),
+        };
+        let received_at = Instant::now();
+        std::thread::sleep(Duration::from_millis(5));
+
+        let recorder = HistogramRecorder::default();
+        metrics::with_local_recorder(&recorder, || {
+            channel
+                .publish(ChainOrders {
+                    received_at: Some(received_at),
+                    ..Default::default()
+                })
+                .unwrap();
+            // A chain without a ticker event has no detection latency.
+            channel.publish(ChainOrders::default()).unwrap();
+        });
+
+        let samples = recorder.0.lock().unwrap().clone();
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].0, "chain_detection_latency_seconds");
+        assert!(samples[0].1 >= 0.005, "{samples:?}");
+        assert!(channel.rx.try_lock().unwrap().borrow().queued_at.is_some());
+    }
+}
@@ -0,0 +1,121 @@
+use std::{
+    future::Future,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use anyhow::bail;
+use tokio_util::sync::CancellationToken;
+use tools::misc;
+use tracing::warn;
+
+/// Last measured drift between the local clock and the exchange clock.
+/// Trading is refused while it exceeds the configured maximum, because signed requests
+/// would be rejected for an invalid timestamp.
+pub struct ClockDrift {
+    max_drift_ms: u64,
+    drift_ms: AtomicU64,
+}
+
+impl ClockDrift {
+    /// How often the drift is re-measured.
+    pub const MEASURE_INTERVAL: Duration = Duration::from_secs(60);
+
+    #[must_use]
+    pub fn new(max_drift: Duration) -> Self {
+        Self {
+            max_drift_ms: u64::try_from(max_drift.as_millis()).unwrap_or(u64::MAX),
+            drift_ms: AtomicU64::new(0),
+        }
+    }
+
+    /// Records a measurement: the exchange time (ms) received for a request sent at `sent_ms`
+    /// and answered at `received_ms`. Returns the absolute drift in milliseconds.
+    pub fn record(&self, server_ms: u128, sent_ms: u128, received_ms: u128) -> u64 {
+        // The exchange stamped the response roughly halfway through the round trip.
+        let local_ms = sent_ms.midpoint(received_ms);
+        let drift_ms = u64::try_from(server_ms.abs_diff(local_ms)).unwrap_or(u64::MAX);
+        self.drift_ms.store(drift_ms, Ordering::Relaxed);
+
+        if drift_ms > self.max_drift_ms {
+            warn!(
+                drift_ms,
+                max_drift_ms = self.max_drift_ms,
+                local_ahead = local_ms > server_ms,
+                "⏰ [Engine] Clock drift exceeds the limit, trading is blocked until it recovers"
+            );
+        }
+
+        drift_ms
+    }
+
+    /// Fails if the last measured drift is above the limit.
+    pub fn check(&self) -> anyhow::Result<()> {
+        let drift_ms = self.drift_ms.load(Ordering::Relaxed);
+        if drift_ms > self.max_drift_ms {
+            bail!(
+                "clock drift of {drift_ms} ms exceeds the limit of {} ms",
+                self.max_drift_ms
+            );
+        }
+        Ok(())
+    }
+
+    /// Measures the drift with `fetch_server_ms` right away and then every `interval` until
+    /// cancelled. Failed measurements are logged and keep the previous value.
+    pub async fn monitor<F, Fut>(&self, token: CancellationToken, interval: Duration, fetch: F)
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = anyhow::Result<u128>>,
+    {
+        loop {
+            let sent_ms = misc::time::get_current_timestamp().as_millis();
+            match fetch().await {
+                Ok(server_ms) => {
+                    let received_ms = misc::time::get_current_timestamp().as_millis();
+                    self.record(server_ms, sent_ms, received_ms);
+                }
+                Err(e) => warn!(error = ?e, "Failed to measure clock drift"),
+            }
+
+            tokio::select! {
+                () = token.cancelled() => return,
+                () = tokio::time::sleep(interval) => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_excessive_drift_blocks_trading() {
+        let clock = ClockDrift::new(Duration::from_millis(1_000));
+        assert!(clock.check().is_ok());
+
+        // Local clock is 5s behind the exchange.
+        assert_eq!(clock.record(1_005_000, 999_900, 1_000_100), 5_000);
+        assert!(clock.check().is_err());
+
+        // Back within the limit after the clock has been corrected.
+        assert_eq!(clock.record(1_000_500, 1_000_000, 1_000_200), 400);
+        assert!(clock.check().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_monitor_records_server_time() {
+        let clock = ClockDrift::new(Duration::from_millis(1_000));
+        let token = CancellationToken::new();
+        token.cancel();
+
+        clock
+            .monitor(token, Duration::from_secs(60), || async {
+                Ok(misc::time::get_current_timestamp().as_millis() + 60_000)
+            })
+            .await;
+
+        assert!(clock.check().is_err());
+    }
+}
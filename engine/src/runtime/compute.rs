@@ -0,0 +1,58 @@
+use anyhow::Context;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use tokio::sync::oneshot;
+
+/// Dedicated thread pool for chain profit calculations, keeping the CPU-heavy work off the
+/// tokio worker threads so it cannot starve websocket IO under load.
+#[derive(Debug)]
+pub struct ComputePool {
+    pool: ThreadPool,
+}
+
+impl ComputePool {
+    /// Builds a pool of `threads` threads named `chain-compute-<idx>`.
+    pub fn new(threads: usize) -> anyhow::Result<Self> {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|idx| format!("chain-compute-{idx}"))
+            .build()
+            .context("Failed to build chain compute pool")?;
+        Ok(Self { pool })
+    }
+
+    /// Runs `f` on the pool and waits for its result without blocking the runtime.
+    pub async fn run<F, R>(&self, f: F) -> anyhow::Result<R>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        self.pool.spawn(move || {
+            _ = tx.send(f());
+        });
+        rx.await.context("Chain compute task panicked")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_runs_off_the_runtime_threads() -> anyhow::Result<()> {
+        let pool = ComputePool::new(1)?;
+
+        let (thread_name, in_runtime) = pool
+            .run(|| {
+                (
+                    std::thread::current().name().map(str::to_owned),
+                    tokio::runtime::Handle::try_current().is_ok(),
+                )
+            })
+            .await?;
+
+        assert_eq!(thread_name.as_deref(), Some("chain-compute-0"));
+        assert!(!in_runtime);
+        Ok(())
+    }
+}
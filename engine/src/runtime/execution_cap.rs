@@ -0,0 +1,94 @@
+use std::{collections::HashMap, time::Duration};
+
+use tokio::time::Instant;
+
+use crate::model::orders::ChainOrders;
+
+/// Caps how many times each chain is executed within a time window. The window of a chain
+/// starts with its first execution; once the cap is reached the chain is skipped until the
+/// window rolls, independent of the interval between executions.
+pub struct ExecutionCap {
+    max_executions: u32,
+    window: Duration,
+    executed: HashMap<String, ExecutionWindow>,
+}
+
+struct ExecutionWindow {
+    started_at: Instant,
+    count: u32,
+}
+
+impl ExecutionCap {
+    /// Creates a cap of `max_executions` per chain within every `window`.
+    #[must_use]
+    pub fn new(max_executions: u32, window: Duration) -> Self {
+        Self {
+            max_executions,
+            window,
+            executed: HashMap::new(),
+        }
+    }
+
+    /// Returns true if the chain has reached the cap within its current window at `now`.
+    #[must_use]
+    pub fn is_capped(&self, chain: &ChainOrders, now: Instant) -> bool {
+        self.executed.get(&Self::key(chain)).is_some_and(|window| {
+            now.duration_since(window.started_at) < self.window
+                && window.count >= self.max_executions
+        })
+    }
+
+    /// Records an execution of the chain at `now`, starting a new window if the last one
+    /// has rolled.
+    pub fn record(&mut self, chain: &ChainOrders, now: Instant) {
+        let window = self
+            .executed
+            .entry(Self::key(chain))
+            .or_insert(ExecutionWindow {
+                started_at: now,
+                count: 0,
+            });
+        if now.duration_since(window.started_at) >= self.window {
+            window.started_at = now;
+            window.count = 0;
+        }
+        window.count += 1;
+    }
+
+    fn key(chain: &ChainOrders) -> String {
+        chain.extract_symbols().join("-")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::orders::test_utils::chain;
+
+    #[test]
+    fn test_chain_capped_until_window_rolls() {
+        let secs = Duration::from_secs;
+        let start = Instant::now();
+        let mut cap = ExecutionCap::new(2, secs(3600));
+        let capped = chain(&["BTCUSDT", "ETHBTC", "ETHUSDT"]);
+        let other = chain(&["BTCUSDT", "SOLBTC", "SOLUSDT"]);
+
+        assert!(!cap.is_capped(&capped, start));
+        cap.record(&capped, start);
+        assert!(!cap.is_capped(&capped, start + secs(10)));
+        cap.record(&capped, start + secs(10));
+
+        // Cap reached: skipped for the rest of the window, other chains are unaffected.
+        assert!(cap.is_capped(&capped, start + secs(20)));
+        assert!(cap.is_capped(&capped, start + secs(3599)));
+        assert!(!cap.is_capped(&other, start + secs(20)));
+
+        // The next window starts with the next execution.
+        assert!(!cap.is_capped(&capped, start + secs(3600)));
+        cap.record(&capped, start + secs(4000));
+        assert!(!cap.is_capped(&capped, start + secs(4010)));
+        cap.record(&capped, start + secs(4010));
+        assert!(cap.is_capped(&capped, start + secs(4020)));
+        assert!(!cap.is_capped(&capped, start + secs(7600)));
+    }
+}
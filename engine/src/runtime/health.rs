@@ -0,0 +1,208 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, LazyLock, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use axum::{Json, Router, extract::State, http::StatusCode, routing::get};
+use serde::Serialize;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+use tools::http::http_server::HttpServerProcess;
+
+use crate::runtime::kill_switch::KillSwitch;
+
+/// Last book ticker received per market data stream, published by the exchange services.
+pub static TICKS: LazyLock<Arc<TickClock>> = LazyLock::new(Arc::default);
+
+/// When each market data stream last received a book ticker.
+#[derive(Debug, Default)]
+pub struct TickClock {
+    streams: Mutex<HashMap<String, Instant>>,
+}
+
+impl TickClock {
+    /// Records a book ticker received on `stream` at `at`.
+    pub fn record(&self, stream: &str, at: Instant) {
+        let mut streams = self.streams.lock().unwrap_or_else(|e| e.into_inner());
+        match streams.get_mut(stream) {
+            Some(last) => *last = at,
+            None => {
+                streams.insert(stream.to_owned(), at);
+            }
+        }
+    }
+
+    /// Reports the age of the last tick of every stream at `now`. Healthy while the latest
+    /// tick of any stream is at most `stale_after` old.
+    #[must_use]
+    pub fn report(&self, now: Instant, stale_after: Duration) -> HealthReport {
+        let streams = self.streams.lock().unwrap_or_else(|e| e.into_inner());
+        let ages: BTreeMap<String, Duration> = streams
+            .iter()
+            .map(|(stream, &at)| (stream.clone(), now.saturating_duration_since(at)))
+            .collect();
+
+        HealthReport {
+            healthy: ages.values().any(|&age| age <= stale_after),
+            trading_halted: false,
+            stale_after_ms: stale_after.as_millis(),
+            last_tick_age_ms: ages
+                .into_iter()
+                .map(|(stream, age)| (stream, age.as_millis()))
+                .collect(),
+        }
+    }
+}
+
+/// Market data health served on `GET /health`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HealthReport {
+    pub healthy: bool,
+    /// The kill switch halted trading. Left out of `healthy`, so restarting on failed
+    /// health checks does not silently resume trading.
+    pub trading_halted: bool,
+    pub stale_after_ms: u128,
+    /// Time since the last tick, per stream.
+    pub last_tick_age_ms: BTreeMap<String, u128>,
+}
+
+/// Serves `GET /health`: 200 while book tickers are being received, 503 once the last one is
+/// older than the staleness window or none was received yet. Also reports whether the kill
+/// switch halted trading.
+pub struct HealthProcess {
+    ticks: Arc<TickClock>,
+    kill_switch: Arc<KillSwitch>,
+    stale_after: Duration,
+}
+
+impl HealthProcess {
+    #[must_use]
+    pub fn new(ticks: Arc<TickClock>, kill_switch: Arc<KillSwitch>, stale_after: Duration) -> Self {
+        Self {
+            ticks,
+            kill_switch,
+            stale_after,
+        }
+    }
+}
+
+#[async_trait]
+impl HttpServerProcess for HealthProcess {
+    async fn pre_run(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn run(&self, _token: CancellationToken) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn router(&self) -> Option<Router> {
+        Some(
+            Router::new()
+                .route("/health", get(health))
+                .with_state(HealthState {
+                    ticks: Arc::clone(&self.ticks),
+                    kill_switch: Arc::clone(&self.kill_switch),
+                    stale_after: self.stale_after,
+                }),
+        )
+    }
+}
+
+#[derive(Clone)]
+struct HealthState {
+    ticks: Arc<TickClock>,
+    kill_switch: Arc<KillSwitch>,
+    stale_after: Duration,
+}
+
+async fn health(State(state): State<HealthState>) -> (StatusCode, Json<HealthReport>) {
+    let mut report = state.ticks.report(Instant::now(), state.stale_after);
+    report.trading_halted = state.kill_switch.is_halted();
+    let status = if report.healthy {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_reflects_the_latest_tick() {
+        let secs = Duration::from_secs;
+        let start = Instant::now();
+        let ticks = TickClock::default();
+        assert!(!ticks.report(start, secs(30)).healthy);
+
+        ticks.record("ws-0", start);
+        ticks.record("ws-1", start + secs(20));
+        let report = ticks.report(start + secs(40), secs(30));
+        assert!(report.healthy);
+        assert_eq!(
+            report.last_tick_age_ms,
+            [("ws-0".to_owned(), 40_000), ("ws-1".to_owned(), 20_000)].into()
+        );
+
+        // Stale once the latest tick of every stream is older than the window.
+        assert!(!ticks.report(start + secs(51), secs(30)).healthy);
+        ticks.record("ws-0", start + secs(50));
+        assert!(ticks.report(start + secs(51), secs(30)).healthy);
+    }
+
+    #[tokio::test]
+    async fn test_health_endpoint_status() -> anyhow::Result<()> {
+        let ticks = Arc::new(TickClock::default());
+        let kill_switch = Arc::new(KillSwitch::new(Some(1)));
+        let router = HealthProcess::new(
+            Arc::clone(&ticks),
+            Arc::clone(&kill_switch),
+            Duration::from_secs(30),
+        )
+        .router()
+        .expect("health process serves routes");
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}/health", listener.local_addr()?);
+        let server = tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let response = reqwest::get(&url).await?;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body: serde_json::Value = response.json().await?;
+        assert_eq!(body["healthy"], false);
+        assert_eq!(body["last_tick_age_ms"], serde_json::json!({}));
+
+        ticks.record("ws-0", Instant::now());
+        let response = reqwest::get(&url).await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = response.json().await?;
+        assert_eq!(body["healthy"], true);
+        assert_eq!(body["trading_halted"], false);
+        assert_eq!(body["stale_after_ms"], 30_000);
+        assert!(
+            body["last_tick_age_ms"]["ws-0"]
+                .as_u64()
+                .is_some_and(|age| age < 30_000)
+        );
+
+        ticks.record("ws-0", Instant::now() - Duration::from_secs(31));
+        let response = reqwest::get(&url).await?;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        // A halted bot is reported, without failing the check.
+        ticks.record("ws-0", Instant::now());
+        kill_switch.record_failure();
+        let response = reqwest::get(&url).await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = response.json().await?;
+        assert_eq!(body["trading_halted"], true);
+
+        server.abort();
+        Ok(())
+    }
+}
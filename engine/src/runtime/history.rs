@@ -0,0 +1,414 @@
+use std::{
+    collections::BTreeMap,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use rusqlite::{Connection, params};
+use rust_decimal::Decimal;
+use tokio::sync::broadcast::{
+    Receiver,
+    error::{RecvError, TryRecvError},
+};
+use tokio_util::sync::CancellationToken;
+use tools::http::http_server::HttpServerProcess;
+use tracing::{info, warn};
+
+use crate::{
+    DETECTED_CHAINS, FILLED_CHAINS,
+    model::orders::{ChainOrders, FilledChain},
+};
+
+const CREATE_TABLE: &str = "CREATE TABLE IF NOT EXISTS opportunities (
+    chain_id TEXT PRIMARY KEY,
+    ts INTEGER NOT NULL,
+    exchange TEXT NOT NULL,
+    triangle TEXT NOT NULL,
+    base_asset TEXT NOT NULL,
+    expected_profit TEXT NOT NULL,
+    expected_profit_percent TEXT NOT NULL,
+    fee_percent TEXT NOT NULL,
+    executed INTEGER NOT NULL DEFAULT 0,
+    realized_profit TEXT
+)";
+
+const MS_PER_DAY: u128 = 86_400_000;
+
+/// Keeps every detected chain in SQLite, marking the ones the sender executed, so the
+/// profitability of each triangle can be analyzed over time.
+///
+/// Unlike the ledger, which only holds executed chains with their fills, this records the
+/// opportunities themselves. Chains are received from [`DETECTED_CHAINS`] and
+/// [`FILLED_CHAINS`]; a history lagging too far behind skips the oldest chains.
+pub struct HistoryProcess {
+    conn: Arc<Mutex<Connection>>,
+    exchange: String,
+}
+
+/// Opportunities of a triangle detected within one UTC day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TriangleSummary {
+    /// UTC day as `YYYY-MM-DD`.
+    pub day: String,
+    /// Symbols of the chain legs, in order.
+    pub triangle: String,
+    pub base_asset: String,
+    pub detected: u64,
+    pub executed: u64,
+    /// Sum of the profits estimated on detection, in the base asset.
+    pub expected_profit: Decimal,
+    /// Sum of the profits realized by the executed chains, in the base asset.
+    pub realized_profit: Decimal,
+}
+
+/// Chain to write, as detected or as executed by the sender.
+enum Opportunity {
+    Detected(ChainOrders),
+    Filled(FilledChain),
+}
+
+impl HistoryProcess {
+    /// Opens the history at `path`, creating the database file and table if missing.
+    pub fn open(path: &Path, exchange: &str) -> anyhow::Result<Self> {
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open history {}", path.display()))?;
+        Self::from_connection(conn, exchange)
+    }
+
+    /// Opens a history kept in memory, lost when dropped.
+    pub fn in_memory(exchange: &str) -> anyhow::Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?, exchange)
+    }
+
+    fn from_connection(conn: Connection, exchange: &str) -> anyhow::Result<Self> {
+        conn.execute(CREATE_TABLE, [])
+            .context("Failed to create history table")?;
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+            exchange: exchange.to_owned(),
+        })
+    }
+
+    /// Writes the detected chains in a single transaction.
+    pub async fn record_detected(&self, chains: Vec<ChainOrders>) -> anyhow::Result<()> {
+        self.record(chains.into_iter().map(Opportunity::Detected).collect())
+            .await
+    }
+
+    /// Marks the chains as executed in a single transaction, recording the ones whose
+    /// detection was missed as well.
+    pub async fn record_filled(&self, chains: Vec<FilledChain>) -> anyhow::Result<()> {
+        self.record(chains.into_iter().map(Opportunity::Filled).collect())
+            .await
+    }
+
+    async fn record(&self, opportunities: Vec<Opportunity>) -> anyhow::Result<()> {
+        let conn = self.conn.clone();
+        let exchange = self.exchange.clone();
+        tokio::task::spawn_blocking(move || Self::insert(&conn, &exchange, &opportunities)).await?
+    }
+
+    fn insert(
+        conn: &Mutex<Connection>,
+        exchange: &str,
+        opportunities: &[Opportunity],
+    ) -> anyhow::Result<()> {
+        let mut conn = conn
+            .lock()
+            .map_err(|_| anyhow::anyhow!("History connection poisoned"))?;
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT INTO opportunities (chain_id, ts, exchange, triangle, base_asset,
+                     expected_profit, expected_profit_percent, fee_percent, executed,
+                     realized_profit)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                 ON CONFLICT (chain_id) DO UPDATE SET
+                     executed = max(executed, excluded.executed),
+                     realized_profit = coalesce(excluded.realized_profit, realized_profit)",
+            )?;
+            for opportunity in opportunities {
+                let (chain, realized_profit) = match opportunity {
+                    Opportunity::Detected(chain) => (chain, None),
+                    Opportunity::Filled(filled) => (&filled.chain, Some(filled.profit)),
+                };
+                let (expected_profit, expected_profit_percent) = chain.compute_profit();
+                stmt.execute(params![
+                    chain.chain_id.to_string(),
+                    i64::try_from(chain.ts)?,
+                    exchange,
+                    chain.extract_symbols().join(" → "),
+                    chain.base_asset,
+                    expected_profit.to_string(),
+                    expected_profit_percent.to_string(),
+                    chain.fee_percent.to_string(),
+                    realized_profit.is_some(),
+                    realized_profit.map(|profit| profit.to_string()),
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Summarizes the opportunities detected on the exchange since `since_ts` (ms) by UTC day
+    /// and triangle, oldest day first.
+    pub fn summary(&self, since_ts: u128) -> anyhow::Result<Vec<TriangleSummary>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| anyhow::anyhow!("History connection poisoned"))?;
+        let mut stmt = conn.prepare(
+            "SELECT date(ts / 1000, 'unixepoch'), triangle, base_asset, expected_profit,
+                 executed, realized_profit
+             FROM opportunities WHERE exchange = ?1 AND ts >= ?2",
+        )?;
+        let rows = stmt.query_map(params![self.exchange, i64::try_from(since_ts)?], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, bool>(4)?,
+                row.get::<_, Option<String>>(5)?,
+            ))
+        })?;
+
+        // Summed here rather than in SQL, which would round the decimals through floats.
+        let mut summaries: BTreeMap<(String, String), TriangleSummary> = BTreeMap::new();
+        for row in rows {
+            let (day, triangle, base_asset, expected_profit, executed, realized) = row?;
+            let summary = summaries
+                .entry((day.clone(), triangle.clone()))
+                .or_insert_with(|| TriangleSummary {
+                    day,
+                    triangle,
+                    base_asset,
+                    detected: 0,
+                    executed: 0,
+                    expected_profit: Decimal::ZERO,
+                    realized_profit: Decimal::ZERO,
+                });
+            summary.detected += 1;
+            summary.expected_profit += expected_profit.parse::<Decimal>()?;
+            if executed {
+                summary.executed += 1;
+            }
+            if let Some(realized) = realized {
+                summary.realized_profit += realized.parse::<Decimal>()?;
+            }
+        }
+        Ok(summaries.into_values().collect())
+    }
+
+    /// Summarizes the opportunities of the last `days` days (the current one included), as
+    /// of `now_ts` (ms).
+    pub fn summary_of_days(&self, days: u32, now_ts: u128) -> anyhow::Result<Vec<TriangleSummary>> {
+        let today = now_ts / MS_PER_DAY * MS_PER_DAY;
+        let since_ts = today.saturating_sub(u128::from(days.saturating_sub(1)) * MS_PER_DAY);
+        self.summary(since_ts)
+    }
+
+    /// Writes received chains until cancelled, batching the ones buffered meanwhile. Chains
+    /// still buffered on cancellation are written before returning.
+    pub async fn write_received(
+        &self,
+        mut detected_rx: Receiver<ChainOrders>,
+        mut filled_rx: Receiver<FilledChain>,
+        token: &CancellationToken,
+    ) {
+        loop {
+            let (mut detected, mut filled) = (vec![], vec![]);
+            let stop = tokio::select! {
+                () = token.cancelled() => true,
+                result = detected_rx.recv() => Self::received(result, &mut detected),
+                result = filled_rx.recv() => Self::received(result, &mut filled),
+            };
+            Self::drain(&mut detected_rx, &mut detected);
+            Self::drain(&mut filled_rx, &mut filled);
+
+            // Detections first, so chains detected and executed meanwhile end up executed.
+            let opportunities: Vec<Opportunity> = detected
+                .into_iter()
+                .map(Opportunity::Detected)
+                .chain(filled.into_iter().map(Opportunity::Filled))
+                .collect();
+            if !opportunities.is_empty()
+                && let Err(e) = self.record(opportunities).await
+            {
+                warn!(error = ?e, "Failed to write chains to the history");
+            }
+            if stop {
+                break;
+            }
+        }
+    }
+
+    /// Keeps a received chain, returning whether the channel is closed.
+    fn received<T>(result: Result<T, RecvError>, chains: &mut Vec<T>) -> bool {
+        match result {
+            Ok(chain) => {
+                chains.push(chain);
+                false
+            }
+            Err(RecvError::Lagged(skipped)) => {
+                warn!(skipped, "History chains skipped");
+                false
+            }
+            Err(RecvError::Closed) => true,
+        }
+    }
+
+    /// Takes the chains already buffered in the channel without waiting.
+    fn drain<T: Clone>(rx: &mut Receiver<T>, chains: &mut Vec<T>) {
+        loop {
+            match rx.try_recv() {
+                Ok(chain) => chains.push(chain),
+                Err(TryRecvError::Lagged(skipped)) => {
+                    warn!(skipped, "History chains skipped");
+                }
+                Err(TryRecvError::Empty | TryRecvError::Closed) => break,
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl HttpServerProcess for HistoryProcess {
+    async fn pre_run(&self) -> anyhow::Result<()> {
+        info!(
+            exchange = self.exchange,
+            "Detected chains are written to the history"
+        );
+        Ok(())
+    }
+
+    async fn run(&self, token: CancellationToken) -> anyhow::Result<()> {
+        self.write_received(
+            DETECTED_CHAINS.subscribe(),
+            FILLED_CHAINS.subscribe(),
+            &token,
+        )
+        .await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::{ChainOrder, enums::SymbolOrder, model::orders::test_utils::order};
+
+    // 2024-01-01T00:00:00Z.
+    const DAY_ONE: u128 = 1_704_067_200_000;
+
+    fn chain(ts: u128, symbols: [&str; 3], output_qty: Decimal) -> ChainOrders {
+        let leg = |symbol: &str, quote_qty| ChainOrder {
+            symbol_order: SymbolOrder::Desc,
+            base_qty: Decimal::from(100),
+            quote_qty,
+            ..order(symbol)
+        };
+        ChainOrders {
+            ts,
+            chain_id: Uuid::new_v4(),
+            base_asset: "USDT".to_owned(),
+            orders: vec![
+                leg(symbols[0], Decimal::from(100)),
+                leg(symbols[1], Decimal::from(100)),
+                leg(symbols[2], output_qty),
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn filled(chain: ChainOrders, profit: Decimal) -> FilledChain {
+        FilledChain {
+            chain,
+            fills: vec![],
+            profit,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_summary_by_day_and_triangle() -> anyhow::Result<()> {
+        let history = HistoryProcess::in_memory("binance")?;
+        let btc = ["BTCUSDT", "ETHBTC", "ETHUSDT"];
+        let bnb = ["BNBUSDT", "ETHBNB", "ETHUSDT"];
+
+        let executed = chain(DAY_ONE + 1_000, btc, Decimal::from(102));
+        history
+            .record_detected(vec![
+                executed.clone(),
+                chain(DAY_ONE + 2_000, btc, Decimal::new(1005, 1)),
+                chain(DAY_ONE + 3_000, bnb, Decimal::from(101)),
+                chain(DAY_ONE + MS_PER_DAY, btc, Decimal::from(103)),
+            ])
+            .await?;
+        history
+            .record_filled(vec![
+                filled(executed, Decimal::new(15, 1)),
+                // Executed without its detection being recorded.
+                filled(
+                    chain(DAY_ONE + MS_PER_DAY + 1, bnb, Decimal::from(101)),
+                    Decimal::new(-2, 1),
+                ),
+            ])
+            .await?;
+
+        let summary = history.summary(DAY_ONE)?;
+        assert_eq!(summary.len(), 4);
+        assert_eq!(
+            summary[1],
+            TriangleSummary {
+                day: "2024-01-01".to_owned(),
+                triangle: "BTCUSDT → ETHBTC → ETHUSDT".to_owned(),
+                base_asset: "USDT".to_owned(),
+                detected: 2,
+                executed: 1,
+                expected_profit: Decimal::new(25, 1),
+                realized_profit: Decimal::new(15, 1),
+            }
+        );
+        assert_eq!(summary[0].triangle, "BNBUSDT → ETHBNB → ETHUSDT");
+        assert_eq!((summary[0].detected, summary[0].executed), (1, 0));
+        assert_eq!(summary[2].day, "2024-01-02");
+        assert_eq!(summary[3].triangle, "BTCUSDT → ETHBTC → ETHUSDT");
+        assert_eq!(summary[2].realized_profit, Decimal::new(-2, 1));
+        assert_eq!(summary[2].executed, 1);
+
+        let last_day = history.summary_of_days(1, DAY_ONE + MS_PER_DAY + 5_000)?;
+        assert_eq!(last_day.len(), 2);
+        assert!(last_day.iter().all(|s| s.day == "2024-01-02"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_buffered_chains_written_on_shutdown() -> anyhow::Result<()> {
+        let history = HistoryProcess::in_memory("kucoin")?;
+        let (detected_tx, detected_rx) = tokio::sync::broadcast::channel(8);
+        let (filled_tx, filled_rx) = tokio::sync::broadcast::channel(8);
+        let detected = chain(
+            DAY_ONE,
+            ["BTC-USDT", "ETH-BTC", "ETH-USDT"],
+            Decimal::from(101),
+        );
+        detected_tx.send(detected.clone())?;
+        filled_tx.send(filled(detected, Decimal::ONE))?;
+        let token = CancellationToken::new();
+        token.cancel();
+
+        history.write_received(detected_rx, filled_rx, &token).await;
+
+        let summary = history.summary(0)?;
+        assert_eq!(summary.len(), 1);
+        assert_eq!((summary[0].detected, summary[0].executed), (1, 1));
+        assert_eq!(summary[0].realized_profit, Decimal::ONE);
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+use std::sync::{
+    Arc, LazyLock,
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+use tracing::error;
+
+use crate::METRICS;
+
+/// Trading kill switch shared by the sender and `GET /health`.
+pub static KILL_SWITCH: LazyLock<Arc<KillSwitch>> = LazyLock::new(Arc::default);
+
+/// Halts trading after a number of chains failed in a row, which rather points at an API,
+/// key or balance problem than at a transient one. Once tripped trading stays halted until
+/// the bot is restarted. Disabled by default.
+#[derive(Debug, Default)]
+pub struct KillSwitch {
+    /// Consecutive failed chains tripping the switch, 0 = never.
+    max_failures: AtomicUsize,
+    failures: AtomicUsize,
+    halted: AtomicBool,
+}
+
+impl KillSwitch {
+    /// Trips after `max_failures` consecutive failed chains, never if unset.
+    #[must_use]
+    pub fn new(max_failures: Option<usize>) -> Self {
+        let kill_switch = Self::default();
+        kill_switch.set_max_failures(max_failures);
+        kill_switch
+    }
+
+    /// Sets the consecutive failed chains tripping the switch, never if unset.
+    pub fn set_max_failures(&self, max_failures: Option<usize>) {
+        self.max_failures
+            .store(max_failures.unwrap_or(0), Ordering::Relaxed);
+    }
+
+    /// Returns whether trading is halted.
+    #[must_use]
+    pub fn is_halted(&self) -> bool {
+        self.halted.load(Ordering::Relaxed)
+    }
+
+    /// Returns whether a number of consecutive failed chains is set to trip the switch.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.max_failures.load(Ordering::Relaxed) > 0
+    }
+
+    /// Records an executed chain, starting the failure count over.
+    pub fn record_success(&self) {
+        self.failures.store(0, Ordering::Relaxed);
+    }
+
+    /// Records a failed chain, returns whether it tripped the switch.
+    pub fn record_failure(&self) -> bool {
+        let failures = self.failures.fetch_add(1, Ordering::Relaxed) + 1;
+        let max_failures = self.max_failures.load(Ordering::Relaxed);
+        if max_failures == 0 || failures < max_failures || self.halted.swap(true, Ordering::Relaxed)
+        {
+            return false;
+        }
+
+        error!(
+            failures,
+            "🛑 [Engine] Chains failed {failures} times in a row, trading halted until restart"
+        );
+        METRICS.record_trading_halted(true);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_consecutive_failures_trip_the_switch() {
+        let kill_switch = KillSwitch::new(Some(3));
+        assert!(kill_switch.is_enabled());
+        assert!(!kill_switch.record_failure());
+        assert!(!kill_switch.record_failure());
+        // A success starts the count over.
+        kill_switch.record_success();
+        assert!(!kill_switch.record_failure());
+        assert!(!kill_switch.record_failure());
+        assert!(!kill_switch.is_halted());
+
+        assert!(kill_switch.record_failure());
+        assert!(kill_switch.is_halted());
+        // Tripped once, and for good.
+        assert!(!kill_switch.record_failure());
+        kill_switch.record_success();
+        assert!(kill_switch.is_halted());
+
+        let disabled = KillSwitch::default();
+        assert!(!disabled.is_enabled());
+        for _ in 0..100 {
+            assert!(!disabled.record_failure());
+        }
+        assert!(!disabled.is_halted());
+    }
+}
@@ -0,0 +1,319 @@
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use rusqlite::{Connection, params};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use tokio::sync::broadcast::{
+    Receiver,
+    error::{RecvError, TryRecvError},
+};
+use tokio_util::sync::CancellationToken;
+use tools::http::http_server::HttpServerProcess;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    FILLED_CHAINS,
+    model::orders::{ChainOrder, FilledChain},
+};
+
+const CREATE_TABLE: &str = "CREATE TABLE IF NOT EXISTS chains (
+    chain_id TEXT PRIMARY KEY,
+    ts INTEGER NOT NULL,
+    exchange TEXT NOT NULL,
+    profit TEXT NOT NULL,
+    fee_percent TEXT NOT NULL,
+    legs TEXT NOT NULL
+)";
+
+/// Writes every chain executed by the sender to a SQLite ledger for accounting and replay.
+///
+/// Chains are received from [`FILLED_CHAINS`], whose bounded buffer keeps a slow database
+/// from ever blocking the sender: a ledger lagging too far behind skips the oldest chains.
+pub struct LedgerProcess {
+    conn: Arc<Mutex<Connection>>,
+    exchange: String,
+}
+
+/// Chain leg as stored in the ledger: the planned order and its actual fill.
+#[derive(Debug, Serialize)]
+struct LedgerLeg<'a> {
+    #[serde(flatten)]
+    order: &'a ChainOrder,
+    fill: Decimal,
+}
+
+/// Chain read back from the ledger.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LedgerEntry {
+    pub chain_id: Uuid,
+    pub ts: u128,
+    pub exchange: String,
+    /// Realized profit in the base asset.
+    pub profit: Decimal,
+    pub fee_percent: Decimal,
+    /// Legs with their actual fills.
+    pub legs: serde_json::Value,
+}
+
+impl LedgerProcess {
+    /// Opens the ledger at `path`, creating the database file and table if missing.
+    pub fn open(path: &Path, exchange: &str) -> anyhow::Result<Self> {
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open ledger {}", path.display()))?;
+        Self::from_connection(conn, exchange)
+    }
+
+    /// Opens a ledger kept in memory, lost when dropped.
+    pub fn in_memory(exchange: &str) -> anyhow::Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?, exchange)
+    }
+
+    fn from_connection(conn: Connection, exchange: &str) -> anyhow::Result<Self> {
+        conn.execute(CREATE_TABLE, [])
+            .context("Failed to create ledger table")?;
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+            exchange: exchange.to_owned(),
+        })
+    }
+
+    /// Writes the chains in a single transaction.
+    pub async fn record(&self, chains: Vec<FilledChain>) -> anyhow::Result<()> {
+        let conn = self.conn.clone();
+        let exchange = self.exchange.clone();
+        tokio::task::spawn_blocking(move || Self::insert(&conn, &exchange, &chains)).await?
+    }
+
+    fn insert(
+        conn: &Mutex<Connection>,
+        exchange: &str,
+        chains: &[FilledChain],
+    ) -> anyhow::Result<()> {
+        let mut conn = conn
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Ledger connection poisoned"))?;
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT OR REPLACE INTO chains (chain_id, ts, exchange, profit, fee_percent, legs)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for filled in chains {
+                let legs = filled
+                    .chain
+                    .orders
+                    .iter()
+                    .zip(&filled.fills)
+                    .map(|(order, fill)| LedgerLeg { order, fill: *fill })
+                    .collect::<Vec<_>>();
+                stmt.execute(params![
+                    filled.chain.chain_id.to_string(),
+                    i64::try_from(filled.chain.ts)?,
+                    exchange,
+                    filled.profit.to_string(),
+                    filled.chain.fee_percent.to_string(),
+                    serde_json::to_string(&legs)?,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Returns the last `limit` chains, newest first.
+    pub fn recent(&self, limit: usize) -> anyhow::Result<Vec<LedgerEntry>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Ledger connection poisoned"))?;
+        let mut stmt = conn.prepare(
+            "SELECT chain_id, ts, exchange, profit, fee_percent, legs
+             FROM chains ORDER BY ts DESC, rowid DESC LIMIT ?1",
+        )?;
+        let rows = stmt.query_map([i64::try_from(limit)?], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, String>(5)?,
+            ))
+        })?;
+
+        rows.map(|row| -> anyhow::Result<LedgerEntry> {
+            let (chain_id, ts, exchange, profit, fee_percent, legs) = row?;
+            Ok(LedgerEntry {
+                chain_id: chain_id.parse()?,
+                ts: u128::try_from(ts)?,
+                exchange,
+                profit: profit.parse()?,
+                fee_percent: fee_percent.parse()?,
+                legs: serde_json::from_str(&legs)?,
+            })
+        })
+        .collect()
+    }
+
+    /// Writes received chains until cancelled, batching the ones buffered meanwhile. Chains
+    /// still buffered on cancellation are written before returning.
+    pub async fn write_received(
+        &self,
+        mut filled_rx: Receiver<FilledChain>,
+        token: &CancellationToken,
+    ) {
+        loop {
+            let mut chains = vec![];
+            let stop = tokio::select! {
+                () = token.cancelled() => true,
+                result = filled_rx.recv() => match result {
+                    Ok(filled) => {
+                        chains.push(filled);
+                        false
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!(skipped, "Ledger chains skipped");
+                        false
+                    }
+                    Err(RecvError::Closed) => true,
+                },
+            };
+            Self::drain(&mut filled_rx, &mut chains);
+
+            if !chains.is_empty()
+                && let Err(e) = self.record(chains).await
+            {
+                warn!(error = ?e, "Failed to write chains to the ledger");
+            }
+            if stop {
+                break;
+            }
+        }
+    }
+
+    /// Takes the chains already buffered in the channel without waiting.
+    fn drain(filled_rx: &mut Receiver<FilledChain>, chains: &mut Vec<FilledChain>) {
+        loop {
+            match filled_rx.try_recv() {
+                Ok(filled) => chains.push(filled),
+                Err(TryRecvError::Lagged(skipped)) => {
+                    warn!(skipped, "Ledger chains skipped");
+                }
+                Err(TryRecvError::Empty | TryRecvError::Closed) => break,
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl HttpServerProcess for LedgerProcess {
+    async fn pre_run(&self) -> anyhow::Result<()> {
+        info!(
+            exchange = self.exchange,
+            "Executed chains are written to the ledger"
+        );
+        Ok(())
+    }
+
+    async fn run(&self, token: CancellationToken) -> anyhow::Result<()> {
+        self.write_received(FILLED_CHAINS.subscribe(), &token).await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{
+        ChainOrders,
+        enums::{OrderType, SymbolOrder},
+    };
+
+    fn filled_chain(ts: u128, profit: Decimal) -> FilledChain {
+        let leg = |symbol: &str, symbol_order| ChainOrder {
+            symbol: symbol.to_owned(),
+            symbol_order,
+            order_type: OrderType::Market,
+            price: Decimal::ONE,
+            base_qty: Decimal::ONE,
+            quote_qty: Decimal::ONE,
+            base_increment: Decimal::ONE,
+            quote_increment: Decimal::ONE,
+            min_qty: Decimal::ZERO,
+        };
+        FilledChain {
+            chain: ChainOrders {
+                ts,
+                chain_id: Uuid::new_v4(),
+                base_asset: "USDT".to_owned(),
+                fee_percent: Decimal::new(75, 3),
+                orders: vec![
+                    leg("BTCUSDT", SymbolOrder::Desc),
+                    leg("ETHBTC", SymbolOrder::Desc),
+                    leg("ETHUSDT", SymbolOrder::Asc),
+                ],
+                ..Default::default()
+            },
+            fills: vec![
+                Decimal::from(100),
+                Decimal::new(3, 2),
+                Decimal::new(1005, 1),
+            ],
+            profit,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recorded_chains_read_back_newest_first() -> anyhow::Result<()> {
+        let ledger = LedgerProcess::in_memory("binance")?;
+        let older = filled_chain(1_000, Decimal::new(5, 1));
+        let newer = filled_chain(2_000, Decimal::new(-12_345_678, 8));
+        ledger.record(vec![older.clone(), newer.clone()]).await?;
+
+        let entries = ledger.recent(10)?;
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].chain_id, newer.chain.chain_id);
+        assert_eq!(entries[0].ts, 2_000);
+        assert_eq!(entries[0].exchange, "binance");
+        assert_eq!(entries[0].profit, Decimal::new(-12_345_678, 8));
+        assert_eq!(entries[0].fee_percent, Decimal::new(75, 3));
+        assert_eq!(entries[1].chain_id, older.chain.chain_id);
+
+        let legs = entries[0].legs.as_array().unwrap();
+        assert_eq!(legs.len(), 3);
+        assert_eq!(legs[0]["symbol"], "BTCUSDT");
+        assert_eq!(legs[1]["fill"], "0.03");
+        assert_eq!(legs[2]["fill"], "100.5");
+
+        assert_eq!(ledger.recent(1)?.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_buffered_chains_written_on_shutdown() -> anyhow::Result<()> {
+        let ledger = LedgerProcess::in_memory("kucoin")?;
+        let (tx, rx) = tokio::sync::broadcast::channel(8);
+        // Cancelled before the ledger sees the chains, they are still buffered.
+        tx.send(filled_chain(1, Decimal::ONE))?;
+        tx.send(filled_chain(2, Decimal::TWO))?;
+        let token = CancellationToken::new();
+        token.cancel();
+
+        tokio::time::timeout(Duration::from_secs(5), ledger.write_received(rx, &token)).await?;
+
+        let entries = ledger.recent(10)?;
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].profit, Decimal::TWO);
+        assert!(entries.iter().all(|entry| entry.exchange == "kucoin"));
+        Ok(())
+    }
+}
@@ -1,6 +1,16 @@
-use std::sync::LazyLock;
+use std::{
+    num::NonZeroU64,
+    sync::{
+        LazyLock,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
 
-use metrics::{counter, describe_counter};
+use metrics::{
+    Counter, Unit, counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram,
+};
+use rust_decimal::{Decimal, prelude::ToPrimitive};
 use tracing::warn;
 
 use crate::enums::ChainStatus;
@@ -12,16 +22,85 @@ pub static METRICS: LazyLock<Metrics> = LazyLock::new(|| {
         "Total number of received book ticker events",
     );
 
+    describe_counter!(
+        "stale_book_ticker_events_total",
+        "Total number of book ticker events dropped for an update id not newer than the stored one",
+    );
+
     describe_counter!(
         "processed_chains_total",
         "Total number of processed arbitrage chains",
     );
 
+    describe_counter!(
+        "base_asset_processed_chains_total",
+        "Total number of processed arbitrage chains per base asset",
+    );
+
+    describe_gauge!(
+        "base_asset_realized_profit",
+        "Realized session profit of the executed chains per base asset, in units of that asset",
+    );
+
     describe_counter!(
         "profit_orders_total",
         "Total number of profitable orders found",
     );
 
+    describe_counter!(
+        "no_opportunity_alerts_total",
+        "Total number of alerts for periods without profitable chains",
+    );
+
+    describe_counter!(
+        "rate_limit_hits_total",
+        "Total number of responses rejected by the exchange rate limiter",
+    );
+
+    describe_histogram!(
+        "rate_limit_pause_seconds",
+        Unit::Seconds,
+        "Pauses of all requests and trading after responses rejected by the exchange rate limiter",
+    );
+
+    describe_counter!(
+        "ws_reconnects_total",
+        "Total number of dropped stream connections resubscribed",
+    );
+
+    describe_histogram!(
+        "chain_detection_latency_seconds",
+        Unit::Seconds,
+        "Time from receiving a book ticker event to queuing the chain detected on it",
+    );
+
+    describe_histogram!(
+        "chain_first_leg_ack_latency_seconds",
+        Unit::Seconds,
+        "Time from queuing a chain to the exchange acknowledging its first leg",
+    );
+
+    describe_gauge!(
+        "subscribed_symbols",
+        "Unique symbols with an upstream market data subscription",
+    );
+
+    describe_gauge!(
+        "chain_symbol_subscriptions",
+        "Chain subscriptions to symbol tickers, fanned out from the upstream subscriptions",
+    );
+
+    describe_gauge!(
+        "trading_halted",
+        "1 once the kill switch halted trading after consecutive failed chains",
+    );
+
+    describe_histogram!(
+        "ticker_update_processing_seconds",
+        Unit::Seconds,
+        "Time a chain takes to process a sampled book ticker update, from storing it to queuing the chain",
+    );
+
     Metrics
 });
 
@@ -38,13 +117,51 @@ impl Metrics {
         .increment(1);
     }
 
+    /// Increments the counter of book ticker events dropped as stale for a specific symbol.
+    pub fn record_stale_book_ticker_event(&self, symbol: &str) {
+        counter!(
+            "stale_book_ticker_events_total",
+            "symbol" => symbol.to_owned(),
+        )
+        .increment(1);
+    }
+
     /// Increments the chains counter with labels for symbols and status.
+    ///
+    /// Builds the labels and looks the counter up on every call, hot paths keep the handle
+    /// from [`Self::processed_chain_counter`] instead.
     pub fn record_processed_chain(&self, symbols: &[&str]) {
-        if let Some((a, b, c)) = Self::extract_labels(symbols) {
-            counter!("processed_chains_total", "a" => a, "b" => b, "c" => c).increment(1);
+        self.processed_chain_counter(symbols, None).increment(1);
+    }
+
+    /// Registers the processed chains counters of a chain once, to be kept for its lifetime.
+    /// With `base_asset` the chain is also counted for the asset it starts from.
+    #[must_use]
+    pub fn processed_chain_counter(
+        &self,
+        symbols: &[&str],
+        base_asset: Option<&str>,
+    ) -> ProcessedChainCounter {
+        ProcessedChainCounter {
+            chain: match Self::extract_labels(symbols) {
+                Some((a, b, c)) => {
+                    counter!("processed_chains_total", "a" => a, "b" => b, "c" => c)
+                }
+                None => Counter::noop(),
+            },
+            base_asset: base_asset.map_or_else(Counter::noop, |asset| {
+                counter!("base_asset_processed_chains_total", "base_asset" => asset.to_owned())
+            }),
         }
     }
 
+    /// Sets the realized session profit of a base asset, the total its own session profit
+    /// target is compared with.
+    pub fn record_realized_profit(&self, base_asset: &str, session_profit: Decimal) {
+        gauge!("base_asset_realized_profit", "base_asset" => base_asset.to_owned())
+            .set(session_profit.to_f64().unwrap_or_default());
+    }
+
     /// Increments the chains counter status with labels for symbols and status.
     pub fn record_chain_status(&self, symbols: &[&str], status: &ChainStatus) {
         if let Some((a, b, c)) = Self::extract_labels(symbols) {
@@ -57,6 +174,52 @@ impl Metrics {
         }
     }
 
+    /// Increments the counter of alerts for periods without profitable chains.
+    pub fn record_no_opportunity_alert(&self) {
+        counter!("no_opportunity_alerts_total").increment(1);
+    }
+
+    /// Increments the counter of responses rejected by the exchange rate limiter.
+    pub fn record_rate_limit_hit(&self, status: u16) {
+        counter!("rate_limit_hits_total", "status" => status.to_string()).increment(1);
+    }
+
+    /// Records a pause of all requests and trading after a rate limited response.
+    pub fn record_rate_limit_pause(&self, pause: Duration) {
+        histogram!("rate_limit_pause_seconds").record(pause.as_secs_f64());
+    }
+
+    /// Increments the counter of dropped stream connections resubscribed.
+    pub fn record_ws_reconnect(&self) {
+        counter!("ws_reconnects_total").increment(1);
+    }
+
+    /// Records the time from receiving a book ticker event to queuing a chain detected on it.
+    pub fn record_detection_latency(&self, latency: Duration) {
+        histogram!("chain_detection_latency_seconds").record(latency.as_secs_f64());
+    }
+
+    /// Records the time from queuing a chain to the exchange acknowledging its first leg.
+    pub fn record_first_leg_ack_latency(&self, latency: Duration) {
+        histogram!("chain_first_leg_ack_latency_seconds").record(latency.as_secs_f64());
+    }
+
+    /// Sets the unique symbols subscribed upstream and the chain subscriptions sharing them.
+    pub fn record_symbol_subscriptions(&self, symbols: usize, chain_subscriptions: usize) {
+        gauge!("subscribed_symbols").set(symbols as f64);
+        gauge!("chain_symbol_subscriptions").set(chain_subscriptions as f64);
+    }
+
+    /// Sets whether the kill switch halted trading.
+    pub fn record_trading_halted(&self, halted: bool) {
+        gauge!("trading_halted").set(if halted { 1.0 } else { 0.0 });
+    }
+
+    /// Records the time a chain took to process a book ticker update.
+    pub fn record_update_processing(&self, elapsed: Duration) {
+        histogram!("ticker_update_processing_seconds").record(elapsed.as_secs_f64());
+    }
+
     fn extract_labels(s: &[&str]) -> Option<(String, String, String)> {
         if s.len() < 3 {
             warn!("Metrics: need 3 symbols, got {}", s.len());
@@ -66,8 +229,67 @@ impl Metrics {
     }
 }
 
+/// Processed chains counters of a single chain and its base asset. Updates are lock-free
+/// atomic adds, without allocating labels or looking the counters up.
+#[derive(Clone)]
+pub struct ProcessedChainCounter {
+    chain: Counter,
+    base_asset: Counter,
+}
+
+impl ProcessedChainCounter {
+    /// Adds `count` processed chains, so a batch costs the same as a single chain.
+    pub fn increment(&self, count: u64) {
+        self.chain.increment(count);
+        self.base_asset.increment(count);
+    }
+}
+
+/// Samples the processing time of book ticker updates, one update out of every `sample_every`
+/// being timed so profiling under load stays cheap. Disabled by default.
+#[derive(Debug, Default)]
+pub struct UpdateLatencySampler {
+    sample_every: Option<NonZeroU64>,
+    updates: AtomicU64,
+}
+
+impl UpdateLatencySampler {
+    /// Times one update out of every `sample_every`, none if unset.
+    #[must_use]
+    pub fn new(sample_every: Option<NonZeroU64>) -> Self {
+        Self {
+            sample_every,
+            updates: AtomicU64::new(0),
+        }
+    }
+
+    /// Starts timing an update, `None` if it is not sampled.
+    #[must_use]
+    pub fn start(&self) -> Option<Instant> {
+        let sample_every = self.sample_every?;
+        let update = self.updates.fetch_add(1, Ordering::Relaxed);
+        (update.is_multiple_of(sample_every.get())).then(Instant::now)
+    }
+
+    /// Records the processing time of an update started by [`Self::start`].
+    pub fn finish(&self, started: Option<Instant>) {
+        if let Some(started) = started {
+            METRICS.record_update_processing(started.elapsed());
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    };
+
+    use metrics::{
+        Gauge, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder, SharedString, Unit,
+    };
+
     use super::*;
     use crate::enums::ChainStatus;
 
@@ -77,6 +299,12 @@ mod tests {
         Metrics.record_book_ticker_event("BTCUSDT");
     }
 
+    #[test]
+    fn test_record_rate_limit_hit() {
+        // Smoke test: no panic on call
+        Metrics.record_rate_limit_hit(429);
+    }
+
     #[test]
     fn test_record_processed_chain_valid() {
         // Smoke test: no panic with 3+ symbols
@@ -116,4 +344,131 @@ mod tests {
         Metrics.record_chain_status(&symbols, &ChainStatus::Filled);
         Metrics.record_chain_status(&symbols, &ChainStatus::Cancelled);
     }
+
+    /// Records every counter into one shared atomic.
+    struct AtomicRecorder(Arc<AtomicU64>);
+
+    impl Recorder for AtomicRecorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, _: &Key, _: &Metadata<'_>) -> Counter {
+            Counter::from_arc(Arc::clone(&self.0))
+        }
+
+        fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::noop()
+        }
+    }
+
+    #[test]
+    fn test_processed_chain_counter_concurrent_updates() {
+        let recorded = Arc::new(AtomicU64::new(0));
+        let recorder = AtomicRecorder(Arc::clone(&recorded));
+        let counter = metrics::with_local_recorder(&recorder, || {
+            Metrics.processed_chain_counter(&["BTCUSDT", "ETHBTC", "ETHUSDT"], None)
+        });
+
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                let counter = counter.clone();
+                scope.spawn(move || {
+                    for _ in 0..10_000 {
+                        counter.increment(1);
+                    }
+                    counter.increment(5);
+                });
+            }
+        });
+
+        assert_eq!(recorded.load(Ordering::Relaxed), 8 * 10_005);
+    }
+
+    /// Counts the observations of every histogram.
+    struct HistogramCountRecorder(Arc<ObservationCount>);
+
+    #[derive(Default)]
+    struct ObservationCount(AtomicU64);
+
+    impl HistogramFn for ObservationCount {
+        fn record(&self, _: f64) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    impl Recorder for HistogramCountRecorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, _: &Key, _: &Metadata<'_>) -> Counter {
+            Counter::noop()
+        }
+
+        fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+            assert_eq!(key.name(), "ticker_update_processing_seconds");
+            Histogram::from_arc(Arc::clone(&self.0))
+        }
+    }
+
+    #[test]
+    fn test_update_latency_is_sampled_when_enabled() {
+        let observations = Arc::new(ObservationCount::default());
+        let recorder = HistogramCountRecorder(Arc::clone(&observations));
+        let process = |sampler: &UpdateLatencySampler, updates| {
+            metrics::with_local_recorder(&recorder, || {
+                for _ in 0..updates {
+                    let started = sampler.start();
+                    sampler.finish(started);
+                }
+            });
+        };
+
+        process(&UpdateLatencySampler::default(), 10);
+        assert_eq!(observations.0.load(Ordering::Relaxed), 0);
+
+        // The first update of every four is timed.
+        process(&UpdateLatencySampler::new(NonZeroU64::new(4)), 10);
+        assert_eq!(observations.0.load(Ordering::Relaxed), 3);
+
+        process(&UpdateLatencySampler::new(NonZeroU64::new(1)), 5);
+        assert_eq!(observations.0.load(Ordering::Relaxed), 8);
+    }
+
+    #[test]
+    fn test_chains_and_profit_are_labelled_by_base_asset() {
+        let recorder = metrics_exporter_prometheus::PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        metrics::with_local_recorder(&recorder, || {
+            let usdt =
+                Metrics.processed_chain_counter(&["BTCUSDT", "ETHBTC", "ETHUSDT"], Some("USDT"));
+            let btc =
+                Metrics.processed_chain_counter(&["ETHBTC", "ETHUSDT", "BTCUSDT"], Some("BTC"));
+            usdt.increment(2);
+            btc.increment(1);
+            // Session totals, after a 1.5 USDT and a -0.5 USDT chain.
+            Metrics.record_realized_profit("USDT", Decimal::new(15, 1));
+            Metrics.record_realized_profit("USDT", Decimal::ONE);
+            Metrics.record_realized_profit("BTC", Decimal::new(25, 5));
+        });
+
+        let rendered = handle.render();
+        for line in [
+            r#"base_asset_processed_chains_total{base_asset="USDT"} 2"#,
+            r#"base_asset_processed_chains_total{base_asset="BTC"} 1"#,
+            r#"base_asset_realized_profit{base_asset="USDT"} 1"#,
+            r#"base_asset_realized_profit{base_asset="BTC"} 0.00025"#,
+        ] {
+            assert!(rendered.contains(line), "{line} not in:\n{rendered}");
+        }
+    }
 }
@@ -1,4 +1,26 @@
+pub mod backtest;
+pub mod balances;
+pub mod chains;
 pub mod channel;
+pub mod clock;
+pub mod compute;
+pub mod execution_cap;
+pub mod health;
+pub mod history;
+pub mod kill_switch;
+pub mod ledger;
 pub mod metrics;
+pub mod notifier;
+pub mod open_orders;
+pub mod persistence;
+pub mod pnl;
 pub mod process;
+pub mod ramp;
+pub mod repeat;
+pub mod schedule;
+pub mod shutdown;
+pub mod summary;
+pub mod symbols;
+pub mod usd;
+pub mod watchdog;
 pub mod weight;
@@ -0,0 +1,254 @@
+use std::{fmt::Write, time::Duration};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use serde::Serialize;
+use tokio::{sync::broadcast::error::RecvError, time::Instant};
+use tokio_util::sync::CancellationToken;
+use tools::http::http_server::HttpServerProcess;
+use tracing::{info, warn};
+
+use crate::{FILLED_CHAINS, model::orders::FilledChain};
+
+/// Posts a Telegram message for every chain executed by the sender. Without a bot token or
+/// chat id the messages are only logged.
+pub struct TelegramNotifier {
+    api_url: String,
+    bot_token: Option<String>,
+    chat_id: Option<String>,
+    client: reqwest::Client,
+}
+
+/// Body of the Telegram `sendMessage` method.
+#[derive(Debug, Serialize)]
+struct SendMessage<'a> {
+    chat_id: &'a str,
+    text: &'a str,
+}
+
+impl TelegramNotifier {
+    pub const API_URL: &str = "https://api.telegram.org";
+    /// Timeout of a single message request.
+    pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+    /// Minimum delay between messages, Telegram answers faster ones to a chat with 429.
+    pub const MIN_SEND_INTERVAL: Duration = Duration::from_secs(1);
+
+    pub fn new(bot_token: Option<String>, chat_id: Option<String>) -> anyhow::Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Self::REQUEST_TIMEOUT)
+            .build()
+            .context("Failed to build telegram http client")?;
+
+        Ok(Self {
+            api_url: Self::API_URL.to_owned(),
+            bot_token,
+            chat_id,
+            client,
+        })
+    }
+
+    /// Sends messages to another Bot API server.
+    #[must_use]
+    pub fn with_api_url(mut self, api_url: &str) -> Self {
+        self.api_url = api_url.to_owned();
+        self
+    }
+
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.bot_token.is_some() && self.chat_id.is_some()
+    }
+
+    /// Formats the message of a filled chain: its id, realized profit and the fill of every leg.
+    #[must_use]
+    pub fn message(filled: &FilledChain) -> String {
+        let chain = &filled.chain;
+        let mut text = format!(
+            "✅ Chain filled\nchain_id: {}\nprofit: {} {} ({}%)",
+            chain.chain_id,
+            filled.profit,
+            chain.base_asset,
+            filled.profit_percent()
+        );
+        for (idx, (order, fill)) in chain.orders.iter().zip(&filled.fills).enumerate() {
+            _ = write!(
+                text,
+                "\n{}. {} {}: {fill}",
+                idx + 1,
+                order.symbol,
+                order.symbol_order
+            );
+        }
+        text
+    }
+
+    /// Posts the message of a filled chain, or only logs it when notifications are disabled.
+    pub async fn notify(&self, filled: &FilledChain) -> anyhow::Result<()> {
+        let text = Self::message(filled);
+        let (Some(bot_token), Some(chat_id)) = (&self.bot_token, &self.chat_id) else {
+            info!(chain_id = %filled.chain.chain_id, text, "📨 [Engine] Chain fill notification");
+            return Ok(());
+        };
+
+        self.client
+            .post(format!("{}/bot{bot_token}/sendMessage", self.api_url))
+            .json(&SendMessage {
+                chat_id,
+                text: &text,
+            })
+            .send()
+            .await
+            .with_context(|| format!("Failed to notify about chain {}", filled.chain.chain_id))?
+            .error_for_status()
+            .with_context(|| format!("Telegram rejected chain {}", filled.chain.chain_id))?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl HttpServerProcess for TelegramNotifier {
+    async fn pre_run(&self) -> anyhow::Result<()> {
+        if !self.is_enabled() {
+            info!("Telegram bot token or chat id not set, chain fills are only logged");
+        }
+        Ok(())
+    }
+
+    /// Notifies about filled chains until cancelled, at most one message per
+    /// [`Self::MIN_SEND_INTERVAL`]. Failed messages are logged and dropped.
+    async fn run(&self, token: CancellationToken) -> anyhow::Result<()> {
+        let mut filled_rx = FILLED_CHAINS.subscribe();
+        let mut next_send = Instant::now();
+
+        loop {
+            let filled = tokio::select! {
+                () = token.cancelled() => break,
+                result = filled_rx.recv() => match result {
+                    Ok(filled) => filled,
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!(skipped, "Chain fill notifications skipped");
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+            };
+
+            if self.is_enabled() {
+                tokio::select! {
+                    () = token.cancelled() => break,
+                    () = tokio::time::sleep_until(next_send) => {}
+                }
+                next_send = Instant::now() + Self::MIN_SEND_INTERVAL;
+            }
+
+            if let Err(e) = self.notify(&filled).await {
+                warn!(error = ?e, "Failed to send chain fill notification");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockito::{Matcher, Server};
+    use rust_decimal::Decimal;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::{
+        ChainOrder, ChainOrders,
+        enums::{OrderType, SymbolOrder},
+    };
+
+    fn filled_chain() -> FilledChain {
+        let leg = |symbol: &str, symbol_order| ChainOrder {
+            symbol: symbol.to_owned(),
+            symbol_order,
+            order_type: OrderType::Market,
+            price: Decimal::ONE,
+            base_qty: Decimal::ONE,
+            quote_qty: Decimal::ONE,
+            base_increment: Decimal::ONE,
+            quote_increment: Decimal::ONE,
+            min_qty: Decimal::ZERO,
+        };
+        FilledChain {
+            chain: ChainOrders {
+                chain_id: Uuid::nil(),
+                base_asset: "USDT".to_owned(),
+                orders: vec![
+                    leg("BTCUSDT", SymbolOrder::Desc),
+                    leg("ETHBTC", SymbolOrder::Desc),
+                    leg("ETHUSDT", SymbolOrder::Asc),
+                ],
+                ..Default::default()
+            },
+            fills: vec![
+                Decimal::from(100),
+                Decimal::new(3, 2),
+                Decimal::new(1005, 1),
+            ],
+            profit: Decimal::new(5, 1),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_filled_chain_posted_to_chat() -> anyhow::Result<()> {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/bottest_token/sendMessage")
+            .match_query(Matcher::Any)
+            .match_body(Matcher::Json(serde_json::json!({
+                "chat_id": "42",
+                "text": "✅ Chain filled\n\
+                         chain_id: 00000000-0000-0000-0000-000000000000\n\
+                         profit: 0.5 USDT (0.50%)\n\
+                         1. BTCUSDT DESC: 100\n\
+                         2. ETHBTC DESC: 0.03\n\
+                         3. ETHUSDT ASC: 100.5"
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"ok": true}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let notifier = TelegramNotifier::new(Some("test_token".to_owned()), Some("42".to_owned()))?
+            .with_api_url(&server.url());
+
+        let token = CancellationToken::new();
+        let notified = async {
+            // Let the notifier subscribe before the chain is published.
+            while FILLED_CHAINS.receiver_count() == 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            FILLED_CHAINS.send(filled_chain())?;
+            while !mock.matched_async().await {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            anyhow::Ok(())
+        };
+        let result = tokio::select! {
+            result = notifier.run(token.clone()) => anyhow::bail!("Notifier stopped: {result:?}"),
+            result = tokio::time::timeout(Duration::from_secs(5), notified) => result,
+        };
+        token.cancel();
+        result??;
+
+        mock.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_notifier_without_token_only_logs() -> anyhow::Result<()> {
+        let notifier =
+            TelegramNotifier::new(None, Some("42".to_owned()))?.with_api_url("http://127.0.0.1:1");
+
+        assert!(!notifier.is_enabled());
+        notifier.notify(&filled_chain()).await
+    }
+}
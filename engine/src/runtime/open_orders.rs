@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use tokio::sync::Mutex;
+
+use crate::enums::OrderStatus;
+
+/// Orders placed on the exchange that are not final yet (new or partially filled),
+/// keyed by client order id. New chains are deferred while the count is at the cap.
+pub struct OpenOrders {
+    max_open_orders: Option<usize>,
+    orders: Mutex<HashMap<String, String>>,
+}
+
+impl OpenOrders {
+    /// Creates a tracker; `None` disables the cap.
+    #[must_use]
+    pub fn new(max_open_orders: Option<usize>) -> Self {
+        Self {
+            max_open_orders,
+            orders: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Records the latest known status of an order.
+    pub async fn update(&self, symbol: &str, client_order_id: &str, status: OrderStatus) {
+        let mut orders = self.orders.lock().await;
+        if status.is_open() {
+            orders.insert(client_order_id.to_owned(), symbol.to_owned());
+        } else {
+            orders.remove(client_order_id);
+        }
+    }
+
+    /// Returns true if `count` more orders can be placed without exceeding the cap.
+    pub async fn has_capacity(&self, count: usize) -> bool {
+        match self.max_open_orders {
+            Some(max) => self.orders.lock().await.len().saturating_add(count) <= max,
+            None => true,
+        }
+    }
+
+    /// Returns the tracked orders as `(symbol, client_order_id)` pairs.
+    pub async fn tracked(&self) -> Vec<(String, String)> {
+        self.orders
+            .lock()
+            .await
+            .iter()
+            .map(|(client_order_id, symbol)| (symbol.clone(), client_order_id.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_chains_deferred_at_cap() {
+        let open_orders = OpenOrders::new(Some(4));
+        assert!(open_orders.has_capacity(3).await);
+
+        open_orders
+            .update("BTCUSDT", "arb-1", OrderStatus::New)
+            .await;
+        open_orders
+            .update("ETHBTC", "arb-2", OrderStatus::PartiallyFilled)
+            .await;
+        open_orders
+            .update("ETHUSDT", "arb-3", OrderStatus::Filled)
+            .await;
+        assert!(!open_orders.has_capacity(3).await);
+
+        // Slots are released once the orders reach a final status.
+        open_orders
+            .update("BTCUSDT", "arb-1", OrderStatus::Filled)
+            .await;
+        assert!(open_orders.has_capacity(3).await);
+        assert_eq!(
+            open_orders.tracked().await,
+            vec![("ETHBTC".to_owned(), "arb-2".to_owned())]
+        );
+
+        assert!(OpenOrders::new(None).has_capacity(usize::MAX).await);
+    }
+}
@@ -0,0 +1,80 @@
+use std::{collections::HashMap, time::Duration};
+
+use tokio::time::Instant;
+
+use crate::model::orders::ChainOrders;
+
+/// Filters out one-tick opportunities: a chain becomes actionable only after it has
+/// stayed profitable across consecutive updates for at least the configured duration.
+pub struct ProfitPersistence {
+    min_duration: Duration,
+    streaks: HashMap<String, Streak>,
+}
+
+struct Streak {
+    first_seen: Instant,
+    last_seen: Instant,
+}
+
+impl ProfitPersistence {
+    #[must_use]
+    pub fn new(min_duration: Duration) -> Self {
+        Self {
+            min_duration,
+            streaks: HashMap::new(),
+        }
+    }
+
+    /// Records a profitable update of the chain and returns true once it has persisted
+    /// long enough. A gap between updates longer than the duration starts a new streak.
+    /// A zero duration disables the filter.
+    pub fn observe(&mut self, chain: &ChainOrders, now: Instant) -> bool {
+        if self.min_duration.is_zero() {
+            return true;
+        }
+
+        self.streaks
+            .retain(|_, streak| now.duration_since(streak.last_seen) <= self.min_duration);
+
+        let streak = self
+            .streaks
+            .entry(chain.extract_symbols().join("-"))
+            .or_insert(Streak {
+                first_seen: now,
+                last_seen: now,
+            });
+        streak.last_seen = now;
+
+        now.duration_since(streak.first_seen) >= self.min_duration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::orders::test_utils::chain;
+
+    #[test]
+    fn test_one_tick_ignored_persistent_acted_on() {
+        let ms = Duration::from_millis;
+        let start = Instant::now();
+        let mut persistence = ProfitPersistence::new(ms(100));
+
+        // Flicker: seen once, then gone for longer than the hold time.
+        let flicker = chain(&["BTCUSDT", "ETHUSDT", "ETHBTC"]);
+        assert!(!persistence.observe(&flicker, start));
+        assert!(!persistence.observe(&flicker, start + ms(250)));
+
+        // Persistent: updates keep arriving until the hold time has passed.
+        let persistent = chain(&["SOLUSDT", "SOLBTC", "BTCUSDT"]);
+        assert!(!persistence.observe(&persistent, start + ms(300)));
+        assert!(!persistence.observe(&persistent, start + ms(350)));
+        assert!(persistence.observe(&persistent, start + ms(400)));
+    }
+
+    #[test]
+    fn test_zero_duration_acts_immediately() {
+        let mut persistence = ProfitPersistence::new(Duration::ZERO);
+        assert!(persistence.observe(&chain(&["BTCUSDT"]), Instant::now()));
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+
+use anyhow::bail;
+use rust_decimal::Decimal;
+use tracing::{info, warn};
+
+/// Realized profit of the executed chains, per base asset, for the session and the current
+/// UTC day. Trading stops once the profit of any asset reaches its session target, and pauses
+/// until the next UTC day once the daily loss of any asset exceeds its limit.
+pub struct RealizedPnl {
+    /// Session profit target per base asset, in units of that asset.
+    profit_targets: HashMap<String, Decimal>,
+    /// Daily loss limit per base asset, in units of that asset.
+    max_daily_losses: HashMap<String, Decimal>,
+    session: HashMap<String, Decimal>,
+    day: u64,
+    daily: HashMap<String, Decimal>,
+}
+
+impl RealizedPnl {
+    const SECS_PER_DAY: u64 = 86_400;
+
+    #[must_use]
+    pub fn new(
+        profit_targets: HashMap<String, Decimal>,
+        max_daily_losses: HashMap<String, Decimal>,
+    ) -> Self {
+        Self {
+            profit_targets,
+            max_daily_losses,
+            session: HashMap::new(),
+            day: 0,
+            daily: HashMap::new(),
+        }
+    }
+
+    /// Adds the realized profit of a chain executed at `now_secs` (unix time), denominated in
+    /// its base asset.
+    pub fn record(&mut self, asset: &str, profit: Decimal, now_secs: u64) {
+        let day = now_secs / Self::SECS_PER_DAY;
+        if day != self.day {
+            self.day = day;
+            self.daily.clear();
+        }
+
+        let target_was_reached = self.reached_profit_target().is_some();
+        let loss_was_exceeded = self.daily_loss_exceeded(now_secs).is_some();
+
+        *self.session.entry(asset.to_owned()).or_default() += profit;
+        *self.daily.entry(asset.to_owned()).or_default() += profit;
+
+        if !target_was_reached && let Some((asset, target)) = self.reached_profit_target() {
+            info!(
+                asset,
+                profit = %self.session_profit(asset),
+                target = %target,
+                "🎯 [Engine] Session profit target reached, trading stopped"
+            );
+        }
+        if !loss_was_exceeded
+            && let Some((asset, loss, max_loss)) = self.daily_loss_exceeded(now_secs)
+        {
+            warn!(
+                asset,
+                loss = %loss,
+                max_daily_loss = %max_loss,
+                "🛑 [Engine] Daily loss limit exceeded, trading paused until the next UTC day"
+            );
+        }
+    }
+
+    /// Session profit realized in the given asset.
+    #[must_use]
+    pub fn session_profit(&self, asset: &str) -> Decimal {
+        self.session.get(asset).copied().unwrap_or_default()
+    }
+
+    /// Returns true once the session profit of any asset has reached its target.
+    #[must_use]
+    pub fn profit_target_reached(&self) -> bool {
+        self.reached_profit_target().is_some()
+    }
+
+    /// Fails while trading is stopped by the session profit target or paused by the daily
+    /// loss limit at `now_secs` (unix time).
+    pub fn check(&self, now_secs: u64) -> anyhow::Result<()> {
+        if let Some((asset, target)) = self.reached_profit_target() {
+            bail!("session profit target of {target} {asset} reached");
+        }
+        if let Some((asset, loss, max_loss)) = self.daily_loss_exceeded(now_secs) {
+            bail!("daily loss of {loss} {asset} exceeds the limit of {max_loss} {asset}");
+        }
+        Ok(())
+    }
+
+    /// Returns the first asset whose session profit has reached its target, with the target.
+    fn reached_profit_target(&self) -> Option<(&str, Decimal)> {
+        self.profit_targets
+            .iter()
+            .find(|(asset, target)| self.session_profit(asset) >= **target)
+            .map(|(asset, target)| (asset.as_str(), *target))
+    }
+
+    /// Returns the asset, its loss and its limit if today's loss of any asset exceeds its
+    /// limit. Losses recorded on an earlier day no longer count.
+    fn daily_loss_exceeded(&self, now_secs: u64) -> Option<(&str, Decimal, Decimal)> {
+        if now_secs / Self::SECS_PER_DAY != self.day {
+            return None;
+        }
+        self.daily.iter().find_map(|(asset, total)| {
+            let max_loss = *self.max_daily_losses.get(asset)?;
+            (-*total > max_loss).then_some((asset.as_str(), -*total, max_loss))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2025-10-09 12:00:00 UTC.
+    const NOON: u64 = 1_760_011_200;
+
+    #[test]
+    fn test_trading_stops_at_profit_target() {
+        let targets = HashMap::from([
+            ("USDT".to_owned(), Decimal::from(10)),
+            ("BTC".to_owned(), Decimal::new(1, 2)),
+        ]);
+        let mut pnl = RealizedPnl::new(targets, HashMap::new());
+
+        pnl.record("USDT", Decimal::new(45, 1), NOON);
+        pnl.record("USDT", Decimal::new(-5, 1), NOON);
+        // Each asset is held to its own target: 0.005 BTC is short of 0.01 BTC, and an
+        // asset without a target never stops trading.
+        pnl.record("BTC", Decimal::new(5, 3), NOON);
+        pnl.record("ETH", Decimal::from(100), NOON);
+        assert!(pnl.check(NOON).is_ok());
+        assert_eq!(pnl.session_profit("USDT"), Decimal::from(4));
+
+        pnl.record("USDT", Decimal::from(6), NOON);
+        assert!(pnl.profit_target_reached());
+        assert!(pnl.check(NOON).is_err());
+    }
+
+    #[test]
+    fn test_daily_loss_pauses_until_next_day() {
+        let limits = HashMap::from([("USDT".to_owned(), Decimal::from(5))]);
+        let mut pnl = RealizedPnl::new(HashMap::new(), limits);
+
+        pnl.record("USDT", Decimal::from(-3), NOON);
+        pnl.record("USDT", Decimal::from(-2), NOON + 60);
+        // Exactly at the limit is still allowed.
+        assert!(pnl.check(NOON + 60).is_ok());
+
+        pnl.record("USDT", Decimal::new(-1, 1), NOON + 120);
+        assert!(pnl.check(NOON + 120).is_err());
+        assert!(!pnl.profit_target_reached());
+
+        // Resets at the UTC day rollover.
+        let next_day = NOON + 12 * 3600;
+        assert!(pnl.check(next_day - 1).is_err());
+        assert!(pnl.check(next_day).is_ok());
+
+        pnl.record("USDT", Decimal::from(-4), next_day + 60);
+        assert!(pnl.check(next_day + 60).is_ok());
+        assert_eq!(pnl.session_profit("USDT"), Decimal::new(-91, 1));
+    }
+
+    #[test]
+    fn test_daily_loss_is_limited_per_asset() {
+        let limits = HashMap::from([
+            ("USDT".to_owned(), Decimal::from(10)),
+            ("BTC".to_owned(), Decimal::new(1, 2)),
+        ]);
+        let mut pnl = RealizedPnl::new(HashMap::new(), limits);
+
+        // A 5 USDT loss is within its 10 USDT limit, though far above the BTC one.
+        pnl.record("USDT", Decimal::from(-5), NOON);
+        pnl.record("BTC", Decimal::new(-5, 3), NOON);
+        assert!(pnl.check(NOON).is_ok());
+
+        // 0.015 BTC exceeds its 0.01 BTC limit, though far below the USDT one.
+        pnl.record("BTC", Decimal::new(-1, 2), NOON + 60);
+        let err = pnl.check(NOON + 60).unwrap_err().to_string();
+        assert_eq!(err, "daily loss of 0.015 BTC exceeds the limit of 0.01 BTC");
+    }
+
+    #[test]
+    fn test_no_limits_never_stop() {
+        let mut pnl = RealizedPnl::new(HashMap::new(), HashMap::new());
+        pnl.record("USDT", Decimal::from(1_000_000), NOON);
+        pnl.record("BTC", Decimal::from(-1_000_000), NOON);
+        assert!(pnl.check(NOON).is_ok());
+    }
+}
@@ -3,15 +3,16 @@ use std::{sync::Arc, time::Duration};
 use async_trait::async_trait;
 use tokio_util::sync::CancellationToken;
 use tools::http::http_server::HttpServerProcess;
-use tracing::error;
+use tracing::{error, info, warn};
 
-use crate::service::traits::ArbitrageService;
+use crate::{runtime::shutdown::IN_FLIGHT_CHAINS, service::traits::ArbitrageService};
 
 pub struct GenericProcess<S>
 where
     S: ArbitrageService + ?Sized,
 {
     error_timeout_secs: Duration,
+    shutdown_grace: Option<Duration>,
     service: Arc<S>,
 }
 
@@ -20,8 +21,44 @@ impl<S: ArbitrageService + ?Sized + 'static> GenericProcess<S> {
         Self {
             service,
             error_timeout_secs: Duration::from_secs(60),
+            shutdown_grace: None,
         }
     }
+
+    /// On shutdown, keeps running the cancelled service for up to `grace` so that the chains
+    /// it is placing resolve, instead of aborting it at once.
+    #[must_use]
+    pub fn with_shutdown_grace(mut self, grace: Duration) -> Self {
+        self.shutdown_grace = Some(grace);
+        self
+    }
+
+    /// Drives the cancelled service until it returns or the grace period is exceeded. The
+    /// service no longer accepts new chains, only the ones in flight are waited for.
+    async fn drain(&self, service: impl Future<Output = anyhow::Result<()>>) {
+        let Some(grace) = self.shutdown_grace else {
+            return;
+        };
+
+        let in_flight = IN_FLIGHT_CHAINS.count();
+        let result = tokio::time::timeout(grace, service).await;
+        let remaining = IN_FLIGHT_CHAINS.count();
+        let drained = in_flight.saturating_sub(remaining);
+
+        let Ok(result) = result else {
+            warn!(
+                drained,
+                aborted = remaining,
+                grace_secs = grace.as_secs_f64(),
+                "🛑 [Engine] Shutdown grace period exceeded, aborting in-flight chains"
+            );
+            return;
+        };
+        if let Err(e) = result {
+            error!(error = ?e, "error during arbitrage process shutdown");
+        }
+        info!(drained, "🛑 [Engine] In-flight chains drained");
+    }
 }
 
 #[async_trait]
@@ -32,9 +69,13 @@ impl<S: ArbitrageService + ?Sized + 'static> HttpServerProcess for GenericProces
 
     async fn run(&self, token: CancellationToken) -> anyhow::Result<()> {
         loop {
+            let mut service = self.service.start(token.child_token());
             tokio::select! {
-                _ = token.cancelled() => break,
-                result = self.service.start(token.child_token()) => {
+                () = token.cancelled() => {
+                    self.drain(&mut service).await;
+                    break;
+                }
+                result = &mut service => {
                     if let Err(e) = result {
                         error!(error = ?e, "error during arbitrage process");
                         tokio::time::sleep(self.error_timeout_secs).await;
@@ -45,3 +86,76 @@ impl<S: ArbitrageService + ?Sized + 'static> HttpServerProcess for GenericProces
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use super::*;
+
+    /// Sender whose exchange takes `leg_delay` to fill the chain in flight at shutdown.
+    struct SlowSender {
+        leg_delay: Duration,
+        filled: AtomicBool,
+    }
+
+    #[async_trait]
+    impl ArbitrageService for SlowSender {
+        async fn start(&self, token: CancellationToken) -> anyhow::Result<()> {
+            let _in_flight = IN_FLIGHT_CHAINS.enter();
+            tokio::time::sleep(self.leg_delay).await;
+            self.filled.store(true, Ordering::SeqCst);
+            token.cancelled().await;
+            Ok(())
+        }
+    }
+
+    async fn shut_down(
+        leg_delay: Duration,
+        grace: Option<Duration>,
+    ) -> anyhow::Result<Arc<SlowSender>> {
+        let sender = Arc::new(SlowSender {
+            leg_delay,
+            filled: AtomicBool::new(false),
+        });
+        let mut process = GenericProcess::new(sender.clone());
+        if let Some(grace) = grace {
+            process = process.with_shutdown_grace(grace);
+        }
+
+        let token = CancellationToken::new();
+        let cancel = {
+            let token = token.clone();
+            async move {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                token.cancel();
+            }
+        };
+        let (result, ()) = tokio::time::timeout(Duration::from_secs(5), async {
+            tokio::join!(process.run(token.clone()), cancel)
+        })
+        .await?;
+        result?;
+        Ok(sender)
+    }
+
+    #[tokio::test]
+    async fn test_in_flight_chain_drained_within_grace() -> anyhow::Result<()> {
+        let sender = shut_down(Duration::from_millis(200), Some(Duration::from_secs(3))).await?;
+        assert!(sender.filled.load(Ordering::SeqCst));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_in_flight_chain_aborted_after_grace() -> anyhow::Result<()> {
+        let started = tokio::time::Instant::now();
+        let sender = shut_down(Duration::from_secs(3), Some(Duration::from_millis(50))).await?;
+        assert!(!sender.filled.load(Ordering::SeqCst));
+        assert!(started.elapsed() < Duration::from_secs(1));
+
+        // Without a grace period the chain is aborted at once.
+        let sender = shut_down(Duration::from_millis(200), None).await?;
+        assert!(!sender.filled.load(Ordering::SeqCst));
+        Ok(())
+    }
+}
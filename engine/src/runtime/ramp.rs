@@ -0,0 +1,144 @@
+use anyhow::bail;
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::Deserialize;
+
+use crate::model::orders::ChainOrders;
+
+/// Ramp-up of the executed chain size, as a percentage of the size the chain was built with
+/// (capped by `max_order_qty`).
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct SizeRamp {
+    /// Size of the first chains executed, and of the next one after a loss.
+    #[serde(with = "rust_decimal::serde::float")]
+    pub initial_percent: Decimal,
+    /// Size added after every chain executed without a loss, up to 100.
+    #[serde(with = "rust_decimal::serde::float")]
+    pub step_percent: Decimal,
+}
+
+impl SizeRamp {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.initial_percent <= Decimal::ZERO || self.initial_percent > Decimal::ONE_HUNDRED {
+            bail!("size_ramp.initial_percent must be in (0, 100]");
+        }
+        if self.step_percent <= Decimal::ZERO {
+            bail!("size_ramp.step_percent must be greater than 0");
+        }
+        Ok(())
+    }
+}
+
+/// Adaptive chain size: starts at a fraction of the built size, grows with every chain
+/// executed without a loss and falls back to the initial fraction after a loss.
+#[derive(Debug, Clone)]
+pub struct OrderSizeRamp {
+    ramp: SizeRamp,
+    percent: Decimal,
+}
+
+impl OrderSizeRamp {
+    #[must_use]
+    pub fn new(ramp: SizeRamp) -> Self {
+        Self {
+            ramp,
+            percent: ramp.initial_percent,
+        }
+    }
+
+    /// Current size as a percentage of the built size.
+    #[must_use]
+    pub const fn percent(&self) -> Decimal {
+        self.percent
+    }
+
+    /// Records the realized profit of an executed chain.
+    pub fn record(&mut self, profit: Decimal) {
+        self.percent = if profit < Decimal::ZERO {
+            self.ramp.initial_percent
+        } else {
+            (self.percent + self.ramp.step_percent).min(Decimal::ONE_HUNDRED)
+        };
+    }
+
+    /// Scales the quantities of every leg down to the current size, truncated to the
+    /// precision they were built with so they stay on the symbol's increments.
+    #[must_use]
+    pub fn apply(&self, chain: &ChainOrders) -> ChainOrders {
+        let mut chain = chain.clone();
+        if self.percent >= Decimal::ONE_HUNDRED {
+            return chain;
+        }
+
+        let scale = |qty: Decimal| {
+            (qty * self.percent / Decimal::ONE_HUNDRED)
+                .round_dp_with_strategy(qty.scale(), RoundingStrategy::ToZero)
+        };
+        for order in &mut chain.orders {
+            order.base_qty = scale(order.base_qty);
+            order.quote_qty = scale(order.quote_qty);
+        }
+        chain
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ChainOrder, enums::SymbolOrder, model::orders::test_utils::order};
+
+    fn chain() -> ChainOrders {
+        ChainOrders {
+            orders: vec![ChainOrder {
+                symbol_order: SymbolOrder::Desc,
+                price: Decimal::from(100_000),
+                base_qty: Decimal::new(10_000, 2),
+                quote_qty: Decimal::new(100_000, 8),
+                base_increment: Decimal::new(1, 5),
+                quote_increment: Decimal::new(1, 2),
+                ..order("BTCUSDT")
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_size_ramps_up_on_successes_and_resets_on_loss() {
+        let mut ramp = OrderSizeRamp::new(SizeRamp {
+            initial_percent: Decimal::from(25),
+            step_percent: Decimal::from(30),
+        });
+
+        let scaled = ramp.apply(&chain());
+        assert_eq!(scaled.orders[0].base_qty, Decimal::new(2_500, 2));
+        assert_eq!(scaled.orders[0].quote_qty, Decimal::new(25_000, 8));
+
+        ramp.record(Decimal::new(1, 2));
+        assert_eq!(ramp.percent(), Decimal::from(55));
+        ramp.record(Decimal::ZERO);
+        assert_eq!(ramp.percent(), Decimal::from(85));
+        ramp.record(Decimal::new(1, 2));
+        assert_eq!(ramp.percent(), Decimal::ONE_HUNDRED);
+        assert_eq!(ramp.apply(&chain()), chain());
+
+        ramp.record(Decimal::new(-1, 2));
+        assert_eq!(ramp.percent(), Decimal::from(25));
+    }
+
+    #[test]
+    fn test_scaled_qty_is_truncated_to_built_precision() {
+        let ramp = OrderSizeRamp::new(SizeRamp {
+            initial_percent: Decimal::from(33),
+            step_percent: Decimal::from(10),
+        });
+
+        let scaled = ramp.apply(&chain());
+        assert_eq!(scaled.orders[0].base_qty, Decimal::new(3_300, 2));
+        assert_eq!(scaled.orders[0].quote_qty, Decimal::new(33_000, 8));
+
+        let mut odd = chain();
+        odd.orders[0].base_qty = Decimal::new(1_001, 2);
+        let scaled = ramp.apply(&odd);
+        // 10.01 * 0.33 = 3.3033, truncated to 3.30.
+        assert_eq!(scaled.orders[0].base_qty, Decimal::new(330, 2));
+    }
+}
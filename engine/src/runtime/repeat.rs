@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+
+use crate::model::orders::ChainOrders;
+
+/// Remembers the prices each chain was last executed at, so a chain re-emitted at the
+/// same price level is not executed again, independent of any cooldown.
+pub struct RepeatGuard {
+    tolerance_bps: Decimal,
+    executed: HashMap<String, Vec<Decimal>>,
+}
+
+impl RepeatGuard {
+    /// Creates a guard that treats price changes up to `tolerance_bps` as unchanged.
+    #[must_use]
+    pub fn new(tolerance_bps: u32) -> Self {
+        Self {
+            tolerance_bps: Decimal::from(tolerance_bps),
+            executed: HashMap::new(),
+        }
+    }
+
+    /// Returns true if the same symbols were executed before and no leg's price has moved
+    /// by more than the tolerance since.
+    #[must_use]
+    pub fn is_repeat(&self, chain: &ChainOrders) -> bool {
+        let Some(prices) = self.executed.get(&Self::key(chain)) else {
+            return false;
+        };
+
+        let basis_points = Decimal::from(10_000);
+        prices.iter().zip(&chain.orders).all(|(last, order)| {
+            if last.is_zero() {
+                return order.price.is_zero();
+            }
+            (order.price - last).abs() / last * basis_points <= self.tolerance_bps
+        })
+    }
+
+    /// Records the prices of an executed chain.
+    pub fn record(&mut self, chain: &ChainOrders) {
+        self.executed.insert(
+            Self::key(chain),
+            chain.orders.iter().map(|order| order.price).collect(),
+        );
+    }
+
+    fn key(chain: &ChainOrders) -> String {
+        chain.extract_symbols().join("-")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ChainOrder, model::orders::test_utils::order};
+
+    fn chain(prices: &[(&str, i64)]) -> ChainOrders {
+        ChainOrders {
+            orders: prices
+                .iter()
+                .map(|(symbol, price)| ChainOrder {
+                    price: Decimal::from(*price),
+                    ..order(symbol)
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_identical_prices_skipped() {
+        let mut guard = RepeatGuard::new(10);
+        let executed = chain(&[("BTCUSDT", 100_000), ("ETHBTC", 300), ("ETHUSDT", 3_000)]);
+        assert!(!guard.is_repeat(&executed));
+
+        guard.record(&executed);
+        assert!(guard.is_repeat(&executed));
+
+        // 0.05% move on one leg is within the 10 bps tolerance.
+        let within = chain(&[("BTCUSDT", 100_050), ("ETHBTC", 300), ("ETHUSDT", 3_000)]);
+        assert!(guard.is_repeat(&within));
+
+        // 1% move is a new price level.
+        let moved = chain(&[("BTCUSDT", 101_000), ("ETHBTC", 300), ("ETHUSDT", 3_000)]);
+        assert!(!guard.is_repeat(&moved));
+
+        let other = chain(&[("SOLUSDT", 100_000), ("ETHBTC", 300), ("ETHUSDT", 3_000)]);
+        assert!(!guard.is_repeat(&other));
+    }
+}
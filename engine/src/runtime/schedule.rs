@@ -0,0 +1,155 @@
+use std::{fmt::Display, str::FromStr};
+
+use anyhow::{Context, bail};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, de::Error};
+use tools::misc;
+
+/// Minute of the day in UTC, written as `"HH:MM"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TimeOfDay(u16);
+
+impl TimeOfDay {
+    const MINUTES_PER_DAY: u64 = 24 * 60;
+
+    /// Current UTC time of day.
+    #[must_use]
+    pub fn now() -> Self {
+        Self::from_unix_secs(misc::time::get_current_timestamp().as_secs())
+    }
+
+    #[must_use]
+    pub fn from_unix_secs(secs: u64) -> Self {
+        Self(((secs / 60) % Self::MINUTES_PER_DAY) as u16)
+    }
+}
+
+impl FromStr for TimeOfDay {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let (hours, minutes) = s
+            .split_once(':')
+            .with_context(|| format!("Invalid time {s:?}, expected HH:MM"))?;
+        let hours: u16 = hours
+            .parse()
+            .with_context(|| format!("Invalid hours in {s:?}"))?;
+        let minutes: u16 = minutes
+            .parse()
+            .with_context(|| format!("Invalid minutes in {s:?}"))?;
+
+        if hours >= 24 || minutes >= 60 {
+            bail!("Time {s:?} is out of range");
+        }
+        Ok(Self(hours * 60 + minutes))
+    }
+}
+
+impl Display for TimeOfDay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:02}:{:02}", self.0 / 60, self.0 % 60)
+    }
+}
+
+impl<'de> Deserialize<'de> for TimeOfDay {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+/// Daily UTC window in which the minimum profit threshold is scaled.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProfitWindow {
+    /// Start of the window (inclusive).
+    pub start: TimeOfDay,
+    /// End of the window (exclusive). May be earlier than `start` to wrap past midnight.
+    pub end: TimeOfDay,
+    /// Threshold as a percentage of the configured `min_profit_qty` (e.g. 50 halves it).
+    #[serde(with = "rust_decimal::serde::float")]
+    pub min_profit_percent: Decimal,
+}
+
+impl ProfitWindow {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.start == self.end {
+            bail!("Profit window {} - {} is empty", self.start, self.end);
+        }
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn contains(&self, at: TimeOfDay) -> bool {
+        if self.start < self.end {
+            self.start <= at && at < self.end
+        } else {
+            at >= self.start || at < self.end
+        }
+    }
+}
+
+/// Time-of-day schedule of minimum profit thresholds.
+/// Outside every window the configured threshold applies unchanged.
+#[derive(Debug, Clone, Default)]
+pub struct ProfitSchedule {
+    windows: Vec<ProfitWindow>,
+}
+
+impl ProfitSchedule {
+    #[must_use]
+    pub fn new(windows: Vec<ProfitWindow>) -> Self {
+        Self { windows }
+    }
+
+    /// Multiplier for the configured threshold at the given time. The first matching window
+    /// wins.
+    #[must_use]
+    pub fn min_profit_scale(&self, at: TimeOfDay) -> Decimal {
+        self.windows
+            .iter()
+            .find(|window| window.contains(at))
+            .map_or(Decimal::ONE, |window| {
+                window.min_profit_percent / Decimal::ONE_HUNDRED
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(start: &str, end: &str, min_profit_percent: i64) -> ProfitWindow {
+        ProfitWindow {
+            start: start.parse().unwrap(),
+            end: end.parse().unwrap(),
+            min_profit_percent: Decimal::from(min_profit_percent),
+        }
+    }
+
+    #[test]
+    fn test_active_threshold_follows_clock() {
+        let schedule = ProfitSchedule::new(vec![
+            window("22:00", "06:00", 50),
+            window("13:30", "16:00", 200),
+        ]);
+        let at = |hours: u64, minutes: u64| {
+            // Any day works, only the time of day matters.
+            TimeOfDay::from_unix_secs(1_760_000_000 / 86_400 * 86_400 + hours * 3600 + minutes * 60)
+        };
+
+        assert_eq!(schedule.min_profit_scale(at(23, 0)), Decimal::new(5, 1));
+        assert_eq!(schedule.min_profit_scale(at(5, 59)), Decimal::new(5, 1));
+        assert_eq!(schedule.min_profit_scale(at(6, 0)), Decimal::ONE);
+        assert_eq!(schedule.min_profit_scale(at(13, 30)), Decimal::TWO);
+        assert_eq!(schedule.min_profit_scale(at(16, 0)), Decimal::ONE);
+    }
+
+    #[test]
+    fn test_parse_time_of_day() {
+        assert_eq!("06:05".parse::<TimeOfDay>().unwrap().to_string(), "06:05");
+        assert!("24:00".parse::<TimeOfDay>().is_err());
+        assert!("6".parse::<TimeOfDay>().is_err());
+        assert!(window("10:00", "10:00", 50).validate().is_err());
+    }
+}
@@ -0,0 +1,44 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Chains being placed by the sender, waited for on shutdown.
+pub static IN_FLIGHT_CHAINS: InFlightChains = InFlightChains::new();
+
+/// Counts the chains whose orders are being placed.
+#[derive(Debug, Default)]
+pub struct InFlightChains {
+    count: AtomicUsize,
+}
+
+/// Keeps a chain counted as in flight until dropped.
+pub struct InFlightGuard<'a> {
+    chains: &'a InFlightChains,
+}
+
+impl InFlightChains {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            count: AtomicUsize::new(0),
+        }
+    }
+
+    /// Counts a chain as in flight until the returned guard is dropped, whether its orders
+    /// resolved or the placement was aborted.
+    #[must_use]
+    pub fn enter(&self) -> InFlightGuard<'_> {
+        self.count.fetch_add(1, Ordering::SeqCst);
+        InFlightGuard { chains: self }
+    }
+
+    /// Returns the number of chains in flight.
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        self.chains.count.fetch_sub(1, Ordering::SeqCst);
+    }
+}
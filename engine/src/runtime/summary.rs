@@ -0,0 +1,203 @@
+use std::{collections::BTreeMap, path::Path, sync::LazyLock, time::Duration};
+
+use anyhow::Context;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use tokio::{sync::Mutex, time::Instant};
+use tracing::info;
+
+use crate::runtime::usd::{USD_PROFIT_FLOOR, UsdProfitFloor};
+
+/// Global run statistics, reported once on shutdown.
+pub static RUN_STATS: LazyLock<Mutex<RunStats>> =
+    LazyLock::new(|| Mutex::new(RunStats::new(Instant::now())));
+
+/// Counts what happened during a run, for a post-mortem without scraping metrics.
+pub struct RunStats {
+    started_at: Instant,
+    chains_processed: u64,
+    chains_executed: u64,
+    wins: u64,
+    losses: u64,
+    realized_pnl: BTreeMap<String, Decimal>,
+    rejections: BTreeMap<&'static str, u64>,
+    reconnections: u64,
+}
+
+/// Snapshot of [`RunStats`] at the end of a run.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunSummary {
+    pub uptime_secs: u64,
+    pub chains_processed: u64,
+    pub chains_executed: u64,
+    pub wins: u64,
+    pub losses: u64,
+    /// Realized profit per base asset.
+    pub realized_pnl: BTreeMap<String, Decimal>,
+    /// Realized profit of all base assets in USD, when all of them are priced.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub realized_pnl_usd: Option<Decimal>,
+    /// Skipped chains by reason.
+    pub rejections: BTreeMap<String, u64>,
+    pub reconnections: u64,
+}
+
+impl RunStats {
+    /// Creates empty statistics for a run started at `started_at`.
+    #[must_use]
+    pub fn new(started_at: Instant) -> Self {
+        Self {
+            started_at,
+            chains_processed: 0,
+            chains_executed: 0,
+            wins: 0,
+            losses: 0,
+            realized_pnl: BTreeMap::new(),
+            rejections: BTreeMap::new(),
+            reconnections: 0,
+        }
+    }
+
+    /// Records a profitable chain received by the sender.
+    pub fn record_processed(&mut self) {
+        self.chains_processed += 1;
+    }
+
+    /// Records a chain skipped for the given reason.
+    pub fn record_rejection(&mut self, reason: &'static str) {
+        *self.rejections.entry(reason).or_default() += 1;
+    }
+
+    /// Records an executed chain and its realized profit in the base asset.
+    pub fn record_execution(&mut self, asset: &str, profit: Decimal) {
+        self.chains_executed += 1;
+        if profit > Decimal::ZERO {
+            self.wins += 1;
+        } else if profit < Decimal::ZERO {
+            self.losses += 1;
+        }
+        *self.realized_pnl.entry(asset.to_owned()).or_default() += profit;
+    }
+
+    /// Records a dropped market data connection.
+    pub fn record_reconnection(&mut self) {
+        self.reconnections += 1;
+    }
+
+    /// Realized profit of all base assets in USD, each converted and rounded to the precision
+    /// of `floor` before adding up. `None` while the price of any of them is unknown.
+    #[must_use]
+    pub fn realized_pnl_usd(&self, floor: &UsdProfitFloor) -> Option<Decimal> {
+        self.realized_pnl
+            .iter()
+            .map(|(asset, profit)| floor.to_usd(asset, *profit))
+            .sum()
+    }
+
+    #[must_use]
+    pub fn summary(&self, now: Instant) -> RunSummary {
+        RunSummary {
+            uptime_secs: now.duration_since(self.started_at).as_secs(),
+            chains_processed: self.chains_processed,
+            chains_executed: self.chains_executed,
+            wins: self.wins,
+            losses: self.losses,
+            realized_pnl: self.realized_pnl.clone(),
+            realized_pnl_usd: USD_PROFIT_FLOOR
+                .get()
+                .and_then(|floor| self.realized_pnl_usd(floor)),
+            rejections: self
+                .rejections
+                .iter()
+                .map(|(reason, count)| ((*reason).to_owned(), *count))
+                .collect(),
+            reconnections: self.reconnections,
+        }
+    }
+}
+
+impl RunSummary {
+    /// Logs the summary and, if a path is given, writes it there as JSON.
+    pub fn report(&self, path: Option<&Path>) -> anyhow::Result<()> {
+        info!(
+            uptime = ?Duration::from_secs(self.uptime_secs),
+            chains_processed = self.chains_processed,
+            chains_executed = self.chains_executed,
+            wins = self.wins,
+            losses = self.losses,
+            realized_pnl = ?self.realized_pnl,
+            realized_pnl_usd = self.realized_pnl_usd.map(tracing::field::display),
+            rejections = ?self.rejections,
+            reconnections = self.reconnections,
+            "📋 [Engine] Run summary"
+        );
+
+        if let Some(path) = path {
+            let json =
+                serde_json::to_string_pretty(self).context("Failed to serialize run summary")?;
+            std::fs::write(path, json)
+                .with_context(|| format!("Failed to write run summary to {}", path.display()))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_written_on_shutdown() -> anyhow::Result<()> {
+        let start = Instant::now();
+        let mut stats = RunStats::new(start);
+
+        for _ in 0..4 {
+            stats.record_processed();
+        }
+        stats.record_rejection("interval");
+        stats.record_rejection("interval");
+        stats.record_rejection("repeat");
+        stats.record_execution("USDT", Decimal::new(15, 1));
+        stats.record_execution("USDT", Decimal::new(-5, 1));
+        stats.record_reconnection();
+
+        let summary = stats.summary(start + Duration::from_secs(3600));
+        let path = std::env::temp_dir().join(format!("run-summary-{}.json", std::process::id()));
+        summary.report(Some(&path))?;
+        let written: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+        std::fs::remove_file(&path)?;
+
+        assert_eq!(
+            written,
+            serde_json::json!({
+                "uptime_secs": 3600,
+                "chains_processed": 4,
+                "chains_executed": 2,
+                "wins": 1,
+                "losses": 1,
+                "realized_pnl": {"USDT": "1.0"},
+                "rejections": {"interval": 2, "repeat": 1},
+                "reconnections": 1
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_realized_pnl_is_converted_at_the_usd_precision() {
+        let start = Instant::now();
+        let mut stats = RunStats::new(start);
+        stats.record_execution("USDT", Decimal::new(1_005, 3));
+        stats.record_execution("BTC", Decimal::new(1_234, 8));
+
+        let floor = UsdProfitFloor::new(Decimal::ONE, "USDT");
+        assert_eq!(stats.realized_pnl_usd(&floor), None);
+
+        // 1.01 + 1.35, each asset rounded to cents.
+        floor.set_price("BTC", Decimal::new(10_961_546, 2));
+        assert_eq!(stats.realized_pnl_usd(&floor), Some(Decimal::new(236, 2)));
+
+        let floor = floor.with_precision(3);
+        assert_eq!(stats.realized_pnl_usd(&floor), Some(Decimal::new(2_358, 3)));
+    }
+}
@@ -0,0 +1,194 @@
+use std::{
+    collections::BTreeSet,
+    sync::{Arc, LazyLock, RwLock},
+};
+
+use async_trait::async_trait;
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    routing::{get, post},
+};
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+use tools::http::http_server::HttpServerProcess;
+use tracing::info;
+
+/// Symbols disabled at runtime, shared by the exchange services and `POST /symbols`.
+pub static DISABLED_SYMBOLS: LazyLock<Arc<DisabledSymbols>> = LazyLock::new(Arc::default);
+
+/// Symbols whose chains are not traded until they are enabled again. Symbols are matched
+/// case-insensitively.
+#[derive(Debug, Default)]
+pub struct DisabledSymbols {
+    symbols: RwLock<BTreeSet<String>>,
+}
+
+impl DisabledSymbols {
+    /// Disables `symbol`, returns whether it was enabled.
+    pub fn disable(&self, symbol: &str) -> bool {
+        self.write().insert(symbol.to_uppercase())
+    }
+
+    /// Enables `symbol` again, returns whether it was disabled.
+    pub fn enable(&self, symbol: &str) -> bool {
+        self.write().remove(&symbol.to_uppercase())
+    }
+
+    /// Returns the first disabled symbol among `symbols`.
+    pub fn find<'a>(&self, symbols: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+        let disabled = self.symbols.read().unwrap_or_else(|e| e.into_inner());
+        if disabled.is_empty() {
+            return None;
+        }
+        symbols
+            .into_iter()
+            .find(|symbol| disabled.contains(&symbol.to_uppercase()))
+    }
+
+    /// Returns the disabled symbols, sorted.
+    #[must_use]
+    pub fn list(&self) -> Vec<String> {
+        let disabled = self.symbols.read().unwrap_or_else(|e| e.into_inner());
+        disabled.iter().cloned().collect()
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, BTreeSet<String>> {
+        self.symbols.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Outcome of toggling a symbol.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SymbolState {
+    pub symbol: String,
+    pub disabled: bool,
+    /// Whether the request changed the state of the symbol.
+    pub changed: bool,
+}
+
+/// Serves `POST /symbols/{symbol}/disable` and `POST /symbols/{symbol}/enable`, pausing and
+/// resuming every chain containing the symbol without a restart, and `GET /symbols/disabled`.
+pub struct SymbolsProcess {
+    disabled: Arc<DisabledSymbols>,
+}
+
+impl SymbolsProcess {
+    #[must_use]
+    pub fn new(disabled: Arc<DisabledSymbols>) -> Self {
+        Self { disabled }
+    }
+}
+
+impl Default for SymbolsProcess {
+    fn default() -> Self {
+        Self::new(Arc::clone(&DISABLED_SYMBOLS))
+    }
+}
+
+#[async_trait]
+impl HttpServerProcess for SymbolsProcess {
+    async fn pre_run(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn run(&self, _token: CancellationToken) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn router(&self) -> Option<Router> {
+        Some(
+            Router::new()
+                .route("/symbols/disabled", get(list_disabled))
+                .route("/symbols/{symbol}/disable", post(disable_symbol))
+                .route("/symbols/{symbol}/enable", post(enable_symbol))
+                .with_state(Arc::clone(&self.disabled)),
+        )
+    }
+}
+
+async fn list_disabled(State(disabled): State<Arc<DisabledSymbols>>) -> Json<Vec<String>> {
+    Json(disabled.list())
+}
+
+async fn disable_symbol(
+    State(disabled): State<Arc<DisabledSymbols>>,
+    Path(symbol): Path<String>,
+) -> Json<SymbolState> {
+    let changed = disabled.disable(&symbol);
+    if changed {
+        info!(symbol, "⏸️ [Engine] Symbol disabled, its chains are paused");
+    }
+    Json(SymbolState {
+        symbol: symbol.to_uppercase(),
+        disabled: true,
+        changed,
+    })
+}
+
+async fn enable_symbol(
+    State(disabled): State<Arc<DisabledSymbols>>,
+    Path(symbol): Path<String>,
+) -> Json<SymbolState> {
+    let changed = disabled.enable(&symbol);
+    if changed {
+        info!(symbol, "▶️ [Engine] Symbol enabled, its chains are resumed");
+    }
+    Json(SymbolState {
+        symbol: symbol.to_uppercase(),
+        disabled: false,
+        changed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_symbols_are_toggled_over_http() -> anyhow::Result<()> {
+        let disabled = Arc::new(DisabledSymbols::default());
+        let router = SymbolsProcess::new(Arc::clone(&disabled))
+            .router()
+            .expect("symbols process serves routes");
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}/symbols", listener.local_addr()?);
+        let server = tokio::spawn(async move { axum::serve(listener, router).await });
+        let client = reqwest::Client::new();
+
+        let state: SymbolState = client
+            .post(format!("{url}/ethbtc/disable"))
+            .send()
+            .await?
+            .json()
+            .await?;
+        assert!(state.disabled && state.changed);
+        assert_eq!(state.symbol, "ETHBTC");
+        assert_eq!(
+            disabled.find(["BTCUSDT", "ETHBTC", "ETHUSDT"]),
+            Some("ETHBTC")
+        );
+        assert_eq!(disabled.find(["BTCUSDT", "SOLBTC", "SOLUSDT"]), None);
+
+        let listed: Vec<String> = client
+            .get(format!("{url}/disabled"))
+            .send()
+            .await?
+            .json()
+            .await?;
+        assert_eq!(listed, ["ETHBTC"]);
+
+        let state: SymbolState = client
+            .post(format!("{url}/ETHBTC/enable"))
+            .send()
+            .await?
+            .json()
+            .await?;
+        assert!(!state.disabled && state.changed);
+        assert_eq!(disabled.find(["BTCUSDT", "ETHBTC", "ETHUSDT"]), None);
+
+        server.abort();
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+use std::{
+    collections::HashMap,
+    sync::{OnceLock, PoisonError, RwLock},
+};
+
+use rust_decimal::{Decimal, RoundingStrategy};
+
+/// Chain profit floor in USD, set from the general config before the services start.
+pub static USD_PROFIT_FLOOR: OnceLock<UsdProfitFloor> = OnceLock::new();
+
+/// Minimum chain profit in USD, replacing the per-asset minimum profits so chains starting
+/// from different base assets are held to the same bar.
+///
+/// The USD prices of the base assets are kept up to date from the ticker stream; a chain is
+/// rejected until the price of its base asset is known.
+#[derive(Debug)]
+pub struct UsdProfitFloor {
+    min_profit_usd: Decimal,
+    /// Asset priced at 1 USD, the quote asset of the symbols prices are read from.
+    usd_asset: String,
+    /// Decimal places the USD values are rounded to.
+    precision: u32,
+    prices: RwLock<HashMap<String, Decimal>>,
+}
+
+impl UsdProfitFloor {
+    /// Decimal places of the USD values unless configured, the cents of the USD asset.
+    pub const DEFAULT_PRECISION: u32 = 2;
+
+    #[must_use]
+    pub fn new(min_profit_usd: Decimal, usd_asset: impl Into<String>) -> Self {
+        Self {
+            min_profit_usd,
+            usd_asset: usd_asset.into(),
+            precision: Self::DEFAULT_PRECISION,
+            prices: RwLock::default(),
+        }
+    }
+
+    /// Rounds the USD values to `precision` decimal places.
+    #[must_use]
+    pub fn with_precision(mut self, precision: u32) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    #[must_use]
+    pub fn usd_asset(&self) -> &str {
+        &self.usd_asset
+    }
+
+    /// Records the USD price of `asset`, ignoring non-positive prices of empty books.
+    pub fn set_price(&self, asset: &str, price: Decimal) {
+        if price <= Decimal::ZERO {
+            return;
+        }
+        self.prices
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(asset.to_owned(), price);
+    }
+
+    /// Returns the USD price of `asset`, the USD asset itself being worth 1.
+    #[must_use]
+    pub fn price(&self, asset: &str) -> Option<Decimal> {
+        if asset == self.usd_asset {
+            return Some(Decimal::ONE);
+        }
+        self.prices
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(asset)
+            .copied()
+    }
+
+    /// Returns `qty` of `asset` in USD, rounded to the configured precision.
+    #[must_use]
+    pub fn to_usd(&self, asset: &str, qty: Decimal) -> Option<Decimal> {
+        self.price(asset).map(|price| {
+            (qty * price)
+                .round_dp_with_strategy(self.precision, RoundingStrategy::MidpointAwayFromZero)
+        })
+    }
+
+    /// Returns the floor converted into `asset`, or `None` while its USD price is unknown.
+    #[must_use]
+    pub fn min_profit_qty(&self, asset: &str) -> Option<Decimal> {
+        self.price(asset).map(|price| self.min_profit_usd / price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_floor_is_converted_at_the_usd_price() {
+        let floor = UsdProfitFloor::new(Decimal::from(3), "USDT");
+        floor.set_price("BTC", Decimal::from(100_000));
+        floor.set_price("ETH", Decimal::ZERO);
+
+        assert_eq!(floor.min_profit_qty("USDT"), Some(Decimal::from(3)));
+        assert_eq!(floor.min_profit_qty("BTC"), Some(Decimal::new(3, 5)));
+        assert_eq!(floor.min_profit_qty("ETH"), None);
+        assert_eq!(
+            floor.to_usd("BTC", Decimal::new(2, 5)),
+            Some(Decimal::from(2))
+        );
+    }
+
+    #[test]
+    fn test_usd_values_are_rounded_to_the_precision() {
+        let floor = UsdProfitFloor::new(Decimal::from(3), "USDT");
+        floor.set_price("BTC", Decimal::new(10_961_546, 2));
+
+        // 0.00001234 BTC = 1.352654776... USD.
+        assert_eq!(
+            floor.to_usd("BTC", Decimal::new(1_234, 8)),
+            Some(Decimal::new(135, 2))
+        );
+        assert_eq!(
+            floor.to_usd("USDT", Decimal::new(-1_005, 3)),
+            Some(Decimal::new(-101, 2))
+        );
+
+        let floor = floor.with_precision(4);
+        assert_eq!(
+            floor.to_usd("BTC", Decimal::new(1_234, 8)),
+            Some(Decimal::new(13_527, 4))
+        );
+        assert_eq!(
+            floor
+                .to_usd("BTC", Decimal::new(1, 8))
+                .map(|usd| usd.scale()),
+            Some(4)
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use tokio::{sync::Mutex, time::Instant};
+use tokio_util::sync::CancellationToken;
+use tracing::warn;
+
+use crate::METRICS;
+
+/// Alerts when no chain has passed the profit gate for a while. A long silence usually
+/// points at stale market data or a lost connection rather than a quiet market.
+pub struct OpportunityWatchdog {
+    idle_after: Duration,
+    state: Mutex<WatchdogState>,
+}
+
+struct WatchdogState {
+    last_seen: Instant,
+    alerted: bool,
+}
+
+impl OpportunityWatchdog {
+    /// How often the idle time is checked.
+    pub const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+    /// Creates a watchdog whose idle period starts at `now`.
+    #[must_use]
+    pub fn new(idle_after: Duration, now: Instant) -> Self {
+        Self {
+            idle_after,
+            state: Mutex::new(WatchdogState {
+                last_seen: now,
+                alerted: false,
+            }),
+        }
+    }
+
+    /// Records a profitable chain, resetting the idle period.
+    pub async fn record(&self, now: Instant) {
+        let mut state = self.state.lock().await;
+        state.last_seen = now;
+        state.alerted = false;
+    }
+
+    /// Raises the alert if nothing was recorded for the idle period. Returns true when the
+    /// alert fired; it fires once per idle period, until the next recorded chain.
+    pub async fn check(&self, now: Instant) -> bool {
+        let mut state = self.state.lock().await;
+        let idle = now.duration_since(state.last_seen);
+        if state.alerted || idle < self.idle_after {
+            return false;
+        }
+
+        state.alerted = true;
+        METRICS.record_no_opportunity_alert();
+        warn!(
+            idle_secs = idle.as_secs(),
+            "🔕 [Engine] No profitable chain seen recently, check market data and connectivity"
+        );
+        true
+    }
+
+    /// Checks the idle time every [`Self::CHECK_INTERVAL`] until cancelled.
+    pub async fn monitor(&self, token: CancellationToken) {
+        loop {
+            tokio::select! {
+                () = token.cancelled() => return,
+                () = tokio::time::sleep(Self::CHECK_INTERVAL) => {
+                    self.check(Instant::now()).await;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_alert_fires_after_idle_period() {
+        let secs = Duration::from_secs;
+        let start = Instant::now();
+        let watchdog = OpportunityWatchdog::new(secs(60), start);
+
+        assert!(!watchdog.check(start + secs(59)).await);
+        assert!(watchdog.check(start + secs(60)).await);
+        // Fires once per idle period.
+        assert!(!watchdog.check(start + secs(90)).await);
+
+        // A profitable chain re-arms the alert.
+        watchdog.record(start + secs(100)).await;
+        assert!(!watchdog.check(start + secs(150)).await);
+        assert!(watchdog.check(start + secs(160)).await);
+    }
+}
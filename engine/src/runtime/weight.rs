@@ -1,4 +1,4 @@
-use std::sync::LazyLock;
+use std::{sync::LazyLock, time::Duration};
 
 use tokio::sync::Mutex;
 use tools::misc;
@@ -13,6 +13,20 @@ pub struct RequestWeight {
     weight: usize,
     weight_limit: usize,
     weight_reset_secs: u64,
+    /// Unix time in seconds until which no weight is granted, set from `Retry-After` or
+    /// the back-off threshold.
+    paused_until: u64,
+    /// Percentage of the limit from which the weight reported by the server pauses new
+    /// requests until its next window (unset = off).
+    backoff_percent: Option<u8>,
+    /// Window (Unix time divided by the window length) of the last weight reported by the
+    /// server.
+    synced_window: u64,
+    /// Pause after a 429 or 418 response without `Retry-After`, doubled by every further one
+    /// until a request succeeds (unset = only `Retry-After` pauses).
+    rate_limit_cooldown: Option<Duration>,
+    /// Rate limited responses since the last successful one.
+    rate_limit_strikes: u32,
 }
 
 impl Default for RequestWeight {
@@ -22,6 +36,9 @@ impl Default for RequestWeight {
 }
 
 impl RequestWeight {
+    /// Longest cooldown the doubling reaches, a longer `Retry-After` is still honoured.
+    const MAX_RATE_LIMIT_COOLDOWN: Duration = Duration::from_secs(3_600);
+
     #[must_use]
     pub fn new() -> Self {
         Self {
@@ -29,6 +46,11 @@ impl RequestWeight {
             weight: 0,
             weight_limit: 0,
             weight_reset_secs: 60,
+            paused_until: 0,
+            backoff_percent: None,
+            synced_window: 0,
+            rate_limit_cooldown: None,
+            rate_limit_strikes: 0,
         }
     }
 
@@ -37,9 +59,32 @@ impl RequestWeight {
         self.weight_limit = weight_limit;
     }
 
-    /// Attempts to add weight; returns true if successful (under limit after reset check)
+    /// Pauses new requests until the next window once the server reports `percent` % of
+    /// the limit used.
+    pub fn set_backoff_percent(&mut self, percent: Option<u8>) {
+        self.backoff_percent = percent;
+    }
+
+    /// Pauses every rate limited response for at least `cooldown`, doubled by every further
+    /// one until a request succeeds.
+    pub fn set_rate_limit_cooldown(&mut self, cooldown: Option<Duration>) {
+        self.rate_limit_cooldown = cooldown;
+    }
+
+    /// Weight used in the current window.
+    #[must_use]
+    pub fn used(&self) -> usize {
+        self.weight
+    }
+
+    /// Attempts to add weight; returns true if successful (under limit after reset check
+    /// and not paused by the server)
     pub fn add(&mut self, weight: usize) -> bool {
         let current_ts = misc::time::get_current_timestamp().as_secs();
+        if current_ts < self.paused_until {
+            return false;
+        }
+
         if current_ts - self.timestamp > self.weight_reset_secs {
             self.weight = 0;
             self.timestamp = current_ts;
@@ -53,6 +98,75 @@ impl RequestWeight {
         true
     }
 
+    /// Refuses all weight for the duration the server asked to back off for (`Retry-After`
+    /// of a 429 or 418 response). An earlier pause is never shortened.
+    pub fn pause(&mut self, retry_after: Duration) {
+        let current_ts = misc::time::get_current_timestamp().as_secs();
+        let paused_until = current_ts + retry_after.as_secs_f64().ceil() as u64;
+        self.paused_until = self.paused_until.max(paused_until);
+    }
+
+    /// Pauses all weight after a 429 or 418 response, for the `Retry-After` back-off or, with
+    /// a cooldown set, for the longest of it and the escalated cooldown.
+    ///
+    /// Returns the pause applied, if any.
+    pub fn rate_limited(&mut self, retry_after: Option<Duration>) -> Option<Duration> {
+        let cooldown = self.rate_limit_cooldown.map(|cooldown| {
+            let cooldown = cooldown
+                .saturating_mul(2u32.saturating_pow(self.rate_limit_strikes))
+                .min(Self::MAX_RATE_LIMIT_COOLDOWN);
+            self.rate_limit_strikes = self.rate_limit_strikes.saturating_add(1);
+            cooldown
+        });
+        let pause = retry_after.max(cooldown)?;
+        self.pause(pause);
+        Some(pause)
+    }
+
+    /// Resets the cooldown escalation once a request is no longer rate limited.
+    pub fn clear_rate_limited(&mut self) {
+        self.rate_limit_strikes = 0;
+    }
+
+    /// Remaining pause set by [`RequestWeight::pause`], if any.
+    #[must_use]
+    pub fn paused_for(&self) -> Option<Duration> {
+        let current_ts = misc::time::get_current_timestamp().as_secs();
+        (current_ts < self.paused_until)
+            .then(|| Duration::from_secs(self.paused_until - current_ts))
+    }
+
+    /// Reconciles the used weight with the one the server reports for its current window,
+    /// which also counts requests made by other clients of the same IP. Within a window the
+    /// weight is only raised, as the server does not count requests still in flight yet; the
+    /// first report of a new window replaces it, dropping the drift of the previous one.
+    ///
+    /// Returns true if the reported weight reached the back-off threshold, in which case no
+    /// weight is granted until the next window.
+    pub fn sync_used(&mut self, used_weight: usize) -> bool {
+        self.sync_used_at(used_weight, misc::time::get_current_timestamp().as_secs())
+    }
+
+    fn sync_used_at(&mut self, used_weight: usize, current_ts: u64) -> bool {
+        let window = current_ts / self.weight_reset_secs;
+        if window == self.synced_window {
+            self.weight = self.weight.max(used_weight);
+        } else {
+            self.synced_window = window;
+            self.weight = used_weight;
+            self.timestamp = window * self.weight_reset_secs;
+        }
+
+        let near_limit = self.weight_limit > 0
+            && self.backoff_percent.is_some_and(|percent| {
+                used_weight * 100 >= self.weight_limit * usize::from(percent)
+            });
+        if near_limit {
+            self.paused_until = self.paused_until.max((window + 1) * self.weight_reset_secs);
+        }
+        near_limit
+    }
+
     /// Subtracts weight if possible (no underflow).
     pub fn sub(&mut self, weight: usize) {
         if weight < self.weight {
@@ -63,6 +177,8 @@ impl RequestWeight {
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use crate::runtime::weight::RequestWeight;
 
     #[test]
@@ -81,6 +197,107 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_request_weight_follows_server() {
+        let mut request_weight = RequestWeight::new();
+        request_weight.set_weight_limit(10);
+
+        request_weight.sync_used(8);
+        assert!(!request_weight.add(5));
+        assert!(request_weight.add(2));
+        // A lower reported weight does not undo local accounting.
+        request_weight.sync_used(1);
+        assert_eq!(request_weight.weight, 10);
+
+        request_weight.sub(5);
+        request_weight.pause(Duration::from_secs(30));
+        assert!(!request_weight.add(1));
+        assert!(
+            request_weight
+                .paused_for()
+                .is_some_and(|d| d <= Duration::from_secs(30))
+        );
+
+        // A shorter pause keeps the longer one.
+        request_weight.pause(Duration::from_secs(1));
+        assert!(request_weight.paused_for() > Some(Duration::from_secs(1)));
+
+        request_weight.paused_until = 0;
+        assert_eq!(request_weight.paused_for(), None);
+        assert!(request_weight.add(1));
+    }
+
+    #[test]
+    fn test_repeated_rate_limits_escalate_the_cooldown() {
+        let mut request_weight = RequestWeight::new();
+        request_weight.set_weight_limit(10);
+
+        // Without a cooldown only `Retry-After` pauses.
+        assert_eq!(request_weight.rate_limited(None), None);
+        assert!(request_weight.add(1));
+
+        request_weight.set_rate_limit_cooldown(Some(Duration::from_secs(10)));
+        assert_eq!(
+            request_weight.rate_limited(None),
+            Some(Duration::from_secs(10))
+        );
+        assert!(!request_weight.add(1));
+        assert_eq!(
+            request_weight.rate_limited(Some(Duration::from_secs(5))),
+            Some(Duration::from_secs(20))
+        );
+        // A longer `Retry-After` wins over the cooldown.
+        assert_eq!(
+            request_weight.rate_limited(Some(Duration::from_secs(120))),
+            Some(Duration::from_secs(120))
+        );
+        assert!(
+            request_weight
+                .paused_for()
+                .is_some_and(|d| d > Duration::from_secs(100))
+        );
+
+        request_weight.clear_rate_limited();
+        assert_eq!(
+            request_weight.rate_limited(None),
+            Some(Duration::from_secs(10))
+        );
+
+        request_weight.rate_limit_strikes = 20;
+        assert_eq!(
+            request_weight.rate_limited(None),
+            Some(RequestWeight::MAX_RATE_LIMIT_COOLDOWN)
+        );
+    }
+
+    #[test]
+    fn test_request_weight_reconciles_with_server_windows() {
+        let mut request_weight = RequestWeight::new();
+        request_weight.set_weight_limit(100);
+        request_weight.set_backoff_percent(Some(90));
+        let window_start = 1_700_000_040;
+
+        assert!(!request_weight.sync_used_at(30, window_start + 5));
+        assert_eq!(request_weight.used(), 30);
+        // Local requests the server has not counted yet are kept within the window.
+        request_weight.weight = 50;
+        assert!(!request_weight.sync_used_at(40, window_start + 10));
+        assert_eq!(request_weight.used(), 50);
+
+        // The server's next window starts over, whatever the local estimate drifted to.
+        assert!(!request_weight.sync_used_at(3, window_start + 61));
+        assert_eq!(request_weight.used(), 3);
+        assert_eq!(request_weight.timestamp, window_start + 60);
+
+        // Near the limit nothing is granted until the window after.
+        assert!(request_weight.sync_used_at(90, window_start + 70));
+        assert_eq!(request_weight.paused_until, window_start + 120);
+
+        request_weight.set_backoff_percent(None);
+        request_weight.paused_until = 0;
+        assert!(!request_weight.sync_used_at(99, window_start + 80));
+    }
+
     #[test]
     fn test_request_weight_sub() -> anyhow::Result<()> {
         let mut request_weight = RequestWeight::new();
@@ -1,8 +1,20 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use tools::http::http_server::HttpServerProcess;
 
-use crate::{Exchange, Sender, ServiceFactory, runtime::process::GenericProcess};
+use crate::{
+    Exchange, Sender, ServiceFactory,
+    runtime::{
+        chains::ChainsProcess,
+        health::{HealthProcess, TICKS},
+        history::HistoryProcess,
+        kill_switch::KILL_SWITCH,
+        ledger::LedgerProcess,
+        notifier::TelegramNotifier,
+        process::GenericProcess,
+        symbols::SymbolsProcess,
+    },
+};
 
 pub async fn build_services<P, C>(
     config: &C,
@@ -15,12 +27,35 @@ where
     Ok((exchange, sender))
 }
 
+/// Builds the processes of a run. On shutdown the sender gets `shutdown_grace` to resolve
+/// the chains it is placing before it is aborted. `GET /health` fails once no book ticker
+/// was received for `health_stale_after`.
 pub fn build_processes(
     exchange: Arc<dyn Exchange>,
     sender: Arc<dyn Sender>,
+    notifier: TelegramNotifier,
+    ledger: Option<LedgerProcess>,
+    history: Option<HistoryProcess>,
+    shutdown_grace: Duration,
+    health_stale_after: Duration,
 ) -> Vec<Arc<dyn HttpServerProcess>> {
-    vec![
+    let mut processes: Vec<Arc<dyn HttpServerProcess>> = vec![
         Arc::new(GenericProcess::new(exchange)),
-        Arc::new(GenericProcess::new(sender)),
-    ]
+        Arc::new(GenericProcess::new(sender).with_shutdown_grace(shutdown_grace)),
+        Arc::new(ChainsProcess::default()),
+        Arc::new(HealthProcess::new(
+            Arc::clone(&TICKS),
+            Arc::clone(&KILL_SWITCH),
+            health_stale_after,
+        )),
+        Arc::new(SymbolsProcess::default()),
+        Arc::new(notifier),
+    ];
+    if let Some(ledger) = ledger {
+        processes.push(Arc::new(ledger));
+    }
+    if let Some(history) = history {
+        processes.push(Arc::new(history));
+    }
+    processes
 }
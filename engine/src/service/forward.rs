@@ -0,0 +1,185 @@
+//! Sender that hands detected chains to an external executor instead of trading.
+
+use std::time::Duration;
+
+use anyhow::{Context, bail};
+use async_trait::async_trait;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info};
+
+use crate::{
+    ChainOrders, ORDERS_CHANNEL, Sender, enums::OrderStatus, service::traits::ArbitrageService,
+};
+
+/// Publishes every chain received from [`ORDERS_CHANNEL`] as JSON with an HTTP `POST` to
+/// an external endpoint, turning the bot into a detection-only engine.
+pub struct ForwardSender {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl ForwardSender {
+    /// Timeout of a single forward request.
+    pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+    pub fn new(url: &str) -> anyhow::Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Self::REQUEST_TIMEOUT)
+            .build()
+            .context("Failed to build forward http client")?;
+
+        Ok(Self {
+            url: url.to_owned(),
+            client,
+        })
+    }
+
+    /// Sends a single chain to the external executor.
+    pub async fn forward(&self, chain: &ChainOrders) -> anyhow::Result<()> {
+        self.client
+            .post(&self.url)
+            .json(chain)
+            .send()
+            .await
+            .with_context(|| format!("Failed to forward chain {}", chain.chain_id))?
+            .error_for_status()
+            .with_context(|| format!("Executor rejected chain {}", chain.chain_id))?;
+
+        Ok(())
+    }
+
+    /// Forwards chains until cancelled. A failed forward is logged and the chain dropped,
+    /// since a later chain reflects fresher prices anyway.
+    async fn receive_and_forward(&self, token: CancellationToken) -> anyhow::Result<()> {
+        let mut orders_rx = ORDERS_CHANNEL.rx.lock().await;
+
+        // Get the initial value from watch channel
+        _ = orders_rx.borrow().clone();
+
+        loop {
+            tokio::select! {
+                () = token.cancelled() => break,
+
+                result = orders_rx.changed() => {
+                    if result.is_err() {
+                        break;
+                    }
+
+                    let chain = orders_rx.borrow().clone();
+                    match self.forward(&chain).await {
+                        Ok(()) => info!(
+                            chain_id = %chain.chain_id,
+                            "📤 [Engine] Chain forwarded to external executor"
+                        ),
+                        Err(e) => error!(error = ?e, "❌ [Engine] Error forwarding chain"),
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ArbitrageService for ForwardSender {
+    async fn start(&self, token: CancellationToken) -> anyhow::Result<()> {
+        self.receive_and_forward(token).await
+    }
+}
+
+#[async_trait]
+impl Sender for ForwardSender {
+    async fn query_order(
+        &self,
+        _symbol: &str,
+        client_order_id: &str,
+    ) -> anyhow::Result<OrderStatus> {
+        bail!("Order {client_order_id} is executed externally, its status is unknown")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockito::{Matcher, Server};
+    use rust_decimal::Decimal;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::{
+        ChainOrder,
+        enums::{OrderType, SymbolOrder},
+    };
+
+    #[tokio::test]
+    async fn test_forward_posts_chain_as_json() -> anyhow::Result<()> {
+        let chain = ChainOrders {
+            ts: 1_760_000_000_000,
+            chain_id: Uuid::nil(),
+            base_asset: "USDT".to_owned(),
+            fee_percent: Decimal::new(1, 1),
+            orders: vec![ChainOrder {
+                symbol: "BTCUSDT".to_owned(),
+                symbol_order: SymbolOrder::Desc,
+                order_type: OrderType::Market,
+                price: Decimal::new(10_000_050, 2),
+                base_qty: Decimal::new(1, 3),
+                quote_qty: Decimal::new(10_000_050, 5),
+                base_increment: Decimal::new(1, 5),
+                quote_increment: Decimal::new(1, 2),
+                min_qty: Decimal::ZERO,
+            }],
+            ..Default::default()
+        };
+
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/chains")
+            .match_header("content-type", "application/json")
+            .match_body(Matcher::JsonString(
+                r#"{
+                    "ts": 1760000000000,
+                    "chain_id": "00000000-0000-0000-0000-000000000000",
+                    "base_asset": "USDT",
+                    "fee_percent": "0.1",
+                    "orders": [{
+                        "symbol": "BTCUSDT",
+                        "symbol_order": "DESC",
+                        "order_type": "market",
+                        "price": "100000.50",
+                        "base_qty": "0.001",
+                        "quote_qty": "100.00050",
+                        "base_increment": "0.00001",
+                        "quote_increment": "0.01",
+                        "min_qty": "0"
+                    }]
+                }"#
+                .to_owned(),
+            ))
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let sender = ForwardSender::new(&format!("{}/chains", server.url()))?;
+        sender.forward(&chain).await?;
+        mock.assert_async().await;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_forward_fails_on_rejection() -> anyhow::Result<()> {
+        let mut server = Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/chains")
+            .with_status(503)
+            .create_async()
+            .await;
+
+        let sender = ForwardSender::new(&format!("{}/chains", server.url()))?;
+        assert!(sender.forward(&ChainOrders::default()).await.is_err());
+        assert!(sender.query_order("BTCUSDT", "id").await.is_err());
+
+        Ok(())
+    }
+}
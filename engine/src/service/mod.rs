@@ -1,3 +1,4 @@
 pub mod builder;
 pub mod factory;
+pub mod forward;
 pub mod traits;
@@ -1,6 +1,8 @@
 use async_trait::async_trait;
 use tokio_util::sync::CancellationToken;
 
+use crate::{enums::OrderStatus, runtime::balances::Balances};
+
 /// A trait for types that require internal consistency checks and parameter initialization.
 ///
 /// This trait is primarily used by configuration structures to ensure that all
@@ -16,7 +18,17 @@ pub trait ArbitrageService: Send + Sync {
 }
 
 #[async_trait]
-pub trait Exchange: ArbitrageService {}
+pub trait Exchange: ArbitrageService {
+    /// Returns the available balance per asset, served from a short-lived cache.
+    async fn balances(&self) -> anyhow::Result<Balances>;
+}
 
 #[async_trait]
-pub trait Sender: ArbitrageService {}
+pub trait Sender: ArbitrageService {
+    /// Returns the execution status of an order placed by this sender.
+    ///
+    /// `client_order_id` is the client-assigned order id; on Solana it is the
+    /// transaction signature and `symbol` is ignored.
+    async fn query_order(&self, symbol: &str, client_order_id: &str)
+    -> anyhow::Result<OrderStatus>;
+}
@@ -1,9 +1,12 @@
 use std::hint::black_box;
 
 use criterion::{Criterion, criterion_group};
-use engine::enums::SymbolOrder;
+use engine::{
+    FeeSchedule, MinNotional,
+    enums::{OrderType, SymbolOrder},
+};
 use kucoin::services::{
-    exchange::order::{OrderBuilder, OrderSymbol},
+    exchange::order::{FeeDeduction, OffGridPrice, OrderBuilder, OrderSymbol},
     storage::BookTickerEvent,
 };
 use rust_decimal::{Decimal, prelude::FromPrimitive};
@@ -34,7 +37,9 @@ pub fn calculate_chain_profit_benchmark(c: &mut Criterion) {
         OrderSymbol {
             symbol: "BTCUSDT".to_owned(),
             symbol_order: SymbolOrder::Asc,
+            order_type: OrderType::Market,
             min_profit_qty: Decimal::from_f64(0.000030),
+            min_profit_percent: None,
             max_order_qty: Decimal::from_f64(0.00030),
             order_book: &order_book_1,
             base_min_size: Default::default(),
@@ -48,7 +53,9 @@ pub fn calculate_chain_profit_benchmark(c: &mut Criterion) {
         OrderSymbol {
             symbol: "ETHUSDT".to_owned(),
             symbol_order: SymbolOrder::Desc,
+            order_type: OrderType::Market,
             min_profit_qty: None,
+            min_profit_percent: None,
             max_order_qty: None,
             order_book: &order_book_2,
             base_min_size: Default::default(),
@@ -62,7 +69,9 @@ pub fn calculate_chain_profit_benchmark(c: &mut Criterion) {
         OrderSymbol {
             symbol: "ETHBTC".to_owned(),
             symbol_order: SymbolOrder::Asc,
+            order_type: OrderType::Market,
             min_profit_qty: None,
+            min_profit_percent: None,
             max_order_qty: None,
             order_book: &order_book_3,
             base_min_size: Default::default(),
@@ -76,14 +85,17 @@ pub fn calculate_chain_profit_benchmark(c: &mut Criterion) {
     ];
 
     let market_depth_limit: usize = 1;
-    let fee_percent: Decimal = Decimal::from_f64(0.075).unwrap();
+    let fees = FeeSchedule::flat(Decimal::from_f64(0.075).unwrap());
 
     c.bench_function("kucoin::calculate_chain_profit", |b| {
         b.iter(|| {
             OrderBuilder::calculate_chain_profit(
                 black_box(&order_symbols),
                 black_box(market_depth_limit),
-                black_box(fee_percent),
+                black_box(&fees),
+                black_box(FeeDeduction::Quote),
+                black_box(&MinNotional::default()),
+                black_box(OffGridPrice::Align),
             )
         })
     });
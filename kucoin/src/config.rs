@@ -1,8 +1,18 @@
+use std::collections::{HashMap, HashSet};
+
 use anyhow::bail;
-use engine::Validatable;
+use engine::{
+    ProfitWindow, SizeRamp, Validatable,
+    enums::{OrderType, ReconnectStrategy},
+};
 use rust_decimal::Decimal;
 use serde::Deserialize;
 
+use crate::services::exchange::{
+    chain::InvalidIncrements,
+    order::{FeeCurrency, FeeDeduction, OffGridPrice},
+};
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct Config {
     pub api_url: String,
@@ -12,9 +22,74 @@ pub struct Config {
     pub ws_private_url: String,
     #[serde(with = "rust_decimal::serde::float")]
     pub fee_percent: Decimal,
+    /// Currency fees are paid in per the account's fee settings: "quote" or "kcs".
+    #[serde(default)]
+    pub fee_currency: FeeCurrency,
+    /// Discount on fees paid in KCS, in percent.
+    #[serde(
+        default = "default_kcs_fee_discount_percent",
+        with = "rust_decimal::serde::float"
+    )]
+    pub kcs_fee_discount_percent: Decimal,
+    /// Minimum leg value per symbol in its quote asset, raising the `quoteMinSize` one.
+    #[serde(default)]
+    pub min_notional: HashMap<String, Decimal>,
     pub api_weight_limit: usize,
     pub error_timeout: u64,
     pub send_orders: bool,
+    /// Endpoint receiving detected chains as JSON instead of trading them (unset = trade).
+    #[serde(default)]
+    pub forward_orders_url: Option<String>,
+    /// Minimum time a chain must stay profitable before orders are sent (0 = act at once).
+    #[serde(default)]
+    pub min_profit_persist_ms: u64,
+    /// Minimum price move (bps) on any leg before an already executed chain is sent again.
+    #[serde(default)]
+    pub min_price_change_bps: Option<u32>,
+    /// Maximum executions of a single chain per window (unset = no cap).
+    #[serde(default)]
+    pub max_chain_executions: Option<u32>,
+    /// Window of `max_chain_executions`, started by the first execution of a chain.
+    #[serde(default = "default_chain_executions_window_secs")]
+    pub chain_executions_window_secs: u64,
+    /// Maximum drift from the exchange clock before order placement is refused (unset = off).
+    #[serde(default)]
+    pub max_clock_drift_ms: Option<u64>,
+    /// Refuse to start if a chain symbol has no stream subscription (otherwise only warn).
+    #[serde(default)]
+    pub fail_on_missing_subscriptions: bool,
+    /// Recovery from a dropped stream connection: "resubscribe" or "rebuild" (restart service).
+    #[serde(default)]
+    pub reconnect_strategy: ReconnectStrategy,
+    /// Upper bound of the backoff between resubscription attempts, doubled per failed one.
+    #[serde(default = "default_max_reconnect_backoff_secs")]
+    pub max_reconnect_backoff_secs: u64,
+    /// Symbol streams allowed to fail their first connection at startup: their chains are
+    /// dropped and the rest run, more failures abort startup (unset = failed connections are
+    /// retried).
+    #[serde(default)]
+    pub max_failed_streams: Option<usize>,
+    /// Shut down instead of idling once the session profit target of a base asset is reached.
+    #[serde(default)]
+    pub exit_on_session_profit_target: bool,
+    /// Chains failing in a row after which trading halts until restart (unset = the first
+    /// failed chain stops sending).
+    #[serde(default)]
+    pub max_consecutive_failed_chains: Option<usize>,
+    /// Start chains at a fraction of their size and ramp up over chains executed without a loss.
+    #[serde(default)]
+    pub size_ramp: Option<SizeRamp>,
+    /// Cap `max_order_qty` at the free balance of the base asset, fetched at startup and
+    /// refreshed every this many seconds (unset = off).
+    #[serde(default)]
+    pub balance_refresh_interval_secs: Option<u64>,
+    /// Alert when no chain passes the profit gate for this long (unset = off).
+    #[serde(default)]
+    pub no_opportunity_alert_after_secs: Option<u64>,
+    /// Order type of the chain legs: "market" (fills at any price) or "limit" (IOC at the
+    /// chain price, may leave the chain unfilled).
+    #[serde(default)]
+    pub order_type: OrderType,
     #[serde(with = "rust_decimal::serde::float")]
     pub min_profit_qty: Decimal,
     #[serde(with = "rust_decimal::serde::float")]
@@ -22,11 +97,102 @@ pub struct Config {
     #[serde(with = "rust_decimal::serde::float")]
     pub min_ticker_qty_24h: Decimal,
     pub skip_assets: Vec<String>,
+    /// Chains with a symbol whose base, quote or price increment is zero: "skip" them or
+    /// only "warn".
+    #[serde(default)]
+    pub invalid_increments: InvalidIncrements,
+    /// Threads of a dedicated pool calculating chain profits (unset = on the async runtime).
+    #[serde(default)]
+    pub compute_threads: Option<usize>,
+    /// Times the processing of one book ticker update out of every this many into the
+    /// `ticker_update_processing_seconds` histogram (unset = not timed).
+    #[serde(default)]
+    pub update_latency_sample_every: Option<u64>,
+    /// Refuse chains that do not return to their starting asset.
+    #[serde(default = "default_verify_chain_cycle")]
+    pub verify_chain_cycle: bool,
+    /// Evaluate chains on crossed books (bid at or above ask) instead of skipping them until
+    /// they uncross.
+    #[serde(default)]
+    pub allow_crossed_books: bool,
+    /// Prices off the `price_increment` grid: "align" them against the chain or "skip" the chain.
+    #[serde(default)]
+    pub off_grid_price: OffGridPrice,
+    /// UTC time windows that scale `min_profit_qty` (e.g. lower thresholds in quiet hours).
+    #[serde(default)]
+    pub min_profit_schedule: Vec<ProfitWindow>,
     pub assets: Vec<Asset>,
 }
 
+fn default_kcs_fee_discount_percent() -> Decimal {
+    Decimal::from(20)
+}
+
+fn default_verify_chain_cycle() -> bool {
+    true
+}
+
+fn default_chain_executions_window_secs() -> u64 {
+    3600
+}
+
+fn default_max_reconnect_backoff_secs() -> u64 {
+    30
+}
+
+impl Config {
+    /// Returns how the chain fees are charged.
+    #[must_use]
+    pub fn fee_deduction(&self) -> FeeDeduction {
+        FeeDeduction::new(self.fee_currency, self.kcs_fee_discount_percent)
+    }
+}
+
 impl Validatable for Config {
     fn validate(&mut self) -> anyhow::Result<()> {
+        if self.compute_threads == Some(0) {
+            bail!("compute_threads must be greater than 0");
+        }
+        if self.update_latency_sample_every == Some(0) {
+            bail!("update_latency_sample_every must be greater than 0");
+        }
+        if !(Decimal::ZERO..=Decimal::ONE_HUNDRED).contains(&self.kcs_fee_discount_percent) {
+            bail!("kcs_fee_discount_percent must be between 0 and 100");
+        }
+        if self
+            .min_notional
+            .values()
+            .any(|min_notional| *min_notional < Decimal::ZERO)
+        {
+            bail!("min_notional must not be negative");
+        }
+        if self.max_consecutive_failed_chains == Some(0) {
+            bail!("max_consecutive_failed_chains must be greater than 0");
+        }
+        if let Some(ramp) = &self.size_ramp {
+            ramp.validate()?;
+        }
+        if self.balance_refresh_interval_secs == Some(0) {
+            bail!("balance_refresh_interval_secs must be greater than 0");
+        }
+        if self.no_opportunity_alert_after_secs == Some(0) {
+            bail!("no_opportunity_alert_after_secs must be greater than 0");
+        }
+        if self.max_chain_executions == Some(0) {
+            bail!("max_chain_executions must be greater than 0");
+        }
+        if self.chain_executions_window_secs == 0 {
+            bail!("chain_executions_window_secs must be greater than 0");
+        }
+        if self.max_reconnect_backoff_secs == 0 {
+            bail!("max_reconnect_backoff_secs must be greater than 0");
+        }
+        for window in &self.min_profit_schedule {
+            window.validate()?;
+        }
+        if let Some(asset) = Asset::find_duplicate(&self.assets) {
+            bail!("Base asset {asset} is listed more than once in assets");
+        }
         for asset in self.assets.iter_mut() {
             asset.validate(
                 self.min_profit_qty,
@@ -44,19 +210,67 @@ pub struct Asset {
     pub symbol: Option<String>,
     #[serde(with = "rust_decimal::serde::float")]
     pub min_profit_qty: Decimal,
+    /// Minimum chain profit after fees as a percentage of the quantity spent by the first
+    /// leg, required on top of `min_profit_qty` (unset = off). With `min_profit_qty = 0` it
+    /// is the only threshold.
+    #[serde(default, with = "rust_decimal::serde::float_option")]
+    pub min_profit_percent: Option<Decimal>,
     #[serde(with = "rust_decimal::serde::float")]
     pub max_order_qty: Decimal,
     #[serde(with = "rust_decimal::serde::float")]
     pub min_ticker_qty_24h: Decimal,
+    /// Realized session profit of the chains starting in this asset, in units of it, at which
+    /// trading stops (unset = no target).
+    #[serde(default, with = "rust_decimal::serde::float_option")]
+    pub session_profit_target: Option<Decimal>,
+    /// Realized loss of the chains starting in this asset within a UTC day, in units of it,
+    /// at which trading pauses until the next day (unset = no limit).
+    #[serde(default, with = "rust_decimal::serde::float_option")]
+    pub max_daily_loss: Option<Decimal>,
 }
 
 impl Asset {
+    /// Returns the first asset listed more than once, only its first entry would ever be
+    /// looked up.
+    #[must_use]
+    pub fn find_duplicate(assets: &[Self]) -> Option<&str> {
+        let mut seen = HashSet::new();
+        assets
+            .iter()
+            .map(|asset| asset.asset.as_str())
+            .find(|asset| !seen.insert(*asset))
+    }
+
     pub fn validate(
         &mut self,
         min_profit_qty: Decimal,
         max_order_qty: Decimal,
         min_ticker_qty_24h: Decimal,
     ) -> anyhow::Result<()> {
+        if self
+            .min_profit_percent
+            .is_some_and(|percent| percent <= Decimal::ZERO)
+        {
+            bail!(
+                "min_profit_percent of {} must be greater than 0",
+                self.asset
+            );
+        }
+        if self
+            .session_profit_target
+            .is_some_and(|target| target <= Decimal::ZERO)
+        {
+            bail!(
+                "session_profit_target of {} must be greater than 0",
+                self.asset
+            );
+        }
+        if self
+            .max_daily_loss
+            .is_some_and(|loss| loss <= Decimal::ZERO)
+        {
+            bail!("max_daily_loss of {} must be greater than 0", self.asset);
+        }
         match &self.symbol {
             Some(symbol) => {
                 if !symbol.contains("USDT") {
@@ -80,3 +294,38 @@ impl Asset {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(asset: &str, symbol: Option<&str>) -> Asset {
+        Asset {
+            asset: asset.to_owned(),
+            symbol: symbol.map(ToOwned::to_owned),
+            min_profit_qty: Decimal::ZERO,
+            min_profit_percent: None,
+            max_order_qty: Decimal::ZERO,
+            min_ticker_qty_24h: Decimal::ZERO,
+            session_profit_target: None,
+            max_daily_loss: None,
+        }
+    }
+
+    #[test]
+    fn test_duplicate_base_assets_are_found() {
+        let assets = [
+            asset("USDT", None),
+            asset("BTC", Some("BTC-USDT")),
+            asset("ETH", Some("ETH-USDT")),
+        ];
+        assert_eq!(Asset::find_duplicate(&assets), None);
+
+        let assets = [
+            asset("BTC", Some("BTC-USDT")),
+            asset("USDT", None),
+            asset("BTC", None),
+        ];
+        assert_eq!(Asset::find_duplicate(&assets), Some("BTC"));
+    }
+}
@@ -0,0 +1,22 @@
+use crate::libs::kucoin_client::{
+    Client,
+    api::{Api, Spot},
+    models::{AccountBalance, RestResponse},
+};
+
+/// Wrapper struct for account-related KuCoin API operations.
+#[derive(Clone)]
+pub struct Account {
+    pub client: Client,
+}
+
+impl Account {
+    /// Retrieves the balances of all trading (spot) accounts.
+    pub async fn get_trade_accounts(&self) -> anyhow::Result<RestResponse<Vec<AccountBalance>>> {
+        let params = vec![("type", "trade")];
+
+        self.client
+            .get(Api::Spot(Spot::GetAccounts), Some(&params), true)
+            .await
+    }
+}
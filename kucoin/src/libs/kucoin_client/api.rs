@@ -1,4 +1,6 @@
-use crate::libs::kucoin_client::{BaseInfo, ClientConfig, Market, client::Client};
+use std::borrow::Cow;
+
+use crate::libs::kucoin_client::{Account, BaseInfo, ClientConfig, Market, Trade, client::Client};
 
 pub enum Api {
     Spot(Spot),
@@ -9,17 +11,26 @@ pub enum Spot {
     GetAllTickers,
     GetBulletPublic,
     GetBulletPrivate,
+    GetAccounts,
+    GetServerTime,
+    /// HF order details by client order id.
+    GetOrderByClientOid(String),
 }
 
 impl Api {
     #[must_use]
-    pub fn as_str(&self) -> &'static str {
+    pub fn path(&self) -> Cow<'static, str> {
         match self {
             Self::Spot(route) => match route {
-                Spot::GetAllSymbols => "/api/v2/symbols",
-                Spot::GetAllTickers => "/api/v1/market/allTickers",
-                Spot::GetBulletPublic => "/api/v1/bullet-public",
-                Spot::GetBulletPrivate => "/api/v1/bullet-private",
+                Spot::GetAllSymbols => "/api/v2/symbols".into(),
+                Spot::GetAllTickers => "/api/v1/market/allTickers".into(),
+                Spot::GetBulletPublic => "/api/v1/bullet-public".into(),
+                Spot::GetBulletPrivate => "/api/v1/bullet-private".into(),
+                Spot::GetAccounts => "/api/v1/accounts".into(),
+                Spot::GetServerTime => "/api/v1/timestamp".into(),
+                Spot::GetOrderByClientOid(client_oid) => {
+                    format!("/api/v1/hf/orders/client-order/{client_oid}").into()
+                }
             },
         }
     }
@@ -27,7 +38,7 @@ impl Api {
 
 impl From<Api> for String {
     fn from(item: Api) -> Self {
-        item.as_str().to_owned()
+        item.path().into_owned()
     }
 }
 
@@ -52,3 +63,19 @@ impl Kucoin for BaseInfo {
         })
     }
 }
+
+impl Kucoin for Trade {
+    fn new(cfg: ClientConfig) -> anyhow::Result<Self> {
+        Ok(Self {
+            client: Client::from_config(cfg)?,
+        })
+    }
+}
+
+impl Kucoin for Account {
+    fn new(cfg: ClientConfig) -> anyhow::Result<Self> {
+        Ok(Self {
+            client: Client::from_config(cfg)?,
+        })
+    }
+}
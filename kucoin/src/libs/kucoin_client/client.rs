@@ -228,9 +228,9 @@ impl Client {
         path: &Api,
         query: Option<&Vec<(&str, &str)>>,
     ) -> anyhow::Result<(String, String)> {
-        let path_str = path.as_str();
+        let path_str = path.path();
         let mut full_url = format!("{}{path_str}", self.host);
-        let mut raw_url = path_str.to_owned();
+        let mut raw_url = path_str.into_owned();
 
         if let Some(v) = query {
             let encoded = serde_urlencoded::to_string(v)?;
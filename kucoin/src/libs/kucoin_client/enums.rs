@@ -1,5 +1,6 @@
 use std::fmt::Display;
 
+use engine::enums;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -100,6 +101,24 @@ impl Display for OrderType {
     }
 }
 
+impl From<enums::OrderType> for OrderType {
+    fn from(order_type: enums::OrderType) -> Self {
+        match order_type {
+            enums::OrderType::Market => Self::Market,
+            enums::OrderType::Limit => Self::Limit,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TimeInForce {
+    Gtc,
+    Gtt,
+    Ioc,
+    Fok,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub enum OrderStatus {
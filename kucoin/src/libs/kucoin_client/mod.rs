@@ -1,3 +1,4 @@
+pub mod account;
 pub mod api;
 pub mod client;
 pub mod enums;
@@ -5,10 +6,13 @@ pub mod market;
 pub mod models;
 pub mod stream;
 mod token;
+pub mod trade;
 mod utils;
 pub mod ws;
 
+pub use account::Account;
 pub use api::Kucoin;
 pub use client::{Client, ClientConfig, HttpConfig};
 pub use market::Market;
 pub use token::BaseInfo;
+pub use trade::Trade;
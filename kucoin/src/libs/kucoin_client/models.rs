@@ -109,3 +109,34 @@ pub struct Ticker {
     #[serde(with = "rust_decimal::serde::float")]
     pub maker_coefficient: Decimal,
 }
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderDetails {
+    pub id: String,
+    pub client_oid: String,
+    pub symbol: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub size: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub deal_size: Decimal,
+    /// The order is still open in the order book.
+    pub active: bool,
+    /// Part of the order was cancelled.
+    pub cancel_exist: bool,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountBalance {
+    pub id: String,
+    pub currency: String,
+    #[serde(rename = "type")]
+    pub account_type: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub balance: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub available: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub holds: Decimal,
+}
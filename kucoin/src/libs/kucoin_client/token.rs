@@ -4,7 +4,7 @@ use crate::libs::kucoin_client::{
     models::{RestResponse, Token},
 };
 
-/// Wrapper struct for basic KuCoin API operations focused on token retrieval.
+/// Wrapper struct for basic KuCoin API operations (tokens, server time).
 #[derive(Clone)]
 pub struct BaseInfo {
     pub client: Client,
@@ -24,4 +24,11 @@ impl BaseInfo {
             .post(Api::Spot(Spot::GetBulletPrivate), None, None, true)
             .await
     }
+
+    /// Retrieves the current server time in milliseconds.
+    pub async fn get_server_time(&self) -> anyhow::Result<RestResponse<u64>> {
+        self.client
+            .get(Api::Spot(Spot::GetServerTime), None, false)
+            .await
+    }
 }
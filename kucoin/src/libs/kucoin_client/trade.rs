@@ -0,0 +1,30 @@
+use crate::libs::kucoin_client::{
+    Client,
+    api::{Api, Spot},
+    models::{OrderDetails, RestResponse},
+};
+
+/// Wrapper struct for trade-related KuCoin API operations.
+#[derive(Clone)]
+pub struct Trade {
+    pub client: Client,
+}
+
+impl Trade {
+    /// Retrieves the details of an HF order by its client order id.
+    pub async fn get_order_by_client_oid(
+        &self,
+        symbol: &str,
+        client_oid: &str,
+    ) -> anyhow::Result<RestResponse<OrderDetails>> {
+        let params = vec![("symbol", symbol)];
+
+        self.client
+            .get(
+                Api::Spot(Spot::GetOrderByClientOid(client_oid.to_owned())),
+                Some(&params),
+                true,
+            )
+            .await
+    }
+}
@@ -47,7 +47,7 @@ use url::Url;
 use uuid::Uuid;
 
 use crate::libs::kucoin_client::{
-    enums::{OrderSide, OrderType},
+    enums::{OrderSide, OrderType, TimeInForce},
     stream::{Reader, Writer, ping_loop},
     utils,
     utils::sign,
@@ -498,6 +498,8 @@ pub struct AddOrderRequest {
     pub order_type: OrderType,
     #[serde(rename = "side")]
     pub order_side: OrderSide,
+    pub price: Option<String>,
+    pub time_in_force: Option<TimeInForce>,
     pub size: Option<String>,
     pub funds: Option<String>,
 }
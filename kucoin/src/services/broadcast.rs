@@ -1,6 +1,7 @@
 use std::sync::{Arc, LazyLock};
 
 use dashmap::DashMap;
+use futures_util::future;
 use tokio::sync::watch;
 
 use crate::services::storage::BookTickerEventChanges;
@@ -47,3 +48,14 @@ impl TickerBroadcast {
         tx.subscribe()
     }
 }
+
+/// Waits until any of the receivers sees a change and returns its index.
+///
+/// # Panics
+///
+/// Panics if `receivers` is empty.
+pub async fn next_changed(receivers: &mut [watch::Receiver<BookTickerEventChanges>]) -> usize {
+    let changes = receivers.iter_mut().map(|rx| Box::pin(rx.changed()));
+    let (_, idx, _) = future::select_all(changes).await;
+    idx
+}
@@ -10,11 +10,12 @@ use std::{
 };
 
 use anyhow::bail;
-use engine::enums::SymbolOrder;
+use engine::{MonitoredSymbol, enums::SymbolOrder};
 use rust_decimal::{Decimal, prelude::Zero};
+use serde::Deserialize;
 use strum::IntoEnumIterator;
 use tokio::task::JoinSet;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use crate::{
     config::Asset,
@@ -24,6 +25,17 @@ use crate::{
     },
 };
 
+/// Handling of chains with a symbol whose base, quote or price increment is zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InvalidIncrements {
+    /// Drop the chain at startup.
+    #[default]
+    Skip,
+    /// Keep the chain and log the symbols.
+    Warn,
+}
+
 /// Wrapper for a trading symbol with directional order (Asc for base/quote, Desc for reversed
 /// quote/base).
 #[derive(Clone, Debug)]
@@ -32,6 +44,17 @@ pub struct ChainSymbol {
     pub order: SymbolOrder,
 }
 
+impl From<&ChainSymbol> for MonitoredSymbol {
+    fn from(chain_symbol: &ChainSymbol) -> Self {
+        Self {
+            symbol: chain_symbol.symbol.symbol.clone(),
+            base_asset: chain_symbol.symbol.base_currency.clone(),
+            quote_asset: chain_symbol.symbol.quote_currency.clone(),
+            order: chain_symbol.order,
+        }
+    }
+}
+
 impl ChainSymbol {
     #[must_use]
     pub fn new(symbol: Symbol, order: SymbolOrder) -> Self {
@@ -44,14 +67,20 @@ impl ChainSymbol {
 pub struct ChainBuilder {
     market_api: Market,
     skip_assets: Vec<String>,
+    invalid_increments: InvalidIncrements,
 }
 
 impl ChainBuilder {
     #[must_use]
-    pub fn new(market_api: Market, skip_assets: Vec<String>) -> Self {
+    pub fn new(
+        market_api: Market,
+        skip_assets: Vec<String>,
+        invalid_increments: InvalidIncrements,
+    ) -> Self {
         Self {
             market_api,
             skip_assets,
+            invalid_increments,
         }
     }
 
@@ -64,6 +93,7 @@ impl ChainBuilder {
             Ok(response) => response,
             Err(e) => bail!(e),
         };
+        Self::check_asset_symbols(&symbols_response.data, &base_assets);
 
         let mut chains: Vec<_> = vec![];
         let mut tasks_set = JoinSet::new();
@@ -88,6 +118,7 @@ impl ChainBuilder {
         }
 
         let unique_chains = Self::deduplicate_chains(&chains);
+        let unique_chains = Self::check_increments(unique_chains, self.invalid_increments);
         let filter_chains = self
             .filter_chains_by_24h_vol(&base_assets, unique_chains)
             .await?;
@@ -96,6 +127,9 @@ impl ChainBuilder {
             count = filter_chains.len(),
             "🚀 [Engine] Chains built successfully"
         );
+        for (base_asset, count) in count_chains_by_base_asset(&filter_chains) {
+            info!(base_asset, count, "🚀 [Engine] Chains built for base asset");
+        }
 
         Ok(filter_chains)
     }
@@ -369,6 +403,82 @@ impl ChainBuilder {
     /// # Returns
     /// A new `Vec<Symbol>` containing the filtered symbols. The original slices are
     /// unchanged.
+    /// Checks that every chain symbol has non-zero base, quote and price increments, as
+    /// orders are sized and priced to their scale. Chains with a degenerate symbol are
+    /// dropped or kept depending on `mode`, each such symbol is logged once.
+    fn check_increments(
+        chains: Vec<[ChainSymbol; 3]>,
+        mode: InvalidIncrements,
+    ) -> Vec<[ChainSymbol; 3]> {
+        let mut invalid = BTreeMap::new();
+        let chains: Vec<_> = chains
+            .into_iter()
+            .filter(|chain| {
+                let mut valid = true;
+                for chain_symbol in chain {
+                    let symbol = &chain_symbol.symbol;
+                    if !Self::has_valid_increments(symbol) {
+                        let increments = [
+                            symbol.base_increment,
+                            symbol.quote_increment,
+                            symbol.price_increment,
+                        ];
+                        invalid
+                            .entry(symbol.symbol.clone())
+                            .or_insert((increments, 0))
+                            .1 += 1;
+                        valid = false;
+                    }
+                }
+                valid || mode == InvalidIncrements::Warn
+            })
+            .collect();
+
+        for (symbol, ([base_increment, quote_increment, price_increment], chains_count)) in invalid
+        {
+            warn!(
+                symbol,
+                %base_increment,
+                %quote_increment,
+                %price_increment,
+                chains_count,
+                mode = ?mode,
+                "⚠️ [Engine] Symbol has a zero increment"
+            );
+        }
+        chains
+    }
+
+    fn has_valid_increments(symbol: &Symbol) -> bool {
+        [
+            symbol.base_increment,
+            symbol.quote_increment,
+            symbol.price_increment,
+        ]
+        .iter()
+        .all(|increment| *increment > Decimal::ZERO)
+    }
+
+    /// Warns about the base assets whose `symbol` is not listed on the exchange, their limits
+    /// cannot be converted at its price. Returns those symbols.
+    fn check_asset_symbols<'a>(symbols: &[Symbol], base_assets: &'a [Asset]) -> Vec<&'a str> {
+        let listed: HashSet<&str> = symbols.iter().map(|s| s.symbol.as_str()).collect();
+        base_assets
+            .iter()
+            .filter_map(|asset| {
+                let symbol = asset.symbol.as_deref()?;
+                if listed.contains(symbol) {
+                    return None;
+                }
+                warn!(
+                    asset = %asset.asset,
+                    symbol, "Base asset symbol is not listed on the exchange"
+                );
+                Some(symbol)
+            })
+            .collect()
+    }
+
     #[must_use]
     pub fn sort_symbols(symbols: &[Symbol], skip_assets: &[String]) -> Vec<Symbol> {
         let skip_set: HashSet<&str> = skip_assets.iter().map(|s| s.as_str()).collect();
@@ -390,3 +500,137 @@ pub fn extract_chain_symbols(chain_symbols: &[ChainSymbol]) -> Vec<&str> {
         .map(|v| v.symbol.symbol.as_str())
         .collect()
 }
+
+/// Counts the chains starting from each base asset.
+#[must_use]
+pub fn count_chains_by_base_asset<const N: usize>(
+    chains: &[[ChainSymbol; N]],
+) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for chain in chains {
+        if let Some(first) = chain.first() {
+            *counts
+                .entry(ChainBuilder::find_base_asset(first))
+                .or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::libs::kucoin_client::enums::MarketType;
+
+    fn chain_symbol(name: &str, increment: Decimal) -> ChainSymbol {
+        let (base, quote) = name.split_once('-').unwrap();
+        let symbol = Symbol {
+            symbol: name.to_owned(),
+            name: name.to_owned(),
+            base_currency: base.to_owned(),
+            quote_currency: quote.to_owned(),
+            fee_currency: quote.to_owned(),
+            market: MarketType::Usds,
+            base_min_size: Decimal::ZERO,
+            quote_min_size: Decimal::ZERO,
+            base_max_size: Decimal::MAX,
+            quote_max_size: Decimal::MAX,
+            base_increment: Decimal::new(1, 8),
+            quote_increment: Decimal::new(1, 8),
+            price_increment: increment,
+            price_limit_rate: Decimal::ZERO,
+            min_funds: None,
+            is_margin_enabled: false,
+            enable_trading: true,
+            fee_category: 1,
+            maker_fee_coefficient: Decimal::ONE,
+            taker_fee_coefficient: Decimal::ONE,
+            st: false,
+            callauction_is_enabled: false,
+        };
+        ChainSymbol::new(symbol, SymbolOrder::Asc)
+    }
+
+    fn chains() -> Vec<[ChainSymbol; 3]> {
+        let valid = Decimal::new(1, 8);
+        vec![
+            [
+                chain_symbol("BTC-USDT", valid),
+                chain_symbol("ETH-BTC", valid),
+                chain_symbol("ETH-USDT", valid),
+            ],
+            [
+                chain_symbol("BTC-USDT", valid),
+                // Only the middle symbol has a zero price increment.
+                chain_symbol("XRP-BTC", Decimal::ZERO),
+                chain_symbol("XRP-USDT", valid),
+            ],
+        ]
+    }
+
+    #[test]
+    fn test_symbol_with_zero_increment_is_flagged() {
+        let symbol = |chain: &[ChainSymbol]| chain[1].symbol.symbol.clone();
+
+        let skipped = ChainBuilder::check_increments(chains(), InvalidIncrements::Skip);
+        assert_eq!(
+            skipped.iter().map(|c| symbol(c)).collect::<Vec<_>>(),
+            ["ETH-BTC"]
+        );
+
+        let warned = ChainBuilder::check_increments(chains(), InvalidIncrements::Warn);
+        assert_eq!(
+            warned.iter().map(|c| symbol(c)).collect::<Vec<_>>(),
+            ["ETH-BTC", "XRP-BTC"]
+        );
+        assert!(!ChainBuilder::has_valid_increments(&warned[1][1].symbol));
+    }
+
+    #[test]
+    fn test_asset_with_unlisted_symbol_is_reported() {
+        let valid = Decimal::new(1, 8);
+        let symbols = [
+            chain_symbol("BTC-USDT", valid).symbol,
+            chain_symbol("ETH-BTC", valid).symbol,
+        ];
+        let asset = |asset: &str, symbol: Option<&str>| Asset {
+            asset: asset.to_owned(),
+            symbol: symbol.map(ToOwned::to_owned),
+            min_profit_qty: Decimal::ZERO,
+            min_profit_percent: None,
+            max_order_qty: Decimal::ZERO,
+            min_ticker_qty_24h: Decimal::ZERO,
+            session_profit_target: None,
+            max_daily_loss: None,
+        };
+        let base_assets = [
+            asset("USDT", None),
+            asset("BTC", Some("BTC-USDT")),
+            asset("ETH", Some("ETH-USDC")),
+        ];
+
+        assert_eq!(
+            ChainBuilder::check_asset_symbols(&symbols, &base_assets),
+            ["ETH-USDC"]
+        );
+    }
+
+    #[test]
+    fn test_chains_are_counted_per_base_asset() {
+        let valid = Decimal::new(1, 8);
+        // USDT -> ETH -> BTC -> USDT, next to the chains starting from BTC.
+        let mut usdt_chain = [
+            chain_symbol("ETH-USDT", valid),
+            chain_symbol("ETH-BTC", valid),
+            chain_symbol("BTC-USDT", valid),
+        ];
+        usdt_chain[0].order = SymbolOrder::Desc;
+        let mut chains = chains();
+        chains.push(usdt_chain);
+
+        assert_eq!(
+            count_chains_by_base_asset(&chains),
+            BTreeMap::from([("BTC".to_owned(), 2), ("USDT".to_owned(), 1)])
+        );
+    }
+}
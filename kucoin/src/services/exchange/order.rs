@@ -7,25 +7,32 @@
 //! limits across the chain. Supports Asc/Desc symbol orders with lot/tick filters from exchange
 //! info.
 
-use std::{ops::Sub, sync::Arc};
-
-use engine::{ChainOrder, ChainOrders, METRICS, ORDERS_CHANNEL, enums::SymbolOrder};
-use itertools::Itertools;
+use std::{collections::BTreeMap, num::NonZeroU64, ops::Sub, sync::Arc};
+
+use anyhow::{Context, bail};
+use engine::{
+    BalanceCap, ChainOrder, ChainOrders, ComputePool, DisabledSymbols, FeeSchedule, METRICS,
+    MinNotional, ORDERS_CHANNEL, ProcessedChainCounter, ProfitSchedule, TimeOfDay,
+    UpdateLatencySampler, UsdProfitFloor, check_chain_cycle, check_chain_length,
+    enums::{OrderType, SymbolOrder},
+};
 use rust_decimal::{
     Decimal,
     prelude::{FromPrimitive, Zero},
 };
-use tokio::task::JoinSet;
+use serde::Deserialize;
+use tokio::{task::JoinSet, time::Instant};
 use tokio_util::sync::CancellationToken;
 use tools::misc;
-use tracing::error;
+use tracing::{debug, error, warn};
 use uuid::Uuid;
 
 use crate::{
     config::Asset,
     services::{
-        broadcast::TICKER_BROADCAST,
+        broadcast::{TICKER_BROADCAST, next_changed},
         exchange::{chain, chain::ChainSymbol},
+        storage,
         storage::{BookTickerEvent, BookTickerEventChanges, BookTickerStore},
     },
 };
@@ -35,6 +42,8 @@ use crate::{
 pub struct OrderSymbol<'a> {
     pub symbol: String,
     pub symbol_order: SymbolOrder,
+    /// Order type used for this leg.
+    pub order_type: OrderType,
     pub order_book: &'a BookTickerEvent,
     pub base_min_size: Decimal,
     pub quote_min_size: Decimal,
@@ -44,6 +53,8 @@ pub struct OrderSymbol<'a> {
     pub quote_increment: Decimal,
     pub price_increment: Decimal,
     pub min_profit_qty: Option<Decimal>,
+    /// Minimum profit as a percentage of the quantity spent by the first leg, set on it.
+    pub min_profit_percent: Option<Decimal>,
     pub max_order_qty: Option<Decimal>,
 }
 
@@ -52,6 +63,7 @@ pub struct OrderSymbol<'a> {
 pub struct PreOrder {
     symbol: String,
     symbol_order: SymbolOrder,
+    order_type: OrderType,
     price: Decimal,
     base_qty: Decimal,
     quote_qty: Decimal,
@@ -69,31 +81,205 @@ pub struct OrderBookUnit {
     pub qty: Decimal,
 }
 
+/// Symbol a base asset is priced in USD from.
+#[derive(Clone, Debug, PartialEq)]
+struct UsdPriceSymbol {
+    symbol: String,
+    asset: String,
+    /// The USD asset is the base of the symbol, so its price is inverted.
+    inverted: bool,
+}
+
 /// Builder for processing arbitrage chains and generating profitable orders.
+/// Handling of a price that is not a multiple of the symbol's `price_increment` once truncated
+/// to its scale.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OffGridPrice {
+    /// Move the price onto the grid, against the chain: down for Asc (sell) legs, up for Desc
+    /// (buy) legs.
+    #[default]
+    Align,
+    /// Skip the chain at this depth.
+    Skip,
+}
+
+/// Currency the trading fee is paid in, as set in the account's fee settings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeeCurrency {
+    /// Deducted from the funds of every leg in its quote currency.
+    #[default]
+    Quote,
+    /// Paid from the KCS balance at a discount.
+    Kcs,
+}
+
+/// How the fees of a chain reduce its profit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FeeDeduction {
+    /// Every leg loses its fee from the funds it passes on, so the fees compound along the
+    /// chain and come out of what the last leg returns.
+    Quote,
+    /// Fees are paid in KCS at `discount_percent` off, the legs keep their funds whole and
+    /// the fees are charged on the chain volume.
+    Kcs { discount_percent: Decimal },
+}
+
+impl FeeDeduction {
+    #[must_use]
+    pub fn new(currency: FeeCurrency, kcs_discount_percent: Decimal) -> Self {
+        match currency {
+            FeeCurrency::Quote => Self::Quote,
+            FeeCurrency::Kcs => Self::Kcs {
+                discount_percent: kcs_discount_percent,
+            },
+        }
+    }
+}
+
 pub struct OrderBuilder {
     market_depth_limit: usize,
-    fee_percent: Decimal,
+    fees: FeeSchedule,
+    fee_deduction: FeeDeduction,
+    min_notional: MinNotional,
+    usd_profit_floor: Option<&'static UsdProfitFloor>,
+    compute_pool: Option<Arc<ComputePool>>,
+    balance_cap: Option<Arc<BalanceCap>>,
+    profit_schedule: ProfitSchedule,
+    off_grid_price: OffGridPrice,
+    verify_chain_cycle: bool,
+    order_type: OrderType,
+    allow_crossed_books: bool,
+    /// Symbols whose chains are paused at runtime.
+    disabled_symbols: Arc<DisabledSymbols>,
+    update_latency: UpdateLatencySampler,
 }
 
 impl OrderBuilder {
     #[must_use]
-    pub fn new(fee_percent: Decimal) -> Self {
+    pub fn new(
+        fee_percent: Decimal,
+        profit_schedule: ProfitSchedule,
+        off_grid_price: OffGridPrice,
+        verify_chain_cycle: bool,
+        order_type: OrderType,
+    ) -> Self {
         Self {
             market_depth_limit: 1, // always 1
-            fee_percent,
+            fees: FeeSchedule::flat(fee_percent),
+            fee_deduction: FeeDeduction::Quote,
+            min_notional: MinNotional::default(),
+            usd_profit_floor: None,
+            compute_pool: None,
+            balance_cap: None,
+            profit_schedule,
+            off_grid_price,
+            verify_chain_cycle,
+            order_type,
+            allow_crossed_books: false,
+            disabled_symbols: Arc::default(),
+            update_latency: UpdateLatencySampler::default(),
         }
     }
 
-    /// Builds and monitors order processing tasks for the given chains.
-    pub async fn build_chains_orders(
+    /// Charges the chain legs by `fees` instead of the flat fee.
+    #[must_use]
+    pub fn with_fee_schedule(mut self, fees: FeeSchedule) -> Self {
+        self.fees = fees;
+        self
+    }
+
+    /// Charges the chain fees the way the account pays them.
+    #[must_use]
+    pub fn with_fee_deduction(mut self, fee_deduction: FeeDeduction) -> Self {
+        self.fee_deduction = fee_deduction;
+        self
+    }
+
+    /// Raises the `quoteMinSize` of the symbols configured in `min_notional`.
+    #[must_use]
+    pub fn with_min_notional(mut self, min_notional: MinNotional) -> Self {
+        self.min_notional = min_notional;
+        self
+    }
+
+    /// Holds every chain to the USD `floor` instead of the minimum profit of its base asset.
+    #[must_use]
+    pub fn with_usd_profit_floor(mut self, floor: Option<&'static UsdProfitFloor>) -> Self {
+        self.usd_profit_floor = floor;
+        self
+    }
+
+    /// Calculates chain profits on `pool` instead of the async runtime threads.
+    #[must_use]
+    pub fn with_compute_pool(mut self, pool: Option<Arc<ComputePool>>) -> Self {
+        self.compute_pool = pool;
+        self
+    }
+
+    /// Caps the `max_order_qty` of the base assets at their free balance in `cap`.
+    #[must_use]
+    pub fn with_balance_cap(mut self, cap: Option<Arc<BalanceCap>>) -> Self {
+        self.balance_cap = cap;
+        self
+    }
+
+    /// Evaluates chains on crossed books instead of skipping them until they uncross.
+    #[must_use]
+    pub fn with_crossed_books_allowed(mut self, allow_crossed_books: bool) -> Self {
+        self.allow_crossed_books = allow_crossed_books;
+        self
+    }
+
+    /// Pauses the chains containing a symbol of `disabled_symbols` for as long as it is there.
+    #[must_use]
+    pub fn with_disabled_symbols(mut self, disabled_symbols: Arc<DisabledSymbols>) -> Self {
+        self.disabled_symbols = disabled_symbols;
+        self
+    }
+
+    /// Times the processing of one book ticker update out of every `sample_every`, from
+    /// storing it to queuing the chain, including the wait for the compute pool.
+    #[must_use]
+    pub fn with_update_latency_sampling(mut self, sample_every: Option<NonZeroU64>) -> Self {
+        self.update_latency = UpdateLatencySampler::new(sample_every);
+        self
+    }
+
+    /// Builds and monitors order processing tasks for the given chains of `N` symbols.
+    pub async fn build_chains_orders<const N: usize>(
         self: Arc<Self>,
         token: CancellationToken,
-        chains: Vec<[ChainSymbol; 3]>,
+        chains: Vec<[ChainSymbol; N]>,
         base_assets: Vec<Asset>,
     ) -> anyhow::Result<()> {
         let mut tasks_set: JoinSet<anyhow::Result<()>> = JoinSet::new();
 
+        if let Some(floor) = self.usd_profit_floor {
+            let price_symbols = usd_price_symbols(&chains, &base_assets, floor.usd_asset());
+            if !price_symbols.is_empty() {
+                tasks_set.spawn({
+                    let token = token.clone();
+                    async move {
+                        track_usd_prices(token, floor, price_symbols).await;
+                        Ok(())
+                    }
+                });
+            }
+        }
+
         for chain in chains.iter() {
+            // A malformed chain is left out rather than failing the others.
+            if let Err(e) = check_chain_length(chain.len()) {
+                error!(
+                    chain = ?chain::extract_chain_symbols(chain),
+                    error = ?e,
+                    "Invalid chain skipped"
+                );
+                continue;
+            }
+
             tasks_set.spawn({
                 let this = self.clone();
                 let chain = chain.clone();
@@ -101,21 +287,23 @@ impl OrderBuilder {
                 let token = token.clone();
 
                 async move {
-                    let (mut rx1, mut rx2, mut rx3) = chain
+                    let mut receivers: Vec<_> = chain
                         .iter()
                         .map(|s| TICKER_BROADCAST.subscribe(s.symbol.symbol.as_str()))
-                        .collect_tuple()
-                        .expect("Invalid chain length");
+                        .collect();
 
                     let mut bid_storage = BookTickerStore::new();
                     let mut ask_storage = BookTickerStore::new();
                     let mut last_prices: Vec<Decimal> = vec![];
+                    let base_asset = find_base_asset(&base_assets, &chain[0]).map(|base| base.asset);
+                    let processed_chains = METRICS.processed_chain_counter(
+                        &chain::extract_chain_symbols(&chain),
+                        base_asset.as_deref(),
+                    );
 
                     // Read initial values from watch channel
-                    {
-                        _ = rx1.borrow().clone();
-                        _ = rx2.borrow().clone();
-                        _ = rx3.borrow().clone();
+                    for rx in &receivers {
+                        _ = rx.borrow().clone();
                     }
 
                     loop {
@@ -124,19 +312,21 @@ impl OrderBuilder {
                                 break;
                             },
 
-                            _ = rx1.changed() => {
-                                let msg = rx1.borrow().clone();
-                                this.handle_ticker_event(&mut bid_storage, &mut ask_storage, &chain, msg, &mut last_prices, &base_assets);
-                            },
-
-                            _ = rx2.changed() => {
-                                let msg = rx2.borrow().clone();
-                                this.handle_ticker_event(&mut bid_storage, &mut ask_storage, &chain, msg, &mut last_prices, &base_assets);
-                            },
-
-                            _ = rx3.changed() => {
-                                let msg = rx3.borrow().clone();
-                                this.handle_ticker_event(&mut bid_storage, &mut ask_storage, &chain, msg, &mut last_prices, &base_assets);
+                            idx = next_changed(&mut receivers) => {
+                                let msg = receivers[idx].borrow().clone();
+                                let Some(pool) = &this.compute_pool else {
+                                    this.handle_ticker_event(&mut bid_storage, &mut ask_storage, &chain, msg, &mut last_prices, &base_assets, &processed_chains);
+                                    continue;
+                                };
+                                let started = this.update_latency.start();
+                                if let Some((messages, received_at)) = Self::chain_books(&mut bid_storage, &mut ask_storage, &chain, msg, &mut last_prices, &this.disabled_symbols, this.allow_crossed_books) {
+                                    let (this, chain, base_assets, processed_chains) = (this.clone(), chain.clone(), base_assets.clone(), processed_chains.clone());
+                                    let evaluate = move || this.evaluate_chain(&chain, &messages, &base_assets, &processed_chains, received_at);
+                                    if let Err(e) = pool.run(evaluate).await {
+                                        error!(error = ?e, "Error during process arbitrage");
+                                    }
+                                }
+                                this.update_latency.finish(started);
                             },
                         }
                     }
@@ -165,17 +355,50 @@ impl OrderBuilder {
     }
 
     /// Handles a ticker event update for a chain.
+    #[allow(clippy::too_many_arguments)]
     pub fn handle_ticker_event(
         &self,
         bid_storage: &mut BookTickerStore,
         ask_storage: &mut BookTickerStore,
-        chain: &[ChainSymbol; 3],
+        chain: &[ChainSymbol],
         msg: BookTickerEventChanges,
         last_prices: &mut Vec<Decimal>,
         base_assets: &[Asset],
+        processed_chains: &ProcessedChainCounter,
     ) {
-        if !bid_storage.update_if_valid(msg.bid) && !ask_storage.update_if_valid(msg.ask) {
-            return;
+        let started = self.update_latency.start();
+        if let Some((messages, received_at)) = Self::chain_books(
+            bid_storage,
+            ask_storage,
+            chain,
+            msg,
+            last_prices,
+            &self.disabled_symbols,
+            self.allow_crossed_books,
+        ) {
+            self.evaluate_chain(chain, &messages, base_assets, processed_chains, received_at);
+        }
+        self.update_latency.finish(started);
+    }
+
+    /// Stores `msg` and returns the books of the chain legs with the time `msg` was received,
+    /// or `None` if neither side of `msg` is newer, a book is missing, a book is crossed and
+    /// `allow_crossed_books` is unset, a symbol is among `disabled_symbols`, or the chain
+    /// prices have not changed.
+    fn chain_books(
+        bid_storage: &mut BookTickerStore,
+        ask_storage: &mut BookTickerStore,
+        chain: &[ChainSymbol],
+        msg: BookTickerEventChanges,
+        last_prices: &mut Vec<Decimal>,
+        disabled_symbols: &DisabledSymbols,
+        allow_crossed_books: bool,
+    ) -> Option<(Vec<BookTickerEvent>, Option<Instant>)> {
+        let received_at = msg.received_at;
+        let bid_updated = bid_storage.update_if_valid(msg.bid);
+        let ask_updated = ask_storage.update_if_valid(msg.ask);
+        if !bid_updated && !ask_updated {
+            return None;
         }
 
         // Early return if not all data is available
@@ -188,7 +411,31 @@ impl OrderBuilder {
             .collect();
 
         if messages.len() != chain.len() {
-            return;
+            return None;
+        }
+
+        // Skip until every book uncrosses, the prices are left as they were so the chain is
+        // evaluated again once it does.
+        if !allow_crossed_books
+            && let Some(crossed) = chain
+                .iter()
+                .find(|symbol| storage::is_crossed(bid_storage, ask_storage, &symbol.symbol.symbol))
+        {
+            debug!(
+                chain = ?chain::extract_chain_symbols(chain),
+                symbol = crossed.symbol.symbol,
+                "Crossed book, chain skipped until it uncrosses"
+            );
+            return None;
+        }
+
+        // Paused while a symbol is disabled, the prices are left as they were for the same
+        // reason.
+        if disabled_symbols
+            .find(chain::extract_chain_symbols(chain))
+            .is_some()
+        {
+            return None;
         }
 
         // Calculate prices
@@ -196,42 +443,102 @@ impl OrderBuilder {
 
         // Skip if prices haven't changed
         if *last_prices == prices {
-            return;
+            return None;
         }
 
         *last_prices = prices;
+        Some((messages, received_at))
+    }
 
-        // Process the chain
-        if let Err(e) = Self::process_chain(
-            base_assets,
-            chain,
-            &messages,
-            self.market_depth_limit,
-            self.fee_percent,
-        ) {
-            error!(error = ?e, "Error during process arbitrage");
+    /// Processes the chain on the books of its legs.
+    fn evaluate_chain(
+        &self,
+        chain: &[ChainSymbol],
+        messages: &[BookTickerEvent],
+        base_assets: &[Asset],
+        processed_chains: &ProcessedChainCounter,
+        received_at: Option<Instant>,
+    ) {
+        match self.process_chain(base_assets, chain, messages, received_at) {
+            Ok(()) => processed_chains.increment(1),
+            Err(e) => error!(error = ?e, "Error during process arbitrage"),
         }
     }
 
-    /// Builds orders for the chain and calculates profit.
+    /// Builds orders for the chain with the builder settings and calculates profit.
+    ///
+    /// Chains shorter than [`engine::MIN_CHAIN_LEGS`] or without a book per leg are refused.
+    /// With `verify_chain_cycle` a chain that does not return to its starting asset is
+    /// refused before any order is built. With a `usd_profit_floor` a chain is skipped until
+    /// the USD price of its base asset is known. `received_at` is when the ticker event the
+    /// chain is processed on was received.
     pub fn process_chain(
+        &self,
         base_assets: &[Asset],
-        chain: &[ChainSymbol; 3],
+        chain: &[ChainSymbol],
         order_book: &[BookTickerEvent],
-        market_depth_limit: usize,
-        fee_percent: Decimal,
+        received_at: Option<Instant>,
     ) -> anyhow::Result<()> {
+        let min_profit_scale = self.profit_schedule.min_profit_scale(TimeOfDay::now());
+
+        check_chain_length(chain.len())
+            .with_context(|| format!("Invalid chain {:?}", chain::extract_chain_symbols(chain)))?;
+        if order_book.len() != chain.len() {
+            bail!(
+                "Chain {:?} has {} books for {} legs",
+                chain::extract_chain_symbols(chain),
+                order_book.len(),
+                chain.len()
+            );
+        }
+
+        if self.verify_chain_cycle {
+            check_chain_cycle(chain.iter().map(|chain_symbol| {
+                let symbol = &chain_symbol.symbol;
+                (
+                    symbol.symbol.as_str(),
+                    symbol.base_currency.as_str(),
+                    symbol.quote_currency.as_str(),
+                    chain_symbol.order,
+                )
+            }))
+            .with_context(|| {
+                format!(
+                    "Chain {:?} does not return to its starting asset",
+                    chain::extract_chain_symbols(chain)
+                )
+            })?;
+        }
+
         let mut order_symbols = vec![];
 
         for (i, chain_symbol) in chain.iter().enumerate() {
-            let min_profit_qty = if i == 0 {
-                find_base_asset(base_assets, chain_symbol).map(|base| base.min_profit_qty)
+            // Profit thresholds scaled by the active schedule.
+            let (min_profit_qty, min_profit_percent) = if i == 0
+                && let Some(base) = find_base_asset(base_assets, chain_symbol)
+            {
+                // The chain profit can't be valued before the base asset's USD price is.
+                let Some(min_profit_qty) = base_min_profit_qty(&base, self.usd_profit_floor) else {
+                    return Ok(());
+                };
+                (
+                    Some(min_profit_qty * min_profit_scale),
+                    base.min_profit_percent
+                        .map(|percent| percent * min_profit_scale),
+                )
             } else {
-                None
+                (None, None)
             };
 
-            let max_order_qty = if i == 0 {
-                find_base_asset(base_assets, chain_symbol).map(|base| base.max_order_qty)
+            let max_order_qty = if i == 0
+                && let Some(base) = find_base_asset(base_assets, chain_symbol)
+            {
+                // Nothing to trade while the base asset has no free balance.
+                let max_order_qty = base_max_order_qty(&base, self.balance_cap.as_deref());
+                if max_order_qty <= Decimal::ZERO {
+                    return Ok(());
+                }
+                Some(max_order_qty)
             } else {
                 None
             };
@@ -240,6 +547,7 @@ impl OrderBuilder {
             order_symbols.push(OrderSymbol {
                 symbol: symbol.symbol.clone(),
                 symbol_order: chain_symbol.order,
+                order_type: self.order_type,
                 order_book: &order_book[i],
                 base_min_size: symbol.base_min_size,
                 quote_min_size: symbol.quote_min_size,
@@ -249,12 +557,19 @@ impl OrderBuilder {
                 quote_increment: symbol.quote_increment,
                 price_increment: symbol.price_increment,
                 min_profit_qty,
+                min_profit_percent,
                 max_order_qty,
             });
         }
 
-        let orders = Self::calculate_chain_profit(&order_symbols, market_depth_limit, fee_percent);
-        METRICS.record_processed_chain(&chain::extract_chain_symbols(chain));
+        let orders = Self::calculate_chain_profit(
+            &order_symbols,
+            self.market_depth_limit,
+            &self.fees,
+            self.fee_deduction,
+            &self.min_notional,
+            self.off_grid_price,
+        );
 
         if orders.is_empty() {
             return Ok(());
@@ -263,11 +578,21 @@ impl OrderBuilder {
         let orders_chain = ChainOrders {
             ts: misc::time::get_current_timestamp().as_millis(),
             chain_id: Uuid::new_v4(),
-            fee_percent,
+            base_asset: find_base_asset(base_assets, &chain[0])
+                .map(|base| base.asset)
+                .unwrap_or_default(),
+            // Average fee of the legs, the chain fee stays their sum.
+            fee_percent: self.fees.chain_fee_percent(
+                orders
+                    .iter()
+                    .map(|order| (order.symbol.as_str(), order.symbol_order)),
+            ) / Decimal::from_usize(orders.len()).unwrap_or(Decimal::ONE),
             orders,
+            received_at,
+            queued_at: None,
         };
 
-        if let Err(e) = ORDERS_CHANNEL.tx.send(orders_chain) {
+        if let Err(e) = ORDERS_CHANNEL.publish(orders_chain) {
             error!(error = ?e, "Failed to send chain to channel");
         }
 
@@ -275,11 +600,16 @@ impl OrderBuilder {
     }
 
     /// Builds orders for the chain and calculates profit.
+    ///
+    /// Prices off the `price_increment` grid are handled according to `off_grid_price`.
     #[must_use]
     pub fn calculate_chain_profit(
         chain: &[OrderSymbol],
         market_depth_limit: usize,
-        fee_percent: Decimal,
+        fees: &FeeSchedule,
+        fee_deduction: FeeDeduction,
+        min_notional: &MinNotional,
+        off_grid_price: OffGridPrice,
     ) -> Vec<ChainOrder> {
         let mut orders: Vec<PreOrder> = vec![];
         let mut start_depth_limit = 0;
@@ -330,6 +660,7 @@ impl OrderBuilder {
                 orders.push(PreOrder {
                     symbol: order_symbol.symbol.clone(),
                     symbol_order: order_symbol.symbol_order,
+                    order_type: order_symbol.order_type,
                     base_min_size: order_symbol.base_min_size,
                     _quote_min_size: order_symbol.quote_min_size,
                     _base_max_size: order_symbol.base_max_size,
@@ -343,7 +674,7 @@ impl OrderBuilder {
                 });
 
                 // If first symbol and base qty does not match the max order qty, where max order
-                // qty for 2nd and 3rd symbol is previous symbol quote qty, it is necessary to
+                // qty for the following symbols is previous symbol quote qty, it is necessary to
                 // recalculate the qty of previous orders.
                 if i != 0 && base_qty < max_order_qty {
                     Self::recalculate_orders_qty(&mut orders, i);
@@ -362,6 +693,7 @@ impl OrderBuilder {
         // Round and recalculate quantities according to binance api rules.
         let mut profit_orders = vec![];
         let mut min_profit_qty = get_min_profit_qty(chain.first().unwrap());
+        let min_profit_percent = chain.first().unwrap().min_profit_percent;
 
         // Iterate over every first order in chain.
         'outer_loop: for i in (0..).take(orders.len() - 1).step_by(chain.len()) {
@@ -374,7 +706,33 @@ impl OrderBuilder {
                 let base_scale = order.base_increment.scale();
                 let quote_scale = order.quote_increment.scale();
 
-                let price = order.price.trunc_with_scale(price_scale);
+                let mut price = order.price.trunc_with_scale(price_scale);
+                if !is_on_grid(price, order.price_increment) {
+                    match off_grid_price {
+                        OffGridPrice::Align => {
+                            let aligned =
+                                align_price(price, order.price_increment, order.symbol_order);
+                            debug!(
+                                symbol = %order.symbol,
+                                price = %price,
+                                aligned = %aligned,
+                                price_increment = %order.price_increment,
+                                "Price aligned to the increment grid"
+                            );
+                            price = aligned;
+                        }
+                        OffGridPrice::Skip => {
+                            debug!(
+                                symbol = %order.symbol,
+                                price = %price,
+                                price_increment = %order.price_increment,
+                                "Price off the increment grid, chain skipped"
+                            );
+                            continue 'outer_loop;
+                        }
+                    }
+                }
+
                 let base_qty = if count == 0 {
                     orders[i].base_qty
                 } else {
@@ -409,31 +767,44 @@ impl OrderBuilder {
                 tmp_orders.push(ChainOrder {
                     symbol: order.symbol.clone(),
                     symbol_order: order.symbol_order,
+                    order_type: order.order_type,
                     base_qty: rounded_base_qty,
                     quote_qty: rounded_quote_qty,
                     base_increment: order.base_increment,
                     quote_increment: order.quote_increment,
+                    min_qty: order.base_min_size,
                     price,
                 });
 
                 count += 1;
             }
 
+            // Skip the pass if the exchange would reject a leg for its value.
+            if min_notional
+                .find_below(&tmp_orders, |idx| chain[idx].quote_min_size)
+                .is_some()
+            {
+                continue;
+            }
+
             // Check profit.
-            let fee = calculate_fee(tmp_orders.first().unwrap().base_qty, fee_percent);
+            let first_qty = tmp_orders.first().unwrap().base_qty;
+            let last_qty = tmp_orders.last().unwrap().quote_qty;
+            let fee = calculate_fee(first_qty, last_qty, fees, fee_deduction, chain);
 
             // Difference between the outbound volume of the last symbol in chain and the inbound
             // volume of the first symbol in chain.
-            let diff_qty =
-                tmp_orders.last().unwrap().quote_qty - tmp_orders.first().unwrap().base_qty;
+            let diff_qty = last_qty - first_qty;
 
-            if (diff_qty - fee) >= min_profit_qty {
+            if (diff_qty - fee) >= min_profit_qty
+                && reaches_min_profit_percent(diff_qty - fee, first_qty, min_profit_percent)
+            {
                 min_profit_qty = diff_qty - fee;
                 profit_orders.extend_from_slice(&tmp_orders);
             }
         }
 
-        // Return 3 last profit orders.
+        // Return the orders of the last profitable chain.
         if profit_orders.len() >= chain.len() {
             let idx = profit_orders.len().sub(chain.len());
             profit_orders[idx..].to_vec()
@@ -487,6 +858,104 @@ fn find_base_asset(base_assets: &[Asset], chain_symbol: &ChainSymbol) -> Option<
         .cloned()
 }
 
+/// Minimum profit of a chain starting from `base`: the USD floor converted into the asset if
+/// set, otherwise the asset's own. `None` while the USD price of the asset is unknown.
+fn base_min_profit_qty(base: &Asset, usd_profit_floor: Option<&UsdProfitFloor>) -> Option<Decimal> {
+    match usd_profit_floor {
+        Some(floor) => floor.min_profit_qty(&base.asset),
+        None => Some(base.min_profit_qty),
+    }
+}
+
+/// Returns the configured `max_order_qty` of the base asset, capped at its free balance.
+fn base_max_order_qty(base: &Asset, balance_cap: Option<&BalanceCap>) -> Decimal {
+    balance_cap.map_or(base.max_order_qty, |cap| {
+        cap.cap(&base.asset, base.max_order_qty)
+    })
+}
+
+/// Returns a symbol pricing each base asset in `usd_asset` among the chain symbols. Base
+/// assets without one are logged, their chains never pass the USD floor.
+fn usd_price_symbols<const N: usize>(
+    chains: &[[ChainSymbol; N]],
+    base_assets: &[Asset],
+    usd_asset: &str,
+) -> Vec<UsdPriceSymbol> {
+    let is_base_asset = |asset: &str| base_assets.iter().any(|base| base.asset == asset);
+    let mut price_symbols = BTreeMap::new();
+
+    for symbol in chains
+        .iter()
+        .flatten()
+        .map(|chain_symbol| &chain_symbol.symbol)
+    {
+        let (asset, inverted) = if symbol.quote_currency == usd_asset {
+            (&symbol.base_currency, false)
+        } else if symbol.base_currency == usd_asset {
+            (&symbol.quote_currency, true)
+        } else {
+            continue;
+        };
+        if is_base_asset(asset) {
+            price_symbols
+                .entry(asset.clone())
+                .or_insert_with(|| UsdPriceSymbol {
+                    symbol: symbol.symbol.clone(),
+                    asset: asset.clone(),
+                    inverted,
+                });
+        }
+    }
+
+    for base in base_assets {
+        if base.asset != usd_asset && !price_symbols.contains_key(&base.asset) {
+            warn!(
+                asset = %base.asset,
+                usd_asset,
+                "⚠️ [Engine] No symbol prices the base asset in USD, its chains are skipped"
+            );
+        }
+    }
+
+    price_symbols.into_values().collect()
+}
+
+/// Keeps the USD prices of the base assets up to date from the last bid or ask of their
+/// symbols, whichever side changed.
+async fn track_usd_prices(
+    token: CancellationToken,
+    floor: &UsdProfitFloor,
+    price_symbols: Vec<UsdPriceSymbol>,
+) {
+    let mut receivers: Vec<_> = price_symbols
+        .iter()
+        .map(|price_symbol| TICKER_BROADCAST.subscribe(price_symbol.symbol.as_str()))
+        .collect();
+
+    loop {
+        tokio::select! {
+            () = token.cancelled() => break,
+
+            idx = next_changed(&mut receivers) => {
+                let price = {
+                    let msg = receivers[idx].borrow_and_update();
+                    msg.bid.as_ref().or(msg.ask.as_ref()).map(|event| event.price)
+                };
+                let Some(price) = price.filter(|price| !price.is_zero()) else {
+                    continue;
+                };
+                let price_symbol = &price_symbols[idx];
+                let price = if price_symbol.inverted {
+                    Decimal::ONE / price
+                } else {
+                    price
+                };
+                floor.set_price(&price_symbol.asset, price);
+            },
+        }
+    }
+}
+
 fn define_precision(order_symbol: &OrderSymbol) -> u32 {
     match order_symbol.symbol_order {
         SymbolOrder::Asc => order_symbol.base_increment.scale(),
@@ -508,20 +977,82 @@ fn get_min_profit_qty(order_symbol: &OrderSymbol) -> Decimal {
         .trunc_with_scale(define_precision(order_symbol))
 }
 
-fn calculate_fee(qty: Decimal, fee_percent: Decimal) -> Decimal {
-    let orders_count = Decimal::from_usize(3).unwrap();
+/// Returns true if `profit` reaches `min_profit_percent` of the `spent` quantity, always
+/// without a percentage threshold.
+fn reaches_min_profit_percent(
+    profit: Decimal,
+    spent: Decimal,
+    min_profit_percent: Option<Decimal>,
+) -> bool {
+    min_profit_percent.is_none_or(|min_percent| {
+        !spent.is_zero() && profit / spent * Decimal::ONE_HUNDRED >= min_percent
+    })
+}
+
+/// Returns true if `price` is a multiple of `increment` (any price is, without an increment).
+fn is_on_grid(price: Decimal, increment: Decimal) -> bool {
+    increment.is_zero() || (price % increment).is_zero()
+}
+
+/// Moves `price` onto the `increment` grid, against the chain: Asc legs sell, so the price
+/// is rounded down, Desc legs buy, so it is rounded up.
+fn align_price(price: Decimal, increment: Decimal, symbol_order: SymbolOrder) -> Decimal {
+    let steps = price / increment;
+    let steps = match symbol_order {
+        SymbolOrder::Asc => steps.floor(),
+        SymbolOrder::Desc => steps.ceil(),
+    };
+    (steps * increment).trunc_with_scale(increment.scale())
+}
+
+/// Fee in the base asset of executing every leg of `chain`, which takes in `first_qty` and
+/// returns `last_qty` before fees, each leg charged by the schedule.
+fn calculate_fee(
+    first_qty: Decimal,
+    last_qty: Decimal,
+    fees: &FeeSchedule,
+    fee_deduction: FeeDeduction,
+    chain: &[OrderSymbol],
+) -> Decimal {
     let delimiter = Decimal::from_usize(100).unwrap();
-    (qty * fee_percent * orders_count) / delimiter
+    match fee_deduction {
+        FeeDeduction::Quote => {
+            let kept = chain
+                .iter()
+                .enumerate()
+                .map(|(idx, order)| {
+                    let fee_percent =
+                        fees.leg_fee_percent(idx, order.symbol.as_str(), order.symbol_order);
+                    Decimal::ONE - fee_percent / delimiter
+                })
+                .product::<Decimal>();
+            last_qty * (Decimal::ONE - kept)
+        }
+        FeeDeduction::Kcs { discount_percent } => {
+            let fee_percent = fees.chain_fee_percent(
+                chain
+                    .iter()
+                    .map(|order| (order.symbol.as_str(), order.symbol_order)),
+            );
+            first_qty * fee_percent / delimiter * (Decimal::ONE - discount_percent / delimiter)
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::str::FromStr;
+    use std::{collections::HashMap, str::FromStr};
 
-    use engine::enums::SymbolOrder;
+    use engine::{Balances, FeeRates, enums::SymbolOrder};
     use rust_decimal::prelude::FromPrimitive;
 
     use super::*;
+    use crate::libs::kucoin_client::{enums::MarketType, models::Symbol};
+
+    /// Fees charged on the chain volume at their full rate.
+    const VOLUME_FEES: FeeDeduction = FeeDeduction::Kcs {
+        discount_percent: Decimal::ZERO,
+    };
 
     // Case #1: all orders of the 1st depth have volumes greater than the volume limit.
     // (order - ASC/DESC/ASC)
@@ -555,7 +1086,9 @@ mod tests {
             OrderSymbol {
                 symbol: "BTCUSDT".to_owned(),
                 symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
                 min_profit_qty: Decimal::from_f64(0.000030),
+                min_profit_percent: None,
                 max_order_qty: Decimal::from_f64(0.00030),
                 order_book: &order_book_1,
                 base_min_size: Default::default(),
@@ -569,7 +1102,9 @@ mod tests {
             OrderSymbol {
                 symbol: "ETHUSDT".to_owned(),
                 symbol_order: SymbolOrder::Desc,
+                order_type: OrderType::Market,
                 min_profit_qty: None,
+                min_profit_percent: None,
                 max_order_qty: None,
                 order_book: &order_book_2,
                 base_min_size: Default::default(),
@@ -583,7 +1118,9 @@ mod tests {
             OrderSymbol {
                 symbol: "ETHBTC".to_owned(),
                 symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
                 min_profit_qty: None,
+                min_profit_percent: None,
                 max_order_qty: None,
                 order_book: &order_book_3,
                 base_min_size: Default::default(),
@@ -596,8 +1133,14 @@ mod tests {
             },
         ];
 
-        let orders =
-            OrderBuilder::calculate_chain_profit(&order_symbols, market_depth_limit, fee_percent);
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            market_depth_limit,
+            &FeeSchedule::flat(fee_percent),
+            VOLUME_FEES,
+            &MinNotional::default(),
+            OffGridPrice::Align,
+        );
 
         assert_eq!(orders.len(), 3);
 
@@ -622,6 +1165,115 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_max_order_qty_is_capped_at_free_balance() -> anyhow::Result<()> {
+        let market_depth_limit: usize = 1;
+        let fee_percent: Decimal = Decimal::from_str("0.075").unwrap();
+
+        let order_book_1 = BookTickerEvent {
+            sequence_id: 0,
+            symbol: "BTCUSDT".to_owned(),
+            price: Decimal::from_f64(109615.46000000).unwrap(),
+            qty: Decimal::from_f64(7.27795000).unwrap(),
+        };
+
+        let order_book_2 = BookTickerEvent {
+            sequence_id: 0,
+            symbol: "ETHUSDT".to_owned(),
+            price: Decimal::from_f64(2585.70000000).unwrap(),
+            qty: Decimal::from_f64(14.64600000).unwrap(),
+        };
+
+        let order_book_3 = BookTickerEvent {
+            sequence_id: 0,
+            symbol: "ETHBTC".to_owned(),
+            price: Decimal::from_f64(0.02858000).unwrap(),
+            qty: Decimal::from_f64(105.74550000).unwrap(),
+        };
+
+        // The config asks for 0.0003 BTC while only 0.0001 BTC is free.
+        let btc = Asset {
+            asset: "BTC".to_owned(),
+            symbol: None,
+            min_profit_qty: Decimal::new(3, 5),
+            min_profit_percent: None,
+            max_order_qty: Decimal::new(3, 4),
+            min_ticker_qty_24h: Decimal::ZERO,
+            session_profit_target: None,
+            max_daily_loss: None,
+        };
+        let balance_cap = BalanceCap::default();
+        balance_cap.set(Balances::from([("BTC".to_owned(), Decimal::new(1, 4))]));
+        assert_eq!(base_max_order_qty(&btc, None), Decimal::new(3, 4));
+
+        let order_symbols = vec![
+            OrderSymbol {
+                symbol: "BTCUSDT".to_owned(),
+                symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
+                min_profit_qty: Decimal::from_f64(0.000010),
+                min_profit_percent: None,
+                max_order_qty: Some(base_max_order_qty(&btc, Some(&balance_cap))),
+                order_book: &order_book_1,
+                base_min_size: Default::default(),
+                quote_min_size: Default::default(),
+                base_max_size: Default::default(),
+                quote_max_size: Default::default(),
+                base_increment: Decimal::from_f64(0.00000001).unwrap(),
+                quote_increment: Decimal::from_f64(0.00000001).unwrap(),
+                price_increment: Decimal::from_f64(0.00000001).unwrap(),
+            },
+            OrderSymbol {
+                symbol: "ETHUSDT".to_owned(),
+                symbol_order: SymbolOrder::Desc,
+                order_type: OrderType::Market,
+                min_profit_qty: None,
+                min_profit_percent: None,
+                max_order_qty: None,
+                order_book: &order_book_2,
+                base_min_size: Default::default(),
+                quote_min_size: Default::default(),
+                base_max_size: Default::default(),
+                quote_max_size: Default::default(),
+                base_increment: Decimal::from_f64(0.00000001).unwrap(),
+                quote_increment: Decimal::from_f64(0.00000001).unwrap(),
+                price_increment: Decimal::from_f64(0.00000001).unwrap(),
+            },
+            OrderSymbol {
+                symbol: "ETHBTC".to_owned(),
+                symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
+                min_profit_qty: None,
+                min_profit_percent: None,
+                max_order_qty: None,
+                order_book: &order_book_3,
+                base_min_size: Default::default(),
+                quote_min_size: Default::default(),
+                base_max_size: Default::default(),
+                quote_max_size: Default::default(),
+                base_increment: Decimal::from_f64(0.00000001).unwrap(),
+                quote_increment: Decimal::from_f64(0.00000001).unwrap(),
+                price_increment: Decimal::from_f64(0.00000001).unwrap(),
+            },
+        ];
+
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            market_depth_limit,
+            &FeeSchedule::flat(fee_percent),
+            VOLUME_FEES,
+            &MinNotional::default(),
+            OffGridPrice::Align,
+        );
+
+        assert_eq!(orders.len(), 3);
+        assert_eq!(orders[0].base_qty.to_string(), "0.00010000");
+        assert_eq!(orders[1].base_qty, orders[0].quote_qty);
+        assert_eq!(orders[2].base_qty, orders[1].quote_qty);
+
+        Ok(())
+    }
+
     // Case #2: 1st pair of 1st depth does not have enough volume to reach the volume limit.
     // (order - ASC/DESC/ASC)
     #[tokio::test]
@@ -654,7 +1306,9 @@ mod tests {
             OrderSymbol {
                 symbol: "BTCUSDT".to_owned(),
                 symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
                 min_profit_qty: Decimal::from_f64(0.0),
+                min_profit_percent: None,
                 max_order_qty: Decimal::from_f64(0.00030),
                 order_book: &order_book_1,
                 base_min_size: Default::default(),
@@ -668,7 +1322,9 @@ mod tests {
             OrderSymbol {
                 symbol: "ETHUSDT".to_owned(),
                 symbol_order: SymbolOrder::Desc,
+                order_type: OrderType::Market,
                 min_profit_qty: None,
+                min_profit_percent: None,
                 max_order_qty: None,
                 order_book: &order_book_2,
                 base_min_size: Default::default(),
@@ -682,7 +1338,9 @@ mod tests {
             OrderSymbol {
                 symbol: "ETHBTC".to_owned(),
                 symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
                 min_profit_qty: None,
+                min_profit_percent: None,
                 max_order_qty: None,
                 order_book: &order_book_3,
                 base_min_size: Default::default(),
@@ -695,8 +1353,14 @@ mod tests {
             },
         ];
 
-        let orders =
-            OrderBuilder::calculate_chain_profit(&order_symbols, market_depth_limit, fee_percent);
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            market_depth_limit,
+            &FeeSchedule::flat(fee_percent),
+            VOLUME_FEES,
+            &MinNotional::default(),
+            OffGridPrice::Align,
+        );
 
         assert_eq!(orders.len(), 3);
 
@@ -753,7 +1417,9 @@ mod tests {
             OrderSymbol {
                 symbol: "BTCUSDT".to_owned(),
                 symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
                 min_profit_qty: Decimal::from_f64(0.000030),
+                min_profit_percent: None,
                 max_order_qty: Decimal::from_f64(0.00030),
                 order_book: &order_book_1,
                 base_min_size: Default::default(),
@@ -767,7 +1433,9 @@ mod tests {
             OrderSymbol {
                 symbol: "ETHUSDT".to_owned(),
                 symbol_order: SymbolOrder::Desc,
+                order_type: OrderType::Market,
                 min_profit_qty: None,
+                min_profit_percent: None,
                 max_order_qty: None,
                 order_book: &order_book_2,
                 base_min_size: Default::default(),
@@ -781,7 +1449,9 @@ mod tests {
             OrderSymbol {
                 symbol: "ETHBTC".to_owned(),
                 symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
                 min_profit_qty: None,
+                min_profit_percent: None,
                 max_order_qty: None,
                 order_book: &order_book_3,
                 base_min_size: Default::default(),
@@ -794,8 +1464,14 @@ mod tests {
             },
         ];
 
-        let orders =
-            OrderBuilder::calculate_chain_profit(&order_symbols, market_depth_limit, fee_percent);
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            market_depth_limit,
+            &FeeSchedule::flat(fee_percent),
+            VOLUME_FEES,
+            &MinNotional::default(),
+            OffGridPrice::Align,
+        );
 
         assert_eq!(orders.len(), 3);
 
@@ -852,7 +1528,9 @@ mod tests {
             OrderSymbol {
                 symbol: "BTCUSDT".to_owned(),
                 symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
                 min_profit_qty: Decimal::from_f64(0.000030),
+                min_profit_percent: None,
                 max_order_qty: Decimal::from_f64(0.00030),
                 order_book: &order_book_1,
                 base_min_size: Default::default(),
@@ -866,7 +1544,9 @@ mod tests {
             OrderSymbol {
                 symbol: "ETHUSDT".to_owned(),
                 symbol_order: SymbolOrder::Desc,
+                order_type: OrderType::Market,
                 min_profit_qty: None,
+                min_profit_percent: None,
                 max_order_qty: None,
                 order_book: &order_book_2,
                 base_min_size: Default::default(),
@@ -880,7 +1560,9 @@ mod tests {
             OrderSymbol {
                 symbol: "ETHBTC".to_owned(),
                 symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
                 min_profit_qty: None,
+                min_profit_percent: None,
                 max_order_qty: None,
                 order_book: &order_book_3,
                 base_min_size: Default::default(),
@@ -893,8 +1575,14 @@ mod tests {
             },
         ];
 
-        let orders =
-            OrderBuilder::calculate_chain_profit(&order_symbols, market_depth_limit, fee_percent);
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            market_depth_limit,
+            &FeeSchedule::flat(fee_percent),
+            VOLUME_FEES,
+            &MinNotional::default(),
+            OffGridPrice::Align,
+        );
 
         assert_eq!(orders.len(), 3);
 
@@ -950,7 +1638,9 @@ mod tests {
             OrderSymbol {
                 symbol: "ETHBTC".to_owned(),
                 symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
                 min_profit_qty: Decimal::from_f64(0.0),
+                min_profit_percent: None,
                 max_order_qty: Decimal::from_f64(0.0079),
                 order_book: &order_book_1,
                 base_min_size: Default::default(),
@@ -964,7 +1654,9 @@ mod tests {
             OrderSymbol {
                 symbol: "WBTCBTC".to_owned(),
                 symbol_order: SymbolOrder::Desc,
+                order_type: OrderType::Market,
                 min_profit_qty: None,
+                min_profit_percent: None,
                 max_order_qty: None,
                 order_book: &order_book_2,
                 base_min_size: Default::default(),
@@ -978,7 +1670,9 @@ mod tests {
             OrderSymbol {
                 symbol: "WBTCETH".to_owned(),
                 symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
                 min_profit_qty: None,
+                min_profit_percent: None,
                 max_order_qty: None,
                 order_book: &order_book_3,
                 base_min_size: Default::default(),
@@ -991,8 +1685,14 @@ mod tests {
             },
         ];
 
-        let orders =
-            OrderBuilder::calculate_chain_profit(&order_symbols, market_depth_limit, fee_percent);
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            market_depth_limit,
+            &FeeSchedule::flat(fee_percent),
+            VOLUME_FEES,
+            &MinNotional::default(),
+            OffGridPrice::Align,
+        );
         assert_eq!(orders.len(), 0);
 
         Ok(())
@@ -1027,7 +1727,9 @@ mod tests {
             OrderSymbol {
                 symbol: "ETHBTC".to_owned(),
                 symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
                 min_profit_qty: Decimal::from_f64(0.0),
+                min_profit_percent: None,
                 max_order_qty: Decimal::from_f64(0.0079),
                 order_book: &order_book_1,
                 base_min_size: Default::default(),
@@ -1041,7 +1743,9 @@ mod tests {
             OrderSymbol {
                 symbol: "SSVBTC".to_owned(),
                 symbol_order: SymbolOrder::Desc,
+                order_type: OrderType::Market,
                 min_profit_qty: None,
+                min_profit_percent: None,
                 max_order_qty: None,
                 order_book: &order_book_2,
                 base_min_size: Default::default(),
@@ -1055,7 +1759,9 @@ mod tests {
             OrderSymbol {
                 symbol: "SSVETH".to_owned(),
                 symbol_order: SymbolOrder::Asc,
+                order_type: OrderType::Market,
                 min_profit_qty: None,
+                min_profit_percent: None,
                 max_order_qty: None,
                 order_book: &order_book_3,
                 base_min_size: Default::default(),
@@ -1068,8 +1774,14 @@ mod tests {
             },
         ];
 
-        let orders =
-            OrderBuilder::calculate_chain_profit(&order_symbols, market_depth_limit, fee_percent);
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            market_depth_limit,
+            &FeeSchedule::flat(fee_percent),
+            VOLUME_FEES,
+            &MinNotional::default(),
+            OffGridPrice::Align,
+        );
         assert_eq!(orders.len(), 3);
 
         assert_eq!(orders[0].symbol, "ETHBTC");
@@ -1092,4 +1804,520 @@ mod tests {
 
         Ok(())
     }
+
+    // Case #8: four legs, USDT -> BTC -> ETH -> SOL -> USDT (order - DESC/DESC/DESC/ASC).
+    // 100 USDT returns 106.56 USDT, the fee is charged for all four orders.
+    #[tokio::test]
+    async fn test_calculate_chain_profit_four_legs() -> anyhow::Result<()> {
+        let fee_percent = Decimal::new(1, 1);
+
+        let order_book = |symbol: &str, price: Decimal| BookTickerEvent {
+            sequence_id: 0,
+            symbol: symbol.to_owned(),
+            price,
+            qty: Decimal::from(1000),
+        };
+        let order_book_1 = order_book("BTC-USDT", Decimal::from(100_000));
+        let order_book_2 = order_book("ETH-BTC", Decimal::new(3, 2));
+        let order_book_3 = order_book("SOL-ETH", Decimal::new(5, 2));
+        let order_book_4 = order_book("SOL-USDT", Decimal::from(160));
+
+        let order_symbol =
+            |symbol: &str, order_book, symbol_order, base_increment, quote_increment| OrderSymbol {
+                symbol: symbol.to_owned(),
+                symbol_order,
+                order_type: OrderType::Market,
+                min_profit_qty: None,
+                min_profit_percent: None,
+                max_order_qty: None,
+                order_book,
+                base_min_size: Decimal::new(1, 5),
+                quote_min_size: Default::default(),
+                base_max_size: Default::default(),
+                quote_max_size: Default::default(),
+                base_increment,
+                quote_increment,
+                price_increment: Decimal::new(1, 5),
+            };
+        let mut order_symbols = vec![
+            OrderSymbol {
+                max_order_qty: Some(Decimal::from(100)),
+                ..order_symbol(
+                    "BTC-USDT",
+                    &order_book_1,
+                    SymbolOrder::Desc,
+                    Decimal::new(1, 5),
+                    Decimal::new(1, 2),
+                )
+            },
+            order_symbol(
+                "ETH-BTC",
+                &order_book_2,
+                SymbolOrder::Desc,
+                Decimal::new(1, 4),
+                Decimal::new(1, 6),
+            ),
+            order_symbol(
+                "SOL-ETH",
+                &order_book_3,
+                SymbolOrder::Desc,
+                Decimal::new(1, 3),
+                Decimal::new(1, 5),
+            ),
+            order_symbol(
+                "SOL-USDT",
+                &order_book_4,
+                SymbolOrder::Asc,
+                Decimal::new(1, 3),
+                Decimal::new(1, 2),
+            ),
+        ];
+
+        // Profit after fee: 6.56 - 0.4 = 6.16, a three-order fee would leave 6.26.
+        order_symbols[0].min_profit_qty = Some(Decimal::new(62, 1));
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            1,
+            &FeeSchedule::flat(fee_percent),
+            VOLUME_FEES,
+            &MinNotional::default(),
+            OffGridPrice::Align,
+        );
+        assert!(orders.is_empty());
+
+        order_symbols[0].min_profit_qty = Some(Decimal::new(616, 2));
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            1,
+            &FeeSchedule::flat(fee_percent),
+            VOLUME_FEES,
+            &MinNotional::default(),
+            OffGridPrice::Align,
+        );
+        let legs: Vec<_> = orders
+            .iter()
+            .map(|order| (order.symbol.as_str(), order.base_qty, order.quote_qty))
+            .collect();
+        assert_eq!(
+            legs,
+            [
+                ("BTC-USDT", Decimal::from(100), Decimal::new(1, 3)),
+                ("ETH-BTC", Decimal::new(1, 3), Decimal::new(333, 4)),
+                ("SOL-ETH", Decimal::new(333, 4), Decimal::new(666, 3)),
+                ("SOL-USDT", Decimal::new(666, 3), Decimal::new(10_656, 2)),
+            ]
+        );
+
+        // Selling legs at 0.2%: 6.56 - 0.3 - 0.2 = 6.06 misses the threshold.
+        let fees =
+            FeeSchedule::flat(fee_percent).with_sell_fees(FeeRates::flat(Decimal::new(2, 1)));
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            1,
+            &fees,
+            VOLUME_FEES,
+            &MinNotional::default(),
+            OffGridPrice::Align,
+        );
+        assert!(orders.is_empty());
+
+        // A fee free SOL-USDT overrides the sell fees: 6.56 - 0.3 = 6.26.
+        let fees = fees.with_symbol_fees("SOL-USDT", FeeRates::flat(Decimal::ZERO));
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            1,
+            &fees,
+            VOLUME_FEES,
+            &MinNotional::default(),
+            OffGridPrice::Align,
+        );
+        assert_eq!(orders.len(), 4);
+
+        // ETH-BTC spends 0.001 BTC, below a 0.002 BTC quote minimum.
+        order_symbols[1].quote_min_size = Decimal::new(2, 3);
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            1,
+            &FeeSchedule::flat(fee_percent),
+            VOLUME_FEES,
+            &MinNotional::default(),
+            OffGridPrice::Align,
+        );
+        assert!(orders.is_empty());
+
+        // SOL-USDT returns 106.56 USDT, below the configured 110 USDT.
+        order_symbols[1].quote_min_size = Decimal::new(1, 4);
+        let min_notional =
+            MinNotional::new(HashMap::from([("SOL-USDT".to_owned(), Decimal::from(110))]));
+        let orders = OrderBuilder::calculate_chain_profit(
+            &order_symbols,
+            1,
+            &FeeSchedule::flat(fee_percent),
+            VOLUME_FEES,
+            &min_notional,
+            OffGridPrice::Align,
+        );
+        assert!(orders.is_empty());
+
+        // Fees deducted in USDT compound to 0.4256 (net 6.1344), fees paid in KCS at 20% off
+        // cost 0.32 (net 6.24).
+        order_symbols[0].min_profit_qty = Some(Decimal::new(62, 1));
+        let calculate = |fee_deduction| {
+            OrderBuilder::calculate_chain_profit(
+                &order_symbols,
+                1,
+                &FeeSchedule::flat(fee_percent),
+                fee_deduction,
+                &MinNotional::default(),
+                OffGridPrice::Align,
+            )
+        };
+        assert!(calculate(FeeDeduction::Quote).is_empty());
+        assert_eq!(
+            calculate(FeeDeduction::Kcs {
+                discount_percent: Decimal::from(20)
+            })
+            .len(),
+            4
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_net_profit_by_fee_currency() {
+        let order_book = BookTickerEvent::default();
+        let order_symbol = |symbol: &str, symbol_order| OrderSymbol {
+            symbol: symbol.to_owned(),
+            symbol_order,
+            order_type: OrderType::Market,
+            min_profit_qty: None,
+            min_profit_percent: None,
+            max_order_qty: None,
+            order_book: &order_book,
+            base_min_size: Decimal::ZERO,
+            quote_min_size: Decimal::ZERO,
+            base_max_size: Decimal::ZERO,
+            quote_max_size: Decimal::ZERO,
+            base_increment: Decimal::ZERO,
+            quote_increment: Decimal::ZERO,
+            price_increment: Decimal::ZERO,
+        };
+        let chain = [
+            order_symbol("BTC-USDT", SymbolOrder::Desc),
+            order_symbol("ETH-BTC", SymbolOrder::Desc),
+            order_symbol("SOL-ETH", SymbolOrder::Desc),
+            order_symbol("SOL-USDT", SymbolOrder::Asc),
+        ];
+        let fees = FeeSchedule::flat(Decimal::new(1, 1));
+        let (first_qty, last_qty) = (Decimal::from(100), Decimal::new(10_656, 2));
+        let net_profit = |fee_deduction| {
+            last_qty - first_qty - calculate_fee(first_qty, last_qty, &fees, fee_deduction, &chain)
+        };
+
+        // Every leg passes on 99.9% of its funds: 106.56 * (1 - 0.999^4) goes to fees.
+        assert_eq!(
+            net_profit(FeeDeduction::Quote),
+            Decimal::from_str("6.13439893386656").unwrap()
+        );
+        // 0.4% of the 100 USDT volume, 20% off.
+        let kcs = FeeDeduction::new(FeeCurrency::Kcs, Decimal::from(20));
+        assert_eq!(net_profit(kcs), Decimal::new(624, 2));
+        assert_eq!(net_profit(VOLUME_FEES), Decimal::new(616, 2));
+    }
+
+    // The chain of case #8 against every combination of profit thresholds: its 6.16 USDT
+    // profit after fees is 6.16% of the 100 USDT spent.
+    #[tokio::test]
+    async fn test_calculate_chain_profit_percent_threshold() -> anyhow::Result<()> {
+        let order_book = |symbol: &str, price: Decimal| BookTickerEvent {
+            sequence_id: 0,
+            symbol: symbol.to_owned(),
+            price,
+            qty: Decimal::from(1000),
+        };
+        let order_book_1 = order_book("BTC-USDT", Decimal::from(100_000));
+        let order_book_2 = order_book("ETH-BTC", Decimal::new(3, 2));
+        let order_book_3 = order_book("SOL-ETH", Decimal::new(5, 2));
+        let order_book_4 = order_book("SOL-USDT", Decimal::from(160));
+
+        let order_symbol =
+            |symbol: &str, order_book, symbol_order, base_increment, quote_increment| OrderSymbol {
+                symbol: symbol.to_owned(),
+                symbol_order,
+                order_type: OrderType::Market,
+                min_profit_qty: None,
+                min_profit_percent: None,
+                max_order_qty: None,
+                order_book,
+                base_min_size: Decimal::new(1, 5),
+                quote_min_size: Default::default(),
+                base_max_size: Default::default(),
+                quote_max_size: Default::default(),
+                base_increment,
+                quote_increment,
+                price_increment: Decimal::new(1, 5),
+            };
+        let passes = |min_profit_qty, min_profit_percent| {
+            let order_symbols = [
+                OrderSymbol {
+                    max_order_qty: Some(Decimal::from(100)),
+                    min_profit_qty: Some(min_profit_qty),
+                    min_profit_percent,
+                    ..order_symbol(
+                        "BTC-USDT",
+                        &order_book_1,
+                        SymbolOrder::Desc,
+                        Decimal::new(1, 5),
+                        Decimal::new(1, 2),
+                    )
+                },
+                order_symbol(
+                    "ETH-BTC",
+                    &order_book_2,
+                    SymbolOrder::Desc,
+                    Decimal::new(1, 4),
+                    Decimal::new(1, 6),
+                ),
+                order_symbol(
+                    "SOL-ETH",
+                    &order_book_3,
+                    SymbolOrder::Desc,
+                    Decimal::new(1, 3),
+                    Decimal::new(1, 5),
+                ),
+                order_symbol(
+                    "SOL-USDT",
+                    &order_book_4,
+                    SymbolOrder::Asc,
+                    Decimal::new(1, 3),
+                    Decimal::new(1, 2),
+                ),
+            ];
+            !OrderBuilder::calculate_chain_profit(
+                &order_symbols,
+                1,
+                &FeeSchedule::flat(Decimal::new(1, 1)),
+                VOLUME_FEES,
+                &MinNotional::default(),
+                OffGridPrice::Align,
+            )
+            .is_empty()
+        };
+
+        // Absolute threshold only.
+        assert!(passes(Decimal::new(616, 2), None));
+        assert!(!passes(Decimal::new(62, 1), None));
+        // Percentage threshold only.
+        assert!(passes(Decimal::ZERO, Some(Decimal::new(616, 2))));
+        assert!(!passes(Decimal::ZERO, Some(Decimal::new(62, 1))));
+        // Both thresholds, each failing on its own.
+        assert!(passes(Decimal::from(6), Some(Decimal::from(6))));
+        assert!(!passes(Decimal::from(6), Some(Decimal::new(62, 1))));
+        assert!(!passes(Decimal::new(62, 1), Some(Decimal::from(6))));
+
+        Ok(())
+    }
+
+    // Case #9: the last price 160.03 is off its 0.05 grid. Aligned down to 160.00 (a sell),
+    // the chain returns 106.56 instead of 106.57 at the unaligned price.
+    #[test]
+    fn test_usd_floor_overrides_base_min_profit() {
+        let asset = |asset: &str, min_profit_qty| Asset {
+            asset: asset.to_owned(),
+            symbol: None,
+            min_profit_qty,
+            min_profit_percent: None,
+            max_order_qty: Decimal::ONE,
+            min_ticker_qty_24h: Decimal::ZERO,
+            session_profit_target: None,
+            max_daily_loss: None,
+        };
+        let btc = asset("BTC", Decimal::new(3, 5));
+        let usdt = asset("USDT", Decimal::ONE);
+
+        // A 3 USD floor, with the BTC price not seen yet.
+        let floor = UsdProfitFloor::new(Decimal::from(3), "USDT");
+        assert_eq!(base_min_profit_qty(&btc, None), Some(Decimal::new(3, 5)));
+        assert_eq!(base_min_profit_qty(&btc, Some(&floor)), None);
+        assert_eq!(
+            base_min_profit_qty(&usdt, Some(&floor)),
+            Some(Decimal::from(3))
+        );
+
+        floor.set_price("BTC", Decimal::from(60_000));
+        assert_eq!(
+            base_min_profit_qty(&btc, Some(&floor)),
+            Some(Decimal::new(5, 5))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_off_grid_price_is_aligned() -> anyhow::Result<()> {
+        let order_book = |symbol: &str, price: Decimal| BookTickerEvent {
+            sequence_id: 0,
+            symbol: symbol.to_owned(),
+            price,
+            qty: Decimal::from(1000),
+        };
+        let order_book_1 = order_book("BTC-USDT", Decimal::from(100_000));
+        let order_book_2 = order_book("ETH-BTC", Decimal::new(3, 2));
+        let order_book_3 = order_book("SOL-ETH", Decimal::new(5, 2));
+        let order_book_4 = order_book("SOL-USDT", Decimal::new(16_003, 2));
+
+        let order_symbol =
+            |symbol: &str, order_book, symbol_order, base_increment, quote_increment| OrderSymbol {
+                symbol: symbol.to_owned(),
+                symbol_order,
+                order_type: OrderType::Market,
+                min_profit_qty: None,
+                min_profit_percent: None,
+                max_order_qty: None,
+                order_book,
+                base_min_size: Decimal::new(1, 5),
+                quote_min_size: Default::default(),
+                base_max_size: Default::default(),
+                quote_max_size: Default::default(),
+                base_increment,
+                quote_increment,
+                price_increment: Decimal::new(1, 5),
+            };
+        let order_symbols = vec![
+            OrderSymbol {
+                min_profit_qty: Some(Decimal::ONE),
+                min_profit_percent: None,
+                max_order_qty: Some(Decimal::from(100)),
+                ..order_symbol(
+                    "BTC-USDT",
+                    &order_book_1,
+                    SymbolOrder::Desc,
+                    Decimal::new(1, 5),
+                    Decimal::new(1, 2),
+                )
+            },
+            order_symbol(
+                "ETH-BTC",
+                &order_book_2,
+                SymbolOrder::Desc,
+                Decimal::new(1, 4),
+                Decimal::new(1, 6),
+            ),
+            order_symbol(
+                "SOL-ETH",
+                &order_book_3,
+                SymbolOrder::Desc,
+                Decimal::new(1, 3),
+                Decimal::new(1, 5),
+            ),
+            OrderSymbol {
+                price_increment: Decimal::new(5, 2),
+                ..order_symbol(
+                    "SOL-USDT",
+                    &order_book_4,
+                    SymbolOrder::Asc,
+                    Decimal::new(1, 3),
+                    Decimal::new(1, 2),
+                )
+            },
+        ];
+
+        let calculate = |off_grid_price| {
+            OrderBuilder::calculate_chain_profit(
+                &order_symbols,
+                1,
+                &FeeSchedule::flat(Decimal::new(1, 1)),
+                VOLUME_FEES,
+                &MinNotional::default(),
+                off_grid_price,
+            )
+        };
+
+        let orders = calculate(OffGridPrice::Align);
+        assert_eq!(orders.len(), 4);
+        assert_eq!(orders[3].price, Decimal::from(160));
+        assert_eq!(orders[3].quote_qty, Decimal::new(10_656, 2));
+
+        assert!(calculate(OffGridPrice::Skip).is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_align_price_against_chain() {
+        let increment = Decimal::new(5, 2);
+        assert!(is_on_grid(Decimal::new(125, 2), increment));
+        assert!(!is_on_grid(Decimal::new(123, 2), increment));
+        assert!(is_on_grid(Decimal::new(123, 2), Decimal::ZERO));
+
+        let price = Decimal::new(123, 2);
+        assert_eq!(
+            align_price(price, increment, SymbolOrder::Asc),
+            Decimal::new(120, 2)
+        );
+        assert_eq!(
+            align_price(price, increment, SymbolOrder::Desc),
+            Decimal::new(125, 2)
+        );
+    }
+
+    #[test]
+    fn test_short_chain_is_refused() {
+        let chain_symbol = |name: &str, order| {
+            let (base, quote) = name.split_once('-').unwrap();
+            ChainSymbol::new(
+                Symbol {
+                    symbol: name.to_owned(),
+                    name: name.to_owned(),
+                    base_currency: base.to_owned(),
+                    quote_currency: quote.to_owned(),
+                    fee_currency: quote.to_owned(),
+                    market: MarketType::Usds,
+                    base_min_size: Decimal::ZERO,
+                    quote_min_size: Decimal::ZERO,
+                    base_max_size: Decimal::MAX,
+                    quote_max_size: Decimal::MAX,
+                    base_increment: Decimal::new(1, 8),
+                    quote_increment: Decimal::new(1, 8),
+                    price_increment: Decimal::new(1, 8),
+                    price_limit_rate: Decimal::ZERO,
+                    min_funds: None,
+                    is_margin_enabled: false,
+                    enable_trading: true,
+                    fee_category: 1,
+                    maker_fee_coefficient: Decimal::ONE,
+                    taker_fee_coefficient: Decimal::ONE,
+                    st: false,
+                    callauction_is_enabled: false,
+                },
+                order,
+            )
+        };
+        let chain = [
+            chain_symbol("BTC-USDT", SymbolOrder::Asc),
+            chain_symbol("BTC-USDT", SymbolOrder::Desc),
+        ];
+        let messages = chain.each_ref().map(|chain_symbol| BookTickerEvent {
+            sequence_id: 1,
+            symbol: chain_symbol.symbol.symbol.clone(),
+            price: Decimal::ONE,
+            qty: Decimal::TEN,
+        });
+
+        let builder = OrderBuilder::new(
+            Decimal::new(1, 1),
+            ProfitSchedule::new(vec![]),
+            OffGridPrice::default(),
+            true,
+            OrderType::Market,
+        )
+        .with_fee_deduction(VOLUME_FEES);
+
+        let error = builder
+            .process_chain(&[], &chain, &messages, None)
+            .unwrap_err();
+        assert_eq!(
+            format!("{error:#}"),
+            "Invalid chain [\"BTC-USDT\", \"BTC-USDT\"]: Chain has 2 legs, at least 3 are needed"
+        );
+    }
 }
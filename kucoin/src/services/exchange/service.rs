@@ -1,19 +1,23 @@
 //! Kucoin exchange service module for arbitrage operations.
 
-use std::sync::Arc;
+use std::{num::NonZeroU64, sync::Arc, time::Duration};
 
 use anyhow::Context;
 use async_trait::async_trait;
-use engine::{Exchange, REQUEST_WEIGHT, service::traits::ArbitrageService};
-use tokio::task::JoinSet;
+use engine::{
+    BalanceCache, BalanceCap, Balances, ComputePool, DISABLED_SYMBOLS, Exchange, MinNotional,
+    MonitoredSymbol, ProfitSchedule, REQUEST_WEIGHT, USD_PROFIT_FLOOR, enums::OrderType,
+    publish_chains, service::traits::ArbitrageService,
+};
+use tokio::{sync::oneshot, task::JoinSet};
 use tokio_util::sync::CancellationToken;
-use tracing::error;
+use tracing::{error, warn};
 
 use crate::{
     Config,
     libs::{
         kucoin_client,
-        kucoin_client::{BaseInfo, Kucoin, Market},
+        kucoin_client::{Account, BaseInfo, Kucoin, Market},
     },
     services::exchange::{
         asset::AssetBuilder, chain::ChainBuilder, order::OrderBuilder, ticker::TickerBuilder,
@@ -26,9 +30,20 @@ pub struct ExchangeService {
     ticker_builder: TickerBuilder,
     chain_builder: Arc<ChainBuilder>,
     order_builder: Arc<OrderBuilder>,
+    account_api: Account,
+    balance_cache: BalanceCache,
+    balance_cap: Arc<BalanceCap>,
+    balance_refresh_interval: Option<Duration>,
 }
 
-impl Exchange for ExchangeService {}
+#[async_trait]
+impl Exchange for ExchangeService {
+    async fn balances(&self) -> anyhow::Result<Balances> {
+        self.balance_cache
+            .get_or_refresh(|| fetch_balances(&self.account_api))
+            .await
+    }
+}
 
 #[async_trait]
 impl ArbitrageService for ExchangeService {
@@ -51,23 +66,57 @@ impl ArbitrageService for ExchangeService {
 
         let mut tasks_set = JoinSet::new();
 
+        // Cap order sizes at the free balances before any chain is built, then keep them fresh.
+        if let Some(interval) = self.balance_refresh_interval {
+            self.balance_cap.set(
+                fetch_balances(&self.account_api)
+                    .await
+                    .context("Failed to fetch balances")?,
+            );
+            tasks_set.spawn({
+                let balance_cap = self.balance_cap.clone();
+                let account_api = self.account_api.clone();
+                let token = token.clone();
+                async move {
+                    balance_cap
+                        .refresh_every(token, interval, || fetch_balances(&account_api))
+                        .await
+                }
+            });
+        }
+
+        let (ready_tx, ready_rx) = oneshot::channel();
         tasks_set.spawn({
-            let order_builder = self.order_builder.clone();
+            let ticker_builder = self.ticker_builder.clone();
             let token = token.clone();
-            let chains = chains.clone();
             async move {
-                order_builder
-                    .build_chains_orders(token, chains, base_assets)
+                ticker_builder
+                    .build_order_books(token, chains, ready_tx)
                     .await
             }
         });
 
-        tasks_set.spawn({
-            let ticker_builder = self.ticker_builder.clone();
-            let token = token.clone();
-            let chains = chains.clone();
-            async move { ticker_builder.build_order_books(token, chains).await }
-        });
+        // Only the chains whose streams started are evaluated, none if startup failed.
+        if let Ok(chains) = ready_rx.await {
+            // Expose the chains on the /chains endpoint.
+            publish_chains(
+                chains
+                    .iter()
+                    .map(|chain| chain.iter().map(MonitoredSymbol::from).collect())
+                    .collect(),
+            )
+            .await;
+
+            tasks_set.spawn({
+                let order_builder = self.order_builder.clone();
+                let token = token.clone();
+                async move {
+                    order_builder
+                        .build_chains_orders(token, chains, base_assets)
+                        .await
+                }
+            });
+        }
 
         // Wait for tasks, cancel on first error
         while let Some(result) = tasks_set.join_next().await {
@@ -112,7 +161,9 @@ impl ExchangeService {
         let market_api: Market =
             Kucoin::new(api_config.clone()).context("Failed to init market Kucoin client")?;
         let base_info_api: BaseInfo =
-            Kucoin::new(api_config).context("Failed to init base info Kucoin client")?;
+            Kucoin::new(api_config.clone()).context("Failed to init base info Kucoin client")?;
+        let account_api: Account =
+            Kucoin::new(api_config).context("Failed to init account Kucoin client")?;
 
         // Configure global request weight limit for API rate limiting.
         {
@@ -120,6 +171,21 @@ impl ExchangeService {
             weight_lock.set_weight_limit(config.api_weight_limit);
         }
 
+        if config.send_orders && config.order_type == OrderType::Market {
+            warn!(
+                "⚠️ [Engine] Legs are placed as market orders, realized profit may differ from \
+                 the top-of-book estimate"
+            );
+        }
+
+        let balance_cap = Arc::new(BalanceCap::default());
+
+        let compute_pool = config
+            .compute_threads
+            .map(ComputePool::new)
+            .transpose()?
+            .map(Arc::new);
+
         Ok(Self {
             asset_builder: AssetBuilder::new(
                 market_api.clone(),
@@ -128,12 +194,120 @@ impl ExchangeService {
                 config.max_order_qty,
                 config.min_ticker_qty_24h,
             ),
-            ticker_builder: TickerBuilder::new(base_info_api),
+            ticker_builder: TickerBuilder::new(
+                base_info_api,
+                config.fail_on_missing_subscriptions,
+                config.reconnect_strategy,
+            )
+            .with_max_reconnect_backoff(Duration::from_secs(config.max_reconnect_backoff_secs))
+            .with_max_failed_streams(config.max_failed_streams),
             chain_builder: Arc::new(ChainBuilder::new(
                 market_api.clone(),
                 config.skip_assets.clone(),
+                config.invalid_increments,
             )),
-            order_builder: Arc::new(OrderBuilder::new(config.fee_percent)),
+            order_builder: Arc::new(
+                OrderBuilder::new(
+                    config.fee_percent,
+                    ProfitSchedule::new(config.min_profit_schedule.clone()),
+                    config.off_grid_price,
+                    config.verify_chain_cycle,
+                    config.order_type,
+                )
+                .with_fee_deduction(config.fee_deduction())
+                .with_min_notional(MinNotional::new(config.min_notional.clone()))
+                .with_crossed_books_allowed(config.allow_crossed_books)
+                .with_disabled_symbols(Arc::clone(&DISABLED_SYMBOLS))
+                .with_update_latency_sampling(
+                    config.update_latency_sample_every.and_then(NonZeroU64::new),
+                )
+                .with_usd_profit_floor(USD_PROFIT_FLOOR.get())
+                .with_compute_pool(compute_pool)
+                .with_balance_cap(
+                    config
+                        .balance_refresh_interval_secs
+                        .map(|_| balance_cap.clone()),
+                ),
+            ),
+            account_api,
+            balance_cache: BalanceCache::default(),
+            balance_cap,
+            balance_refresh_interval: config
+                .balance_refresh_interval_secs
+                .map(Duration::from_secs),
         })
     }
 }
+
+/// Fetches the available balance of every currency on the trading accounts.
+async fn fetch_balances(account_api: &Account) -> anyhow::Result<Balances> {
+    let response = account_api
+        .get_trade_accounts()
+        .await
+        .context("Failed to get trade accounts")?;
+
+    Ok(response
+        .data
+        .into_iter()
+        .map(|account| (account.currency, account.available))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use mockito::{Matcher, Server};
+    use rust_decimal::Decimal;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fetch_balances_parses_accounts() -> anyhow::Result<()> {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/accounts")
+            .match_query(Matcher::UrlEncoded("type".into(), "trade".into()))
+            .match_header("KC-API-KEY", "test_api_key")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "code": "200000",
+                    "data": [
+                        {
+                            "id": "548674591753",
+                            "currency": "USDT",
+                            "type": "trade",
+                            "balance": "26.66759503",
+                            "available": "26.66759503",
+                            "holds": "0"
+                        },
+                        {
+                            "id": "63355cd156298d0001b66e61",
+                            "currency": "BTC",
+                            "type": "trade",
+                            "balance": "0.01",
+                            "available": "0.0075",
+                            "holds": "0.0025"
+                        }
+                    ]
+                }"#,
+            )
+            .create_async()
+            .await;
+
+        let account_api: Account = Kucoin::new(kucoin_client::ClientConfig {
+            host: server.url(),
+            api_key: "test_api_key".to_owned(),
+            api_secret: "test_api_secret".to_owned(),
+            api_passphrase: "test_passphrase".to_owned(),
+            http_config: kucoin_client::HttpConfig::default(),
+        })?;
+        let balances = fetch_balances(&account_api).await?;
+
+        mock.assert_async().await;
+        assert_eq!(balances.len(), 2);
+        assert_eq!(balances["USDT"], Decimal::new(2_666_759_503, 8));
+        assert_eq!(balances["BTC"], Decimal::new(75, 4));
+        Ok(())
+    }
+}
@@ -3,15 +3,22 @@
 //! This module provides a `TickerBuilder` for collecting unique symbols from triangular chains,
 //! creating book ticker streams, chunking them across multiple WebSocket connections (to respect
 //! limits), and spawning concurrent tasks to listen for real-time bid/ask updates. Events are
-//! broadcast via a channel.
+//! broadcast via a channel. A dropped connection is either resubscribed in place or ends the
+//! streams, so the exchange service is rebuilt, depending on the reconnect strategy. Up to a
+//! configured number of streams may fail to connect at startup, the chains depending on them are
+//! then dropped.
 
-use std::collections::HashSet;
+use std::{
+    collections::{BTreeSet, HashSet},
+    time::Duration,
+};
 
 use anyhow::bail;
-use engine::METRICS;
-use tokio::task::JoinSet;
+use engine::{METRICS, RUN_STATS, TICKS, enums::ReconnectStrategy};
+use tokio::{sync::oneshot, task::JoinSet, time::Instant};
 use tokio_util::sync::CancellationToken;
-use tracing::{error, info};
+use tools::misc::backoff::ExponentialBackoff;
+use tracing::{error, info, warn};
 
 use crate::{
     libs::kucoin_client::{
@@ -23,7 +30,7 @@ use crate::{
     },
     services::{
         broadcast::TICKER_BROADCAST,
-        exchange::chain::ChainSymbol,
+        exchange::{chain, chain::ChainSymbol},
         storage::{BookTickerEvent, BookTickerEventChanges},
     },
 };
@@ -33,33 +40,88 @@ use crate::{
 pub struct TickerBuilder {
     base_info_api: BaseInfo,
     ws_symbols_limit: usize,
+    fail_on_missing_subscriptions: bool,
+    reconnect_strategy: ReconnectStrategy,
+    max_reconnect_backoff: Duration,
+    /// Streams allowed to fail their first connection, see [`Self::with_max_failed_streams`].
+    max_failed_streams: Option<usize>,
+}
+
+/// Public connection token and the endpoint it is valid for.
+#[derive(Clone)]
+struct Bullet {
+    token: String,
+    ws_endpoint: String,
+    ping_interval: u64,
+}
+
+impl Bullet {
+    async fn fetch(base_info_api: &BaseInfo) -> anyhow::Result<Self> {
+        let resp = base_info_api.get_bullet_public().await?;
+        let Some(server) = resp.data.instance_servers.first() else {
+            bail!("Bullet public has no instance servers");
+        };
+        Ok(Self {
+            token: resp.data.token,
+            ws_endpoint: server.endpoint.clone(),
+            ping_interval: server.ping_interval,
+        })
+    }
 }
 
 impl TickerBuilder {
+    /// Delay before a dropped connection is first resubscribed, doubled per failed attempt.
+    const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(1);
+    /// Time since the last successful connection after which the backoff starts over, so a
+    /// flapping connection keeps backing off.
+    const BACKOFF_RESET_AFTER: Duration = Duration::from_secs(30);
+
     #[must_use]
-    pub fn new(base_info_api: BaseInfo) -> Self {
+    pub fn new(
+        base_info_api: BaseInfo,
+        fail_on_missing_subscriptions: bool,
+        reconnect_strategy: ReconnectStrategy,
+    ) -> Self {
         Self {
             base_info_api,
             ws_symbols_limit: 100,
+            fail_on_missing_subscriptions,
+            reconnect_strategy,
+            max_reconnect_backoff: Duration::from_secs(30),
+            max_failed_streams: None,
         }
     }
 
-    /// Builds and starts book ticker streams for the given chains.
+    /// Sets the upper bound of the backoff between resubscription attempts.
+    #[must_use]
+    pub const fn with_max_reconnect_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_reconnect_backoff = max_backoff;
+        self
+    }
+
+    /// Drops the streams whose first connection fails, with the chains depending on them,
+    /// instead of retrying them. Startup fails once more than `max_failed_streams` streams do.
+    #[must_use]
+    pub const fn with_max_failed_streams(mut self, max_failed_streams: Option<usize>) -> Self {
+        self.max_failed_streams = max_failed_streams;
+        self
+    }
+
+    /// Builds and starts book ticker streams for the given chains, sending the chains they
+    /// serve on `ready` once the streams are started.
     pub async fn build_order_books(
         &self,
         token: CancellationToken,
         chains: Vec<[ChainSymbol; 3]>,
+        ready: oneshot::Sender<Vec<[ChainSymbol; 3]>>,
     ) -> anyhow::Result<()> {
-        let (api_token, ws_endpoint, ping_interval) =
-            match self.base_info_api.get_bullet_public().await {
-                Ok(resp) => (
-                    resp.data.token,
-                    resp.data.instance_servers[0].endpoint.clone(),
-                    resp.data.instance_servers[0].ping_interval,
-                ),
-                Err(err) => bail!("Error getting bullet public: {err}"),
-            };
+        let bullet = match Bullet::fetch(&self.base_info_api).await {
+            Ok(bullet) => bullet,
+            Err(err) => bail!("Error getting bullet public: {err}"),
+        };
 
+        // Every symbol is subscribed once, however many chains share it: the chains subscribe
+        // to its broadcast instead.
         let unique_symbols: Vec<&str> = chains
             .iter()
             .flat_map(|chain| chain.iter())
@@ -70,25 +132,57 @@ impl TickerBuilder {
 
         info!(
             streams = unique_symbols.len(),
+            chain_subscriptions = Self::chain_subscriptions(&chains),
             "📡 [Network] WebSocket streams active"
         );
 
+        let chunks: Vec<&[&str]> = unique_symbols.chunks(self.ws_symbols_limit).collect();
+        self.check_subscriptions(&chains, &chunks.concat())?;
+
         let mut tasks_set: JoinSet<anyhow::Result<()>> = JoinSet::new();
-        for chunk in unique_symbols.chunks(self.ws_symbols_limit) {
-            let ws_endpoint = ws_endpoint.clone();
-            let topics = [order_book_increment_topic(chunk)];
-            let api_token = api_token.clone();
-            let token = token.clone();
-
-            tasks_set.spawn(Self::handle_events_task(
-                ws_endpoint,
-                topics,
-                api_token,
-                token,
-                ping_interval,
+        let mut startups = vec![];
+        for (idx, chunk) in chunks.into_iter().enumerate() {
+            let symbols: Vec<String> = chunk.iter().map(|&symbol| symbol.to_owned()).collect();
+            let started = self.max_failed_streams.map(|_| {
+                let (started_tx, started_rx) = oneshot::channel();
+                startups.push((symbols.clone(), started_rx));
+                started_tx
+            });
+            tasks_set.spawn(self.clone().run_events_task(
+                bullet.clone(),
+                format!("ws-{idx}"),
+                symbols,
+                token.clone(),
+                started,
             ));
         }
 
+        let mut failed_symbols = vec![];
+        for (symbols, started) in startups {
+            if let Ok(Err(e)) = started.await {
+                warn!(
+                    ?symbols,
+                    error = ?e,
+                    "⚠️ [Network] Streams failed to connect, dropped"
+                );
+                failed_symbols.extend(symbols);
+            }
+        }
+        if let Some(max_failed_streams) = self.max_failed_streams
+            && failed_symbols.len() > max_failed_streams
+        {
+            bail!(
+                "{} of {} streams failed to connect, more than max_failed_streams \
+                 ({max_failed_streams})",
+                failed_symbols.len(),
+                unique_symbols.len()
+            );
+        }
+        let subscribed_symbols = unique_symbols.len() - failed_symbols.len();
+        let chains = Self::drop_failed_chains(chains, &failed_symbols);
+        METRICS.record_symbol_subscriptions(subscribed_symbols, Self::chain_subscriptions(&chains));
+        _ = ready.send(chains);
+
         while let Some(result) = tasks_set.join_next().await {
             match result {
                 Ok(Err(e)) => {
@@ -108,21 +202,163 @@ impl TickerBuilder {
         Ok(())
     }
 
-    /// Handles a chunk of book ticker streams in a dedicated WebSocket connection.
+    /// Returns the ticker subscriptions of the chains, one per chain symbol.
+    fn chain_subscriptions(chains: &[[ChainSymbol; 3]]) -> usize {
+        chains.iter().map(|chain| chain.len()).sum()
+    }
+
+    /// Drops the chains with a symbol among `failed_symbols`, logging them.
+    fn drop_failed_chains(
+        chains: Vec<[ChainSymbol; 3]>,
+        failed_symbols: &[String],
+    ) -> Vec<[ChainSymbol; 3]> {
+        let failed: HashSet<&str> = failed_symbols.iter().map(String::as_str).collect();
+        let (kept, dropped): (Vec<_>, Vec<_>) = chains.into_iter().partition(|chain| {
+            chain
+                .iter()
+                .all(|chain_symbol| !failed.contains(chain_symbol.symbol.symbol.as_str()))
+        });
+        for chain in &dropped {
+            warn!(
+                chain = ?chain::extract_chain_symbols(chain),
+                "⚠️ [Network] Chain dropped, a stream it depends on failed to connect"
+            );
+        }
+        kept
+    }
+
+    /// Verifies that every chain symbol is subscribed to, since a chain missing one order book
+    /// never completes. Fails or only warns, as configured.
+    fn check_subscriptions(
+        &self,
+        chains: &[[ChainSymbol; 3]],
+        subscribed: &[&str],
+    ) -> anyhow::Result<()> {
+        let subscribed: HashSet<&str> = subscribed.iter().copied().collect();
+        let missing: BTreeSet<&str> = chains
+            .iter()
+            .flatten()
+            .map(|chain_symbol| chain_symbol.symbol.symbol.as_str())
+            .filter(|symbol| !subscribed.contains(symbol))
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let missing: Vec<&str> = missing.into_iter().collect();
+        if self.fail_on_missing_subscriptions {
+            bail!(
+                "No stream subscribed for chain symbols: {}",
+                missing.join(", ")
+            );
+        }
+        warn!(
+            ?missing,
+            "⚠️ [Network] Chain symbols without a stream subscription, their chains never complete"
+        );
+        Ok(())
+    }
+
+    /// Keeps a chunk of symbols subscribed until cancelled. With [`ReconnectStrategy::Rebuild`]
+    /// a dropped connection ends the task instead, which stops all streams so the exchange
+    /// service is restarted.
+    ///
+    /// Resubscriptions back off exponentially up to the configured bound and fetch a fresh
+    /// bullet first, since the one the connection was opened with may have expired.
+    ///
+    /// With `started` the outcome of the first connection is sent on it, and a failed one
+    /// ends the task instead of being retried.
+    async fn run_events_task(
+        self,
+        mut bullet: Bullet,
+        connection: String,
+        symbols: Vec<String>,
+        token: CancellationToken,
+        mut started: Option<oneshot::Sender<anyhow::Result<()>>>,
+    ) -> anyhow::Result<()> {
+        let symbols: Vec<&str> = symbols.iter().map(String::as_str).collect();
+        let mut backoff = ExponentialBackoff::new(
+            Self::RESUBSCRIBE_DELAY,
+            self.max_reconnect_backoff,
+            Self::BACKOFF_RESET_AFTER,
+        );
+        let mut disconnected_at: Option<Instant> = None;
+        loop {
+            let topics = [order_book_increment_topic(&symbols)];
+            let on_connected = || {
+                if let Some(started) = started.take() {
+                    _ = started.send(Ok(()));
+                }
+                backoff.reset();
+                if let Some(disconnected_at) = disconnected_at.take() {
+                    METRICS.record_ws_reconnect();
+                    warn!(
+                        downtime_ms = disconnected_at.elapsed().as_millis(),
+                        "🔌 [Network] WebSocket resubscribed"
+                    );
+                }
+            };
+            let result =
+                Self::handle_events_task(&bullet, &connection, topics, token.clone(), on_connected)
+                    .await;
+            if let Some(started) = started.take() {
+                _ = started.send(result);
+                return Ok(());
+            }
+            if token.is_cancelled() {
+                return result;
+            }
+
+            RUN_STATS.lock().await.record_reconnection();
+            let delay = backoff.next_delay();
+            match self.reconnect_strategy {
+                ReconnectStrategy::Rebuild => {
+                    warn!("🔌 [Network] WebSocket disconnected, rebuilding exchange service");
+                    return result;
+                }
+                ReconnectStrategy::Resubscribe => {
+                    warn!(
+                        error = ?result.err(),
+                        retry_in_ms = delay.as_millis(),
+                        "🔌 [Network] WebSocket disconnected, resubscribing"
+                    );
+                }
+            }
+            disconnected_at.get_or_insert_with(Instant::now);
+
+            tokio::select! {
+                () = token.cancelled() => return Ok(()),
+                () = tokio::time::sleep(delay) => {}
+            }
+
+            match Bullet::fetch(&self.base_info_api).await {
+                Ok(fresh) => bullet = fresh,
+                Err(e) => warn!(error = ?e, "Failed to refresh bullet, reusing the previous one"),
+            }
+        }
+    }
+
+    /// Handles a chunk of book ticker streams in a dedicated WebSocket connection, calling
+    /// `on_connected` once the topics are subscribed. Its ticks are published to [`TICKS`] as
+    /// `connection`.
     async fn handle_events_task(
-        ws_endpoint: String,
+        bullet: &Bullet,
+        connection: &str,
         topics: [Topic; 1],
-        api_token: String,
         token: CancellationToken,
-        ping_interval: u64,
+        on_connected: impl FnOnce(),
     ) -> anyhow::Result<()> {
-        let mut ws = WebsocketStream::<'_, Events>::new(ws_endpoint.clone(), ping_interval)
-            .with_callback(Self::handle_events_callback());
+        let ws_endpoint = &bullet.ws_endpoint;
+        let mut ws = WebsocketStream::<'_, Events>::new(ws_endpoint.clone(), bullet.ping_interval)
+            .with_callback(Self::handle_events_callback(connection.to_owned()));
 
-        ws.connect(&topics, api_token).await.map_err(|e| {
-            error!(error = ?e, ws_url = %ws_endpoint, "Failed to connect websocket");
-            e
-        })?;
+        ws.connect(&topics, bullet.token.clone())
+            .await
+            .map_err(|e| {
+                error!(error = ?e, ws_url = %ws_endpoint, "Failed to connect websocket");
+                e
+            })?;
+        on_connected();
 
         if let Err(e) = ws.handle_messages(token).await {
             error!(error = ?e, ws_url = %ws_endpoint, "Error while running websocket");
@@ -133,18 +369,20 @@ impl TickerBuilder {
         Ok(())
     }
 
-    fn handle_events_callback() -> impl Fn(Events) -> anyhow::Result<()> + Send + Sync + 'static {
+    fn handle_events_callback(
+        connection: String,
+    ) -> impl Fn(Events) -> anyhow::Result<()> + Send + Sync + 'static {
         move |event: Events| {
             if let Events::Message(event) = event
                 && let MessageEvents::IncrementOrderBook(message) = *event
             {
-                Self::process_order_book_update(&message)?;
+                Self::process_order_book_update(&message, &connection)?;
             }
             Ok(())
         }
     }
 
-    fn process_order_book_update(update: &Level2Update) -> anyhow::Result<()> {
+    fn process_order_book_update(update: &Level2Update, connection: &str) -> anyhow::Result<()> {
         let create_ticker_event = |symbol: &str, row: OrderRow| -> BookTickerEvent {
             let OrderRow(price, qty, sequence_id) = row;
             BookTickerEvent {
@@ -166,13 +404,206 @@ impl TickerBuilder {
         }
 
         if changes != BookTickerEventChanges::default() {
+            changes.received_at = Some(Instant::now());
             if let Err(e) = TICKER_BROADCAST.broadcast_event(changes) {
                 error!(error = ?e, symbol = %symbol, "Failed to broadcast changes event");
                 // Don't bail here to keep WS alive; just log and continue
             }
             METRICS.record_book_ticker_event(symbol);
+            TICKS.record(connection, Instant::now());
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+    use tokio::{net::TcpListener, sync::mpsc};
+    use tokio_tungstenite::{accept_async, tungstenite::Message};
+
+    use super::*;
+    use crate::libs::kucoin_client::{self, Kucoin};
+
+    #[tokio::test]
+    async fn test_dropped_connection_resubscribes() -> anyhow::Result<()> {
+        // Records the topic each connection subscribes to, then drops the first connection
+        // without a closing handshake.
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let ws_endpoint = format!("ws://{}", listener.local_addr()?);
+        let (topics_tx, mut topics_rx) = mpsc::unbounded_channel::<String>();
+        let server = tokio::spawn(async move {
+            let (mut accepted, mut connections) = (0, Vec::new());
+            while let Ok((stream, _)) = listener.accept().await {
+                let Ok(mut ws) = accept_async(stream).await else {
+                    continue;
+                };
+                if let Some(Ok(Message::Text(text))) = ws.next().await {
+                    let msg: serde_json::Value = serde_json::from_str(&text).unwrap();
+                    _ = topics_tx.send(msg["topic"].as_str().unwrap_or_default().to_owned());
+                }
+                if accepted > 0 {
+                    connections.push(ws);
+                }
+                accepted += 1;
+            }
+        });
+
+        let mut server_api = mockito::Server::new_async().await;
+        let bullet_mock = server_api
+            .mock("POST", "/api/v1/bullet-public")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{
+                    "code": "200000",
+                    "data": {{
+                        "token": "fresh_token",
+                        "instanceServers": [
+                            {{
+                                "endpoint": "{ws_endpoint}",
+                                "encrypt": false,
+                                "protocol": "websocket",
+                                "pingInterval": 18000,
+                                "pingTimeout": 10000
+                            }}
+                        ]
+                    }}
+                }}"#
+            ))
+            .expect_at_least(1)
+            .create_async()
+            .await;
+
+        let base_info_api: BaseInfo = Kucoin::new(kucoin_client::ClientConfig {
+            host: server_api.url(),
+            api_key: String::new(),
+            api_secret: String::new(),
+            api_passphrase: String::new(),
+            http_config: kucoin_client::HttpConfig::default(),
+        })?;
+        let ticker_builder =
+            TickerBuilder::new(base_info_api, false, ReconnectStrategy::Resubscribe);
+        let bullet = Bullet {
+            token: "expired_token".to_owned(),
+            ws_endpoint,
+            ping_interval: 18000,
+        };
+
+        let token = CancellationToken::new();
+        let task = tokio::spawn(ticker_builder.run_events_task(
+            bullet,
+            "ws-0".to_owned(),
+            vec!["BTC-USDT".to_owned(), "ETH-BTC".to_owned()],
+            token.clone(),
+            None,
+        ));
+
+        let wait = Duration::from_secs(5);
+        let first = tokio::time::timeout(wait, topics_rx.recv()).await?;
+        let resubscribed = tokio::time::timeout(wait, topics_rx.recv()).await?;
+        token.cancel();
+        task.await??;
+        server.abort();
+
+        bullet_mock.assert_async().await;
+        assert_eq!(first, Some("/market/level2:BTC-USDT,ETH-BTC".to_owned()));
+        assert_eq!(resubscribed, first);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_failed_first_connection_is_reported_not_retried() -> anyhow::Result<()> {
+        // Nothing listens on the endpoint once the listener is dropped.
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let ws_endpoint = format!("ws://{}", listener.local_addr()?);
+        drop(listener);
+
+        let base_info_api: BaseInfo = Kucoin::new(kucoin_client::ClientConfig {
+            host: "http://127.0.0.1:1".to_owned(),
+            api_key: String::new(),
+            api_secret: String::new(),
+            api_passphrase: String::new(),
+            http_config: kucoin_client::HttpConfig::default(),
+        })?;
+        let ticker_builder =
+            TickerBuilder::new(base_info_api, false, ReconnectStrategy::Resubscribe)
+                .with_max_failed_streams(Some(1));
+        let bullet = Bullet {
+            token: "token".to_owned(),
+            ws_endpoint,
+            ping_interval: 18000,
+        };
+
+        let (started_tx, started_rx) = oneshot::channel();
+        let task = ticker_builder.run_events_task(
+            bullet,
+            "ws-0".to_owned(),
+            vec!["BTC-USDT".to_owned()],
+            CancellationToken::new(),
+            Some(started_tx),
+        );
+
+        // The task ends on the failed connection instead of resubscribing.
+        tokio::time::timeout(Duration::from_secs(5), task).await??;
+        assert!(started_rx.await?.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_failed_streams_drop_their_chains() {
+        use engine::enums::SymbolOrder;
+        use rust_decimal::Decimal;
+
+        use crate::libs::kucoin_client::{enums::MarketType, models::Symbol};
+
+        let chain_symbol = |name: &str| {
+            let (base, quote) = name.split_once('-').unwrap();
+            ChainSymbol::new(
+                Symbol {
+                    symbol: name.to_owned(),
+                    name: name.to_owned(),
+                    base_currency: base.to_owned(),
+                    quote_currency: quote.to_owned(),
+                    fee_currency: quote.to_owned(),
+                    market: MarketType::Usds,
+                    base_min_size: Decimal::ZERO,
+                    quote_min_size: Decimal::ZERO,
+                    base_max_size: Decimal::MAX,
+                    quote_max_size: Decimal::MAX,
+                    base_increment: Decimal::new(1, 8),
+                    quote_increment: Decimal::new(1, 8),
+                    price_increment: Decimal::new(1, 8),
+                    price_limit_rate: Decimal::ZERO,
+                    min_funds: None,
+                    is_margin_enabled: false,
+                    enable_trading: true,
+                    fee_category: 1,
+                    maker_fee_coefficient: Decimal::ONE,
+                    taker_fee_coefficient: Decimal::ONE,
+                    st: false,
+                    callauction_is_enabled: false,
+                },
+                SymbolOrder::Asc,
+            )
+        };
+        let chains = vec![
+            ["BTC-USDT", "ETH-BTC", "ETH-USDT"].map(chain_symbol),
+            ["BTC-USDT", "SOL-BTC", "SOL-USDT"].map(chain_symbol),
+            ["BTC-USDT", "XRP-BTC", "XRP-USDT"].map(chain_symbol),
+        ];
+
+        let kept = TickerBuilder::drop_failed_chains(
+            chains,
+            &["SOL-BTC".to_owned(), "XRP-USDT".to_owned()],
+        );
+        let kept: Vec<_> = kept
+            .iter()
+            .map(|chain| chain::extract_chain_symbols(chain))
+            .collect();
+        assert_eq!(kept, [["BTC-USDT", "ETH-BTC", "ETH-USDT"]]);
+    }
+}
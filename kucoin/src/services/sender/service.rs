@@ -1,15 +1,19 @@
-use std::time::Duration;
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use anyhow::{Context, anyhow, bail};
 use async_trait::async_trait;
 use engine::{
-    ChainOrder, ChainOrders, METRICS, ORDERS_CHANNEL, REQUEST_WEIGHT, Sender,
-    enums::{ChainStatus, SymbolOrder},
+    ChainOrder, ChainOrders, ClockDrift, ExecutionCap, FILLED_CHAINS, FilledChain,
+    IN_FLIGHT_CHAINS, KILL_SWITCH, KillSwitch, METRICS, ORDERS_CHANNEL, OpportunityWatchdog,
+    OrderSizeRamp, ProfitPersistence, REQUEST_WEIGHT, RUN_STATS, RealizedPnl, RepeatGuard, Sender,
+    SizeRamp,
+    enums::{self, ChainStatus, SymbolOrder},
     service::traits::ArbitrageService,
 };
 use rust_decimal::{Decimal, prelude::Zero};
 use tokio::{sync::mpsc, task::JoinSet, time::Instant};
 use tokio_util::sync::CancellationToken;
+use tools::misc;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
@@ -18,8 +22,9 @@ use crate::{
     libs::{
         kucoin_client,
         kucoin_client::{
-            BaseInfo, Kucoin,
-            enums::{OrderSide, OrderStatus, OrderType},
+            BaseInfo, Kucoin, Trade,
+            enums::{OrderSide, OrderStatus, TimeInForce},
+            models::OrderDetails,
             stream::{Events, MessageEvents, OrderChange, WebsocketStream, order_change_topic},
             ws,
             ws::{AddOrderRequest, WebsocketClient},
@@ -32,35 +37,94 @@ use crate::{
 pub struct SenderService {
     send_orders: bool,
     process_chain_interval: Duration,
+    min_profit_persist: Duration,
+    min_price_change_bps: Option<u32>,
+    max_chain_executions: Option<u32>,
+    chain_executions_window: Duration,
+    profit_targets: HashMap<String, Decimal>,
+    max_daily_losses: HashMap<String, Decimal>,
+    size_ramp: Option<SizeRamp>,
+    exit_on_session_profit_target: bool,
     ws_url: String,
     api_token: String,
     api_secret: String,
     api_passphrase: String,
     base_info_api: BaseInfo,
+    trade_api: Trade,
+    clock_drift: Option<Arc<ClockDrift>>,
+    opportunity_watchdog: Option<Arc<OpportunityWatchdog>>,
+    kill_switch: Arc<KillSwitch>,
 }
 
-impl Sender for SenderService {}
+#[async_trait]
+impl Sender for SenderService {
+    async fn query_order(
+        &self,
+        symbol: &str,
+        client_order_id: &str,
+    ) -> anyhow::Result<enums::OrderStatus> {
+        let response = self
+            .trade_api
+            .get_order_by_client_oid(symbol, client_order_id)
+            .await
+            .with_context(|| format!("Failed to query order {client_order_id}"))?;
+
+        Ok(order_status(&response.data))
+    }
+}
 
 #[async_trait]
 impl ArbitrageService for SenderService {
     async fn start(&self, token: CancellationToken) -> anyhow::Result<()> {
         let mut tasks: JoinSet<anyhow::Result<()>> = JoinSet::new();
         let (order_change_tx, order_change_rx) = mpsc::unbounded_channel();
+        // Order streams outlive the shutdown signal until the chain in flight has resolved.
+        let streams_token = CancellationToken::new();
 
         // Run balance stream listener
         tasks.spawn({
             let this = self.clone();
-            let token = token.clone();
-            async move { this.listen_balance_stream(token, order_change_tx).await }
+            let streams_token = streams_token.clone();
+            async move {
+                this.listen_balance_stream(streams_token, order_change_tx)
+                    .await
+            }
         });
 
         // Run send orders
         tasks.spawn({
             let this = self.clone();
             let token = token.clone();
-            async move { this.receive_and_send_orders(token, order_change_rx).await }
+            async move {
+                this.receive_and_send_orders(token, streams_token, order_change_rx)
+                    .await
+            }
         });
 
+        // Run clock drift monitor
+        if self.clock_drift.is_some() {
+            tasks.spawn({
+                let this = self.clone();
+                let token = token.clone();
+                async move {
+                    this.monitor_clock_drift(token).await;
+                    Ok(())
+                }
+            });
+        }
+
+        // Run opportunity watchdog
+        if let Some(watchdog) = &self.opportunity_watchdog {
+            tasks.spawn({
+                let watchdog = watchdog.clone();
+                let token = token.clone();
+                async move {
+                    watchdog.monitor(token).await;
+                    Ok(())
+                }
+            });
+        }
+
         while let Some(result) = tasks.join_next().await {
             match result {
                 Ok(Err(e)) => {
@@ -88,6 +152,7 @@ impl SenderService {
             let mut weight_lock = REQUEST_WEIGHT.lock().await;
             weight_lock.set_weight_limit(config.api_weight_limit);
         }
+        KILL_SWITCH.set_max_failures(config.max_consecutive_failed_chains);
 
         let api_config = kucoin_client::ClientConfig {
             host: config.api_url.clone(),
@@ -97,16 +162,45 @@ impl SenderService {
             http_config: kucoin_client::HttpConfig::default(),
         };
         let base_info_api: BaseInfo =
-            Kucoin::new(api_config).context("Failed to create kucoin base info api")?;
+            Kucoin::new(api_config.clone()).context("Failed to create kucoin base info api")?;
+        let trade_api: Trade =
+            Kucoin::new(api_config).context("Failed to create kucoin trade api")?;
 
         Ok(Self {
             send_orders: config.send_orders,
             process_chain_interval: Duration::from_secs(5),
+            min_profit_persist: Duration::from_millis(config.min_profit_persist_ms),
+            min_price_change_bps: config.min_price_change_bps,
+            max_chain_executions: config.max_chain_executions,
+            chain_executions_window: Duration::from_secs(config.chain_executions_window_secs),
+            profit_targets: config
+                .assets
+                .iter()
+                .filter_map(|asset| Some((asset.asset.clone(), asset.session_profit_target?)))
+                .collect(),
+            max_daily_losses: config
+                .assets
+                .iter()
+                .filter_map(|asset| Some((asset.asset.clone(), asset.max_daily_loss?)))
+                .collect(),
+            size_ramp: config.size_ramp,
+            exit_on_session_profit_target: config.exit_on_session_profit_target,
             ws_url: config.ws_private_url.clone(),
             api_token: config.api_token.clone(),
             api_secret: config.api_secret_key.clone(),
             api_passphrase: config.api_passphrase.clone(),
             base_info_api,
+            trade_api,
+            clock_drift: config
+                .max_clock_drift_ms
+                .map(|ms| Arc::new(ClockDrift::new(Duration::from_millis(ms)))),
+            opportunity_watchdog: config.no_opportunity_alert_after_secs.map(|secs| {
+                Arc::new(OpportunityWatchdog::new(
+                    Duration::from_secs(secs),
+                    Instant::now(),
+                ))
+            }),
+            kill_switch: Arc::clone(&KILL_SWITCH),
         })
     }
 
@@ -156,14 +250,44 @@ impl SenderService {
         Ok(())
     }
 
+    /// Periodically compares the local clock with the KuCoin server time.
+    async fn monitor_clock_drift(&self, token: CancellationToken) {
+        let Some(clock_drift) = &self.clock_drift else {
+            return;
+        };
+
+        clock_drift
+            .monitor(token, ClockDrift::MEASURE_INTERVAL, || async {
+                let response = self.base_info_api.get_server_time().await?;
+                Ok(u128::from(response.data))
+            })
+            .await;
+    }
+
+    /// Returns false while the clock drift is too large to place signed orders.
+    fn trading_allowed(&self) -> bool {
+        if let Some(clock_drift) = &self.clock_drift
+            && let Err(e) = clock_drift.check()
+        {
+            warn!(error = %e, "⏰ [Engine] Refusing to send chain orders");
+            return false;
+        }
+        true
+    }
+
     /// Main loop for receiving arbitrage chains and sending orders.
     /// Monitors watch channel for chains, processes with rate limiting,
     /// and integrates order change updates from receiver channel.
+    ///
+    /// No chain is taken once `token` is cancelled, while the order streams keep running
+    /// until `streams_token` is cancelled on return, so that the chain in flight resolves.
     async fn receive_and_send_orders(
         &self,
         token: CancellationToken,
+        streams_token: CancellationToken,
         mut order_change_rx: mpsc::UnboundedReceiver<OrderChange>,
     ) -> anyhow::Result<()> {
+        let _stop_streams = streams_token.clone().drop_guard();
         let mut ws_client = ws::connect_ws(
             ws::ConnectConfig {
                 ws_url: self.ws_url.clone(),
@@ -171,18 +295,28 @@ impl SenderService {
                 secret_key: self.api_secret.clone(),
                 passphrase: self.api_passphrase.clone(),
             },
-            token.clone(),
+            streams_token.clone(),
         )
         .await?;
 
         let mut orders_rx = ORDERS_CHANNEL.rx.lock().await;
         let mut last_chain_exec_ts: Option<Instant> = None;
+        let mut persistence = ProfitPersistence::new(self.min_profit_persist);
+        let mut repeat_guard = self.min_price_change_bps.map(RepeatGuard::new);
+        let mut execution_cap = self
+            .max_chain_executions
+            .map(|max| ExecutionCap::new(max, self.chain_executions_window));
+        let mut pnl = RealizedPnl::new(self.profit_targets.clone(), self.max_daily_losses.clone());
+        let mut size_ramp = self.size_ramp.map(OrderSizeRamp::new);
 
         // Get the initial value from watch channel
         _ = orders_rx.borrow().clone();
 
         loop {
             tokio::select! {
+                // Checked first: no new chain is taken once shutting down.
+                biased;
+
                 _ = token.cancelled() => {
                     break;
                 }
@@ -191,28 +325,117 @@ impl SenderService {
                     let chain = orders_rx.borrow().clone();
                     let chain_symbols = chain.extract_symbols();
 
+                    // Every chain in the channel has passed the profit gate.
+                    if let Some(watchdog) = &self.opportunity_watchdog {
+                        watchdog.record(Instant::now()).await;
+                    }
+                    RUN_STATS.lock().await.record_processed();
+
                     if !self.send_orders {
                         chain.print_info(self.send_orders);
                         continue;
                     }
 
+                    // Skip opportunities that have not stayed profitable long enough yet.
+                    if !persistence.observe(&chain, Instant::now()) {
+                        RUN_STATS.lock().await.record_rejection("not_persisted");
+                        continue;
+                    }
+
                     if last_chain_exec_ts.is_some_and(|t| t.elapsed() < self.process_chain_interval) {
+                        RUN_STATS.lock().await.record_rejection("interval");
                         continue;
                     }
 
-                    chain.print_info(self.send_orders);
-                    METRICS.record_chain_status(&chain_symbols, &ChainStatus::New);
+                    // Same prices as the last execution of this chain: nothing new to capture.
+                    if repeat_guard.as_ref().is_some_and(|guard| guard.is_repeat(&chain)) {
+                        RUN_STATS.lock().await.record_rejection("repeat");
+                        continue;
+                    }
 
-                    if let Err(e) =
-                        Self::process_chain_orders(&mut ws_client, &mut order_change_rx, chain.clone()).await
+                    // Executed as often as allowed within the chain's current window.
+                    if execution_cap
+                        .as_ref()
+                        .is_some_and(|cap| cap.is_capped(&chain, Instant::now()))
                     {
-                        METRICS.record_chain_status(&chain_symbols, &ChainStatus::Cancelled);
-                        error!(error = ?e, "❌ [Engine] Error processing chain orders");
-                        break;
+                        RUN_STATS.lock().await.record_rejection("execution_cap");
+                        continue;
+                    }
+
+                    if !self.trading_allowed() {
+                        RUN_STATS.lock().await.record_rejection("clock_drift");
+                        continue;
+                    }
+
+                    // Too many chains failed in a row, halted until restart.
+                    if self.kill_switch.is_halted() {
+                        RUN_STATS.lock().await.record_rejection("kill_switch");
+                        continue;
                     }
 
+                    // Session profit target reached or daily loss limit exceeded.
+                    if pnl.check(misc::time::get_current_timestamp().as_secs()).is_err() {
+                        RUN_STATS.lock().await.record_rejection("pnl_limit");
+                        continue;
+                    }
+
+                    // Sized down while ramping up.
+                    let chain = size_ramp
+                        .as_ref()
+                        .map_or_else(|| chain.clone(), |ramp| ramp.apply(&chain));
+                    chain.print_info(self.send_orders);
+                    METRICS.record_chain_status(&chain_symbols, &ChainStatus::New);
+
+                    let in_flight = IN_FLIGHT_CHAINS.enter();
+                    let result = Self::process_chain_orders(&mut ws_client, &mut order_change_rx, chain.clone()).await;
+                    drop(in_flight);
+                    let profit = match result {
+                        Ok(profit) => {
+                            self.kill_switch.record_success();
+                            profit
+                        }
+                        Err(e) => {
+                            self.kill_switch.record_failure();
+                            METRICS.record_chain_status(&chain_symbols, &ChainStatus::Cancelled);
+                            error!(error = ?e, "❌ [Engine] Error processing chain orders");
+                            // Without a kill switch the first failed chain stops sending,
+                            // otherwise the switch decides when to stop trading.
+                            if !self.kill_switch.is_enabled() {
+                                break;
+                            }
+                            // Changes of the failed chain's orders must not fill the next one.
+                            while order_change_rx.try_recv().is_ok() {}
+                            last_chain_exec_ts = Some(Instant::now());
+                            continue;
+                        }
+                    };
+
                     last_chain_exec_ts = Some(Instant::now());
+                    if let Some(guard) = repeat_guard.as_mut() {
+                        guard.record(&chain);
+                    }
+                    if let Some(cap) = execution_cap.as_mut() {
+                        cap.record(&chain, Instant::now());
+                    }
                     METRICS.record_chain_status(&chain_symbols, &ChainStatus::Filled);
+
+                    RUN_STATS.lock().await.record_execution(&chain.base_asset, profit);
+                    if let Some(ramp) = size_ramp.as_mut() {
+                        ramp.record(profit);
+                    }
+                    pnl.record(
+                        &chain.base_asset,
+                        profit,
+                        misc::time::get_current_timestamp().as_secs(),
+                    );
+                    METRICS.record_realized_profit(
+                        &chain.base_asset,
+                        pnl.session_profit(&chain.base_asset),
+                    );
+                    if self.exit_on_session_profit_target && pnl.profit_target_reached() {
+                        info!("🛑 [Engine] Session profit target reached, shutting down");
+                        break;
+                    }
                 }
             }
         }
@@ -223,23 +446,32 @@ impl SenderService {
 
     /// Processes an entire arbitrage chain by sequentially placing orders.
     /// Computes quantities based on previous fills (with fee adjustment) and waits for fills via
-    /// channel. Logs the final profit.
+    /// channel. Returns the realized profit.
     async fn process_chain_orders(
         ws_client: &mut WebsocketClient,
         order_change_rx: &mut mpsc::UnboundedReceiver<OrderChange>,
         chain: ChainOrders,
-    ) -> anyhow::Result<()> {
-        let build_order_request =
-            |order: &ChainOrder, size: Option<String>, funds: Option<String>| -> AddOrderRequest {
-                AddOrderRequest {
-                    client_oid: Uuid::new_v4().to_string(),
-                    symbol: order.symbol.clone(),
-                    order_type: OrderType::Market,
-                    order_side: define_order_side(order),
-                    size,
-                    funds,
-                }
+    ) -> anyhow::Result<Decimal> {
+        let build_order_request = |order: &ChainOrder,
+                                   size: Option<String>,
+                                   funds: Option<String>|
+         -> AddOrderRequest {
+            // Limit legs are immediate-or-cancel at the chain price.
+            let (price, time_in_force) = match order.order_type {
+                enums::OrderType::Market => (None, None),
+                enums::OrderType::Limit => (Some(order.price.to_string()), Some(TimeInForce::Ioc)),
             };
+            AddOrderRequest {
+                client_oid: Uuid::new_v4().to_string(),
+                symbol: order.symbol.clone(),
+                order_type: order.order_type.into(),
+                order_side: define_order_side(order),
+                price,
+                time_in_force,
+                size,
+                funds,
+            }
+        };
 
         let mut filled_sizes = Vec::with_capacity(chain.orders.len());
         let mut last_filled_size: Option<Decimal> = None;
@@ -273,7 +505,14 @@ impl SenderService {
             "✅ [Engine] Chain completed: profit calculated"
         );
 
-        Ok(())
+        // Nobody may be listening for filled chains, which is fine.
+        _ = FILLED_CHAINS.send(FilledChain {
+            chain,
+            fills: filled_sizes,
+            profit,
+        });
+
+        Ok(profit)
     }
 
     /// Places a single order and waits for fill updates via the order change channel.
@@ -289,6 +528,12 @@ impl SenderService {
             Err(e) => bail!(e),
         };
 
+        if order_idx == 0
+            && let Some(queued_at) = chain.queued_at
+        {
+            METRICS.record_first_leg_ack_latency(queued_at.elapsed());
+        }
+
         let order = &chain.orders[order_idx];
         let mut stats_filled_qty = Decimal::zero();
         let mut filled_qty = Decimal::zero();
@@ -319,6 +564,11 @@ impl SenderService {
             }
         }
 
+        // An IOC limit leg is done unfilled once the book moves past the chain price.
+        if filled_qty.is_zero() {
+            bail!("Order {order_id} on {} was not filled", order.symbol);
+        }
+
         info!(
             chain_id = chain.chain_id.to_string(),
             order_index = order_idx + 1,
@@ -400,10 +650,10 @@ impl SenderService {
                 (Some(size.to_string()), None)
             }
             SymbolOrder::Desc => {
-                let size = ((filled_size * (Decimal::ONE - fee_rate)) / order.quote_increment)
+                let funds = ((filled_size * (Decimal::ONE - fee_rate)) / order.quote_increment)
                     .floor()
                     * order.quote_increment;
-                (None, Some(size.to_string()))
+                desc_quantities(order, funds)
             }
         }
     }
@@ -437,6 +687,149 @@ fn define_order_side(order: &ChainOrder) -> OrderSide {
 fn define_order_quantities(order: &ChainOrder) -> (Option<String>, Option<String>) {
     match order.symbol_order {
         SymbolOrder::Asc => (Some(order.base_qty.to_string()), None),
-        SymbolOrder::Desc => (None, Some(order.base_qty.to_string())),
+        SymbolOrder::Desc => desc_quantities(order, order.base_qty),
+    }
+}
+
+/// Maps the quote amount a Desc leg spends onto `(size, funds)`. Limit orders take no funds,
+/// so they buy the size the amount covers at the chain price, rounded down to the increment.
+fn desc_quantities(order: &ChainOrder, funds: Decimal) -> (Option<String>, Option<String>) {
+    match order.order_type {
+        enums::OrderType::Market => (None, Some(funds.to_string())),
+        enums::OrderType::Limit => {
+            let size = (funds / order.price / order.base_increment).floor() * order.base_increment;
+            (Some(size.normalize().to_string()), None)
+        }
+    }
+}
+
+/// Derives the exchange-agnostic status of an HF order from its details.
+fn order_status(details: &OrderDetails) -> enums::OrderStatus {
+    let has_fills = details.deal_size > Decimal::ZERO;
+
+    match (details.active, details.cancel_exist) {
+        (true, _) if has_fills => enums::OrderStatus::PartiallyFilled,
+        (true, _) => enums::OrderStatus::New,
+        (false, true) => enums::OrderStatus::Canceled,
+        (false, false) => enums::OrderStatus::Filled,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockito::{Matcher, Server};
+
+    use super::*;
+
+    fn sender(host: String) -> SenderService {
+        let api_config = kucoin_client::ClientConfig {
+            host,
+            api_key: "test_api_key".to_owned(),
+            api_secret: "test_api_secret".to_owned(),
+            api_passphrase: "test_passphrase".to_owned(),
+            http_config: kucoin_client::HttpConfig::default(),
+        };
+
+        SenderService {
+            send_orders: true,
+            process_chain_interval: Duration::from_secs(5),
+            min_profit_persist: Duration::ZERO,
+            min_price_change_bps: None,
+            max_chain_executions: None,
+            chain_executions_window: Duration::from_secs(3600),
+            profit_targets: HashMap::new(),
+            max_daily_losses: HashMap::new(),
+            size_ramp: None,
+            exit_on_session_profit_target: false,
+            ws_url: String::new(),
+            api_token: "test_api_key".to_owned(),
+            api_secret: "test_api_secret".to_owned(),
+            api_passphrase: "test_passphrase".to_owned(),
+            base_info_api: Kucoin::new(api_config.clone()).unwrap(),
+            trade_api: Kucoin::new(api_config).unwrap(),
+            clock_drift: None,
+            opportunity_watchdog: None,
+            kill_switch: Arc::default(),
+        }
+    }
+
+    async fn query(active: bool, cancel_exist: bool, deal_size: &str) -> enums::OrderStatus {
+        let mut server = Server::new_async().await;
+        let body = format!(
+            r#"{{
+                "code": "200000",
+                "data": {{
+                    "id": "6717422bd51c29000775ea03",
+                    "clientOid": "arb-1",
+                    "symbol": "BTC-USDT",
+                    "size": "0.001",
+                    "dealSize": "{deal_size}",
+                    "active": {active},
+                    "cancelExist": {cancel_exist}
+                }}
+            }}"#
+        );
+        let mock = server
+            .mock("GET", "/api/v1/hf/orders/client-order/arb-1")
+            .match_query(Matcher::UrlEncoded("symbol".into(), "BTC-USDT".into()))
+            .match_header("KC-API-KEY", "test_api_key")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body)
+            .create_async()
+            .await;
+
+        let status = sender(server.url())
+            .query_order("BTC-USDT", "arb-1")
+            .await
+            .unwrap();
+        mock.assert_async().await;
+        status
+    }
+
+    #[tokio::test]
+    async fn test_query_order_maps_status() {
+        assert_eq!(query(true, false, "0").await, enums::OrderStatus::New);
+        assert_eq!(
+            query(true, false, "0.0005").await,
+            enums::OrderStatus::PartiallyFilled
+        );
+        assert_eq!(
+            query(false, false, "0.001").await,
+            enums::OrderStatus::Filled
+        );
+        assert_eq!(
+            query(false, true, "0.0005").await,
+            enums::OrderStatus::Canceled
+        );
+    }
+
+    #[test]
+    fn test_limit_desc_leg_sized_in_base() {
+        let order = ChainOrder {
+            symbol: "BTC-USDT".to_owned(),
+            symbol_order: SymbolOrder::Desc,
+            order_type: enums::OrderType::Market,
+            price: Decimal::from(100_000),
+            base_qty: Decimal::new(1005, 1),
+            quote_qty: Decimal::new(1, 3),
+            base_increment: Decimal::new(1, 5),
+            quote_increment: Decimal::new(1, 2),
+            min_qty: Decimal::ZERO,
+        };
+        assert_eq!(
+            define_order_quantities(&order),
+            (None, Some("100.5".to_owned()))
+        );
+
+        // 100.5 USDT at 100000 covers 0.001005 BTC, rounded down to the increment.
+        let order = ChainOrder {
+            order_type: enums::OrderType::Limit,
+            ..order
+        };
+        assert_eq!(
+            define_order_quantities(&order),
+            (Some("0.001".to_owned()), None)
+        );
     }
 }
@@ -1,6 +1,8 @@
 use std::collections::{HashMap, hash_map::Entry};
 
+use engine::METRICS;
 use rust_decimal::Decimal;
+use tokio::time::Instant;
 
 /// Changes in book ticker events (bid/ask updates for a symbol).
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -11,6 +13,8 @@ pub struct BookTickerEventChanges {
     pub bid: Option<BookTickerEvent>,
     /// Optional ask update event.
     pub ask: Option<BookTickerEvent>,
+    /// When the changes were received from the exchange.
+    pub received_at: Option<Instant>,
 }
 
 /// A single book ticker event (price/quantity update).
@@ -54,15 +58,13 @@ impl BookTickerStore {
     }
 
     /// Updates the store with an optional event if valid (non-zero price/qty and newer sequence).
-    /// Returns true if updated or no event (always succeeds unless invalid).
+    /// Returns whether the event was stored. The store holds a single book side, so sequences
+    /// are compared per side; stale events are counted in [`METRICS`].
     pub fn update_if_valid(&mut self, event: Option<BookTickerEvent>) -> bool {
-        if let Some(event) = event {
-            if event.price.is_zero() || event.qty.is_zero() {
-                return false;
-            }
-            self.update(event);
+        match event {
+            Some(event) if !event.price.is_zero() && !event.qty.is_zero() => self.update(event),
+            _ => false,
         }
-        true
     }
 
     /// Retrieves the latest event for a symbol.
@@ -84,16 +86,78 @@ impl BookTickerStore {
     }
 
     /// Internal update: inserts or replaces if sequence_id is newer.
-    fn update(&mut self, event: BookTickerEvent) {
+    fn update(&mut self, event: BookTickerEvent) -> bool {
         match self.data.entry(event.symbol.clone()) {
             Entry::Occupied(mut entry) => {
-                if event.sequence_id > entry.get().sequence_id {
-                    entry.insert(event);
+                if event.sequence_id <= entry.get().sequence_id {
+                    METRICS.record_stale_book_ticker_event(&event.symbol);
+                    return false;
                 }
+                entry.insert(event);
             }
             Entry::Vacant(entry) => {
                 entry.insert(event);
             }
         }
+        true
+    }
+}
+
+/// Returns whether the best bid of `symbol` in `bids` is at or above its best ask in `asks`.
+/// The sides are updated separately, a symbol missing from either is not crossed.
+#[must_use]
+pub fn is_crossed(bids: &BookTickerStore, asks: &BookTickerStore, symbol: &str) -> bool {
+    match (bids.get(symbol), asks.get(symbol)) {
+        (Some(bid), Some(ask)) => bid.price >= ask.price,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(sequence_id: u64, price: i64) -> Option<BookTickerEvent> {
+        Some(BookTickerEvent {
+            sequence_id,
+            symbol: "BTC-USDT".to_owned(),
+            price: Decimal::from(price),
+            qty: Decimal::ONE,
+        })
+    }
+
+    #[test]
+    fn test_out_of_order_events_dropped_per_side() {
+        let (mut bids, mut asks) = (BookTickerStore::new(), BookTickerStore::new());
+
+        assert!(bids.update_if_valid(event(5, 100)));
+        // The ask side has its own sequence, an older one is still its newest.
+        assert!(asks.update_if_valid(event(3, 101)));
+        assert!(!bids.update_if_valid(event(4, 90)));
+        assert!(!asks.update_if_valid(event(3, 99)));
+        assert!(!bids.update_if_valid(event(6, 0)));
+        assert!(!bids.update_if_valid(None));
+
+        assert_eq!(bids.get("BTC-USDT").unwrap().price, Decimal::from(100));
+        assert_eq!(asks.get("BTC-USDT").unwrap().price, Decimal::from(101));
+
+        assert!(asks.update_if_valid(event(4, 102)));
+        assert_eq!(asks.get("BTC-USDT").unwrap().sequence_id, 4);
+    }
+
+    #[test]
+    fn test_crossed_book_from_separate_sides() {
+        let (mut bids, mut asks) = (BookTickerStore::new(), BookTickerStore::new());
+        bids.update_if_valid(event(1, 100));
+        assert!(!is_crossed(&bids, &asks, "BTC-USDT"));
+
+        asks.update_if_valid(event(1, 101));
+        assert!(!is_crossed(&bids, &asks, "BTC-USDT"));
+        bids.update_if_valid(event(2, 101));
+        assert!(is_crossed(&bids, &asks, "BTC-USDT"));
+        asks.update_if_valid(event(2, 100));
+        assert!(is_crossed(&bids, &asks, "BTC-USDT"));
+        asks.update_if_valid(event(3, 102));
+        assert!(!is_crossed(&bids, &asks, "BTC-USDT"));
     }
 }
@@ -1,46 +1,235 @@
-use std::time::Duration;
+use std::{path::PathBuf, time::Duration};
 
 use ahash::{AHashSet, HashSet};
-use anyhow::bail;
+use anyhow::{anyhow, bail};
 use engine::Validatable;
 use serde::Deserialize;
-use serde_with::{DisplayFromStr, DurationMicroSeconds, serde_as};
+use serde_with::{DisplayFromStr, DurationMicroSeconds, DurationMilliSeconds, serde_as};
+use solana_client::rpc_config::CommitmentConfig;
 use solana_sdk::pubkey::Pubkey;
 
-use crate::{libs::solana_client::*, services::exchange::compute::*};
+use crate::{
+    libs::solana_client::*,
+    services::{
+        exchange::{compute::*, sizing::SizeSearch},
+        sender::{confirm::RetryPolicy, freshness::FreshnessGuard, transaction::ComputeBudget},
+    },
+};
 
 #[derive(Deserialize, Clone, Debug)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum TransportConfig {
-    Websocket { url: String },
-    Grpc { url: String, x_token: String },
+    Websocket {
+        url: String,
+    },
+    Grpc {
+        url: String,
+        x_token: String,
+        /// Subscribe only to accounts matching the registered data size and
+        /// discriminator of each protocol entity.
+        #[serde(default = "default_filter_accounts")]
+        filter_accounts: bool,
+        /// File to record raw account updates to for offline replay.
+        #[serde(default)]
+        record_path: Option<PathBuf>,
+    },
+    /// Replays account updates recorded by the gRPC transport instead of streaming.
+    Replay {
+        path: PathBuf,
+    },
 }
 
+fn default_filter_accounts() -> bool {
+    true
+}
+
+fn default_decode_error_log_every() -> u64 {
+    metrics::DecodeErrors::DEFAULT_LOG_EVERY
+}
+
+#[serde_as]
 #[derive(Debug, Deserialize, Clone)]
 pub struct StrategyConfig {
     pub min_liquidity_fraction_bps: u64,
     pub max_liquidity_fraction_bps: u64,
     pub min_profit_bps: u64,
+    /// Mints that cycles may route through between base mints.
+    /// Empty means any mint paired with a base mint is allowed.
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    #[serde(default)]
+    pub intermediate_mints: Vec<Pubkey>,
+    /// Method used to search for the profit-maximizing input amount.
+    #[serde(default)]
+    pub size_search: SizeSearch,
+}
+
+/// Commitment level a submitted transaction must reach.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Commitment {
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+impl From<Commitment> for CommitmentConfig {
+    fn from(commitment: Commitment) -> Self {
+        match commitment {
+            Commitment::Processed => Self::processed(),
+            Commitment::Confirmed => Self::confirmed(),
+            Commitment::Finalized => Self::finalized(),
+        }
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct ConfirmationConfig {
+    pub commitment: Commitment,
+    #[serde_as(as = "DurationMilliSeconds<u64>")]
+    pub confirm_timeout_ms: Duration,
+    #[serde_as(as = "DurationMilliSeconds<u64>")]
+    pub poll_interval_ms: Duration,
+    pub max_attempts: u32,
+    /// Maximum number of transactions awaiting confirmation at once; further routes are
+    /// skipped until one of them settles.
+    pub max_in_flight: usize,
+}
+
+impl Default for ConfirmationConfig {
+    fn default() -> Self {
+        Self {
+            commitment: Commitment::Confirmed,
+            confirm_timeout_ms: Duration::from_secs(10),
+            poll_interval_ms: Duration::from_millis(500),
+            max_attempts: 3,
+            max_in_flight: 4,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct FreshnessConfig {
+    /// Abort submission when a route's pool state changed since the quote.
+    pub enabled: bool,
+    /// Maximum number of slots a pool may have advanced since the quote.
+    pub max_slot_lag: u64,
+}
+
+impl Default for FreshnessConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_slot_lag: 1,
+        }
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct WarmupConfig {
+    /// Minimum number of decoded and synced pools before reporting ready.
+    pub min_ready_pools: usize,
+    /// Base mints that must start at least one cycle before reporting ready.
+    /// Empty means a cycle for any base mint is enough.
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    pub required_mints: Vec<Pubkey>,
+}
+
+impl Default for WarmupConfig {
+    fn default() -> Self {
+        Self {
+            min_ready_pools: 1,
+            required_mints: vec![],
+        }
+    }
 }
 
 #[serde_as]
 #[derive(Debug, Deserialize, Clone)]
 pub struct Config {
     pub rpc_endpoint: String,
+    /// Submit transactions for detected opportunities (false for dry-run).
+    #[serde(default)]
+    pub send_transactions: bool,
+    /// Trading wallet whose balances are reported by the exchange.
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    pub wallet: Option<Pubkey>,
+    /// Keypair file signing the submitted transactions, required with `send_transactions`.
+    /// In dry-run the routes are simulated as paid by it.
+    #[serde(default)]
+    pub keypair_path: Option<PathBuf>,
+    /// Priority fee paid per compute unit of the submitted transactions, in micro-lamports.
+    #[serde(default)]
+    pub priority_fee_micro_lamports: u64,
     pub transport: TransportConfig,
     pub stream_batch_size: usize,
     #[serde_as(as = "DurationMicroSeconds<u64>")]
     pub stream_wait_timeout_us: Duration,
     pub strategy: StrategyConfig,
+    #[serde(default)]
+    pub confirmation: ConfirmationConfig,
+    #[serde(default)]
+    pub freshness: FreshnessConfig,
+    #[serde(default)]
+    pub warmup: WarmupConfig,
     pub exchanges: HashSet<ProtocolConfig>,
+    /// DEXes enabled for this run (e.g. `orca`, `raydium_cpmm`).
+    /// Empty means every configured exchange is enabled.
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    #[serde(default)]
+    pub enabled_dexes: Vec<ProtocolKind>,
+    /// Log one out of every N decode errors; all of them are counted in metrics.
+    #[serde(default = "default_decode_error_log_every")]
+    pub decode_error_log_every: u64,
     pub base_mints: HashSet<MintConfig>,
 }
 
+/// Checks that an endpoint is a URL with one of the given schemes and a host.
+fn check_endpoint(name: &str, endpoint: &str, schemes: &[&str]) -> anyhow::Result<()> {
+    let Some(rest) = schemes
+        .iter()
+        .find_map(|scheme| endpoint.strip_prefix(scheme)?.strip_prefix("://"))
+    else {
+        bail!(
+            "{name} must be a {} URL, got {endpoint:?}",
+            schemes.join("/")
+        );
+    };
+    if rest.split(['/', '?', '#']).next().is_none_or(str::is_empty) {
+        bail!("{name} has no host: {endpoint:?}");
+    }
+    Ok(())
+}
+
 impl Validatable for Config {
     fn validate(&mut self) -> anyhow::Result<()> {
         if self.rpc_endpoint.is_empty() {
             bail!("RPC endpoint cannot be empty");
         }
+        check_endpoint("rpc_endpoint", &self.rpc_endpoint, &["http", "https"])?;
+        match &self.transport {
+            TransportConfig::Websocket { url } => {
+                check_endpoint("transport.url", url, &["ws", "wss"])?;
+            }
+            TransportConfig::Grpc { url, .. } => {
+                check_endpoint("transport.url", url, &["http", "https"])?;
+            }
+            TransportConfig::Replay { .. } => {}
+        }
+        if self.send_transactions && self.keypair_path.is_none() {
+            bail!("keypair_path is required when send_transactions is enabled");
+        }
+        if self.base_mints.is_empty() {
+            bail!("base_mints must contain at least one mint");
+        }
+        for exchange in &self.exchanges {
+            exchange.protocol()?;
+        }
         if self.strategy.min_liquidity_fraction_bps >= self.strategy.max_liquidity_fraction_bps {
             bail!("min_liquidity_fraction_bps must be less than max_liquidity_fraction_bps");
         }
@@ -50,6 +239,37 @@ impl Validatable for Config {
         if self.strategy.max_liquidity_fraction_bps > BPS_DENOMINATOR {
             bail!("max_liquidity_fraction_bps cannot exceed 10000 (100%)");
         }
+        if self.confirmation.max_attempts == 0 {
+            bail!("confirmation.max_attempts must be greater than 0");
+        }
+        if self.confirmation.max_in_flight == 0 {
+            bail!("confirmation.max_in_flight must be greater than 0");
+        }
+        if self.decode_error_log_every == 0 {
+            bail!("decode_error_log_every must be greater than 0");
+        }
+        if self.confirmation.poll_interval_ms.is_zero() {
+            bail!("confirmation.poll_interval_ms must be greater than 0");
+        }
+        let base_mints = self.get_mints_addrs();
+        if let Some(mint) = self
+            .warmup
+            .required_mints
+            .iter()
+            .find(|m| !base_mints.contains(*m))
+        {
+            bail!("warmup.required_mints contains {mint}, which is not a base mint");
+        }
+        for dex in &self.enabled_dexes {
+            let program_id = dex.program_id().to_string();
+            if !self.exchanges.iter().any(|d| d.program_id == program_id) {
+                bail!(
+                    "enabled_dexes contains {}, which is not a configured exchange",
+                    dex.as_str()
+                );
+            }
+        }
+        self.get_route_protocols()?;
         Ok(())
     }
 }
@@ -59,10 +279,39 @@ impl Config {
     pub fn get_dex_addrs(&self) -> Vec<String> {
         self.exchanges
             .iter()
+            .filter(|d| self.is_dex_enabled(&d.program_id))
             .map(|d| d.program_id.clone())
             .collect()
     }
 
+    /// Returns the programs of `enabled_dexes`, or `None` if every exchange is enabled.
+    #[must_use]
+    pub fn get_enabled_programs(&self) -> Option<Vec<Pubkey>> {
+        (!self.enabled_dexes.is_empty()).then(|| {
+            self.enabled_dexes
+                .iter()
+                .map(ProtocolKind::program_id)
+                .collect()
+        })
+    }
+
+    fn is_dex_enabled(&self, program_id: &str) -> bool {
+        self.enabled_dexes.is_empty()
+            || self
+                .enabled_dexes
+                .iter()
+                .any(|dex| dex.program_id().to_string() == program_id)
+    }
+
+    /// Returns the protocols whose pools may be used as swap steps in arbitrage routes.
+    pub fn get_route_protocols(&self) -> anyhow::Result<AHashSet<ProtocolKind>> {
+        self.exchanges
+            .iter()
+            .filter(|d| d.include_in_routes && self.is_dex_enabled(&d.program_id))
+            .map(ProtocolConfig::protocol)
+            .collect()
+    }
+
     #[must_use]
     pub fn get_mints_addrs(&self) -> AHashSet<Pubkey> {
         self.base_mints.iter().map(|c| c.mint_addr).collect()
@@ -80,6 +329,27 @@ impl Config {
 #[derive(Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct ProtocolConfig {
     pub program_id: String,
+    /// Whether pools of this program participate in arbitrage routes.
+    /// Accounts are still streamed and cached when disabled.
+    #[serde(default = "default_include_in_routes")]
+    pub include_in_routes: bool,
+}
+
+impl ProtocolConfig {
+    /// Resolves the supported protocol of the configured program.
+    pub fn protocol(&self) -> anyhow::Result<ProtocolKind> {
+        let program_id = self
+            .program_id
+            .parse::<Pubkey>()
+            .map_err(|e| anyhow!("Invalid program_id {}: {e}", self.program_id))?;
+
+        ProtocolKind::from_program_id(&program_id)
+            .ok_or_else(|| anyhow!("Unsupported exchange program_id: {program_id}"))
+    }
+}
+
+fn default_include_in_routes() -> bool {
+    true
 }
 
 #[serde_as]
@@ -109,7 +379,9 @@ impl TryFrom<&Config> for WebsocketStreamConfig {
     fn try_from(cfg: &Config) -> Result<Self, Self::Error> {
         let endpoint = match &cfg.transport {
             TransportConfig::Websocket { url } => url.clone(),
-            TransportConfig::Grpc { .. } => bail!("Transport is not set to 'websocket'"),
+            TransportConfig::Grpc { .. } | TransportConfig::Replay { .. } => {
+                bail!("Transport is not set to 'websocket'")
+            }
         };
 
         Ok(Self {
@@ -126,9 +398,21 @@ impl TryFrom<&Config> for GrpcStreamConfig {
     type Error = anyhow::Error;
 
     fn try_from(cfg: &Config) -> Result<Self, Self::Error> {
-        let (endpoint, x_token) = match &cfg.transport {
-            TransportConfig::Grpc { url, x_token } => (url.clone(), Some(x_token.clone())),
-            TransportConfig::Websocket { .. } => bail!("Transport is not set to 'grpc'"),
+        let (endpoint, x_token, filter_accounts, record_path) = match &cfg.transport {
+            TransportConfig::Grpc {
+                url,
+                x_token,
+                filter_accounts,
+                record_path,
+            } => (
+                url.clone(),
+                Some(x_token.clone()),
+                *filter_accounts,
+                record_path.clone(),
+            ),
+            TransportConfig::Websocket { .. } | TransportConfig::Replay { .. } => {
+                bail!("Transport is not set to 'grpc'")
+            }
         };
 
         Ok(Self {
@@ -136,11 +420,31 @@ impl TryFrom<&Config> for GrpcStreamConfig {
             x_token,
             batch_size: cfg.stream_batch_size,
             batch_fill_timeout: cfg.stream_wait_timeout_us,
+            options: Some(SubscribeOptions {
+                filter_accounts,
+                ..Default::default()
+            }),
+            record_path,
             ..Default::default()
         })
     }
 }
 
+impl TryFrom<&Config> for ReplayStreamConfig {
+    type Error = anyhow::Error;
+
+    fn try_from(cfg: &Config) -> Result<Self, Self::Error> {
+        let TransportConfig::Replay { path } = &cfg.transport else {
+            bail!("Transport is not set to 'replay'");
+        };
+
+        Ok(Self {
+            path: path.clone(),
+            batch_size: cfg.stream_batch_size,
+        })
+    }
+}
+
 impl TryFrom<&Config> for ComputeConfig {
     type Error = anyhow::Error;
 
@@ -150,6 +454,136 @@ impl TryFrom<&Config> for ComputeConfig {
             min_liquidity_fraction_bps: cfg.strategy.min_liquidity_fraction_bps,
             max_liquidity_fraction_bps: cfg.strategy.max_liquidity_fraction_bps,
             min_profit_bps: cfg.strategy.min_profit_bps,
+            route_protocols: cfg.get_route_protocols()?,
+            intermediate_mints: cfg.strategy.intermediate_mints.iter().copied().collect(),
+            size_search: cfg.strategy.size_search,
+            warmup_min_pools: cfg.warmup.min_ready_pools,
+            warmup_mints: cfg.warmup.required_mints.iter().copied().collect(),
         })
     }
 }
+
+impl From<&Config> for RetryPolicy {
+    fn from(cfg: &Config) -> Self {
+        Self {
+            commitment: cfg.confirmation.commitment.into(),
+            confirm_timeout: cfg.confirmation.confirm_timeout_ms,
+            poll_interval: cfg.confirmation.poll_interval_ms,
+            max_attempts: cfg.confirmation.max_attempts,
+        }
+    }
+}
+
+impl From<&Config> for FreshnessGuard {
+    fn from(cfg: &Config) -> Self {
+        Self {
+            enabled: cfg.freshness.enabled,
+            max_slot_lag: cfg.freshness.max_slot_lag,
+        }
+    }
+}
+
+impl From<&Config> for ComputeBudget {
+    fn from(cfg: &Config) -> Self {
+        Self {
+            compute_unit_price: cfg.priority_fee_micro_lamports,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(overrides: serde_json::Value) -> Config {
+        let mut config = serde_json::json!({
+            "rpc_endpoint": "https://api.mainnet-beta.solana.com",
+            "transport": {"type": "grpc", "url": "https://grpc.example.com:10000", "x_token": ""},
+            "stream_batch_size": 128,
+            "stream_wait_timeout_us": 500,
+            "strategy": {
+                "min_liquidity_fraction_bps": 50,
+                "max_liquidity_fraction_bps": 2000,
+                "min_profit_bps": 10
+            },
+            "exchanges": [{"program_id": "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc"}],
+            "base_mints": [{"mint_addr": "So11111111111111111111111111111111111111112"}]
+        });
+        for (key, value) in overrides.as_object().into_iter().flatten() {
+            config[key] = value.clone();
+        }
+        serde_json::from_value(config).unwrap()
+    }
+
+    fn validation_error(overrides: serde_json::Value) -> String {
+        config(overrides).validate().unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_valid_config() {
+        assert!(config(serde_json::json!({})).validate().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_endpoints() {
+        assert_eq!(
+            validation_error(serde_json::json!({"rpc_endpoint": "api.mainnet-beta.solana.com"})),
+            "rpc_endpoint must be a http/https URL, got \"api.mainnet-beta.solana.com\""
+        );
+        assert_eq!(
+            validation_error(serde_json::json!({"rpc_endpoint": "https:///path"})),
+            "rpc_endpoint has no host: \"https:///path\""
+        );
+        assert_eq!(
+            validation_error(serde_json::json!({
+                "transport": {"type": "grpc", "url": "wss://grpc.example.com", "x_token": ""}
+            })),
+            "transport.url must be a http/https URL, got \"wss://grpc.example.com\""
+        );
+        assert_eq!(
+            validation_error(serde_json::json!({
+                "transport": {"type": "websocket", "url": "https://ws.example.com"}
+            })),
+            "transport.url must be a ws/wss URL, got \"https://ws.example.com\""
+        );
+    }
+
+    #[test]
+    fn test_invalid_exchange_program_ids() {
+        assert!(
+            validation_error(serde_json::json!({"exchanges": [{"program_id": "not-a-pubkey"}]}))
+                .starts_with("Invalid program_id not-a-pubkey")
+        );
+        // Exchanges excluded from routes are streamed, so they are checked too.
+        assert_eq!(
+            validation_error(serde_json::json!({
+                "exchanges": [{
+                    "program_id": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+                    "include_in_routes": false
+                }]
+            })),
+            "Unsupported exchange program_id: TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        );
+    }
+
+    #[test]
+    fn test_live_mode_requires_keypair() {
+        assert_eq!(
+            validation_error(serde_json::json!({"send_transactions": true})),
+            "keypair_path is required when send_transactions is enabled"
+        );
+        assert!(
+            config(serde_json::json!({"send_transactions": true, "keypair_path": "id.json"}))
+                .validate()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_no_base_mints() {
+        assert_eq!(
+            validation_error(serde_json::json!({"base_mints": []})),
+            "base_mints must contain at least one mint"
+        );
+    }
+}
@@ -1,6 +1,8 @@
 pub mod config;
 pub mod libs;
 pub mod services;
+#[cfg(test)]
+pub mod test_utils;
 
 pub use config::Config;
 pub use services::Provider;
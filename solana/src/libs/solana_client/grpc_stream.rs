@@ -1,5 +1,6 @@
 use std::{
     collections::HashMap,
+    path::PathBuf,
     time::{Duration, Instant},
 };
 
@@ -11,7 +12,7 @@ use solana_client::rpc_response::transaction::Signature;
 use solana_sdk::{clock::Clock, pubkey::Pubkey, sysvar::clock};
 use tokio::time::timeout;
 use tokio_util::sync::CancellationToken;
-use tracing::{error, warn};
+use tracing::{error, info, warn};
 use yellowstone_grpc_client::{ClientTlsConfig, GeyserGrpcClient, Interceptor};
 use yellowstone_grpc_proto::{
     prelude::{subscribe_update::UpdateOneof, *},
@@ -22,7 +23,7 @@ use yellowstone_grpc_proto::{
 };
 
 use crate::libs::solana_client::{
-    SolanaStream, callback::*, metrics::*, models::*, registry::*, utils,
+    SolanaStream, callback::*, metrics::*, models::*, registry::*, replay::*, utils,
 };
 
 /// Configuration for the Solana RPC client.
@@ -45,6 +46,8 @@ pub struct GrpcStreamConfig {
     pub targets: Vec<SubscribeTarget>,
     /// Options for subscription.
     pub options: Option<SubscribeOptions>,
+    /// File to record raw account updates to for offline replay.
+    pub record_path: Option<PathBuf>,
 }
 
 /// Options for subscription.
@@ -66,6 +69,10 @@ pub struct SubscribeOptions {
     pub include_vote: bool,
     /// Commitment level override
     pub commitment: Option<CommitmentLevel>,
+    /// Narrow program account subscriptions to the data size and discriminator
+    /// of each registered entity. When disabled, every account owned by the
+    /// program is streamed and unknown layouts are dropped on parse.
+    pub filter_accounts: bool,
 }
 
 impl Default for SubscribeOptions {
@@ -78,6 +85,7 @@ impl Default for SubscribeOptions {
             include_failed: false,
             include_vote: false,
             commitment: Some(CommitmentLevel::Processed),
+            filter_accounts: true,
         }
     }
 }
@@ -86,6 +94,7 @@ impl Default for SubscribeOptions {
 pub struct GrpcStream {
     config: GrpcStreamConfig,
     callback: Option<BatchEventCallbackWrapper>,
+    recorder: Option<AccountRecorder>,
 }
 
 #[async_trait]
@@ -99,6 +108,11 @@ impl SolanaStream for GrpcStream {
             bail!("Program IDs cannot be empty");
         }
 
+        if let Some(path) = &self.config.record_path {
+            self.recorder = Some(AccountRecorder::create(path)?);
+            info!(path = %path.display(), "⏺️ [Replay] Recording account updates");
+        }
+
         let mut delay = Duration::from_secs(1);
 
         while !token.is_cancelled() {
@@ -125,6 +139,11 @@ impl SolanaStream for GrpcStream {
                 }
             }
         }
+
+        // Write out the updates still queued for the recording.
+        if let Some(recorder) = self.recorder.take() {
+            recorder.finish().await?;
+        }
         Ok(())
     }
 }
@@ -136,6 +155,7 @@ impl GrpcStream {
         Self {
             config,
             callback: None,
+            recorder: None,
         }
     }
 
@@ -202,7 +222,12 @@ impl GrpcStream {
                 HashMap::new()
             },
 
-            accounts: Self::build_subscribe_accounts(targets, &registry_entries, protocols)?,
+            accounts: Self::build_subscribe_accounts(
+                targets,
+                &registry_entries,
+                protocols,
+                options,
+            )?,
 
             transactions: if targets.contains(&SubscribeTarget::Instruction) {
                 Self::build_subscribe_transactions(&program_ids, options)
@@ -234,9 +259,10 @@ impl GrpcStream {
         targets: &[SubscribeTarget],
         registry_entries: &[(&RegistryLookup, &RegistryItem)],
         protocols: &ProtocolMap,
+        options: &SubscribeOptions,
     ) -> anyhow::Result<HashMap<String, SubscribeRequestFilterAccounts>> {
         let mut accounts = if targets.contains(&SubscribeTarget::Program) {
-            Self::build_accounts(registry_entries, protocols)?
+            Self::build_accounts(registry_entries, protocols, options)?
         } else {
             HashMap::new()
         };
@@ -273,7 +299,12 @@ impl GrpcStream {
     fn build_accounts(
         registry_entries: &[(&RegistryLookup, &RegistryItem)],
         protocol_map: &ProtocolMap,
+        options: &SubscribeOptions,
     ) -> anyhow::Result<HashMap<String, SubscribeRequestFilterAccounts>> {
+        if !options.filter_accounts {
+            return Ok(Self::build_owner_accounts(registry_entries, protocol_map));
+        }
+
         registry_entries
             .iter()
             .enumerate()
@@ -306,6 +337,28 @@ impl GrpcStream {
             .collect()
     }
 
+    /// Builds a single owner-only filter per program, without layout constraints.
+    fn build_owner_accounts(
+        registry_entries: &[(&RegistryLookup, &RegistryItem)],
+        protocol_map: &ProtocolMap,
+    ) -> HashMap<String, SubscribeRequestFilterAccounts> {
+        registry_entries
+            .iter()
+            .filter(|(lookup, _)| matches!(lookup, RegistryLookup::Program { .. }))
+            .filter_map(|(lookup, _)| {
+                let program_id = lookup.program_id().to_string();
+                let protocol = protocol_map.get(&program_id)?;
+                let filter = SubscribeRequestFilterAccounts {
+                    account: protocol.account_ids.clone(),
+                    owner: vec![program_id.clone()],
+                    filters: vec![],
+                    ..Default::default()
+                };
+                Some((format!("acc_sub_{program_id}"), filter))
+            })
+            .collect()
+    }
+
     fn build_program_filter(
         program_id: &Pubkey,
         size: usize,
@@ -393,6 +446,10 @@ impl GrpcStream {
             let start_time = Instant::now();
             let batch_size = batch.len();
 
+            if let Some(recorder) = &self.recorder {
+                Self::record_accounts(recorder, &batch).await?;
+            }
+
             let events: Vec<Event> = batch
                 .into_par_iter()
                 .filter_map(|res| res.ok())
@@ -414,6 +471,28 @@ impl GrpcStream {
         Ok(())
     }
 
+    /// Queues the raw account updates of a batch for the recording.
+    async fn record_accounts(
+        recorder: &AccountRecorder,
+        batch: &[Result<SubscribeUpdate, Status>],
+    ) -> anyhow::Result<()> {
+        let accounts: Vec<RecordedAccount> = batch
+            .iter()
+            .filter_map(|res| match res {
+                Ok(SubscribeUpdate {
+                    update_oneof: Some(UpdateOneof::Account(acc)),
+                    ..
+                }) => RecordedAccount::from_update(acc),
+                _ => None,
+            })
+            .collect();
+
+        if accounts.is_empty() {
+            return Ok(());
+        }
+        recorder.record(accounts).await
+    }
+
     /// Parses a `UpdateOneof` to an `Event`.
     fn parse_update(event: &UpdateOneof) -> Option<Event> {
         match event {
@@ -449,7 +528,7 @@ impl GrpcStream {
     }
 
     /// Parses an account update to a `AccountEvent`.
-    fn parse_account(acc: &SubscribeUpdateAccount) -> Option<Event> {
+    pub(crate) fn parse_account(acc: &SubscribeUpdateAccount) -> Option<Event> {
         let info = acc.account.as_ref()?;
         let owner = Pubkey::try_from(info.owner.as_slice()).ok()?;
         let pubkey = Pubkey::try_from(info.pubkey.as_slice()).ok()?;
@@ -517,11 +596,14 @@ impl GrpcStream {
             .get_account_item(program_id, payload.len(), payload)
             .or_else(|| {
                 STREAM_METRICS.record_error(Transport::Grpc, StreamErrorKind::Parse);
-                warn!(
-                    "No registered parser found for program {} with data size {}",
-                    program_id,
-                    payload.len()
-                );
+                if let Some(total) = DECODE_ERRORS.record("unknown", DecodeErrorKind::NoParser) {
+                    warn!(
+                        total,
+                        "No registered parser found for program {} with data size {}",
+                        program_id,
+                        payload.len()
+                    );
+                }
                 None
             })?;
 
@@ -540,12 +622,15 @@ impl GrpcStream {
 
         let pool_state = parser_fn(payload).or_else(|| {
             STREAM_METRICS.record_error(Transport::Grpc, StreamErrorKind::Parse);
-            error!(
-                "[{}] Failed to parse account: {}. Data size: {}",
-                item.name,
-                pubkey,
-                payload.len()
-            );
+            if let Some(total) = DECODE_ERRORS.record(item.name, DecodeErrorKind::ParseFailed) {
+                error!(
+                    total,
+                    "[{}] Failed to parse account: {}. Data size: {}",
+                    item.name,
+                    pubkey,
+                    payload.len()
+                );
+            }
             None
         })?;
 
@@ -565,10 +650,13 @@ impl GrpcStream {
 
         let event = parser_fn(data).or_else(|| {
             STREAM_METRICS.record_error(Transport::Grpc, StreamErrorKind::Parse);
-            error!(
-            "[{}] Failed to parse transaction event for program {program_id}. Payload: {data:?}",
-            item.name
-        );
+            if let Some(total) = DECODE_ERRORS.record(item.name, DecodeErrorKind::ParseFailed) {
+                error!(
+                    total,
+                    "[{}] Failed to parse transaction event for program {program_id}. Payload: {data:?}",
+                    item.name
+                );
+            }
             None
         })?;
 
@@ -625,3 +713,108 @@ fn extract_program_id(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::libs::solana_client::protocols::{orca, raydium_cpmm};
+
+    /// `(owner, data size, discriminator)` of a single account filter.
+    type AccountFilter = (String, Option<u64>, Option<Vec<u8>>);
+
+    fn stream(program_ids: &[Pubkey]) -> GrpcStream {
+        GrpcStream::from_config(GrpcStreamConfig {
+            protocols: program_ids
+                .iter()
+                .map(|id| ProtocolConfig {
+                    program_id: id.to_string(),
+                    account_ids: vec![],
+                })
+                .collect(),
+            targets: vec![SubscribeTarget::Program],
+            ..Default::default()
+        })
+    }
+
+    fn layout_filter<T: ProtocolEntity>() -> AccountFilter {
+        (
+            T::PROGRAM_ID.to_string(),
+            (T::DATA_SIZE > 0).then_some(T::DATA_SIZE as u64),
+            (!T::DISCRIMINATOR.is_empty()).then(|| T::DISCRIMINATOR.to_vec()),
+        )
+    }
+
+    fn account_filters(request: &SubscribeRequest) -> HashSet<AccountFilter> {
+        request
+            .accounts
+            .values()
+            .map(|acc| {
+                let mut size = None;
+                let mut discriminator = None;
+
+                for filter in acc.filters.iter().filter_map(|f| f.filter.as_ref()) {
+                    match filter {
+                        subscribe_request_filter_accounts_filter::Filter::Datasize(s) => {
+                            size = Some(*s);
+                        }
+                        subscribe_request_filter_accounts_filter::Filter::Memcmp(m) => {
+                            assert_eq!(m.offset, 0);
+                            if let Some(
+                                subscribe_request_filter_accounts_filter_memcmp::Data::Bytes(b),
+                            ) = &m.data
+                            {
+                                discriminator = Some(b.clone());
+                            }
+                        }
+                        _ => panic!("unexpected account filter: {filter:?}"),
+                    }
+                }
+
+                assert_eq!(acc.owner.len(), 1);
+                (acc.owner[0].clone(), size, discriminator)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_subscribe_request_filters_by_layout() -> anyhow::Result<()> {
+        let stream = stream(&[raydium_cpmm::RAYDIUM_CPMM_ID, orca::ORCA_ID]);
+        let request = stream.build_subscribe_request(&SubscribeOptions::default())?;
+
+        let expected = HashSet::from([
+            layout_filter::<raydium_cpmm::PoolState>(),
+            layout_filter::<orca::Whirlpool>(),
+            layout_filter::<orca::FixedTickArray>(),
+            layout_filter::<orca::DynamicTickArray>(),
+            layout_filter::<orca::Oracle>(),
+        ]);
+
+        assert_eq!(request.accounts.len(), expected.len());
+        assert_eq!(account_filters(&request), expected);
+        assert!(request.transactions.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_subscribe_request_without_layout_filters() -> anyhow::Result<()> {
+        let stream = stream(&[raydium_cpmm::RAYDIUM_CPMM_ID, orca::ORCA_ID]);
+        let options = SubscribeOptions {
+            filter_accounts: false,
+            ..Default::default()
+        };
+        let request = stream.build_subscribe_request(&options)?;
+
+        let expected = HashSet::from([
+            (raydium_cpmm::RAYDIUM_CPMM_ID.to_string(), None, None),
+            (orca::ORCA_ID.to_string(), None, None),
+        ]);
+
+        assert_eq!(request.accounts.len(), expected.len());
+        assert_eq!(account_filters(&request), expected);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,120 @@
+use std::sync::{
+    LazyLock,
+    atomic::{AtomicU64, Ordering},
+};
+
+use metrics::{Unit, counter, describe_counter};
+
+/// Global sampler and counter for account/instruction decode errors.
+pub static DECODE_ERRORS: LazyLock<DecodeErrors> =
+    LazyLock::new(|| DecodeErrors::new(DecodeErrors::DEFAULT_LOG_EVERY));
+
+/// Reason an account or instruction payload could not be decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeErrorKind {
+    SizeMismatch,
+    DiscriminatorMismatch,
+    NoParser,
+    ParseFailed,
+}
+
+impl DecodeErrorKind {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::SizeMismatch => "size_mismatch",
+            Self::DiscriminatorMismatch => "discriminator_mismatch",
+            Self::NoParser => "no_parser",
+            Self::ParseFailed => "parse_failed",
+        }
+    }
+}
+
+/// Counts every decode error and decides which of them get logged.
+///
+/// Only the first error and then one out of every `log_every` errors is logged,
+/// so a misbehaving stream doesn't flood the logs; the metric counts all of them.
+pub struct DecodeErrors {
+    log_every: AtomicU64,
+    total: AtomicU64,
+}
+
+impl DecodeErrors {
+    const LBL_ENTITY: &'static str = "entity";
+    const LBL_ERROR: &'static str = "error";
+
+    const METRIC_DECODE_ERRORS: &'static str = "solana_client_decode_errors_total";
+
+    pub const DEFAULT_LOG_EVERY: u64 = 100;
+
+    #[must_use]
+    pub fn new(log_every: u64) -> Self {
+        describe_counter!(
+            Self::METRIC_DECODE_ERRORS,
+            Unit::Count,
+            "Account and instruction payloads that failed to decode"
+        );
+
+        Self {
+            log_every: AtomicU64::new(log_every.max(1)),
+            total: AtomicU64::new(0),
+        }
+    }
+
+    /// Sets the sampling rate: one out of every `log_every` errors is logged (1 = all).
+    pub fn set_log_every(&self, log_every: u64) {
+        self.log_every.store(log_every.max(1), Ordering::Relaxed);
+    }
+
+    /// Counts a decode error. Returns the running total if this error should be logged.
+    pub fn record(&self, entity: &'static str, kind: DecodeErrorKind) -> Option<u64> {
+        counter!(
+            Self::METRIC_DECODE_ERRORS,
+            Self::LBL_ENTITY => entity,
+            Self::LBL_ERROR => kind.as_str()
+        )
+        .increment(1);
+
+        let total = self.total.fetch_add(1, Ordering::Relaxed) + 1;
+        let log_every = self.log_every.load(Ordering::Relaxed);
+
+        ((total - 1) % log_every == 0).then_some(total)
+    }
+
+    /// Total number of decode errors seen so far.
+    #[must_use]
+    pub fn total(&self) -> u64 {
+        self.total.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_logs_sampled_subset_and_counts_all() {
+        let errors = DecodeErrors::new(100);
+
+        let logged: Vec<u64> = (0..1_000)
+            .filter_map(|_| errors.record("pool", DecodeErrorKind::SizeMismatch))
+            .collect();
+
+        assert_eq!(errors.total(), 1_000);
+        assert_eq!(logged.len(), 10);
+        assert_eq!(logged[..3], [1, 101, 201]);
+    }
+
+    #[test]
+    fn test_log_every_one_logs_all() {
+        let errors = DecodeErrors::new(0);
+        errors.set_log_every(1);
+
+        let logged = (0..10)
+            .filter_map(|_| errors.record("pool", DecodeErrorKind::ParseFailed))
+            .count();
+
+        assert_eq!(logged, 10);
+        assert_eq!(errors.total(), 10);
+    }
+}
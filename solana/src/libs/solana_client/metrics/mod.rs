@@ -1,7 +1,9 @@
+pub mod decode;
 pub mod labels;
 pub mod rpc;
 pub mod stream;
 
+pub use decode::*;
 pub use labels::*;
 pub use rpc::*;
 pub use stream::*;
@@ -5,6 +5,7 @@ pub mod models;
 pub mod pool;
 pub mod protocols;
 pub mod registry;
+pub mod replay;
 pub mod rpc;
 pub mod traits;
 pub mod utils;
@@ -13,6 +14,7 @@ pub mod ws_stream;
 pub use callback::*;
 pub use grpc_stream::*;
 pub use models::*;
+pub use replay::*;
 pub use rpc::*;
 pub use traits::*;
 pub use ws_stream::*;
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, str::FromStr};
 
 use solana_sdk::pubkey::Pubkey;
 
@@ -7,7 +7,7 @@ use crate::libs::solana_client::{
         DEX_METEORA_DAMM_V2, DEX_METEORA_DLMM, DEX_ORCA, DEX_RAYDIUM_AMM, DEX_RAYDIUM_CLMM,
         DEX_RAYDIUM_CPMM,
     },
-    protocols::{meteora_dlmm, raydium_clmm},
+    protocols::{meteora_damm_v2, meteora_dlmm, orca, raydium_amm, raydium_clmm, raydium_cpmm},
 };
 
 /// Configuration for a single protocol, including its program ID
@@ -60,7 +60,7 @@ pub trait ProtocolIdentity {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ProtocolKind {
     MeteoraDammV2,
     MeteoraDlmm,
@@ -83,6 +83,33 @@ impl ProtocolKind {
         }
     }
 
+    /// Returns the on-chain program owning the protocol's accounts.
+    #[must_use]
+    pub fn program_id(&self) -> Pubkey {
+        match self {
+            Self::MeteoraDammV2 => meteora_damm_v2::METEORA_DAMM_V2_ID,
+            Self::MeteoraDlmm => meteora_dlmm::METEORA_DLMM_ID,
+            Self::RaydiumAmm => raydium_amm::RAYDIUM_AMM_ID,
+            Self::RaydiumClmm => raydium_clmm::RAYDIUM_CLMM_ID,
+            Self::RaydiumCpmm => raydium_cpmm::RAYDIUM_CPMM_ID,
+            Self::Orca => orca::ORCA_ID,
+        }
+    }
+
+    /// Resolves the protocol owning the given on-chain program.
+    #[must_use]
+    pub fn from_program_id(program_id: &Pubkey) -> Option<Self> {
+        match *program_id {
+            meteora_damm_v2::METEORA_DAMM_V2_ID => Some(Self::MeteoraDammV2),
+            meteora_dlmm::METEORA_DLMM_ID => Some(Self::MeteoraDlmm),
+            raydium_amm::RAYDIUM_AMM_ID => Some(Self::RaydiumAmm),
+            raydium_clmm::RAYDIUM_CLMM_ID => Some(Self::RaydiumClmm),
+            raydium_cpmm::RAYDIUM_CPMM_ID => Some(Self::RaydiumCpmm),
+            orca::ORCA_ID => Some(Self::Orca),
+            _ => None,
+        }
+    }
+
     #[must_use]
     pub fn bitmap_pda(&self, pool_id: &Pubkey) -> Option<Pubkey> {
         match self {
@@ -92,3 +119,22 @@ impl ProtocolKind {
         }
     }
 }
+
+impl FromStr for ProtocolKind {
+    type Err = anyhow::Error;
+
+    /// Parses a protocol from its label (e.g. `orca`, `raydium_cpmm`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            Self::MeteoraDammV2,
+            Self::MeteoraDlmm,
+            Self::RaydiumAmm,
+            Self::RaydiumClmm,
+            Self::RaydiumCpmm,
+            Self::Orca,
+        ]
+        .into_iter()
+        .find(|kind| kind.as_str() == s)
+        .ok_or_else(|| anyhow::anyhow!("Unknown DEX: {s}"))
+    }
+}
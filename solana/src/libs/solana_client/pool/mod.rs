@@ -9,3 +9,6 @@ pub use liquidity::*;
 
 pub mod config;
 pub use config::*;
+
+pub mod swap;
+pub use swap::*;
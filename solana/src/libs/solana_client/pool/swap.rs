@@ -0,0 +1,38 @@
+use solana_sdk::pubkey::Pubkey;
+
+/// Input parameters for building a swap instruction against a pool.
+#[derive(Debug, Clone, Copy)]
+pub struct SwapContext {
+    /// Address of the pool state account.
+    pub pool_id: Pubkey,
+
+    /// Wallet signing the swap; its associated token accounts are debited and credited.
+    pub payer: Pubkey,
+
+    /// Swap direction: `true` means token A → token B, `false` means B → A.
+    pub a_to_b: bool,
+
+    /// Token program owning the input mint (`spl-token` or `spl-token-2022`).
+    pub token_program_in: Pubkey,
+
+    /// Token program owning the output mint (`spl-token` or `spl-token-2022`).
+    pub token_program_out: Pubkey,
+
+    /// Exact amount of the input token to swap.
+    pub amount_in: u64,
+
+    /// Minimum amount of the output token, below which the swap fails on-chain.
+    pub min_amount_out: u64,
+}
+
+impl SwapContext {
+    /// Returns the token programs of token A and token B.
+    #[must_use]
+    pub fn token_programs(&self) -> (Pubkey, Pubkey) {
+        if self.a_to_b {
+            (self.token_program_in, self.token_program_out)
+        } else {
+            (self.token_program_out, self.token_program_in)
+        }
+    }
+}
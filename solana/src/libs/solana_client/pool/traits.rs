@@ -1,6 +1,6 @@
-use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
 
-use super::quote::*;
+use super::{quote::*, swap::*};
 use crate::libs::solana_client::ProtocolIdentity;
 
 /// Core trait for DEX pool implementations.
@@ -42,4 +42,12 @@ pub trait DexPool: ProtocolIdentity + Send + Sync {
     /// # Errors
     /// Returns an error if the pool state is invalid or liquidity is insufficient.
     fn quote(&self, ctx: &QuoteContext) -> anyhow::Result<QuoteResult>;
+
+    /// Builds the instruction swapping `ctx.amount_in` through this pool.
+    ///
+    /// # Errors
+    /// Returns an error if the protocol does not support building swaps.
+    fn swap_instruction(&self, _ctx: &SwapContext) -> anyhow::Result<Instruction> {
+        anyhow::bail!("Building {} swaps is not supported", self.protocol_name())
+    }
 }
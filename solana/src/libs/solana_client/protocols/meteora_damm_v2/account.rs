@@ -1,6 +1,6 @@
 use bytemuck::{Pod, Zeroable};
 use num_enum::{IntoPrimitive, TryFromPrimitive};
-use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
 
 use crate::libs::solana_client::{
     ProtocolIdentity, ProtocolKind,
@@ -18,6 +18,7 @@ use crate::libs::solana_client::{
         },
         params::*,
         state::*,
+        swap::swap_exact_in_instruction,
         utils::{activation_handler::*, token::*},
     },
     registry::ProtocolEntity,
@@ -191,12 +192,7 @@ impl DexPool for Pool {
                     get_transfer_fee(&mint_out, ctx.clock.epoch, result.output_amount);
                 let total_amount_out = result.output_amount.saturating_sub(transfer_fee_out);
 
-                let total_fee = result
-                    .claiming_fee
-                    .checked_add(result.protocol_fee)
-                    .and_then(|f| f.checked_add(result.compounding_fee))
-                    .and_then(|f| f.checked_add(result.referral_fee))
-                    .ok_or_else(|| anyhow::anyhow!("fee overflow"))?;
+                let total_fee = result.total_fee()?;
 
                 Ok(QuoteResult {
                     steps: vec![],
@@ -231,12 +227,7 @@ impl DexPool for Pool {
                     .checked_add(transfer_fee_in)
                     .ok_or_else(|| anyhow::anyhow!("overflow in total_amount_in_gross"))?;
 
-                let total_fee = result
-                    .claiming_fee
-                    .checked_add(result.protocol_fee)
-                    .and_then(|f| f.checked_add(result.compounding_fee))
-                    .and_then(|f| f.checked_add(result.referral_fee))
-                    .ok_or_else(|| anyhow::anyhow!("fee overflow"))?;
+                let total_fee = result.total_fee()?;
 
                 Ok(QuoteResult {
                     steps: vec![],
@@ -249,6 +240,10 @@ impl DexPool for Pool {
             }
         }
     }
+
+    fn swap_instruction(&self, ctx: &SwapContext) -> anyhow::Result<Instruction> {
+        Ok(swap_exact_in_instruction(self, ctx))
+    }
 }
 
 impl ProtocolIdentity for Pool {
@@ -1037,6 +1032,17 @@ pub struct SwapResult2 {
     pub referral_fee: u64,
 }
 
+impl SwapResult2 {
+    /// Sum of all fee components charged by the swap.
+    pub fn total_fee(&self) -> anyhow::Result<u64> {
+        self.claiming_fee
+            .checked_add(self.protocol_fee)
+            .and_then(|f| f.checked_add(self.compounding_fee))
+            .and_then(|f| f.checked_add(self.referral_fee))
+            .ok_or_else(|| anyhow::anyhow!("fee overflow"))
+    }
+}
+
 pub struct SwapAmountFromInput {
     pub output_amount: u64,
     pub next_sqrt_price: u128,
@@ -1047,3 +1053,196 @@ pub struct SwapAmountFromOutput {
     pub input_amount: u64,
     pub next_sqrt_price: u128,
 }
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::clock::Clock;
+
+    use super::*;
+    use crate::{
+        libs::solana_client::{
+            PoolState,
+            protocols::{meteora_damm_v2::SWAP_DISCRIMINATOR, utils::get_associated_token_address},
+            registry::{PROTOCOL_REGISTRY, ProtocolParser},
+        },
+        test_utils::mint_account,
+    };
+
+    const TOKEN_A_AMOUNT: u64 = 1_000_000_000;
+    const TOKEN_B_AMOUNT: u64 = 2_000_000_000;
+
+    /// 0.25% base fee (fee denominator is 1e9).
+    const CLIFF_FEE_NUMERATOR: u64 = 2_500_000;
+
+    /// Compounding (constant-product) pool with a static base fee, activated by timestamp.
+    fn compounding_pool() -> Pool {
+        let mut pool = Pool::zeroed();
+        pool.token_a_mint = Pubkey::new_unique().to_bytes();
+        pool.token_b_mint = Pubkey::new_unique().to_bytes();
+        pool.token_a_amount = TOKEN_A_AMOUNT;
+        pool.token_b_amount = TOKEN_B_AMOUNT;
+        pool.liquidity = [1_000_000_000, 0];
+        pool.collect_fee_mode = CollectFeeMode::Compounding.into();
+        pool.activation_type = ActivationType::Timestamp.into();
+        pool.layout_version = LayoutVersion::V1.into();
+
+        // Linear fee time scheduler with no periods: the cliff fee applies forever.
+        let fee_data = &mut pool.pool_fees.base_fee.base_fee_info.data;
+        fee_data[..8].copy_from_slice(&CLIFF_FEE_NUMERATOR.to_le_bytes());
+        pool.pool_fees.protocol_fee_percent = 20;
+        pool.pool_fees.compounding_fee_bps = 5_000;
+
+        pool
+    }
+
+    /// Serializes the pool the way it is stored on-chain (discriminator + payload).
+    fn pool_account_data(pool: &Pool) -> Vec<u8> {
+        [Pool::DISCRIMINATOR, bytemuck::bytes_of(pool)].concat()
+    }
+
+    fn quote(
+        pool: &Pool,
+        clock: &Clock,
+        quote_type: QuoteType,
+        a_to_b: bool,
+    ) -> anyhow::Result<QuoteResult> {
+        let mint_in = mint_account(9);
+        let mint_out = mint_account(6);
+
+        pool.quote(&QuoteContext {
+            quote_type,
+            a_to_b,
+            clock,
+            mint_in: &mint_in,
+            mint_out: &mint_out,
+            vaults: None,
+            liquidity: None,
+            bitmap: None,
+            amm_config: None,
+            oracle: None,
+        })
+    }
+
+    #[test]
+    fn test_decode_pool_account() {
+        let expected = compounding_pool();
+        let data = pool_account_data(&expected);
+        assert_eq!(data.len(), Pool::DATA_SIZE);
+
+        let item = PROTOCOL_REGISTRY
+            .get_account_item(&METEORA_DAMM_V2_ID, data.len(), &data)
+            .expect("DAMM v2 pool is registered");
+        let ProtocolParser::Program(parse) = &item.parser else {
+            panic!("DAMM v2 pool must be parsed as program account");
+        };
+
+        let Some(PoolState::PoolMeteoraDammV2(decoded)) = parse(&data) else {
+            panic!("DAMM v2 pool account must decode into PoolMeteoraDammV2");
+        };
+        assert_eq!(decoded.get_mints(), expected.get_mints());
+        assert_eq!(decoded.token_a_amount, TOKEN_A_AMOUNT);
+        assert_eq!(decoded.token_b_amount, TOKEN_B_AMOUNT);
+        assert_eq!(decoded.protocol(), ProtocolKind::MeteoraDammV2);
+
+        assert!(Pool::deserialize(&data[..data.len() - 1]).is_none());
+    }
+
+    #[test]
+    fn test_quote_exact_in() -> anyhow::Result<()> {
+        let pool =
+            Pool::deserialize(&pool_account_data(&compounding_pool())).expect("valid pool account");
+        let clock = Clock::default();
+
+        // A -> B collects the fee on the output token.
+        let result = quote(&pool, &clock, QuoteType::ExactIn(1_000_000), true)?;
+        assert_eq!(result.total_amount_in_gross, 1_000_000);
+        assert_eq!(result.total_amount_in_net, 1_000_000);
+        assert_eq!(result.total_amount_out, 1_993_005);
+        assert_eq!(result.total_fee, 4_996);
+
+        // B -> A collects the fee on the input token.
+        let result = quote(&pool, &clock, QuoteType::ExactIn(1_000_000), false)?;
+        assert_eq!(result.total_amount_in_gross, 1_000_000);
+        assert_eq!(result.total_amount_out, 498_501);
+        assert_eq!(result.total_fee, 2_500);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_quote_exact_out() -> anyhow::Result<()> {
+        let pool = compounding_pool();
+
+        let result = quote(
+            &pool,
+            &Clock::default(),
+            QuoteType::ExactOut(1_993_005),
+            true,
+        )?;
+        assert_eq!(result.total_amount_out, 1_993_005);
+        assert_eq!(result.total_amount_in_gross, 999_999);
+        assert_eq!(result.total_amount_in_net, 999_999);
+        assert_eq!(result.total_fee, 4_995);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_quote_rejects_inactive_pool() {
+        let mut pool = compounding_pool();
+        pool.pool_status = PoolStatus::Disable.into();
+        assert!(
+            quote(
+                &pool,
+                &Clock::default(),
+                QuoteType::ExactIn(1_000_000),
+                true
+            )
+            .is_err()
+        );
+
+        let mut pool = compounding_pool();
+        pool.activation_point = 100;
+        let clock = Clock {
+            unix_timestamp: 50,
+            ..Clock::default()
+        };
+        assert!(quote(&pool, &clock, QuoteType::ExactIn(1_000_000), true).is_err());
+    }
+
+    #[test]
+    fn test_swap_instruction_uses_input_token_program() -> anyhow::Result<()> {
+        let mut pool = compounding_pool();
+        pool.token_a_vault = Pubkey::new_unique().to_bytes();
+        pool.token_b_vault = Pubkey::new_unique().to_bytes();
+
+        let ctx = SwapContext {
+            pool_id: Pubkey::new_unique(),
+            payer: Pubkey::new_unique(),
+            a_to_b: false,
+            token_program_in: spl_token_2022::ID,
+            token_program_out: spl_token::ID,
+            amount_in: 1_000,
+            min_amount_out: 990,
+        };
+        let ix = pool.swap_instruction(&ctx)?;
+
+        let mint_b = Pubkey::from(pool.token_b_mint);
+        assert_eq!(ix.program_id, METEORA_DAMM_V2_ID);
+        assert_eq!(ix.accounts.len(), 15);
+        assert_eq!(ix.accounts[1].pubkey, ctx.pool_id);
+        // B -> A spends the token-2022 token B.
+        assert_eq!(
+            ix.accounts[2].pubkey,
+            get_associated_token_address(&ctx.payer, &mint_b, &spl_token_2022::ID)
+        );
+        assert!(ix.accounts[8].is_signer);
+        assert_eq!(ix.accounts[9].pubkey, spl_token::ID);
+        assert_eq!(ix.accounts[10].pubkey, spl_token_2022::ID);
+        assert_eq!(&ix.data[..8], SWAP_DISCRIMINATOR);
+        assert_eq!(ix.data[8..16], 1_000u64.to_le_bytes());
+        assert_eq!(ix.data[16..], 990u64.to_le_bytes());
+
+        Ok(())
+    }
+}
@@ -5,8 +5,9 @@ mod error;
 mod liquidity_handler;
 mod math;
 mod params;
+mod pda;
 mod state;
 mod swap;
 mod utils;
 
-pub use super::meteora_damm_v2::{account::*, constants::*, swap::*};
+pub use super::meteora_damm_v2::{account::*, constants::*, pda::*, swap::*};
@@ -0,0 +1,18 @@
+use solana_sdk::pubkey::Pubkey;
+
+use crate::libs::solana_client::protocols::meteora_damm_v2::METEORA_DAMM_V2_ID;
+
+pub const POOL_AUTHORITY_SEED: &[u8] = b"pool_authority";
+pub const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
+
+/// Derives the authority owning the vaults of every pool.
+#[must_use]
+pub fn derive_pool_authority() -> Pubkey {
+    Pubkey::find_program_address(&[POOL_AUTHORITY_SEED], &METEORA_DAMM_V2_ID).0
+}
+
+/// Derives the authority the program emits its events through.
+#[must_use]
+pub fn derive_event_authority() -> Pubkey {
+    Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], &METEORA_DAMM_V2_ID).0
+}
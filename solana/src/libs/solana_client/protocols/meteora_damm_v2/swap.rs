@@ -1,10 +1,26 @@
 use bytemuck::{Pod, Zeroable};
-use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    sysvar,
+};
 
 use crate::libs::solana_client::{
-    protocols::meteora_damm_v2::constants::METEORA_DAMM_V2_ID, registry::ProtocolEntity,
+    pool::SwapContext,
+    protocols::{
+        meteora_damm_v2::{
+            Pool,
+            constants::METEORA_DAMM_V2_ID,
+            pda::{derive_event_authority, derive_pool_authority},
+        },
+        utils::get_associated_token_address,
+    },
+    registry::ProtocolEntity,
 };
 
+/// Anchor discriminator of the `swap` instruction.
+pub const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Pod, Zeroable)]
 pub struct Swap {
@@ -20,3 +36,56 @@ impl ProtocolEntity for Swap {
         Some(Self {})
     }
 }
+
+/// Builds a `swap` instruction swapping exactly `ctx.amount_in` between the associated
+/// token accounts of the payer, without a referral account.
+///
+/// The instructions sysvar is passed last, pools with a rate limiting fee read it to
+/// check the swap is the only one of the transaction.
+#[must_use]
+pub fn swap_exact_in_instruction(pool: &Pool, ctx: &SwapContext) -> Instruction {
+    let (token_program_a, token_program_b) = ctx.token_programs();
+    let (mint_a, mint_b) = (
+        Pubkey::from(pool.token_a_mint),
+        Pubkey::from(pool.token_b_mint),
+    );
+    let (input_mint, output_mint) = if ctx.a_to_b {
+        (mint_a, mint_b)
+    } else {
+        (mint_b, mint_a)
+    };
+
+    let mut data = Vec::with_capacity(24);
+    data.extend_from_slice(&SWAP_DISCRIMINATOR);
+    data.extend_from_slice(&ctx.amount_in.to_le_bytes());
+    data.extend_from_slice(&ctx.min_amount_out.to_le_bytes());
+
+    Instruction {
+        program_id: METEORA_DAMM_V2_ID,
+        accounts: vec![
+            AccountMeta::new_readonly(derive_pool_authority(), false),
+            AccountMeta::new(ctx.pool_id, false),
+            AccountMeta::new(
+                get_associated_token_address(&ctx.payer, &input_mint, &ctx.token_program_in),
+                false,
+            ),
+            AccountMeta::new(
+                get_associated_token_address(&ctx.payer, &output_mint, &ctx.token_program_out),
+                false,
+            ),
+            AccountMeta::new(Pubkey::from(pool.token_a_vault), false),
+            AccountMeta::new(Pubkey::from(pool.token_b_vault), false),
+            AccountMeta::new_readonly(mint_a, false),
+            AccountMeta::new_readonly(mint_b, false),
+            AccountMeta::new_readonly(ctx.payer, true),
+            AccountMeta::new_readonly(token_program_a, false),
+            AccountMeta::new_readonly(token_program_b, false),
+            // No referral token account.
+            AccountMeta::new_readonly(METEORA_DAMM_V2_ID, false),
+            AccountMeta::new_readonly(derive_event_authority(), false),
+            AccountMeta::new_readonly(METEORA_DAMM_V2_ID, false),
+            AccountMeta::new_readonly(sysvar::instructions::ID, false),
+        ],
+        data,
+    }
+}
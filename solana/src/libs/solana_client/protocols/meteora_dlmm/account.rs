@@ -1,10 +1,10 @@
 use bytemuck::{Pod, Zeroable};
-use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
 
 use crate::libs::solana_client::{
     models::*,
     pool::*,
-    protocols::meteora_dlmm::{constants::*, quote::*},
+    protocols::meteora_dlmm::{constants::*, quote::*, swap::swap_exact_in_instruction},
     registry::ProtocolEntity,
 };
 
@@ -98,6 +98,10 @@ impl DexPool for LbPair {
             ),
         }
     }
+
+    fn swap_instruction(&self, ctx: &SwapContext) -> anyhow::Result<Instruction> {
+        swap_exact_in_instruction(self, ctx)
+    }
 }
 
 impl ProtocolIdentity for LbPair {
@@ -3,8 +3,25 @@ use solana_sdk::pubkey::Pubkey;
 use crate::libs::solana_client::protocols::meteora_dlmm::METEORA_DLMM_ID;
 
 pub const BIN_ARRAY_BITMAP_SEED: &[u8] = b"bitmap";
+pub const BIN_ARRAY_SEED: &[u8] = b"bin_array";
+pub const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
 
 #[must_use]
 pub fn derive_bin_array_bitmap_extension(lb_pair: Pubkey) -> Pubkey {
     Pubkey::find_program_address(&[BIN_ARRAY_BITMAP_SEED, lb_pair.as_ref()], &METEORA_DLMM_ID).0
 }
+
+#[must_use]
+pub fn derive_bin_array(lb_pair: Pubkey, index: i64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[BIN_ARRAY_SEED, lb_pair.as_ref(), &index.to_le_bytes()],
+        &METEORA_DLMM_ID,
+    )
+    .0
+}
+
+/// Derives the authority the program emits its events through.
+#[must_use]
+pub fn derive_event_authority() -> Pubkey {
+    Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], &METEORA_DLMM_ID).0
+}
@@ -1,10 +1,29 @@
 use bytemuck::{Pod, Zeroable};
-use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
 
 use crate::libs::solana_client::{
-    protocols::meteora_dlmm::constants::METEORA_DLMM_ID, registry::ProtocolEntity,
+    pool::SwapContext,
+    protocols::{
+        meteora_dlmm::{
+            BinArray, LbPair,
+            constants::METEORA_DLMM_ID,
+            extensions::{BinArrayExtension, LbPairExtension},
+            pda::{derive_bin_array, derive_event_authority},
+        },
+        utils::get_associated_token_address,
+    },
+    registry::ProtocolEntity,
 };
 
+/// Anchor discriminator of the `swap` instruction.
+pub const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+
+/// Bin arrays passed to a swap, enough for the sizes the quotes allow.
+const SWAP_BIN_ARRAYS: usize = 3;
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Pod, Zeroable)]
 pub struct Swap {
@@ -20,3 +39,146 @@ impl ProtocolEntity for Swap {
         Some(Self {})
     }
 }
+
+/// Builds a `swap` instruction swapping exactly `ctx.amount_in` between the associated
+/// token accounts of the payer, without a host fee account.
+///
+/// The bin arrays are the ones with liquidity in the swap direction, starting from the
+/// active bin, as located by the pair's default bin array bitmap.
+///
+/// # Errors
+/// Returns an error if the active bin is outside the default bitmap, which would require
+/// the bitmap extension to locate the bin arrays, or no bin array has liquidity in the
+/// swap direction.
+pub fn swap_exact_in_instruction(
+    lb_pair: &LbPair,
+    ctx: &SwapContext,
+) -> anyhow::Result<Instruction> {
+    let swap_for_y = ctx.a_to_b;
+    let mut start_index = BinArray::bin_id_to_bin_array_index(lb_pair.active_id)?;
+    let mut bin_arrays = Vec::with_capacity(SWAP_BIN_ARRAYS);
+    while bin_arrays.len() < SWAP_BIN_ARRAYS {
+        if lb_pair.is_overflow_default_bin_array_bitmap(start_index) {
+            if bin_arrays.is_empty() {
+                anyhow::bail!("Meteora DLMM: active bin is outside the default bin array bitmap");
+            }
+            break;
+        }
+        let (index, has_liquidity) =
+            lb_pair.next_bin_array_index_with_liquidity_internal(swap_for_y, start_index)?;
+        if !has_liquidity {
+            break;
+        }
+        bin_arrays.push(derive_bin_array(ctx.pool_id, i64::from(index)));
+        start_index = if swap_for_y { index - 1 } else { index + 1 };
+    }
+    if bin_arrays.is_empty() {
+        anyhow::bail!("Meteora DLMM: no bin array with liquidity in the swap direction");
+    }
+
+    let (token_program_x, token_program_y) = ctx.token_programs();
+    let (mint_x, mint_y) = (
+        Pubkey::from(lb_pair.token_x_mint),
+        Pubkey::from(lb_pair.token_y_mint),
+    );
+    let (input_mint, output_mint) = if ctx.a_to_b {
+        (mint_x, mint_y)
+    } else {
+        (mint_y, mint_x)
+    };
+
+    let mut data = Vec::with_capacity(24);
+    data.extend_from_slice(&SWAP_DISCRIMINATOR);
+    data.extend_from_slice(&ctx.amount_in.to_le_bytes());
+    data.extend_from_slice(&ctx.min_amount_out.to_le_bytes());
+
+    let mut accounts = vec![
+        AccountMeta::new(ctx.pool_id, false),
+        // No bitmap extension, the bin arrays are within the default bitmap.
+        AccountMeta::new_readonly(METEORA_DLMM_ID, false),
+        AccountMeta::new(Pubkey::from(lb_pair.reserve_x), false),
+        AccountMeta::new(Pubkey::from(lb_pair.reserve_y), false),
+        AccountMeta::new(
+            get_associated_token_address(&ctx.payer, &input_mint, &ctx.token_program_in),
+            false,
+        ),
+        AccountMeta::new(
+            get_associated_token_address(&ctx.payer, &output_mint, &ctx.token_program_out),
+            false,
+        ),
+        AccountMeta::new_readonly(mint_x, false),
+        AccountMeta::new_readonly(mint_y, false),
+        AccountMeta::new(Pubkey::from(lb_pair.oracle), false),
+        // No host fee account.
+        AccountMeta::new_readonly(METEORA_DLMM_ID, false),
+        AccountMeta::new_readonly(ctx.payer, true),
+        AccountMeta::new_readonly(token_program_x, false),
+        AccountMeta::new_readonly(token_program_y, false),
+        AccountMeta::new_readonly(derive_event_authority(), false),
+        AccountMeta::new_readonly(METEORA_DLMM_ID, false),
+    ];
+    accounts.extend(
+        bin_arrays
+            .into_iter()
+            .map(|bin_array| AccountMeta::new(bin_array, false)),
+    );
+
+    Ok(Instruction {
+        program_id: METEORA_DLMM_ID,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use bytemuck::Zeroable;
+
+    use super::*;
+    use crate::libs::solana_client::pool::DexPool;
+
+    #[test]
+    fn test_swap_instruction_passes_bin_arrays_with_liquidity() -> anyhow::Result<()> {
+        let mut lb_pair = LbPair::zeroed();
+        lb_pair.token_x_mint = Pubkey::new_unique().to_bytes();
+        lb_pair.token_y_mint = Pubkey::new_unique().to_bytes();
+        lb_pair.reserve_x = Pubkey::new_unique().to_bytes();
+        lb_pair.reserve_y = Pubkey::new_unique().to_bytes();
+        lb_pair.oracle = Pubkey::new_unique().to_bytes();
+        // Bin arrays 0 and -1 have liquidity, -2 has none.
+        lb_pair.bin_array_bitmap[8] = 1;
+        lb_pair.bin_array_bitmap[7] = 1 << 63;
+
+        let ctx = SwapContext {
+            pool_id: Pubkey::new_unique(),
+            payer: Pubkey::new_unique(),
+            a_to_b: true,
+            token_program_in: spl_token::ID,
+            token_program_out: spl_token_2022::ID,
+            amount_in: 1_000,
+            min_amount_out: 990,
+        };
+        let ix = lb_pair.swap_instruction(&ctx)?;
+
+        assert_eq!(ix.program_id, METEORA_DLMM_ID);
+        assert_eq!(ix.accounts.len(), 17);
+        assert_eq!(ix.accounts[0].pubkey, ctx.pool_id);
+        assert_eq!(
+            ix.accounts[5].pubkey,
+            get_associated_token_address(
+                &ctx.payer,
+                &Pubkey::from(lb_pair.token_y_mint),
+                &spl_token_2022::ID
+            )
+        );
+        assert!(ix.accounts[10].is_signer);
+        assert_eq!(ix.accounts[12].pubkey, spl_token_2022::ID);
+        assert_eq!(ix.accounts[15].pubkey, derive_bin_array(ctx.pool_id, 0));
+        assert_eq!(ix.accounts[16].pubkey, derive_bin_array(ctx.pool_id, -1));
+        assert_eq!(&ix.data[..8], SWAP_DISCRIMINATOR);
+        assert_eq!(ix.data[8..16], 1_000u64.to_le_bytes());
+        assert_eq!(ix.data[16..], 990u64.to_le_bytes());
+
+        Ok(())
+    }
+}
@@ -5,13 +5,16 @@ use orca_whirlpools_core::{
     TickArrays, TickFacade, WhirlpoolFacade, WhirlpoolRewardInfoFacade, swap_quote_by_input_token,
     swap_quote_by_output_token, try_apply_transfer_fee,
 };
-use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
 use tracing::error;
 
 use crate::libs::solana_client::{
     models::*,
     pool::*,
-    protocols::orca::{constants::*, math::floor_division, token::get_epoch_transfer_fee},
+    protocols::orca::{
+        constants::*, math::floor_division, swap::swap_v2_instruction,
+        token::get_epoch_transfer_fee,
+    },
     registry::ProtocolEntity,
 };
 
@@ -176,6 +179,10 @@ impl DexPool for Whirlpool {
             }
         }
     }
+
+    fn swap_instruction(&self, ctx: &SwapContext) -> anyhow::Result<Instruction> {
+        swap_v2_instruction(self, ctx)
+    }
 }
 
 impl ProtocolIdentity for Whirlpool {
@@ -222,6 +229,26 @@ impl From<&OrcaTickArray> for TickArrayFacade {
     }
 }
 
+impl OrcaTickArray {
+    /// Returns the first tick index covered by this array.
+    #[must_use]
+    pub fn start_tick_index(&self) -> i32 {
+        match self {
+            Self::Fixed(a) => a.start_tick_index,
+            Self::Dynamic(a) => a.start_tick_index,
+        }
+    }
+
+    /// Returns the whirlpool this array belongs to.
+    #[must_use]
+    pub fn pubkey(&self) -> Pubkey {
+        match self {
+            Self::Fixed(a) => a.pubkey(),
+            Self::Dynamic(a) => a.pubkey(),
+        }
+    }
+}
+
 impl ProtocolIdentity for OrcaTickArray {
     fn protocol(&self) -> ProtocolKind {
         ProtocolKind::Orca
@@ -365,6 +392,7 @@ impl ProtocolEntity for DynamicTickArray {
         offset += 16;
 
         let mut ticks = Vec::with_capacity(TICK_ARRAY_SIZE);
+        let mut initialized_bitmap = 0u128;
 
         for i in 0..TICK_ARRAY_SIZE {
             // Ensure at least 1 byte remains for discriminant
@@ -401,6 +429,7 @@ impl ProtocolEntity for DynamicTickArray {
 
                 // Advance offset by the size of the parsed data
                 offset += DynamicTickData::LEN;
+                initialized_bitmap |= 1 << i;
             } else if discriminant == 0 {
                 // Uninitialized — only 1 byte (discriminant) was read
                 // No additional data to parse
@@ -411,6 +440,13 @@ impl ProtocolEntity for DynamicTickArray {
             }
         }
 
+        // Ticks are stored compactly, so the bitmap is the only way to map them back
+        // to their offsets. A mismatch would silently shift liquidity to the wrong ticks.
+        if initialized_bitmap != tick_bitmap {
+            error!("Tick bitmap mismatch: header {tick_bitmap:#x}, parsed {initialized_bitmap:#x}");
+            return None;
+        }
+
         Some(Self {
             start_tick_index,
             whirlpool,
@@ -554,21 +590,22 @@ impl From<&FixedTickArray> for TickArrayFacade {
 impl From<&DynamicTickArray> for TickArrayFacade {
     fn from(ta: &DynamicTickArray) -> Self {
         let mut ticks = [TickFacade::default(); TICK_ARRAY_SIZE];
-        let mut tick_idx = 0usize;
+        let mut initialized_ticks = ta.ticks.iter();
         for (offset, tick) in ticks.iter_mut().enumerate() {
-            let initialized = (ta.tick_bitmap >> offset) & 1 == 1;
-            if initialized {
-                let data = &ta.ticks[tick_idx];
-                *tick = TickFacade {
-                    initialized: true,
-                    liquidity_net: data.liquidity_net,
-                    liquidity_gross: data.liquidity_gross,
-                    fee_growth_outside_a: data.fee_growth_outside_a,
-                    fee_growth_outside_b: data.fee_growth_outside_b,
-                    reward_growths_outside: data.reward_growths_outside,
-                };
-                tick_idx += 1;
+            if (ta.tick_bitmap >> offset) & 1 == 0 {
+                continue;
             }
+            let Some(data) = initialized_ticks.next() else {
+                break;
+            };
+            *tick = TickFacade {
+                initialized: true,
+                liquidity_net: data.liquidity_net,
+                liquidity_gross: data.liquidity_gross,
+                fee_growth_outside_a: data.fee_growth_outside_a,
+                fee_growth_outside_b: data.fee_growth_outside_b,
+                reward_growths_outside: data.reward_growths_outside,
+            };
         }
         Self {
             start_tick_index: ta.start_tick_index,
@@ -576,3 +613,227 @@ impl From<&DynamicTickArray> for TickArrayFacade {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bytemuck::Zeroable;
+
+    use super::*;
+    use crate::libs::solana_client::{
+        protocols::orca::{SWAP_V2_DISCRIMINATOR, derive_oracle, derive_tick_array},
+        registry::{PROTOCOL_REGISTRY, ProtocolParser},
+    };
+
+    const LIQUIDITY: u128 = 1_000_000_000_000;
+
+    /// Initialized ticks of the test array as `(offset, liquidity_net)`.
+    const INITIALIZED_TICKS: [(usize, i128); 2] = [(10, 500_000_000_000), (20, -500_000_000_000)];
+
+    fn split(value: u128) -> [u64; 2] {
+        [value as u64, (value >> 64) as u64]
+    }
+
+    fn whirlpool_bytes() -> [u8; 32] {
+        Pubkey::new_unique().to_bytes()
+    }
+
+    fn fixed_tick_array_data(whirlpool: [u8; 32]) -> Vec<u8> {
+        let mut array = FixedTickArray::zeroed();
+        array.whirlpool = whirlpool;
+
+        for (offset, liquidity_net) in INITIALIZED_TICKS {
+            let mut tick = Tick::zeroed();
+            tick.initialized = 1;
+            tick.liquidity_net = split(liquidity_net as u128);
+            tick.liquidity_gross = split(liquidity_net.unsigned_abs());
+
+            if offset < 64 {
+                array.ticks_1[offset] = tick;
+            } else {
+                array.ticks_2[offset - 64] = tick;
+            }
+        }
+
+        let mut data = FixedTickArray::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&array));
+        data
+    }
+
+    fn dynamic_tick_array_data(whirlpool: [u8; 32]) -> Vec<u8> {
+        let tick_bitmap = INITIALIZED_TICKS
+            .iter()
+            .fold(0u128, |bitmap, (offset, _)| bitmap | (1 << offset));
+
+        let mut data = DynamicTickArray::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&0i32.to_le_bytes());
+        data.extend_from_slice(&whirlpool);
+        data.extend_from_slice(&tick_bitmap.to_le_bytes());
+
+        for offset in 0..TICK_ARRAY_SIZE {
+            if let Some((_, liquidity_net)) = INITIALIZED_TICKS.iter().find(|(o, _)| *o == offset) {
+                data.push(1);
+                data.extend_from_slice(&liquidity_net.to_le_bytes());
+                data.extend_from_slice(&liquidity_net.unsigned_abs().to_le_bytes());
+                data.extend_from_slice(&[0u8; DynamicTickData::LEN - 32]);
+            } else {
+                data.push(0);
+            }
+        }
+
+        data
+    }
+
+    fn whirlpool() -> Whirlpool {
+        let mut whirlpool = Whirlpool::zeroed();
+        whirlpool.tick_spacing = 1;
+        whirlpool.fee_rate = 3000;
+        whirlpool.liquidity = split(LIQUIDITY);
+        // Q64.64 price of 1.0 at tick 0
+        whirlpool.sqrt_price = split(1 << 64);
+        whirlpool.tick_current_index = 0;
+        whirlpool
+    }
+
+    fn quote_exact_in(array: &OrcaTickArray, amount: u64) -> (u64, u64) {
+        let quote = swap_quote_by_input_token(
+            amount,
+            false,
+            0,
+            WhirlpoolFacade::from(&whirlpool()),
+            None,
+            TickArrays::One(TickArrayFacade::from(array)),
+            0,
+            None,
+            None,
+        )
+        .expect("quote must succeed");
+
+        (quote.token_est_out, quote.trade_fee)
+    }
+
+    /// Decodes a tick array the way account updates are, through the protocol registry.
+    fn decode(data: &[u8]) -> Option<OrcaTickArray> {
+        let item = PROTOCOL_REGISTRY.get_account_item(&ORCA_ID, data.len(), data)?;
+        let ProtocolParser::Program(parser) = &item.parser else {
+            return None;
+        };
+
+        match parser(data)? {
+            PoolState::FixedTickArrayOrca(array) => Some(OrcaTickArray::Fixed(array)),
+            PoolState::DynamicTickArrayOrca(array) => Some(OrcaTickArray::Dynamic(*array)),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_decode_tick_array_layouts() {
+        let whirlpool = whirlpool_bytes();
+
+        let fixed = decode(&fixed_tick_array_data(whirlpool)).unwrap();
+        let dynamic = decode(&dynamic_tick_array_data(whirlpool)).unwrap();
+
+        assert!(matches!(fixed, OrcaTickArray::Fixed(_)));
+        assert!(matches!(dynamic, OrcaTickArray::Dynamic(_)));
+        assert_eq!(fixed.pubkey(), dynamic.pubkey());
+        assert_eq!(fixed.start_tick_index(), dynamic.start_tick_index());
+        assert_eq!(
+            TickArrayFacade::from(&fixed),
+            TickArrayFacade::from(&dynamic)
+        );
+    }
+
+    #[test]
+    fn test_decode_dynamic_tick_array_bitmap_mismatch() {
+        let mut data = dynamic_tick_array_data(whirlpool_bytes());
+        // Flip a bitmap bit for a tick stored as uninitialized
+        data[8 + 4 + 32] ^= 1;
+
+        assert!(decode(&data).is_none());
+    }
+
+    #[test]
+    fn test_registry_selects_tick_array_layout() {
+        let whirlpool = whirlpool_bytes();
+
+        for data in [
+            fixed_tick_array_data(whirlpool),
+            dynamic_tick_array_data(whirlpool),
+        ] {
+            let item = PROTOCOL_REGISTRY
+                .get_account_item(&ORCA_ID, data.len(), &data)
+                .unwrap();
+            let ProtocolParser::Program(parser) = &item.parser else {
+                panic!("Unexpected parser for {}", item.name);
+            };
+
+            match parser(&data).unwrap() {
+                PoolState::FixedTickArrayOrca(_) => {
+                    assert_eq!(data.len(), FixedTickArray::DATA_SIZE);
+                }
+                PoolState::DynamicTickArrayOrca(_) => {
+                    assert!(data.starts_with(DynamicTickArray::DISCRIMINATOR));
+                }
+                state => panic!("Unexpected pool state: {state:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_quote_is_layout_independent() {
+        let whirlpool = whirlpool_bytes();
+
+        let fixed = decode(&fixed_tick_array_data(whirlpool)).unwrap();
+        let dynamic = decode(&dynamic_tick_array_data(whirlpool)).unwrap();
+
+        // Large enough to cross the initialized ticks
+        let amount = 1_500_000_000;
+        let (fixed_out, fixed_fee) = quote_exact_in(&fixed, amount);
+        let (dynamic_out, dynamic_fee) = quote_exact_in(&dynamic, amount);
+
+        assert!(fixed_out > 0);
+        assert_eq!(fixed_out, dynamic_out);
+        assert_eq!(fixed_fee, dynamic_fee);
+    }
+
+    #[test]
+    fn test_swap_instruction_walks_tick_arrays_towards_the_price() -> anyhow::Result<()> {
+        let mut pool = whirlpool();
+        pool.token_mint_a = whirlpool_bytes();
+        pool.token_mint_b = whirlpool_bytes();
+        pool.token_vault_a = whirlpool_bytes();
+        pool.token_vault_b = whirlpool_bytes();
+
+        let ctx = SwapContext {
+            pool_id: Pubkey::new_unique(),
+            payer: Pubkey::new_unique(),
+            a_to_b: true,
+            token_program_in: spl_token::ID,
+            token_program_out: spl_token_2022::ID,
+            amount_in: 1_000,
+            min_amount_out: 990,
+        };
+        let ix = pool.swap_instruction(&ctx)?;
+
+        assert_eq!(ix.program_id, ORCA_ID);
+        assert_eq!(ix.accounts.len(), 15);
+        assert_eq!(ix.accounts[0].pubkey, spl_token::ID);
+        assert_eq!(ix.accounts[1].pubkey, spl_token_2022::ID);
+        assert!(ix.accounts[3].is_signer);
+        assert_eq!(ix.accounts[4].pubkey, ctx.pool_id);
+        // A to B walks down from the array holding the current tick.
+        for (account, start_tick_index) in ix.accounts[11..14].iter().zip([0, -88, -176]) {
+            assert_eq!(
+                account.pubkey,
+                derive_tick_array(ctx.pool_id, start_tick_index)
+            );
+        }
+        assert_eq!(ix.accounts[14].pubkey, derive_oracle(ctx.pool_id));
+        assert_eq!(&ix.data[..8], SWAP_V2_DISCRIMINATOR);
+        assert_eq!(ix.data[8..16], 1_000u64.to_le_bytes());
+        assert_eq!(ix.data[16..24], 990u64.to_le_bytes());
+        assert_eq!(ix.data[24..40], MIN_SQRT_PRICE.to_le_bytes());
+        assert_eq!(ix.data[40..], [1, 1, 0]);
+
+        Ok(())
+    }
+}
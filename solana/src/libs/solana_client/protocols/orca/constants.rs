@@ -1,3 +1,8 @@
 use solana_sdk::{pubkey, pubkey::Pubkey};
 
 pub const ORCA_ID: Pubkey = pubkey!("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc");
+
+/// Lowest sqrt price a swap may move the pool to, as a Q64.64 value.
+pub const MIN_SQRT_PRICE: u128 = 4_295_048_016;
+/// Highest sqrt price a swap may move the pool to, as a Q64.64 value.
+pub const MAX_SQRT_PRICE: u128 = 79_226_673_515_401_279_992_447_579_055;
@@ -2,7 +2,8 @@ pub mod account;
 pub mod constants;
 pub mod math;
 pub mod oracle;
+pub mod pda;
 pub mod swap;
 pub mod token;
 
-pub use super::orca::{account::*, constants::*, oracle::*, swap::*};
+pub use super::orca::{account::*, constants::*, oracle::*, pda::*, swap::*};
@@ -0,0 +1,26 @@
+use solana_sdk::pubkey::Pubkey;
+
+use crate::libs::solana_client::protocols::orca::ORCA_ID;
+
+pub const TICK_ARRAY_SEED: &[u8] = b"tick_array";
+pub const ORACLE_SEED: &[u8] = b"oracle";
+
+/// Derives the tick array of `whirlpool` starting at `start_tick_index`.
+#[must_use]
+pub fn derive_tick_array(whirlpool: Pubkey, start_tick_index: i32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            TICK_ARRAY_SEED,
+            whirlpool.as_ref(),
+            start_tick_index.to_string().as_bytes(),
+        ],
+        &ORCA_ID,
+    )
+    .0
+}
+
+/// Derives the adaptive fee oracle of `whirlpool`.
+#[must_use]
+pub fn derive_oracle(whirlpool: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[ORACLE_SEED, whirlpool.as_ref()], &ORCA_ID).0
+}
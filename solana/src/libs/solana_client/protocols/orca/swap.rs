@@ -1,7 +1,25 @@
 use bytemuck::{Pod, Zeroable};
-use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
 
-use crate::libs::solana_client::{protocols::orca::constants::ORCA_ID, registry::ProtocolEntity};
+use crate::libs::solana_client::{
+    pool::SwapContext,
+    protocols::{
+        orca::{
+            Whirlpool,
+            constants::*,
+            get_start_tick_indexes,
+            pda::{derive_oracle, derive_tick_array},
+        },
+        utils::{MEMO_PROGRAM_ID, get_associated_token_address},
+    },
+    registry::ProtocolEntity,
+};
+
+/// Anchor discriminator of the `swap_v2` instruction.
+pub const SWAP_V2_DISCRIMINATOR: [u8; 8] = [43, 4, 237, 11, 26, 201, 30, 98];
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Pod, Zeroable)]
@@ -18,3 +36,74 @@ impl ProtocolEntity for Swap {
         Some(Self {})
     }
 }
+
+/// Builds a `swap_v2` instruction swapping exactly `ctx.amount_in` between the associated
+/// token accounts of the payer.
+///
+/// The tick arrays are the ones the quote walks from the current tick; missing ones are
+/// padded with the last, as the program skips uninitialized and repeated arrays.
+///
+/// # Errors
+/// Returns an error if the current tick has no valid tick array.
+pub fn swap_v2_instruction(pool: &Whirlpool, ctx: &SwapContext) -> anyhow::Result<Instruction> {
+    let mut tick_arrays: Vec<Pubkey> =
+        get_start_tick_indexes(pool.tick_current_index, pool.tick_spacing, ctx.a_to_b)
+            .into_iter()
+            .map(|start_tick_index| derive_tick_array(ctx.pool_id, start_tick_index))
+            .collect();
+    let Some(&last) = tick_arrays.last() else {
+        anyhow::bail!("Orca Whirlpool: no tick arrays found");
+    };
+    tick_arrays.resize(3, last);
+
+    let (token_program_a, token_program_b) = ctx.token_programs();
+    let (mint_a, mint_b) = (
+        Pubkey::from(pool.token_mint_a),
+        Pubkey::from(pool.token_mint_b),
+    );
+    let sqrt_price_limit = if ctx.a_to_b {
+        MIN_SQRT_PRICE
+    } else {
+        MAX_SQRT_PRICE
+    };
+
+    let mut data = Vec::with_capacity(43);
+    data.extend_from_slice(&SWAP_V2_DISCRIMINATOR);
+    data.extend_from_slice(&ctx.amount_in.to_le_bytes());
+    data.extend_from_slice(&ctx.min_amount_out.to_le_bytes());
+    data.extend_from_slice(&sqrt_price_limit.to_le_bytes());
+    // amount_specified_is_input, a_to_b, no remaining accounts info.
+    data.extend_from_slice(&[1, u8::from(ctx.a_to_b), 0]);
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(token_program_a, false),
+        AccountMeta::new_readonly(token_program_b, false),
+        AccountMeta::new_readonly(MEMO_PROGRAM_ID, false),
+        AccountMeta::new_readonly(ctx.payer, true),
+        AccountMeta::new(ctx.pool_id, false),
+        AccountMeta::new_readonly(mint_a, false),
+        AccountMeta::new_readonly(mint_b, false),
+        AccountMeta::new(
+            get_associated_token_address(&ctx.payer, &mint_a, &token_program_a),
+            false,
+        ),
+        AccountMeta::new(Pubkey::from(pool.token_vault_a), false),
+        AccountMeta::new(
+            get_associated_token_address(&ctx.payer, &mint_b, &token_program_b),
+            false,
+        ),
+        AccountMeta::new(Pubkey::from(pool.token_vault_b), false),
+    ];
+    accounts.extend(
+        tick_arrays
+            .into_iter()
+            .map(|tick_array| AccountMeta::new(tick_array, false)),
+    );
+    accounts.push(AccountMeta::new(derive_oracle(ctx.pool_id), false));
+
+    Ok(Instruction {
+        program_id: ORCA_ID,
+        accounts,
+        data,
+    })
+}
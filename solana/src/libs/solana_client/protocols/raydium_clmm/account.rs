@@ -1,13 +1,14 @@
 use std::{collections::VecDeque, ops::BitAnd};
 
 use bytemuck::{Pod, Zeroable};
-use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
 
 use crate::libs::solana_client::{
     models::*,
     pool::*,
     protocols::raydium_clmm::{
-        constants::*, error::*, instructions::swap_internal, libraries::*, token_2022::*,
+        constants::*, error::*, instructions::swap_internal, libraries::*,
+        swap::swap_v2_instruction, token_2022::*,
     },
     registry::ProtocolEntity,
 };
@@ -280,6 +281,10 @@ impl DexPool for PoolState {
             compute_units,
         })
     }
+
+    fn swap_instruction(&self, ctx: &SwapContext) -> anyhow::Result<Instruction> {
+        swap_v2_instruction(self, ctx)
+    }
 }
 
 impl ProtocolIdentity for PoolState {
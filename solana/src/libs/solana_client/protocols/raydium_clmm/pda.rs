@@ -12,3 +12,19 @@ pub fn derive_tick_array_bitmap_extension(pool_id: Pubkey) -> Pubkey {
     )
     .0
 }
+
+pub const TICK_ARRAY_SEED: &str = "tick_array";
+
+/// Derives the tick array of `pool_id` starting at `start_index`.
+#[must_use]
+pub fn derive_tick_array(pool_id: Pubkey, start_index: i32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_id.as_ref(),
+            &start_index.to_be_bytes(),
+        ],
+        &RAYDIUM_CLMM_ID,
+    )
+    .0
+}
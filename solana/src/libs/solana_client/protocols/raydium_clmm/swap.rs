@@ -1,10 +1,29 @@
 use bytemuck::{Pod, Zeroable};
-use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
 
 use crate::libs::solana_client::{
-    protocols::raydium_clmm::constants::RAYDIUM_CLMM_ID, registry::ProtocolEntity,
+    pool::SwapContext,
+    protocols::{
+        raydium_clmm::{
+            PoolState,
+            constants::RAYDIUM_CLMM_ID,
+            libraries::{MAX_SQRT_PRICE_X64, MIN_SQRT_PRICE_X64},
+            pda::{derive_tick_array, derive_tick_array_bitmap_extension},
+        },
+        utils::{MEMO_PROGRAM_ID, get_associated_token_address},
+    },
+    registry::ProtocolEntity,
 };
 
+/// Anchor discriminator of the `swap_v2` instruction.
+pub const SWAP_V2_DISCRIMINATOR: [u8; 8] = [43, 4, 237, 11, 26, 201, 30, 98];
+
+/// Tick arrays passed to a swap, enough for the sizes the quotes allow.
+const SWAP_TICK_ARRAYS: usize = 3;
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Pod, Zeroable)]
 pub struct Swap {
@@ -20,3 +39,154 @@ impl ProtocolEntity for Swap {
         Some(Self {})
     }
 }
+
+/// Builds a `swap_v2` instruction swapping exactly `ctx.amount_in` between the associated
+/// token accounts of the payer.
+///
+/// The tick arrays are the initialized ones in the swap direction, starting from the
+/// current tick, as located by the pool's default tick array bitmap.
+///
+/// # Errors
+/// Returns an error if the current tick is outside the default bitmap, which would require
+/// the bitmap extension to locate the tick arrays, or no tick array is initialized in the
+/// swap direction.
+pub fn swap_v2_instruction(pool: &PoolState, ctx: &SwapContext) -> anyhow::Result<Instruction> {
+    let zero_for_one = ctx.a_to_b;
+    if pool.is_overflow_default_tickarray_bitmap(vec![pool.tick_current]) {
+        anyhow::bail!("Raydium CLMM: current tick is outside the default tick array bitmap");
+    }
+
+    let (_, mut start_index) = pool.get_first_initialized_tick_array(&None, zero_for_one)?;
+    let mut tick_arrays = vec![derive_tick_array(ctx.pool_id, start_index)];
+    while tick_arrays.len() < SWAP_TICK_ARRAYS {
+        // Arrays beyond the default bitmap need the extension, the swap stops short of them.
+        let Ok(Some(next_index)) =
+            pool.next_initialized_tick_array_start_index(&None, start_index, zero_for_one)
+        else {
+            break;
+        };
+        start_index = next_index;
+        tick_arrays.push(derive_tick_array(ctx.pool_id, start_index));
+    }
+
+    let (input_vault, output_vault, input_mint, output_mint) = if zero_for_one {
+        (
+            pool.token_vault_0,
+            pool.token_vault_1,
+            pool.token_mint_0,
+            pool.token_mint_1,
+        )
+    } else {
+        (
+            pool.token_vault_1,
+            pool.token_vault_0,
+            pool.token_mint_1,
+            pool.token_mint_0,
+        )
+    };
+    let [input_mint, output_mint] = [input_mint, output_mint].map(Pubkey::from);
+    let sqrt_price_limit_x64 = if zero_for_one {
+        MIN_SQRT_PRICE_X64 + 1
+    } else {
+        MAX_SQRT_PRICE_X64 - 1
+    };
+
+    let mut data = Vec::with_capacity(41);
+    data.extend_from_slice(&SWAP_V2_DISCRIMINATOR);
+    data.extend_from_slice(&ctx.amount_in.to_le_bytes());
+    data.extend_from_slice(&ctx.min_amount_out.to_le_bytes());
+    data.extend_from_slice(&sqrt_price_limit_x64.to_le_bytes());
+    // is_base_input
+    data.push(1);
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(ctx.payer, true),
+        AccountMeta::new_readonly(Pubkey::from(pool.amm_config), false),
+        AccountMeta::new(ctx.pool_id, false),
+        AccountMeta::new(
+            get_associated_token_address(&ctx.payer, &input_mint, &ctx.token_program_in),
+            false,
+        ),
+        AccountMeta::new(
+            get_associated_token_address(&ctx.payer, &output_mint, &ctx.token_program_out),
+            false,
+        ),
+        AccountMeta::new(Pubkey::from(input_vault), false),
+        AccountMeta::new(Pubkey::from(output_vault), false),
+        AccountMeta::new(Pubkey::from(pool.observation_key), false),
+        AccountMeta::new_readonly(spl_token::ID, false),
+        AccountMeta::new_readonly(spl_token_2022::ID, false),
+        AccountMeta::new_readonly(MEMO_PROGRAM_ID, false),
+        AccountMeta::new_readonly(input_mint, false),
+        AccountMeta::new_readonly(output_mint, false),
+        AccountMeta::new_readonly(derive_tick_array_bitmap_extension(ctx.pool_id), false),
+    ];
+    accounts.extend(
+        tick_arrays
+            .into_iter()
+            .map(|tick_array| AccountMeta::new(tick_array, false)),
+    );
+
+    Ok(Instruction {
+        program_id: RAYDIUM_CLMM_ID,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use bytemuck::Zeroable;
+
+    use super::*;
+    use crate::libs::solana_client::pool::DexPool;
+
+    #[test]
+    fn test_swap_instruction_passes_initialized_tick_arrays() -> anyhow::Result<()> {
+        let mut pool = PoolState::zeroed();
+        pool.token_mint_0 = Pubkey::new_unique().to_bytes();
+        pool.token_mint_1 = Pubkey::new_unique().to_bytes();
+        pool.token_vault_0 = Pubkey::new_unique().to_bytes();
+        pool.token_vault_1 = Pubkey::new_unique().to_bytes();
+        pool.tick_spacing = 1;
+        // Tick arrays starting at 0 and -60 are initialized, -120 is not.
+        pool.tick_array_bitmap[8] = 1;
+        pool.tick_array_bitmap[7] = 1 << 63;
+
+        let ctx = SwapContext {
+            pool_id: Pubkey::new_unique(),
+            payer: Pubkey::new_unique(),
+            a_to_b: true,
+            token_program_in: spl_token::ID,
+            token_program_out: spl_token::ID,
+            amount_in: 1_000,
+            min_amount_out: 990,
+        };
+        let ix = pool.swap_instruction(&ctx)?;
+
+        assert_eq!(ix.program_id, RAYDIUM_CLMM_ID);
+        assert!(ix.accounts[0].is_signer);
+        assert_eq!(ix.accounts[2].pubkey, ctx.pool_id);
+        assert_eq!(ix.accounts[5].pubkey, Pubkey::from(pool.token_vault_0));
+        assert_eq!(ix.accounts[11].pubkey, Pubkey::from(pool.token_mint_0));
+        assert_eq!(
+            ix.accounts[13].pubkey,
+            derive_tick_array_bitmap_extension(ctx.pool_id)
+        );
+        let tick_arrays: Vec<_> = ix.accounts[14..].iter().map(|a| a.pubkey).collect();
+        assert_eq!(
+            tick_arrays,
+            [
+                derive_tick_array(ctx.pool_id, 0),
+                derive_tick_array(ctx.pool_id, -60)
+            ]
+        );
+        assert_eq!(&ix.data[..8], SWAP_V2_DISCRIMINATOR);
+        assert_eq!(ix.data[8..16], 1_000u64.to_le_bytes());
+        assert_eq!(ix.data[16..24], 990u64.to_le_bytes());
+        assert_eq!(ix.data[24..40], (MIN_SQRT_PRICE_X64 + 1).to_le_bytes());
+        assert_eq!(ix.data[40], 1);
+
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 #![allow(clippy::identity_op)]
 
 use bytemuck::{Pod, Zeroable};
-use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
 
 use crate::libs::solana_client::{
     models::*,
@@ -11,6 +11,7 @@ use crate::libs::solana_client::{
         curve,
         curve::TradeDirection,
         error::ErrorCode,
+        swap::swap_base_input_instruction,
         token_2022::{get_transfer_fee, get_transfer_inverse_fee},
     },
     registry::ProtocolEntity,
@@ -189,6 +190,11 @@ impl DexPool for PoolState {
             anyhow::bail!("Missing AmmConfig for Raydium CPMM")
         };
 
+        let block_timestamp = u64::try_from(ctx.clock.unix_timestamp).unwrap_or_default();
+        if !self.is_swap_enabled(block_timestamp) {
+            anyhow::bail!("Raydium CPMM: swap is disabled or pool is not open yet")
+        }
+
         let (vault_0, vault_1) = ctx
             .vaults
             .ok_or_else(|| anyhow::anyhow!("Missing vault amounts for Raydium CPMM"))?;
@@ -292,6 +298,10 @@ impl DexPool for PoolState {
             }
         }
     }
+
+    fn swap_instruction(&self, ctx: &SwapContext) -> anyhow::Result<Instruction> {
+        Ok(swap_base_input_instruction(self, ctx))
+    }
 }
 
 impl ProtocolIdentity for PoolState {
@@ -301,6 +311,13 @@ impl ProtocolIdentity for PoolState {
 }
 
 impl PoolState {
+    /// Mirrors the on-chain swap precondition: the swap status bit must be clear
+    /// and the pool must already be open.
+    #[must_use]
+    pub fn is_swap_enabled(&self, block_timestamp: u64) -> bool {
+        self.status & POOL_STATUS_SWAP_DISABLED == 0 && block_timestamp >= self.open_time
+    }
+
     pub fn vault_amount_without_fee(
         &self,
         vault_0: u64,
@@ -348,3 +365,106 @@ impl PoolState {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bytemuck::Zeroable;
+    use solana_sdk::clock::Clock;
+
+    use super::*;
+    use crate::{
+        libs::solana_client::protocols::raydium_cpmm::swap::SWAP_BASE_INPUT_DISCRIMINATOR,
+        test_utils::mint_account,
+    };
+
+    const VAULT_AMOUNT: u64 = 1_000_000_000;
+
+    fn pool_state() -> PoolState {
+        let mut pool = PoolState::zeroed();
+        pool.token_0_mint = Pubkey::new_unique().to_bytes();
+        pool.token_1_mint = Pubkey::new_unique().to_bytes();
+        pool
+    }
+
+    fn quote_exact_in(pool: &PoolState, clock: &Clock, amount: u64) -> anyhow::Result<QuoteResult> {
+        let mint_in = mint_account(9);
+        let mint_out = mint_account(6);
+
+        let mut amm_config = AmmConfig::zeroed();
+        amm_config.trade_fee_rate = 2500;
+
+        pool.quote(&QuoteContext {
+            quote_type: QuoteType::ExactIn(amount),
+            a_to_b: true,
+            clock,
+            mint_in: &mint_in,
+            mint_out: &mint_out,
+            vaults: Some((VAULT_AMOUNT, VAULT_AMOUNT)),
+            liquidity: None,
+            bitmap: None,
+            amm_config: Some(AmmConfigType::Cpmm(amm_config)),
+            oracle: None,
+        })
+    }
+
+    #[test]
+    fn test_quote_exact_in() -> anyhow::Result<()> {
+        let result = quote_exact_in(&pool_state(), &Clock::default(), 1_000_000)?;
+
+        // 0.25% trade fee, then x * y = k on the remaining 997_500
+        assert_eq!(result.total_amount_in_gross, 1_000_000);
+        assert_eq!(result.total_amount_in_net, 1_000_000);
+        assert_eq!(result.total_fee, 2_500);
+        assert_eq!(result.total_amount_out, 996_505);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_quote_rejects_disabled_swap() {
+        let mut pool = pool_state();
+        pool.status = POOL_STATUS_SWAP_DISABLED;
+        assert!(quote_exact_in(&pool, &Clock::default(), 1_000_000).is_err());
+
+        let mut pool = pool_state();
+        pool.open_time = 100;
+        let clock = Clock {
+            unix_timestamp: 50,
+            ..Clock::default()
+        };
+        assert!(quote_exact_in(&pool, &clock, 1_000_000).is_err());
+    }
+
+    #[test]
+    fn test_swap_instruction_routes_through_input_vault() -> anyhow::Result<()> {
+        let mut pool = pool_state();
+        pool.token_0_vault = Pubkey::new_unique().to_bytes();
+        pool.token_1_vault = Pubkey::new_unique().to_bytes();
+        pool.token_0_program = spl_token::ID.to_bytes();
+        pool.token_1_program = spl_token::ID.to_bytes();
+
+        let ctx = SwapContext {
+            pool_id: Pubkey::new_unique(),
+            payer: Pubkey::new_unique(),
+            a_to_b: false,
+            token_program_in: spl_token::ID,
+            token_program_out: spl_token::ID,
+            amount_in: 1_000,
+            min_amount_out: 990,
+        };
+        let ix = pool.swap_instruction(&ctx)?;
+
+        assert_eq!(ix.program_id, RAYDIUM_CPMM_ID);
+        assert_eq!(ix.accounts.len(), 13);
+        assert!(ix.accounts[0].is_signer);
+        assert_eq!(ix.accounts[3].pubkey, ctx.pool_id);
+        assert_eq!(ix.accounts[6].pubkey, Pubkey::from(pool.token_1_vault));
+        assert_eq!(ix.accounts[7].pubkey, Pubkey::from(pool.token_0_vault));
+        assert_eq!(ix.accounts[10].pubkey, Pubkey::from(pool.token_1_mint));
+        assert_eq!(&ix.data[..8], SWAP_BASE_INPUT_DISCRIMINATOR);
+        assert_eq!(ix.data[8..16], 1_000u64.to_le_bytes());
+        assert_eq!(ix.data[16..], 990u64.to_le_bytes());
+
+        Ok(())
+    }
+}
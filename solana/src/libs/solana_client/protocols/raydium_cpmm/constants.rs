@@ -1,3 +1,6 @@
 use solana_sdk::{pubkey, pubkey::Pubkey};
 
 pub const RAYDIUM_CPMM_ID: Pubkey = pubkey!("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C");
+
+/// Bit of `PoolState::status` that disables swaps when set.
+pub const POOL_STATUS_SWAP_DISABLED: u8 = 1 << 2;
@@ -2,6 +2,7 @@ pub mod account;
 pub mod constants;
 pub mod curve;
 pub mod error;
+pub mod pda;
 pub mod swap;
 pub mod token_2022;
 pub mod utils;
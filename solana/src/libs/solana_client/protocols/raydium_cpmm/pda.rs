@@ -0,0 +1,11 @@
+use solana_sdk::pubkey::Pubkey;
+
+use crate::libs::solana_client::protocols::raydium_cpmm::RAYDIUM_CPMM_ID;
+
+pub const AUTH_SEED: &str = "vault_and_lp_mint_auth_seed";
+
+/// Derives the authority owning the vaults of every pool.
+#[must_use]
+pub fn derive_authority() -> Pubkey {
+    Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &RAYDIUM_CPMM_ID).0
+}
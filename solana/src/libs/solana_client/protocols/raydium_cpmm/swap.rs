@@ -1,10 +1,21 @@
 use bytemuck::{Pod, Zeroable};
-use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
 
 use crate::libs::solana_client::{
-    protocols::raydium_cpmm::constants::RAYDIUM_CPMM_ID, registry::ProtocolEntity,
+    pool::SwapContext,
+    protocols::{
+        raydium_cpmm::{PoolState, constants::RAYDIUM_CPMM_ID, pda::derive_authority},
+        utils::get_associated_token_address,
+    },
+    registry::ProtocolEntity,
 };
 
+/// Anchor discriminator of the `swap_base_input` instruction.
+pub const SWAP_BASE_INPUT_DISCRIMINATOR: [u8; 8] = [143, 190, 90, 218, 196, 30, 51, 222];
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Pod, Zeroable)]
 pub struct Swap {
@@ -20,3 +31,62 @@ impl ProtocolEntity for Swap {
         Some(Self {})
     }
 }
+
+/// Builds a `swap_base_input` instruction swapping exactly `ctx.amount_in` between the
+/// associated token accounts of the payer.
+#[must_use]
+pub fn swap_base_input_instruction(pool: &PoolState, ctx: &SwapContext) -> Instruction {
+    let (input_vault, output_vault, input_program, output_program, input_mint, output_mint) =
+        if ctx.a_to_b {
+            (
+                pool.token_0_vault,
+                pool.token_1_vault,
+                pool.token_0_program,
+                pool.token_1_program,
+                pool.token_0_mint,
+                pool.token_1_mint,
+            )
+        } else {
+            (
+                pool.token_1_vault,
+                pool.token_0_vault,
+                pool.token_1_program,
+                pool.token_0_program,
+                pool.token_1_mint,
+                pool.token_0_mint,
+            )
+        };
+    let [input_program, output_program, input_mint, output_mint] =
+        [input_program, output_program, input_mint, output_mint].map(Pubkey::from);
+
+    let mut data = Vec::with_capacity(24);
+    data.extend_from_slice(&SWAP_BASE_INPUT_DISCRIMINATOR);
+    data.extend_from_slice(&ctx.amount_in.to_le_bytes());
+    data.extend_from_slice(&ctx.min_amount_out.to_le_bytes());
+
+    Instruction {
+        program_id: RAYDIUM_CPMM_ID,
+        accounts: vec![
+            AccountMeta::new_readonly(ctx.payer, true),
+            AccountMeta::new_readonly(derive_authority(), false),
+            AccountMeta::new_readonly(Pubkey::from(pool.amm_config), false),
+            AccountMeta::new(ctx.pool_id, false),
+            AccountMeta::new(
+                get_associated_token_address(&ctx.payer, &input_mint, &input_program),
+                false,
+            ),
+            AccountMeta::new(
+                get_associated_token_address(&ctx.payer, &output_mint, &output_program),
+                false,
+            ),
+            AccountMeta::new(Pubkey::from(input_vault), false),
+            AccountMeta::new(Pubkey::from(output_vault), false),
+            AccountMeta::new_readonly(input_program, false),
+            AccountMeta::new_readonly(output_program, false),
+            AccountMeta::new_readonly(input_mint, false),
+            AccountMeta::new_readonly(output_mint, false),
+            AccountMeta::new(Pubkey::from(pool.observation_key), false),
+        ],
+        data,
+    }
+}
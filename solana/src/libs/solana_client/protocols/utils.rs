@@ -1,3 +1,12 @@
+use solana_sdk::{pubkey, pubkey::Pubkey};
+
+/// Program owning the associated token accounts of wallets.
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+/// SPL memo program, required by the swaps supporting `spl-token-2022` mints.
+pub const MEMO_PROGRAM_ID: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
 /// Parses the token amount from a raw SPL token account data buffer.
 ///
 /// SPL token account layout:
@@ -16,3 +25,18 @@ pub fn parse_vault_amount(data: &[u8]) -> anyhow::Result<u64> {
         .ok_or_else(|| anyhow::anyhow!("vault account data too short"))?;
     Ok(u64::from_le_bytes(bytes.try_into()?))
 }
+
+/// Derives the associated token account of `owner` for `mint` under `token_program`
+/// (`spl-token` or `spl-token-2022`).
+#[must_use]
+pub fn get_associated_token_address(
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
@@ -1,4 +1,6 @@
-use ahash::AHashMap;
+use std::sync::OnceLock;
+
+use ahash::{AHashMap, AHashSet};
 use anyhow::anyhow;
 use solana_sdk::pubkey::Pubkey;
 
@@ -13,6 +15,8 @@ pub struct RegistryItem {
 
 pub struct ProtocolRegistry {
     pub map: AHashMap<RegistryLookup, RegistryItem>,
+    /// Programs whose entries are active for this run (unset = all).
+    enabled_programs: OnceLock<AHashSet<Pubkey>>,
 }
 
 impl Default for ProtocolRegistry {
@@ -27,9 +31,28 @@ impl ProtocolRegistry {
     pub fn new() -> Self {
         Self {
             map: AHashMap::new(),
+            enabled_programs: OnceLock::new(),
         }
     }
 
+    /// Restricts lookups to entries of the given programs for the rest of the run.
+    ///
+    /// Entries of other programs stay registered but are never matched, so their
+    /// accounts and instructions are neither decoded nor subscribed to.
+    pub fn enable_only(&self, program_ids: impl IntoIterator<Item = Pubkey>) -> anyhow::Result<()> {
+        self.enabled_programs
+            .set(program_ids.into_iter().collect())
+            .map_err(|_| anyhow!("Enabled programs already set"))
+    }
+
+    /// Returns true if entries of the program are active for this run.
+    #[must_use]
+    pub fn is_enabled(&self, program_id: &Pubkey) -> bool {
+        self.enabled_programs
+            .get()
+            .is_none_or(|enabled| enabled.contains(program_id))
+    }
+
     /// Registers a new entity and its parser into the registry.
     pub fn add<T, Out>(&mut self, wrap: fn(T) -> Out)
     where
@@ -75,6 +98,10 @@ impl ProtocolRegistry {
         size: usize,
         payload: &[u8],
     ) -> Option<&RegistryItem> {
+        if !self.is_enabled(program_id) {
+            return None;
+        }
+
         self.map.iter().find_map(|(lookup, item)| {
             let RegistryLookup::Program {
                 program_id: reg_id,
@@ -112,6 +139,10 @@ impl ProtocolRegistry {
         program_id: &Pubkey,
         payload: &[u8],
     ) -> Option<&RegistryItem> {
+        if !self.is_enabled(program_id) {
+            return None;
+        }
+
         self.map
             .iter()
             .filter_map(|(lookup, item)| match lookup {
@@ -129,11 +160,16 @@ impl ProtocolRegistry {
     }
 
     /// Returns all registered lookups and items associated with a specific Program ID.
+    /// Disabled programs have no entries.
     #[must_use]
     pub fn get_all_by_program_id(
         &self,
         program_id: &Pubkey,
     ) -> Vec<(&RegistryLookup, &RegistryItem)> {
+        if !self.is_enabled(program_id) {
+            return vec![];
+        }
+
         self.map
             .iter()
             .filter(|(lookup, _)| match lookup {
@@ -150,7 +186,8 @@ impl ProtocolRegistry {
     /// Returns a list of registry entries for the provided program address strings.
     ///
     /// This method follows an "all-or-nothing" strategy: it short-circuits and returns
-    /// an error if any string is not a valid `Pubkey` or if a program is missing from the registry.
+    /// an error if any string is not a valid `Pubkey` or if a program is missing from the registry
+    /// or disabled for this run.
     pub fn get_all_from_strings(
         &self,
         program_ids: &[String],
@@ -166,9 +203,65 @@ impl ProtocolRegistry {
 
                 (!entries.is_empty())
                     .then_some(entries)
-                    .ok_or_else(|| anyhow!("Program ID not found in registry: {pk}"))
+                    .ok_or_else(|| anyhow!("Program ID not found in registry or disabled: {pk}"))
             })
             .collect::<anyhow::Result<Vec<_>>>()
             .map(|vecs| vecs.into_iter().flatten().collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::libs::solana_client::{
+        models::PoolState,
+        protocols::{
+            orca::ORCA_ID,
+            raydium_cpmm::{self, RAYDIUM_CPMM_ID},
+        },
+    };
+
+    fn registry() -> ProtocolRegistry {
+        let mut reg = ProtocolRegistry::new();
+        definitions::fill_registry(&mut reg);
+        reg
+    }
+
+    #[test]
+    fn test_disabled_program_is_not_decoded() {
+        let reg = registry();
+        let mut data = raydium_cpmm::PoolState::DISCRIMINATOR.to_vec();
+        data.resize(raydium_cpmm::PoolState::DATA_SIZE, 0);
+        let decode = |reg: &ProtocolRegistry| {
+            reg.get_account_item(&RAYDIUM_CPMM_ID, data.len(), &data)
+                .and_then(|item| match &item.parser {
+                    ProtocolParser::Program(parse) => parse(&data),
+                    ProtocolParser::Tx(_) => None,
+                })
+        };
+
+        assert!(matches!(
+            decode(&reg),
+            Some(PoolState::PoolStateRaydiumCpmm(_))
+        ));
+
+        reg.enable_only([ORCA_ID]).unwrap();
+
+        assert!(decode(&reg).is_none());
+        assert!(reg.enable_only([RAYDIUM_CPMM_ID]).is_err());
+    }
+
+    #[test]
+    fn test_disabled_program_is_not_subscribed() {
+        let reg = registry();
+        reg.enable_only([ORCA_ID]).unwrap();
+
+        assert!(!reg.get_all_by_program_id(&ORCA_ID).is_empty());
+        assert!(reg.get_all_by_program_id(&RAYDIUM_CPMM_ID).is_empty());
+        assert!(reg.get_all_from_strings(&[ORCA_ID.to_string()]).is_ok());
+        assert!(
+            reg.get_all_from_strings(&[RAYDIUM_CPMM_ID.to_string()])
+                .is_err()
+        );
+    }
+}
@@ -2,7 +2,7 @@ use bytemuck::Pod;
 use solana_sdk::pubkey::Pubkey;
 use tracing::error;
 
-use crate::libs::solana_client::models::*;
+use crate::libs::solana_client::{metrics::*, models::*};
 
 /// Defines the criteria used to locate a specific parser in the registry.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -86,20 +86,28 @@ pub trait ProtocolEntity: Sized {
 
         // Ensure buffer contains at least the required amount of bytes
         if data.len() != expected_size {
-            error!(
-                "[{type_name}] Size mismatch: expected exactly {expected_size}, got {}",
-                data.len()
-            );
+            if let Some(total) = DECODE_ERRORS.record(type_name, DecodeErrorKind::SizeMismatch) {
+                error!(
+                    total,
+                    "[{type_name}] Size mismatch: expected exactly {expected_size}, got {}",
+                    data.len()
+                );
+            }
             return None;
         }
 
         // Validate the type discriminator prefix
         if disc_size > 0 && !data.starts_with(Self::DISCRIMINATOR) {
-            error!(
-                "[{type_name}] Discriminator mismatch. Expected prefix {:?}, got {:?}",
-                Self::DISCRIMINATOR,
-                &data[..disc_size.min(data.len())]
-            );
+            if let Some(total) =
+                DECODE_ERRORS.record(type_name, DecodeErrorKind::DiscriminatorMismatch)
+            {
+                error!(
+                    total,
+                    "[{type_name}] Discriminator mismatch. Expected prefix {:?}, got {:?}",
+                    Self::DISCRIMINATOR,
+                    &data[..disc_size.min(data.len())]
+                );
+            }
             return None;
         }
 
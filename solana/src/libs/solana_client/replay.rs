@@ -0,0 +1,358 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, bail};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_with::{DisplayFromStr, serde_as};
+use solana_sdk::pubkey::Pubkey;
+use tokio::{
+    sync::{Mutex, mpsc},
+    task::JoinHandle,
+};
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+use yellowstone_grpc_proto::prelude::{SubscribeUpdateAccount, SubscribeUpdateAccountInfo};
+
+use crate::libs::solana_client::{GrpcStream, SolanaStream, callback::*, models::*};
+
+/// A raw account update as received from the gRPC stream, before decoding.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedAccount {
+    pub slot: u64,
+    pub is_startup: bool,
+    #[serde_as(as = "DisplayFromStr")]
+    pub pubkey: Pubkey,
+    #[serde_as(as = "DisplayFromStr")]
+    pub owner: Pubkey,
+    pub lamports: u64,
+    pub executable: bool,
+    pub rent_epoch: u64,
+    pub write_version: u64,
+    #[serde(with = "base64_data")]
+    pub data: Vec<u8>,
+}
+
+impl RecordedAccount {
+    /// Captures a raw gRPC account update. Returns `None` if it has no account info.
+    #[must_use]
+    pub fn from_update(update: &SubscribeUpdateAccount) -> Option<Self> {
+        let info = update.account.as_ref()?;
+
+        Some(Self {
+            slot: update.slot,
+            is_startup: update.is_startup,
+            pubkey: Pubkey::try_from(info.pubkey.as_slice()).ok()?,
+            owner: Pubkey::try_from(info.owner.as_slice()).ok()?,
+            lamports: info.lamports,
+            executable: info.executable,
+            rent_epoch: info.rent_epoch,
+            write_version: info.write_version,
+            data: info.data.clone(),
+        })
+    }
+
+    /// Rebuilds the gRPC account update so it can be decoded like a live one.
+    #[must_use]
+    pub fn to_update(&self) -> SubscribeUpdateAccount {
+        SubscribeUpdateAccount {
+            account: Some(SubscribeUpdateAccountInfo {
+                pubkey: self.pubkey.to_bytes().to_vec(),
+                lamports: self.lamports,
+                owner: self.owner.to_bytes().to_vec(),
+                executable: self.executable,
+                rent_epoch: self.rent_epoch,
+                data: self.data.clone(),
+                write_version: self.write_version,
+                ..Default::default()
+            }),
+            slot: self.slot,
+            is_startup: self.is_startup,
+        }
+    }
+}
+
+/// Appends raw account updates to a file, one JSON object per line.
+///
+/// Batches are serialized and written on a blocking task, so the stream never waits on disk
+/// I/O unless the writer falls [`Self::CHANNEL_CAPACITY`] batches behind.
+pub struct AccountRecorder {
+    tx: mpsc::Sender<Vec<RecordedAccount>>,
+    /// Taken by whoever first reports how the writer ended.
+    writer: Mutex<Option<JoinHandle<anyhow::Result<()>>>>,
+}
+
+impl AccountRecorder {
+    /// Batches queued for the writer before recording waits for it.
+    const CHANNEL_CAPACITY: usize = 1024;
+
+    /// Creates (or truncates) the recording file and starts its writer.
+    pub fn create(path: &Path) -> anyhow::Result<Self> {
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create recording {}", path.display()))?;
+        let (tx, mut rx) = mpsc::channel::<Vec<RecordedAccount>>(Self::CHANNEL_CAPACITY);
+
+        let writer = tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
+            let mut writer = BufWriter::new(file);
+            while let Some(accounts) = rx.blocking_recv() {
+                for account in &accounts {
+                    serde_json::to_writer(&mut writer, account)?;
+                    writer.write_all(b"\n")?;
+                }
+                // Flushed once the queue drains rather than per batch.
+                if rx.is_empty() {
+                    writer.flush()?;
+                }
+            }
+            writer.flush()?;
+            Ok(())
+        });
+
+        Ok(Self {
+            tx,
+            writer: Mutex::new(Some(writer)),
+        })
+    }
+
+    /// Queues a batch of account updates for writing. Once the writer has stopped, returns
+    /// the error it stopped on.
+    pub async fn record(&self, accounts: Vec<RecordedAccount>) -> anyhow::Result<()> {
+        if self.tx.send(accounts).await.is_ok() {
+            return Ok(());
+        }
+
+        // The writer only closes the channel by returning.
+        if let Some(writer) = self.writer.lock().await.take() {
+            Self::join(writer).await?;
+        }
+        bail!("Recording writer stopped")
+    }
+
+    /// Writes out the queued updates and closes the recording.
+    pub async fn finish(self) -> anyhow::Result<()> {
+        drop(self.tx);
+        match self.writer.into_inner() {
+            Some(writer) => Self::join(writer).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn join(writer: JoinHandle<anyhow::Result<()>>) -> anyhow::Result<()> {
+        writer
+            .await
+            .context("Recording writer panicked")?
+            .context("Recording writer failed")
+    }
+}
+
+/// Reads every account update from a recording made by [`AccountRecorder`].
+pub fn read_recording(path: &Path) -> anyhow::Result<Vec<RecordedAccount>> {
+    let file =
+        File::open(path).with_context(|| format!("Failed to open recording {}", path.display()))?;
+
+    BufReader::new(file)
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, Ok(l) if l.trim().is_empty()))
+        .map(|(idx, line)| {
+            let line = line?;
+            serde_json::from_str(&line)
+                .with_context(|| format!("Invalid record at line {}", idx + 1))
+        })
+        .collect()
+}
+
+/// Configuration for replaying a recording.
+#[derive(Clone, Default)]
+pub struct ReplayStreamConfig {
+    /// Recording made by [`AccountRecorder`].
+    pub path: PathBuf,
+    /// Number of updates delivered to the callback per batch.
+    pub batch_size: usize,
+}
+
+/// Offline stream that feeds a recording through the same decoding path as the
+/// live gRPC stream, so detected opportunities can be reproduced.
+///
+/// Only streamed accounts are replayed; RPC-backed caches (mints, vaults,
+/// AMM configs) are still fetched from the configured endpoint.
+pub struct ReplayStream {
+    config: ReplayStreamConfig,
+    callback: Option<BatchEventCallbackWrapper>,
+}
+
+#[async_trait]
+impl SolanaStream for ReplayStream {
+    fn set_callback(&mut self, callback: BatchEventCallbackWrapper) {
+        self.callback = Some(callback);
+    }
+
+    async fn subscribe(&mut self, token: CancellationToken) -> anyhow::Result<()> {
+        let replayed = self.replay(&token).await?;
+        info!(
+            replayed,
+            path = %self.config.path.display(),
+            "⏪ [Replay] Recording replayed, waiting for shutdown"
+        );
+
+        // Keep running so downstream services can finish processing.
+        token.cancelled().await;
+        Ok(())
+    }
+}
+
+impl ReplayStream {
+    #[must_use]
+    pub fn from_config(config: ReplayStreamConfig) -> Self {
+        Self {
+            config,
+            callback: None,
+        }
+    }
+
+    /// Decodes and delivers every recorded update in order.
+    /// Returns the number of updates that decoded into events.
+    pub async fn replay(&mut self, token: &CancellationToken) -> anyhow::Result<usize> {
+        let records = read_recording(&self.config.path)?;
+        let mut replayed = 0;
+
+        for chunk in records.chunks(self.config.batch_size.max(1)) {
+            if token.is_cancelled() {
+                break;
+            }
+
+            let events: Vec<Event> = chunk
+                .iter()
+                .filter_map(|record| GrpcStream::parse_account(&record.to_update()))
+                .collect();
+            replayed += events.len();
+
+            if let Some(ref mut cb) = self.callback {
+                cb.call(events).await.context("callback failed")?;
+            }
+        }
+
+        Ok(replayed)
+    }
+}
+
+mod base64_data {
+    use base64::{Engine, engine::general_purpose};
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    pub fn serialize<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&general_purpose::STANDARD.encode(data))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use parking_lot::Mutex;
+
+    use super::*;
+    use crate::libs::solana_client::{
+        protocols::raydium_cpmm::{self, RAYDIUM_CPMM_ID},
+        registry::ProtocolEntity,
+    };
+
+    fn cpmm_record(slot: u64) -> RecordedAccount {
+        let mut data = raydium_cpmm::PoolState::DISCRIMINATOR.to_vec();
+        data.resize(raydium_cpmm::PoolState::DATA_SIZE, 0);
+
+        RecordedAccount {
+            slot,
+            is_startup: false,
+            pubkey: Pubkey::new_unique(),
+            owner: RAYDIUM_CPMM_ID,
+            lamports: 1_000_000,
+            executable: false,
+            rent_epoch: u64::MAX,
+            write_version: slot,
+            data,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recording_round_trip_replays_events() -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join(format!("replay-{}.jsonl", Pubkey::new_unique()));
+        let records = vec![cpmm_record(10), cpmm_record(11), cpmm_record(12)];
+
+        let recorder = AccountRecorder::create(&path)?;
+        for record in &records {
+            let update = record.to_update();
+            recorder
+                .record(vec![RecordedAccount::from_update(&update).unwrap()])
+                .await?;
+        }
+        recorder.finish().await?;
+
+        assert_eq!(read_recording(&path)?, records);
+
+        let slots = Arc::new(Mutex::new(vec![]));
+        let mut stream = ReplayStream::from_config(ReplayStreamConfig {
+            path: path.clone(),
+            batch_size: 2,
+        });
+        stream.set_callback(BatchEventCallbackWrapper::new({
+            let slots = Arc::clone(&slots);
+            move |events: Vec<Event>| {
+                let slots = Arc::clone(&slots);
+                async move {
+                    for event in events {
+                        if let Event::Program(acc) = event {
+                            assert!(matches!(acc.pool_state, PoolState::PoolStateRaydiumCpmm(_)));
+                            slots.lock().push(acc.slot);
+                        }
+                    }
+                    Ok(())
+                }
+            }
+        }));
+
+        let replayed = stream.replay(&CancellationToken::new()).await?;
+        std::fs::remove_file(&path)?;
+
+        assert_eq!(replayed, 3);
+        assert_eq!(*slots.lock(), vec![10, 11, 12]);
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_record_returns_writer_error() -> anyhow::Result<()> {
+        // Every write to /dev/full fails with ENOSPC, stopping the writer on its first flush.
+        let recorder = AccountRecorder::create(Path::new("/dev/full"))?;
+
+        let error = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            loop {
+                if let Err(e) = recorder.record(vec![cpmm_record(10)]).await {
+                    break e;
+                }
+                tokio::task::yield_now().await;
+            }
+        })
+        .await?;
+        assert!(
+            format!("{error:#}").starts_with("Recording writer failed: No space left on device"),
+            "{error:#}"
+        );
+
+        // Reported once, the writer is gone afterwards.
+        let error = recorder.record(vec![cpmm_record(11)]).await.unwrap_err();
+        assert_eq!(error.to_string(), "Recording writer stopped");
+        recorder.finish().await
+    }
+}
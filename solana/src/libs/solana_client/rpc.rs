@@ -1,10 +1,18 @@
 use anyhow::Context;
 use solana_client::{
     nonblocking::rpc_client::RpcClient as SolanaRpcClient,
-    rpc_config::{CommitmentConfig, RpcProgramAccountsConfig},
-    rpc_response::{Response, UiAccount},
+    rpc_config::{
+        CommitmentConfig, RpcProgramAccountsConfig, RpcSendTransactionConfig,
+        RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig, UiAccountEncoding,
+    },
+    rpc_request::TokenAccountsFilter,
+    rpc_response::{
+        Response, RpcKeyedAccount, RpcSimulateTransactionResult, UiAccount, transaction::Signature,
+    },
+};
+use solana_sdk::{
+    account::Account, clock::Slot, hash::Hash, pubkey::Pubkey, transaction::Transaction,
 };
-use solana_sdk::{account::Account, clock::Slot, pubkey::Pubkey};
 
 use crate::libs::solana_client::metrics::MeterSender;
 
@@ -52,4 +60,94 @@ impl RpcClient {
             .await
             .context("Failed to get program ui accounts")
     }
+
+    /// Returns the lamports held by the account.
+    pub async fn get_balance(&self, pubkey: &Pubkey) -> anyhow::Result<u64> {
+        self.inner
+            .get_balance_with_commitment(pubkey, CommitmentConfig::confirmed())
+            .await
+            .map(|response| response.value)
+            .context("Failed to get balance")
+    }
+
+    /// Returns the token accounts of `owner` under the token program, in `jsonParsed` encoding.
+    pub async fn get_token_accounts_by_owner(
+        &self,
+        owner: &Pubkey,
+        token_program: &Pubkey,
+    ) -> anyhow::Result<Vec<RpcKeyedAccount>> {
+        self.inner
+            .get_token_accounts_by_owner_with_commitment(
+                owner,
+                TokenAccountsFilter::ProgramId(*token_program),
+                CommitmentConfig::confirmed(),
+            )
+            .await
+            .map(|response| response.value)
+            .context("Failed to get token accounts by owner")
+    }
+
+    pub async fn get_latest_blockhash(&self, commitment: CommitmentConfig) -> anyhow::Result<Hash> {
+        self.inner
+            .get_latest_blockhash_with_commitment(commitment)
+            .await
+            .map(|(blockhash, _)| blockhash)
+            .context("Failed to get latest blockhash")
+    }
+
+    /// Sends a signed transaction without preflight simulation, relying on the on-chain
+    /// checks of its instructions instead.
+    pub async fn send_transaction(&self, transaction: &Transaction) -> anyhow::Result<Signature> {
+        self.inner
+            .send_transaction_with_config(
+                transaction,
+                RpcSendTransactionConfig {
+                    skip_preflight: true,
+                    max_retries: Some(0),
+                    ..RpcSendTransactionConfig::default()
+                },
+            )
+            .await
+            .context("Failed to send transaction")
+    }
+
+    /// Simulates the transaction against the latest blockhash without verifying its
+    /// signatures, returning the `addresses` accounts as they would be after it executed.
+    pub async fn simulate_transaction(
+        &self,
+        transaction: &Transaction,
+        addresses: &[Pubkey],
+    ) -> anyhow::Result<RpcSimulateTransactionResult> {
+        self.inner
+            .simulate_transaction_with_config(
+                transaction,
+                RpcSimulateTransactionConfig {
+                    sig_verify: false,
+                    replace_recent_blockhash: true,
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    accounts: Some(RpcSimulateTransactionAccountsConfig {
+                        encoding: Some(UiAccountEncoding::Base64),
+                        addresses: addresses.iter().map(ToString::to_string).collect(),
+                    }),
+                    ..RpcSimulateTransactionConfig::default()
+                },
+            )
+            .await
+            .map(|response| response.value)
+            .context("Failed to simulate transaction")
+    }
+
+    /// Returns `None` until the transaction reaches the given commitment level,
+    /// then whether it executed successfully.
+    pub async fn get_signature_status(
+        &self,
+        signature: &Signature,
+        commitment: CommitmentConfig,
+    ) -> anyhow::Result<Option<bool>> {
+        self.inner
+            .get_signature_status_with_commitment(signature, commitment)
+            .await
+            .map(|status| status.map(|result| result.is_ok()))
+            .context("Failed to get signature status")
+    }
 }
@@ -481,11 +481,14 @@ impl WebsocketStream {
             .get_account_item(program_id, payload.len(), payload)
             .or_else(|| {
                 STREAM_METRICS.record_error(Transport::Ws, StreamErrorKind::Parse);
-                warn!(
-                    "No registered parser found for program {} with data size {}",
-                    program_id,
-                    payload.len()
-                );
+                if let Some(total) = DECODE_ERRORS.record("unknown", DecodeErrorKind::NoParser) {
+                    warn!(
+                        total,
+                        "No registered parser found for program {} with data size {}",
+                        program_id,
+                        payload.len()
+                    );
+                }
                 None
             })?;
 
@@ -499,12 +502,15 @@ impl WebsocketStream {
 
         let pool_state = parser_fn(payload).or_else(|| {
             STREAM_METRICS.record_error(Transport::Ws, StreamErrorKind::Parse);
-            error!(
-                "[{}] Failed to parse account: {}. Data size: {}",
-                item.name,
-                pubkey,
-                payload.len()
-            );
+            if let Some(total) = DECODE_ERRORS.record(item.name, DecodeErrorKind::ParseFailed) {
+                error!(
+                    total,
+                    "[{}] Failed to parse account: {}. Data size: {}",
+                    item.name,
+                    pubkey,
+                    payload.len()
+                );
+            }
             None
         })?;
 
@@ -525,7 +531,13 @@ impl WebsocketStream {
 
         let event = parser_fn(&payload).or_else(|| {
             STREAM_METRICS.record_error(Transport::Ws, StreamErrorKind::Parse);
-            error!("[{}] Failed to parse transaction event for program {program_id}. Payload: {payload:?}", item.name);
+            if let Some(total) = DECODE_ERRORS.record(item.name, DecodeErrorKind::ParseFailed) {
+                error!(
+                    total,
+                    "[{}] Failed to parse transaction event for program {program_id}. Payload: {payload:?}",
+                    item.name
+                );
+            }
             None
         })?;
 
@@ -0,0 +1,117 @@
+use anyhow::Context;
+use engine::Balances;
+use rust_decimal::Decimal;
+use serde_json::Value;
+use solana_client::rpc_response::RpcKeyedAccount;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::libs::solana_client::RpcClient;
+
+/// Key of the native SOL balance; token balances are keyed by mint address.
+pub const NATIVE_SOL: &str = "SOL";
+
+/// Lamports per SOL is 10^9.
+const SOL_DECIMALS: u32 = 9;
+
+/// Fetches the native SOL balance and every SPL / Token-2022 balance of the wallet.
+pub async fn fetch_balances(rpc: &RpcClient, wallet: &Pubkey) -> anyhow::Result<Balances> {
+    let lamports = rpc.get_balance(wallet).await?;
+
+    let mut balances = Balances::from([(
+        NATIVE_SOL.to_owned(),
+        Decimal::from_i128_with_scale(i128::from(lamports), SOL_DECIMALS),
+    )]);
+
+    for token_program in [spl_token::ID, spl_token_2022::ID] {
+        let accounts = rpc
+            .get_token_accounts_by_owner(wallet, &token_program)
+            .await?;
+        add_token_balances(&mut balances, &accounts)?;
+    }
+
+    Ok(balances)
+}
+
+/// Sums `jsonParsed` token account amounts per mint.
+fn add_token_balances(balances: &mut Balances, accounts: &[RpcKeyedAccount]) -> anyhow::Result<()> {
+    for keyed in accounts {
+        let data = serde_json::to_value(&keyed.account.data)?;
+        let info = &data["parsed"]["info"];
+
+        let mint = info["mint"]
+            .as_str()
+            .with_context(|| format!("Token account {} has no mint", keyed.pubkey))?;
+        let amount = parse_token_amount(&info["tokenAmount"])
+            .with_context(|| format!("Token account {} has an invalid amount", keyed.pubkey))?;
+
+        *balances.entry(mint.to_owned()).or_default() += amount;
+    }
+
+    Ok(())
+}
+
+fn parse_token_amount(token_amount: &Value) -> Option<Decimal> {
+    let amount: u64 = token_amount["amount"].as_str()?.parse().ok()?;
+    let decimals = u32::try_from(token_amount["decimals"].as_u64()?).ok()?;
+
+    Some(Decimal::from_i128_with_scale(i128::from(amount), decimals))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn token_account(mint: &str, amount: &str, decimals: u8) -> RpcKeyedAccount {
+        serde_json::from_value(json!({
+            "pubkey": Pubkey::new_unique().to_string(),
+            "account": {
+                "lamports": 2_039_280,
+                "owner": spl_token::ID.to_string(),
+                "executable": false,
+                "rentEpoch": 18_446_744_073_709_551_615_u64,
+                "space": 165,
+                "data": {
+                    "program": "spl-token",
+                    "space": 165,
+                    "parsed": {
+                        "type": "account",
+                        "info": {
+                            "isNative": false,
+                            "mint": mint,
+                            "owner": Pubkey::new_unique().to_string(),
+                            "state": "initialized",
+                            "tokenAmount": {
+                                "amount": amount,
+                                "decimals": decimals,
+                                "uiAmount": null,
+                                "uiAmountString": ""
+                            }
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parses_token_balances_per_mint() -> anyhow::Result<()> {
+        let usdc = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+        let bonk = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+        let accounts = [
+            token_account(usdc, "1500000", 6),
+            token_account(bonk, "12345", 5),
+            token_account(usdc, "250000", 6),
+        ];
+
+        let mut balances = Balances::new();
+        add_token_balances(&mut balances, &accounts)?;
+
+        assert_eq!(balances.len(), 2);
+        assert_eq!(balances[usdc], Decimal::new(175, 2));
+        assert_eq!(balances[bonk], Decimal::new(12_345, 5));
+        Ok(())
+    }
+}
@@ -140,9 +140,6 @@ impl LiquidityUpdate for orca::OrcaTickArray {
     type Key = i32;
 
     fn get_key(&self) -> Self::Key {
-        match self {
-            Self::Fixed(a) => a.start_tick_index,
-            Self::Dynamic(a) => a.start_tick_index,
-        }
+        self.start_tick_index()
     }
 }
@@ -39,6 +39,10 @@ pub struct PoolCache {
 
     /// Index mapping a TokenPair to all available pool addresses for that pair.
     pair_index: AHashMap<TokenPair, Vec<Pubkey>>,
+
+    /// Slot of the most recent state update seen for each pool
+    /// (pool account or its liquidity arrays).
+    slots: AHashMap<Pubkey, u64>,
 }
 
 impl Default for PoolCache {
@@ -53,6 +57,7 @@ impl PoolCache {
         Self {
             data: AHashMap::with_capacity(1024),
             pair_index: AHashMap::with_capacity(1024),
+            slots: AHashMap::with_capacity(1024),
         }
     }
 
@@ -91,6 +96,19 @@ impl PoolCache {
             .filter_map(|id| self.data.get(id).map(|p| p.as_ref()))
     }
 
+    /// Records the slot of a state update for the pool, keeping the most recent one.
+    pub fn record_slot(&mut self, pool_id: Pubkey, slot: u64) {
+        let entry = self.slots.entry(pool_id).or_default();
+        *entry = (*entry).max(slot);
+    }
+
+    /// Returns the slot of the most recent state update seen for the pool.
+    #[inline]
+    #[must_use]
+    pub fn get_slot(&self, pool_id: &Pubkey) -> Option<u64> {
+        self.slots.get(pool_id).copied()
+    }
+
     /// Returns all pool IDs for a given token pair.
     #[inline]
     #[must_use]
@@ -115,6 +115,8 @@ impl MarketState {
                         result.vaults.extend(vaults);
                     }
 
+                    self.pools.record_slot(updated.pool_id, slot);
+
                     result.record_pool_update(pubkey, slot, updated.is_new);
                 }
                 _ => {}
@@ -94,6 +94,12 @@ impl PoolSyncCache {
             .unwrap_or(false)
     }
 
+    /// Returns the number of tracked pools that are ready for quoting.
+    #[must_use]
+    pub fn ready_count(&self) -> usize {
+        self.statuses.values().filter(|s| s.is_ready()).count()
+    }
+
     #[must_use]
     pub fn get_pending_pools(&self, limit: usize) -> Vec<(Pubkey, PoolSyncStatus)> {
         self.statuses
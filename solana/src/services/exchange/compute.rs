@@ -6,9 +6,16 @@ use rayon::prelude::*;
 use solana_sdk::{account::Account, pubkey::Pubkey};
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
-use tracing::error;
+use tools::http::readiness::READINESS;
+use tracing::{debug, error};
 
-use crate::{libs::solana_client::pool::*, services::exchange::cache::*};
+use crate::{
+    libs::solana_client::{ProtocolKind, pool::*},
+    services::{
+        exchange::{cache::*, sizing::*, warmup::GraphWarmup},
+        sender::channel::OPPORTUNITY_CHANNEL,
+    },
+};
 
 /// Basis points denominator (10_000 bps = 100%).
 pub const BPS_DENOMINATOR: u64 = 10_000;
@@ -23,12 +30,29 @@ pub struct ComputeConfig {
     /// Base token mints to use as entry/exit points for arbitrage cycles.
     pub base_mints: AHashSet<Pubkey>,
     /// Min fraction of Kamino reserve to use as input in bps (e.g. 10 = 0.1%).
-    /// Also used as the ternary search precision threshold.
+    /// Also used as the size search precision threshold.
     pub min_liquidity_fraction_bps: u64,
     /// Max fraction of Kamino reserve to use as input in bps (e.g. 2000 = 20%).
     pub max_liquidity_fraction_bps: u64,
     /// Minimum profit as a fraction of amount_in in bps (e.g. 10 = 0.1%).
     pub min_profit_bps: u64,
+    /// Protocols whose pools may be used as swap steps in arbitrage paths.
+    pub route_protocols: AHashSet<ProtocolKind>,
+    /// Mints allowed as the intermediate token of a cycle (empty = any).
+    pub intermediate_mints: AHashSet<Pubkey>,
+    /// Method used to search for the profit-maximizing input amount.
+    pub size_search: SizeSearch,
+    /// Minimum number of synced pools before the graph is reported ready.
+    pub warmup_min_pools: usize,
+    /// Base mints that must have at least one cycle before the graph is reported ready.
+    pub warmup_mints: AHashSet<Pubkey>,
+}
+
+impl ComputeConfig {
+    /// Returns true if cycles may route through the given mint.
+    fn is_intermediate_allowed(&self, mint: &Pubkey) -> bool {
+        self.intermediate_mints.is_empty() || self.intermediate_mints.contains(mint)
+    }
 }
 
 /// A detected arbitrage opportunity ready for execution.
@@ -42,6 +66,10 @@ pub struct ArbOpportunity {
     pub amount_out: u64,
     /// Gross profit in base token native units.
     pub profit: u64,
+    /// Minimum acceptable output of the route (`amount_in` plus the minimum profit).
+    pub min_amount_out: u64,
+    /// Slot of the latest state of each step's pool the quotes were computed on.
+    pub quoted_slots: [u64; 2],
     /// Quote results for each step.
     pub step_quotes: [QuoteResult; 2],
 }
@@ -53,6 +81,8 @@ pub struct ComputeService {
     path_manager: PathManager,
     /// Runtime configuration.
     config: ComputeConfig,
+    /// Readiness of the path graph, reported via the health endpoints.
+    warmup: GraphWarmup,
     tx: mpsc::Sender<PoolUpdate>,
     rx: mpsc::Receiver<PoolUpdate>,
 }
@@ -61,9 +91,16 @@ impl ComputeService {
     #[must_use]
     pub fn new(config: ComputeConfig) -> Self {
         let (tx, rx) = mpsc::channel(1024);
+        let warmup = GraphWarmup::new(
+            config.warmup_min_pools,
+            config.warmup_mints.clone(),
+            READINESS.register("solana_graph_warmup"),
+        );
+
         Self {
             path_manager: PathManager::new(),
             config,
+            warmup,
             tx,
             rx,
         }
@@ -95,7 +132,12 @@ impl ComputeService {
 
         if !update.new_pools.is_empty() {
             self.path_manager
-                .add_pools(&update.new_pools, &self.config.base_mints, market.pools());
+                .add_pools(&update.new_pools, &self.config, market.pools());
+        }
+
+        if !self.warmup.is_ready() {
+            let ready_pools = get_pool_sync_cache().read().ready_count();
+            self.warmup.update(ready_pools, &self.path_manager);
         }
 
         let paths: Vec<&ComputePath> = {
@@ -119,8 +161,9 @@ impl ComputeService {
         paths.par_iter().for_each(|path| {
             match self.evaluate_path(path, &market, &mint_cache, &amm_config_cache) {
                 Ok(Some(opportunity)) => {
-                    // todo: send opportunity to executor
-                    tracing::debug!("profit: {:?}", opportunity)
+                    if let Err(e) = OPPORTUNITY_CHANNEL.tx.try_send(opportunity) {
+                        debug!("Opportunity dropped: {e}");
+                    }
                 }
                 Ok(None) => {}
                 Err(e) => {
@@ -200,17 +243,24 @@ impl ComputeService {
             return Ok(None);
         };
 
+        let quoted_slots = [
+            market.pools().get_slot(&step0.pool_id).unwrap_or_default(),
+            market.pools().get_slot(&step1.pool_id).unwrap_or_default(),
+        ];
+
         Ok(Some(ArbOpportunity {
             path: path.clone(),
             amount_in,
             amount_out: quote1.total_amount_out,
             profit,
+            min_amount_out: amount_in.saturating_add(self.min_profit(amount_in)),
+            quoted_slots,
             step_quotes: [quote0, quote1],
         }))
     }
 
-    /// Runs a ternary search over `[min_amount, max_amount]` to find the input amount
-    /// that maximises profit for the given path.
+    /// Searches `[min_amount, max_amount]` for the input amount that maximises
+    /// profit for the given path, using the configured [`SizeSearch`] method.
     ///
     /// The search terminates when the interval narrows below `min_amount` (precision threshold).
     /// Returns the best `(amount_in, profit, quote0, quote1)` found, or `None` if no
@@ -229,67 +279,38 @@ impl ComputeService {
         pool1: &dyn DexPool,
         amm_config_cache: &AmmConfigCache,
     ) -> Option<(u64, u64, QuoteResult, QuoteResult)> {
-        let precision = min_amount;
-
-        let mut lo = min_amount;
-        let mut hi = max_amount;
-        let mut best_result: Option<(u64, u64, QuoteResult, QuoteResult)> = None;
-        let mut max_profit = 0u64;
-
-        loop {
-            if hi.saturating_sub(lo) < precision {
-                break;
-            }
-
-            let m1 = lo + (hi - lo) / 3;
-            let m2 = hi - (hi - lo) / 3;
-
-            let res1 = Self::compute_profit(
-                path,
-                market,
-                m1,
-                mint_in0,
-                mint_out0,
-                mint_out1,
-                pool0,
-                pool1,
-                amm_config_cache,
-            );
-            let res2 = Self::compute_profit(
-                path,
-                market,
-                m2,
-                mint_in0,
-                mint_out0,
-                mint_out1,
-                pool0,
-                pool1,
-                amm_config_cache,
-            );
-
-            let p1 = res1.as_ref().map(|(p, _, _)| *p).unwrap_or(0);
-            let p2 = res2.as_ref().map(|(p, _, _)| *p).unwrap_or(0);
-
-            if p1 > max_profit {
-                max_profit = p1;
-                best_result = res1.map(|(p, q0, q1)| (m1, p, q0, q1));
-            }
-            if p2 > max_profit {
-                max_profit = p2;
-                best_result = res2.map(|(p, q0, q1)| (m2, p, q0, q1));
-            }
-
-            if p1 < p2 {
-                lo = m1;
-            } else {
-                hi = m2;
-            }
+        let best = find_optimal_amount(
+            self.config.size_search,
+            min_amount,
+            max_amount,
+            min_amount,
+            |amount_in| {
+                Self::compute_profit(
+                    path,
+                    market,
+                    amount_in,
+                    mint_in0,
+                    mint_out0,
+                    mint_out1,
+                    pool0,
+                    pool1,
+                    amm_config_cache,
+                )
+                .map(|(profit, quote0, quote1)| (profit, (quote0, quote1)))
+            },
+        )?;
+
+        if best.profit < self.min_profit(best.amount_in) {
+            return None;
         }
 
-        best_result.filter(|(amount_in, profit, _, _)| {
-            let min_profit = amount_in.saturating_mul(self.config.min_profit_bps) / BPS_DENOMINATOR;
-            *profit >= min_profit
-        })
+        let (quote0, quote1) = best.quote;
+        Some((best.amount_in, best.profit, quote0, quote1))
+    }
+
+    /// Minimum profit required for the given input amount.
+    fn min_profit(&self, amount_in: u64) -> u64 {
+        amount_in.saturating_mul(self.config.min_profit_bps) / BPS_DENOMINATOR
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -404,7 +425,7 @@ impl Hash for ComputePath {
 }
 
 /// Stores pre-computed arbitrage paths indexed by pool ID.
-struct PathManager {
+pub(crate) struct PathManager {
     /// Single source of truth: path_hash → ComputePath.
     paths: AHashMap<u64, ComputePath>,
     /// Index: pool_id → set of path hashes passing through it.
@@ -439,14 +460,18 @@ impl PathManager {
 
     /// Called when new pools appear in cache.
     /// Finds all 2-step arb paths through new pools that involve base assets.
+    /// Pools of protocols outside `route_protocols` are never used as a path step,
+    /// and cycles only route through allowed intermediate mints.
     pub fn add_pools(
         &mut self,
         pool_ids: &[Pubkey],
-        base_assets: &AHashSet<Pubkey>,
+        config: &ComputeConfig,
         pool_cache: &PoolCache,
     ) {
+        let is_routable = |pool: &dyn DexPool| config.route_protocols.contains(&pool.protocol());
+
         for &pool_id in pool_ids {
-            let Some(pool) = pool_cache.get_pool(&pool_id) else {
+            let Some(pool) = pool_cache.get_pool(&pool_id).filter(|p| is_routable(*p)) else {
                 continue;
             };
 
@@ -455,7 +480,7 @@ impl PathManager {
 
             let base_mints: Vec<Pubkey> = [mint_a, mint_b]
                 .into_iter()
-                .filter(|m| base_assets.contains(m))
+                .filter(|m| config.base_mints.contains(m))
                 .collect();
 
             if base_mints.is_empty() {
@@ -467,7 +492,8 @@ impl PathManager {
             };
 
             for &sibling_id in sibling_ids.iter().filter(|&&id| id != pool_id) {
-                let Some(sibling) = pool_cache.get_pool(&sibling_id) else {
+                let Some(sibling) = pool_cache.get_pool(&sibling_id).filter(|p| is_routable(*p))
+                else {
                     continue;
                 };
 
@@ -475,6 +501,9 @@ impl PathManager {
 
                 for &base_mint in &base_mints {
                     let quote_mint = if base_mint == mint_a { mint_b } else { mint_a };
+                    if !config.is_intermediate_allowed(&quote_mint) {
+                        continue;
+                    }
 
                     // base → quote via pool_id, quote → base via sibling
                     self.insert(
@@ -525,6 +554,20 @@ impl PathManager {
         self.record_metrics()
     }
 
+    /// Returns true if no arbitrage path has been discovered yet.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Returns true if at least one arbitrage cycle starts from the given mint.
+    #[must_use]
+    pub fn has_paths_for(&self, base_token: &Pubkey) -> bool {
+        self.paths
+            .values()
+            .any(|path| path.base_token == *base_token)
+    }
+
     /// Returns all arbitrage paths that pass through the given pools.
     pub fn get_paths_for_pools<'a>(
         &'a self,
@@ -565,3 +608,154 @@ impl Default for PathManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bytemuck::Zeroable;
+
+    use super::*;
+    use crate::{
+        libs::solana_client::protocols::{meteora_damm_v2, orca},
+        test_utils::{compute_config, cpmm_pool},
+    };
+
+    fn whirlpool(mint_a: Pubkey, mint_b: Pubkey) -> Box<orca::Whirlpool> {
+        let mut pool = orca::Whirlpool::zeroed();
+        pool.token_mint_a = mint_a.to_bytes();
+        pool.token_mint_b = mint_b.to_bytes();
+        Box::new(pool)
+    }
+
+    fn damm_v2_pool(mint_a: Pubkey, mint_b: Pubkey) -> Box<meteora_damm_v2::Pool> {
+        let mut pool = meteora_damm_v2::Pool::zeroed();
+        pool.token_a_mint = mint_a.to_bytes();
+        pool.token_b_mint = mint_b.to_bytes();
+        Box::new(pool)
+    }
+
+    #[test]
+    fn test_add_pools_includes_cpmm_edge() {
+        let base_mint = Pubkey::new_unique();
+        let quote_mint = Pubkey::new_unique();
+        let cpmm_id = Pubkey::new_unique();
+        let orca_id = Pubkey::new_unique();
+
+        let mut pool_cache = PoolCache::new();
+        pool_cache.update(cpmm_id, cpmm_pool(base_mint, quote_mint));
+        pool_cache.update(orca_id, whirlpool(quote_mint, base_mint));
+
+        let config = compute_config(
+            &[base_mint],
+            &[ProtocolKind::RaydiumCpmm, ProtocolKind::Orca],
+            &[],
+        );
+
+        let mut manager = PathManager::new();
+        manager.add_pools(&[cpmm_id], &config, &pool_cache);
+
+        assert_eq!(manager.paths.len(), 2);
+
+        let cycle = manager
+            .paths
+            .values()
+            .find(|p| p.steps[0].pool_id == cpmm_id)
+            .expect("cycle starting with the CPMM pool");
+
+        assert_eq!(cycle.base_token, base_mint);
+        assert!(cycle.steps[0].a_to_b);
+        assert_eq!(cycle.steps[0].mint_out, quote_mint);
+        assert_eq!(cycle.steps[1].pool_id, orca_id);
+        assert!(cycle.steps[1].a_to_b);
+        assert_eq!(cycle.steps[1].mint_out, base_mint);
+    }
+
+    #[test]
+    fn test_add_pools_skips_excluded_protocols() {
+        let base_mint = Pubkey::new_unique();
+        let quote_mint = Pubkey::new_unique();
+        let cpmm_id = Pubkey::new_unique();
+        let orca_id = Pubkey::new_unique();
+
+        let mut pool_cache = PoolCache::new();
+        pool_cache.update(cpmm_id, cpmm_pool(base_mint, quote_mint));
+        pool_cache.update(orca_id, whirlpool(quote_mint, base_mint));
+
+        let config = compute_config(&[base_mint], &[ProtocolKind::Orca], &[]);
+
+        let mut manager = PathManager::new();
+        manager.add_pools(&[cpmm_id, orca_id], &config, &pool_cache);
+
+        assert!(manager.paths.is_empty());
+        assert!(manager.index.is_empty());
+    }
+
+    #[test]
+    fn test_add_pools_includes_damm_v2_edge() {
+        let base_mint = Pubkey::new_unique();
+        let quote_mint = Pubkey::new_unique();
+        let damm_id = Pubkey::new_unique();
+        let cpmm_id = Pubkey::new_unique();
+
+        let mut pool_cache = PoolCache::new();
+        pool_cache.update(damm_id, damm_v2_pool(quote_mint, base_mint));
+        pool_cache.update(cpmm_id, cpmm_pool(base_mint, quote_mint));
+
+        let config = compute_config(
+            &[base_mint],
+            &[ProtocolKind::MeteoraDammV2, ProtocolKind::RaydiumCpmm],
+            &[],
+        );
+
+        let mut manager = PathManager::new();
+        manager.add_pools(&[damm_id], &config, &pool_cache);
+
+        assert_eq!(manager.paths.len(), 2);
+
+        let cycle = manager
+            .paths
+            .values()
+            .find(|p| p.steps[0].pool_id == damm_id)
+            .expect("cycle starting with the DAMM v2 pool");
+
+        assert_eq!(cycle.base_token, base_mint);
+        assert!(!cycle.steps[0].a_to_b);
+        assert_eq!(cycle.steps[0].mint_out, quote_mint);
+        assert_eq!(cycle.steps[1].pool_id, cpmm_id);
+        assert!(!cycle.steps[1].a_to_b);
+        assert_eq!(cycle.steps[1].mint_out, base_mint);
+    }
+
+    #[test]
+    fn test_add_pools_only_routes_through_allowed_intermediates() {
+        let base_mint = Pubkey::new_unique();
+        let allowed_mint = Pubkey::new_unique();
+        let other_mint = Pubkey::new_unique();
+
+        let allowed_ids = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let other_ids = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let mut pool_cache = PoolCache::new();
+        pool_cache.update(allowed_ids[0], cpmm_pool(base_mint, allowed_mint));
+        pool_cache.update(allowed_ids[1], whirlpool(allowed_mint, base_mint));
+        pool_cache.update(other_ids[0], cpmm_pool(base_mint, other_mint));
+        pool_cache.update(other_ids[1], whirlpool(other_mint, base_mint));
+
+        let config = compute_config(
+            &[base_mint],
+            &[ProtocolKind::RaydiumCpmm, ProtocolKind::Orca],
+            &[allowed_mint],
+        );
+
+        let mut manager = PathManager::new();
+        manager.add_pools(&[allowed_ids[0], other_ids[0]], &config, &pool_cache);
+
+        assert_eq!(manager.paths.len(), 2);
+        assert!(manager.paths.values().all(|p| {
+            p.base_token == base_mint
+                && p.steps[0].mint_out == allowed_mint
+                && p.steps[1].mint_in == allowed_mint
+        }));
+        assert!(!manager.index.contains_key(&other_ids[0]));
+        assert!(!manager.index.contains_key(&other_ids[1]));
+    }
+}
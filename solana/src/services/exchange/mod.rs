@@ -1,5 +1,8 @@
 pub mod background;
+pub mod balance;
 pub mod cache;
 pub mod compute;
 pub mod market;
 pub mod service;
+pub mod sizing;
+pub mod warmup;
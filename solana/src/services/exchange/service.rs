@@ -1,9 +1,10 @@
 use std::sync::Arc;
 
-use anyhow::anyhow;
+use anyhow::{anyhow, bail};
 use async_trait::async_trait;
-use engine::{Exchange, service::traits::ArbitrageService};
+use engine::{BalanceCache, Balances, Exchange, service::traits::ArbitrageService};
 use metrics_exporter_prometheus::Matcher;
+use solana_sdk::pubkey::Pubkey;
 use tokio::{sync::Mutex, task::JoinSet};
 use tokio_util::sync::CancellationToken;
 use tools::http::http_metrics::HttpMetrics;
@@ -12,16 +13,32 @@ use crate::{
     Config,
     config::TransportConfig,
     libs::solana_client::{protocols::kamino::KAMINO_ID, *},
-    services::exchange::{background::*, cache, compute::ComputeService, market::MarketService},
+    services::exchange::{
+        background::*, balance, cache, compute::ComputeService, market::MarketService,
+    },
 };
 
 pub struct ExchangeService {
     market_stream: Mutex<Option<Box<dyn SolanaStream>>>,
     compute_service: Mutex<Option<ComputeService>>,
     background_services: Vec<Arc<dyn BackgroundService + Send + Sync>>,
+    rpc: Arc<RpcClient>,
+    wallet: Option<Pubkey>,
+    balance_cache: BalanceCache,
 }
 
-impl Exchange for ExchangeService {}
+#[async_trait]
+impl Exchange for ExchangeService {
+    async fn balances(&self) -> anyhow::Result<Balances> {
+        let Some(wallet) = self.wallet else {
+            bail!("Cannot fetch balances: wallet is not configured");
+        };
+
+        self.balance_cache
+            .get_or_refresh(|| balance::fetch_balances(&self.rpc, &wallet))
+            .await
+    }
+}
 
 #[async_trait]
 impl ArbitrageService for ExchangeService {
@@ -82,6 +99,13 @@ impl ExchangeService {
         cache::init_local_cache()?;
         init_client_metrics();
 
+        metrics::DECODE_ERRORS.set_log_every(config.decode_error_log_every);
+
+        // Deactivate parsers of DEXes excluded from this run; Kamino reserves are always needed.
+        if let Some(programs) = config.get_enabled_programs() {
+            PROTOCOL_REGISTRY.enable_only(programs.into_iter().chain([KAMINO_ID]))?;
+        }
+
         let rpc = Arc::new(RpcClient::from_config(config.try_into()?));
         let compute_service = ComputeService::new(config.try_into()?);
 
@@ -92,7 +116,10 @@ impl ExchangeService {
         Ok(Self {
             market_stream: Mutex::new(Some(market_stream)),
             compute_service: Mutex::new(Some(compute_service)),
-            background_services: build_background_services(rpc),
+            background_services: build_background_services(rpc.clone()),
+            rpc,
+            wallet: config.wallet,
+            balance_cache: BalanceCache::default(),
         })
     }
 }
@@ -133,6 +160,9 @@ fn build_market_stream(config: &Config) -> anyhow::Result<Box<dyn SolanaStream>>
             };
             Ok(Box::new(GrpcStream::from_config(cfg)))
         }
+        TransportConfig::Replay { .. } => {
+            Ok(Box::new(ReplayStream::from_config(config.try_into()?)))
+        }
     }
 }
 
@@ -0,0 +1,175 @@
+use serde::Deserialize;
+
+/// Smallest interval the search keeps narrowing; below it the probe points collapse
+/// onto the bounds and the interval stops shrinking.
+const MIN_PRECISION: u64 = 3;
+
+/// Golden ratio conjugate (1/φ) scaled by [`GOLDEN_SCALE`].
+const GOLDEN_NUM: u128 = 618_034;
+const GOLDEN_SCALE: u128 = 1_000_000;
+
+/// Method used to search for the profit-maximizing input amount.
+///
+/// Profit of an AMM/CLMM cycle is concave in the input amount, so both methods
+/// converge to the optimum; golden-section reuses one probe per iteration and
+/// needs fewer quotes for the same precision.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SizeSearch {
+    #[default]
+    Ternary,
+    GoldenSection,
+}
+
+/// Best input amount found by [`find_optimal_amount`].
+#[derive(Debug)]
+pub struct SizedQuote<T> {
+    pub amount_in: u64,
+    pub profit: u64,
+    pub quote: T,
+}
+
+/// Searches `[lo, hi]` for the input amount that maximizes the profit returned by `eval`.
+///
+/// `eval` returns `None` for amounts that can't be quoted or aren't profitable.
+/// The search stops once the interval narrows below `precision`. Returns the most
+/// profitable probe seen, or `None` if no probe produced a positive profit.
+pub fn find_optimal_amount<T>(
+    method: SizeSearch,
+    lo: u64,
+    hi: u64,
+    precision: u64,
+    eval: impl Fn(u64) -> Option<(u64, T)>,
+) -> Option<SizedQuote<T>> {
+    let precision = precision.max(MIN_PRECISION);
+    let mut best: Option<SizedQuote<T>> = None;
+
+    // Probes the amount, keeps the best result and returns the profit (0 if unquotable).
+    let mut probe = |amount_in: u64| -> u64 {
+        let Some((profit, quote)) = eval(amount_in) else {
+            return 0;
+        };
+        if profit > 0 && best.as_ref().is_none_or(|b| profit > b.profit) {
+            best = Some(SizedQuote {
+                amount_in,
+                profit,
+                quote,
+            });
+        }
+        profit
+    };
+
+    match method {
+        SizeSearch::Ternary => ternary(lo, hi, precision, &mut probe),
+        SizeSearch::GoldenSection => golden_section(lo, hi, precision, &mut probe),
+    }
+
+    best
+}
+
+fn ternary(mut lo: u64, mut hi: u64, precision: u64, probe: &mut impl FnMut(u64) -> u64) {
+    while hi.saturating_sub(lo) >= precision {
+        let m1 = lo + (hi - lo) / 3;
+        let m2 = hi - (hi - lo) / 3;
+
+        if probe(m1) < probe(m2) {
+            lo = m1;
+        } else {
+            hi = m2;
+        }
+    }
+}
+
+fn golden_section(mut lo: u64, mut hi: u64, precision: u64, probe: &mut impl FnMut(u64) -> u64) {
+    let golden = |span: u64| (u128::from(span) * GOLDEN_NUM / GOLDEN_SCALE) as u64;
+
+    if hi.saturating_sub(lo) < precision {
+        return;
+    }
+
+    let mut x1 = hi - golden(hi - lo);
+    let mut x2 = lo + golden(hi - lo);
+    let mut p1 = probe(x1);
+    let mut p2 = probe(x2);
+
+    // Rounding can make the probes meet on narrow intervals; stop there.
+    while hi - lo >= precision && x1 < x2 {
+        if p1 < p2 {
+            lo = x1;
+            x1 = x2;
+            p1 = p2;
+            x2 = lo + golden(hi - lo);
+            p2 = probe(x2);
+        } else {
+            hi = x2;
+            x2 = x1;
+            p2 = p1;
+            x1 = hi - golden(hi - lo);
+            p1 = probe(x1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEE_BPS: u128 = 25;
+
+    /// Constant-product swap output with the fee taken from the input.
+    fn swap_out(amount_in: u64, reserve_in: u64, reserve_out: u64) -> u64 {
+        let amount_in = u128::from(amount_in) * (10_000 - FEE_BPS) / 10_000;
+        (amount_in * u128::from(reserve_out) / (u128::from(reserve_in) + amount_in)) as u64
+    }
+
+    /// Profit of a base -> quote -> base cycle over two mispriced pools.
+    fn cycle_profit(amount_in: u64) -> Option<(u64, u64)> {
+        let quote_out = swap_out(amount_in, 1_000_000_000, 2_000_000_000);
+        let base_out = swap_out(quote_out, 1_900_000_000, 1_000_000_000);
+        base_out
+            .checked_sub(amount_in)
+            .filter(|profit| *profit > 0)
+            .map(|profit| (profit, base_out))
+    }
+
+    fn brute_force_max(lo: u64, hi: u64, step: u64) -> u64 {
+        (lo..=hi)
+            .step_by(step as usize)
+            .filter_map(|amount| cycle_profit(amount).map(|(profit, _)| profit))
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn assert_maximizes_profit(method: SizeSearch) {
+        let (lo, hi) = (1_000_000, 200_000_000);
+
+        let found = find_optimal_amount(method, lo, hi, 1_000, cycle_profit).unwrap();
+        let best = brute_force_max(lo, hi, 1_000);
+
+        assert_eq!(cycle_profit(found.amount_in).unwrap().0, found.profit);
+        assert_eq!(found.quote, found.amount_in + found.profit);
+        // Within rounding of the best profit on a 1_000-unit grid.
+        assert!(found.profit + 1 >= best, "{} < {best}", found.profit);
+        // Strictly better than the interval bounds of a fixed size.
+        assert!(found.profit > cycle_profit(lo).unwrap().0);
+        assert!(found.profit > cycle_profit(hi).map_or(0, |(profit, _)| profit));
+    }
+
+    #[test]
+    fn test_ternary_finds_profit_maximizing_amount() {
+        assert_maximizes_profit(SizeSearch::Ternary);
+    }
+
+    #[test]
+    fn test_golden_section_finds_profit_maximizing_amount() {
+        assert_maximizes_profit(SizeSearch::GoldenSection);
+    }
+
+    #[test]
+    fn test_unprofitable_range_returns_none() {
+        for method in [SizeSearch::Ternary, SizeSearch::GoldenSection] {
+            let found = find_optimal_amount(method, 1, 1_000, 0, |_| None::<(u64, ())>);
+            assert!(found.is_none());
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use ahash::AHashSet;
+use solana_sdk::pubkey::Pubkey;
+use tools::http::readiness::ReadinessGate;
+use tracing::info;
+
+use crate::services::exchange::compute::PathManager;
+
+/// Tracks warmup of the arbitrage graph and opens its readiness gate once
+/// enough pools are decoded and synced and the target mints are connected.
+pub struct GraphWarmup {
+    /// Minimum number of decoded pools with their liquidity synced.
+    min_ready_pools: usize,
+    /// Mints that must start at least one cycle (empty = any base mint).
+    required_mints: AHashSet<Pubkey>,
+    gate: Arc<ReadinessGate>,
+}
+
+impl GraphWarmup {
+    #[must_use]
+    pub fn new(
+        min_ready_pools: usize,
+        required_mints: AHashSet<Pubkey>,
+        gate: Arc<ReadinessGate>,
+    ) -> Self {
+        Self {
+            min_ready_pools,
+            required_mints,
+            gate,
+        }
+    }
+
+    #[must_use]
+    pub fn is_ready(&self) -> bool {
+        self.gate.is_ready()
+    }
+
+    /// Re-evaluates warmup and opens the gate once it completes.
+    /// The gate is never closed again afterwards.
+    pub(crate) fn update(&self, ready_pools: usize, path_manager: &PathManager) -> bool {
+        if self.gate.is_ready() {
+            return true;
+        }
+
+        if ready_pools < self.min_ready_pools {
+            return false;
+        }
+
+        let connected = if self.required_mints.is_empty() {
+            !path_manager.is_empty()
+        } else {
+            self.required_mints
+                .iter()
+                .all(|mint| path_manager.has_paths_for(mint))
+        };
+
+        if connected {
+            info!(ready_pools, "✅ [Compute] Arbitrage graph warmed up");
+            self.gate.set_ready(true);
+        }
+        connected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        libs::solana_client::ProtocolKind,
+        services::exchange::cache::PoolCache,
+        test_utils::{compute_config, cpmm_pool},
+    };
+
+    #[test]
+    fn test_not_ready_until_required_pools_loaded() {
+        let (sol, usdc, bonk) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let config = compute_config(&[sol, usdc], &[ProtocolKind::RaydiumCpmm], &[]);
+        let gate = Arc::new(ReadinessGate::new("test"));
+        let warmup = GraphWarmup::new(2, [sol, usdc].into_iter().collect(), Arc::clone(&gate));
+
+        let mut pool_cache = PoolCache::new();
+        let mut path_manager = PathManager::new();
+        assert!(!warmup.update(0, &path_manager));
+
+        // A single pool neither meets the pool count nor forms a cycle.
+        let sol_bonk = Pubkey::new_unique();
+        pool_cache.update(sol_bonk, cpmm_pool(sol, bonk));
+        path_manager.add_pools(&[sol_bonk], &config, &pool_cache);
+        assert!(!warmup.update(1, &path_manager));
+
+        // SOL is connected, but USDC has no cycle yet.
+        let sol_bonk_2 = Pubkey::new_unique();
+        pool_cache.update(sol_bonk_2, cpmm_pool(bonk, sol));
+        path_manager.add_pools(&[sol_bonk_2], &config, &pool_cache);
+        assert!(!warmup.update(2, &path_manager));
+        assert!(!gate.is_ready());
+
+        let usdc_bonk = Pubkey::new_unique();
+        let usdc_bonk_2 = Pubkey::new_unique();
+        pool_cache.update(usdc_bonk, cpmm_pool(usdc, bonk));
+        pool_cache.update(usdc_bonk_2, cpmm_pool(usdc, bonk));
+        path_manager.add_pools(&[usdc_bonk, usdc_bonk_2], &config, &pool_cache);
+        assert!(warmup.update(4, &path_manager));
+        assert!(gate.is_ready());
+
+        // Once warmed up, the gate stays open.
+        assert!(warmup.update(0, &path_manager));
+    }
+}
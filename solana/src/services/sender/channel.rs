@@ -0,0 +1,23 @@
+use std::sync::LazyLock;
+
+use tokio::sync::{Mutex, mpsc};
+
+use crate::services::exchange::compute::ArbOpportunity;
+
+/// Maximum number of opportunities buffered between the compute and sender services.
+/// Opportunities beyond this limit are dropped, as they become stale quickly.
+const OPPORTUNITY_CHANNEL_CAPACITY: usize = 256;
+
+/// Global channel carrying detected opportunities from the compute service to the sender.
+pub static OPPORTUNITY_CHANNEL: LazyLock<OpportunityChannel> = LazyLock::new(|| {
+    let (tx, rx) = mpsc::channel(OPPORTUNITY_CHANNEL_CAPACITY);
+    OpportunityChannel {
+        tx,
+        rx: Mutex::new(rx),
+    }
+});
+
+pub struct OpportunityChannel {
+    pub tx: mpsc::Sender<ArbOpportunity>,
+    pub rx: Mutex<mpsc::Receiver<ArbOpportunity>>,
+}
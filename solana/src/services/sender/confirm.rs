@@ -0,0 +1,285 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use solana_client::{rpc_config::CommitmentConfig, rpc_response::transaction::Signature};
+use solana_sdk::hash::Hash;
+use tokio::time::{Instant, sleep};
+use tracing::warn;
+
+use crate::{
+    libs::solana_client::RpcClient,
+    services::sender::metrics::{SENDER_METRICS, SubmissionOutcome},
+};
+
+/// Confirmation and resend policy for submitted transactions.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Commitment level a transaction must reach to be considered landed.
+    pub commitment: CommitmentConfig,
+    /// How long to wait for confirmation before resending with a fresh blockhash.
+    pub confirm_timeout: Duration,
+    /// Interval between signature status polls.
+    pub poll_interval: Duration,
+    /// Maximum number of send attempts, including the first one.
+    pub max_attempts: u32,
+}
+
+/// A transaction that reached the requested commitment level.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Confirmation {
+    pub signature: Signature,
+    pub attempts: u32,
+}
+
+/// Status of a sent signature at the requested commitment level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureStatus {
+    /// Not observed at the commitment level yet.
+    Pending,
+    /// Landed and executed successfully.
+    Confirmed,
+    /// Landed but failed on-chain (e.g. the min-out check tripped).
+    Failed,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfirmError {
+    #[error("Transaction not confirmed after {attempts} attempts")]
+    NotConfirmed { attempts: u32 },
+
+    #[error("Transaction {signature} landed but failed")]
+    Reverted { signature: Signature },
+
+    #[error(transparent)]
+    Rpc(#[from] anyhow::Error),
+}
+
+/// Looks up the status of sent signatures.
+#[async_trait]
+pub trait SignatureStatusSource: Send + Sync {
+    /// Returns the status of the signature at the commitment level.
+    async fn signature_status(
+        &self,
+        signature: &Signature,
+        commitment: CommitmentConfig,
+    ) -> anyhow::Result<SignatureStatus>;
+}
+
+#[async_trait]
+impl SignatureStatusSource for RpcClient {
+    async fn signature_status(
+        &self,
+        signature: &Signature,
+        commitment: CommitmentConfig,
+    ) -> anyhow::Result<SignatureStatus> {
+        Ok(
+            match self.get_signature_status(signature, commitment).await? {
+                None => SignatureStatus::Pending,
+                Some(true) => SignatureStatus::Confirmed,
+                Some(false) => SignatureStatus::Failed,
+            },
+        )
+    }
+}
+
+/// Transport used by [`send_and_confirm`] to (re)submit and track a transaction.
+#[async_trait]
+pub trait TransactionSubmitter: SignatureStatusSource {
+    /// Fetches a fresh blockhash to build the transaction against.
+    async fn latest_blockhash(&self, commitment: CommitmentConfig) -> anyhow::Result<Hash>;
+
+    /// Signs the transaction with the given blockhash and sends it.
+    async fn send(&self, blockhash: Hash) -> anyhow::Result<Signature>;
+}
+
+/// Sends a transaction and polls for its confirmation, resending it with a fresh
+/// blockhash whenever the deadline passes, up to `max_attempts` times.
+///
+/// Every previously sent signature keeps being polled, so a late landing of an
+/// earlier attempt is reported instead of executing the route twice. Resent
+/// copies can still land concurrently; the on-chain min-out check guards that case.
+///
+/// Sends and final outcomes are recorded in [`SENDER_METRICS`].
+pub async fn send_and_confirm(
+    submitter: &dyn TransactionSubmitter,
+    policy: &RetryPolicy,
+) -> Result<Confirmation, ConfirmError> {
+    let started = Instant::now();
+    let mut signatures = Vec::with_capacity(policy.max_attempts as usize);
+
+    for attempt in 1..=policy.max_attempts {
+        let blockhash = submitter.latest_blockhash(policy.commitment).await?;
+        signatures.push(submitter.send(blockhash).await?);
+        SENDER_METRICS.record_send();
+
+        let deadline = Instant::now() + policy.confirm_timeout;
+        loop {
+            for signature in &signatures {
+                match submitter
+                    .signature_status(signature, policy.commitment)
+                    .await?
+                {
+                    SignatureStatus::Pending => {}
+                    SignatureStatus::Confirmed => {
+                        SENDER_METRICS.record_outcome(SubmissionOutcome::Confirmed {
+                            latency: started.elapsed(),
+                        });
+                        return Ok(Confirmation {
+                            signature: *signature,
+                            attempts: attempt,
+                        });
+                    }
+                    SignatureStatus::Failed => {
+                        SENDER_METRICS.record_outcome(SubmissionOutcome::Reverted);
+                        return Err(ConfirmError::Reverted {
+                            signature: *signature,
+                        });
+                    }
+                }
+            }
+
+            if Instant::now() >= deadline {
+                break;
+            }
+            sleep(policy.poll_interval).await;
+        }
+
+        warn!(
+            attempt,
+            max_attempts = policy.max_attempts,
+            "Transaction not confirmed within {:?}, resending",
+            policy.confirm_timeout
+        );
+    }
+
+    SENDER_METRICS.record_outcome(SubmissionOutcome::Dropped);
+    Err(ConfirmError::NotConfirmed {
+        attempts: policy.max_attempts,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    /// Submitter whose transactions land only from the given attempt on.
+    struct MockSubmitter {
+        landing_attempt: Option<u8>,
+        landed_status: SignatureStatus,
+        blockhashes: Mutex<Vec<Hash>>,
+    }
+
+    impl MockSubmitter {
+        fn new(landing_attempt: Option<u8>) -> Self {
+            Self {
+                landing_attempt,
+                landed_status: SignatureStatus::Confirmed,
+                blockhashes: Mutex::new(vec![]),
+            }
+        }
+
+        fn reverting(landing_attempt: u8) -> Self {
+            Self {
+                landed_status: SignatureStatus::Failed,
+                ..Self::new(Some(landing_attempt))
+            }
+        }
+
+        fn sent(&self) -> Vec<Hash> {
+            self.blockhashes.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl TransactionSubmitter for MockSubmitter {
+        async fn latest_blockhash(&self, _commitment: CommitmentConfig) -> anyhow::Result<Hash> {
+            let next = self.blockhashes.lock().unwrap().len() as u8 + 1;
+            Ok(Hash::new_from_array([next; 32]))
+        }
+
+        async fn send(&self, blockhash: Hash) -> anyhow::Result<Signature> {
+            let mut blockhashes = self.blockhashes.lock().unwrap();
+            blockhashes.push(blockhash);
+            Ok(Signature::from([blockhashes.len() as u8; 64]))
+        }
+    }
+
+    #[async_trait]
+    impl SignatureStatusSource for MockSubmitter {
+        async fn signature_status(
+            &self,
+            signature: &Signature,
+            _commitment: CommitmentConfig,
+        ) -> anyhow::Result<SignatureStatus> {
+            let attempt = signature.as_ref()[0];
+            let landed = self
+                .landing_attempt
+                .is_some_and(|landing| attempt >= landing);
+
+            Ok(if landed {
+                self.landed_status
+            } else {
+                SignatureStatus::Pending
+            })
+        }
+    }
+
+    fn policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            commitment: CommitmentConfig::confirmed(),
+            confirm_timeout: Duration::from_millis(5),
+            poll_interval: Duration::from_millis(1),
+            max_attempts,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resends_dropped_transaction() -> anyhow::Result<()> {
+        let submitter = MockSubmitter::new(Some(2));
+
+        let confirmation = send_and_confirm(&submitter, &policy(3)).await?;
+
+        assert_eq!(
+            confirmation,
+            Confirmation {
+                signature: Signature::from([2; 64]),
+                attempts: 2,
+            }
+        );
+
+        // The resend was built against a fresh blockhash.
+        let sent = submitter.sent();
+        assert_eq!(sent.len(), 2);
+        assert_ne!(sent[0], sent[1]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let submitter = MockSubmitter::new(None);
+
+        let result = send_and_confirm(&submitter, &policy(3)).await;
+
+        assert!(matches!(
+            result,
+            Err(ConfirmError::NotConfirmed { attempts: 3 })
+        ));
+        assert_eq!(submitter.sent().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_reports_reverted_transaction() {
+        let submitter = MockSubmitter::reverting(1);
+
+        let result = send_and_confirm(&submitter, &policy(3)).await;
+
+        assert!(matches!(
+            result,
+            Err(ConfirmError::Reverted { signature }) if signature == Signature::from([1; 64])
+        ));
+        assert_eq!(submitter.sent().len(), 1);
+    }
+}
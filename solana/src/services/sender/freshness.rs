@@ -0,0 +1,56 @@
+use solana_sdk::pubkey::Pubkey;
+
+use crate::services::exchange::compute::ArbOpportunity;
+
+/// Guards against submitting routes quoted on pool state that has since moved,
+/// which is typical of a front-run or sandwich in progress.
+#[derive(Debug, Clone, Copy)]
+pub struct FreshnessGuard {
+    /// Disabled guards accept every opportunity.
+    pub enabled: bool,
+    /// Maximum number of slots a pool may have advanced since the quote.
+    pub max_slot_lag: u64,
+}
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum StaleQuote {
+    #[error("Pool {0} is no longer tracked")]
+    PoolMissing(Pubkey),
+
+    #[error("Pool {pool_id} advanced from slot {quoted_slot} to {current_slot}")]
+    PoolAdvanced {
+        pool_id: Pubkey,
+        quoted_slot: u64,
+        current_slot: u64,
+    },
+}
+
+impl FreshnessGuard {
+    /// Checks every pool of the route against its latest observed state slot.
+    ///
+    /// `current_slot` returns the slot of the most recent state seen for a pool.
+    pub fn check(
+        &self,
+        opportunity: &ArbOpportunity,
+        current_slot: impl Fn(&Pubkey) -> Option<u64>,
+    ) -> Result<(), StaleQuote> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        for (step, &quoted_slot) in opportunity.path.steps.iter().zip(&opportunity.quoted_slots) {
+            let current_slot =
+                current_slot(&step.pool_id).ok_or(StaleQuote::PoolMissing(step.pool_id))?;
+
+            if current_slot.saturating_sub(quoted_slot) > self.max_slot_lag {
+                return Err(StaleQuote::PoolAdvanced {
+                    pool_id: step.pool_id,
+                    quoted_slot,
+                    current_slot,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use ahash::AHashSet;
+use parking_lot::Mutex;
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::services::exchange::compute::ComputePath;
+
+/// Pools and swap directions of a route's legs.
+type RouteKey = [(Pubkey, bool); 2];
+
+/// Admission reasons of a route refused by [`InFlight::try_admit`].
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Busy {
+    #[error("{0} transactions already awaiting confirmation")]
+    Saturated(usize),
+
+    #[error("A transaction of the route is already awaiting confirmation")]
+    RouteInFlight,
+}
+
+/// Bounds the transactions awaiting confirmation, with at most one per route so a route
+/// is not resubmitted while its previous transaction may still land.
+#[derive(Debug, Clone)]
+pub struct InFlight {
+    max_in_flight: usize,
+    permits: Arc<Semaphore>,
+    routes: Arc<Mutex<AHashSet<RouteKey>>>,
+}
+
+/// Slot of an admitted route, released on drop.
+#[derive(Debug)]
+pub struct InFlightGuard {
+    route: RouteKey,
+    routes: Arc<Mutex<AHashSet<RouteKey>>>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl InFlight {
+    #[must_use]
+    pub fn new(max_in_flight: usize) -> Self {
+        Self {
+            max_in_flight,
+            permits: Arc::new(Semaphore::new(max_in_flight)),
+            routes: Arc::default(),
+        }
+    }
+
+    /// Admits the route unless it is already in flight or no slot is free.
+    ///
+    /// # Errors
+    /// Returns why the route was refused.
+    pub fn try_admit(&self, path: &ComputePath) -> Result<InFlightGuard, Busy> {
+        let route = path.steps.each_ref().map(|step| (step.pool_id, step.a_to_b));
+
+        let mut routes = self.routes.lock();
+        if routes.contains(&route) {
+            return Err(Busy::RouteInFlight);
+        }
+        let permit = self
+            .permits
+            .clone()
+            .try_acquire_owned()
+            .map_err(|_| Busy::Saturated(self.max_in_flight))?;
+        routes.insert(route);
+
+        Ok(InFlightGuard {
+            route,
+            routes: self.routes.clone(),
+            _permit: permit,
+        })
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.routes.lock().remove(&self.route);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::exchange::compute::ComputeStep;
+
+    fn path() -> ComputePath {
+        let step = || ComputeStep {
+            pool_id: Pubkey::new_unique(),
+            mint_in: Pubkey::new_unique(),
+            mint_out: Pubkey::new_unique(),
+            a_to_b: true,
+        };
+        ComputePath {
+            base_token: Pubkey::new_unique(),
+            steps: [step(), step()],
+        }
+    }
+
+    #[test]
+    fn test_routes_are_admitted_once_and_within_the_bound() {
+        let in_flight = InFlight::new(2);
+        let (first, second, third) = (path(), path(), path());
+
+        let guard = in_flight.try_admit(&first).unwrap();
+        assert_eq!(
+            in_flight.try_admit(&first).unwrap_err(),
+            Busy::RouteInFlight
+        );
+
+        // The same pools swapped the other way are another route.
+        let mut reversed = first.clone();
+        reversed.steps[0].a_to_b = false;
+        let _reversed = in_flight.try_admit(&reversed).unwrap();
+        assert_eq!(
+            in_flight.try_admit(&second).unwrap_err(),
+            Busy::Saturated(2)
+        );
+
+        // Settling a transaction frees its route and slot.
+        drop(guard);
+        let _first = in_flight.try_admit(&first).unwrap();
+        assert_eq!(in_flight.try_admit(&third).unwrap_err(), Busy::Saturated(2));
+    }
+}
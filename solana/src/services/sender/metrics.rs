@@ -0,0 +1,184 @@
+use std::{
+    sync::{
+        LazyLock,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use metrics::{
+    Unit, counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram,
+};
+
+/// Global metrics provider for submitted Solana transactions.
+pub static SENDER_METRICS: LazyLock<SenderMetrics> = LazyLock::new(SenderMetrics::new);
+
+/// Final outcome of a submitted arbitrage transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubmissionOutcome {
+    /// Reached the requested commitment level after `latency` since the first send.
+    Confirmed { latency: Duration },
+    /// Never observed on-chain within the retry policy.
+    Dropped,
+    /// Landed on-chain but failed (e.g. the min-out check tripped).
+    Reverted,
+}
+
+impl SubmissionOutcome {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Confirmed { .. } => "confirmed",
+            Self::Dropped => "dropped",
+            Self::Reverted => "reverted",
+        }
+    }
+}
+
+/// Metrics manager for transaction landing rate and confirmation latency.
+///
+/// Outcome totals are also kept locally to publish the landing rate as a gauge.
+#[derive(Default)]
+pub struct SenderMetrics {
+    sends: AtomicU64,
+    confirmed: AtomicU64,
+    dropped: AtomicU64,
+    reverted: AtomicU64,
+}
+
+impl SenderMetrics {
+    const LBL_OUTCOME: &'static str = "outcome";
+
+    const METRIC_SENDS: &'static str = "solana_sender_sends_total";
+    const METRIC_OUTCOMES: &'static str = "solana_sender_transactions_total";
+    const METRIC_LANDING_RATE: &'static str = "solana_sender_landing_rate";
+    const METRIC_CONFIRM_LATENCY: &'static str = "solana_sender_confirmation_latency_seconds";
+
+    /// Buckets for confirmation latency, from a single slot up to blockhash expiry.
+    pub const CONFIRM_LATENCY_BUCKETS: &[f64] = &[
+        0.2, 0.4, 0.6, 0.8, 1.0, 1.5, 2.0, 3.0, 5.0, 10.0, 20.0, 30.0, 60.0, 90.0,
+    ];
+
+    #[must_use]
+    pub fn new() -> Self {
+        describe_counter!(
+            Self::METRIC_SENDS,
+            Unit::Count,
+            "Transactions sent to the cluster, including resends"
+        );
+        describe_counter!(
+            Self::METRIC_OUTCOMES,
+            Unit::Count,
+            "Submitted transactions by final outcome"
+        );
+        describe_gauge!(
+            Self::METRIC_LANDING_RATE,
+            "Fraction of submitted transactions that confirmed, from 0 to 1"
+        );
+        describe_histogram!(
+            Self::METRIC_CONFIRM_LATENCY,
+            Unit::Seconds,
+            "Time from the first send until confirmation"
+        );
+
+        Self::default()
+    }
+
+    /// Returns buckets and metric name for external registration.
+    #[must_use]
+    pub fn confirm_latency_buckets() -> (&'static str, &'static [f64]) {
+        (Self::METRIC_CONFIRM_LATENCY, Self::CONFIRM_LATENCY_BUCKETS)
+    }
+
+    /// Records a single send (or resend) of a transaction.
+    pub fn record_send(&self) {
+        self.sends.fetch_add(1, Ordering::Relaxed);
+        counter!(Self::METRIC_SENDS).increment(1);
+    }
+
+    /// Records the final outcome of a submitted transaction.
+    pub fn record_outcome(&self, outcome: SubmissionOutcome) {
+        match outcome {
+            SubmissionOutcome::Confirmed { latency } => {
+                self.confirmed.fetch_add(1, Ordering::Relaxed);
+                histogram!(Self::METRIC_CONFIRM_LATENCY).record(latency.as_secs_f64());
+            }
+            SubmissionOutcome::Dropped => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+            SubmissionOutcome::Reverted => {
+                self.reverted.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        counter!(Self::METRIC_OUTCOMES, Self::LBL_OUTCOME => outcome.as_str()).increment(1);
+        gauge!(Self::METRIC_LANDING_RATE).set(self.landing_rate());
+    }
+
+    /// Total number of sends, including resends.
+    #[must_use]
+    pub fn sends(&self) -> u64 {
+        self.sends.load(Ordering::Relaxed)
+    }
+
+    /// Fraction of transactions with a final outcome that confirmed.
+    #[must_use]
+    pub fn landing_rate(&self) -> f64 {
+        let confirmed = self.confirmed.load(Ordering::Relaxed);
+        let total = confirmed
+            + self.dropped.load(Ordering::Relaxed)
+            + self.reverted.load(Ordering::Relaxed);
+
+        if total == 0 {
+            return 0.0;
+        }
+        confirmed as f64 / total as f64
+    }
+
+    /// Returns `(confirmed, dropped, reverted)` totals.
+    #[must_use]
+    pub fn outcomes(&self) -> (u64, u64, u64) {
+        (
+            self.confirmed.load(Ordering::Relaxed),
+            self.dropped.load(Ordering::Relaxed),
+            self.reverted.load(Ordering::Relaxed),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_landing_rate_from_outcomes() {
+        let metrics = SenderMetrics::new();
+        assert!(metrics.landing_rate().abs() < f64::EPSILON);
+
+        for _ in 0..5 {
+            metrics.record_send();
+        }
+        metrics.record_outcome(SubmissionOutcome::Confirmed {
+            latency: Duration::from_millis(400),
+        });
+        metrics.record_outcome(SubmissionOutcome::Confirmed {
+            latency: Duration::from_millis(1200),
+        });
+        metrics.record_outcome(SubmissionOutcome::Dropped);
+        metrics.record_outcome(SubmissionOutcome::Reverted);
+
+        assert_eq!(metrics.sends(), 5);
+        assert_eq!(metrics.outcomes(), (2, 1, 1));
+        assert!((metrics.landing_rate() - 0.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_outcome_labels() {
+        let confirmed = SubmissionOutcome::Confirmed {
+            latency: Duration::ZERO,
+        };
+        assert_eq!(confirmed.as_str(), "confirmed");
+        assert_eq!(SubmissionOutcome::Dropped.as_str(), "dropped");
+        assert_eq!(SubmissionOutcome::Reverted.as_str(), "reverted");
+    }
+}
@@ -1 +1,7 @@
+pub mod channel;
+pub mod confirm;
+pub mod freshness;
+pub mod in_flight;
+pub mod metrics;
 pub mod service;
+pub mod transaction;
@@ -1,24 +1,649 @@
+use std::{str::FromStr, sync::Arc};
+
+use anyhow::Context;
 use async_trait::async_trait;
-use engine::{Sender, service::traits::ArbitrageService};
+use engine::{Sender, enums::OrderStatus, service::traits::ArbitrageService};
+use metrics_exporter_prometheus::Matcher;
+use solana_client::rpc_response::transaction::Signature;
+use solana_sdk::{
+    instruction::Instruction,
+    signature::{Keypair, Signer, read_keypair_file},
+};
 use tokio_util::sync::CancellationToken;
+use tools::http::http_metrics::HttpMetrics;
+use tracing::{debug, error, info, warn};
+
+use crate::{
+    Config,
+    libs::solana_client::{RpcClient, protocols::utils::get_associated_token_address},
+    services::{
+        exchange::{
+            cache::{PoolCache, get_market_state, get_mint_cache},
+            compute::ArbOpportunity,
+        },
+        sender::{
+            channel::OPPORTUNITY_CHANNEL,
+            confirm::{RetryPolicy, SignatureStatus, SignatureStatusSource, send_and_confirm},
+            freshness::{FreshnessGuard, StaleQuote},
+            in_flight::InFlight,
+            metrics::SenderMetrics,
+            transaction::{ComputeBudget, SwapTransaction, TransactionSimulator},
+        },
+    },
+};
+
+/// Outcome of handling a single arbitrage opportunity.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Dispatch {
+    /// The opportunity was logged without submitting a transaction.
+    DryRun { profit: u64 },
+    /// The route's instructions were built and are to be simulated, not submitted.
+    Simulate { instructions: Vec<Instruction> },
+    /// The opportunity was dropped without submitting a transaction.
+    Skipped,
+    /// The route's pool state changed since the quote; submission was aborted.
+    Stale(StaleQuote),
+    /// The route's instructions were built and are to be signed and submitted.
+    Submit { instructions: Vec<Instruction> },
+}
+
+/// Service for sending transactions for detected arbitrage opportunities.
+pub struct SenderService {
+    send_transactions: bool,
+    retry_policy: RetryPolicy,
+    freshness: FreshnessGuard,
+    compute_budget: ComputeBudget,
+    in_flight: InFlight,
+    /// Keypair signing submitted transactions, or paying the simulated ones in dry-run.
+    signer: Option<Arc<Keypair>>,
+    rpc: Arc<RpcClient>,
+    statuses: Arc<dyn SignatureStatusSource>,
+    simulator: Arc<dyn TransactionSimulator>,
+}
 
-use crate::Config;
+#[async_trait]
+impl Sender for SenderService {
+    /// Reports the confirmation status of a sent transaction; `client_order_id`
+    /// is its signature and `symbol` is ignored.
+    async fn query_order(
+        &self,
+        _symbol: &str,
+        client_order_id: &str,
+    ) -> anyhow::Result<OrderStatus> {
+        let signature = Signature::from_str(client_order_id)
+            .with_context(|| format!("Invalid transaction signature {client_order_id}"))?;
 
-/// Service for sending and polling orders from arbitrage chains.
-pub struct SenderService {}
+        let status = self
+            .statuses
+            .signature_status(&signature, self.retry_policy.commitment)
+            .await?;
 
-impl Sender for SenderService {}
+        Ok(match status {
+            SignatureStatus::Pending => OrderStatus::New,
+            SignatureStatus::Confirmed => OrderStatus::Filled,
+            SignatureStatus::Failed => OrderStatus::Rejected,
+        })
+    }
+}
 
 #[async_trait]
 impl ArbitrageService for SenderService {
     async fn start(&self, token: CancellationToken) -> anyhow::Result<()> {
-        token.cancelled().await;
+        let mut opportunities_rx = OPPORTUNITY_CHANNEL.rx.lock().await;
+
+        info!(
+            send_transactions = self.send_transactions,
+            commitment = ?self.retry_policy.commitment.commitment,
+            max_attempts = self.retry_policy.max_attempts,
+            "Solana sender started"
+        );
+
+        loop {
+            tokio::select! {
+                _ = token.cancelled() => break,
+                Some(opportunity) = opportunities_rx.recv() => {
+                    let dispatch = self.dispatch(&opportunity, get_market_state().read().pools());
+                    match dispatch {
+                        Dispatch::Submit { instructions } => self.send(&opportunity, instructions),
+                        Dispatch::Simulate { instructions } => {
+                            if let Err(e) = self.simulate(&opportunity, &instructions).await {
+                                warn!(
+                                    base_token = %opportunity.path.base_token,
+                                    "🔎 [Dry-run] Failed to simulate transaction: {e:#}"
+                                );
+                            }
+                        }
+                        Dispatch::DryRun { .. } | Dispatch::Skipped | Dispatch::Stale(_) => {}
+                    }
+                }
+            }
+        }
+
         Ok(())
     }
 }
 
 impl SenderService {
-    pub async fn from_config(_config: &Config) -> anyhow::Result<Self> {
-        Ok(Self {})
+    pub async fn from_config(config: &Config) -> anyhow::Result<Self> {
+        init_sender_metrics();
+
+        let signer = match &config.keypair_path {
+            Some(path) => {
+                let keypair = read_keypair_file(path).map_err(|e| {
+                    anyhow::anyhow!("Failed to read keypair {}: {e}", path.display())
+                })?;
+                Some(Arc::new(keypair))
+            }
+            None => None,
+        };
+        let rpc = Arc::new(RpcClient::from_config(config.try_into()?));
+
+        Ok(Self {
+            send_transactions: config.send_transactions,
+            retry_policy: config.into(),
+            freshness: config.into(),
+            compute_budget: config.into(),
+            in_flight: InFlight::new(config.confirmation.max_in_flight),
+            signer,
+            rpc: rpc.clone(),
+            statuses: rpc.clone(),
+            simulator: rpc,
+        })
+    }
+
+    /// Handles a single opportunity. In dry-run mode the composed route and
+    /// its quoted profit are logged and nothing is submitted; with a keypair loaded
+    /// the route's transaction is built to be simulated.
+    ///
+    /// Routes whose pools moved since the quote (see [`FreshnessGuard`]) are
+    /// aborted. The swap instructions of every leg are built from the cached `pools`
+    /// and mints; the route is skipped if any of them cannot be built.
+    fn dispatch(&self, opportunity: &ArbOpportunity, pools: &PoolCache) -> Dispatch {
+        if let Err(e) = self
+            .freshness
+            .check(opportunity, |pool_id| pools.get_slot(pool_id))
+        {
+            debug!(
+                base_token = %opportunity.path.base_token,
+                profit = opportunity.profit,
+                "Stale quote, opportunity aborted: {e}"
+            );
+            return Dispatch::Stale(e);
+        }
+
+        if !self.send_transactions {
+            Self::log_opportunity(opportunity);
+            if self.signer.is_none() {
+                return Dispatch::DryRun {
+                    profit: opportunity.profit,
+                };
+            }
+        }
+
+        let Some(signer) = &self.signer else {
+            warn!("No keypair loaded, opportunity skipped");
+            return Dispatch::Skipped;
+        };
+
+        match self.compute_budget.build_instructions(
+            opportunity,
+            pools,
+            &get_mint_cache().read(),
+            signer.pubkey(),
+        ) {
+            Ok(instructions) if self.send_transactions => Dispatch::Submit { instructions },
+            Ok(instructions) => Dispatch::Simulate { instructions },
+            Err(e) => {
+                warn!(
+                    base_token = %opportunity.path.base_token,
+                    profit = opportunity.profit,
+                    "Opportunity skipped: {e:#}"
+                );
+                Dispatch::Skipped
+            }
+        }
+    }
+
+    /// Signs and submits the route's instructions as a single transaction in the
+    /// background, resending it per the retry policy until it is confirmed.
+    ///
+    /// The route is skipped while a previous transaction of it is awaiting confirmation,
+    /// or once `max_in_flight` transactions are.
+    fn send(&self, opportunity: &ArbOpportunity, instructions: Vec<Instruction>) {
+        let Some(signer) = self.signer.clone() else {
+            return;
+        };
+        let guard = match self.in_flight.try_admit(&opportunity.path) {
+            Ok(guard) => guard,
+            Err(e) => {
+                debug!(
+                    base_token = %opportunity.path.base_token,
+                    profit = opportunity.profit,
+                    "Opportunity skipped: {e}"
+                );
+                return;
+            }
+        };
+        let transaction = SwapTransaction {
+            rpc: self.rpc.clone(),
+            signer,
+            instructions,
+        };
+        let policy = self.retry_policy.clone();
+        let base_token = opportunity.path.base_token;
+        let profit = opportunity.profit;
+
+        tokio::spawn(async move {
+            match send_and_confirm(&transaction, &policy).await {
+                Ok(confirmation) => info!(
+                    %base_token,
+                    profit,
+                    signature = %confirmation.signature,
+                    attempts = confirmation.attempts,
+                    "Arbitrage transaction confirmed"
+                ),
+                Err(e) => error!(%base_token, profit, "Arbitrage transaction failed: {e}"),
+            }
+            drop(guard);
+        });
+    }
+
+    /// Simulates the route's transaction as paid by the keypair and logs the outcome.
+    ///
+    /// Returns the simulated profit, the change of the keypair's base token balance, or
+    /// `None` if the transaction would fail.
+    async fn simulate(
+        &self,
+        opportunity: &ArbOpportunity,
+        instructions: &[Instruction],
+    ) -> anyhow::Result<Option<i128>> {
+        let Some(signer) = &self.signer else {
+            anyhow::bail!("No keypair loaded");
+        };
+        let payer = signer.pubkey();
+        let base_token = opportunity.path.base_token;
+        let token_program = get_mint_cache()
+            .read()
+            .get(&base_token)
+            .map(|account| account.owner)
+            .with_context(|| format!("Mint {base_token} is not cached"))?;
+        let token_account = get_associated_token_address(&payer, &base_token, &token_program);
+
+        let balance_before = self.simulator.token_balance(&token_account).await?;
+        let simulation = self
+            .simulator
+            .simulate(instructions, &payer, &token_account)
+            .await?;
+
+        if let Some(err) = &simulation.err {
+            warn!(
+                %base_token,
+                profit = opportunity.profit,
+                units_consumed = simulation.units_consumed,
+                "🔎 [Dry-run] Simulated transaction failed: {err}"
+            );
+            return Ok(None);
+        }
+
+        let balance_after = simulation
+            .token_balance
+            .context("Simulation did not return the base token account")?;
+        let simulated_profit = i128::from(balance_after) - i128::from(balance_before);
+        info!(
+            %base_token,
+            profit = opportunity.profit,
+            simulated_profit,
+            units_consumed = simulation.units_consumed,
+            "🔎 [Dry-run] Transaction simulated, not sent"
+        );
+
+        Ok(Some(simulated_profit))
+    }
+
+    fn log_opportunity(opportunity: &ArbOpportunity) {
+        let path = &opportunity.path;
+
+        for (idx, (step, quote)) in path.steps.iter().zip(&opportunity.step_quotes).enumerate() {
+            info!(
+                step = idx + 1,
+                pool_id = %step.pool_id,
+                mint_in = %step.mint_in,
+                mint_out = %step.mint_out,
+                a_to_b = step.a_to_b,
+                amount_in = quote.total_amount_in_gross,
+                amount_out = quote.total_amount_out,
+                fee = quote.total_fee,
+                compute_units = quote.compute_units,
+                "🔎 [Dry-run] Swap step"
+            );
+        }
+
+        info!(
+            base_token = %path.base_token,
+            amount_in = opportunity.amount_in,
+            amount_out = opportunity.amount_out,
+            profit = opportunity.profit,
+            "🔎 [Dry-run] Arbitrage opportunity found, transaction not sent"
+        );
+    }
+}
+
+fn init_sender_metrics() {
+    let (name, buckets) = SenderMetrics::confirm_latency_buckets();
+    HttpMetrics::register_buckets(Matcher::Full(name.to_owned()), buckets.to_vec());
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bytemuck::Zeroable;
+    use solana_client::rpc_config::CommitmentConfig;
+    use solana_sdk::{account::Account, pubkey::Pubkey};
+
+    use super::*;
+    use crate::{
+        libs::solana_client::{
+            RpcConfig,
+            pool::QuoteResult,
+            protocols::{raydium_amm, raydium_cpmm},
+        },
+        services::{
+            exchange::compute::{ComputePath, ComputeStep},
+            sender::transaction::{COMPUTE_BUDGET_PROGRAM_ID, Simulation},
+        },
+        test_utils::cpmm_pool,
+    };
+
+    fn quote(amount_in: u64, amount_out: u64) -> QuoteResult {
+        QuoteResult {
+            steps: vec![],
+            total_amount_in_gross: amount_in,
+            total_amount_in_net: amount_in,
+            total_amount_out: amount_out,
+            total_fee: 0,
+            compute_units: 0,
+        }
+    }
+
+    /// Reports the same status for every signature.
+    struct FixedStatus(SignatureStatus);
+
+    #[async_trait]
+    impl SignatureStatusSource for FixedStatus {
+        async fn signature_status(
+            &self,
+            _signature: &Signature,
+            _commitment: CommitmentConfig,
+        ) -> anyhow::Result<SignatureStatus> {
+            Ok(self.0)
+        }
+    }
+
+    /// Simulates every transaction with the given outcome, the watched token account
+    /// holding 1_000 beforehand.
+    struct FixedSimulation(Simulation);
+
+    #[async_trait]
+    impl TransactionSimulator for FixedSimulation {
+        async fn token_balance(&self, _account: &Pubkey) -> anyhow::Result<u64> {
+            Ok(1_000)
+        }
+
+        async fn simulate(
+            &self,
+            _instructions: &[Instruction],
+            _payer: &Pubkey,
+            _watched: &Pubkey,
+        ) -> anyhow::Result<Simulation> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn sender(send_transactions: bool) -> SenderService {
+        SenderService {
+            send_transactions,
+            retry_policy: RetryPolicy {
+                commitment: CommitmentConfig::confirmed(),
+                confirm_timeout: Duration::from_secs(1),
+                poll_interval: Duration::from_millis(100),
+                max_attempts: 1,
+            },
+            freshness: FreshnessGuard {
+                enabled: true,
+                max_slot_lag: 1,
+            },
+            compute_budget: ComputeBudget {
+                compute_unit_price: 10_000,
+            },
+            in_flight: InFlight::new(1),
+            signer: send_transactions.then(|| Arc::new(Keypair::new())),
+            rpc: Arc::new(RpcClient::from_config(RpcConfig::default())),
+            statuses: Arc::new(FixedStatus(SignatureStatus::Pending)),
+            simulator: Arc::new(FixedSimulation(Simulation {
+                err: None,
+                units_consumed: Some(82_500),
+                token_balance: Some(1_045),
+            })),
+        }
+    }
+
+    /// Pool cache reporting the pools of the route at the given slot.
+    fn pools_at(opportunity: &ArbOpportunity, slot: u64) -> PoolCache {
+        let mut pools = PoolCache::new();
+        for step in &opportunity.path.steps {
+            pools.record_slot(step.pool_id, slot);
+        }
+        pools
+    }
+
+    /// Caches the mints as owned by the SPL token program.
+    fn cache_mints(mints: &[Pubkey]) {
+        let mut mint_cache = get_mint_cache().write();
+        for mint in mints {
+            mint_cache.update(
+                *mint,
+                Account {
+                    owner: spl_token::ID,
+                    ..Account::default()
+                },
+            );
+        }
+    }
+
+    fn opportunity() -> ArbOpportunity {
+        let base_token = Pubkey::new_unique();
+        let quote_token = Pubkey::new_unique();
+
+        ArbOpportunity {
+            path: ComputePath {
+                base_token,
+                steps: [
+                    ComputeStep {
+                        pool_id: Pubkey::new_unique(),
+                        mint_in: base_token,
+                        mint_out: quote_token,
+                        a_to_b: true,
+                    },
+                    ComputeStep {
+                        pool_id: Pubkey::new_unique(),
+                        mint_in: quote_token,
+                        mint_out: base_token,
+                        a_to_b: false,
+                    },
+                ],
+            },
+            amount_in: 1_000,
+            amount_out: 1_050,
+            profit: 50,
+            min_amount_out: 1_001,
+            quoted_slots: [100, 100],
+            step_quotes: [quote(1_000, 2_000), quote(2_000, 1_050)],
+        }
+    }
+
+    #[test]
+    fn test_dry_run_does_not_submit() {
+        let sender = sender(false);
+        let opportunity = opportunity();
+
+        assert_eq!(
+            sender.dispatch(&opportunity, &pools_at(&opportunity, 100)),
+            Dispatch::DryRun { profit: 50 }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_simulates_without_submitting() -> anyhow::Result<()> {
+        let mut sender = sender(false);
+        sender.signer = Some(Arc::new(Keypair::new()));
+        let opportunity = opportunity();
+
+        let [first, second] = opportunity.path.steps.clone();
+        let mut pools = pools_at(&opportunity, 100);
+        pools.update(first.pool_id, cpmm_pool(first.mint_in, first.mint_out));
+        pools.update(second.pool_id, cpmm_pool(second.mint_out, second.mint_in));
+        cache_mints(&[first.mint_in, first.mint_out]);
+
+        // The transaction is built to be simulated, never submitted.
+        let Dispatch::Simulate { instructions } = sender.dispatch(&opportunity, &pools) else {
+            panic!("route instructions not built for simulation");
+        };
+        assert_eq!(instructions.len(), 4);
+
+        // The base token balance went from 1_000 to 1_045.
+        assert_eq!(
+            sender.simulate(&opportunity, &instructions).await?,
+            Some(45)
+        );
+
+        sender.simulator = Arc::new(FixedSimulation(Simulation {
+            err: Some("InstructionError(2, Custom(6005))".to_owned()),
+            units_consumed: Some(41_000),
+            token_balance: None,
+        }));
+        assert_eq!(sender.simulate(&opportunity, &instructions).await?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_live_mode_builds_route_instructions() {
+        let sender = sender(true);
+        let mut opportunity = opportunity();
+        opportunity.step_quotes[0].compute_units = 41_250;
+        opportunity.step_quotes[1].compute_units = 41_250;
+
+        let [first, second] = opportunity.path.steps.clone();
+        let mut pools = pools_at(&opportunity, 100);
+        pools.update(first.pool_id, cpmm_pool(first.mint_in, first.mint_out));
+        pools.update(second.pool_id, cpmm_pool(second.mint_out, second.mint_in));
+        cache_mints(&[first.mint_in, first.mint_out]);
+
+        let Dispatch::Submit { instructions } = sender.dispatch(&opportunity, &pools) else {
+            panic!("route instructions not built");
+        };
+
+        // Compute unit limit with 20% headroom, priority fee, then one swap per leg.
+        assert_eq!(instructions.len(), 4);
+        assert_eq!(instructions[0].program_id, COMPUTE_BUDGET_PROGRAM_ID);
+        assert_eq!(instructions[0].data[1..], 99_000u32.to_le_bytes());
+        assert_eq!(instructions[1].program_id, COMPUTE_BUDGET_PROGRAM_ID);
+        assert_eq!(instructions[1].data[1..], 10_000u64.to_le_bytes());
+
+        let payer = sender.signer.as_ref().unwrap().pubkey();
+        for (instruction, step, amount_in, min_amount_out) in [
+            (&instructions[2], first, 1_000u64, 2_000u64),
+            (&instructions[3], second, 2_000, 1_001),
+        ] {
+            assert_eq!(instruction.program_id, raydium_cpmm::RAYDIUM_CPMM_ID);
+            assert_eq!(instruction.accounts[0].pubkey, payer);
+            assert_eq!(instruction.accounts[3].pubkey, step.pool_id);
+            assert_eq!(instruction.accounts[10].pubkey, step.mint_in);
+            assert_eq!(instruction.accounts[11].pubkey, step.mint_out);
+            assert_eq!(instruction.data[8..16], amount_in.to_le_bytes());
+            assert_eq!(instruction.data[16..], min_amount_out.to_le_bytes());
+        }
+    }
+
+    #[test]
+    fn test_live_mode_skips_route_with_unbuildable_leg() {
+        let sender = sender(true);
+        let opportunity = opportunity();
+
+        let [first, second] = opportunity.path.steps.clone();
+        let mut pools = pools_at(&opportunity, 100);
+        pools.update(first.pool_id, cpmm_pool(first.mint_in, first.mint_out));
+        cache_mints(&[first.mint_in, first.mint_out]);
+
+        // The second pool is not cached.
+        assert_eq!(sender.dispatch(&opportunity, &pools), Dispatch::Skipped);
+
+        // The second pool's protocol cannot build swaps.
+        let mut amm = raydium_amm::AmmInfo::zeroed();
+        amm.coin_vault_mint = second.mint_out.to_bytes();
+        amm.pc_vault_mint = second.mint_in.to_bytes();
+        pools.update(second.pool_id, Box::new(amm));
+        assert_eq!(sender.dispatch(&opportunity, &pools), Dispatch::Skipped);
+    }
+
+    #[test]
+    fn test_stale_quote_aborts_submission() {
+        let sender = sender(true);
+        let opportunity = opportunity();
+        let pool_id = opportunity.path.steps[0].pool_id;
+
+        assert_eq!(
+            sender.dispatch(&opportunity, &pools_at(&opportunity, 102)),
+            Dispatch::Stale(StaleQuote::PoolAdvanced {
+                pool_id,
+                quoted_slot: 100,
+                current_slot: 102,
+            })
+        );
+    }
+
+    #[test]
+    fn test_untracked_pool_aborts_submission() {
+        let sender = sender(false);
+        let opportunity = opportunity();
+        let pool_id = opportunity.path.steps[1].pool_id;
+
+        let mut pools = PoolCache::new();
+        pools.record_slot(opportunity.path.steps[0].pool_id, 100);
+
+        let dispatch = sender.dispatch(&opportunity, &pools);
+
+        assert_eq!(dispatch, Dispatch::Stale(StaleQuote::PoolMissing(pool_id)));
+    }
+
+    #[test]
+    fn test_disabled_guard_accepts_stale_quote() {
+        let mut sender = sender(false);
+        sender.freshness.enabled = false;
+        let opportunity = opportunity();
+
+        assert_eq!(
+            sender.dispatch(&opportunity, &pools_at(&opportunity, 1_000)),
+            Dispatch::DryRun { profit: 50 }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query_order_maps_confirmation_status() -> anyhow::Result<()> {
+        let signature = Signature::from([7; 64]).to_string();
+        let mut sender = sender(true);
+
+        for (status, expected) in [
+            (SignatureStatus::Pending, OrderStatus::New),
+            (SignatureStatus::Confirmed, OrderStatus::Filled),
+            (SignatureStatus::Failed, OrderStatus::Rejected),
+        ] {
+            sender.statuses = Arc::new(FixedStatus(status));
+            assert_eq!(sender.query_order("", &signature).await?, expected);
+        }
+
+        assert!(sender.query_order("", "not-a-signature").await.is_err());
+        Ok(())
     }
 }
@@ -0,0 +1,228 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use solana_client::{rpc_config::CommitmentConfig, rpc_response::transaction::Signature};
+use solana_sdk::{
+    account::Account,
+    hash::Hash,
+    instruction::Instruction,
+    pubkey,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+use crate::{
+    libs::solana_client::{RpcClient, pool::SwapContext, protocols::utils::parse_vault_amount},
+    services::{
+        exchange::{
+            cache::{MintCache, PoolCache},
+            compute::ArbOpportunity,
+        },
+        sender::confirm::{SignatureStatus, SignatureStatusSource, TransactionSubmitter},
+    },
+};
+
+/// Program setting the compute unit limit and price of a transaction.
+pub const COMPUTE_BUDGET_PROGRAM_ID: Pubkey =
+    pubkey!("ComputeBudget111111111111111111111111111111");
+
+/// Maximum compute unit limit of a transaction.
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Headroom added to the quoted compute units, as the quotes are estimates.
+const COMPUTE_UNIT_MARGIN_PERCENT: u32 = 20;
+
+/// Compute budget of the submitted transactions.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ComputeBudget {
+    /// Priority fee paid per compute unit, in micro-lamports.
+    pub compute_unit_price: u64,
+}
+
+impl ComputeBudget {
+    /// Instruction capping the compute units the transaction may consume.
+    #[must_use]
+    pub fn set_compute_unit_limit(units: u32) -> Instruction {
+        let mut data = vec![2];
+        data.extend_from_slice(&units.to_le_bytes());
+        Instruction::new_with_bytes(COMPUTE_BUDGET_PROGRAM_ID, &data, vec![])
+    }
+
+    /// Instruction setting the priority fee per compute unit, in micro-lamports.
+    #[must_use]
+    pub fn set_compute_unit_price(micro_lamports: u64) -> Instruction {
+        let mut data = vec![3];
+        data.extend_from_slice(&micro_lamports.to_le_bytes());
+        Instruction::new_with_bytes(COMPUTE_BUDGET_PROGRAM_ID, &data, vec![])
+    }
+
+    /// Builds the instructions of the atomic transaction executing every leg of the route,
+    /// preceded by the compute budget instructions.
+    ///
+    /// Each leg swaps exactly its quoted input; an intermediate leg must return at least
+    /// the input of the next one and the last leg at least the route's minimum output.
+    ///
+    /// The token program of each side of a swap is the owner of its cached mint.
+    ///
+    /// # Errors
+    /// Returns an error if a pool or mint is no longer cached or a swap instruction cannot
+    /// be built, in which case nothing of the route should be submitted.
+    pub fn build_instructions(
+        &self,
+        opportunity: &ArbOpportunity,
+        pools: &PoolCache,
+        mints: &MintCache,
+        payer: Pubkey,
+    ) -> anyhow::Result<Vec<Instruction>> {
+        let steps = &opportunity.path.steps;
+        let quotes = &opportunity.step_quotes;
+
+        let mut instructions = Vec::with_capacity(steps.len() + 2);
+        let compute_units: u32 = quotes.iter().map(|quote| quote.compute_units).sum();
+        let compute_units = compute_units.saturating_mul(100 + COMPUTE_UNIT_MARGIN_PERCENT) / 100;
+        instructions.push(Self::set_compute_unit_limit(
+            compute_units.min(MAX_COMPUTE_UNIT_LIMIT),
+        ));
+        if self.compute_unit_price > 0 {
+            instructions.push(Self::set_compute_unit_price(self.compute_unit_price));
+        }
+
+        for (idx, (step, quote)) in steps.iter().zip(quotes).enumerate() {
+            let pool = pools
+                .get_pool(&step.pool_id)
+                .with_context(|| format!("Pool {} is no longer cached", step.pool_id))?;
+            let token_program = |mint: &Pubkey| {
+                mints
+                    .get(mint)
+                    .map(|account| account.owner)
+                    .with_context(|| format!("Mint {mint} is not cached"))
+            };
+
+            let min_amount_out = quotes
+                .get(idx + 1)
+                .map_or(opportunity.min_amount_out, |next| {
+                    next.total_amount_in_gross
+                });
+
+            let instruction = pool
+                .swap_instruction(&SwapContext {
+                    pool_id: step.pool_id,
+                    payer,
+                    a_to_b: step.a_to_b,
+                    token_program_in: token_program(&step.mint_in)?,
+                    token_program_out: token_program(&step.mint_out)?,
+                    amount_in: quote.total_amount_in_gross,
+                    min_amount_out,
+                })
+                .with_context(|| {
+                    format!("Failed to build swap {} on pool {}", idx + 1, step.pool_id)
+                })?;
+            instructions.push(instruction);
+        }
+
+        Ok(instructions)
+    }
+}
+
+/// Signed submission of a route's instructions, rebuilt against a fresh blockhash on
+/// every attempt of [`send_and_confirm`](super::confirm::send_and_confirm).
+pub struct SwapTransaction {
+    pub rpc: Arc<RpcClient>,
+    pub signer: Arc<Keypair>,
+    pub instructions: Vec<Instruction>,
+}
+
+#[async_trait]
+impl SignatureStatusSource for SwapTransaction {
+    async fn signature_status(
+        &self,
+        signature: &Signature,
+        commitment: CommitmentConfig,
+    ) -> anyhow::Result<SignatureStatus> {
+        self.rpc.signature_status(signature, commitment).await
+    }
+}
+
+#[async_trait]
+impl TransactionSubmitter for SwapTransaction {
+    async fn latest_blockhash(&self, commitment: CommitmentConfig) -> anyhow::Result<Hash> {
+        self.rpc.get_latest_blockhash(commitment).await
+    }
+
+    async fn send(&self, blockhash: Hash) -> anyhow::Result<Signature> {
+        let transaction = Transaction::new_signed_with_payer(
+            &self.instructions,
+            Some(&self.signer.pubkey()),
+            &[self.signer.as_ref()],
+            blockhash,
+        );
+        self.rpc.send_transaction(&transaction).await
+    }
+}
+
+/// Outcome of a simulated route transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Simulation {
+    /// Why the transaction would fail, `None` if it would succeed.
+    pub err: Option<String>,
+    pub units_consumed: Option<u64>,
+    /// Balance of the watched token account after the transaction.
+    pub token_balance: Option<u64>,
+}
+
+/// Simulates route transactions without submitting them.
+#[async_trait]
+pub trait TransactionSimulator: Send + Sync {
+    /// Returns the balance of a token account, 0 if it does not exist.
+    async fn token_balance(&self, account: &Pubkey) -> anyhow::Result<u64>;
+
+    /// Simulates the unsigned transaction paid by `payer` against the latest blockhash,
+    /// reporting the balance of the `watched` token account after it executed.
+    async fn simulate(
+        &self,
+        instructions: &[Instruction],
+        payer: &Pubkey,
+        watched: &Pubkey,
+    ) -> anyhow::Result<Simulation>;
+}
+
+#[async_trait]
+impl TransactionSimulator for RpcClient {
+    async fn token_balance(&self, account: &Pubkey) -> anyhow::Result<u64> {
+        match self
+            .get_multiple_accounts(&[*account])
+            .await?
+            .value
+            .pop()
+            .flatten()
+        {
+            Some(account) => parse_vault_amount(&account.data),
+            None => Ok(0),
+        }
+    }
+
+    async fn simulate(
+        &self,
+        instructions: &[Instruction],
+        payer: &Pubkey,
+        watched: &Pubkey,
+    ) -> anyhow::Result<Simulation> {
+        let transaction = Transaction::new_with_payer(instructions, Some(payer));
+        let result = self.simulate_transaction(&transaction, &[*watched]).await?;
+
+        let token_balance = result
+            .accounts
+            .and_then(|accounts| accounts.into_iter().next().flatten())
+            .and_then(|account| account.decode::<Account>())
+            .map(|account| parse_vault_amount(&account.data))
+            .transpose()?;
+
+        Ok(Simulation {
+            err: result.err.map(|e| format!("{e:?}")),
+            units_consumed: result.units_consumed,
+            token_balance,
+        })
+    }
+}
@@ -0,0 +1,60 @@
+use ahash::AHashSet;
+use bytemuck::Zeroable;
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+use crate::{
+    libs::solana_client::{ProtocolKind, protocols::raydium_cpmm},
+    services::exchange::{compute::ComputeConfig, sizing::SizeSearch},
+};
+
+/// Builds a plain SPL mint account (82 bytes, no extensions).
+#[must_use]
+pub fn mint_account(decimals: u8) -> Account {
+    let mut data = vec![0u8; 82];
+    data[44] = decimals;
+    // is_initialized
+    data[45] = 1;
+
+    Account {
+        lamports: 0,
+        data,
+        owner: Pubkey::default(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Raydium CPMM pool trading `mint_0` against `mint_1`, both SPL tokens.
+#[must_use]
+pub fn cpmm_pool(mint_0: Pubkey, mint_1: Pubkey) -> Box<raydium_cpmm::PoolState> {
+    let mut pool = raydium_cpmm::PoolState::zeroed();
+    pool.amm_config = Pubkey::new_unique().to_bytes();
+    pool.token_0_vault = Pubkey::new_unique().to_bytes();
+    pool.token_1_vault = Pubkey::new_unique().to_bytes();
+    pool.token_0_mint = mint_0.to_bytes();
+    pool.token_1_mint = mint_1.to_bytes();
+    pool.token_0_program = spl_token::ID.to_bytes();
+    pool.token_1_program = spl_token::ID.to_bytes();
+    pool.observation_key = Pubkey::new_unique().to_bytes();
+    Box::new(pool)
+}
+
+/// Route config without liquidity or profit bounds.
+#[must_use]
+pub fn compute_config(
+    base_mints: &[Pubkey],
+    route_protocols: &[ProtocolKind],
+    intermediate_mints: &[Pubkey],
+) -> ComputeConfig {
+    ComputeConfig {
+        base_mints: base_mints.iter().copied().collect(),
+        min_liquidity_fraction_bps: 0,
+        max_liquidity_fraction_bps: 0,
+        min_profit_bps: 0,
+        route_protocols: route_protocols.iter().copied().collect(),
+        intermediate_mints: intermediate_mints.iter().copied().collect(),
+        size_search: SizeSearch::default(),
+        warmup_min_pools: 0,
+        warmup_mints: AHashSet::new(),
+    }
+}
@@ -2,19 +2,20 @@ use std::{fmt::Display, future::ready, net::SocketAddr, sync::Arc, time::Duratio
 
 use anyhow::{Context, Result};
 use async_trait::async_trait;
-use axum::{Router, routing::get};
+use axum::{Router, http::StatusCode, routing::get};
 use tokio::{signal, task::JoinHandle, time::timeout};
 use tokio_util::sync::CancellationToken;
 use tracing::{error, info};
 
-use crate::http::http_metrics;
+use crate::http::{http_metrics, readiness::READINESS};
 
 /// Asynchronous trait for server processes that can be pre-run and run concurrently with the
 /// server.
 ///
 /// Implementors must provide `pre_run` (initialization tasks) and `run` (main loop, cancellable via
-/// token). Used to orchestrate background tasks (e.g., WebSocket connections, data processors)
-/// alongside HTTP servers.
+/// token), and may provide `router` to serve extra routes on the application server. Used to
+/// orchestrate background tasks (e.g., WebSocket connections, data processors) alongside HTTP
+/// servers.
 ///
 /// # Examples
 /// ```rust,no_run
@@ -59,6 +60,13 @@ pub trait HttpServerProcess: Send + Sync + 'static {
     /// # Errors
     /// Returns an error if the process fails during execution.
     async fn run(&self, token: CancellationToken) -> Result<()>;
+
+    /// Returns routes merged into the application server router.
+    ///
+    /// Defaults to none.
+    fn router(&self) -> Option<Router> {
+        None
+    }
 }
 
 /// Configuration for the HTTP server and related timeouts.
@@ -92,7 +100,8 @@ impl Default for HttpServerConfig {
 /// - Graceful shutdown on signals (Ctrl+C, SIGTERM, SIGQUIT).
 /// - Pre-run tasks with timeout.
 /// - Prometheus metrics export.
-/// - Basic health endpoints (/readiness, /liveness).
+/// - Basic health endpoints (/readiness, /health/ready, /liveness).
+/// - Extra application routes provided by processes.
 ///
 /// # Examples
 /// ```rust,no_run
@@ -171,8 +180,11 @@ impl HttpServer {
         let mut runnable_tasks = Self::run_processes(processes, &shutdown);
 
         // Bootstrap servers
-        let app_server =
-            bootstrap_server(&self.addr, get_default_router(), ServerKind::Application);
+        let app_router = processes
+            .iter()
+            .filter_map(|p| p.router())
+            .fold(get_default_router(), Router::merge);
+        let app_server = bootstrap_server(&self.addr, app_router, ServerKind::Application);
         let metrics_server = bootstrap_server(
             &self.metrics_addr,
             get_metrics_router(),
@@ -344,10 +356,24 @@ impl Display for ServerKind {
 /// Returns a basic Axum router with health check endpoints.
 fn get_default_router() -> Router {
     Router::new()
-        .route("/readiness", get(|| async { "OK" }))
+        .route("/readiness", get(readiness))
+        .route("/health/ready", get(readiness))
         .route("/liveness", get(|| async { "OK" }))
 }
 
+/// Reports ready only once every registered readiness gate is open.
+async fn readiness() -> (StatusCode, String) {
+    let pending = READINESS.pending();
+    if pending.is_empty() {
+        return (StatusCode::OK, String::from("OK"));
+    }
+
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        format!("Not ready: {}", pending.join(", ")),
+    )
+}
+
 /// Returns an Axum router for metrics with Prometheus rendering.
 fn get_metrics_router() -> Router {
     let recorder_handle = http_metrics::setup_metrics_recorder();
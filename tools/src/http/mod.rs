@@ -1,2 +1,3 @@
 pub mod http_metrics;
 pub mod http_server;
+pub mod readiness;
@@ -0,0 +1,77 @@
+use std::sync::{
+    Arc, LazyLock, RwLock,
+    atomic::{AtomicBool, Ordering},
+};
+
+/// Global registry of readiness gates reported by the health endpoints.
+pub static READINESS: LazyLock<Readiness> = LazyLock::new(Readiness::default);
+
+/// A named condition that must hold before the application reports itself as ready
+/// (e.g. a warmed-up cache or an established stream).
+#[derive(Debug)]
+pub struct ReadinessGate {
+    name: &'static str,
+    ready: AtomicBool,
+}
+
+impl ReadinessGate {
+    /// Creates a closed gate that is not registered anywhere.
+    #[must_use]
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            ready: AtomicBool::new(false),
+        }
+    }
+
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    #[must_use]
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Relaxed)
+    }
+
+    /// Opens or closes the gate.
+    pub fn set_ready(&self, ready: bool) {
+        self.ready.store(ready, Ordering::Relaxed);
+    }
+}
+
+/// Set of readiness gates. The application is ready once every gate is open.
+#[derive(Debug, Default)]
+pub struct Readiness {
+    gates: RwLock<Vec<Arc<ReadinessGate>>>,
+}
+
+impl Readiness {
+    /// Registers a new closed gate and returns a handle to open it.
+    pub fn register(&self, name: &'static str) -> Arc<ReadinessGate> {
+        let gate = Arc::new(ReadinessGate::new(name));
+        self.gates
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .push(Arc::clone(&gate));
+        gate
+    }
+
+    /// Returns the names of gates that are still closed.
+    #[must_use]
+    pub fn pending(&self) -> Vec<&'static str> {
+        self.gates
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .iter()
+            .filter(|gate| !gate.is_ready())
+            .map(|gate| gate.name())
+            .collect()
+    }
+
+    /// Returns true if every registered gate is open.
+    #[must_use]
+    pub fn is_ready(&self) -> bool {
+        self.pending().is_empty()
+    }
+}